@@ -0,0 +1,43 @@
+-- RSWS 支付宝 / 微信支付配置
+-- 结构与 paypal_configs 一致：同一时间仅读取最新一条 is_active = true 的配置
+
+-- 1. 支付宝配置
+CREATE TABLE IF NOT EXISTS alipay_configs (
+    id                BIGINT        PRIMARY KEY,  -- ID 由 Rust snowflake::next_id() 生成
+    app_id            VARCHAR(64)   NOT NULL,
+    private_key       TEXT          NOT NULL,     -- 应用私钥（PKCS#8/PKCS#1，PEM 或裸 base64）
+    alipay_public_key TEXT          NOT NULL,     -- 支付宝公钥
+    sandbox           BOOLEAN       NOT NULL DEFAULT true,
+    notify_url        VARCHAR(500)  NOT NULL DEFAULT 'http://localhost:5170/api/v1/webhook/alipay',
+    return_url        VARCHAR(500)  NOT NULL DEFAULT 'http://localhost:4001/payment/success',
+    exchange_rate     NUMERIC(12,6) NOT NULL DEFAULT 1,  -- 订单金额 → CNY
+    min_amount        NUMERIC(10,2) NOT NULL DEFAULT 0.01,
+    max_amount        NUMERIC(10,2) NOT NULL DEFAULT 10000.00,
+    fee_rate          NUMERIC(5,4)  NOT NULL DEFAULT 0.0060,
+    is_active         BOOLEAN       NOT NULL DEFAULT false,
+    created_at        TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    updated_at        TIMESTAMPTZ   NOT NULL DEFAULT NOW()
+);
+
+-- 2. 微信支付配置（API v3）
+CREATE TABLE IF NOT EXISTS wechatpay_configs (
+    id                     BIGINT        PRIMARY KEY,
+    mch_id                 VARCHAR(32)   NOT NULL,
+    app_id                 VARCHAR(64)   NOT NULL,
+    merchant_serial_no     VARCHAR(64)   NOT NULL,  -- 商户 API 证书序列号
+    merchant_private_key   TEXT          NOT NULL,  -- 商户 API 私钥（apiclient_key.pem）
+    api_v3_key             VARCHAR(32)   NOT NULL,  -- APIv3 密钥（32 字节）
+    platform_public_key    TEXT          NOT NULL,  -- 微信支付公钥
+    platform_public_key_id VARCHAR(64),             -- 微信支付公钥 ID（对应 Wechatpay-Serial）
+    notify_url             VARCHAR(500)  NOT NULL DEFAULT 'http://localhost:5170/api/v1/webhook/wechatpay',
+    exchange_rate          NUMERIC(12,6) NOT NULL DEFAULT 1,
+    min_amount             NUMERIC(10,2) NOT NULL DEFAULT 0.01,
+    max_amount             NUMERIC(10,2) NOT NULL DEFAULT 10000.00,
+    fee_rate               NUMERIC(5,4)  NOT NULL DEFAULT 0.0060,
+    is_active              BOOLEAN       NOT NULL DEFAULT false,
+    created_at             TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    updated_at             TIMESTAMPTZ   NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_alipay_configs_active ON alipay_configs(is_active);
+CREATE INDEX IF NOT EXISTS idx_wechatpay_configs_active ON wechatpay_configs(is_active);
@@ -0,0 +1,28 @@
+-- RSWS 发票 / 收据
+-- 订单支付完成后生成一条发票记录，快照买家、商品、金额、币种、支付方式与交易哈希，
+-- 之后订单、用户或资源信息变更不影响已开具的发票。
+
+-- 发票号序列：INV-YYYYMMDD-000001
+CREATE SEQUENCE IF NOT EXISTS invoice_number_seq START 1;
+
+CREATE TABLE IF NOT EXISTS invoices (
+    id             BIGINT        PRIMARY KEY,  -- ID 由 Rust snowflake::next_id() 生成
+    invoice_no     VARCHAR(32)   NOT NULL UNIQUE,
+    order_id       BIGINT        NOT NULL UNIQUE REFERENCES orders(id) ON DELETE RESTRICT,
+    user_id        BIGINT,
+    buyer_email    VARCHAR(255)  NOT NULL DEFAULT '',
+    buyer_name     VARCHAR(100)  NOT NULL DEFAULT '',
+    items          JSONB         NOT NULL DEFAULT '[]',  -- [{resource_id, title, quantity, unit_price, amount}]
+    subtotal       NUMERIC(10,2) NOT NULL,               -- 订单金额
+    paid_amount    NUMERIC(20,6) NOT NULL,               -- 实付金额（按 currency 计）
+    currency       VARCHAR(10)   NOT NULL,
+    payment_method VARCHAR(50)   NOT NULL DEFAULT '',
+    tx_hash        VARCHAR(255),                         -- 支付网关交易号 / 链上交易哈希
+    issued_at      TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    emailed_at     TIMESTAMPTZ,                          -- 付款成功邮件发送时间，NULL 表示待发送
+    created_at     TIMESTAMPTZ   NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_invoices_user_id ON invoices(user_id);
+CREATE INDEX IF NOT EXISTS idx_invoices_issued_at ON invoices(issued_at);
+CREATE INDEX IF NOT EXISTS idx_invoices_pending_email ON invoices(id) WHERE emailed_at IS NULL;
@@ -0,0 +1,100 @@
+-- RSWS 复式记账总账 + 预付余额
+--
+-- 记账约定：ledger_postings.amount 借方为正、贷方为负，同一凭证的分录合计必须为 0。
+-- 科目（ledger_accounts.code）：
+--   payment_clearing          资产  第三方支付 / 链上收款待清算
+--   platform_revenue          收入  平台收入
+--   fees                      费用  支付通道手续费
+--   user_wallet:{user_id}     负债  用户预付余额
+--   creator_payable:{user_id} 负债  应付创作者分成
+-- 凭证与分录只允许追加，不允许修改或删除。
+
+-- 1. 科目
+CREATE TABLE IF NOT EXISTS ledger_accounts (
+    id           BIGINT         PRIMARY KEY,  -- ID 由 Rust snowflake::next_id() 生成
+    code         VARCHAR(64)    NOT NULL UNIQUE,
+    account_type VARCHAR(20)    NOT NULL,     -- asset / liability / revenue / expense
+    owner_id     BIGINT,                      -- user_wallet / creator_payable 对应的用户 ID
+    balance      NUMERIC(20,6)  NOT NULL DEFAULT 0,  -- 借方余额缓存（借正贷负），与分录同事务更新
+    created_at   TIMESTAMPTZ    NOT NULL DEFAULT NOW(),
+    updated_at   TIMESTAMPTZ    NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_ledger_accounts_owner ON ledger_accounts(owner_id);
+
+-- 2. 凭证
+CREATE TABLE IF NOT EXISTS ledger_journal_entries (
+    id              BIGINT        PRIMARY KEY,
+    entry_type      VARCHAR(50)   NOT NULL,   -- order_payment / order_refund / topup / usdt_overpayment / commission_settlement
+    reference_type  VARCHAR(50),
+    reference_id    BIGINT,
+    idempotency_key VARCHAR(128)  NOT NULL UNIQUE,
+    description     TEXT,
+    created_at      TIMESTAMPTZ   NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_ledger_entries_reference ON ledger_journal_entries(reference_type, reference_id);
+
+-- 3. 分录
+CREATE TABLE IF NOT EXISTS ledger_postings (
+    id         BIGINT        PRIMARY KEY,
+    entry_id   BIGINT        NOT NULL REFERENCES ledger_journal_entries(id),
+    account_id BIGINT        NOT NULL REFERENCES ledger_accounts(id),
+    amount     NUMERIC(20,6) NOT NULL CHECK (amount <> 0),
+    created_at TIMESTAMPTZ   NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_ledger_postings_entry ON ledger_postings(entry_id);
+CREATE INDEX IF NOT EXISTS idx_ledger_postings_account ON ledger_postings(account_id, created_at);
+
+-- 凭证借贷平衡校验（提交时检查）
+CREATE OR REPLACE FUNCTION ledger_check_entry_balanced() RETURNS TRIGGER AS $$
+BEGIN
+    IF (SELECT COALESCE(SUM(amount), 0) FROM ledger_postings WHERE entry_id = NEW.entry_id) <> 0 THEN
+        RAISE EXCEPTION 'Journal entry % is not balanced', NEW.entry_id;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_ledger_postings_balanced ON ledger_postings;
+CREATE CONSTRAINT TRIGGER trg_ledger_postings_balanced
+    AFTER INSERT ON ledger_postings
+    DEFERRABLE INITIALLY DEFERRED
+    FOR EACH ROW EXECUTE FUNCTION ledger_check_entry_balanced();
+
+-- 凭证与分录只允许追加
+CREATE OR REPLACE FUNCTION ledger_reject_mutation() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION '% is append-only', TG_TABLE_NAME;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_ledger_postings_immutable ON ledger_postings;
+CREATE TRIGGER trg_ledger_postings_immutable
+    BEFORE UPDATE OR DELETE ON ledger_postings
+    FOR EACH ROW EXECUTE FUNCTION ledger_reject_mutation();
+
+DROP TRIGGER IF EXISTS trg_ledger_entries_immutable ON ledger_journal_entries;
+CREATE TRIGGER trg_ledger_entries_immutable
+    BEFORE UPDATE OR DELETE ON ledger_journal_entries
+    FOR EACH ROW EXECUTE FUNCTION ledger_reject_mutation();
+
+-- 4. 余额充值单
+CREATE TABLE IF NOT EXISTS wallet_topups (
+    id              BIGINT        PRIMARY KEY,
+    user_id         BIGINT        NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    amount          NUMERIC(20,6) NOT NULL CHECK (amount > 0),
+    payment_method  VARCHAR(50)   NOT NULL,
+    receive_address VARCHAR(64),                               -- USDT 收款地址
+    status          VARCHAR(20)   NOT NULL DEFAULT 'pending',  -- pending / completed / failed
+    provider_tx_id  VARCHAR(255),                              -- 网关订单号 / 交易号 / 链上交易哈希
+    created_at      TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    updated_at      TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    completed_at    TIMESTAMPTZ,
+    expired_at      TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_wallet_topups_user ON wallet_topups(user_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_wallet_topups_pending ON wallet_topups(payment_method, receive_address, amount) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_wallet_topups_provider_tx ON wallet_topups(provider_tx_id);
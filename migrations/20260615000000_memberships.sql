@@ -0,0 +1,39 @@
+-- RSWS 会员套餐
+-- 套餐按分类授权：订阅有效期内，套餐分类及其所有子分类（沿 categories.parent_id 逐级查找）
+-- 下的资源视为已购买。会员订单复用 orders 表（resource_id 为 NULL），
+-- 每笔订单对应一期订阅，支付完成后激活，续费从当前有效期结束时顺延。
+
+-- 1. 套餐
+CREATE TABLE IF NOT EXISTS membership_plans (
+    id            BIGINT        PRIMARY KEY,  -- ID 由 Rust snowflake::next_id() 生成
+    name          VARCHAR(100)  NOT NULL,
+    description   TEXT,
+    category_id   BIGINT        NOT NULL REFERENCES categories(id) ON DELETE RESTRICT,
+    duration_days INTEGER       NOT NULL CHECK (duration_days > 0),
+    price         NUMERIC(10,2) NOT NULL CHECK (price > 0),
+    is_active     BOOLEAN       NOT NULL DEFAULT true,
+    sort_order    INTEGER       NOT NULL DEFAULT 0,
+    created_at    TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    updated_at    TIMESTAMPTZ   NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_membership_plans_category ON membership_plans(category_id);
+
+-- 2. 用户订阅（每笔订单一期）
+CREATE TABLE IF NOT EXISTS user_memberships (
+    id          BIGINT       PRIMARY KEY,
+    user_id     BIGINT       NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    plan_id     BIGINT       NOT NULL REFERENCES membership_plans(id) ON DELETE RESTRICT,
+    category_id BIGINT       NOT NULL,                 -- 下单时套餐授权分类快照
+    order_id    BIGINT       NOT NULL UNIQUE REFERENCES orders(id) ON DELETE CASCADE,
+    status      VARCHAR(20)  NOT NULL DEFAULT 'pending',  -- pending / active / refunded
+    starts_at   TIMESTAMPTZ,
+    expires_at  TIMESTAMPTZ,
+    reminded_at TIMESTAMPTZ,                           -- 到期提醒邮件发送时间
+    created_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    updated_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_memberships_access ON user_memberships(user_id, category_id, expires_at) WHERE status = 'active';
+CREATE INDEX IF NOT EXISTS idx_user_memberships_plan ON user_memberships(user_id, plan_id);
+CREATE INDEX IF NOT EXISTS idx_user_memberships_expiry ON user_memberships(expires_at) WHERE status = 'active' AND reminded_at IS NULL;
@@ -0,0 +1,25 @@
+-- RSWS 资源授权档位
+-- 同一资源可按 personal / commercial / extended 三档授权出售，每档独立定价、条款与下载次数。
+-- 订单记录所购档位（orders.license_id），升级购买只收取与已持有档位的差价；
+-- 授权档位上线前的订单 license_id 为空，视为最低档位。
+
+-- 1. 授权档位
+CREATE TABLE IF NOT EXISTS resource_licenses (
+    id            BIGINT        PRIMARY KEY,  -- ID 由 Rust snowflake::next_id() 生成
+    resource_id   BIGINT        NOT NULL REFERENCES resources(id) ON DELETE CASCADE,
+    tier          VARCHAR(20)   NOT NULL CHECK (tier IN ('personal', 'commercial', 'extended')),
+    name          VARCHAR(100)  NOT NULL,
+    price         NUMERIC(10,2) NOT NULL CHECK (price > 0),
+    terms         TEXT,
+    max_downloads INTEGER       CHECK (max_downloads IS NULL OR max_downloads > 0),  -- NULL 表示不限
+    is_active     BOOLEAN       NOT NULL DEFAULT true,
+    created_at    TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    updated_at    TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    UNIQUE (resource_id, tier)
+);
+
+-- 2. 订单记录所购档位与已下载次数
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS license_id BIGINT REFERENCES resource_licenses(id) ON DELETE SET NULL;
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS download_count INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_orders_user_resource ON orders(user_id, resource_id) WHERE status IN ('paid', 'completed');
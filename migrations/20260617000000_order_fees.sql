@@ -0,0 +1,8 @@
+-- RSWS 订单手续费
+-- 下单时按支付通道配置（fee_rate / min_amount / max_amount）报价：
+-- - fee_amount：按通道费率估算的手续费
+-- - surcharge：买家承担的部分（system_configs.payment_fee_bearer = 'buyer' 时等于 fee_amount），已含在 amount 中
+-- 平台承担手续费（默认）时 surcharge 为 0，amount 即商品金额。
+
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS surcharge  NUMERIC(10,2) NOT NULL DEFAULT 0;
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS fee_amount NUMERIC(10,2) NOT NULL DEFAULT 0;
@@ -0,0 +1,30 @@
+-- RSWS 入站 Webhook 日志
+-- 所有支付网关回调在处理前先落库，按 (source, event_id) 去重；处理结果回写 status / response_*。
+-- 签名校验通过后才落库。网关重试时：上次处理失败、或处理超时仍为 received 的记录重新处理
+-- （retry_count + 1）；处理中的记录应答失败让网关稍后重试；其余视为重复投递直接应答。
+-- 管理员可按原始载荷重放已存储的回调（修复处理逻辑后补处理）。
+
+CREATE TABLE IF NOT EXISTS webhook_logs (
+    id               BIGINT       PRIMARY KEY,  -- ID 由 Rust snowflake::next_id() 生成
+    webhook_type     VARCHAR(20)  NOT NULL DEFAULT 'payment',
+    source           VARCHAR(20)  NOT NULL,     -- paypal / usdt / alipay / wechatpay
+    event_id         VARCHAR(128),              -- 网关事件 ID，NULL 时不去重
+    event_type       VARCHAR(100) NOT NULL,
+    payload          JSONB        NOT NULL,
+    body             TEXT,                      -- 原始请求体（验签依赖原文的来源保留）
+    headers          JSONB,
+    signature        TEXT,
+    status           VARCHAR(20)  NOT NULL DEFAULT 'received'
+                     CHECK (status IN ('received', 'processed', 'ignored', 'failed')),
+    response_code    INTEGER,
+    response_message TEXT,
+    processed_at     TIMESTAMPTZ,
+    retry_count      INTEGER      NOT NULL DEFAULT 0,
+    ip_address       VARCHAR(45),
+    received_at      TIMESTAMPTZ  NOT NULL DEFAULT NOW(),  -- 最近一次开始处理的时间（判断处理超时）
+    created_at       TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    UNIQUE (source, event_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_logs_created ON webhook_logs(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_webhook_logs_status ON webhook_logs(status, created_at DESC);
@@ -0,0 +1,40 @@
+-- RSWS 出站事件 Webhook
+-- 管理员配置订阅端点（URL、签名密钥、事件过滤），事件发生时为每个匹配的订阅写入一条投递记录，
+-- 后台任务按 next_attempt_at 取出投递，失败按指数退避重试，超过最大次数进入死信（dead），
+-- 管理员可手动重新投递。取代内存中的 CrossPlatformService::sync_data。
+
+-- 1. 订阅端点
+CREATE TABLE IF NOT EXISTS webhook_subscriptions (
+    id          BIGINT       PRIMARY KEY,  -- ID 由 Rust snowflake::next_id() 生成
+    name        VARCHAR(100) NOT NULL,
+    url         TEXT         NOT NULL,
+    secret      VARCHAR(128) NOT NULL,
+    event_types TEXT[]       NOT NULL DEFAULT '{}',  -- 为空表示订阅全部事件
+    is_active   BOOLEAN      NOT NULL DEFAULT true,
+    created_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    updated_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
+
+-- 2. 投递队列
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id                 BIGINT       PRIMARY KEY,
+    subscription_id    BIGINT       NOT NULL REFERENCES webhook_subscriptions(id) ON DELETE CASCADE,
+    event_id           BIGINT       NOT NULL,  -- 同一事件投递给各订阅时相同
+    event_key          VARCHAR(100),           -- 幂等键（如 order.paid:{order_id}），重复事件不再入队
+    event_type         VARCHAR(50)  NOT NULL,
+    payload            JSONB        NOT NULL,
+    status             VARCHAR(20)  NOT NULL DEFAULT 'pending'
+                       CHECK (status IN ('pending', 'succeeded', 'dead')),
+    attempts           INTEGER      NOT NULL DEFAULT 0,
+    next_attempt_at    TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    last_response_code INTEGER,
+    last_error         TEXT,
+    delivered_at       TIMESTAMPTZ,
+    created_at         TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    updated_at         TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    UNIQUE (subscription_id, event_id),
+    UNIQUE (subscription_id, event_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due ON webhook_deliveries(next_attempt_at) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_subscription ON webhook_deliveries(subscription_id, created_at DESC);
@@ -0,0 +1,63 @@
+-- RSWS 订单风控
+-- 下单与发起支付时按规则为订单打分，命中规则的动作取最严重者（allow < review < block），
+-- 累计分数达到阈值时同样升级为 review / block。
+-- review 的订单进入管理员审核队列，审核前不可支付；block 的订单直接取消。
+-- 每次评估写入 order_risk_assessments，审核结论记录在订单上。
+
+-- 1. 风控规则
+CREATE TABLE IF NOT EXISTS risk_rules (
+    id          BIGINT        PRIMARY KEY,  -- ID 由 Rust snowflake::next_id() 生成
+    name        VARCHAR(100)  NOT NULL,
+    -- user_orders_1h / ip_orders_1h / device_accounts_24h / account_age_hours /
+    -- failed_payments_24h / payer_country_mismatch / order_amount
+    signal      VARCHAR(50)   NOT NULL,
+    operator    VARCHAR(10)   NOT NULL CHECK (operator IN ('gt', 'gte', 'lt', 'lte', 'eq')),
+    threshold   NUMERIC(18,2) NOT NULL,
+    score       INTEGER       NOT NULL DEFAULT 0,
+    action      VARCHAR(10)   NOT NULL DEFAULT 'allow' CHECK (action IN ('allow', 'review', 'block')),
+    stage       VARCHAR(20)   NOT NULL DEFAULT 'any' CHECK (stage IN ('creation', 'payment', 'any')),
+    is_active   BOOLEAN       NOT NULL DEFAULT true,
+    description TEXT,
+    created_at  TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    updated_at  TIMESTAMPTZ   NOT NULL DEFAULT NOW()
+);
+
+-- 默认规则（可在后台调整或停用）
+INSERT INTO risk_rules (id, name, signal, operator, threshold, score, action, stage, description) VALUES
+    (7400000000001, '用户 1 小时内下单过多',       'user_orders_1h',         'gte', 5,    30, 'review', 'creation', '疑似盗卡试单'),
+    (7400000000002, '用户 1 小时内下单异常',       'user_orders_1h',         'gte', 15,   60, 'block',  'creation', NULL),
+    (7400000000003, '同一 IP 1 小时内下单过多',    'ip_orders_1h',           'gte', 10,   40, 'review', 'creation', '多账号同 IP 下单'),
+    (7400000000004, '同一设备 24 小时内多账号登录', 'device_accounts_24h',    'gte', 3,    40, 'review', 'any',      '设备取登录日志中客户端上报的 device_id'),
+    (7400000000005, '新注册账户',                 'account_age_hours',      'lt',  1,    20, 'allow',  'any',      NULL),
+    (7400000000006, '24 小时内支付失败多次',       'failed_payments_24h',    'gte', 3,    40, 'review', 'any',      NULL),
+    (7400000000007, '24 小时内支付失败过多',       'failed_payments_24h',    'gte', 8,    80, 'block',  'any',      NULL),
+    (7400000000008, 'PayPal 付款人国家与下单 IP 不一致', 'payer_country_mismatch', 'eq', 1, 30, 'review', 'payment', NULL)
+ON CONFLICT (id) DO NOTHING;
+
+-- 2. 评估记录
+CREATE TABLE IF NOT EXISTS order_risk_assessments (
+    id            BIGINT       PRIMARY KEY,
+    order_id      BIGINT       NOT NULL REFERENCES orders(id) ON DELETE CASCADE,
+    stage         VARCHAR(20)  NOT NULL CHECK (stage IN ('creation', 'payment')),
+    score         INTEGER      NOT NULL,
+    action        VARCHAR(10)  NOT NULL CHECK (action IN ('allow', 'review', 'block')),
+    triggered     JSONB        NOT NULL DEFAULT '[]',  -- 命中的规则及信号值
+    signals       JSONB        NOT NULL DEFAULT '{}',  -- 评估时采集的全部信号
+    ip_address    VARCHAR(45),
+    ip_country    VARCHAR(2),
+    user_agent    TEXT,
+    payer_country VARCHAR(2),
+    created_at    TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_risk_assessments_order ON order_risk_assessments(order_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_order_risk_assessments_ip ON order_risk_assessments(ip_address, created_at) WHERE stage = 'creation';
+
+-- 3. 订单风控状态：clear / review（待审核）/ approved（审核通过）/ rejected（拦截或审核拒绝）
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS risk_score INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS risk_status VARCHAR(20) NOT NULL DEFAULT 'clear';
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS risk_reviewed_by BIGINT;
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS risk_reviewed_at TIMESTAMPTZ;
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS risk_review_note TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_orders_risk_review ON orders(created_at) WHERE risk_status = 'review';
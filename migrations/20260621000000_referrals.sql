@@ -0,0 +1,52 @@
+-- RSWS 推荐计划
+-- 每个用户一个推荐码；点击推荐链接时记录点击，注册与下单时按参数或 Cookie 归因到推荐人。
+-- 推荐佣金写入 commission_records（referrer_id 非空），由平台收入承担，与创作者分成相互独立；
+-- 冻结期（commission_hold_days）过后结算到推荐人余额，订单退款时未结算的佣金作废。
+
+-- 1. 推荐码
+CREATE TABLE IF NOT EXISTS referral_codes (
+    user_id    BIGINT       PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    code       VARCHAR(16)  NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
+
+-- 2. 推荐链接点击
+CREATE TABLE IF NOT EXISTS referral_clicks (
+    id           BIGINT       PRIMARY KEY,  -- ID 由 Rust snowflake::next_id() 生成
+    referrer_id  BIGINT       NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    code         VARCHAR(16)  NOT NULL,
+    ip_address   VARCHAR(45),
+    user_agent   TEXT,
+    landing_path VARCHAR(500),
+    created_at   TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_referral_clicks_referrer ON referral_clicks(referrer_id, created_at);
+
+-- 3. 注册归因（每个用户最多一个推荐人）
+CREATE TABLE IF NOT EXISTS referrals (
+    referee_id  BIGINT       PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    referrer_id BIGINT       NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    code        VARCHAR(16)  NOT NULL,
+    created_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    CHECK (referee_id <> referrer_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_referrals_referrer ON referrals(referrer_id, created_at);
+
+-- 4. 订单归因
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS referrer_id BIGINT REFERENCES users(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_orders_referrer ON orders(referrer_id) WHERE referrer_id IS NOT NULL;
+
+-- 5. 每笔订单最多一条推荐佣金
+CREATE UNIQUE INDEX IF NOT EXISTS uq_commission_records_referral
+    ON commission_records(order_id) WHERE referrer_id IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_commission_records_referrer ON commission_records(referrer_id, created_at);
+
+-- 6. 配置
+INSERT INTO system_configs (id, config_key, config_value, config_type, description) VALUES
+    (7500000000001, 'referral_commission_rate', '0.05', 'decimal', '推荐佣金比例（商品金额 × 比例），0 为关闭'),
+    (7500000000002, 'commission_hold_days', '7', 'int', '佣金冻结天数，过后结算到收款人余额')
+ON CONFLICT (config_key) DO NOTHING;
@@ -0,0 +1,16 @@
+-- RSWS 创作者收益
+-- 每笔已支付订单记录一条创作者分成（commission_records.referrer_id 为空），
+-- 分成 = 商品金额（amount - surcharge）× resources.commission_rate，与总账应付创作者分成一致。
+-- 月度对账单按 orders.paid_at 归属月份。
+
+-- 1. 每笔订单最多一条创作者分成
+CREATE UNIQUE INDEX IF NOT EXISTS uq_commission_records_creator
+    ON commission_records(order_id) WHERE referrer_id IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_commission_records_user ON commission_records(user_id, status);
+
+-- 2. 支付时间：此前未写入，已支付订单以最后更新时间回填
+UPDATE orders SET paid_at = updated_at
+WHERE paid_at IS NULL AND status::TEXT IN ('paid', 'completed');
+
+CREATE INDEX IF NOT EXISTS idx_resources_provider ON resources(provider_id) WHERE provider_id IS NOT NULL;
@@ -0,0 +1,46 @@
+-- RSWS 创作者分成规则
+-- 订单支付时按规则计算创作者分成：规则可限定分类（含子分类）、创作者等级、价格区间和生效时间，
+-- 多条规则命中时取 priority 最高者，同优先级取条件更多（更具体）者，再取 ID 较小者。
+-- 分成 = 商品金额（amount - surcharge）× 比例，再按保底 / 封顶调整，且不超过商品金额；
+-- 无规则命中时沿用 resources.commission_rate。
+-- 命中的规则 ID 与实际分成比例记录在 commission_records 上，总账应付创作者分成使用同一结果。
+
+-- 1. 规则条件、优先级与保底 / 封顶
+--    rule_type: percentage（按规则比例）/ resource（按资源自身比例，仅套用保底 / 封顶）
+--    min_amount / max_amount: 商品金额区间 [min_amount, max_amount)
+ALTER TABLE commission_rules ADD COLUMN IF NOT EXISTS category_id BIGINT REFERENCES categories(id) ON DELETE CASCADE;
+ALTER TABLE commission_rules ADD COLUMN IF NOT EXISTS creator_tier VARCHAR(30);
+ALTER TABLE commission_rules ADD COLUMN IF NOT EXISTS starts_at TIMESTAMPTZ;
+ALTER TABLE commission_rules ADD COLUMN IF NOT EXISTS ends_at TIMESTAMPTZ;
+ALTER TABLE commission_rules ADD COLUMN IF NOT EXISTS priority INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE commission_rules ADD COLUMN IF NOT EXISTS min_commission NUMERIC(10,2);
+ALTER TABLE commission_rules ADD COLUMN IF NOT EXISTS max_commission NUMERIC(10,2);
+ALTER TABLE commission_rules ADD COLUMN IF NOT EXISTS description TEXT;
+
+-- 此前 rule_type 未被使用：取值未知的旧规则改为 resource（按资源自身比例），
+-- 避免其 rate 作为无条件规则套用到所有订单，分成结果与迁移前一致
+UPDATE commission_rules SET rule_type = 'resource' WHERE rule_type IS NULL OR rule_type NOT IN ('percentage', 'resource');
+UPDATE commission_rules SET rate = COALESCE(rate, 0), is_active = COALESCE(is_active, true),
+    created_at = COALESCE(created_at, NOW()), updated_at = COALESCE(updated_at, NOW());
+
+ALTER TABLE commission_rules ALTER COLUMN rate SET NOT NULL;
+ALTER TABLE commission_rules ALTER COLUMN is_active SET NOT NULL;
+ALTER TABLE commission_rules ALTER COLUMN created_at SET NOT NULL;
+ALTER TABLE commission_rules ALTER COLUMN updated_at SET NOT NULL;
+ALTER TABLE commission_rules ALTER COLUMN min_amount DROP DEFAULT;
+
+ALTER TABLE commission_rules DROP CONSTRAINT IF EXISTS chk_commission_rules_type;
+ALTER TABLE commission_rules ADD CONSTRAINT chk_commission_rules_type
+    CHECK (rule_type IN ('percentage', 'resource'));
+
+CREATE INDEX IF NOT EXISTS idx_commission_rules_active ON commission_rules(priority DESC) WHERE is_active = true;
+
+-- 2. 创作者等级（未设置时为 standard）
+CREATE TABLE IF NOT EXISTS creator_tiers (
+    user_id    BIGINT       PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    tier       VARCHAR(30)  NOT NULL,
+    updated_by BIGINT,
+    updated_at TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_creator_tiers_tier ON creator_tiers(tier);
@@ -0,0 +1,35 @@
+-- RSWS 资源审核
+-- 用户上传的资源需审核通过后才公开：draft（草稿）→ pending（待审核）→ approved / rejected，
+-- 已通过的资源可被下架（taken_down）。创作者修改已提交的资源后重新进入待审核。
+-- 平台资源直接为 approved；已有资源按 approved 处理，保持现有可见性。
+-- 每次提交与审核决定写入 resource_reviews。
+
+-- 1. 资源审核状态
+ALTER TABLE resources ADD COLUMN IF NOT EXISTS review_status VARCHAR(20) NOT NULL DEFAULT 'approved';
+ALTER TABLE resources ADD COLUMN IF NOT EXISTS review_reason TEXT;
+ALTER TABLE resources ADD COLUMN IF NOT EXISTS submitted_at TIMESTAMPTZ;
+ALTER TABLE resources ADD COLUMN IF NOT EXISTS reviewed_by BIGINT;
+ALTER TABLE resources ADD COLUMN IF NOT EXISTS reviewed_at TIMESTAMPTZ;
+
+ALTER TABLE resources DROP CONSTRAINT IF EXISTS chk_resources_review_status;
+ALTER TABLE resources ADD CONSTRAINT chk_resources_review_status
+    CHECK (review_status IN ('draft', 'pending', 'approved', 'rejected', 'taken_down'));
+
+CREATE INDEX IF NOT EXISTS idx_resources_review_status ON resources(review_status, submitted_at);
+
+-- 2. 审核记录
+CREATE TABLE IF NOT EXISTS resource_reviews (
+    id          BIGINT       PRIMARY KEY,
+    resource_id BIGINT       NOT NULL REFERENCES resources(id) ON DELETE CASCADE,
+    -- submit / approve / reject / take_down
+    action      VARCHAR(20)  NOT NULL,
+    from_status VARCHAR(20)  NOT NULL,
+    to_status   VARCHAR(20)  NOT NULL,
+    reason      TEXT,
+    actor_id    BIGINT,
+    -- user / admin
+    actor_type  VARCHAR(10)  NOT NULL,
+    created_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_resource_reviews_resource ON resource_reviews(resource_id, created_at);
@@ -0,0 +1,44 @@
+-- RSWS 资源版本
+-- 每次发布新文件记录为一个版本（文件、大小、校验和、更新日志、发布日期），
+-- resources.file_url / version / file_size 同步为最新版本，供旧代码路径与列表展示使用。
+-- 买家可下载其有权获取的任一版本：
+--   resources.update_months 为空时包含全部后续更新；
+--   否则为购买时已发布的版本及购买后 N 个月内发布的版本（按最近一次付款计）。
+-- 新版本发布后由后台任务邮件通知已购买的用户（owners_notified_at 防止重复通知）。
+
+-- 1. 更新政策
+ALTER TABLE resources ADD COLUMN IF NOT EXISTS update_months INTEGER;
+
+ALTER TABLE resources DROP CONSTRAINT IF EXISTS chk_resources_update_months;
+ALTER TABLE resources ADD CONSTRAINT chk_resources_update_months
+    CHECK (update_months IS NULL OR update_months > 0);
+
+-- 2. 资源版本
+CREATE TABLE IF NOT EXISTS resource_versions (
+    id                 BIGINT        PRIMARY KEY,  -- ID 由 Rust snowflake::next_id() 生成
+    resource_id        BIGINT        NOT NULL REFERENCES resources(id) ON DELETE CASCADE,
+    version            VARCHAR(50)   NOT NULL,
+    file_url           VARCHAR(500)  NOT NULL,
+    file_size          BIGINT,
+    -- SHA-256 十六进制
+    checksum           VARCHAR(128),
+    changelog          TEXT,
+    released_at        TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    created_by         BIGINT,
+    owners_notified_at TIMESTAMPTZ,
+    created_at         TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    UNIQUE (resource_id, version)
+);
+
+CREATE INDEX IF NOT EXISTS idx_resource_versions_resource ON resource_versions(resource_id, released_at DESC);
+CREATE INDEX IF NOT EXISTS idx_resource_versions_pending_notify ON resource_versions(created_at) WHERE owners_notified_at IS NULL;
+
+-- 3. 已有文件作为首个版本（沿用资源 ID 作为版本 ID，不发送通知）
+INSERT INTO resource_versions (id, resource_id, version, file_url, file_size, released_at, created_by, owners_notified_at, created_at)
+SELECT r.id, r.id, COALESCE(NULLIF(r.version, ''), '1.0'), r.file_url, r.file_size,
+       COALESCE(r.created_at, NOW()), r.provider_id, NOW(), NOW()
+FROM resources r
+WHERE r.file_url IS NOT NULL AND r.file_url <> ''
+ON CONFLICT DO NOTHING;
+
+UPDATE resources SET version = '1.0' WHERE file_url IS NOT NULL AND file_url <> '' AND (version IS NULL OR version = '');
@@ -0,0 +1,49 @@
+-- RSWS 资源评分与评价
+-- 已购买资源的用户可评分（1–5）并评价，每个用户每个资源一条，可修改。
+-- 创作者可回复评价；用户可举报评价，管理员隐藏 / 恢复（处理时关闭该评价的待处理举报）。
+-- 资源的平均分与评分数按可见评价冗余到 resources，用于列表排序与展示。
+
+-- 1. 资源评分汇总
+ALTER TABLE resources ADD COLUMN IF NOT EXISTS rating_avg NUMERIC(3, 2) NOT NULL DEFAULT 0;
+ALTER TABLE resources ADD COLUMN IF NOT EXISTS rating_count INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_resources_rating ON resources(rating_avg DESC, rating_count DESC);
+
+-- 2. 评价
+CREATE TABLE IF NOT EXISTS resource_ratings (
+    id            BIGINT       PRIMARY KEY,
+    resource_id   BIGINT       NOT NULL REFERENCES resources(id) ON DELETE CASCADE,
+    user_id       BIGINT       NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    rating        SMALLINT     NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    content       TEXT,
+    -- 创作者回复
+    reply         TEXT,
+    replied_at    TIMESTAMPTZ,
+    -- visible / hidden
+    status        VARCHAR(10)  NOT NULL DEFAULT 'visible' CHECK (status IN ('visible', 'hidden')),
+    hidden_reason TEXT,
+    hidden_by     BIGINT,
+    hidden_at     TIMESTAMPTZ,
+    report_count  INTEGER      NOT NULL DEFAULT 0,
+    created_at    TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    updated_at    TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    UNIQUE (resource_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_resource_ratings_resource ON resource_ratings(resource_id, status, created_at DESC);
+
+-- 3. 评价举报
+CREATE TABLE IF NOT EXISTS rating_reports (
+    id          BIGINT       PRIMARY KEY,
+    rating_id   BIGINT       NOT NULL REFERENCES resource_ratings(id) ON DELETE CASCADE,
+    reporter_id BIGINT       NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reason      TEXT         NOT NULL,
+    -- open / resolved
+    status      VARCHAR(10)  NOT NULL DEFAULT 'open' CHECK (status IN ('open', 'resolved')),
+    resolved_by BIGINT,
+    resolved_at TIMESTAMPTZ,
+    created_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    UNIQUE (rating_id, reporter_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_rating_reports_open ON rating_reports(status, created_at);
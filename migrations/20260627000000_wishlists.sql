@@ -0,0 +1,45 @@
+-- RSWS 收藏与心愿单
+-- 用户可收藏资源，并创建公开或私有的命名心愿单。
+-- saved_resources 记录用户保存过的资源（收藏或任一心愿单，每个用户每个资源一条）及上次通知时的价格：
+-- 资源降价（含限时降价促销）时后台任务邮件通知，价格上涨时只同步价格不通知。
+-- 管理员"最受期待"报表按 saved_resources 统计。
+
+-- 1. 收藏
+CREATE TABLE IF NOT EXISTS favorites (
+    user_id     BIGINT       NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    resource_id BIGINT       NOT NULL REFERENCES resources(id) ON DELETE CASCADE,
+    created_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, resource_id)
+);
+
+-- 2. 心愿单
+CREATE TABLE IF NOT EXISTS wishlists (
+    id          BIGINT       PRIMARY KEY,
+    user_id     BIGINT       NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name        VARCHAR(100) NOT NULL,
+    description TEXT,
+    is_public   BOOLEAN      NOT NULL DEFAULT false,
+    created_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    updated_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS wishlist_items (
+    wishlist_id BIGINT       NOT NULL REFERENCES wishlists(id) ON DELETE CASCADE,
+    resource_id BIGINT       NOT NULL REFERENCES resources(id) ON DELETE CASCADE,
+    created_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (wishlist_id, resource_id)
+);
+
+-- 3. 已保存资源（降价通知与报表）
+CREATE TABLE IF NOT EXISTS saved_resources (
+    user_id       BIGINT         NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    resource_id   BIGINT         NOT NULL REFERENCES resources(id) ON DELETE CASCADE,
+    -- 保存时或上次通知时的价格
+    watched_price NUMERIC(10, 2) NOT NULL,
+    notified_at   TIMESTAMPTZ,
+    created_at    TIMESTAMPTZ    NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, resource_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_saved_resources_resource ON saved_resources(resource_id);
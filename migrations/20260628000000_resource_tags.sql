@@ -0,0 +1,28 @@
+-- RSWS 资源标签与分面筛选
+-- 标签由管理员维护，创作者为自己的资源设置标签时可自动新建。slug 为规范化后的标签名，用于筛选参数。
+-- 资源列表按标签、价格区间、免费 / 付费、supported_os、归属类型与创作者筛选，并返回各分面计数。
+
+-- 1. 标签
+CREATE TABLE IF NOT EXISTS tags (
+    id         BIGINT       PRIMARY KEY,
+    name       VARCHAR(30)  NOT NULL,
+    slug       VARCHAR(60)  NOT NULL UNIQUE,
+    created_by BIGINT,
+    created_at TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
+
+-- 2. 资源标签
+CREATE TABLE IF NOT EXISTS resource_tags (
+    resource_id BIGINT       NOT NULL REFERENCES resources(id) ON DELETE CASCADE,
+    tag_id      BIGINT       NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    created_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (resource_id, tag_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_resource_tags_tag ON resource_tags(tag_id);
+
+-- 3. 分面筛选索引
+CREATE INDEX IF NOT EXISTS idx_resources_supported_os ON resources USING GIN (supported_os);
+CREATE INDEX IF NOT EXISTS idx_resources_price ON resources(price);
+CREATE INDEX IF NOT EXISTS idx_resources_provider ON resources(provider_id);
@@ -0,0 +1,85 @@
+-- RSWS 资源全文搜索
+-- search_vector 按字段加权：标题 A、标签 B、简介 C、详情 D，由触发器维护（标签增删与改名同步刷新）。
+-- 中文不分词，'simple' 配置下整段中文为一个词，部分匹配与错拼由 pg_trgm 三元组索引兜底。
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- 1. 搜索向量
+ALTER TABLE resources ADD COLUMN IF NOT EXISTS search_vector TSVECTOR;
+
+CREATE OR REPLACE FUNCTION resource_search_vector(
+    p_resource_id BIGINT,
+    p_title TEXT,
+    p_description TEXT,
+    p_detail_description TEXT
+) RETURNS TSVECTOR AS $$
+    SELECT setweight(to_tsvector('simple', COALESCE(p_title, '')), 'A')
+        || setweight(to_tsvector('simple', COALESCE((
+               SELECT string_agg(t.name || ' ' || t.slug, ' ')
+               FROM resource_tags rt
+               JOIN tags t ON t.id = rt.tag_id
+               WHERE rt.resource_id = p_resource_id
+           ), '')), 'B')
+        || setweight(to_tsvector('simple', COALESCE(p_description, '')), 'C')
+        || setweight(to_tsvector('simple', COALESCE(p_detail_description, '')), 'D');
+$$ LANGUAGE sql STABLE;
+
+-- 资源标题、简介、详情变更时刷新
+CREATE OR REPLACE FUNCTION resources_refresh_search_vector() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.search_vector := resource_search_vector(NEW.id, NEW.title, NEW.description, NEW.detail_description);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_resources_search_vector ON resources;
+CREATE TRIGGER trg_resources_search_vector
+    BEFORE INSERT OR UPDATE OF title, description, detail_description ON resources
+    FOR EACH ROW EXECUTE FUNCTION resources_refresh_search_vector();
+
+-- 资源标签增删时刷新
+CREATE OR REPLACE FUNCTION resource_tags_refresh_search_vector() RETURNS TRIGGER AS $$
+DECLARE
+    v_resource_id BIGINT;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        v_resource_id := OLD.resource_id;
+    ELSE
+        v_resource_id := NEW.resource_id;
+    END IF;
+
+    UPDATE resources r
+    SET search_vector = resource_search_vector(r.id, r.title, r.description, r.detail_description)
+    WHERE r.id = v_resource_id;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_resource_tags_search_vector ON resource_tags;
+CREATE TRIGGER trg_resource_tags_search_vector
+    AFTER INSERT OR DELETE ON resource_tags
+    FOR EACH ROW EXECUTE FUNCTION resource_tags_refresh_search_vector();
+
+-- 标签改名时刷新使用该标签的资源
+CREATE OR REPLACE FUNCTION tags_refresh_search_vector() RETURNS TRIGGER AS $$
+BEGIN
+    UPDATE resources r
+    SET search_vector = resource_search_vector(r.id, r.title, r.description, r.detail_description)
+    WHERE r.id IN (SELECT rt.resource_id FROM resource_tags rt WHERE rt.tag_id = NEW.id);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_tags_search_vector ON tags;
+CREATE TRIGGER trg_tags_search_vector
+    AFTER UPDATE OF name, slug ON tags
+    FOR EACH ROW EXECUTE FUNCTION tags_refresh_search_vector();
+
+-- 2. 回填已有资源
+UPDATE resources
+SET search_vector = resource_search_vector(id, title, description, detail_description);
+
+-- 3. 索引
+CREATE INDEX IF NOT EXISTS idx_resources_search_vector ON resources USING GIN (search_vector);
+CREATE INDEX IF NOT EXISTS idx_resources_title_trgm ON resources USING GIN (title gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_resources_description_trgm ON resources USING GIN (description gin_trgm_ops);
@@ -0,0 +1,43 @@
+-- RSWS 资源推荐
+-- 详情页"相关资源"（同分类 + 共同标签 + 共同购买）与"买了还买"，以及"本周热门"。
+-- 推荐结果由后台任务定期计算写入缓存表，详情页只读缓存。
+
+-- 1. 资源每日浏览 / 下载统计（热门榜数据源）
+CREATE TABLE IF NOT EXISTS resource_daily_stats (
+    resource_id BIGINT  NOT NULL REFERENCES resources(id) ON DELETE CASCADE,
+    stat_date   DATE    NOT NULL DEFAULT CURRENT_DATE,
+    views       INTEGER NOT NULL DEFAULT 0,
+    downloads   INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (resource_id, stat_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_resource_daily_stats_date ON resource_daily_stats(stat_date);
+
+-- 2. 资源推荐缓存
+CREATE TABLE IF NOT EXISTS resource_recommendations (
+    resource_id BIGINT           NOT NULL REFERENCES resources(id) ON DELETE CASCADE,
+    kind        VARCHAR(20)      NOT NULL,  -- related / also_bought
+    related_id  BIGINT           NOT NULL REFERENCES resources(id) ON DELETE CASCADE,
+    score       DOUBLE PRECISION NOT NULL,
+    rank        INTEGER          NOT NULL,
+    computed_at TIMESTAMPTZ      NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (resource_id, kind, related_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_resource_recommendations_rank
+    ON resource_recommendations(resource_id, kind, rank);
+
+-- 3. 本周热门缓存
+CREATE TABLE IF NOT EXISTS popular_resources (
+    resource_id BIGINT           PRIMARY KEY REFERENCES resources(id) ON DELETE CASCADE,
+    views       BIGINT           NOT NULL DEFAULT 0,
+    downloads   BIGINT           NOT NULL DEFAULT 0,
+    score       DOUBLE PRECISION NOT NULL,
+    rank        INTEGER          NOT NULL,
+    computed_at TIMESTAMPTZ      NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_popular_resources_rank ON popular_resources(rank);
+
+-- 共同购买统计
+CREATE INDEX IF NOT EXISTS idx_orders_resource_status ON orders(resource_id, status);
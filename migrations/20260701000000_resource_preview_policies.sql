@@ -0,0 +1,12 @@
+-- RSWS 付费资源预览策略
+-- 未购买用户看到的 detail_description / usage_guide / precautions 预览方式与免费图片数量。
+-- 没有记录的资源使用默认策略：按 25% 截取，图片全部可见。
+
+CREATE TABLE IF NOT EXISTS resource_preview_policies (
+    resource_id   BIGINT      PRIMARY KEY REFERENCES resources(id) ON DELETE CASCADE,
+    mode          VARCHAR(20) NOT NULL DEFAULT 'percent',  -- percent / marker / sections
+    percent       SMALLINT    NOT NULL DEFAULT 25 CHECK (percent BETWEEN 0 AND 100),
+    free_sections TEXT[]      NOT NULL DEFAULT '{}',
+    free_images   INTEGER     CHECK (free_images >= 0),     -- NULL 表示全部免费
+    updated_at    TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
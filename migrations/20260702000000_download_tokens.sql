@@ -0,0 +1,40 @@
+-- RSWS 签名下载令牌
+-- 下载接口不再返回原始 file_url，而是签发绑定用户与资源的短期令牌；
+-- 令牌只存哈希，S3 存储重定向到预签名 URL，本地存储经认证接口流式下载（支持 Range 断点续传）。
+
+-- 1. 下载插件配置（令牌有效期、每用户每日下载上限）
+CREATE TABLE IF NOT EXISTS download_plugin_configs (
+    id                   BIGINT       PRIMARY KEY,
+    plugin_name          VARCHAR(50)  NOT NULL UNIQUE,
+    storage_type         VARCHAR(20)  NOT NULL DEFAULT 'local',
+    storage_config       JSONB,
+    max_file_size        BIGINT,
+    allowed_extensions   TEXT[],
+    token_ttl_secs       INTEGER      NOT NULL DEFAULT 300 CHECK (token_ttl_secs > 0),
+    daily_limit_per_user INTEGER      CHECK (daily_limit_per_user >= 0),  -- NULL 表示不限
+    is_active            BOOLEAN      NOT NULL DEFAULT true,
+    created_at           TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    updated_at           TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO download_plugin_configs (id, plugin_name)
+VALUES (1, 'default')
+ON CONFLICT (plugin_name) DO NOTHING;
+
+-- 2. 下载令牌（签发即计一次下载，用于每日限额统计）
+CREATE TABLE IF NOT EXISTS download_tokens (
+    id           BIGINT       PRIMARY KEY,
+    token_hash   VARCHAR(64)  NOT NULL UNIQUE,
+    user_id      BIGINT       NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    resource_id  BIGINT       NOT NULL REFERENCES resources(id) ON DELETE CASCADE,
+    version_id   BIGINT       REFERENCES resource_versions(id) ON DELETE SET NULL,
+    file_url     TEXT         NOT NULL,
+    file_name    VARCHAR(255) NOT NULL,
+    expires_at   TIMESTAMPTZ  NOT NULL,
+    use_count    INTEGER      NOT NULL DEFAULT 0,
+    last_used_at TIMESTAMPTZ,
+    created_at   TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_download_tokens_user_created ON download_tokens(user_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_download_tokens_expires ON download_tokens(expires_at);
@@ -0,0 +1,32 @@
+-- RSWS 下载水印
+-- 每次下载按买家生成个性化文件：zip 内注入 LICENSE（买家、订单、时间），
+-- PDF 写入 Info 字段、PNG/JPEG 写入 XMP；水印内容带 HMAC 签名，管理员可凭泄露文件反查订单。
+
+-- 1. 下载配置：是否启用水印、参与水印的文件大小上限（超过则原样下载）
+ALTER TABLE download_plugin_configs
+    ADD COLUMN IF NOT EXISTS watermark_enabled   BOOLEAN NOT NULL DEFAULT true,
+    ADD COLUMN IF NOT EXISTS watermark_max_bytes BIGINT  NOT NULL DEFAULT 536870912 CHECK (watermark_max_bytes > 0);
+
+-- 2. 水印记录（长期保留，不随令牌清理；用户、订单删除后仍可追溯）
+CREATE TABLE IF NOT EXISTS download_watermarks (
+    id          BIGINT       PRIMARY KEY,
+    payload     VARCHAR(128) NOT NULL UNIQUE,
+    token_id    BIGINT       NOT NULL,
+    user_id     BIGINT       NOT NULL,
+    order_id    BIGINT,
+    resource_id BIGINT       NOT NULL,
+    version_id  BIGINT,
+    file_name   VARCHAR(255) NOT NULL,
+    issued_at   TIMESTAMPTZ  NOT NULL,
+    created_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_download_watermarks_token ON download_watermarks(token_id);
+CREATE INDEX IF NOT EXISTS idx_download_watermarks_user ON download_watermarks(user_id, created_at);
+
+-- 3. 水印签名密钥（每个部署独立生成；更换后旧水印签名将无法校验）
+INSERT INTO system_configs (id, config_key, config_value, config_type, description) VALUES
+    (7600000000001, 'download.watermark_secret',
+     replace(gen_random_uuid()::text || gen_random_uuid()::text, '-', ''),
+     'string', '下载水印 HMAC 签名密钥')
+ON CONFLICT (config_key) DO NOTHING;
@@ -0,0 +1,7 @@
+-- RSWS 资源批量导入导出
+-- 从旧站点迁移目录时按 external_id（旧站点资源 ID）新建或更新资源，重复导入不会产生重复资源。
+
+ALTER TABLE resources ADD COLUMN IF NOT EXISTS external_id VARCHAR(100);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_resources_external_id
+    ON resources(external_id) WHERE external_id IS NOT NULL;
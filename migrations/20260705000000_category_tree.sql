@@ -0,0 +1,53 @@
+-- RSWS 分类树物化路径
+-- path 形如 /<根分类ID>/<子分类ID>/.../<自身ID>/：面包屑按 path 拆分，子树按 path 前缀匹配，
+-- 移动子树时在一个事务内整体替换前缀。
+
+-- 1. 按 parent_id 重新计算 path（历史数据未完整维护）
+-- 无法从根分类到达的分类说明 parent_id 成环：每轮只断开一个环（环上 ID 最小的分类改为根分类），
+-- 再重新计算，挂在环下的子分类保持原有层级。
+DO $$
+DECLARE
+    unreachable BIGINT;
+    cycle_node  BIGINT;
+BEGIN
+    LOOP
+        UPDATE categories SET path = NULL;
+
+        WITH RECURSIVE tree AS (
+            SELECT id, '/' || id || '/' AS path
+            FROM categories
+            WHERE parent_id IS NULL
+            UNION ALL
+            SELECT c.id, t.path || c.id || '/'
+            FROM categories c
+            JOIN tree t ON c.parent_id = t.id
+        )
+        UPDATE categories c SET path = tree.path FROM tree WHERE tree.id = c.id;
+
+        SELECT COUNT(*) INTO unreachable FROM categories WHERE path IS NULL;
+        EXIT WHEN unreachable = 0;
+
+        -- 沿 parent_id 向上走，能回到起点的分类在环上
+        WITH RECURSIVE walk AS (
+            SELECT id AS start_id, parent_id AS node, 1 AS depth
+            FROM categories
+            WHERE path IS NULL
+            UNION ALL
+            SELECT w.start_id, c.parent_id, w.depth + 1
+            FROM walk w
+            JOIN categories c ON c.id = w.node
+            WHERE w.node <> w.start_id AND w.depth < unreachable
+        )
+        SELECT MIN(start_id) INTO cycle_node FROM walk WHERE node = start_id;
+        IF cycle_node IS NULL THEN
+            RAISE EXCEPTION 'categories unreachable from root without a parent_id cycle';
+        END IF;
+
+        UPDATE categories SET parent_id = NULL WHERE id = cycle_node;
+    END LOOP;
+END $$;
+
+-- 2. path 必填，前缀匹配索引
+ALTER TABLE categories ALTER COLUMN path DROP DEFAULT;
+ALTER TABLE categories ALTER COLUMN path SET NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_categories_path ON categories(path text_pattern_ops);
@@ -1,11 +1,13 @@
 //! 管理员分类处理器
 
-use crate::state::get_state;
+use crate::state::{get_state, AppState};
 use rsws_common::{ResponseExt, RswsError};
-use rsws_db::category::CategoryRepository;
+use rsws_db::category::{CategoryMove, CategoryRepository, CATEGORY_TREE_CACHE_KEY};
 use salvo::prelude::*;
 use salvo_oapi::endpoint;
 use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::warn;
 
 #[derive(Debug, Deserialize)]
 pub struct CreateCategoryRequest {
@@ -24,6 +26,12 @@ pub struct UpdateCategoryRequest {
     pub is_active: Option<bool>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct MoveCategoryRequest {
+    /// 新的父分类，为空表示移为根分类
+    pub parent_id: Option<i64>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct BatchSortRequest {
     pub orders: Vec<SortItem>,
@@ -41,11 +49,22 @@ pub async fn admin_list_categories(_req: &mut Request, depot: &mut Depot, res: &
     let state = get_state(depot);
     let repo = CategoryRepository::new(state.pool());
 
+    let counts: HashMap<i64, (i64, i64)> = match repo.resource_counts(false).await {
+        Ok(counts) => counts
+            .into_iter()
+            .map(|c| (c.category_id, (c.direct_count, c.total_count)))
+            .collect(),
+        Err(e) => {
+            res.error(RswsError::Database(e));
+            return;
+        }
+    };
+
     match repo.find_all_with_inactive().await {
         Ok(categories) => {
             let mut categories_with_count = Vec::with_capacity(categories.len());
             for cat in categories {
-                let count = repo.count_resources(cat.id).await.unwrap_or(0);
+                let (count, total_count) = counts.get(&cat.id).copied().unwrap_or((0, 0));
                 categories_with_count.push(serde_json::json!({
                     "id": cat.id,
                     "name": cat.name,
@@ -55,6 +74,7 @@ pub async fn admin_list_categories(_req: &mut Request, depot: &mut Depot, res: &
                     "sort_order": cat.sort_order,
                     "is_active": cat.is_active,
                     "resource_count": count,
+                    "total_resource_count": total_count,
                     "created_at": cat.created_at,
                     "updated_at": cat.updated_at,
                 }));
@@ -106,7 +126,10 @@ pub async fn create_category(req: &mut Request, depot: &mut Depot, res: &mut Res
         )
         .await
     {
-        Ok(category) => res.success(category),
+        Ok(category) => {
+            invalidate_tree_cache(&state).await;
+            res.success(category)
+        }
         Err(e) => res.error(RswsError::Database(e)),
     }
 }
@@ -146,6 +169,14 @@ pub async fn update_category(req: &mut Request, depot: &mut Depot, res: &mut Res
         }
     }
 
+    // 修改父分类时连同子树一起移动
+    if let Some(parent_id) = body.parent_id {
+        if !move_category_to(&repo, id, parent_id, res).await {
+            return;
+        }
+        invalidate_tree_cache(&state).await;
+    }
+
     let desc_ref = body.description.as_deref();
 
     match repo
@@ -153,18 +184,95 @@ pub async fn update_category(req: &mut Request, depot: &mut Depot, res: &mut Res
             id,
             body.name.as_deref().map(|s| s.trim()),
             Some(desc_ref),
-            body.parent_id,
             body.sort_order,
             body.is_active,
         )
         .await
     {
-        Ok(Some(category)) => res.success(category),
+        Ok(Some(category)) => {
+            invalidate_tree_cache(&state).await;
+            res.success(category)
+        }
         Ok(None) => res.http_error(salvo::http::StatusCode::NOT_FOUND, "分类不存在"),
         Err(e) => res.error(RswsError::Database(e)),
     }
 }
 
+/// 管理员 - 移动分类（连同全部子分类）
+#[endpoint]
+pub async fn move_category(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let state = get_state(depot);
+    let repo = CategoryRepository::new(state.pool());
+
+    let id: i64 = match req.param("id").and_then(|v: &str| v.parse::<i64>().ok()) {
+        Some(v) => v,
+        None => {
+            res.http_error(salvo::http::StatusCode::BAD_REQUEST, "无效的分类ID");
+            return;
+        }
+    };
+
+    let body: MoveCategoryRequest = match req.parse_json().await {
+        Ok(b) => b,
+        Err(_) => {
+            res.http_error(salvo::http::StatusCode::BAD_REQUEST, "请求参数格式错误");
+            return;
+        }
+    };
+
+    if move_category_to(&repo, id, body.parent_id, res).await {
+        invalidate_tree_cache(&state).await;
+        match repo.find_by_id(id).await {
+            Ok(Some(category)) => res.success(category),
+            Ok(None) => res.http_error(salvo::http::StatusCode::NOT_FOUND, "分类不存在"),
+            Err(e) => res.error(RswsError::Database(e)),
+        }
+    }
+}
+
+/// 移动分类，失败时写入错误响应并返回 false
+async fn move_category_to(
+    repo: &CategoryRepository,
+    id: i64,
+    parent_id: Option<i64>,
+    res: &mut Response,
+) -> bool {
+    match repo.move_to(id, parent_id).await {
+        Ok(CategoryMove::Moved(_)) => true,
+        Ok(CategoryMove::NotFound) => {
+            res.http_error(salvo::http::StatusCode::NOT_FOUND, "分类不存在");
+            false
+        }
+        Ok(CategoryMove::ParentNotFound) => {
+            res.http_error(salvo::http::StatusCode::BAD_REQUEST, "父分类不存在");
+            false
+        }
+        Ok(CategoryMove::Cycle) => {
+            res.http_error(
+                salvo::http::StatusCode::BAD_REQUEST,
+                "不能移动到自身或其子分类下",
+            );
+            false
+        }
+        Err(e) => {
+            res.error(RswsError::Database(e));
+            false
+        }
+    }
+}
+
+/// 删除公开分类树缓存（失败只记录日志，缓存到期后自动刷新）
+async fn invalidate_tree_cache(state: &AppState) {
+    if let Err(e) = state
+        .config_service
+        .redis_client()
+        .del(CATEGORY_TREE_CACHE_KEY)
+        .await
+    {
+        warn!("Failed to invalidate category tree cache: {}", e);
+    }
+}
+
 /// 管理员 - 删除分类
 #[endpoint]
 pub async fn delete_category(req: &mut Request, depot: &mut Depot, res: &mut Response) {
@@ -198,7 +306,10 @@ pub async fn delete_category(req: &mut Request, depot: &mut Depot, res: &mut Res
     }
 
     match repo.delete(id).await {
-        Ok(true) => res.ok(),
+        Ok(true) => {
+            invalidate_tree_cache(&state).await;
+            res.ok()
+        }
         Ok(false) => res.http_error(salvo::http::StatusCode::NOT_FOUND, "分类不存在"),
         Err(e) => res.error(RswsError::Database(e)),
     }
@@ -230,7 +341,10 @@ pub async fn batch_update_sort(req: &mut Request, depot: &mut Depot, res: &mut R
         .collect();
 
     match repo.batch_update_sort_order(&orders).await {
-        Ok(()) => res.ok(),
+        Ok(()) => {
+            invalidate_tree_cache(&state).await;
+            res.ok()
+        }
         Err(e) => res.error(RswsError::Database(e)),
     }
 }
@@ -0,0 +1,238 @@
+//! 创作者分成规则管理处理器
+//!
+//! **权限说明：**
+//! - 所有 handler 已通过 `require_admin` 中间件保护
+//! - handler 内部无需再检查权限
+
+use crate::state::{get_state, require_user_id};
+use rsws_common::{ResponseExt, RswsError};
+use rsws_model::commission::{
+    CreateCommissionRuleRequest, SetCreatorTierRequest, SimulateCommissionRequest,
+    UpdateCommissionRuleRequest,
+};
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+
+/// 获取全部分成规则
+#[endpoint(
+    responses(
+        (status_code = 200, description = "规则列表"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn list_commission_rules(depot: &mut Depot, res: &mut Response) {
+    let state = get_state(depot);
+
+    match state.commission_service.list_rules().await {
+        Ok(rules) => res.success(rules),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 创建分成规则
+#[endpoint(
+    request_body = CreateCommissionRuleRequest,
+    responses(
+        (status_code = 200, description = "创建成功"),
+        (status_code = 400, description = "参数错误"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn create_commission_rule(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let body: CreateCommissionRuleRequest = match req.parse_json().await {
+        Ok(b) => b,
+        Err(e) => {
+            res.error(RswsError::bad_request(format!("Invalid request: {}", e)));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.commission_service.create_rule(&body).await {
+        Ok(rule) => res.success(rule),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 更新分成规则（含启停）
+#[endpoint(
+    request_body = UpdateCommissionRuleRequest,
+    responses(
+        (status_code = 200, description = "更新成功"),
+        (status_code = 400, description = "参数错误"),
+        (status_code = 401, description = "未授权"),
+        (status_code = 404, description = "规则不存在"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn update_commission_rule(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let body: UpdateCommissionRuleRequest = match req.parse_json().await {
+        Ok(b) => b,
+        Err(e) => {
+            res.error(RswsError::bad_request(format!("Invalid request: {}", e)));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.commission_service.update_rule(id, &body).await {
+        Ok(rule) => res.success(rule),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 删除分成规则
+#[endpoint(
+    responses(
+        (status_code = 200, description = "删除成功"),
+        (status_code = 401, description = "未授权"),
+        (status_code = 404, description = "规则不存在"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn delete_commission_rule(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.commission_service.delete_rule(id).await {
+        Ok(()) => res.success(serde_json::json!({ "id": id, "deleted": true })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 模拟分成计算
+///
+/// 指定 `order_id` 时按该订单的商品金额与支付时间计算；
+/// 指定 `resource_id` 时可传入 `amount`（默认资源价格）与 `at`（默认当前时间）。
+/// 返回评估上下文、命中的规则（按优先级排序）及计算结果，不写入任何记录。
+#[endpoint(
+    request_body = SimulateCommissionRequest,
+    responses(
+        (status_code = 200, description = "模拟结果"),
+        (status_code = 400, description = "参数错误"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn simulate_commission(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let body: SimulateCommissionRequest = match req.parse_json().await {
+        Ok(b) => b,
+        Err(e) => {
+            res.error(RswsError::bad_request(format!("Invalid request: {}", e)));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.commission_service.simulate(&body).await {
+        Ok(simulation) => res.success(simulation),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 获取创作者等级
+#[endpoint(
+    responses(
+        (status_code = 200, description = "创作者等级"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn get_creator_tier(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.commission_service.creator_tier(user_id).await {
+        Ok(tier) => res.success(serde_json::json!({ "user_id": user_id, "tier": tier })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 设置创作者等级
+#[endpoint(
+    request_body = SetCreatorTierRequest,
+    responses(
+        (status_code = 200, description = "设置成功"),
+        (status_code = 400, description = "等级格式错误"),
+        (status_code = 401, description = "未授权"),
+        (status_code = 404, description = "用户不存在"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn set_creator_tier(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let admin_id = match require_user_id(depot) {
+        Ok(id) => id,
+        Err(status) => {
+            res.status_code(status);
+            return;
+        }
+    };
+
+    let user_id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let body: SetCreatorTierRequest = match req.parse_json().await {
+        Ok(b) => b,
+        Err(e) => {
+            res.error(RswsError::bad_request(format!("Invalid request: {}", e)));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state
+        .commission_service
+        .set_creator_tier(user_id, &body.tier, admin_id)
+        .await
+    {
+        Ok(tier) => res.success(serde_json::json!({ "user_id": user_id, "tier": tier })),
+        Err(e) => res.error(e),
+    }
+}
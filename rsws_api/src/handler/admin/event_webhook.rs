@@ -0,0 +1,210 @@
+//! 出站事件 Webhook 管理处理器
+//!
+//! **权限说明：**
+//! - 所有 handler 已通过 `require_admin` 中间件保护
+//! - handler 内部无需再检查权限
+
+use crate::state::get_state;
+use rsws_common::{ResponseExt, RswsError};
+use rsws_model::event_webhook::{
+    CreateWebhookSubscriptionRequest, UpdateWebhookSubscriptionRequest, WebhookDeliveryQuery,
+};
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+
+/// 获取全部事件订阅
+#[endpoint(
+    responses(
+        (status_code = 200, description = "订阅列表"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn list_event_webhooks(depot: &mut Depot, res: &mut Response) {
+    let state = get_state(depot);
+
+    match state.cross_platform_service.list_subscriptions().await {
+        Ok(subscriptions) => res.success(subscriptions),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 创建事件订阅
+///
+/// 未指定 `secret` 时自动生成，签名密钥明文仅在此返回一次。
+#[endpoint(
+    request_body = CreateWebhookSubscriptionRequest,
+    responses(
+        (status_code = 200, description = "创建成功"),
+        (status_code = 400, description = "参数错误"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn create_event_webhook(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let body: CreateWebhookSubscriptionRequest = match req.parse_json().await {
+        Ok(b) => b,
+        Err(e) => {
+            res.error(RswsError::bad_request(format!("Invalid request: {}", e)));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state
+        .cross_platform_service
+        .create_subscription(&body)
+        .await
+    {
+        Ok((subscription, secret)) => res.success(serde_json::json!({
+            "subscription": subscription,
+            "secret": secret,
+        })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 更新事件订阅（含启停、轮换密钥）
+#[endpoint(
+    request_body = UpdateWebhookSubscriptionRequest,
+    responses(
+        (status_code = 200, description = "更新成功"),
+        (status_code = 400, description = "参数错误"),
+        (status_code = 401, description = "未授权"),
+        (status_code = 404, description = "订阅不存在"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn update_event_webhook(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let body: UpdateWebhookSubscriptionRequest = match req.parse_json().await {
+        Ok(b) => b,
+        Err(e) => {
+            res.error(RswsError::bad_request(format!("Invalid request: {}", e)));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state
+        .cross_platform_service
+        .update_subscription(id, &body)
+        .await
+    {
+        Ok(subscription) => res.success(subscription),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 删除事件订阅（连同投递记录）
+#[endpoint(
+    responses(
+        (status_code = 200, description = "删除成功"),
+        (status_code = 401, description = "未授权"),
+        (status_code = 404, description = "订阅不存在"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn delete_event_webhook(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.cross_platform_service.delete_subscription(id).await {
+        Ok(()) => res.success(serde_json::json!({ "id": id, "deleted": true })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 分页查询事件投递记录
+#[endpoint(
+    parameters(
+        ("subscription_id" = Option<i64>, Query, description = "订阅 ID"),
+        ("status" = Option<String>, Query, description = "状态：pending / succeeded / dead"),
+        ("event_type" = Option<String>, Query, description = "事件类型"),
+        ("page" = Option<i64>, Query, description = "页码"),
+        ("page_size" = Option<i64>, Query, description = "每页数量"),
+    ),
+    responses(
+        (status_code = 200, description = "投递记录列表"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn list_webhook_deliveries(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let query: WebhookDeliveryQuery = req.parse_queries().unwrap_or_default();
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+
+    let state = get_state(depot);
+
+    match state
+        .cross_platform_service
+        .list_deliveries(&query, page, page_size)
+        .await
+    {
+        Ok((deliveries, total)) => res.success(serde_json::json!({
+            "items": deliveries,
+            "total": total,
+            "page": page,
+            "page_size": page_size,
+            "total_pages": (total + page_size - 1) / page_size,
+        })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 手动重新投递
+///
+/// 重置为待投递并清零重试次数，由后台任务按原事件 ID 重新发送。
+#[endpoint(
+    responses(
+        (status_code = 200, description = "已重新入队"),
+        (status_code = 401, description = "未授权"),
+        (status_code = 404, description = "投递记录不存在"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn redeliver_webhook(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.cross_platform_service.redeliver(id).await {
+        Ok(delivery) => res.success(delivery),
+        Err(e) => res.error(e),
+    }
+}
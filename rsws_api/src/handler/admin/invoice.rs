@@ -0,0 +1,83 @@
+//! 发票管理处理器
+//!
+//! **权限说明：**
+//! - 所有 handler 已通过 `require_admin` 中间件保护
+//! - handler 内部无需再检查权限
+
+use crate::state::get_state;
+use rsws_common::{ResponseExt, RswsError};
+use rsws_model::invoice::InvoiceExportQuery;
+use rsws_service::invoice_service::invoices_to_csv;
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+
+/// 按日期区间批量导出发票
+///
+/// `format=csv`（默认）返回 CSV 附件，每个商品明细一行；`format=json` 返回发票列表。
+#[endpoint(
+    parameters(
+        ("start_date" = String, Query, description = "开始日期（含），YYYY-MM-DD"),
+        ("end_date" = String, Query, description = "结束日期（含），YYYY-MM-DD"),
+        ("format" = Option<String>, Query, description = "导出格式：csv / json"),
+    ),
+    responses(
+        (status_code = 200, description = "发票导出"),
+        (status_code = 400, description = "日期参数错误"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn export_invoices(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let query: InvoiceExportQuery = match req.parse_queries() {
+        Ok(q) => q,
+        Err(_) => {
+            res.error(RswsError::bad_request(
+                "start_date and end_date are required (YYYY-MM-DD)",
+            ));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    let invoices = match state
+        .invoice_service
+        .list_by_date_range(&query.start_date, &query.end_date)
+        .await
+    {
+        Ok(invoices) => invoices,
+        Err(e) => {
+            res.error(e);
+            return;
+        }
+    };
+
+    match query.format.as_deref().unwrap_or("csv") {
+        "json" => res.success(serde_json::json!({
+            "items": invoices,
+            "total": invoices.len(),
+        })),
+        "csv" => {
+            let filename = format!(
+                "invoices-{}-{}.csv",
+                query.start_date.trim(),
+                query.end_date.trim()
+            );
+            res.add_header("Content-Type", "text/csv; charset=utf-8", true)
+                .ok();
+            res.add_header(
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"", filename),
+                true,
+            )
+            .ok();
+            res.write_body(invoices_to_csv(&invoices)).ok();
+        }
+        other => res.error(RswsError::bad_request(format!(
+            "Unsupported export format: {}",
+            other
+        ))),
+    }
+}
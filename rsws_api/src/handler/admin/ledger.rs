@@ -0,0 +1,31 @@
+//! 总账管理处理器
+//!
+//! **权限说明：**
+//! - 所有 handler 已通过 `require_admin` 中间件保护
+//! - handler 内部无需再检查权限
+
+use crate::state::get_state;
+use rsws_common::ResponseExt;
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+
+/// 试算平衡表
+///
+/// 按科目汇总借方 / 贷方余额，`balanced = false` 表示总账数据异常需排查。
+#[endpoint(
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn trial_balance(_req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let state = get_state(depot);
+
+    match state.ledger_service.trial_balance().await {
+        Ok(tb) => res.success(tb),
+        Err(e) => res.error(e),
+    }
+}
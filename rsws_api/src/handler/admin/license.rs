@@ -0,0 +1,129 @@
+//! 资源授权档位管理处理器
+//!
+//! **权限说明：**
+//! - 所有 handler 已通过 `require_admin` 中间件保护
+//! - handler 内部无需再检查权限，可管理任意资源（含平台资源）的档位
+
+use crate::state::get_state;
+use rsws_common::{ResponseExt, RswsError};
+use rsws_model::license::{CreateResourceLicenseRequest, UpdateResourceLicenseRequest};
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+
+/// 获取资源全部授权档位（含已下架）
+#[endpoint(
+    responses(
+        (status_code = 200, description = "授权档位列表"),
+        (status_code = 401, description = "未授权"),
+        (status_code = 404, description = "资源不存在"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn admin_list_resource_licenses(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) {
+    let id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.license_service.list_for_owner(id, None).await {
+        Ok(licenses) => res.success(licenses),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 创建资源授权档位
+#[endpoint(
+    request_body = CreateResourceLicenseRequest,
+    responses(
+        (status_code = 200, description = "创建成功"),
+        (status_code = 400, description = "参数错误"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn admin_create_resource_license(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) {
+    let id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let body: CreateResourceLicenseRequest = match req.parse_json().await {
+        Ok(b) => b,
+        Err(e) => {
+            res.error(RswsError::bad_request(format!("Invalid request: {}", e)));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.license_service.create(id, None, &body).await {
+        Ok(license) => res.success(license),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 更新资源授权档位（下架请设置 `is_active = false`）
+#[endpoint(
+    request_body = UpdateResourceLicenseRequest,
+    responses(
+        (status_code = 200, description = "更新成功"),
+        (status_code = 400, description = "参数错误"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn admin_update_resource_license(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) {
+    let (id, license_id): (i64, i64) = match (req.param("id"), req.param("license_id")) {
+        (Some(id), Some(license_id)) => (id, license_id),
+        _ => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let body: UpdateResourceLicenseRequest = match req.parse_json().await {
+        Ok(b) => b,
+        Err(e) => {
+            res.error(RswsError::bad_request(format!("Invalid request: {}", e)));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state
+        .license_service
+        .update(id, license_id, None, &body)
+        .await
+    {
+        Ok(license) => res.success(license),
+        Err(e) => res.error(e),
+    }
+}
@@ -0,0 +1,96 @@
+//! 会员套餐管理处理器
+//!
+//! **权限说明：**
+//! - 所有 handler 已通过 `require_admin` 中间件保护
+//! - handler 内部无需再检查权限
+
+use crate::state::get_state;
+use rsws_common::{ResponseExt, RswsError};
+use rsws_model::membership::{CreateMembershipPlanRequest, UpdateMembershipPlanRequest};
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+
+/// 获取所有会员套餐（含已下架）
+#[endpoint(
+    responses(
+        (status_code = 200, description = "会员套餐列表"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn admin_list_membership_plans(depot: &mut Depot, res: &mut Response) {
+    let state = get_state(depot);
+
+    match state.membership_service.list_plans(false).await {
+        Ok(plans) => res.success(plans),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 创建会员套餐
+#[endpoint(
+    request_body = CreateMembershipPlanRequest,
+    responses(
+        (status_code = 200, description = "创建成功"),
+        (status_code = 400, description = "参数错误"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn create_membership_plan(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let body: CreateMembershipPlanRequest = match req.parse_json().await {
+        Ok(b) => b,
+        Err(e) => {
+            res.error(RswsError::bad_request(format!("Invalid request: {}", e)));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.membership_service.create_plan(&body).await {
+        Ok(plan) => res.success(plan),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 更新会员套餐（授权分类不可修改，下架请设置 `is_active = false`）
+#[endpoint(
+    request_body = UpdateMembershipPlanRequest,
+    responses(
+        (status_code = 200, description = "更新成功"),
+        (status_code = 404, description = "套餐不存在"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn update_membership_plan(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let body: UpdateMembershipPlanRequest = match req.parse_json().await {
+        Ok(b) => b,
+        Err(e) => {
+            res.error(RswsError::bad_request(format!("Invalid request: {}", e)));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.membership_service.update_plan(id, &body).await {
+        Ok(plan) => res.success(plan),
+        Err(e) => res.error(e),
+    }
+}
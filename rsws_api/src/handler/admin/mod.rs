@@ -4,23 +4,39 @@
 //! 使 `handler::admin::*` 路由引用路径保持不变。
 
 // 子模块声明
+mod alipay;
 mod api_key;
 mod audit_log;
 mod auth;
 mod category;
+mod commission;
 mod dashboard;
 mod email;
 mod error_log;
+mod event_webhook;
+mod invoice;
+mod ledger;
+mod license;
 mod log;
 mod login_log;
 mod management;
+mod membership;
+mod moderation;
 mod order;
 mod oss;
 mod payment_method;
 mod paypal;
+mod rating;
+mod recommendation;
 mod resource;
+mod resource_version;
+mod risk;
+mod tag;
 mod user;
 mod wallet;
+mod webhook_log;
+mod wechatpay;
+mod wishlist;
 
 // ---- re-export：保持路由引用兼容 ----
 // auth.rs
@@ -79,8 +95,11 @@ pub use user::deactivate_user;
 pub use user::list_users;
 
 // resource.rs
+pub use resource::admin_set_preview_policy;
 pub use resource::create_platform_resource;
 pub use resource::delete_platform_resource;
+pub use resource::export_resources;
+pub use resource::import_resources;
 pub use resource::list_resources;
 pub use resource::toggle_platform_resource;
 pub use resource::update_platform_resource;
@@ -95,8 +114,12 @@ pub use payment_method::delete_payment_method;
 pub use payment_method::list_payment_methods;
 
 // oss.rs
+pub use oss::get_download_config;
 pub use oss::get_storage_config;
+pub use oss::lookup_watermark;
+pub use oss::migrate_private_storage;
 pub use oss::test_storage_connection;
+pub use oss::update_download_config;
 pub use oss::update_storage_config;
 
 // paypal.rs
@@ -106,12 +129,102 @@ pub use paypal::list_paypal_configs;
 pub use paypal::set_paypal_config_active;
 pub use paypal::update_paypal_config;
 
+// alipay.rs
+pub use alipay::create_alipay_config;
+pub use alipay::get_alipay_config;
+pub use alipay::list_alipay_configs;
+pub use alipay::set_alipay_config_active;
+pub use alipay::update_alipay_config;
+
+// wechatpay.rs
+pub use wechatpay::create_wechatpay_config;
+pub use wechatpay::get_wechatpay_config;
+pub use wechatpay::list_wechatpay_configs;
+pub use wechatpay::set_wechatpay_config_active;
+pub use wechatpay::update_wechatpay_config;
+
 // category.rs
 pub use category::admin_list_categories;
 pub use category::batch_update_sort;
 pub use category::create_category;
 pub use category::delete_category;
+pub use category::move_category;
 pub use category::update_category;
 
 // order.rs
 pub use order::admin_list_orders;
+
+// event_webhook.rs
+pub use event_webhook::create_event_webhook;
+pub use event_webhook::delete_event_webhook;
+pub use event_webhook::list_event_webhooks;
+pub use event_webhook::list_webhook_deliveries;
+pub use event_webhook::redeliver_webhook;
+pub use event_webhook::update_event_webhook;
+
+// invoice.rs
+pub use invoice::export_invoices;
+
+// ledger.rs
+pub use ledger::trial_balance;
+
+// license.rs
+pub use license::admin_create_resource_license;
+pub use license::admin_list_resource_licenses;
+pub use license::admin_update_resource_license;
+
+// resource_version.rs
+pub use resource_version::admin_list_resource_versions;
+pub use resource_version::admin_publish_resource_version;
+pub use resource_version::admin_set_resource_update_policy;
+
+// membership.rs
+pub use membership::admin_list_membership_plans;
+pub use membership::create_membership_plan;
+pub use membership::update_membership_plan;
+
+// commission.rs
+pub use commission::create_commission_rule;
+pub use commission::delete_commission_rule;
+pub use commission::get_creator_tier;
+pub use commission::list_commission_rules;
+pub use commission::set_creator_tier;
+pub use commission::simulate_commission;
+pub use commission::update_commission_rule;
+
+// moderation.rs
+pub use moderation::get_resource_moderation_history;
+pub use moderation::list_moderation_queue;
+pub use moderation::moderate_resource;
+
+// rating.rs
+pub use rating::hide_rating;
+pub use rating::list_reported_ratings;
+pub use rating::restore_rating;
+
+// recommendation.rs
+pub use recommendation::refresh_recommendations;
+
+// risk.rs
+pub use risk::create_risk_rule;
+pub use risk::delete_risk_rule;
+pub use risk::get_order_risk;
+pub use risk::list_risk_reviews;
+pub use risk::list_risk_rules;
+pub use risk::review_risk_order;
+pub use risk::update_risk_rule;
+
+// tag.rs
+pub use tag::admin_set_resource_tags;
+pub use tag::create_tag;
+pub use tag::delete_tag;
+pub use tag::list_tags;
+pub use tag::update_tag;
+
+// webhook_log.rs
+pub use webhook_log::get_webhook_log;
+pub use webhook_log::list_webhook_logs;
+pub use webhook_log::replay_webhook_log;
+
+// wishlist.rs
+pub use wishlist::most_wished_report;
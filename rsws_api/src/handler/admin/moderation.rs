@@ -0,0 +1,145 @@
+//! 资源审核管理处理器
+//!
+//! **权限说明：**
+//! - 所有 handler 已通过 `require_admin` 中间件保护
+//! - handler 内部无需再检查权限
+
+use crate::state::{get_state, require_user_id};
+use rsws_common::{ResponseExt, RswsError};
+use rsws_model::moderation::ModerateResourceRequest;
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+use serde::Deserialize;
+
+/// 审核队列查询参数
+#[derive(Debug, Default, Deserialize)]
+struct ModerationQueueQuery {
+    status: Option<String>,
+    page: Option<i64>,
+    page_size: Option<i64>,
+}
+
+/// 获取资源审核队列
+///
+/// 默认返回待审核资源，按提交时间先后排序；`status` 可查看其他审核状态。
+#[endpoint(
+    parameters(
+        ("status" = Option<String>, Query, description = "审核状态：draft / pending / approved / rejected / taken_down，默认 pending"),
+        ("page" = Option<i64>, Query, description = "页码"),
+        ("page_size" = Option<i64>, Query, description = "每页数量"),
+    ),
+    responses(
+        (status_code = 200, description = "审核队列"),
+        (status_code = 400, description = "参数错误"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn list_moderation_queue(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let query: ModerationQueueQuery = req.parse_queries().unwrap_or_default();
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+
+    let state = get_state(depot);
+
+    match state
+        .moderation_service
+        .queue(query.status.as_deref(), page, page_size)
+        .await
+    {
+        Ok((items, total)) => res.success(serde_json::json!({
+            "items": items,
+            "total": total,
+            "page": page,
+            "page_size": page_size,
+            "total_pages": (total + page_size - 1) / page_size,
+        })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 审核资源
+///
+/// `action`：approve（通过）/ reject（拒绝）/ take_down（下架），拒绝与下架须填写 `reason`。
+/// 审核结果邮件通知创作者。
+#[endpoint(
+    request_body = ModerateResourceRequest,
+    responses(
+        (status_code = 200, description = "审核完成"),
+        (status_code = 400, description = "参数错误或当前状态不允许该操作"),
+        (status_code = 401, description = "未授权"),
+        (status_code = 404, description = "资源不存在"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn moderate_resource(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let admin_id = match require_user_id(depot) {
+        Ok(id) => id,
+        Err(status) => {
+            res.status_code(status);
+            return;
+        }
+    };
+
+    let resource_id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let body: ModerateResourceRequest = match req.parse_json().await {
+        Ok(b) => b,
+        Err(e) => {
+            res.error(RswsError::bad_request(format!("Invalid request: {}", e)));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state
+        .moderation_service
+        .moderate(resource_id, admin_id, &body)
+        .await
+    {
+        Ok(resource) => res.success(resource),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 获取资源审核记录
+#[endpoint(
+    responses(
+        (status_code = 200, description = "审核记录"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn get_resource_moderation_history(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) {
+    let resource_id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.moderation_service.history(resource_id).await {
+        Ok(history) => res.success(history),
+        Err(e) => res.error(e),
+    }
+}
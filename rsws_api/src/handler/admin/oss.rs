@@ -1,8 +1,10 @@
 //! 管理员 OSS 配置 Handler
 
 use crate::state::get_state;
-use rsws_common::ResponseExt;
+use rsws_common::{ResponseExt, RswsError};
+use rsws_model::download::UpdateDownloadConfigRequest;
 use rsws_service::config_service::OssStorageConfig;
+use rsws_service::oss_service::{check_private_dir, local_private_dir};
 use salvo::oapi::extract::JsonBody;
 use salvo::prelude::*;
 use tracing::error;
@@ -50,6 +52,14 @@ pub async fn update_storage_config(
         }
     } else if config.endpoint.is_empty() {
         return res.http_error(StatusCode::BAD_REQUEST, "本地存储路径不能为空");
+    } else {
+        // 私有区目录不能位于静态文件目录下
+        let private_dir = local_private_dir(&config);
+        if let Err(e) = check_private_dir(private_dir, &state.config.server.upload_dir)
+            .and_then(|_| check_private_dir(private_dir, &config.endpoint))
+        {
+            return res.http_error(StatusCode::BAD_REQUEST, e.to_string());
+        }
     }
 
     match state.config_service.save_storage_config(&config).await {
@@ -110,3 +120,82 @@ pub async fn test_storage_connection(
         }
     }
 }
+
+/// 获取下载配置（令牌有效期、每用户每日下载上限）
+#[endpoint]
+pub async fn get_download_config(_req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let state = get_state(depot);
+
+    match state.download_service.get_config().await {
+        Ok(config) => res.success(config),
+        Err(e) => {
+            error!("Failed to get download config: {}", e);
+            res.error(e);
+        }
+    }
+}
+
+/// 保存下载配置
+#[endpoint]
+pub async fn update_download_config(
+    _req: &mut Request,
+    depot: &mut Depot,
+    body: JsonBody<UpdateDownloadConfigRequest>,
+    res: &mut Response,
+) {
+    let state = get_state(depot);
+
+    match state
+        .download_service
+        .update_config(body.into_inner())
+        .await
+    {
+        Ok(config) => res.success(config),
+        Err(e) => {
+            error!("Failed to save download config: {}", e);
+            res.error(e);
+        }
+    }
+}
+
+/// 泄露文件反查（multipart 字段 `file`）：返回文件中的水印、签名校验结果与对应下载记录
+#[endpoint]
+pub async fn lookup_watermark(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let state = get_state(depot);
+
+    let Some(path) = req.file("file").await.map(|f| f.path().clone()) else {
+        res.error(RswsError::bad_request("Missing file"));
+        return;
+    };
+    let data = match tokio::fs::read(&path).await {
+        Ok(data) => data,
+        Err(e) => {
+            error!("Failed to read uploaded file: {}", e);
+            res.error(RswsError::internal("Failed to read uploaded file"));
+            return;
+        }
+    };
+
+    match state.download_service.lookup_watermark(data).await {
+        Ok(matches) => res.success(matches),
+        Err(e) => {
+            error!("Failed to look up watermark: {}", e);
+            res.error(e);
+        }
+    }
+}
+
+/// 把付费资源文件迁移到私有存储区（`dry_run=true` 只列出待迁移文件）
+#[endpoint]
+pub async fn migrate_private_storage(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let state = get_state(depot);
+    let dry_run = req.query::<bool>("dry_run").unwrap_or(false);
+
+    match state.resource_service.migrate_private_files(dry_run).await {
+        Ok(report) => res.success(report),
+        Err(e) => {
+            error!("Failed to migrate private storage: {}", e);
+            res.error(e);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! 资源评价管理处理器
+//!
+//! **权限说明：**
+//! - 所有 handler 已通过 `require_admin` 中间件保护
+//! - handler 内部无需再检查权限
+
+use crate::state::{get_state, require_user_id};
+use rsws_common::{ResponseExt, RswsError};
+use rsws_model::rating::HideRatingRequest;
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+use serde::Deserialize;
+
+/// 举报队列查询参数
+#[derive(Debug, Default, Deserialize)]
+struct ReportedRatingQuery {
+    page: Option<i64>,
+    page_size: Option<i64>,
+}
+
+/// 获取被举报的评价
+///
+/// 仅含有待处理举报的评价，按举报数从多到少排序。
+#[endpoint(
+    parameters(
+        ("page" = Option<i64>, Query, description = "页码"),
+        ("page_size" = Option<i64>, Query, description = "每页数量"),
+    ),
+    responses(
+        (status_code = 200, description = "举报队列"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn list_reported_ratings(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let query: ReportedRatingQuery = req.parse_queries().unwrap_or_default();
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+
+    let state = get_state(depot);
+
+    match state.rating_service.reported(page, page_size).await {
+        Ok((items, total)) => res.success(serde_json::json!({
+            "items": items,
+            "total": total,
+            "page": page,
+            "page_size": page_size,
+            "total_pages": (total + page_size - 1) / page_size,
+        })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 隐藏评价
+///
+/// 隐藏后不再展示、不计入资源评分，并关闭该评价的待处理举报。请求体可省略。
+#[endpoint(
+    request_body = HideRatingRequest,
+    responses(
+        (status_code = 200, description = "已隐藏"),
+        (status_code = 401, description = "未授权"),
+        (status_code = 404, description = "评价不存在"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn hide_rating(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let admin_id = match require_user_id(depot) {
+        Ok(id) => id,
+        Err(status) => {
+            res.status_code(status);
+            return;
+        }
+    };
+
+    let rating_id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let body: HideRatingRequest = req.parse_json().await.unwrap_or_default();
+
+    let state = get_state(depot);
+
+    match state.rating_service.hide(rating_id, admin_id, &body).await {
+        Ok(rating) => res.success(rating),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 恢复评价
+///
+/// 恢复展示并重新计入资源评分，同时关闭该评价的待处理举报（视为举报不成立）。
+#[endpoint(
+    responses(
+        (status_code = 200, description = "已恢复"),
+        (status_code = 401, description = "未授权"),
+        (status_code = 404, description = "评价不存在"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn restore_rating(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let admin_id = match require_user_id(depot) {
+        Ok(id) => id,
+        Err(status) => {
+            res.status_code(status);
+            return;
+        }
+    };
+
+    let rating_id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.rating_service.restore(rating_id, admin_id).await {
+        Ok(rating) => res.success(rating),
+        Err(e) => res.error(e),
+    }
+}
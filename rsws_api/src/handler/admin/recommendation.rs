@@ -0,0 +1,31 @@
+//! 资源推荐管理处理器
+//!
+//! **权限说明：**
+//! - 所有 handler 已通过 `require_admin` 中间件保护
+//! - handler 内部无需再检查权限
+
+use crate::state::get_state;
+use rsws_common::ResponseExt;
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+
+/// 立即重算推荐缓存
+///
+/// 后台任务每小时重算一次，调整标签或导入订单后可手动触发。
+#[endpoint(
+    responses(
+        (status_code = 200, description = "各类推荐写入条数"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn refresh_recommendations(depot: &mut Depot, res: &mut Response) {
+    let state = get_state(depot);
+
+    match state.recommendation_service.refresh().await {
+        Ok(result) => res.success(result),
+        Err(e) => res.error(e),
+    }
+}
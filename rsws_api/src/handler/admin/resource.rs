@@ -1,13 +1,16 @@
 //! 管理员资源管理
 //!
-//! 列表、创建、更新、删除、切换上下架
+//! 列表、创建、更新、删除、切换上下架、批量导入导出
 
 use crate::state::{get_state, require_user_id};
 use rsws_common::{error_code::ErrorCode, ResponseExt, RswsError};
-use rsws_model::resource::{CreateResourceRequest, UpdateResourceRequest};
+use rsws_model::preview::SetPreviewPolicyRequest;
+use rsws_model::resource::{CreateResourceRequest, ResourceFilter, UpdateResourceRequest};
+use rsws_model::resource_import::{FORMAT_CSV, FORMAT_JSONL};
 use salvo::http::StatusCode;
 use salvo::prelude::*;
 use salvo_oapi::endpoint;
+use tracing::error;
 
 /// 管理员列出所有资源
 #[endpoint(
@@ -39,9 +42,14 @@ pub async fn list_resources(req: &mut Request, depot: &mut Depot, res: &mut Resp
             .list(category_id, page, page_size)
             .await
     } else {
+        let filter = ResourceFilter {
+            category_id,
+            search,
+            ..Default::default()
+        };
         state
             .resource_service
-            .search(category_id, search.as_deref(), page, page_size)
+            .search(&filter, page, page_size)
             .await
     };
 
@@ -259,3 +267,179 @@ pub async fn toggle_platform_resource(req: &mut Request, depot: &mut Depot, res:
         Err(e) => res.error(e),
     }
 }
+
+/// 管理员设置资源预览策略（任意资源，跳过归属校验）
+#[endpoint(
+    request_body = SetPreviewPolicyRequest,
+    responses(
+        (status_code = 200, description = "设置成功"),
+        (status_code = 400, description = "策略无效"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 403, description = "非管理员"),
+        (status_code = 404, description = "资源不存在"),
+    )
+)]
+pub async fn admin_set_preview_policy(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let id: i64 = req.param("id").unwrap_or(0);
+    if id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid resource ID",
+        );
+        return;
+    }
+
+    let _admin_id = match require_user_id(depot) {
+        Ok(id) => id,
+        Err(status) => {
+            res.status_code(status);
+            return;
+        }
+    };
+
+    let data = match req.parse_json::<SetPreviewPolicyRequest>().await {
+        Ok(d) => d,
+        Err(e) => {
+            res.error_msg(
+                RswsError::from(ErrorCode::INVALID_REQUEST_FORMAT),
+                format!("Invalid request: {}", e),
+            );
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+    match state
+        .resource_service
+        .set_preview_policy(id, None, data)
+        .await
+    {
+        Ok(policy) => res.success(policy),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 批量导入资源（multipart 字段 `file`，CSV 或 JSON Lines）
+///
+/// 按 `external_id` 新建或更新平台资源；`dry_run=true` 只校验并返回逐行报告。
+/// 未指定 `format` 时按文件扩展名判断（`.jsonl` / `.ndjson` 为 JSON Lines，其余按 CSV）。
+#[endpoint(
+    parameters(
+        ("format" = Option<String>, Query, description = "导入格式：csv / jsonl"),
+        ("dry_run" = Option<bool>, Query, description = "只校验，不写入"),
+    ),
+    responses(
+        (status_code = 200, description = "导入报告"),
+        (status_code = 400, description = "文件格式错误"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 403, description = "非管理员"),
+    )
+)]
+pub async fn import_resources(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let admin_id = match require_user_id(depot) {
+        Ok(id) => id,
+        Err(status) => {
+            res.status_code(status);
+            return;
+        }
+    };
+
+    let dry_run = req.query::<bool>("dry_run").unwrap_or(false);
+    let format: Option<String> = req.query("format");
+    let Some((path, name)) = req.file("file").await.map(|f| {
+        (
+            f.path().clone(),
+            f.name().unwrap_or_default().to_lowercase(),
+        )
+    }) else {
+        res.error(RswsError::bad_request("Missing file"));
+        return;
+    };
+    let format = format.unwrap_or_else(|| {
+        if name.ends_with(".jsonl") || name.ends_with(".ndjson") {
+            FORMAT_JSONL.to_string()
+        } else {
+            FORMAT_CSV.to_string()
+        }
+    });
+
+    let text = match tokio::fs::read(&path).await.map(String::from_utf8) {
+        Ok(Ok(text)) => text,
+        Ok(Err(_)) => {
+            res.error(RswsError::bad_request("Import file must be UTF-8 encoded"));
+            return;
+        }
+        Err(e) => {
+            error!("Failed to read uploaded file: {}", e);
+            res.error(RswsError::internal("Failed to read uploaded file"));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+    match state
+        .resource_bulk_service
+        .import(&text, &format, dry_run, admin_id)
+        .await
+    {
+        Ok(report) => res.success(report),
+        Err(e) => {
+            error!("Failed to import resources: {}", e);
+            res.error(e);
+        }
+    }
+}
+
+/// 导出资源（含已下架资源、分类名、标签与销售统计）
+///
+/// `format=csv`（默认）返回 CSV 附件，`format=jsonl` 返回 JSON Lines 附件；导出文件可直接再导入。
+#[endpoint(
+    parameters(
+        ("format" = Option<String>, Query, description = "导出格式：csv / jsonl"),
+        ("category_id" = Option<i64>, Query, description = "按分类筛选"),
+    ),
+    responses(
+        (status_code = 200, description = "资源导出"),
+        (status_code = 400, description = "格式参数错误"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 403, description = "非管理员"),
+    )
+)]
+pub async fn export_resources(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let format: String = req
+        .query("format")
+        .unwrap_or_else(|| FORMAT_CSV.to_string());
+    let category_id: Option<i64> = req.query("category_id");
+
+    let state = get_state(depot);
+    match state
+        .resource_bulk_service
+        .export(&format, category_id)
+        .await
+    {
+        Ok(body) => {
+            let content_type = if format == FORMAT_JSONL {
+                "application/x-ndjson; charset=utf-8"
+            } else {
+                "text/csv; charset=utf-8"
+            };
+            let filename = format!(
+                "resources-{}.{}",
+                chrono::Utc::now().format("%Y%m%d"),
+                format
+            );
+            res.add_header("Content-Type", content_type, true).ok();
+            res.add_header(
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"", filename),
+                true,
+            )
+            .ok();
+            res.write_body(body).ok();
+        }
+        Err(e) => {
+            error!("Failed to export resources: {}", e);
+            res.error(e);
+        }
+    }
+}
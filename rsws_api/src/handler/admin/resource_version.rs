@@ -0,0 +1,147 @@
+//! 资源版本管理处理器
+//!
+//! **权限说明：**
+//! - 所有 handler 已通过 `require_admin` 中间件保护
+//! - handler 内部无需再检查权限，可管理任意资源（含平台资源）的版本
+
+use crate::state::{get_state, require_user_id};
+use rsws_common::{ResponseExt, RswsError};
+use rsws_model::resource_version::{PublishVersionRequest, SetUpdatePolicyRequest};
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+
+/// 获取资源全部版本（含文件地址）
+#[endpoint(
+    responses(
+        (status_code = 200, description = "版本列表"),
+        (status_code = 401, description = "未授权"),
+        (status_code = 404, description = "资源不存在"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn admin_list_resource_versions(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) {
+    let id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state
+        .resource_version_service
+        .list_for_owner(id, None)
+        .await
+    {
+        Ok(versions) => res.success(versions),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 发布资源新版本
+///
+/// 资源文件同步为新版本（不改变审核状态）；已购买的用户会收到新版本邮件通知。
+#[endpoint(
+    request_body = PublishVersionRequest,
+    responses(
+        (status_code = 200, description = "发布成功"),
+        (status_code = 400, description = "参数错误或版本号已存在"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn admin_publish_resource_version(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) {
+    let admin_id = match require_user_id(depot) {
+        Ok(id) => id,
+        Err(status) => {
+            res.status_code(status);
+            return;
+        }
+    };
+
+    let id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let body: PublishVersionRequest = match req.parse_json().await {
+        Ok(b) => b,
+        Err(e) => {
+            res.error(RswsError::bad_request(format!("Invalid request: {}", e)));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state
+        .resource_version_service
+        .publish(id, None, admin_id, &body)
+        .await
+    {
+        Ok(version) => res.success(version),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 设置资源更新政策
+#[endpoint(
+    request_body = SetUpdatePolicyRequest,
+    responses(
+        (status_code = 200, description = "设置成功"),
+        (status_code = 400, description = "参数错误"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn admin_set_resource_update_policy(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) {
+    let id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let body: SetUpdatePolicyRequest = match req.parse_json().await {
+        Ok(b) => b,
+        Err(e) => {
+            res.error(RswsError::bad_request(format!("Invalid request: {}", e)));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state
+        .resource_version_service
+        .set_update_policy(id, None, &body)
+        .await
+    {
+        Ok(resource) => res.success(resource),
+        Err(e) => res.error(e),
+    }
+}
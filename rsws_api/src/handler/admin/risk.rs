@@ -0,0 +1,282 @@
+//! 订单风控管理处理器
+//!
+//! **权限说明：**
+//! - 所有 handler 已通过 `require_admin` 中间件保护
+//! - handler 内部无需再检查权限
+
+use crate::handler::common::{flag_rejected_payment, release_held_payment};
+use crate::state::{get_state, require_user_id};
+use rsws_common::{ResponseExt, RswsError};
+use rsws_model::risk::{CreateRiskRuleRequest, RiskReviewRequest, UpdateRiskRuleRequest};
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+use serde::Deserialize;
+
+/// 审核队列查询参数
+#[derive(Debug, Default, Deserialize)]
+struct RiskReviewQuery {
+    page: Option<i64>,
+    page_size: Option<i64>,
+}
+
+/// 获取全部风控规则
+#[endpoint(
+    responses(
+        (status_code = 200, description = "规则列表"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn list_risk_rules(depot: &mut Depot, res: &mut Response) {
+    let state = get_state(depot);
+
+    match state.risk_service.list_rules().await {
+        Ok(rules) => res.success(rules),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 创建风控规则
+#[endpoint(
+    request_body = CreateRiskRuleRequest,
+    responses(
+        (status_code = 200, description = "创建成功"),
+        (status_code = 400, description = "参数错误"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn create_risk_rule(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let body: CreateRiskRuleRequest = match req.parse_json().await {
+        Ok(b) => b,
+        Err(e) => {
+            res.error(RswsError::bad_request(format!("Invalid request: {}", e)));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.risk_service.create_rule(&body).await {
+        Ok(rule) => res.success(rule),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 更新风控规则（含启停）
+#[endpoint(
+    request_body = UpdateRiskRuleRequest,
+    responses(
+        (status_code = 200, description = "更新成功"),
+        (status_code = 400, description = "参数错误"),
+        (status_code = 401, description = "未授权"),
+        (status_code = 404, description = "规则不存在"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn update_risk_rule(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let body: UpdateRiskRuleRequest = match req.parse_json().await {
+        Ok(b) => b,
+        Err(e) => {
+            res.error(RswsError::bad_request(format!("Invalid request: {}", e)));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.risk_service.update_rule(id, &body).await {
+        Ok(rule) => res.success(rule),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 删除风控规则
+#[endpoint(
+    responses(
+        (status_code = 200, description = "删除成功"),
+        (status_code = 401, description = "未授权"),
+        (status_code = 404, description = "规则不存在"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn delete_risk_rule(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.risk_service.delete_rule(id).await {
+        Ok(()) => res.success(serde_json::json!({ "id": id, "deleted": true })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 分页获取待审核订单
+#[endpoint(
+    parameters(
+        ("page" = Option<i64>, Query, description = "页码"),
+        ("page_size" = Option<i64>, Query, description = "每页数量"),
+    ),
+    responses(
+        (status_code = 200, description = "审核队列"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn list_risk_reviews(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let query: RiskReviewQuery = req.parse_queries().unwrap_or_default();
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+
+    let state = get_state(depot);
+
+    match state.risk_service.review_queue(page, page_size).await {
+        Ok((items, total)) => res.success(serde_json::json!({
+            "items": items,
+            "total": total,
+            "page": page,
+            "page_size": page_size,
+            "total_pages": (total + page_size - 1) / page_size,
+        })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 获取订单的风控评估记录
+#[endpoint(
+    responses(
+        (status_code = 200, description = "评估记录"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn get_order_risk(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let order_id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.risk_service.list_assessments(order_id).await {
+        Ok(assessments) => res.success(assessments),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 审核待审核订单
+///
+/// 通过后订单可继续支付；若 PayPal / USDT 付款已被风控挂起，同时完成该笔付款。
+/// 拒绝则取消待支付订单；已到账的 PayPal / USDT 付款标记为待退款（`refund_required`）。
+#[endpoint(
+    request_body = RiskReviewRequest,
+    responses(
+        (status_code = 200, description = "审核完成"),
+        (status_code = 400, description = "订单不在审核中"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn review_risk_order(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let admin_id = match require_user_id(depot) {
+        Ok(id) => id,
+        Err(status) => {
+            res.status_code(status);
+            return;
+        }
+    };
+
+    let order_id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let body: RiskReviewRequest = match req.parse_json().await {
+        Ok(b) => b,
+        Err(e) => {
+            res.error(RswsError::bad_request(format!("Invalid request: {}", e)));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    let order = match state.risk_service.review(order_id, admin_id, &body).await {
+        Ok(order) => order,
+        Err(e) => {
+            res.error(e);
+            return;
+        }
+    };
+
+    let payment_released = if body.approve {
+        match release_held_payment(&state, order_id).await {
+            Ok(released) => released,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to release held payment for order {}: {}",
+                    order_id,
+                    e
+                );
+                false
+            }
+        }
+    } else {
+        false
+    };
+    let refund_required = if body.approve {
+        false
+    } else {
+        match flag_rejected_payment(&state, order_id).await {
+            Ok(flagged) => flagged,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to flag rejected payment for order {}: {}",
+                    order_id,
+                    e
+                );
+                false
+            }
+        }
+    };
+
+    res.success(serde_json::json!({
+        "order": order,
+        "payment_released": payment_released,
+        "refund_required": refund_required,
+    }));
+}
@@ -0,0 +1,217 @@
+//! 资源标签管理处理器
+//!
+//! **权限说明：**
+//! - 所有 handler 已通过 `require_admin` 中间件保护
+//! - handler 内部无需再检查权限
+
+use crate::state::{get_state, require_user_id};
+use rsws_common::{error_code::ErrorCode, ResponseExt, RswsError};
+use rsws_model::tag::{SaveTagRequest, SetResourceTagsRequest};
+use salvo::http::StatusCode;
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+use serde::Deserialize;
+
+/// 标签列表查询参数
+#[derive(Debug, Default, Deserialize)]
+struct TagQuery {
+    search: Option<String>,
+    page: Option<i64>,
+    page_size: Option<i64>,
+}
+
+/// 获取标签列表（含使用资源数）
+#[endpoint(
+    parameters(
+        ("search" = Option<String>, Query, description = "按名称或 slug 搜索"),
+        ("page" = Option<i64>, Query, description = "页码"),
+        ("page_size" = Option<i64>, Query, description = "每页数量"),
+    ),
+    responses(
+        (status_code = 200, description = "标签列表"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn list_tags(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let query: TagQuery = req.parse_queries().unwrap_or_default();
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+    let search = query.search.filter(|s| !s.trim().is_empty());
+
+    let state = get_state(depot);
+
+    match state
+        .tag_service
+        .list(search.as_deref(), page, page_size)
+        .await
+    {
+        Ok((items, total)) => res.success(serde_json::json!({
+            "items": items,
+            "total": total,
+            "page": page,
+            "page_size": page_size,
+            "total_pages": (total + page_size - 1) / page_size,
+        })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 创建标签
+#[endpoint(
+    request_body = SaveTagRequest,
+    responses(
+        (status_code = 201, description = "创建成功"),
+        (status_code = 400, description = "名称无效或标签已存在"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn create_tag(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let admin_id = match require_user_id(depot) {
+        Ok(id) => id,
+        Err(status) => {
+            res.status_code(status);
+            return;
+        }
+    };
+
+    let data = match req.parse_json::<SaveTagRequest>().await {
+        Ok(d) => d,
+        Err(e) => {
+            res.error_msg(
+                RswsError::from(ErrorCode::INVALID_REQUEST_FORMAT),
+                format!("Invalid request: {}", e),
+            );
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.tag_service.create(admin_id, &data.name).await {
+        Ok(tag) => {
+            res.status_code(StatusCode::CREATED);
+            res.success(tag);
+        }
+        Err(e) => res.error(e),
+    }
+}
+
+/// 重命名标签（slug 随名称更新）
+#[endpoint(
+    request_body = SaveTagRequest,
+    responses(
+        (status_code = 200, description = "修改成功"),
+        (status_code = 400, description = "名称无效或标签已存在"),
+        (status_code = 401, description = "未授权"),
+        (status_code = 404, description = "标签不存在"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn update_tag(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let tag_id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let data = match req.parse_json::<SaveTagRequest>().await {
+        Ok(d) => d,
+        Err(e) => {
+            res.error_msg(
+                RswsError::from(ErrorCode::INVALID_REQUEST_FORMAT),
+                format!("Invalid request: {}", e),
+            );
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.tag_service.rename(tag_id, &data.name).await {
+        Ok(tag) => res.success(tag),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 删除标签（资源上的该标签一并移除）
+#[endpoint(
+    responses(
+        (status_code = 200, description = "删除成功"),
+        (status_code = 401, description = "未授权"),
+        (status_code = 404, description = "标签不存在"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn delete_tag(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let tag_id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.tag_service.delete(tag_id).await {
+        Ok(()) => res.success(serde_json::json!({ "id": tag_id, "deleted": true })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 设置资源标签（任意资源，整体替换）
+#[endpoint(
+    request_body = SetResourceTagsRequest,
+    responses(
+        (status_code = 200, description = "设置成功"),
+        (status_code = 400, description = "标签无效或数量超限"),
+        (status_code = 401, description = "未授权"),
+        (status_code = 404, description = "资源不存在"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn admin_set_resource_tags(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let resource_id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let data = match req.parse_json::<SetResourceTagsRequest>().await {
+        Ok(d) => d,
+        Err(e) => {
+            res.error_msg(
+                RswsError::from(ErrorCode::INVALID_REQUEST_FORMAT),
+                format!("Invalid request: {}", e),
+            );
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state
+        .tag_service
+        .set_resource_tags(resource_id, None, &data.tags)
+        .await
+    {
+        Ok(tags) => res.success(tags),
+        Err(e) => res.error(e),
+    }
+}
@@ -0,0 +1,138 @@
+//! 入站 Webhook 日志处理器
+//!
+//! **权限说明：**
+//! - 所有 handler 已通过 `require_admin` 中间件保护
+//! - handler 内部无需再检查权限
+
+use crate::handler::common::{dispatch_webhook, finish_webhook, InboundWebhook};
+use crate::state::get_state;
+use rsws_common::{ResponseExt, RswsError};
+use rsws_model::log::webhook_log::WebhookLogQuery;
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+
+/// 分页查询入站 Webhook 日志
+#[endpoint(
+    parameters(
+        ("source" = Option<String>, Query, description = "来源：paypal / usdt / alipay / wechatpay"),
+        ("status" = Option<String>, Query, description = "状态：received / processed / ignored / failed"),
+        ("event_type" = Option<String>, Query, description = "事件类型"),
+        ("event_id" = Option<String>, Query, description = "网关事件 ID"),
+        ("page" = Option<i64>, Query, description = "页码"),
+        ("page_size" = Option<i64>, Query, description = "每页数量"),
+    ),
+    responses(
+        (status_code = 200, description = "Webhook 日志列表"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn list_webhook_logs(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let query: WebhookLogQuery = req.parse_queries().unwrap_or_default();
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+
+    let state = get_state(depot);
+
+    match state
+        .webhook_service
+        .list_logs(&query, page, page_size)
+        .await
+    {
+        Ok((logs, total)) => res.success(serde_json::json!({
+            "items": logs,
+            "total": total,
+            "page": page,
+            "page_size": page_size,
+            "total_pages": (total + page_size - 1) / page_size,
+        })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 获取 Webhook 日志详情（含原始载荷与请求头）
+#[endpoint(
+    responses(
+        (status_code = 200, description = "Webhook 日志"),
+        (status_code = 401, description = "未授权"),
+        (status_code = 404, description = "日志不存在"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn get_webhook_log(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.webhook_service.get_log(id).await {
+        Ok(log) => res.success(log),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 重放已存储的 Webhook
+///
+/// 按原始载荷与请求头重新走对应网关的处理流程（含验签），结果回写日志。
+/// 订单确认 / 充值到账均为幂等操作，已处理成功的回调重放不会重复入账。
+#[endpoint(
+    responses(
+        (status_code = 200, description = "重放完成"),
+        (status_code = 401, description = "未授权"),
+        (status_code = 404, description = "日志不存在"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn replay_webhook_log(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let id: i64 = match req.param("id") {
+        Some(id) => id,
+        None => {
+            res.error(RswsError::bad_request("Missing id parameter"));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    let inbound = match state
+        .webhook_service
+        .get_log(id)
+        .await
+        .and_then(|log| InboundWebhook::from_log(&log))
+    {
+        Ok(inbound) => inbound,
+        Err(e) => {
+            res.error(e);
+            return;
+        }
+    };
+    let log = match state.webhook_service.begin_replay(id).await {
+        Ok(log) => log,
+        Err(e) => {
+            res.error(e);
+            return;
+        }
+    };
+
+    let outcome = dispatch_webhook(&state, &inbound).await;
+    finish_webhook(&state, log.id, &outcome).await;
+
+    res.success(serde_json::json!({
+        "id": log.id,
+        "status": outcome.status,
+        "response_code": outcome.response_code().as_u16(),
+        "message": outcome.message,
+        "retry_count": log.retry_count,
+    }));
+}
@@ -0,0 +1,50 @@
+//! 心愿单报表处理器
+//!
+//! **权限说明：**
+//! - 所有 handler 已通过 `require_admin` 中间件保护
+//! - handler 内部无需再检查权限
+
+use crate::state::get_state;
+use rsws_common::ResponseExt;
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+use serde::Deserialize;
+
+/// 最受期待报表查询参数
+#[derive(Debug, Default, Deserialize)]
+struct MostWishedQuery {
+    days: Option<i32>,
+    limit: Option<i64>,
+}
+
+/// 最受期待资源报表
+///
+/// 按收藏或加入心愿单的用户数排序，附近 `days` 天新增保存数与已购买转化数。
+#[endpoint(
+    parameters(
+        ("days" = Option<i32>, Query, description = "近期新增统计天数，默认 30"),
+        ("limit" = Option<i64>, Query, description = "返回数量，默认 50"),
+    ),
+    responses(
+        (status_code = 200, description = "报表"),
+        (status_code = 401, description = "未授权"),
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn most_wished_report(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let query: MostWishedQuery = req.parse_queries().unwrap_or_default();
+    let days = query.days.unwrap_or(30).clamp(1, 365);
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    let state = get_state(depot);
+
+    match state.wishlist_service.most_wished(days, limit).await {
+        Ok(items) => res.success(serde_json::json!({
+            "days": days,
+            "items": items,
+        })),
+        Err(e) => res.error(e),
+    }
+}
@@ -14,8 +14,16 @@ pub use upload::upload_chunk;
 pub use upload::upload_single;
 
 // webhook.rs
+pub use webhook::alipay_webhook;
+pub(crate) use webhook::dispatch_webhook;
+pub(crate) use webhook::finish_webhook;
 pub use webhook::paypal_webhook;
+pub(crate) use webhook::spawn_order_paid_tasks;
+pub(crate) use webhook::spawn_order_refunded_tasks;
 pub use webhook::usdt_webhook;
+pub use webhook::wechatpay_webhook;
+pub(crate) use webhook::InboundWebhook;
+pub(crate) use webhook::{flag_rejected_payment, release_held_payment};
 
 // payment.rs
 pub use payment::get_usdt_address;
@@ -6,7 +6,8 @@ use futures_util::StreamExt;
 use http_body_util::BodyStream;
 use multer::Multipart;
 use rand::Rng;
-use rsws_common::{ResponseExt, RswsError};
+use rsws_common::{AuthHandler, ResponseExt, RswsError};
+use rsws_service::oss_service::{record_private_upload, StorageArea, StorageService};
 use salvo::oapi::extract::JsonBody;
 use salvo::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -114,6 +115,9 @@ pub struct InitUploadRequest {
     pub file_size: i64,
     pub chunk_size: Option<i64>,
     pub content_type: Option<String>,
+    /// 存储区域（资源文件传 `private`，缩略图/展示图默认 `public`）
+    #[serde(default)]
+    pub area: StorageArea,
 }
 
 /// 初始化上传响应
@@ -161,6 +165,11 @@ struct UploadSession {
     content_type: Option<String>,
     uploaded_chunks: Vec<i32>,
     created_at: i64,
+    #[serde(default)]
+    area: StorageArea,
+    /// 上传者用户 ID
+    #[serde(default)]
+    user_id: i64,
 }
 
 // ==================== Handler ====================
@@ -175,6 +184,10 @@ pub async fn init_upload(
 ) {
     let req = body.into_inner();
     let state = get_state(depot);
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
 
     if req.file_size <= 0 {
         return res.http_error(StatusCode::BAD_REQUEST, "文件大小必须大于 0");
@@ -197,6 +210,8 @@ pub async fn init_upload(
         content_type: req.content_type,
         uploaded_chunks: Vec::new(),
         created_at: chrono::Utc::now().timestamp(),
+        area: req.area,
+        user_id,
     };
 
     let session_key = format!("upload_session:{}", upload_id);
@@ -227,6 +242,10 @@ pub async fn init_upload(
 #[endpoint]
 pub async fn upload_chunk(req: &mut Request, depot: &mut Depot, res: &mut Response) {
     let state = get_state(depot);
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
 
     // 解析查询参数
     let upload_id: String = req
@@ -261,6 +280,9 @@ pub async fn upload_chunk(req: &mut Request, depot: &mut Depot, res: &mut Respon
             return res.error(RswsError::internal("读取上传会话失败"));
         }
     };
+    if session.user_id != user_id {
+        return res.http_error(StatusCode::NOT_FOUND, "上传会话不存在或已过期");
+    }
 
     if chunk_index < 0 || chunk_index >= session.total_chunks {
         return res.http_error(StatusCode::BAD_REQUEST, "无效的分块索引");
@@ -281,7 +303,7 @@ pub async fn upload_chunk(req: &mut Request, depot: &mut Depot, res: &mut Respon
         }
     };
 
-    let storage_service = match rsws_service::oss_service::StorageService::new(&oss_config).await {
+    let storage_service = match StorageService::for_area(&oss_config, session.area).await {
         Ok(s) => s,
         Err(e) => {
             error!("Failed to create storage service: {}", e);
@@ -334,6 +356,10 @@ pub async fn complete_upload(
 ) {
     let req = body.into_inner();
     let state = get_state(depot);
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
 
     let session_key = format!("upload_session:{}", req.upload_id);
     let session: UploadSession = match state
@@ -349,6 +375,9 @@ pub async fn complete_upload(
             return res.error(RswsError::internal("读取上传会话失败"));
         }
     };
+    if session.user_id != user_id {
+        return res.http_error(StatusCode::NOT_FOUND, "上传会话不存在或已过期");
+    }
 
     if session.uploaded_chunks.len() != session.total_chunks as usize {
         return res.http_error(
@@ -365,7 +394,7 @@ pub async fn complete_upload(
         Err(_e) => return res.error(RswsError::internal("获取存储配置失败")),
     };
 
-    let storage_service = match rsws_service::oss_service::StorageService::new(&oss_config).await {
+    let storage_service = match StorageService::for_area(&oss_config, session.area).await {
         Ok(s) => s,
         Err(_e) => return res.error(RswsError::internal("创建存储服务失败")),
     };
@@ -412,6 +441,19 @@ pub async fn complete_upload(
         result.url
     };
 
+    if session.area == StorageArea::Private {
+        if let Err(e) = record_private_upload(
+            state.config_service.redis_client(),
+            &session.file_key,
+            user_id,
+        )
+        .await
+        {
+            error!("Failed to record private upload: {}", e);
+            return res.error(RswsError::internal("记录上传文件归属失败"));
+        }
+    }
+
     if let Err(e) = state.config_service.redis_client().del(&session_key).await {
         error!("Failed to delete session: {}", e);
     }
@@ -428,10 +470,15 @@ pub async fn complete_upload(
     });
 }
 
-/// 单文件上传
+/// 单文件上传（查询参数 `area=private` 上传到私有区）
 #[endpoint]
 pub async fn upload_single(req: &mut Request, depot: &mut Depot, res: &mut Response) {
     let state = get_state(depot);
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+    let area: StorageArea = req.query("area").unwrap_or_default();
 
     let (file_data, filename, content_type) = match read_multipart_file(req).await {
         Ok((data, name, ct)) => (data, name, ct),
@@ -445,7 +492,7 @@ pub async fn upload_single(req: &mut Request, depot: &mut Depot, res: &mut Respo
         Err(_e) => return res.error(RswsError::internal("获取存储配置失败")),
     };
 
-    let storage_service = match rsws_service::oss_service::StorageService::new(&oss_config).await {
+    let storage_service = match StorageService::for_area(&oss_config, area).await {
         Ok(s) => s,
         Err(_e) => return res.error(RswsError::internal("创建存储服务失败")),
     };
@@ -457,6 +504,14 @@ pub async fn upload_single(req: &mut Request, depot: &mut Depot, res: &mut Respo
         Ok(r) => r,
         Err(e) => return res.error(RswsError::internal(format!("上传文件失败: {}", e))),
     };
+    if area == StorageArea::Private {
+        if let Err(e) =
+            record_private_upload(state.config_service.redis_client(), &file_key, user_id).await
+        {
+            error!("Failed to record private upload: {}", e);
+            return res.error(RswsError::internal("记录上传文件归属失败"));
+        }
+    }
 
     info!(
         "Single file uploaded: file_key={}, url={}",
@@ -1,12 +1,378 @@
 //! Webhook 处理器
 //!
-//! PayPal 和 USDT 的 webhook 回调，无需 API Key 认证，
+//! PayPal、USDT、支付宝、微信支付的 webhook 回调，无需 API Key 认证，
 //! 有独立的签名验证机制。
+//!
+//! 每个回调先验签，通过后写入 webhook_logs 并按网关事件 ID 去重，处理结果回写日志。
+//! 管理员重放时由 [`dispatch_webhook`] 走与实时回调相同的验签与处理流程。
 
-use crate::state::get_state;
-use rsws_common::ResponseExt;
+use crate::state::{get_state, AppState};
+use rsws_common::{error_code::ErrorCode, ResponseExt, RswsError};
+use rsws_model::event_webhook::{EVENT_ORDER_PAID, EVENT_ORDER_REFUNDED};
+use rsws_model::ledger::WalletTopup;
+use rsws_model::log::webhook_log::{
+    CreateWebhookLogRequest, WebhookLog, WebhookRecord, WEBHOOK_STATUS_FAILED,
+    WEBHOOK_STATUS_IGNORED, WEBHOOK_STATUS_PROCESSED,
+};
+use rsws_model::payment::{Order, PaymentTransaction};
+use rsws_model::risk::{
+    RiskContext, RISK_STAGE_PAYMENT, RISK_STATUS_APPROVED, RISK_STATUS_REJECTED, RISK_STATUS_REVIEW,
+};
+use rsws_service::wechatpay_service::WechatPayNotify;
+use rust_decimal::Decimal;
 use salvo::prelude::*;
 use salvo_oapi::endpoint;
+use serde_json::Value;
+use std::collections::HashMap;
+
+// ==================== 入站回调 ====================
+
+/// 回调来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WebhookSource {
+    PayPal,
+    Usdt,
+    Alipay,
+    WechatPay,
+}
+
+impl WebhookSource {
+    /// 对应 webhook_logs.source
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::PayPal => "paypal",
+            Self::Usdt => "usdt",
+            Self::Alipay => "alipay",
+            Self::WechatPay => "wechatpay",
+        }
+    }
+
+    fn parse(source: &str) -> Option<Self> {
+        match source {
+            "paypal" => Some(Self::PayPal),
+            "usdt" => Some(Self::Usdt),
+            "alipay" => Some(Self::Alipay),
+            "wechatpay" => Some(Self::WechatPay),
+            _ => None,
+        }
+    }
+}
+
+/// 入站回调（网关实时投递或从 webhook_logs 重放）
+pub(crate) struct InboundWebhook {
+    source: WebhookSource,
+    /// 请求头（键为小写）
+    headers: HashMap<String, String>,
+    /// 载荷：JSON 请求体；支付宝为表单参数对象
+    payload: Value,
+    /// 原始请求体（微信支付验签需要原文）
+    body: Option<String>,
+    /// 管理员重放（跳过通知时间戳有效期校验）
+    replay: bool,
+}
+
+impl InboundWebhook {
+    /// 读取 JSON 请求体（无法解析时载荷为 null，由处理流程记为失败）
+    async fn from_json(source: WebhookSource, req: &mut Request) -> Result<Self, String> {
+        let headers = collect_headers(req);
+        let body = match req.payload().await {
+            Ok(bytes) => String::from_utf8_lossy(bytes).to_string(),
+            Err(e) => return Err(e.to_string()),
+        };
+        let payload = serde_json::from_str(&body).unwrap_or(Value::Null);
+        Ok(Self {
+            source,
+            headers,
+            payload,
+            body: Some(body),
+            replay: false,
+        })
+    }
+
+    /// 读取表单请求体（支付宝异步通知）
+    async fn from_form(source: WebhookSource, req: &mut Request) -> Result<Self, String> {
+        let headers = collect_headers(req);
+        let payload: serde_json::Map<String, Value> = match req.form_data().await {
+            Ok(form) => form
+                .fields
+                .iter()
+                .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+                .collect(),
+            Err(e) => return Err(e.to_string()),
+        };
+        Ok(Self {
+            source,
+            headers,
+            payload: Value::Object(payload),
+            body: None,
+            replay: false,
+        })
+    }
+
+    /// 从已存储的日志还原，用于重放
+    pub(crate) fn from_log(log: &WebhookLog) -> Result<Self, RswsError> {
+        let source = WebhookSource::parse(&log.source)
+            .ok_or_else(|| RswsError::business(ErrorCode::WEBHOOK_SOURCE_INVALID))?;
+        let headers = log
+            .headers
+            .clone()
+            .and_then(|h| serde_json::from_value(h).ok())
+            .unwrap_or_default();
+        Ok(Self {
+            source,
+            headers,
+            payload: log.payload.clone(),
+            body: log.body.clone(),
+            replay: true,
+        })
+    }
+
+    fn header(&self, name: &str) -> &str {
+        self.headers.get(name).map(String::as_str).unwrap_or("")
+    }
+
+    /// 网关事件 ID（去重依据）
+    fn event_id(&self) -> Option<String> {
+        let id = match self.source {
+            WebhookSource::PayPal | WebhookSource::WechatPay => self.payload["id"].as_str(),
+            WebhookSource::Usdt => self.payload["tx_hash"].as_str(),
+            WebhookSource::Alipay => self.payload["notify_id"].as_str(),
+        };
+        id.filter(|s| !s.is_empty()).map(str::to_string)
+    }
+
+    fn event_type(&self) -> String {
+        let event_type = match self.source {
+            WebhookSource::PayPal | WebhookSource::WechatPay => self.payload["event_type"].as_str(),
+            WebhookSource::Usdt => self.payload["network"].as_str(),
+            WebhookSource::Alipay => self.payload["trade_status"].as_str(),
+        };
+        event_type.unwrap_or("UNKNOWN").to_string()
+    }
+
+    fn signature(&self) -> Option<String> {
+        let signature = match self.source {
+            WebhookSource::PayPal => self.header("paypal-transmission-sig"),
+            WebhookSource::Usdt => "",
+            WebhookSource::Alipay => self.payload["sign"].as_str().unwrap_or(""),
+            WebhookSource::WechatPay => self.header("wechatpay-signature"),
+        };
+        (!signature.is_empty()).then(|| signature.to_string())
+    }
+}
+
+fn collect_headers(req: &Request) -> HashMap<String, String> {
+    req.headers()
+        .iter()
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|v| (name.as_str().to_string(), v.to_string()))
+        })
+        .collect()
+}
+
+/// 回调处理结果，按来源渲染为网关约定的应答格式
+pub(crate) struct WebhookOutcome {
+    source: WebhookSource,
+    /// 写入日志的处理状态：processed / ignored / failed
+    pub(crate) status: &'static str,
+    /// 失败时的 HTTP 状态码
+    http_status: StatusCode,
+    pub(crate) message: String,
+    data: Option<Value>,
+}
+
+impl WebhookOutcome {
+    fn processed(source: WebhookSource, message: impl Into<String>) -> Self {
+        Self::new(source, WEBHOOK_STATUS_PROCESSED, StatusCode::OK, message)
+    }
+
+    fn ignored(source: WebhookSource, message: impl Into<String>) -> Self {
+        Self::new(source, WEBHOOK_STATUS_IGNORED, StatusCode::OK, message)
+    }
+
+    /// 处理失败，网关收到失败应答后会重试
+    fn failed(source: WebhookSource, http_status: StatusCode, message: impl Into<String>) -> Self {
+        Self::new(source, WEBHOOK_STATUS_FAILED, http_status, message)
+    }
+
+    fn new(
+        source: WebhookSource,
+        status: &'static str,
+        http_status: StatusCode,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            source,
+            status,
+            http_status,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    /// 自定义成功应答数据（PayPal / USDT）
+    fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// 应答 HTTP 状态码
+    pub(crate) fn response_code(&self) -> StatusCode {
+        if self.status == WEBHOOK_STATUS_FAILED {
+            self.http_status
+        } else if self.source == WebhookSource::WechatPay {
+            StatusCode::NO_CONTENT
+        } else {
+            StatusCode::OK
+        }
+    }
+
+    /// 按网关约定输出应答：
+    /// - PayPal / USDT：统一响应格式
+    /// - 支付宝：纯文本 `success` / `failure`
+    /// - 微信支付：成功 204，失败 `{"code":"FAIL"}`
+    fn render(self, res: &mut Response) {
+        let failed = self.status == WEBHOOK_STATUS_FAILED;
+        match self.source {
+            WebhookSource::PayPal | WebhookSource::Usdt => {
+                if failed {
+                    res.http_error(self.http_status, self.message);
+                } else {
+                    res.success(
+                        self.data
+                            .unwrap_or_else(|| serde_json::json!({ "status": self.status })),
+                    );
+                }
+            }
+            WebhookSource::Alipay => {
+                res.status_code(self.response_code());
+                res.render(Text::Plain(if failed { "failure" } else { "success" }));
+            }
+            WebhookSource::WechatPay => {
+                res.status_code(self.response_code());
+                if failed {
+                    res.render(Json(
+                        serde_json::json!({ "code": "FAIL", "message": self.message }),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// 验签、记录并处理实时回调
+///
+/// 验签失败不落库；落库失败或同一事件仍在处理中时返回失败应答让网关重试；
+/// 重复投递直接应答成功，不再处理。
+async fn receive_webhook(
+    req: &Request,
+    depot: &mut Depot,
+    res: &mut Response,
+    inbound: InboundWebhook,
+) {
+    let source = inbound.source;
+    let state = get_state(depot);
+
+    if let Some(outcome) = verify_webhook(&state, &inbound).await {
+        outcome.render(res);
+        return;
+    }
+
+    let ip_address = match req.remote_addr() {
+        salvo::conn::SocketAddr::IPv4(v4) => Some(v4.ip().to_string()),
+        salvo::conn::SocketAddr::IPv6(v6) => Some(v6.ip().to_string()),
+        _ => None,
+    };
+    let record = CreateWebhookLogRequest {
+        webhook_type: "payment".to_string(),
+        source: source.as_str().to_string(),
+        event_id: inbound.event_id(),
+        event_type: inbound.event_type(),
+        payload: inbound.payload.clone(),
+        body: inbound.body.clone(),
+        headers: serde_json::to_value(&inbound.headers).ok(),
+        signature: inbound.signature(),
+        ip_address,
+    };
+
+    let log = match state.webhook_service.record(&record).await {
+        Ok(WebhookRecord::Process(log)) => log,
+        Ok(WebhookRecord::Duplicate) => {
+            WebhookOutcome::ignored(source, "Duplicate delivery")
+                .with_data(serde_json::json!({ "status": "duplicate" }))
+                .render(res);
+            return;
+        }
+        Ok(WebhookRecord::InProgress) => {
+            WebhookOutcome::failed(
+                source,
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Delivery in progress",
+            )
+            .render(res);
+            return;
+        }
+        Err(e) => {
+            tracing::error!("Failed to record {} webhook: {}", source.as_str(), e);
+            WebhookOutcome::failed(source, StatusCode::INTERNAL_SERVER_ERROR, "Database error")
+                .render(res);
+            return;
+        }
+    };
+
+    let outcome = process_webhook(&state, &inbound).await;
+    finish_webhook(&state, log.id, &outcome).await;
+    outcome.render(res);
+}
+
+/// 验签后按来源分发处理（管理员重放）
+pub(crate) async fn dispatch_webhook(state: &AppState, inbound: &InboundWebhook) -> WebhookOutcome {
+    if let Some(outcome) = verify_webhook(state, inbound).await {
+        return outcome;
+    }
+    process_webhook(state, inbound).await
+}
+
+/// 校验回调签名，失败时返回失败应答（USDT 通知无签名，由链上监听确认）
+async fn verify_webhook(state: &AppState, inbound: &InboundWebhook) -> Option<WebhookOutcome> {
+    match inbound.source {
+        WebhookSource::PayPal => verify_paypal(state, inbound).await,
+        WebhookSource::Usdt => None,
+        WebhookSource::Alipay => verify_alipay(state, inbound),
+        WebhookSource::WechatPay => verify_wechatpay(state, inbound),
+    }
+}
+
+/// 按来源处理已验签的回调
+async fn process_webhook(state: &AppState, inbound: &InboundWebhook) -> WebhookOutcome {
+    match inbound.source {
+        WebhookSource::PayPal => process_paypal(state, inbound).await,
+        WebhookSource::Usdt => process_usdt(state, inbound).await,
+        WebhookSource::Alipay => process_alipay(state, inbound).await,
+        WebhookSource::WechatPay => process_wechatpay(state, inbound).await,
+    }
+}
+
+/// 回写处理结果（失败只记录日志，不影响应答）
+pub(crate) async fn finish_webhook(state: &AppState, log_id: i64, outcome: &WebhookOutcome) {
+    if let Err(e) = state
+        .webhook_service
+        .finish(
+            log_id,
+            outcome.status,
+            outcome.response_code().as_u16() as i32,
+            &outcome.message,
+        )
+        .await
+    {
+        tracing::warn!("Failed to update webhook log {}: {}", log_id, e);
+    }
+}
+
+// ==================== PayPal ====================
 
 /// PayPal Webhook — 接收并处理 PayPal 事件通知
 ///
@@ -21,34 +387,27 @@ use salvo_oapi::endpoint;
     )
 )]
 pub async fn paypal_webhook(req: &mut Request, depot: &mut Depot, res: &mut Response) {
-    // 提取关键请求头（用于签名验证）
-    let transmission_id = req
-        .headers()
-        .get("paypal-transmission-id")
-        .and_then(|v| v.to_str().ok())
-        .map(|s| s.to_string());
-    let transmission_time = req
-        .headers()
-        .get("paypal-transmission-time")
-        .and_then(|v| v.to_str().ok())
-        .map(|s| s.to_string());
-    let transmission_sig = req
-        .headers()
-        .get("paypal-transmission-sig")
-        .and_then(|v| v.to_str().ok())
-        .map(|s| s.to_string());
-
-    // 解析事件体
-    let event: serde_json::Value = match req.parse_json().await {
-        Ok(v) => v,
+    match InboundWebhook::from_json(WebhookSource::PayPal, req).await {
+        Ok(inbound) => receive_webhook(req, depot, res, inbound).await,
         Err(e) => {
-            tracing::error!("Failed to parse PayPal webhook body: {}", e);
-            res.http_error(StatusCode::BAD_REQUEST, "Invalid JSON");
-            return;
+            tracing::error!("Failed to read PayPal webhook body: {}", e);
+            res.http_error(StatusCode::BAD_REQUEST, "Invalid body");
         }
-    };
+    }
+}
 
-    let state = get_state(depot);
+/// 验证 PayPal Webhook 签名（dev 模式下验证出错不影响处理）
+async fn verify_paypal(state: &AppState, inbound: &InboundWebhook) -> Option<WebhookOutcome> {
+    let source = WebhookSource::PayPal;
+    let event = &inbound.payload;
+    if !event.is_object() {
+        tracing::error!("Failed to parse PayPal webhook body");
+        return Some(WebhookOutcome::failed(
+            source,
+            StatusCode::BAD_REQUEST,
+            "Invalid JSON",
+        ));
+    }
 
     // 构造签名验证参数
     let _webhook_id = state
@@ -59,47 +418,44 @@ pub async fn paypal_webhook(req: &mut Request, depot: &mut Depot, res: &mut Resp
         .flatten()
         .unwrap_or_default();
     let event_json = event.to_string();
-    let headers_for_verify: Vec<(String, String)> = vec![
-        (
-            "PAYPAL-TRANSMISSION-ID".to_string(),
-            transmission_id.unwrap_or_default(),
-        ),
-        (
-            "PAYPAL-TRANSMISSION-TIME".to_string(),
-            transmission_time.unwrap_or_default(),
-        ),
-        (
-            "PAYPAL-TRANSMISSION-SIG".to_string(),
-            transmission_sig.unwrap_or_default(),
-        ),
-        (
-            "PAYPAL-CERT-URL".to_string(),
-            req.headers()
-                .get("paypal-cert-url")
-                .and_then(|v| v.to_str().ok())
-                .unwrap_or("")
-                .to_string(),
-        ),
-        ("CONTENT-TYPE".to_string(), "application/json".to_string()),
-    ];
-
-    // 验证 PayPal Webhook 签名（dev 模式下失败不影响处理）
+    let headers_for_verify: Vec<(String, String)> = [
+        ("PAYPAL-TRANSMISSION-ID", "paypal-transmission-id"),
+        ("PAYPAL-TRANSMISSION-TIME", "paypal-transmission-time"),
+        ("PAYPAL-TRANSMISSION-SIG", "paypal-transmission-sig"),
+        ("PAYPAL-CERT-URL", "paypal-cert-url"),
+    ]
+    .iter()
+    .map(|(key, header)| (key.to_string(), inbound.header(header).to_string()))
+    .chain(std::iter::once((
+        "CONTENT-TYPE".to_string(),
+        "application/json".to_string(),
+    )))
+    .collect();
+
     match state
         .paypal_service
         .verify_webhook(&headers_for_verify, event_json.as_bytes())
         .await
     {
-        Ok(true) => {}
+        Ok(true) => None,
         Ok(false) => {
             tracing::warn!("PayPal webhook signature verification failed");
-            res.http_error(StatusCode::FORBIDDEN, "Invalid signature");
-            return;
+            Some(WebhookOutcome::failed(
+                source,
+                StatusCode::FORBIDDEN,
+                "Invalid signature",
+            ))
         }
         Err(e) => {
             tracing::warn!("PayPal signature verify error (dev mode): {}", e);
+            None
         }
     }
+}
 
+async fn process_paypal(state: &AppState, inbound: &InboundWebhook) -> WebhookOutcome {
+    let source = WebhookSource::PayPal;
+    let event = &inbound.payload;
     let event_type = event["event_type"].as_str().unwrap_or("UNKNOWN");
     let resource = &event["resource"];
 
@@ -125,29 +481,51 @@ pub async fn paypal_webhook(req: &mut Request, depot: &mut Depot, res: &mut Resp
                 .await
             {
                 let order_id = tx.order_id;
-                let tx_id = tx.id;
 
-                if let Err(e) = state.order_service.mark_paid(order_id, "paypal").await {
-                    tracing::error!("Failed to mark order {} as paid: {}", order_id, e);
-                    res.http_error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to update order");
-                    return;
+                let captured = event_type == "PAYMENT.CAPTURE.COMPLETED";
+                if let Some(outcome) =
+                    screen_paypal_payment(state, source, &tx, paypal_order_id, resource, captured)
+                        .await
+                {
+                    return outcome;
+                }
+
+                match confirm_paypal_order(state, &tx, paypal_order_id, captured).await {
+                    Ok(true) => {
+                        WebhookOutcome::processed(source, format!("Order {} paid", order_id))
+                    }
+                    Ok(false) => WebhookOutcome::ignored(
+                        source,
+                        format!("Order {} is not pending", order_id),
+                    ),
+                    Err(e) => {
+                        tracing::error!("Failed to mark order {} as paid: {}", order_id, e);
+                        WebhookOutcome::failed(
+                            source,
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            "Failed to update order",
+                        )
+                    }
                 }
-                if let Err(e) = state
-                    .payment_service
-                    .update_status(tx_id, "completed", Some(paypal_order_id))
-                    .await
+            } else if let Ok(Some(topup)) = state
+                .ledger_service
+                .get_topup_by_provider_tx(paypal_order_id)
+                .await
+            {
+                if let Err(e) = confirm_notify_topup(state, &topup, "paypal", paypal_order_id).await
                 {
-                    tracing::error!("Failed to update transaction {}: {}", tx_id, e);
+                    tracing::error!("Failed to credit topup {}: {}", topup.id, e);
+                    return WebhookOutcome::failed(
+                        source,
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "Failed to update topup",
+                    );
                 }
-                tracing::info!(
-                    "Order {} paid via PayPal. TX: {}",
-                    order_id,
-                    paypal_order_id
-                );
+                WebhookOutcome::processed(source, format!("Topup {} credited", topup.id))
             } else {
                 tracing::warn!("PayPal order {} not found in our records", paypal_order_id);
+                WebhookOutcome::ignored(source, format!("Order {} not found", paypal_order_id))
             }
-            res.success(serde_json::json!({ "status": "processed" }));
         }
 
         // 支付被拒绝/退款
@@ -160,33 +538,81 @@ pub async fn paypal_webhook(req: &mut Request, depot: &mut Depot, res: &mut Resp
                 resource["status"].as_str().unwrap_or("")
             );
 
-            if let Ok(Some(tx)) = state
+            let tx = match state
                 .payment_service
                 .get_by_paypal_order(paypal_order_id)
                 .await
             {
-                let order_id = tx.order_id;
-                let _ = state.order_service.cancel(order_id, 0).await;
-                let status = if event_type == "PAYMENT.CAPTURE.REFUNDED" {
-                    "refunded"
-                } else {
-                    "failed"
-                };
-                let _ = state
-                    .payment_service
-                    .update_status(tx.id, status, None)
-                    .await;
+                Ok(Some(tx)) => tx,
+                Ok(None) => {
+                    return WebhookOutcome::ignored(
+                        source,
+                        format!("Order {} not found", paypal_order_id),
+                    )
+                }
+                Err(e) => {
+                    tracing::error!("Failed to load PayPal order {}: {}", paypal_order_id, e);
+                    return WebhookOutcome::failed(
+                        source,
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "Failed to load order",
+                    );
+                }
+            };
+            let order_id = tx.order_id;
+            let refunded = event_type == "PAYMENT.CAPTURE.REFUNDED";
+            let result = if refunded {
+                state.ledger_service.refund_order(order_id).await
+            } else {
+                state.order_service.cancel_unpaid(order_id).await
+            };
+            let changed = match result {
+                Ok(changed) => changed,
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to apply {} to order {}: {}",
+                        event_type,
+                        order_id,
+                        e
+                    );
+                    return WebhookOutcome::failed(
+                        source,
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "Failed to update order",
+                    );
+                }
+            };
+
+            let status = if refunded { "refunded" } else { "failed" };
+            if let Err(e) = state
+                .payment_service
+                .update_status(tx.id, status, None)
+                .await
+            {
+                tracing::error!("Failed to update transaction {}: {}", tx.id, e);
+            }
+            if refunded && changed {
+                spawn_order_refunded_tasks(state, order_id);
+            }
+            if changed {
+                WebhookOutcome::processed(source, event_type)
+            } else {
+                WebhookOutcome::ignored(
+                    source,
+                    format!("Order {} status unchanged by {}", order_id, event_type),
+                )
             }
-            res.success(serde_json::json!({ "status": "processed" }));
         }
 
         _ => {
             tracing::info!("Unhandled PayPal event: {}", event_type);
-            res.success(serde_json::json!({ "status": "ignored" }));
+            WebhookOutcome::ignored(source, format!("Unhandled event {}", event_type))
         }
     }
 }
 
+// ==================== USDT ====================
+
 /// USDT 支付确认 Webhook — 接收链上 USDT 转账通知
 #[endpoint(
     responses(
@@ -195,6 +621,16 @@ pub async fn paypal_webhook(req: &mut Request, depot: &mut Depot, res: &mut Resp
     )
 )]
 pub async fn usdt_webhook(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    match InboundWebhook::from_json(WebhookSource::Usdt, req).await {
+        Ok(inbound) => receive_webhook(req, depot, res, inbound).await,
+        Err(e) => {
+            tracing::error!("Failed to read USDT webhook body: {}", e);
+            res.http_error(StatusCode::BAD_REQUEST, "Invalid payload");
+        }
+    }
+}
+
+async fn process_usdt(state: &AppState, inbound: &InboundWebhook) -> WebhookOutcome {
     #[derive(serde::Deserialize)]
     struct UsdtWebhookPayload {
         tx_hash: String,
@@ -205,55 +641,772 @@ pub async fn usdt_webhook(req: &mut Request, depot: &mut Depot, res: &mut Respon
         network: String,
     }
 
-    let payload: Result<UsdtWebhookPayload, _> = req.parse_json().await;
+    let source = WebhookSource::Usdt;
+    let data: UsdtWebhookPayload = match serde_json::from_value(inbound.payload.clone()) {
+        Ok(d) => d,
+        Err(e) => {
+            tracing::error!("USDT webhook parse error: {}", e);
+            return WebhookOutcome::failed(source, StatusCode::BAD_REQUEST, "Invalid payload");
+        }
+    };
 
-    match payload {
-        Ok(data) => {
-            tracing::info!(
-                "USDT webhook: {} {} from {} to {} amount {}",
-                data.network,
-                data.tx_hash,
-                data.from_address,
-                data.to_address,
-                data.amount
+    tracing::info!(
+        "USDT webhook: {} {} from {} to {} amount {}",
+        data.network,
+        data.tx_hash,
+        data.from_address,
+        data.to_address,
+        data.amount
+    );
+
+    // 验证 to_address 是否是我们的收款地址
+    let expected_address = match data.network.as_str() {
+        "tron" => state.blockchain_service.get_trc20_address().await,
+        "ethereum" => state.blockchain_service.get_erc20_address().await,
+        _ => {
+            tracing::warn!("Unknown USDT network: {}", data.network);
+            return WebhookOutcome::ignored(source, format!("Unknown network {}", data.network));
+        }
+    };
+
+    if data.to_address.to_lowercase() != expected_address.to_lowercase() {
+        tracing::warn!(
+            "USDT webhook: to_address mismatch. Expected: {}, Got: {}",
+            expected_address,
+            data.to_address
+        );
+        return WebhookOutcome::ignored(source, "to_address mismatch");
+    }
+
+    tracing::info!(
+        "USDT deposit confirmed: {} {} amount {}",
+        data.network,
+        data.tx_hash,
+        data.amount
+    );
+
+    // 真实确认由 processor.rs 链上监听完成，这里只记录
+    WebhookOutcome::processed(source, "Recorded, confirmation by chain listener")
+        .with_data(serde_json::json!({ "status": "received", "tx_hash": data.tx_hash }))
+}
+
+// ==================== 支付宝 ====================
+
+/// 支付宝异步通知
+///
+/// 表单参数经 RSA2 验签后处理：
+/// - TRADE_SUCCESS / TRADE_FINISHED: 校验金额后标记订单已支付
+/// - 其他状态仅记录
+///
+/// 按支付宝约定，处理成功返回纯文本 `success`，否则返回 `failure` 触发重试。
+#[endpoint(
+    responses(
+        (status_code = 200, description = "处理成功"),
+        (status_code = 400, description = "验签失败"),
+    )
+)]
+pub async fn alipay_webhook(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    match InboundWebhook::from_form(WebhookSource::Alipay, req).await {
+        Ok(inbound) => receive_webhook(req, depot, res, inbound).await,
+        Err(e) => {
+            tracing::error!("Failed to parse Alipay notify body: {}", e);
+            res.status_code(StatusCode::BAD_REQUEST);
+            res.render(Text::Plain("failure"));
+        }
+    }
+}
+
+/// 支付宝异步通知 RSA2 验签
+fn verify_alipay(state: &AppState, inbound: &InboundWebhook) -> Option<WebhookOutcome> {
+    let source = WebhookSource::Alipay;
+    let params: HashMap<String, String> =
+        serde_json::from_value(inbound.payload.clone()).unwrap_or_default();
+
+    match state.alipay_service.verify_notify(&params) {
+        Ok(true) => None,
+        Ok(false) => {
+            tracing::warn!("Alipay notify signature verification failed");
+            Some(WebhookOutcome::failed(
+                source,
+                StatusCode::BAD_REQUEST,
+                "Invalid signature",
+            ))
+        }
+        Err(e) => {
+            tracing::error!("Alipay notify verify error: {}", e);
+            Some(WebhookOutcome::failed(
+                source,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Verify error",
+            ))
+        }
+    }
+}
+
+async fn process_alipay(state: &AppState, inbound: &InboundWebhook) -> WebhookOutcome {
+    let source = WebhookSource::Alipay;
+    let params: HashMap<String, String> =
+        serde_json::from_value(inbound.payload.clone()).unwrap_or_default();
+
+    let get = |key: &str| params.get(key).map(|s| s.as_str()).unwrap_or("");
+    let trade_status = get("trade_status");
+    let out_trade_no = get("out_trade_no");
+    let trade_no = get("trade_no");
+
+    tracing::info!(
+        "Alipay notify: {} | out_trade_no={} trade_no={}",
+        trade_status,
+        out_trade_no,
+        trade_no
+    );
+
+    if trade_status != "TRADE_SUCCESS" && trade_status != "TRADE_FINISHED" {
+        return WebhookOutcome::ignored(source, format!("Trade status {}", trade_status));
+    }
+
+    let order = match out_trade_no.parse::<i64>() {
+        Ok(id) => state.order_service.get(id).await,
+        Err(_) => Ok(None),
+    };
+    let order = match order {
+        Ok(Some(o)) => o,
+        Ok(None) => {
+            // 非资源订单时按余额充值单处理
+            return match load_topup(state, out_trade_no).await {
+                Ok(Some(topup)) => {
+                    if !state
+                        .alipay_service
+                        .amount_matches(topup.amount, get("total_amount"))
+                    {
+                        tracing::error!(
+                            "Alipay amount mismatch for topup {}: notify total_amount={}",
+                            topup.id,
+                            get("total_amount")
+                        );
+                        WebhookOutcome::ignored(source, "Amount mismatch")
+                    } else if let Err(e) =
+                        confirm_notify_topup(state, &topup, "alipay", trade_no).await
+                    {
+                        tracing::error!("Failed to credit Alipay topup {}: {}", topup.id, e);
+                        WebhookOutcome::failed(
+                            source,
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            "Failed to update topup",
+                        )
+                    } else {
+                        WebhookOutcome::processed(source, format!("Topup {} credited", topup.id))
+                    }
+                }
+                Ok(None) => {
+                    tracing::warn!("Alipay order {} not found in our records", out_trade_no);
+                    WebhookOutcome::ignored(source, format!("Order {} not found", out_trade_no))
+                }
+                Err(e) => {
+                    tracing::error!("Failed to load topup {}: {}", out_trade_no, e);
+                    WebhookOutcome::failed(
+                        source,
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "Database error",
+                    )
+                }
+            };
+        }
+        Err(e) => {
+            tracing::error!("Failed to load order {}: {}", out_trade_no, e);
+            return WebhookOutcome::failed(
+                source,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
             );
+        }
+    };
 
-            let state = get_state(depot);
+    if !state
+        .alipay_service
+        .amount_matches(order.amount, get("total_amount"))
+    {
+        tracing::error!(
+            "Alipay amount mismatch for order {}: notify total_amount={}",
+            order.id,
+            get("total_amount")
+        );
+        return WebhookOutcome::ignored(source, "Amount mismatch");
+    }
 
-            // 验证 to_address 是否是我们的收款地址
-            let expected_address = match data.network.as_str() {
-                "tron" => state.blockchain_service.get_trc20_address().await,
-                "ethereum" => state.blockchain_service.get_erc20_address().await,
-                _ => {
-                    tracing::warn!("Unknown USDT network: {}", data.network);
-                    res.success(serde_json::json!({ "status": "ignored" }));
-                    return;
+    match confirm_notify_payment(state, &order, "alipay", trade_no).await {
+        Ok(()) => WebhookOutcome::processed(source, format!("Order {} paid", order.id)),
+        Err(e) => {
+            tracing::error!("Failed to confirm Alipay order {}: {}", order.id, e);
+            WebhookOutcome::failed(
+                source,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to update order",
+            )
+        }
+    }
+}
+
+// ==================== 微信支付 ====================
+
+/// 微信支付回调通知（API v3）
+///
+/// 使用微信支付公钥验签后，以 APIv3 密钥解密 resource：
+/// - TRANSACTION.SUCCESS 且 trade_state = SUCCESS: 校验金额后标记订单已支付
+/// - 其他事件仅记录
+///
+/// 按微信支付约定，成功返回 204，失败返回 4XX/5XX 及 `{"code":"FAIL"}` 触发重试。
+#[endpoint(
+    responses(
+        (status_code = 204, description = "处理成功"),
+        (status_code = 400, description = "验签或解密失败"),
+    )
+)]
+pub async fn wechatpay_webhook(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    match InboundWebhook::from_json(WebhookSource::WechatPay, req).await {
+        Ok(inbound) => receive_webhook(req, depot, res, inbound).await,
+        Err(e) => {
+            tracing::error!("Failed to read WeChat Pay notify body: {}", e);
+            WebhookOutcome::failed(
+                WebhookSource::WechatPay,
+                StatusCode::BAD_REQUEST,
+                "Invalid body",
+            )
+            .render(res);
+        }
+    }
+}
+
+/// 微信支付回调验签（重放时通知时间戳必然过期，只校验签名）
+fn verify_wechatpay(state: &AppState, inbound: &InboundWebhook) -> Option<WebhookOutcome> {
+    let source = WebhookSource::WechatPay;
+    let timestamp = inbound.header("wechatpay-timestamp");
+    let nonce = inbound.header("wechatpay-nonce");
+    let signature = inbound.header("wechatpay-signature");
+    let serial = inbound.header("wechatpay-serial");
+    let body = inbound.body.as_deref().unwrap_or("");
+
+    let verified = if inbound.replay {
+        state
+            .wechatpay_service
+            .verify_signature(timestamp, nonce, body, signature, serial)
+    } else {
+        state
+            .wechatpay_service
+            .verify_notify(timestamp, nonce, body, signature, serial)
+    };
+    match verified {
+        Ok(true) => None,
+        Ok(false) => {
+            tracing::warn!("WeChat Pay notify signature verification failed");
+            Some(WebhookOutcome::failed(
+                source,
+                StatusCode::BAD_REQUEST,
+                "Invalid signature",
+            ))
+        }
+        Err(e) => {
+            tracing::error!("WeChat Pay notify verify error: {}", e);
+            Some(WebhookOutcome::failed(
+                source,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Verify error",
+            ))
+        }
+    }
+}
+
+async fn process_wechatpay(state: &AppState, inbound: &InboundWebhook) -> WebhookOutcome {
+    let source = WebhookSource::WechatPay;
+    let body = inbound.body.as_deref().unwrap_or("");
+
+    let notify: WechatPayNotify = match serde_json::from_str(body) {
+        Ok(n) => n,
+        Err(e) => {
+            tracing::error!("Failed to parse WeChat Pay notify: {}", e);
+            return WebhookOutcome::failed(source, StatusCode::BAD_REQUEST, "Invalid JSON");
+        }
+    };
+
+    tracing::info!(
+        "WeChat Pay notify: {} | id={}",
+        notify.event_type,
+        notify.id
+    );
+
+    if notify.event_type != "TRANSACTION.SUCCESS" {
+        return WebhookOutcome::ignored(source, format!("Unhandled event {}", notify.event_type));
+    }
+
+    let transaction = match state.wechatpay_service.decrypt_resource(&notify.resource) {
+        Ok(t) => t,
+        Err(e) => {
+            tracing::error!("Failed to decrypt WeChat Pay resource: {}", e);
+            return WebhookOutcome::failed(source, StatusCode::BAD_REQUEST, "Decrypt failed");
+        }
+    };
+
+    if transaction["trade_state"].as_str() != Some("SUCCESS") {
+        return WebhookOutcome::ignored(
+            source,
+            format!(
+                "Trade state {}",
+                transaction["trade_state"].as_str().unwrap_or("")
+            ),
+        );
+    }
+
+    let out_trade_no = transaction["out_trade_no"].as_str().unwrap_or("");
+    let transaction_id = transaction["transaction_id"].as_str().unwrap_or("");
+    let total = transaction["amount"]["total"].as_i64().unwrap_or(-1);
+
+    let order = match out_trade_no.parse::<i64>() {
+        Ok(id) => state.order_service.get(id).await,
+        Err(_) => Ok(None),
+    };
+    let order = match order {
+        Ok(Some(o)) => o,
+        Ok(None) => {
+            // 非资源订单时按余额充值单处理
+            return match load_topup(state, out_trade_no).await {
+                Ok(Some(topup)) => {
+                    if state.wechatpay_service.to_fen(topup.amount) != total {
+                        tracing::error!(
+                            "WeChat Pay amount mismatch for topup {}: notify total={}",
+                            topup.id,
+                            total
+                        );
+                        WebhookOutcome::ignored(source, "Amount mismatch")
+                    } else if let Err(e) =
+                        confirm_notify_topup(state, &topup, "wechatpay", transaction_id).await
+                    {
+                        tracing::error!("Failed to credit WeChat Pay topup {}: {}", topup.id, e);
+                        WebhookOutcome::failed(
+                            source,
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            "Failed to update topup",
+                        )
+                    } else {
+                        WebhookOutcome::processed(source, format!("Topup {} credited", topup.id))
+                    }
+                }
+                Ok(None) => {
+                    tracing::warn!("WeChat Pay order {} not found in our records", out_trade_no);
+                    WebhookOutcome::ignored(source, format!("Order {} not found", out_trade_no))
+                }
+                Err(e) => {
+                    tracing::error!("Failed to load topup {}: {}", out_trade_no, e);
+                    WebhookOutcome::failed(
+                        source,
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "Database error",
+                    )
                 }
             };
+        }
+        Err(e) => {
+            tracing::error!("Failed to load order {}: {}", out_trade_no, e);
+            return WebhookOutcome::failed(
+                source,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
+            );
+        }
+    };
 
-            if data.to_address.to_lowercase() != expected_address.to_lowercase() {
-                tracing::warn!(
-                    "USDT webhook: to_address mismatch. Expected: {}, Got: {}",
-                    expected_address,
-                    data.to_address
-                );
-                res.success(serde_json::json!({ "status": "ignored" }));
-                return;
-            }
+    if state.wechatpay_service.to_fen(order.amount) != total {
+        tracing::error!(
+            "WeChat Pay amount mismatch for order {}: notify total={}",
+            order.id,
+            total
+        );
+        return WebhookOutcome::ignored(source, "Amount mismatch");
+    }
 
-            tracing::info!(
-                "USDT deposit confirmed: {} {} amount {}",
-                data.network,
-                data.tx_hash,
-                data.amount
+    match confirm_notify_payment(state, &order, "wechatpay", transaction_id).await {
+        Ok(()) => WebhookOutcome::processed(source, format!("Order {} paid", order.id)),
+        Err(e) => {
+            tracing::error!("Failed to confirm WeChat Pay order {}: {}", order.id, e);
+            WebhookOutcome::failed(
+                source,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to update order",
+            )
+        }
+    }
+}
+
+// ==================== 支付确认 ====================
+
+/// 第三方支付异步通知确认订单已支付
+///
+/// 幂等：订单已是 paid/completed 时直接返回成功，便于支付网关重复投递。
+async fn confirm_notify_payment(
+    state: &AppState,
+    order: &Order,
+    payment_method: &str,
+    provider_tx_id: &str,
+) -> Result<(), RswsError> {
+    if order.status == "paid" || order.status == "completed" {
+        return Ok(());
+    }
+    if order.status != "pending" {
+        return Err(RswsError::business(ErrorCode::ORDER_STATUS_INVALID));
+    }
+
+    if !state.ledger_service.confirm_order_payment(order.id).await? {
+        // 并发通知已确认或订单刚被取消，不再重复处理
+        tracing::warn!(
+            "Order {} is no longer pending, {} notification {} not applied",
+            order.id,
+            payment_method,
+            provider_tx_id
+        );
+        return Ok(());
+    }
+
+    let transactions = state.payment_service.get_by_order(order.id).await?;
+    if let Some(tx) = transactions
+        .iter()
+        .find(|t| t.payment_method == payment_method && t.status == "pending")
+    {
+        state
+            .payment_service
+            .update_status(tx.id, "completed", Some(provider_tx_id))
+            .await?;
+    }
+
+    tracing::info!(
+        "Order {} paid via {}. TX: {}",
+        order.id,
+        payment_method,
+        provider_tx_id
+    );
+    spawn_order_paid_tasks(state, order.id);
+    Ok(())
+}
+
+/// 第三方支付异步通知确认余额充值到账（幂等）
+async fn confirm_notify_topup(
+    state: &AppState,
+    topup: &WalletTopup,
+    payment_method: &str,
+    provider_tx_id: &str,
+) -> Result<(), RswsError> {
+    if topup.payment_method != payment_method {
+        return Err(RswsError::business(ErrorCode::PAYMENT_METHOD_NOT_SUPPORTED));
+    }
+    state
+        .ledger_service
+        .complete_topup(
+            topup.id,
+            Some(provider_tx_id),
+            gateway_fee(state, payment_method, topup.amount),
+        )
+        .await?;
+    Ok(())
+}
+
+/// 按通知中的商户订单号查找余额充值单
+async fn load_topup(
+    state: &AppState,
+    out_trade_no: &str,
+) -> Result<Option<WalletTopup>, RswsError> {
+    match out_trade_no.parse::<i64>() {
+        Ok(id) => state.ledger_service.get_topup(id).await,
+        Err(_) => Ok(None),
+    }
+}
+
+/// 余额充值的通道手续费（订单手续费在下单时已报价，见 orders.fee_amount）
+fn gateway_fee(state: &AppState, payment_method: &str, amount: Decimal) -> Decimal {
+    match payment_method {
+        "paypal" => state.paypal_service.fee_for(amount),
+        "alipay" => state.alipay_service.fee_for(amount),
+        "wechatpay" => state.wechatpay_service.fee_for(amount),
+        _ => Decimal::ZERO,
+    }
+}
+
+/// PayPal 付款确认订单已支付（付款回调与风控审核放行共用）
+///
+/// 订单已不是待支付状态时不入账，返回 false；`captured`（款项已扣）且订单已取消 / 过期 /
+/// 退款时交易标记为待退款。
+async fn confirm_paypal_order(
+    state: &AppState,
+    tx: &PaymentTransaction,
+    paypal_order_id: &str,
+    captured: bool,
+) -> Result<bool, RswsError> {
+    let order_id = tx.order_id;
+    if !state.ledger_service.confirm_order_payment(order_id).await? {
+        let settled = state
+            .order_service
+            .get(order_id)
+            .await?
+            .is_some_and(|o| o.status == "paid" || o.status == "completed");
+        if captured && !settled {
+            state
+                .payment_service
+                .update_status(tx.id, PAYPAL_TX_REFUND_PENDING, Some(paypal_order_id))
+                .await?;
+            tracing::warn!(
+                "PayPal capture {} for order {} which is no longer pending, refund required",
+                paypal_order_id,
+                order_id
             );
+        }
+        return Ok(false);
+    }
+    if let Err(e) = state
+        .payment_service
+        .update_status(tx.id, "completed", Some(paypal_order_id))
+        .await
+    {
+        tracing::error!("Failed to update transaction {}: {}", tx.id, e);
+    }
+    tracing::info!(
+        "Order {} paid via PayPal. TX: {}",
+        order_id,
+        paypal_order_id
+    );
+    spawn_order_paid_tasks(state, order_id);
+    Ok(true)
+}
 
-            // 真实确认由 processor.rs 链上监听完成，这里只记录
-            res.success(serde_json::json!({ "status": "received", "tx_hash": data.tx_hash }));
+/// PayPal 付款风控：按付款人国家再评估一次
+///
+/// 返回 Some 时不再入账：订单待审核或被拦截。待审核的付款在管理员放行时
+/// 由 `release_held_payment` 入账；被拦截的订单已取消，PayPal 订单不再扣款。
+/// 已扣款（`captured`）的付款不会被拦截，只转人工审核，交易记为
+/// [`PAYPAL_TX_CAPTURED_HELD`]，审核拒绝时标记待退款。
+async fn screen_paypal_payment(
+    state: &AppState,
+    source: WebhookSource,
+    tx: &PaymentTransaction,
+    paypal_order_id: &str,
+    resource: &Value,
+    captured: bool,
+) -> Option<WebhookOutcome> {
+    let order = match state.order_service.get(tx.order_id).await {
+        Ok(Some(order)) if order.status == "pending" => order,
+        _ => return None,
+    };
+    match order.risk_status.as_str() {
+        RISK_STATUS_APPROVED => return None,
+        RISK_STATUS_REVIEW => {
+            // 审核期间完成扣款：标记已扣款，审核拒绝时据此退款
+            if captured {
+                if let Err(e) = state
+                    .payment_service
+                    .update_status(tx.id, PAYPAL_TX_CAPTURED_HELD, Some(paypal_order_id))
+                    .await
+                {
+                    tracing::error!("Failed to update transaction {}: {}", tx.id, e);
+                }
+            }
+            return Some(WebhookOutcome::ignored(
+                source,
+                format!("Order {} held for risk review", order.id),
+            ));
         }
+        RISK_STATUS_REJECTED => {
+            return Some(WebhookOutcome::ignored(
+                source,
+                format!("Order {} blocked by risk control", order.id),
+            ))
+        }
+        _ => {}
+    }
+
+    let ctx = RiskContext {
+        payer_country: resource["payer"]["address"]["country_code"]
+            .as_str()
+            .map(|s| s.to_string()),
+        ..Default::default()
+    };
+    let assessed = if captured {
+        state
+            .risk_service
+            .assess_captured_payment(&order, &ctx)
+            .await
+    } else {
+        state
+            .risk_service
+            .assess(&order, RISK_STAGE_PAYMENT, &ctx)
+            .await
+    };
+    let outcome = match assessed {
+        Ok(outcome) => outcome,
         Err(e) => {
-            tracing::error!("USDT webhook parse error: {}", e);
-            res.http_error(StatusCode::BAD_REQUEST, "Invalid payload");
+            tracing::error!("Risk assessment failed for order {}: {}", order.id, e);
+            return None;
         }
+    };
+
+    if outcome.is_held() {
+        // 记录 PayPal 订单号，审核放行时据此入账
+        let status = if captured {
+            PAYPAL_TX_CAPTURED_HELD
+        } else {
+            "pending"
+        };
+        if let Err(e) = state
+            .payment_service
+            .update_status(tx.id, status, Some(paypal_order_id))
+            .await
+        {
+            tracing::error!("Failed to update transaction {}: {}", tx.id, e);
+        }
+        Some(WebhookOutcome::processed(
+            source,
+            format!("Order {} held for risk review", order.id),
+        ))
+    } else if outcome.is_blocked() {
+        let _ = state
+            .payment_service
+            .update_status(tx.id, "failed", Some(paypal_order_id))
+            .await;
+        Some(WebhookOutcome::processed(
+            source,
+            format!("Order {} blocked by risk control", order.id),
+        ))
+    } else {
+        None
     }
 }
+
+/// 已扣款但待风控审核的 PayPal 交易状态
+pub(crate) const PAYPAL_TX_CAPTURED_HELD: &str = "captured_held";
+
+/// 审核拒绝后待财务退款的 PayPal 交易状态
+pub(crate) const PAYPAL_TX_REFUND_PENDING: &str = "refund_pending";
+
+/// 付款回调因待审核未入账的 PayPal 交易（记有 PayPal 订单号）
+async fn held_paypal_payment(
+    state: &AppState,
+    order_id: i64,
+) -> Result<Option<PaymentTransaction>, RswsError> {
+    Ok(state
+        .payment_service
+        .get_by_order(order_id)
+        .await?
+        .into_iter()
+        .find(|tx| {
+            tx.payment_method == "paypal"
+                && (tx.status == "pending" || tx.status == PAYPAL_TX_CAPTURED_HELD)
+                && tx.provider_transaction_id.is_some()
+        }))
+}
+
+/// 风控审核放行后为已付款的订单入账
+///
+/// 付款回调因待审核未入账的订单，其 PayPal 交易保持 pending（已扣款时为
+/// captured_held）并记有 PayPal 订单号；USDT 链上收款记为 held。
+/// 返回是否完成入账（尚未付款的订单返回 false，由用户继续支付）。
+pub(crate) async fn release_held_payment(
+    state: &AppState,
+    order_id: i64,
+) -> Result<bool, RswsError> {
+    if let Some(tx) = held_paypal_payment(state, order_id).await? {
+        let paypal_order_id = tx.provider_transaction_id.clone().unwrap_or_default();
+        let captured = tx.status == PAYPAL_TX_CAPTURED_HELD;
+        return confirm_paypal_order(state, &tx, &paypal_order_id, captured).await;
+    }
+    if !state
+        .ledger_service
+        .release_held_usdt_payment(order_id)
+        .await?
+    {
+        return Ok(false);
+    }
+    spawn_order_paid_tasks(state, order_id);
+    Ok(true)
+}
+
+/// 风控审核拒绝后处理已到账的付款
+///
+/// 订单已取消，款项须退回：PayPal 交易与 USDT 链上收款标记为 refund_pending
+/// 供财务退款。返回是否需要退款。
+pub(crate) async fn flag_rejected_payment(
+    state: &AppState,
+    order_id: i64,
+) -> Result<bool, RswsError> {
+    if state.ledger_service.flag_held_usdt_refund(order_id).await? {
+        tracing::warn!(
+            "Order {} rejected after USDT payment, refund required",
+            order_id
+        );
+        return Ok(true);
+    }
+    let captured = held_paypal_payment(state, order_id)
+        .await?
+        .filter(|tx| tx.status == PAYPAL_TX_CAPTURED_HELD);
+    let Some(tx) = captured else {
+        return Ok(false);
+    };
+    state
+        .payment_service
+        .update_status(
+            tx.id,
+            PAYPAL_TX_REFUND_PENDING,
+            tx.provider_transaction_id.as_deref(),
+        )
+        .await?;
+    tracing::warn!(
+        "Order {} rejected after PayPal capture, refund required. TX: {}",
+        order_id,
+        tx.provider_transaction_id.as_deref().unwrap_or("")
+    );
+    Ok(true)
+}
+
+/// 订单支付后的异步任务，不阻塞回调响应
+///
+/// - 激活会员订阅（会员订单）
+/// - 开具发票并发送付款成功邮件
+/// - 推送 order.paid 事件给集成方（同一订单只入队一次）
+///
+/// 会员激活与开票失败时由 MembershipService / InvoiceService 后台任务补做。
+/// 创作者分成与推荐佣金不在这里记录，已随订单收款在同一事务内写入。
+pub(crate) fn spawn_order_paid_tasks(state: &AppState, order_id: i64) {
+    let membership_service = state.membership_service.clone();
+    let invoice_service = state.invoice_service.clone();
+    let cross_platform_service = state.cross_platform_service.clone();
+    tokio::spawn(async move {
+        if let Err(e) = membership_service.activate_for_order(order_id).await {
+            tracing::warn!(
+                "Failed to activate membership for order {}: {}",
+                order_id,
+                e
+            );
+        }
+        if let Err(e) = invoice_service.issue_and_notify(order_id).await {
+            tracing::warn!("Failed to issue invoice for order {}: {}", order_id, e);
+        }
+        if let Err(e) = cross_platform_service
+            .publish_order_event(EVENT_ORDER_PAID, order_id)
+            .await
+        {
+            tracing::warn!("Failed to publish order.paid for order {}: {}", order_id, e);
+        }
+    });
+}
+
+/// 订单退款后推送 order.refunded 事件，不阻塞响应
+///
+/// 佣金作废与冲销凭证已随退款在同一事务内完成。
+pub(crate) fn spawn_order_refunded_tasks(state: &AppState, order_id: i64) {
+    let cross_platform_service = state.cross_platform_service.clone();
+    tokio::spawn(async move {
+        if let Err(e) = cross_platform_service
+            .publish_order_event(EVENT_ORDER_REFUNDED, order_id)
+            .await
+        {
+            tracing::warn!(
+                "Failed to publish order.refunded for order {}: {}",
+                order_id,
+                e
+            );
+        }
+    });
+}
@@ -0,0 +1,306 @@
+//! 用户端余额处理器
+//!
+//! 预付余额查询、流水与充值。余额可在订单支付时选择 `balance` 方式即时扣款。
+
+use crate::state::get_state;
+use num_traits::cast::ToPrimitive;
+use rsws_common::{error_code::ErrorCode, AuthHandler, ResponseExt, RswsError};
+use rsws_model::ledger::CreateTopupRequest;
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+
+/// 获取当前用户余额
+#[endpoint(
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 401, description = "未认证"),
+    )
+)]
+pub async fn get_balance(_req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let state = get_state(depot);
+
+    match state.ledger_service.balance(user_id).await {
+        Ok(balance) => res.success(serde_json::json!({ "balance": balance })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 获取余额流水
+#[endpoint(
+    parameters(
+        ("page", Query, description = "页码"),
+        ("page_size", Query, description = "每页数量"),
+    ),
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 401, description = "未认证"),
+    )
+)]
+pub async fn list_balance_entries(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let page: i64 = req.query("page").unwrap_or(1).max(1);
+    let page_size: i64 = req.query("page_size").unwrap_or(20).clamp(1, 100);
+
+    let state = get_state(depot);
+
+    match state
+        .ledger_service
+        .statement(user_id, page, page_size)
+        .await
+    {
+        Ok((items, total)) => res.success(serde_json::json!({
+            "items": items,
+            "total": total,
+            "page": page,
+            "page_size": page_size,
+        })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 获取充值记录
+#[endpoint(
+    parameters(
+        ("page", Query, description = "页码"),
+        ("page_size", Query, description = "每页数量"),
+    ),
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 401, description = "未认证"),
+    )
+)]
+pub async fn list_topups(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let page: i64 = req.query("page").unwrap_or(1).max(1);
+    let page_size: i64 = req.query("page_size").unwrap_or(20).clamp(1, 100);
+
+    let state = get_state(depot);
+
+    match state
+        .ledger_service
+        .list_topups(user_id, page, page_size)
+        .await
+    {
+        Ok((items, total)) => res.success(serde_json::json!({
+            "items": items,
+            "total": total,
+            "page": page,
+            "page_size": page_size,
+        })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 创建余额充值
+///
+/// 创建充值单后按支付方式返回支付信息，网关回调 / 链上到账后自动入账。
+#[endpoint(
+    request_body = CreateTopupRequest,
+    responses(
+        (status_code = 201, description = "创建成功"),
+        (status_code = 400, description = "金额或支付方式无效"),
+        (status_code = 401, description = "未认证"),
+    )
+)]
+pub async fn create_topup(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let data = match req.parse_json::<CreateTopupRequest>().await {
+        Ok(d) => d,
+        Err(e) => {
+            res.error_msg(
+                RswsError::from(ErrorCode::INVALID_REQUEST_FORMAT),
+                format!("Invalid request: {}", e),
+            );
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+    let payment_method = data.payment_method.to_lowercase();
+
+    // USDT 充值记录收款地址，链上到账时按地址与金额匹配
+    let usdt_receiver = match payment_method.as_str() {
+        "usdt_trc20" => Some(("tron", state.blockchain_service.get_trc20_address().await)),
+        "usdt_erc20" => Some((
+            "ethereum",
+            state.blockchain_service.get_erc20_address().await,
+        )),
+        _ => None,
+    };
+    if usdt_receiver
+        .as_ref()
+        .is_some_and(|(_, address)| address.is_empty())
+    {
+        res.error_msg(
+            RswsError::from(ErrorCode::PAYMENT_METHOD_NOT_SUPPORTED),
+            "USDT receiving address is not configured",
+        );
+        return;
+    }
+
+    let topup = match state
+        .ledger_service
+        .create_topup(
+            user_id,
+            data.amount,
+            &payment_method,
+            usdt_receiver.as_ref().map(|(_, address)| address.as_str()),
+        )
+        .await
+    {
+        Ok(t) => t,
+        Err(e) => {
+            res.error(e);
+            return;
+        }
+    };
+
+    let subject = format!("Balance topup #{}", topup.id);
+
+    match payment_method.as_str() {
+        "paypal" => {
+            match state
+                .paypal_service
+                .create_order(
+                    topup.amount.to_f64().unwrap_or(0.0),
+                    "USD",
+                    &subject,
+                    topup.id,
+                )
+                .await
+            {
+                Ok(paypal_order) => {
+                    let paypal_order_id = paypal_order["id"].as_str().unwrap_or("").to_string();
+                    let approve_url = paypal_order["links"]
+                        .as_array()
+                        .and_then(|links| links.iter().find(|l| l["rel"] == "approve"))
+                        .and_then(|l| l["href"].as_str())
+                        .map(|s| s.to_string());
+
+                    if let Err(e) = state
+                        .ledger_service
+                        .set_topup_provider_tx(topup.id, &paypal_order_id)
+                        .await
+                    {
+                        res.error(e);
+                        return;
+                    }
+
+                    res.status_code(StatusCode::CREATED);
+                    res.success(serde_json::json!({
+                        "topup_id": topup.id,
+                        "amount": topup.amount,
+                        "payment_method": "paypal",
+                        "paypal_order_id": paypal_order_id,
+                        "approve_url": approve_url,
+                    }));
+                }
+                Err(e) => {
+                    tracing::error!("Failed to create PayPal order for topup: {}", e);
+                    res.error_msg(
+                        RswsError::from(ErrorCode::INTERNAL_ERROR),
+                        "PayPal service unavailable, please try another payment method",
+                    );
+                }
+            }
+        }
+        "alipay" => {
+            let mode = data.mode.unwrap_or_else(|| "page".to_string());
+            let result = if mode == "qr" {
+                state
+                    .alipay_service
+                    .precreate(topup.id, topup.amount, &subject)
+                    .await
+            } else {
+                state
+                    .alipay_service
+                    .page_pay_url(topup.id, topup.amount, &subject)
+            };
+
+            match result {
+                Ok(url) => {
+                    res.status_code(StatusCode::CREATED);
+                    if mode == "qr" {
+                        res.success(serde_json::json!({
+                            "topup_id": topup.id,
+                            "amount": topup.amount,
+                            "payment_method": "alipay",
+                            "mode": "qr",
+                            "qr_code": url,
+                        }));
+                    } else {
+                        res.success(serde_json::json!({
+                            "topup_id": topup.id,
+                            "amount": topup.amount,
+                            "payment_method": "alipay",
+                            "mode": "page",
+                            "pay_url": url,
+                        }));
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Failed to create Alipay payment for topup: {}", e);
+                    res.error_msg(
+                        RswsError::from(ErrorCode::ALIPAY_REQUEST_FAILED),
+                        "Alipay service unavailable, please try another payment method",
+                    );
+                }
+            }
+        }
+        "wechatpay" => {
+            match state
+                .wechatpay_service
+                .native_order(topup.id, topup.amount, &subject)
+                .await
+            {
+                Ok(code_url) => {
+                    res.status_code(StatusCode::CREATED);
+                    res.success(serde_json::json!({
+                        "topup_id": topup.id,
+                        "amount": topup.amount,
+                        "payment_method": "wechatpay",
+                        "code_url": code_url,
+                    }));
+                }
+                Err(e) => {
+                    tracing::error!("Failed to create WeChat Pay order for topup: {}", e);
+                    res.error_msg(
+                        RswsError::from(ErrorCode::WECHATPAY_REQUEST_FAILED),
+                        "WeChat Pay service unavailable, please try another payment method",
+                    );
+                }
+            }
+        }
+        _ => {
+            // usdt_trc20 / usdt_erc20：链上到账后按收款地址与金额匹配充值单
+            let (network, address) = usdt_receiver.unwrap_or_default();
+
+            res.status_code(StatusCode::CREATED);
+            res.success(serde_json::json!({
+                "topup_id": topup.id,
+                "amount": topup.amount,
+                "payment_method": payment_method,
+                "network": network,
+                "address": address,
+                "expired_at": topup.expired_at,
+            }));
+        }
+    }
+}
@@ -2,9 +2,12 @@
 
 use crate::state::get_state;
 use rsws_common::{ResponseExt, RswsError};
-use rsws_db::category::CategoryRepository;
+use rsws_db::category::{CategoryRepository, CATEGORY_TREE_CACHE_KEY, CATEGORY_TREE_CACHE_TTL};
+use salvo::http::StatusCode;
 use salvo::prelude::*;
 use salvo_oapi::endpoint;
+use std::collections::HashMap;
+use tracing::warn;
 
 /// Get category list (浠呮椿璺冨垎绫?
 #[endpoint(
@@ -13,17 +16,106 @@ use salvo_oapi::endpoint;
     )
 )]
 pub async fn list_categories(_req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let state = get_state(depot);
+    let redis = state.config_service.redis_client();
+
+    // 分类树缓存（分类变更时由管理端删除，过期后重建以刷新资源数）
+    match redis
+        .get_json::<serde_json::Value>(CATEGORY_TREE_CACHE_KEY)
+        .await
+    {
+        Ok(Some(cached)) => {
+            res.success(cached);
+            return;
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Failed to read category tree cache: {}", e),
+    }
+
+    let repo = CategoryRepository::new(state.pool());
+    let categories = match repo.find_all().await {
+        Ok(categories) => categories,
+        Err(e) => {
+            res.error(RswsError::Database(e));
+            return;
+        }
+    };
+    let counts: HashMap<i64, i64> = match repo.resource_counts(true).await {
+        Ok(counts) => counts
+            .into_iter()
+            .map(|c| (c.category_id, c.total_count))
+            .collect(),
+        Err(e) => {
+            res.error(RswsError::Database(e));
+            return;
+        }
+    };
+
+    let tree = CategoryRepository::build_tree(&categories, &counts);
+    let data = serde_json::json!({
+        "categories": categories,
+        "tree": tree
+    });
+    if let Err(e) = redis
+        .set_json(CATEGORY_TREE_CACHE_KEY, &data, CATEGORY_TREE_CACHE_TTL)
+        .await
+    {
+        warn!("Failed to cache category tree: {}", e);
+    }
+    res.success(data)
+}
+
+/// 分类面包屑（从根分类到该分类）
+#[endpoint(
+    responses(
+        (status_code = 200, description = "Breadcrumb"),
+        (status_code = 404, description = "Category not found"),
+    )
+)]
+pub async fn category_breadcrumb(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let id: i64 = req.param("id").unwrap_or(0);
     let state = get_state(depot);
     let repo = CategoryRepository::new(state.pool());
 
-    match repo.find_all().await {
-        Ok(categories) => {
-            let tree = CategoryRepository::build_tree(&categories);
-            res.success(serde_json::json!({
-                "categories": categories,
-                "tree": tree
-            }))
+    match repo.ancestors(id).await {
+        // 分类或任一祖先已停用时前台不可见
+        Ok(categories)
+            if categories.last().is_some_and(|c| c.id == id)
+                && categories.iter().all(|c| c.is_active) =>
+        {
+            res.success(serde_json::json!({ "breadcrumb": categories }))
         }
+        Ok(_) => res.http_error(StatusCode::NOT_FOUND, "分类不存在"),
+        Err(e) => res.error(RswsError::Database(e)),
+    }
+}
+
+/// 子分类（含全部层级，不含已停用的分类）
+#[endpoint(
+    responses(
+        (status_code = 200, description = "Descendant categories"),
+        (status_code = 404, description = "Category not found"),
+    )
+)]
+pub async fn category_descendants(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let id: i64 = req.param("id").unwrap_or(0);
+    let state = get_state(depot);
+    let repo = CategoryRepository::new(state.pool());
+
+    match repo.find_by_id(id).await {
+        Ok(Some(category)) if category.is_active => {}
+        Ok(_) => {
+            res.http_error(StatusCode::NOT_FOUND, "分类不存在");
+            return;
+        }
+        Err(e) => {
+            res.error(RswsError::Database(e));
+            return;
+        }
+    }
+
+    match repo.descendants(id, true).await {
+        Ok(categories) => res.success(serde_json::json!({ "categories": categories })),
         Err(e) => res.error(RswsError::Database(e)),
     }
 }
@@ -0,0 +1,135 @@
+//! 用户端创作者收益处理器
+//!
+//! 发布资源的用户查看销售统计、分成结算状态与月度对账单。
+
+use crate::state::get_state;
+use rsws_common::{AuthHandler, ResponseExt, RswsError};
+use rsws_service::creator_service::statement_to_csv;
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+
+/// 获取收益概览（销售额、平台佣金、净收入、待结算 / 已结算分成）
+#[endpoint(
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 401, description = "未认证"),
+    )
+)]
+pub async fn get_creator_earnings(_req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let state = get_state(depot);
+
+    match state.creator_service.summary(user_id).await {
+        Ok(summary) => res.success(summary),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 获取按资源统计的销售
+#[endpoint(
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 401, description = "未认证"),
+    )
+)]
+pub async fn list_creator_resource_sales(
+    _req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let state = get_state(depot);
+
+    match state.creator_service.resource_sales(user_id).await {
+        Ok(items) => res.success(serde_json::json!({ "items": items })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 获取月度对账单列表（每月汇总）
+#[endpoint(
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 401, description = "未认证"),
+    )
+)]
+pub async fn list_creator_statements(_req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let state = get_state(depot);
+
+    match state.creator_service.statement_months(user_id).await {
+        Ok(items) => res.success(serde_json::json!({ "items": items })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 获取月度对账单
+///
+/// `format=json`（默认）返回明细与合计；`format=csv` 返回 CSV 附件。
+#[endpoint(
+    parameters(
+        ("month", Path, description = "对账月份，YYYY-MM"),
+        ("format", Query, description = "导出格式：json / csv"),
+    ),
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 400, description = "月份格式错误"),
+        (status_code = 401, description = "未认证"),
+    )
+)]
+pub async fn get_creator_statement(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let month: String = match req.param("month") {
+        Some(m) => m,
+        None => {
+            res.error(RswsError::bad_request("Missing month parameter"));
+            return;
+        }
+    };
+    let format: String = req.query("format").unwrap_or_else(|| "json".to_string());
+
+    let state = get_state(depot);
+
+    let statement = match state.creator_service.statement(user_id, &month).await {
+        Ok(s) => s,
+        Err(e) => {
+            res.error(e);
+            return;
+        }
+    };
+
+    match format.as_str() {
+        "json" => res.success(statement),
+        "csv" => {
+            res.add_header("Content-Type", "text/csv; charset=utf-8", true)
+                .ok();
+            res.add_header(
+                "Content-Disposition",
+                format!("attachment; filename=\"statement-{}.csv\"", statement.month),
+                true,
+            )
+            .ok();
+            res.write_body(statement_to_csv(&statement)).ok();
+        }
+        other => res.error(RswsError::bad_request(format!(
+            "Unsupported export format: {}",
+            other
+        ))),
+    }
+}
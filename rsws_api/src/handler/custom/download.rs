@@ -0,0 +1,62 @@
+//! 资源文件下载处理器
+//!
+//! 凭下载接口签发的短期令牌下载文件：S3 存储重定向到预签名 URL；
+//! 本地存储流式输出，支持 Range / If-Range 断点续传。
+
+use crate::state::get_state;
+use rsws_common::{error_code::ErrorCode, AuthHandler, ResponseExt, RswsError};
+use rsws_service::download_service::DownloadTarget;
+use salvo::fs::NamedFile;
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+
+/// 凭令牌下载资源文件
+#[endpoint(
+    responses(
+        (status_code = 200, description = "文件内容"),
+        (status_code = 206, description = "部分内容（Range 请求）"),
+        (status_code = 302, description = "重定向到预签名地址"),
+        (status_code = 400, description = "下载链接无效或已过期"),
+        (status_code = 401, description = "未认证"),
+    )
+)]
+pub async fn download_file(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let token: String = match req.param("token") {
+        Some(token) => token,
+        None => {
+            res.error_msg(
+                RswsError::from(ErrorCode::INVALID_PARAMETER),
+                "Missing download token",
+            );
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    let stored = match state.download_service.resolve(&token, user_id).await {
+        Ok(stored) => stored,
+        Err(e) => {
+            res.error(e);
+            return;
+        }
+    };
+
+    match state.download_service.target(&stored).await {
+        Ok(DownloadTarget::Local(path)) => {
+            NamedFile::builder(path)
+                .attached_name(stored.file_name)
+                .send(req.headers(), res)
+                .await;
+        }
+        Ok(DownloadTarget::Redirect(url)) => {
+            res.render(Redirect::found(url));
+        }
+        Err(e) => res.error(e),
+    }
+}
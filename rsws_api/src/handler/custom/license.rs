@@ -0,0 +1,145 @@
+//! 资源授权档位处理器（创作者）
+//!
+//! 创作者为自己的资源设置 personal / commercial / extended 档位。
+//! 档位列表随资源详情返回，下单时通过 `license_id` 选择档位。
+
+use crate::state::get_state;
+use rsws_common::{error_code::ErrorCode, AuthHandler, ResponseExt, RswsError};
+use rsws_model::license::{CreateResourceLicenseRequest, UpdateResourceLicenseRequest};
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+
+/// 获取资源授权档位（含已下架，仅资源创作者）
+#[endpoint(
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 403, description = "无权限"),
+    )
+)]
+pub async fn list_resource_licenses(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let resource_id: i64 = req.param("id").unwrap_or(0);
+    if resource_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid resource ID",
+        );
+        return;
+    }
+
+    let state = get_state(depot);
+
+    match state
+        .license_service
+        .list_for_owner(resource_id, Some(user_id))
+        .await
+    {
+        Ok(licenses) => res.success(licenses),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 创建资源授权档位
+#[endpoint(
+    request_body = CreateResourceLicenseRequest,
+    responses(
+        (status_code = 201, description = "创建成功"),
+        (status_code = 400, description = "档位无效或价格不递增"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 403, description = "无权限"),
+    )
+)]
+pub async fn create_resource_license(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let resource_id: i64 = req.param("id").unwrap_or(0);
+    if resource_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid resource ID",
+        );
+        return;
+    }
+
+    let data = match req.parse_json::<CreateResourceLicenseRequest>().await {
+        Ok(d) => d,
+        Err(e) => {
+            res.error_msg(
+                RswsError::from(ErrorCode::INVALID_REQUEST_FORMAT),
+                format!("Invalid request: {}", e),
+            );
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state
+        .license_service
+        .create(resource_id, Some(user_id), &data)
+        .await
+    {
+        Ok(license) => {
+            res.status_code(StatusCode::CREATED);
+            res.success(license);
+        }
+        Err(e) => res.error(e),
+    }
+}
+
+/// 更新资源授权档位（下架请设置 `is_active = false`）
+#[endpoint(
+    request_body = UpdateResourceLicenseRequest,
+    responses(
+        (status_code = 200, description = "更新成功"),
+        (status_code = 400, description = "价格不递增"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 403, description = "无权限"),
+    )
+)]
+pub async fn update_resource_license(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let resource_id: i64 = req.param("id").unwrap_or(0);
+    let license_id: i64 = req.param("license_id").unwrap_or(0);
+    if resource_id <= 0 || license_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid resource or license ID",
+        );
+        return;
+    }
+
+    let data = match req.parse_json::<UpdateResourceLicenseRequest>().await {
+        Ok(d) => d,
+        Err(e) => {
+            res.error_msg(
+                RswsError::from(ErrorCode::INVALID_REQUEST_FORMAT),
+                format!("Invalid request: {}", e),
+            );
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state
+        .license_service
+        .update(resource_id, license_id, Some(user_id), &data)
+        .await
+    {
+        Ok(license) => res.success(license),
+        Err(e) => res.error(e),
+    }
+}
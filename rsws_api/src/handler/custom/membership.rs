@@ -0,0 +1,109 @@
+//! 用户端会员处理器
+//!
+//! 订阅 / 续费会员会创建一笔会员订单，之后与资源订单一样通过
+//! `POST /order/{id}/pay` 发起支付，支付完成后自动激活。
+
+use crate::handler::custom::request_referral_code;
+use crate::state::get_state;
+use rsws_common::{error_code::ErrorCode, AuthHandler, ResponseExt, RswsError};
+use rsws_model::membership::SubscribeMembershipRequest;
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+
+/// 获取可购买的会员套餐
+#[endpoint(
+    responses(
+        (status_code = 200, description = "成功"),
+    )
+)]
+pub async fn list_membership_plans(_req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let state = get_state(depot);
+
+    match state.membership_service.list_plans(true).await {
+        Ok(plans) => res.success(plans),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 获取当前用户的会员订阅
+#[endpoint(
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 401, description = "未认证"),
+    )
+)]
+pub async fn list_my_memberships(_req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let state = get_state(depot);
+
+    match state.membership_service.list_by_user(user_id).await {
+        Ok(items) => res.success(serde_json::json!({ "items": items })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 订阅 / 续费会员
+///
+/// 创建待支付的会员订单；已有有效订阅时，新一期从当前有效期结束时开始。
+#[endpoint(
+    request_body = SubscribeMembershipRequest,
+    responses(
+        (status_code = 201, description = "会员订单已创建"),
+        (status_code = 400, description = "套餐不可用或支付方式不支持"),
+        (status_code = 401, description = "未认证"),
+    )
+)]
+pub async fn subscribe_membership(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let data = match req.parse_json::<SubscribeMembershipRequest>().await {
+        Ok(d) => d,
+        Err(e) => {
+            res.error_msg(
+                RswsError::from(ErrorCode::INVALID_REQUEST_FORMAT),
+                format!("Invalid request: {}", e),
+            );
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state
+        .membership_service
+        .subscribe(user_id, data.plan_id, &data.payment_method.to_lowercase())
+        .await
+    {
+        Ok((order, membership)) => {
+            // 推荐归因：取 `ref` 查询参数或推荐码 Cookie，否则沿用注册时的推荐人
+            let referral_code = request_referral_code(req, None);
+            if let Err(e) = state
+                .referral_service
+                .attribute_order(order.id, user_id, referral_code.as_deref())
+                .await
+            {
+                tracing::warn!("Failed to attribute order {}: {}", order.id, e);
+            }
+
+            res.status_code(StatusCode::CREATED);
+            res.success(serde_json::json!({
+                "order_id": order.id,
+                "membership_id": membership.id,
+                "plan_id": membership.plan_id,
+                "amount": order.amount,
+                "surcharge": order.surcharge,
+                "payment_method": order.payment_method,
+                "status": order.status,
+                "expired_at": order.expired_at,
+            }));
+        }
+        Err(e) => res.error(e),
+    }
+}
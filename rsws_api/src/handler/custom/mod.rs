@@ -3,14 +3,52 @@
 //! 用户端 handler 按功能域拆分为子模块，统一在此 re-export，
 //! 使 `handler::custom::*` 路由引用路径保持不变。
 
+mod balance;
 mod category;
+mod creator;
+mod download;
+mod license;
+mod membership;
 mod order;
+mod rating;
+mod recommendation;
+mod referral;
 mod resource;
+mod resource_version;
+mod tag;
 mod user;
+mod wishlist;
+
+// balance.rs
+pub use balance::create_topup;
+pub use balance::get_balance;
+pub use balance::list_balance_entries;
+pub use balance::list_topups;
 
 // category.rs
+pub use category::category_breadcrumb;
+pub use category::category_descendants;
 pub use category::list_categories;
 
+// creator.rs
+pub use creator::get_creator_earnings;
+pub use creator::get_creator_statement;
+pub use creator::list_creator_resource_sales;
+pub use creator::list_creator_statements;
+
+// download.rs
+pub use download::download_file;
+
+// license.rs
+pub use license::create_resource_license;
+pub use license::list_resource_licenses;
+pub use license::update_resource_license;
+
+// membership.rs
+pub use membership::list_membership_plans;
+pub use membership::list_my_memberships;
+pub use membership::subscribe_membership;
+
 // order.rs
 pub use order::cancel_order;
 pub use order::check_order_status;
@@ -18,18 +56,50 @@ pub use order::check_purchase;
 pub use order::complete_order;
 pub use order::create_order;
 pub use order::get_order;
+pub use order::get_order_receipt;
 pub use order::get_resource_download;
 pub use order::initiate_payment;
 pub use order::list_orders;
+pub use order::quote_order;
 pub use order::refund_order;
 
+// rating.rs
+pub use rating::list_resource_ratings;
+pub use rating::reply_rating;
+pub use rating::report_rating;
+pub use rating::submit_resource_rating;
+
+// recommendation.rs
+pub use recommendation::get_resource_recommendations;
+pub use recommendation::list_popular_resources;
+
+// referral.rs
+pub use referral::get_referral_dashboard;
+pub use referral::list_referral_earnings;
+pub use referral::record_referral_click;
+pub(crate) use referral::request_referral_code;
+
 // resource.rs
 pub use resource::create_resource;
 pub use resource::delete_resource;
+pub use resource::get_preview_policy;
 pub use resource::get_resource;
+pub use resource::list_my_resources;
 pub use resource::list_resources;
+pub use resource::set_preview_policy;
+pub use resource::submit_resource;
 pub use resource::update_resource;
 
+// resource_version.rs
+pub use resource_version::get_resource_version_download;
+pub use resource_version::list_resource_versions;
+pub use resource_version::publish_resource_version;
+pub use resource_version::set_resource_update_policy;
+
+// tag.rs
+pub use tag::list_popular_tags;
+pub use tag::set_resource_tags;
+
 // user.rs
 pub use user::change_password;
 pub use user::get_current_user;
@@ -39,3 +109,16 @@ pub use user::register;
 pub use user::send_code;
 pub use user::update_profile;
 pub use user::upload_avatar;
+
+// wishlist.rs
+pub use wishlist::add_favorite;
+pub use wishlist::add_wishlist_item;
+pub use wishlist::create_wishlist;
+pub use wishlist::delete_wishlist;
+pub use wishlist::get_public_wishlist;
+pub use wishlist::get_wishlist;
+pub use wishlist::list_favorites;
+pub use wishlist::list_wishlists;
+pub use wishlist::remove_favorite;
+pub use wishlist::remove_wishlist_item;
+pub use wishlist::update_wishlist;
@@ -1,8 +1,15 @@
 //! 用户端订单处理器
 
-use crate::state::get_state;
+use crate::handler::common::{spawn_order_paid_tasks, spawn_order_refunded_tasks};
+use crate::handler::custom::request_referral_code;
+use crate::middleware::{get_real_client_ip, is_from_trusted_proxy};
+use crate::state::{get_state, AppState};
 use num_traits::cast::ToPrimitive;
 use rsws_common::{error_code::ErrorCode, AuthHandler, ResponseExt, RswsError};
+use rsws_model::payment::Order;
+use rsws_model::risk::{RiskContext, RISK_STAGE_CREATION, RISK_STAGE_PAYMENT};
+use rsws_service::invoice_service::render_receipt_html;
+use rsws_service::resource_service::ensure_purchasable;
 use salvo::prelude::*;
 use salvo_oapi::endpoint;
 use salvo_oapi::ToSchema;
@@ -12,7 +19,11 @@ use serde::Deserialize;
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateOrderRequest {
     pub resource_id: i64,
+    /// 授权档位，资源设置了档位时为空则默认最低档位
+    pub license_id: Option<i64>,
     pub payment_method: String,
+    /// 推荐码（为空时取 `ref` 查询参数或推荐码 Cookie，再为空时沿用注册时的推荐人）
+    pub referral_code: Option<String>,
 }
 
 /// 获取订单列表
@@ -115,7 +126,14 @@ pub async fn create_order(req: &mut Request, depot: &mut Depot, res: &mut Respon
 
     match body {
         Ok(data) => {
-            let valid_methods = ["paypal", "usdt_trc20", "usdt_erc20"];
+            let valid_methods = [
+                "paypal",
+                "usdt_trc20",
+                "usdt_erc20",
+                "alipay",
+                "wechatpay",
+                "balance",
+            ];
             let method_lower = data.payment_method.to_lowercase();
             if !valid_methods.contains(&method_lower.as_str()) {
                 res.error_msg(
@@ -127,9 +145,9 @@ pub async fn create_order(req: &mut Request, depot: &mut Depot, res: &mut Respon
 
             let state = get_state(depot);
 
-            // 获取资源价格
-            let amount = match state.resource_service.get(data.resource_id).await {
-                Ok(Some(resource)) => resource.price,
+            // 获取资源
+            let resource = match state.resource_service.get(data.resource_id).await {
+                Ok(Some(resource)) => resource,
                 Ok(None) => {
                     res.error(RswsError::from(ErrorCode::RESOURCE_NOT_FOUND));
                     return;
@@ -140,18 +158,78 @@ pub async fn create_order(req: &mut Request, depot: &mut Depot, res: &mut Respon
                 }
             };
 
+            // 仅审核通过的资源可购买
+            if let Err(e) = ensure_purchasable(&resource) {
+                res.error(e);
+                return;
+            }
+
+            // 按授权档位报价（已持有低档位时只收差价）
+            let (license_id, amount) = match state
+                .license_service
+                .quote(user_id, &resource, data.license_id)
+                .await
+            {
+                Ok(quote) => quote,
+                Err(e) => {
+                    res.error(e);
+                    return;
+                }
+            };
+
+            // 按通道费率与限额计算费用明细
+            let quote = match state.quote_service.quote(&method_lower, amount).await {
+                Ok(q) => q,
+                Err(e) => {
+                    res.error(e);
+                    return;
+                }
+            };
+
             match state
                 .order_service
-                .create(user_id, data.resource_id, amount, &data.payment_method)
+                .create(user_id, data.resource_id, license_id, &quote)
                 .await
             {
                 Ok(order) => {
+                    // 推荐归因：归因失败不影响下单
+                    let referral_code = request_referral_code(req, data.referral_code.as_deref());
+                    if let Err(e) = state
+                        .referral_service
+                        .attribute_order(order.id, user_id, referral_code.as_deref())
+                        .await
+                    {
+                        tracing::warn!("Failed to attribute order {}: {}", order.id, e);
+                    }
+
+                    // 风控评估：拦截的订单已取消，待审核的订单暂不创建支付
+                    let ctx = risk_context(req, &state);
+                    if let Err(e) = screen_order(&state, &order, RISK_STAGE_CREATION, &ctx).await {
+                        if e.error_code() == ErrorCode::ORDER_RISK_REVIEW {
+                            res.status_code(StatusCode::CREATED);
+                            res.success(serde_json::json!({
+                                "id": order.id,
+                                "resource_id": order.resource_id,
+                                "license_id": order.license_id,
+                                "amount": order.amount,
+                                "payment_method": order.payment_method,
+                                "status": order.status,
+                                "risk_status": "review",
+                                "quote": quote,
+                                "message": "Order is under review and can be paid once approved.",
+                            }));
+                        } else {
+                            res.error(e);
+                        }
+                        return;
+                    }
+
                     // 如果是 PayPal 支付，需要创建 PayPal 订单
-                    if data.payment_method == "paypal" {
+                    if method_lower == "paypal" {
                         match state
                             .paypal_service
                             .create_order(
-                                amount.to_f64().unwrap_or(0.0),
+                                order.amount.to_f64().unwrap_or(0.0),
                                 "USDT",
                                 &format!("Resource #{}", data.resource_id),
                                 order.id,
@@ -169,16 +247,18 @@ pub async fn create_order(req: &mut Request, depot: &mut Depot, res: &mut Respon
                                 // 创建支付交易记录
                                 let _ = state
                                     .payment_service
-                                    .create(order.id, user_id, amount, "USDT", "paypal")
+                                    .create(order.id, user_id, order.amount, "USDT", "paypal")
                                     .await;
 
                                 res.status_code(StatusCode::CREATED);
                                 res.success(serde_json::json!({
                                     "id": order.id,
                                     "resource_id": order.resource_id,
+                                    "license_id": order.license_id,
                                     "amount": order.amount,
                                     "payment_method": order.payment_method,
                                     "status": order.status,
+                                    "quote": quote,
                                     "paypal_order_id": paypal_order_id,
                                     "approve_url": approve_url,
                                 }));
@@ -189,9 +269,11 @@ pub async fn create_order(req: &mut Request, depot: &mut Depot, res: &mut Respon
                                 res.success(serde_json::json!({
                                     "id": order.id,
                                     "resource_id": order.resource_id,
+                                    "license_id": order.license_id,
                                     "amount": order.amount,
                                     "payment_method": order.payment_method,
                                     "status": order.status,
+                                    "quote": quote,
                                     "message": "Order created but PayPal unavailable. Please use USDT payment.",
                                 }));
                             }
@@ -201,9 +283,11 @@ pub async fn create_order(req: &mut Request, depot: &mut Depot, res: &mut Respon
                         res.success(serde_json::json!({
                             "id": order.id,
                             "resource_id": order.resource_id,
+                            "license_id": order.license_id,
                             "amount": order.amount,
                             "payment_method": order.payment_method,
                             "status": order.status,
+                            "quote": quote,
                         }));
                     }
                 }
@@ -221,6 +305,90 @@ pub async fn create_order(req: &mut Request, depot: &mut Depot, res: &mut Respon
     }
 }
 
+/// 订单报价查询参数
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct QuoteOrderQuery {
+    pub resource_id: i64,
+    pub license_id: Option<i64>,
+    pub payment_method: String,
+}
+
+/// 下单前获取费用明细（商品金额、通道手续费、买家附加费、应付总额）
+#[endpoint(
+    parameters(
+        ("resource_id", Query, description = "资源ID"),
+        ("license_id", Query, description = "授权档位ID（可选）"),
+        ("payment_method", Query, description = "支付方式"),
+    ),
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 400, description = "金额超出通道限额"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 404, description = "资源不存在"),
+    )
+)]
+pub async fn quote_order(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let query: QuoteOrderQuery = match req.parse_queries() {
+        Ok(q) => q,
+        Err(e) => {
+            res.error_msg(
+                RswsError::from(ErrorCode::INVALID_PARAMETER),
+                format!("Invalid query: {}", e),
+            );
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    let resource = match state.resource_service.get(query.resource_id).await {
+        Ok(Some(resource)) => resource,
+        Ok(None) => {
+            res.error(RswsError::from(ErrorCode::RESOURCE_NOT_FOUND));
+            return;
+        }
+        Err(e) => {
+            res.error(e);
+            return;
+        }
+    };
+
+    if let Err(e) = ensure_purchasable(&resource) {
+        res.error(e);
+        return;
+    }
+
+    let (license_id, amount) = match state
+        .license_service
+        .quote(user_id, &resource, query.license_id)
+        .await
+    {
+        Ok(quote) => quote,
+        Err(e) => {
+            res.error(e);
+            return;
+        }
+    };
+
+    match state
+        .quote_service
+        .quote(&query.payment_method.to_lowercase(), amount)
+        .await
+    {
+        Ok(quote) => res.success(serde_json::json!({
+            "resource_id": resource.id,
+            "license_id": license_id,
+            "quote": quote,
+        })),
+        Err(e) => res.error(e),
+    }
+}
+
 /// 取消订单
 #[endpoint(
     responses(
@@ -287,8 +455,15 @@ pub async fn refund_order(req: &mut Request, depot: &mut Depot, res: &mut Respon
     }
 
     let state = get_state(depot);
-    match state.order_service.refund(id).await {
-        Ok(()) => {
+    match state.ledger_service.refund_order(id).await {
+        Ok(false) => {
+            res.error_msg(
+                RswsError::from(ErrorCode::ORDER_STATUS_INVALID),
+                "Only paid orders can be refunded",
+            );
+        }
+        Ok(true) => {
+            spawn_order_refunded_tasks(&state, id);
             res.success(serde_json::json!({
                 "id": id,
                 "status": "refunded",
@@ -379,6 +554,67 @@ pub async fn check_order_status(req: &mut Request, depot: &mut Depot, res: &mut
     }
 }
 
+/// 获取订单收据（HTML）
+///
+/// 订单已支付但尚未开具发票时按需开具。
+#[endpoint(
+    responses(
+        (status_code = 200, description = "HTML 收据"),
+        (status_code = 400, description = "订单未支付"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 404, description = "订单不存在"),
+    )
+)]
+pub async fn get_order_receipt(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let order_id: i64 = req.param("id").unwrap_or(0);
+    if order_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid order ID",
+        );
+        return;
+    }
+
+    let state = get_state(depot);
+
+    let order = match state.order_service.get(order_id).await {
+        Ok(Some(o)) => o,
+        Ok(None) => {
+            res.error(RswsError::from(ErrorCode::ORDER_NOT_FOUND));
+            return;
+        }
+        Err(e) => {
+            res.error(e);
+            return;
+        }
+    };
+
+    if order.user_id != user_id {
+        res.error_msg(
+            RswsError::from(ErrorCode::AUTH_PERMISSION_DENIED),
+            "Not your order",
+        );
+        return;
+    }
+
+    match state.invoice_service.get_or_issue(order_id).await {
+        Ok(Some(invoice)) => {
+            res.render(Text::Html(render_receipt_html(&invoice)));
+        }
+        Ok(None) => {
+            res.error(RswsError::from(ErrorCode::ORDER_NOT_PAID));
+        }
+        Err(e) => {
+            res.error(e);
+        }
+    }
+}
+
 /// 检查用户是否已购买某资源
 #[endpoint(
     responses(
@@ -421,16 +657,19 @@ pub async fn check_purchase(req: &mut Request, depot: &mut Depot, res: &mut Resp
     }
 }
 
-/// 获取资源下载信息
+/// 获取资源下载凭证
+///
+/// 返回绑定当前用户与资源的短期下载地址（`/api/v1/download/{token}`），不暴露原始文件地址。
 #[endpoint(
     responses(
         (status_code = 200, description = "成功"),
         (status_code = 401, description = "未认证"),
         (status_code = 403, description = "未购买，无权下载"),
+        (status_code = 400, description = "已达授权或每日下载次数上限"),
     )
 )]
 pub async fn get_resource_download(req: &mut Request, depot: &mut Depot, res: &mut Response) {
-    let resource_id: i64 = req.param("resource_id").unwrap_or(0);
+    let resource_id: i64 = req.param("id").unwrap_or(0);
 
     let user_id = match res.auth_require_user_id(depot) {
         Some(id) => id,
@@ -460,26 +699,53 @@ pub async fn get_resource_download(req: &mut Request, depot: &mut Depot, res: &m
             );
         }
         Ok(true) => {
-            // 获取资源下载链接
-            match state.resource_service.get(resource_id).await {
-                Ok(Some(resource)) => {
-                    // 递增下载计数
-                    let _ = state
-                        .resource_service
-                        .increment_download_count(resource_id)
-                        .await;
-
-                    res.success(serde_json::json!({
-                        "file_url": resource.file_url,
-                        "file_name": format!("{}.zip", resource.title.replace(" ", "_"))
-                    }));
-                }
+            let resource = match state.resource_service.get(resource_id).await {
+                Ok(Some(resource)) => resource,
                 Ok(None) => {
                     res.error(RswsError::from(ErrorCode::RESOURCE_NOT_FOUND));
+                    return;
+                }
+                Err(e) => {
+                    res.error(e);
+                    return;
+                }
+            };
+
+            // 有版本记录时为用户可下载的最新版本
+            let version = match state
+                .resource_version_service
+                .latest_entitled(user_id, &resource)
+                .await
+            {
+                Ok(version) => version,
+                Err(e) => {
+                    res.error(e);
+                    return;
                 }
+            };
+
+            // 每日下载上限
+            if let Err(e) = state.download_service.check_quota(user_id).await {
+                res.error(e);
+                return;
+            }
+
+            // 持有档位限次时，签发令牌同时占用下载次数
+            let licensed = match state.license_service.owned(user_id, resource_id).await {
+                Ok(licensed) => licensed,
                 Err(e) => {
                     res.error(e);
+                    return;
                 }
+            };
+
+            match state
+                .download_service
+                .issue(user_id, &resource, version.as_ref(), licensed.as_ref())
+                .await
+            {
+                Ok(ticket) => res.success(ticket),
+                Err(e) => res.error(e),
             }
         }
         Err(e) => {
@@ -545,6 +811,17 @@ pub async fn initiate_payment(req: &mut Request, depot: &mut Depot, res: &mut Re
         return;
     }
 
+    // 风控：待审核 / 已拦截的订单不可支付，发起支付前再评估一次
+    if let Err(e) = state.risk_service.ensure_payable(&order) {
+        res.error(e);
+        return;
+    }
+    let ctx = risk_context(req, &state);
+    if let Err(e) = screen_order(&state, &order, RISK_STAGE_PAYMENT, &ctx).await {
+        res.error(e);
+        return;
+    }
+
     // 根据支付方式返回支付信息
     let payment_method = order.payment_method.as_deref().unwrap_or("");
     match payment_method {
@@ -589,6 +866,103 @@ pub async fn initiate_payment(req: &mut Request, depot: &mut Depot, res: &mut Re
                 }
             }
         }
+        "alipay" => {
+            // mode=qr 当面付扫码，默认电脑网站支付跳转
+            let mode: String = req.query("mode").unwrap_or_else(|| "page".to_string());
+            let subject = format!("Order #{}", order.id);
+            let result = if mode == "qr" {
+                state
+                    .alipay_service
+                    .precreate(order.id, order.amount, &subject)
+                    .await
+            } else {
+                state
+                    .alipay_service
+                    .page_pay_url(order.id, order.amount, &subject)
+            };
+
+            match result {
+                Ok(url) => {
+                    let _ = state
+                        .payment_service
+                        .create(
+                            order_id,
+                            user_id,
+                            state.alipay_service.to_cny(order.amount),
+                            "CNY",
+                            "alipay",
+                        )
+                        .await;
+
+                    if mode == "qr" {
+                        res.success(serde_json::json!({
+                            "payment_method": "alipay",
+                            "mode": "qr",
+                            "qr_code": url,
+                        }));
+                    } else {
+                        res.success(serde_json::json!({
+                            "payment_method": "alipay",
+                            "mode": "page",
+                            "pay_url": url,
+                        }));
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Failed to create Alipay payment: {}", e);
+                    res.error_msg(
+                        RswsError::from(ErrorCode::ALIPAY_REQUEST_FAILED),
+                        "Alipay service unavailable, please try another payment method",
+                    );
+                }
+            }
+        }
+        "wechatpay" => {
+            match state
+                .wechatpay_service
+                .native_order(order.id, order.amount, &format!("Order #{}", order.id))
+                .await
+            {
+                Ok(code_url) => {
+                    let _ = state
+                        .payment_service
+                        .create(
+                            order_id,
+                            user_id,
+                            state.wechatpay_service.to_cny(order.amount),
+                            "CNY",
+                            "wechatpay",
+                        )
+                        .await;
+
+                    res.success(serde_json::json!({
+                        "payment_method": "wechatpay",
+                        "code_url": code_url,
+                    }));
+                }
+                Err(e) => {
+                    tracing::error!("Failed to create WeChat Pay order: {}", e);
+                    res.error_msg(
+                        RswsError::from(ErrorCode::WECHATPAY_REQUEST_FAILED),
+                        "WeChat Pay service unavailable, please try another payment method",
+                    );
+                }
+            }
+        }
+        "balance" => {
+            // 余额即时支付：扣款、记账、订单置为已支付在同一事务内完成
+            if let Err(e) = state.ledger_service.pay_order(order.id, user_id).await {
+                res.error(e);
+                return;
+            }
+
+            spawn_order_paid_tasks(&state, order_id);
+
+            res.success(serde_json::json!({
+                "payment_method": "balance",
+                "status": "paid",
+            }));
+        }
         "usdt_trc20" | "usdt_erc20" => {
             let network = if payment_method == "usdt_trc20" {
                 "tron"
@@ -617,3 +991,47 @@ pub async fn initiate_payment(req: &mut Request, depot: &mut Depot, res: &mut Re
         }
     }
 }
+
+// ==================== 风控 ====================
+
+/// 采集风控评估所需的请求信息
+fn risk_context(req: &Request, state: &AppState) -> RiskContext {
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string())
+    };
+    let trusted_proxies = &state.config.server.trusted_proxies;
+    RiskContext {
+        ip_address: Some(get_real_client_ip(req, trusted_proxies)),
+        // CF-IPCountry 由 Cloudflare 注入，直连请求可任意伪造
+        ip_country: is_from_trusted_proxy(req, trusted_proxies)
+            .then(|| header("CF-IPCountry"))
+            .flatten(),
+        user_agent: header("User-Agent"),
+        payer_country: None,
+    }
+}
+
+/// 风控评估，订单被拦截或转人工审核时返回对应错误
+///
+/// 评估本身失败（如数据库异常）时只记录日志并放行，不阻塞下单与支付。
+async fn screen_order(
+    state: &AppState,
+    order: &Order,
+    stage: &str,
+    ctx: &RiskContext,
+) -> Result<(), RswsError> {
+    match state.risk_service.assess(order, stage, ctx).await {
+        Ok(outcome) if outcome.is_blocked() => {
+            Err(RswsError::business(ErrorCode::ORDER_RISK_BLOCKED))
+        }
+        Ok(outcome) if outcome.is_held() => Err(RswsError::business(ErrorCode::ORDER_RISK_REVIEW)),
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::error!("Risk assessment failed for order {}: {}", order.id, e);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! 资源评分与评价处理器
+//!
+//! 游客可查看资源的评价；已购买的用户评分评价，创作者回复，用户举报不当评价。
+
+use crate::state::get_state;
+use rsws_common::{error_code::ErrorCode, AuthHandler, ResponseExt, RswsError};
+use rsws_model::rating::{ReplyRatingRequest, ReportRatingRequest, SubmitRatingRequest};
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+use serde::Deserialize;
+
+/// 评价列表查询参数
+#[derive(Debug, Default, Deserialize)]
+struct RatingQuery {
+    page: Option<i64>,
+    page_size: Option<i64>,
+}
+
+/// 获取资源评价列表（公开，最新在前）
+#[endpoint(
+    parameters(
+        ("page" = Option<i64>, Query, description = "页码"),
+        ("page_size" = Option<i64>, Query, description = "每页数量"),
+    ),
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 404, description = "资源不存在"),
+    )
+)]
+pub async fn list_resource_ratings(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let resource_id: i64 = req.param("id").unwrap_or(0);
+    if resource_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid resource ID",
+        );
+        return;
+    }
+
+    let query: RatingQuery = req.parse_queries().unwrap_or_default();
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+
+    let state = get_state(depot);
+
+    match state
+        .rating_service
+        .list(resource_id, page, page_size)
+        .await
+    {
+        Ok((items, total)) => res.success(serde_json::json!({
+            "items": items,
+            "total": total,
+            "page": page,
+            "page_size": page_size,
+            "total_pages": (total + page_size - 1) / page_size,
+        })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 评分并评价资源（仅已购买的用户，再次提交即修改）
+#[endpoint(
+    request_body = SubmitRatingRequest,
+    responses(
+        (status_code = 200, description = "评价成功"),
+        (status_code = 400, description = "参数错误或未购买"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 404, description = "资源不存在"),
+    )
+)]
+pub async fn submit_resource_rating(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let resource_id: i64 = req.param("id").unwrap_or(0);
+    if resource_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid resource ID",
+        );
+        return;
+    }
+
+    let data = match req.parse_json::<SubmitRatingRequest>().await {
+        Ok(d) => d,
+        Err(e) => {
+            res.error_msg(
+                RswsError::from(ErrorCode::INVALID_REQUEST_FORMAT),
+                format!("Invalid request: {}", e),
+            );
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state
+        .rating_service
+        .submit(user_id, resource_id, &data)
+        .await
+    {
+        Ok(rating) => res.success(rating),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 回复评价（仅资源创作者，再次回复覆盖）
+#[endpoint(
+    request_body = ReplyRatingRequest,
+    responses(
+        (status_code = 200, description = "回复成功"),
+        (status_code = 400, description = "参数错误"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 403, description = "无权限"),
+        (status_code = 404, description = "评价不存在"),
+    )
+)]
+pub async fn reply_rating(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let rating_id: i64 = req.param("id").unwrap_or(0);
+    if rating_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid rating ID",
+        );
+        return;
+    }
+
+    let data = match req.parse_json::<ReplyRatingRequest>().await {
+        Ok(d) => d,
+        Err(e) => {
+            res.error_msg(
+                RswsError::from(ErrorCode::INVALID_REQUEST_FORMAT),
+                format!("Invalid request: {}", e),
+            );
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.rating_service.reply(rating_id, user_id, &data).await {
+        Ok(rating) => res.success(rating),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 举报评价（每个用户对同一评价只能举报一次）
+#[endpoint(
+    request_body = ReportRatingRequest,
+    responses(
+        (status_code = 200, description = "举报成功"),
+        (status_code = 400, description = "参数错误或已举报"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 404, description = "评价不存在"),
+    )
+)]
+pub async fn report_rating(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let rating_id: i64 = req.param("id").unwrap_or(0);
+    if rating_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid rating ID",
+        );
+        return;
+    }
+
+    let data = match req.parse_json::<ReportRatingRequest>().await {
+        Ok(d) => d,
+        Err(e) => {
+            res.error_msg(
+                RswsError::from(ErrorCode::INVALID_REQUEST_FORMAT),
+                format!("Invalid request: {}", e),
+            );
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.rating_service.report(rating_id, user_id, &data).await {
+        Ok(()) => res.success(serde_json::json!({ "reported": true })),
+        Err(e) => res.error(e),
+    }
+}
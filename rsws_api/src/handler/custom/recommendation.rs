@@ -0,0 +1,76 @@
+//! 资源推荐处理器
+//!
+//! 资源详情页"相关资源"与"买了还买"、本周热门榜，均无需认证。
+
+use crate::state::get_state;
+use rsws_common::{error_code::ErrorCode, ResponseExt, RswsError};
+use rsws_model::recommendation::{MAX_RECOMMENDATIONS, POPULAR_LIMIT};
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+use serde::Deserialize;
+
+/// 推荐数量查询参数
+#[derive(Debug, Default, Deserialize)]
+struct RecommendationQuery {
+    limit: Option<i64>,
+}
+
+/// 获取资源推荐（相关资源 + 买了还买）
+#[endpoint(
+    parameters(
+        ("limit" = Option<i64>, Query, description = "每类返回数量，默认 6，最多 12"),
+    ),
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 400, description = "资源ID无效"),
+    )
+)]
+pub async fn get_resource_recommendations(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) {
+    let resource_id: i64 = req.param("id").unwrap_or(0);
+    if resource_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid resource ID",
+        );
+        return;
+    }
+
+    let query: RecommendationQuery = req.parse_queries().unwrap_or_default();
+    let limit = query.limit.unwrap_or(6).clamp(1, MAX_RECOMMENDATIONS);
+
+    let state = get_state(depot);
+
+    match state
+        .recommendation_service
+        .for_resource(resource_id, limit)
+        .await
+    {
+        Ok(recommendations) => res.success(recommendations),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 本周热门资源（近 7 天浏览与下载加权）
+#[endpoint(
+    parameters(
+        ("limit" = Option<i64>, Query, description = "返回数量，默认 20，最多 100"),
+    ),
+    responses(
+        (status_code = 200, description = "成功"),
+    )
+)]
+pub async fn list_popular_resources(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let query: RecommendationQuery = req.parse_queries().unwrap_or_default();
+    let limit = query.limit.unwrap_or(20).clamp(1, POPULAR_LIMIT);
+
+    let state = get_state(depot);
+
+    match state.recommendation_service.popular(limit).await {
+        Ok(items) => res.success(items),
+        Err(e) => res.error(e),
+    }
+}
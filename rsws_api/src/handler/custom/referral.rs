@@ -0,0 +1,143 @@
+//! 用户端推荐计划处理器
+//!
+//! 推荐链接 `/?ref=CODE` 落地时前端调用点击接口，服务端记录点击并写入推荐码 Cookie；
+//! 注册与下单时按请求参数、`ref` 查询参数、Cookie 的顺序取推荐码归因。
+
+use crate::middleware::get_real_client_ip;
+use crate::state::get_state;
+use rsws_common::{AuthHandler, ResponseExt, RswsError};
+use rsws_model::referral::{
+    ReferralClickRequest, REFERRAL_COOKIE, REFERRAL_COOKIE_DAYS, REFERRAL_QUERY_PARAM,
+};
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+
+/// 记录推荐链接点击（无需认证）
+///
+/// 推荐码有效时写入推荐码 Cookie，之后的注册和下单归因到该推荐人。
+#[endpoint(
+    request_body = ReferralClickRequest,
+    responses(
+        (status_code = 200, description = "已记录"),
+        (status_code = 400, description = "推荐码无效"),
+    )
+)]
+pub async fn record_referral_click(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let body: ReferralClickRequest = match req.parse_json().await {
+        Ok(b) => b,
+        Err(e) => {
+            res.error(RswsError::bad_request(format!("Invalid request: {}", e)));
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+    let ip = get_real_client_ip(req, &state.config.server.trusted_proxies);
+    let user_agent = req
+        .headers()
+        .get("User-Agent")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string());
+
+    match state
+        .referral_service
+        .record_click(
+            &body.code,
+            Some(ip.as_str()),
+            user_agent.as_deref(),
+            body.landing_path.as_deref(),
+        )
+        .await
+    {
+        Ok(code) => {
+            let cookie = format!(
+                "{}={}; Max-Age={}; Path=/; SameSite=Lax; HttpOnly",
+                REFERRAL_COOKIE,
+                code,
+                REFERRAL_COOKIE_DAYS * 24 * 3600
+            );
+            res.add_header("Set-Cookie", cookie, false).ok();
+            res.success(serde_json::json!({ "code": code }));
+        }
+        Err(e) => res.error(e),
+    }
+}
+
+/// 获取推荐人仪表盘（推荐码、推荐链接、点击 / 注册 / 订单 / 佣金统计）
+#[endpoint(
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 401, description = "未认证"),
+    )
+)]
+pub async fn get_referral_dashboard(_req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let state = get_state(depot);
+
+    match state.referral_service.dashboard(user_id).await {
+        Ok(dashboard) => res.success(dashboard),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 获取推荐佣金明细
+#[endpoint(
+    parameters(
+        ("status", Query, description = "状态：pending / settled / cancelled"),
+        ("page", Query, description = "页码"),
+        ("page_size", Query, description = "每页数量"),
+    ),
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 401, description = "未认证"),
+    )
+)]
+pub async fn list_referral_earnings(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let status: Option<String> = req.query("status");
+    let page: i64 = req.query("page").unwrap_or(1).max(1);
+    let page_size: i64 = req.query("page_size").unwrap_or(20).clamp(1, 100);
+
+    let state = get_state(depot);
+
+    match state
+        .referral_service
+        .list_earnings(user_id, status.as_deref(), page, page_size)
+        .await
+    {
+        Ok((items, total)) => res.success(serde_json::json!({
+            "items": items,
+            "total": total,
+            "page": page,
+            "page_size": page_size,
+            "total_pages": (total + page_size - 1) / page_size,
+        })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 取本次请求携带的推荐码：请求体参数 > `ref` 查询参数 > 推荐码 Cookie
+pub(crate) fn request_referral_code(req: &Request, explicit: Option<&str>) -> Option<String> {
+    explicit
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .or_else(|| req.query::<String>(REFERRAL_QUERY_PARAM))
+        .or_else(|| {
+            req.headers()
+                .get_all("Cookie")
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .flat_map(|v| v.split(';'))
+                .filter_map(|pair| pair.trim().split_once('='))
+                .find(|(name, _)| *name == REFERRAL_COOKIE)
+                .map(|(_, value)| value.to_string())
+        })
+}
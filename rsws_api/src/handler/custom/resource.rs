@@ -4,7 +4,13 @@
 
 use crate::state::{get_state, get_user_id};
 use rsws_common::{error_code::ErrorCode, AuthHandler, ResponseExt, RswsError};
-use rsws_model::resource::{CreateResourceRequest, UpdateResourceRequest};
+use rsws_model::preview::SetPreviewPolicyRequest;
+use rsws_model::resource::{
+    CreateResourceRequest, ResourceFilter, ResourceSort, UpdateResourceRequest,
+    OWNER_TYPE_PLATFORM, OWNER_TYPE_USER,
+};
+use rsws_model::tag::slugify;
+use rust_decimal::Decimal;
 use salvo::prelude::*;
 use salvo_oapi::endpoint;
 use serde::Deserialize;
@@ -15,7 +21,81 @@ pub struct ResourceQuery {
     pub page: Option<i64>,
     pub page_size: Option<i64>,
     pub category_id: Option<i64>,
+    /// 分类筛选包含子分类的资源
+    pub include_subcategories: Option<bool>,
     pub search: Option<String>,
+    /// relevance / latest / price_asc / price_desc / rating / downloads
+    pub sort: Option<String>,
+    /// 标签 slug，逗号分隔，须同时包含
+    pub tags: Option<String>,
+    pub min_price: Option<Decimal>,
+    pub max_price: Option<Decimal>,
+    pub is_free: Option<bool>,
+    /// 支持的系统，逗号分隔，满足任一即可
+    pub os: Option<String>,
+    /// user / platform
+    pub owner_type: Option<String>,
+    /// 创作者用户 ID
+    pub creator_id: Option<i64>,
+}
+
+/// 逗号分隔的查询参数
+fn split_list(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 查询参数转为筛选条件
+fn build_filter(query: ResourceQuery) -> Result<ResourceFilter, String> {
+    let search = query.search.filter(|s| !s.trim().is_empty());
+    let sort = match query.sort.as_deref() {
+        // 有关键词时默认按相关度
+        None | Some("") if search.is_some() => ResourceSort::Relevance,
+        None | Some("") => ResourceSort::default(),
+        Some(value) => {
+            ResourceSort::parse(value).ok_or_else(|| format!("Invalid sort: {}", value))?
+        }
+    };
+
+    if let (Some(min), Some(max)) = (query.min_price, query.max_price) {
+        if min > max {
+            return Err("min_price must not exceed max_price".to_string());
+        }
+    }
+
+    let owner_type = query.owner_type.filter(|s| !s.is_empty());
+    if let Some(ref value) = owner_type {
+        if value != OWNER_TYPE_USER && value != OWNER_TYPE_PLATFORM {
+            return Err(format!("Invalid owner_type: {}", value));
+        }
+    }
+
+    let mut tags: Vec<String> = split_list(query.tags.as_deref())
+        .iter()
+        .map(|t| slugify(t))
+        .filter(|t| !t.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+
+    Ok(ResourceFilter {
+        category_id: query.category_id,
+        include_subcategories: query.include_subcategories.unwrap_or(false),
+        search,
+        tags,
+        min_price: query.min_price,
+        max_price: query.max_price,
+        is_free: query.is_free,
+        supported_os: split_list(query.os.as_deref()),
+        owner_type,
+        provider_id: query.creator_id,
+        sort,
+    })
 }
 
 /// 获取资源列表
@@ -24,10 +104,20 @@ pub struct ResourceQuery {
         ("page", Query, description = "页码"),
         ("page_size", Query, description = "每页数量"),
         ("category_id", Query, description = "分类ID"),
-        ("search", Query, description = "搜索关键词"),
+        ("include_subcategories", Query, description = "true 时分类筛选包含全部子分类的资源"),
+        ("search", Query, description = "搜索关键词（支持 \"短语\"、-排除词、or），结果附高亮摘要 snippet"),
+        ("sort", Query, description = "排序：relevance（相关度，有关键词时默认）/ latest（最新，无关键词时默认）/ price_asc / price_desc（价格）/ rating（评分）/ downloads（下载量）"),
+        ("tags", Query, description = "标签，逗号分隔，须同时包含"),
+        ("min_price", Query, description = "最低价格"),
+        ("max_price", Query, description = "最高价格"),
+        ("is_free", Query, description = "true 仅免费，false 仅付费"),
+        ("os", Query, description = "支持的系统，逗号分隔"),
+        ("owner_type", Query, description = "归属类型：user / platform"),
+        ("creator_id", Query, description = "创作者用户ID"),
     ),
     responses(
-        (status_code = 200, description = "成功"),
+        (status_code = 200, description = "成功（含分面计数 facets）"),
+        (status_code = 400, description = "筛选或排序参数无效"),
     )
 )]
 pub async fn list_resources(req: &mut Request, depot: &mut Depot, res: &mut Response) {
@@ -35,17 +125,47 @@ pub async fn list_resources(req: &mut Request, depot: &mut Depot, res: &mut Resp
         page: Some(1),
         page_size: Some(20),
         category_id: None,
+        include_subcategories: None,
         search: None,
+        sort: None,
+        tags: None,
+        min_price: None,
+        max_price: None,
+        is_free: None,
+        os: None,
+        owner_type: None,
+        creator_id: None,
     });
 
     let page = query.page.unwrap_or(1);
     let page_size = query.page_size.unwrap_or(20);
 
+    let filter = match build_filter(query) {
+        Ok(filter) => filter,
+        Err(msg) => {
+            res.error_msg(RswsError::from(ErrorCode::INVALID_PARAMETER), msg);
+            return;
+        }
+    };
+
+    // 与详情一致：优先从 depot 获取已认证 user_id，其次从 query 参数获取
+    let query_user_id: Option<i64> = req
+        .query::<String>("user_id")
+        .and_then(|s| s.parse::<i64>().ok());
+    let user_id = get_user_id(depot).or(query_user_id);
     let state = get_state(depot);
 
+    let facets = match state.resource_service.facets(&filter).await {
+        Ok(facets) => facets,
+        Err(e) => {
+            res.error(e);
+            return;
+        }
+    };
+
     match state
         .resource_service
-        .search(query.category_id, query.search.as_deref(), page, page_size)
+        .search(&filter, page, page_size)
         .await
     {
         Ok((resources, total)) => {
@@ -54,12 +174,17 @@ pub async fn list_resources(req: &mut Request, depot: &mut Depot, res: &mut Resp
             } else {
                 0
             };
+            let items = state
+                .resource_service
+                .mark_saved(user_id, filter.search.as_deref(), resources)
+                .await;
             res.success(serde_json::json!({
-                "items": resources,
+                "items": items,
                 "total": total,
                 "page": page,
                 "page_size": page_size,
                 "total_pages": total_pages,
+                "facets": facets,
             }));
         }
         Err(e) => {
@@ -248,3 +373,167 @@ pub async fn delete_resource(req: &mut Request, depot: &mut Depot, res: &mut Res
         }
     }
 }
+
+/// 提交资源审核
+///
+/// 草稿、被拒绝或被下架的资源提交后进入待审核，审核通过后公开展示和售卖。
+#[endpoint(
+    responses(
+        (status_code = 200, description = "已提交审核"),
+        (status_code = 400, description = "当前状态不允许提交"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 403, description = "无权限"),
+        (status_code = 404, description = "资源不存在"),
+    )
+)]
+pub async fn submit_resource(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let id: i64 = req.param("id").unwrap_or(0);
+
+    if id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid resource ID",
+        );
+        return;
+    }
+
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(uid) => uid,
+        None => return,
+    };
+
+    let state = get_state(depot);
+
+    match state.moderation_service.submit(id, user_id).await {
+        Ok(resource) => {
+            res.success(resource);
+        }
+        Err(e) => {
+            res.error(e);
+        }
+    }
+}
+
+/// 获取我发布的资源（含草稿、待审核、被拒绝等各审核状态）
+#[endpoint(
+    parameters(
+        ("page", Query, description = "页码"),
+        ("page_size", Query, description = "每页数量"),
+    ),
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 401, description = "未认证"),
+    )
+)]
+pub async fn list_my_resources(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(uid) => uid,
+        None => return,
+    };
+
+    let page = req.query::<i64>("page").unwrap_or(1).max(1);
+    let page_size = req.query::<i64>("page_size").unwrap_or(20).clamp(1, 100);
+
+    let state = get_state(depot);
+
+    match state
+        .resource_service
+        .list_mine(user_id, page, page_size)
+        .await
+    {
+        Ok((resources, total)) => {
+            res.success(serde_json::json!({
+                "items": resources,
+                "total": total,
+                "page": page,
+                "page_size": page_size,
+                "total_pages": (total + page_size - 1) / page_size,
+            }));
+        }
+        Err(e) => {
+            res.error(e);
+        }
+    }
+}
+
+/// 获取资源预览策略（未配置时返回默认策略：按 25% 截取，图片全部可见）
+#[endpoint(
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 401, description = "未认证"),
+    )
+)]
+pub async fn get_preview_policy(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    if res.auth_require_user_id(depot).is_none() {
+        return;
+    }
+
+    let id: i64 = req.param("id").unwrap_or(0);
+    if id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid resource ID",
+        );
+        return;
+    }
+
+    let state = get_state(depot);
+
+    match state.resource_service.get_preview_policy(id).await {
+        Ok(policy) => res.success(policy),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 设置我的资源预览策略
+///
+/// 未购买用户看到的详情、使用说明与注意事项按策略截取：
+/// `percent` 按比例、`marker` 截取到 `<!--more-->`、`sections` 只保留指定标题下的章节；
+/// `free_images` 限制可见的展示图数量。
+#[endpoint(
+    request_body = SetPreviewPolicyRequest,
+    responses(
+        (status_code = 200, description = "设置成功"),
+        (status_code = 400, description = "策略无效"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 403, description = "非资源创作者"),
+        (status_code = 404, description = "资源不存在"),
+    )
+)]
+pub async fn set_preview_policy(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(uid) => uid,
+        None => return,
+    };
+
+    let id: i64 = req.param("id").unwrap_or(0);
+    if id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid resource ID",
+        );
+        return;
+    }
+
+    let data = match req.parse_json::<SetPreviewPolicyRequest>().await {
+        Ok(d) => d,
+        Err(e) => {
+            res.error_msg(
+                RswsError::from(ErrorCode::INVALID_REQUEST_FORMAT),
+                format!("Invalid request: {}", e),
+            );
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state
+        .resource_service
+        .set_preview_policy(id, Some(user_id), data)
+        .await
+    {
+        Ok(policy) => res.success(policy),
+        Err(e) => res.error(e),
+    }
+}
@@ -0,0 +1,231 @@
+//! 资源版本处理器
+//!
+//! 创作者发布新版本、设置更新政策；买家查看版本更新日志并下载有权获取的版本。
+
+use crate::state::get_state;
+use rsws_common::{error_code::ErrorCode, AuthHandler, ResponseExt, RswsError};
+use rsws_model::resource_version::{PublishVersionRequest, SetUpdatePolicyRequest};
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+
+/// 获取资源版本列表（含更新日志，`entitled` 标记当前用户可下载的版本）
+#[endpoint(
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 404, description = "资源不存在"),
+    )
+)]
+pub async fn list_resource_versions(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let resource_id: i64 = req.param("id").unwrap_or(0);
+    if resource_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid resource ID",
+        );
+        return;
+    }
+
+    let state = get_state(depot);
+
+    match state
+        .resource_version_service
+        .list_for_user(user_id, resource_id)
+        .await
+    {
+        Ok(versions) => res.success(versions),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 发布资源新版本（仅资源创作者）
+///
+/// 资源文件同步为新版本，资源重新进入审核；已购买的用户会收到新版本邮件通知。
+#[endpoint(
+    request_body = PublishVersionRequest,
+    responses(
+        (status_code = 201, description = "发布成功"),
+        (status_code = 400, description = "参数错误或版本号已存在"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 403, description = "无权限"),
+    )
+)]
+pub async fn publish_resource_version(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let resource_id: i64 = req.param("id").unwrap_or(0);
+    if resource_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid resource ID",
+        );
+        return;
+    }
+
+    let data = match req.parse_json::<PublishVersionRequest>().await {
+        Ok(d) => d,
+        Err(e) => {
+            res.error_msg(
+                RswsError::from(ErrorCode::INVALID_REQUEST_FORMAT),
+                format!("Invalid request: {}", e),
+            );
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state
+        .resource_version_service
+        .publish(resource_id, Some(user_id), user_id, &data)
+        .await
+    {
+        Ok(version) => {
+            res.status_code(StatusCode::CREATED);
+            res.success(version);
+        }
+        Err(e) => res.error(e),
+    }
+}
+
+/// 设置资源更新政策（仅资源创作者）
+///
+/// `update_months` 为空表示包含全部后续更新；否则买家可获取购买后 N 个月内发布的版本。
+#[endpoint(
+    request_body = SetUpdatePolicyRequest,
+    responses(
+        (status_code = 200, description = "设置成功"),
+        (status_code = 400, description = "参数错误"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 403, description = "无权限"),
+    )
+)]
+pub async fn set_resource_update_policy(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let resource_id: i64 = req.param("id").unwrap_or(0);
+    if resource_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid resource ID",
+        );
+        return;
+    }
+
+    let data = match req.parse_json::<SetUpdatePolicyRequest>().await {
+        Ok(d) => d,
+        Err(e) => {
+            res.error_msg(
+                RswsError::from(ErrorCode::INVALID_REQUEST_FORMAT),
+                format!("Invalid request: {}", e),
+            );
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state
+        .resource_version_service
+        .set_update_policy(resource_id, Some(user_id), &data)
+        .await
+    {
+        Ok(resource) => res.success(resource),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 下载资源的指定版本
+///
+/// 需已购买（或有覆盖该分类的会员），且版本在更新政策范围内；按授权档位占用下载次数。
+/// 返回短期下载地址（`/api/v1/download/{token}`），不暴露原始文件地址。
+#[endpoint(
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 400, description = "已达授权或每日下载次数上限，或版本不在更新期内"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 403, description = "未购买，无权下载"),
+        (status_code = 404, description = "版本不存在"),
+    )
+)]
+pub async fn get_resource_version_download(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let resource_id: i64 = req.param("id").unwrap_or(0);
+    let version_id: i64 = req.param("version_id").unwrap_or(0);
+    if resource_id <= 0 || version_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid resource or version ID",
+        );
+        return;
+    }
+
+    let state = get_state(depot);
+
+    let version = match state
+        .resource_version_service
+        .entitled_version(user_id, resource_id, version_id)
+        .await
+    {
+        Ok(version) => version,
+        Err(e) => {
+            res.error(e);
+            return;
+        }
+    };
+
+    let resource = match state.resource_service.get(resource_id).await {
+        Ok(Some(resource)) => resource,
+        Ok(None) => {
+            res.error(RswsError::from(ErrorCode::RESOURCE_NOT_FOUND));
+            return;
+        }
+        Err(e) => {
+            res.error(e);
+            return;
+        }
+    };
+
+    // 每日下载上限
+    if let Err(e) = state.download_service.check_quota(user_id).await {
+        res.error(e);
+        return;
+    }
+
+    // 持有档位限次时，签发令牌同时占用下载次数
+    let licensed = match state.license_service.owned(user_id, resource_id).await {
+        Ok(licensed) => licensed,
+        Err(e) => {
+            res.error(e);
+            return;
+        }
+    };
+
+    match state
+        .download_service
+        .issue(user_id, &resource, Some(&version), licensed.as_ref())
+        .await
+    {
+        Ok(ticket) => res.success(ticket),
+        Err(e) => res.error(e),
+    }
+}
@@ -0,0 +1,86 @@
+//! 资源标签处理器
+//!
+//! 游客浏览热门标签；创作者为自己的资源设置标签。
+
+use crate::state::get_state;
+use rsws_common::{error_code::ErrorCode, AuthHandler, ResponseExt, RswsError};
+use rsws_model::tag::SetResourceTagsRequest;
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+use serde::Deserialize;
+
+/// 热门标签查询参数
+#[derive(Debug, Default, Deserialize)]
+struct PopularTagQuery {
+    limit: Option<i64>,
+}
+
+/// 获取热门标签（按公开资源数排序）
+#[endpoint(
+    parameters(
+        ("limit" = Option<i64>, Query, description = "返回数量，默认 50"),
+    ),
+    responses(
+        (status_code = 200, description = "成功"),
+    )
+)]
+pub async fn list_popular_tags(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let query: PopularTagQuery = req.parse_queries().unwrap_or_default();
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    let state = get_state(depot);
+
+    match state.tag_service.popular(limit).await {
+        Ok(tags) => res.success(tags),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 设置我的资源标签（整体替换，不存在的标签自动创建）
+#[endpoint(
+    request_body = SetResourceTagsRequest,
+    responses(
+        (status_code = 200, description = "设置成功"),
+        (status_code = 400, description = "标签无效或数量超限"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 403, description = "非资源创作者"),
+        (status_code = 404, description = "资源不存在"),
+    )
+)]
+pub async fn set_resource_tags(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let resource_id: i64 = req.param("id").unwrap_or(0);
+    if resource_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid resource ID",
+        );
+        return;
+    }
+
+    let data = match req.parse_json::<SetResourceTagsRequest>().await {
+        Ok(d) => d,
+        Err(e) => {
+            res.error_msg(
+                RswsError::from(ErrorCode::INVALID_REQUEST_FORMAT),
+                format!("Invalid request: {}", e),
+            );
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state
+        .tag_service
+        .set_resource_tags(resource_id, Some(user_id), &data.tags)
+        .await
+    {
+        Ok(tags) => res.success(tags),
+        Err(e) => res.error(e),
+    }
+}
@@ -2,13 +2,16 @@
 //!
 //! 使用 ResponseExt 和 AuthHandler trait 简化样板代码
 
-use crate::state::get_state;
+use crate::handler::custom::request_referral_code;
+use crate::middleware::{get_real_client_ip, get_request_id};
+use crate::state::{get_state, AppState};
 use base64::Engine as _;
 use chrono::{Duration, Utc};
 use rsws_common::{error_code::ErrorCode, AuthHandler, ResponseExt, RswsError};
 use rsws_model::user_models::user::{
     ChangePasswordRequest, LoginRequest, RegisterRequest, UpdateProfileRequest,
 };
+use rsws_service::{CreateLoginLogRequest, LoginStatus, LoginType};
 use salvo::prelude::*;
 use salvo_oapi::endpoint;
 use serde::Deserialize;
@@ -61,6 +64,17 @@ pub async fn register(req: &mut Request, depot: &mut Depot, res: &mut Response)
 
             match state.user_service.register(&data).await {
                 Ok(user) => {
+                    // 推荐归因：推荐码无效或归因失败不影响注册
+                    if let Some(code) = request_referral_code(req, data.referral_code.as_deref()) {
+                        if let Err(e) = state
+                            .referral_service
+                            .attribute_signup(user.id, &code)
+                            .await
+                        {
+                            tracing::warn!("Failed to attribute signup {}: {}", user.id, e);
+                        }
+                    }
+
                     // 注册成功，自动登录：创建并持久化 api_key
                     let create_req = rsws_model::api_key::CreateApiKeyRequest {
                         name: "login_session".to_string(),
@@ -137,6 +151,9 @@ pub async fn login(req: &mut Request, depot: &mut Depot, res: &mut Response) {
 
             match state.user_service.login(&data).await {
                 Ok(mut login_response) => {
+                    let user_id = login_response.user.as_ref().map(|u| u.id);
+                    record_login(&state, req, &data, user_id, None).await;
+
                     // 登录成功后，创建并持久化 api_key
                     if let Some(ref user) = login_response.user {
                         let create_req = rsws_model::api_key::CreateApiKeyRequest {
@@ -165,6 +182,7 @@ pub async fn login(req: &mut Request, depot: &mut Depot, res: &mut Response) {
                     res.success(login_response);
                 }
                 Err(e) => {
+                    record_login(&state, req, &data, None, Some(e.to_string())).await;
                     res.error(e);
                 }
             }
@@ -454,3 +472,42 @@ pub async fn send_code(req: &mut Request, _depot: &mut Depot, res: &mut Response
         }
     }
 }
+
+/// 记录登录日志（订单风控按登录设备统计多账号），写入失败只记录错误日志
+async fn record_login(
+    state: &AppState,
+    req: &Request,
+    data: &LoginRequest,
+    user_id: Option<i64>,
+    fail_reason: Option<String>,
+) {
+    let user_agent = data.user_agent.clone().or_else(|| {
+        req.headers()
+            .get("User-Agent")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string())
+    });
+    let log = CreateLoginLogRequest {
+        user_id,
+        login_type: if data.login_type == "code" {
+            LoginType::EmailLink
+        } else {
+            LoginType::Password
+        },
+        status: if fail_reason.is_none() {
+            LoginStatus::Success
+        } else {
+            LoginStatus::Failed
+        },
+        ip_address: get_real_client_ip(req, &state.config.server.trusted_proxies)
+            .parse()
+            .ok(),
+        user_agent,
+        device_info: data.device_info.clone(),
+        fail_reason: fail_reason.map(|r| r.chars().take(100).collect()),
+        request_id: get_request_id(req),
+    };
+    if let Err(e) = state.login_log_service.record_login(log).await {
+        tracing::error!("Failed to record login log: {}", e);
+    }
+}
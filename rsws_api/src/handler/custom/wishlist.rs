@@ -0,0 +1,421 @@
+//! 收藏与心愿单处理器
+//!
+//! 用户收藏资源、管理命名心愿单；公开心愿单可分享给游客查看。
+
+use crate::state::get_state;
+use rsws_common::{error_code::ErrorCode, AuthHandler, ResponseExt, RswsError};
+use rsws_model::wishlist::{CreateWishlistRequest, UpdateWishlistRequest};
+use salvo::prelude::*;
+use salvo_oapi::endpoint;
+use serde::Deserialize;
+
+/// 收藏列表查询参数
+#[derive(Debug, Default, Deserialize)]
+struct FavoriteQuery {
+    page: Option<i64>,
+    page_size: Option<i64>,
+}
+
+/// 获取我的收藏（最近收藏在前）
+#[endpoint(
+    parameters(
+        ("page" = Option<i64>, Query, description = "页码"),
+        ("page_size" = Option<i64>, Query, description = "每页数量"),
+    ),
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 401, description = "未认证"),
+    )
+)]
+pub async fn list_favorites(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let query: FavoriteQuery = req.parse_queries().unwrap_or_default();
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+
+    let state = get_state(depot);
+
+    match state
+        .wishlist_service
+        .list_favorites(user_id, page, page_size)
+        .await
+    {
+        Ok((items, total)) => res.success(serde_json::json!({
+            "items": items,
+            "total": total,
+            "page": page,
+            "page_size": page_size,
+            "total_pages": (total + page_size - 1) / page_size,
+        })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 收藏资源
+#[endpoint(
+    responses(
+        (status_code = 200, description = "收藏成功"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 404, description = "资源不存在"),
+    )
+)]
+pub async fn add_favorite(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let resource_id: i64 = req.param("id").unwrap_or(0);
+    if resource_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid resource ID",
+        );
+        return;
+    }
+
+    let state = get_state(depot);
+
+    match state
+        .wishlist_service
+        .add_favorite(user_id, resource_id)
+        .await
+    {
+        Ok(()) => res.success(serde_json::json!({ "resource_id": resource_id, "is_saved": true })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 取消收藏
+#[endpoint(
+    responses(
+        (status_code = 200, description = "已取消收藏"),
+        (status_code = 401, description = "未认证"),
+    )
+)]
+pub async fn remove_favorite(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let resource_id: i64 = req.param("id").unwrap_or(0);
+    if resource_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid resource ID",
+        );
+        return;
+    }
+
+    let state = get_state(depot);
+
+    if let Err(e) = state
+        .wishlist_service
+        .remove_favorite(user_id, resource_id)
+        .await
+    {
+        res.error(e);
+        return;
+    }
+
+    // 资源可能仍在心愿单中
+    match state.wishlist_service.is_saved(user_id, resource_id).await {
+        Ok(is_saved) => res.success(serde_json::json!({
+            "resource_id": resource_id,
+            "is_saved": is_saved,
+        })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 获取我的心愿单
+#[endpoint(
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 401, description = "未认证"),
+    )
+)]
+pub async fn list_wishlists(depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let state = get_state(depot);
+
+    match state.wishlist_service.list_wishlists(user_id).await {
+        Ok(wishlists) => res.success(wishlists),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 创建心愿单
+#[endpoint(
+    request_body = CreateWishlistRequest,
+    responses(
+        (status_code = 201, description = "创建成功"),
+        (status_code = 400, description = "参数错误、重名或数量已达上限"),
+        (status_code = 401, description = "未认证"),
+    )
+)]
+pub async fn create_wishlist(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let data = match req.parse_json::<CreateWishlistRequest>().await {
+        Ok(d) => d,
+        Err(e) => {
+            res.error_msg(
+                RswsError::from(ErrorCode::INVALID_REQUEST_FORMAT),
+                format!("Invalid request: {}", e),
+            );
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state.wishlist_service.create_wishlist(user_id, &data).await {
+        Ok(wishlist) => {
+            res.status_code(StatusCode::CREATED);
+            res.success(wishlist);
+        }
+        Err(e) => res.error(e),
+    }
+}
+
+/// 获取心愿单详情（本人的心愿单或他人的公开心愿单）
+#[endpoint(
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 404, description = "心愿单不存在"),
+    )
+)]
+pub async fn get_wishlist(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let wishlist_id: i64 = req.param("id").unwrap_or(0);
+    if wishlist_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid wishlist ID",
+        );
+        return;
+    }
+
+    let state = get_state(depot);
+
+    match state
+        .wishlist_service
+        .get_wishlist(Some(user_id), wishlist_id)
+        .await
+    {
+        Ok(detail) => res.success(detail),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 查看公开心愿单（无需认证，用于分享）
+#[endpoint(
+    responses(
+        (status_code = 200, description = "成功"),
+        (status_code = 404, description = "心愿单不存在或未公开"),
+    )
+)]
+pub async fn get_public_wishlist(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let wishlist_id: i64 = req.param("id").unwrap_or(0);
+    if wishlist_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid wishlist ID",
+        );
+        return;
+    }
+
+    let state = get_state(depot);
+
+    match state.wishlist_service.get_wishlist(None, wishlist_id).await {
+        Ok(detail) => res.success(detail),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 修改心愿单（名称、描述、是否公开）
+#[endpoint(
+    request_body = UpdateWishlistRequest,
+    responses(
+        (status_code = 200, description = "修改成功"),
+        (status_code = 400, description = "参数错误或重名"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 404, description = "心愿单不存在"),
+    )
+)]
+pub async fn update_wishlist(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let wishlist_id: i64 = req.param("id").unwrap_or(0);
+    if wishlist_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid wishlist ID",
+        );
+        return;
+    }
+
+    let data = match req.parse_json::<UpdateWishlistRequest>().await {
+        Ok(d) => d,
+        Err(e) => {
+            res.error_msg(
+                RswsError::from(ErrorCode::INVALID_REQUEST_FORMAT),
+                format!("Invalid request: {}", e),
+            );
+            return;
+        }
+    };
+
+    let state = get_state(depot);
+
+    match state
+        .wishlist_service
+        .update_wishlist(user_id, wishlist_id, &data)
+        .await
+    {
+        Ok(wishlist) => res.success(wishlist),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 删除心愿单
+#[endpoint(
+    responses(
+        (status_code = 200, description = "删除成功"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 404, description = "心愿单不存在"),
+    )
+)]
+pub async fn delete_wishlist(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let wishlist_id: i64 = req.param("id").unwrap_or(0);
+    if wishlist_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid wishlist ID",
+        );
+        return;
+    }
+
+    let state = get_state(depot);
+
+    match state
+        .wishlist_service
+        .delete_wishlist(user_id, wishlist_id)
+        .await
+    {
+        Ok(()) => res.success(serde_json::json!({ "id": wishlist_id, "deleted": true })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 资源加入心愿单
+#[endpoint(
+    responses(
+        (status_code = 200, description = "已加入"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 404, description = "心愿单或资源不存在"),
+    )
+)]
+pub async fn add_wishlist_item(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let wishlist_id: i64 = req.param("id").unwrap_or(0);
+    let resource_id: i64 = req.param("resource_id").unwrap_or(0);
+    if wishlist_id <= 0 || resource_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid wishlist or resource ID",
+        );
+        return;
+    }
+
+    let state = get_state(depot);
+
+    match state
+        .wishlist_service
+        .add_item(user_id, wishlist_id, resource_id)
+        .await
+    {
+        Ok(()) => res.success(serde_json::json!({
+            "wishlist_id": wishlist_id,
+            "resource_id": resource_id,
+            "is_saved": true,
+        })),
+        Err(e) => res.error(e),
+    }
+}
+
+/// 资源移出心愿单
+#[endpoint(
+    responses(
+        (status_code = 200, description = "已移出"),
+        (status_code = 401, description = "未认证"),
+        (status_code = 404, description = "心愿单不存在"),
+    )
+)]
+pub async fn remove_wishlist_item(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let user_id = match res.auth_require_user_id(depot) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let wishlist_id: i64 = req.param("id").unwrap_or(0);
+    let resource_id: i64 = req.param("resource_id").unwrap_or(0);
+    if wishlist_id <= 0 || resource_id <= 0 {
+        res.error_msg(
+            RswsError::from(ErrorCode::INVALID_PARAMETER),
+            "Invalid wishlist or resource ID",
+        );
+        return;
+    }
+
+    let state = get_state(depot);
+
+    if let Err(e) = state
+        .wishlist_service
+        .remove_item(user_id, wishlist_id, resource_id)
+        .await
+    {
+        res.error(e);
+        return;
+    }
+
+    // 资源可能仍在收藏或其他心愿单中
+    match state.wishlist_service.is_saved(user_id, resource_id).await {
+        Ok(is_saved) => res.success(serde_json::json!({
+            "wishlist_id": wishlist_id,
+            "resource_id": resource_id,
+            "is_saved": is_saved,
+        })),
+        Err(e) => res.error(e),
+    }
+}
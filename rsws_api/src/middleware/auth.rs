@@ -12,17 +12,23 @@ use rsws_common::error::RswsError;
 use rsws_db::redis::RedisService;
 use salvo::prelude::*;
 
-/// 获取真实客户端 IP（考虑可信代理）
-fn get_real_client_ip(req: &Request, trusted_proxies: &[String]) -> String {
-    let remote_ip = match req.remote_addr() {
+/// 连接对端 IP
+fn remote_ip(req: &Request) -> String {
+    match req.remote_addr() {
         salvo::conn::SocketAddr::IPv4(v4) => v4.ip().to_string(),
         salvo::conn::SocketAddr::IPv6(v6) => v6.ip().to_string(),
         _ => "unknown".to_string(),
-    };
-
-    if trusted_proxies.is_empty() {
-        return remote_ip;
     }
+}
+
+/// 请求是否经由可信代理转发（代理注入的请求头仅在此时可信）
+pub(crate) fn is_from_trusted_proxy(req: &Request, trusted_proxies: &[String]) -> bool {
+    trusted_proxies.contains(&remote_ip(req))
+}
+
+/// 获取真实客户端 IP（考虑可信代理）
+pub(crate) fn get_real_client_ip(req: &Request, trusted_proxies: &[String]) -> String {
+    let remote_ip = remote_ip(req);
 
     if !trusted_proxies.contains(&remote_ip) {
         return remote_ip;
@@ -5,6 +5,7 @@ pub mod request_id;
 pub mod tracing;
 
 pub use auth::{api_key_auth, rate_limit, require_admin};
+pub(crate) use auth::{get_real_client_ip, is_from_trusted_proxy};
 pub use request_id::{get_request_id, REQUEST_ID_HEADER};
 pub use tracing::tracing_logger;
 
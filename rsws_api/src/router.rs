@@ -21,18 +21,65 @@ pub fn create_router(state: AppState) -> Router {
         .push(Router::with_path("health").get(handler::health))
         // 分类列表（无需认证）
         .push(Router::with_path("api/v1/categories").get(handler::custom::list_categories))
+        .push(
+            Router::with_path("api/v1/categories/{id}/breadcrumb")
+                .get(handler::custom::category_breadcrumb),
+        )
+        .push(
+            Router::with_path("api/v1/categories/{id}/descendants")
+                .get(handler::custom::category_descendants),
+        )
+        // 会员套餐列表（无需认证）
+        .push(
+            Router::with_path("api/v1/membership/plans")
+                .get(handler::custom::list_membership_plans),
+        )
+        // 推荐链接点击（无需认证）
+        .push(
+            Router::with_path("api/v1/referral/click").post(handler::custom::record_referral_click),
+        )
         // 公开认证端点（无需 API Key）
         .push(Router::with_path("api/v1/user/register").post(handler::custom::register))
         .push(Router::with_path("api/v1/user/login").post(handler::custom::login))
         .push(Router::with_path("api/v1/user/send-code").post(handler::custom::send_code))
         // 资源浏览（公开端点 — 游客可浏览资源列表和详情）
         .push(Router::with_path("api/v1/resource").get(handler::custom::list_resources))
+        // 本周热门（须在 {id} 之前注册）
+        .push(
+            Router::with_path("api/v1/resource/popular")
+                .get(handler::custom::list_popular_resources),
+        )
         .push(Router::with_path("api/v1/resource/{id}").get(handler::custom::get_resource))
+        .push(
+            Router::with_path("api/v1/resource/{id}/ratings")
+                .get(handler::custom::list_resource_ratings),
+        )
+        .push(
+            Router::with_path("api/v1/resource/{id}/recommendations")
+                .get(handler::custom::get_resource_recommendations),
+        )
+        // 热门标签（无需认证）
+        .push(Router::with_path("api/v1/tags").get(handler::custom::list_popular_tags))
+        // 公开心愿单（分享链接，无需认证）
+        .push(
+            Router::with_path("api/v1/public/wishlists/{id}")
+                .get(handler::custom::get_public_wishlist),
+        )
         // API v1（统一 API Key 认证 + 速率限制）
         .push(
             Router::with_path("api/v1")
                 .hoop(api_key_auth)
                 .hoop(rate_limit)
+                // 凭令牌下载资源文件（支持 Range 断点续传）
+                .push(Router::with_path("download/{token}").get(handler::custom::download_file))
+                // 文件上传（需认证，私有区文件记录上传者）
+                .push(
+                    Router::with_path("upload")
+                        .push(Router::with_path("init").post(handler::common::init_upload))
+                        .push(Router::with_path("chunk").post(handler::common::upload_chunk))
+                        .push(Router::with_path("complete").post(handler::common::complete_upload))
+                        .push(Router::with_path("single").post(handler::common::upload_single)),
+                )
                 // 用户相关（需要认证）
                 .push(
                     Router::with_path("user")
@@ -55,6 +102,10 @@ pub fn create_router(state: AppState) -> Router {
                             Router::with_path("{id}")
                                 .put(handler::custom::update_resource)
                                 .delete(handler::custom::delete_resource)
+                                .push(
+                                    Router::with_path("submit")
+                                        .post(handler::custom::submit_resource),
+                                )
                                 .push(
                                     Router::with_path("purchase-check")
                                         .get(handler::custom::check_purchase),
@@ -62,9 +113,51 @@ pub fn create_router(state: AppState) -> Router {
                                 .push(
                                     Router::with_path("download")
                                         .get(handler::custom::get_resource_download),
+                                )
+                                .push(
+                                    Router::with_path("versions")
+                                        .get(handler::custom::list_resource_versions)
+                                        .post(handler::custom::publish_resource_version)
+                                        .push(
+                                            Router::with_path("{version_id}/download").get(
+                                                handler::custom::get_resource_version_download,
+                                            ),
+                                        ),
+                                )
+                                .push(
+                                    Router::with_path("update-policy")
+                                        .put(handler::custom::set_resource_update_policy),
+                                )
+                                .push(
+                                    Router::with_path("ratings")
+                                        .post(handler::custom::submit_resource_rating),
+                                )
+                                .push(
+                                    Router::with_path("tags")
+                                        .put(handler::custom::set_resource_tags),
+                                )
+                                .push(
+                                    Router::with_path("preview-policy")
+                                        .get(handler::custom::get_preview_policy)
+                                        .put(handler::custom::set_preview_policy),
+                                )
+                                .push(
+                                    Router::with_path("licenses")
+                                        .get(handler::custom::list_resource_licenses)
+                                        .post(handler::custom::create_resource_license)
+                                        .push(
+                                            Router::with_path("{license_id}")
+                                                .put(handler::custom::update_resource_license),
+                                        ),
                                 ),
                         ),
                 )
+                // 资源评价（创作者回复、用户举报）
+                .push(
+                    Router::with_path("ratings/{id}")
+                        .push(Router::with_path("reply").post(handler::custom::reply_rating))
+                        .push(Router::with_path("report").post(handler::custom::report_rating)),
+                )
                 // 订单相关
                 .push(
                     Router::with_path("order")
@@ -73,6 +166,7 @@ pub fn create_router(state: AppState) -> Router {
                                 .get(handler::custom::list_orders)
                                 .post(handler::custom::create_order),
                         )
+                        .push(Router::with_path("quote").get(handler::custom::quote_order))
                         .push(
                             Router::with_path("{id}")
                                 .get(handler::custom::get_order)
@@ -93,9 +187,90 @@ pub fn create_router(state: AppState) -> Router {
                                 .push(
                                     Router::with_path("complete")
                                         .post(handler::custom::complete_order),
+                                )
+                                .push(
+                                    Router::with_path("receipt")
+                                        .get(handler::custom::get_order_receipt),
                                 ),
                         ),
                 )
+                // 会员订阅
+                .push(
+                    Router::with_path("membership")
+                        .get(handler::custom::list_my_memberships)
+                        .push(
+                            Router::with_path("subscribe")
+                                .post(handler::custom::subscribe_membership),
+                        ),
+                )
+                // 创作者收益与我的资源
+                .push(
+                    Router::with_path("creator")
+                        .push(
+                            Router::with_path("resources").get(handler::custom::list_my_resources),
+                        )
+                        .push(
+                            Router::with_path("earnings")
+                                .get(handler::custom::get_creator_earnings)
+                                .push(
+                                    Router::with_path("resources")
+                                        .get(handler::custom::list_creator_resource_sales),
+                                ),
+                        )
+                        .push(
+                            Router::with_path("statements")
+                                .get(handler::custom::list_creator_statements)
+                                .push(
+                                    Router::with_path("{month}")
+                                        .get(handler::custom::get_creator_statement),
+                                ),
+                        ),
+                )
+                // 收藏与心愿单
+                .push(
+                    Router::with_path("favorites")
+                        .get(handler::custom::list_favorites)
+                        .push(
+                            Router::with_path("{id}")
+                                .post(handler::custom::add_favorite)
+                                .delete(handler::custom::remove_favorite),
+                        ),
+                )
+                .push(
+                    Router::with_path("wishlists")
+                        .get(handler::custom::list_wishlists)
+                        .post(handler::custom::create_wishlist)
+                        .push(
+                            Router::with_path("{id}")
+                                .get(handler::custom::get_wishlist)
+                                .put(handler::custom::update_wishlist)
+                                .delete(handler::custom::delete_wishlist)
+                                .push(
+                                    Router::with_path("items/{resource_id}")
+                                        .post(handler::custom::add_wishlist_item)
+                                        .delete(handler::custom::remove_wishlist_item),
+                                ),
+                        ),
+                )
+                // 推荐计划
+                .push(
+                    Router::with_path("referral")
+                        .get(handler::custom::get_referral_dashboard)
+                        .push(
+                            Router::with_path("earnings")
+                                .get(handler::custom::list_referral_earnings),
+                        ),
+                )
+                // 余额与充值
+                .push(
+                    Router::with_path("balance")
+                        .get(handler::custom::get_balance)
+                        .push(
+                            Router::with_path("entries").get(handler::custom::list_balance_entries),
+                        )
+                        .push(Router::with_path("topup").post(handler::custom::create_topup))
+                        .push(Router::with_path("topups").get(handler::custom::list_topups)),
+                )
                 // 管理后台（需要 Admin 权限）
                 .push(
                     Router::with_path("admin")
@@ -183,17 +358,171 @@ pub fn create_router(state: AppState) -> Router {
                                 .put(handler::admin::update_category)
                                 .delete(handler::admin::delete_category),
                         )
+                        .push(
+                            Router::with_path("categories/{id}/move")
+                                .put(handler::admin::move_category),
+                        )
                         .push(
                             Router::with_path("categories/sort")
                                 .put(handler::admin::batch_update_sort),
                         )
                         // 订单管理
                         .push(Router::with_path("orders").get(handler::admin::admin_list_orders))
+                        // 发票导出
+                        .push(
+                            Router::with_path("invoices/export")
+                                .get(handler::admin::export_invoices),
+                        )
+                        .push(
+                            Router::with_path("ledger/trial-balance")
+                                .get(handler::admin::trial_balance),
+                        )
+                        // 入站 Webhook 日志
+                        .push(
+                            Router::with_path("webhook-logs")
+                                .get(handler::admin::list_webhook_logs)
+                                .push(
+                                    Router::with_path("{id}")
+                                        .get(handler::admin::get_webhook_log)
+                                        .push(
+                                            Router::with_path("replay")
+                                                .post(handler::admin::replay_webhook_log),
+                                        ),
+                                ),
+                        )
+                        // 出站事件 Webhook
+                        .push(
+                            Router::with_path("event-webhooks")
+                                .get(handler::admin::list_event_webhooks)
+                                .post(handler::admin::create_event_webhook)
+                                .push(
+                                    Router::with_path("{id}")
+                                        .put(handler::admin::update_event_webhook)
+                                        .delete(handler::admin::delete_event_webhook),
+                                ),
+                        )
+                        .push(
+                            Router::with_path("webhook-deliveries")
+                                .get(handler::admin::list_webhook_deliveries)
+                                .push(
+                                    Router::with_path("{id}/redeliver")
+                                        .post(handler::admin::redeliver_webhook),
+                                ),
+                        )
+                        // 订单风控
+                        .push(
+                            Router::with_path("risk/rules")
+                                .get(handler::admin::list_risk_rules)
+                                .post(handler::admin::create_risk_rule)
+                                .push(
+                                    Router::with_path("{id}")
+                                        .put(handler::admin::update_risk_rule)
+                                        .delete(handler::admin::delete_risk_rule),
+                                ),
+                        )
+                        .push(
+                            Router::with_path("risk/reviews")
+                                .get(handler::admin::list_risk_reviews),
+                        )
+                        .push(
+                            Router::with_path("risk/orders/{id}")
+                                .get(handler::admin::get_order_risk)
+                                .push(
+                                    Router::with_path("review")
+                                        .post(handler::admin::review_risk_order),
+                                ),
+                        )
+                        // 创作者分成规则
+                        .push(
+                            Router::with_path("commission/rules")
+                                .get(handler::admin::list_commission_rules)
+                                .post(handler::admin::create_commission_rule)
+                                .push(
+                                    Router::with_path("{id}")
+                                        .put(handler::admin::update_commission_rule)
+                                        .delete(handler::admin::delete_commission_rule),
+                                ),
+                        )
+                        .push(
+                            Router::with_path("commission/simulate")
+                                .post(handler::admin::simulate_commission),
+                        )
+                        .push(
+                            Router::with_path("commission/creators/{id}/tier")
+                                .get(handler::admin::get_creator_tier)
+                                .put(handler::admin::set_creator_tier),
+                        )
+                        // 资源审核
+                        .push(
+                            Router::with_path("moderation/resources")
+                                .get(handler::admin::list_moderation_queue)
+                                .push(
+                                    Router::with_path("{id}")
+                                        .post(handler::admin::moderate_resource)
+                                        .push(
+                                            Router::with_path("history").get(
+                                                handler::admin::get_resource_moderation_history,
+                                            ),
+                                        ),
+                                ),
+                        )
+                        // 最受期待资源报表
+                        .push(
+                            Router::with_path("wishlists/most-wished")
+                                .get(handler::admin::most_wished_report),
+                        )
+                        // 推荐缓存
+                        .push(
+                            Router::with_path("recommendations/refresh")
+                                .post(handler::admin::refresh_recommendations),
+                        )
+                        // 资源标签
+                        .push(
+                            Router::with_path("tags")
+                                .get(handler::admin::list_tags)
+                                .post(handler::admin::create_tag)
+                                .push(
+                                    Router::with_path("{id}")
+                                        .put(handler::admin::update_tag)
+                                        .delete(handler::admin::delete_tag),
+                                ),
+                        )
+                        // 评价举报处理
+                        .push(
+                            Router::with_path("ratings/reports")
+                                .get(handler::admin::list_reported_ratings),
+                        )
+                        .push(
+                            Router::with_path("ratings/{id}")
+                                .push(Router::with_path("hide").post(handler::admin::hide_rating))
+                                .push(
+                                    Router::with_path("restore")
+                                        .post(handler::admin::restore_rating),
+                                ),
+                        )
+                        // 会员套餐
+                        .push(
+                            Router::with_path("membership/plans")
+                                .get(handler::admin::admin_list_membership_plans)
+                                .post(handler::admin::create_membership_plan),
+                        )
+                        .push(
+                            Router::with_path("membership/plans/{id}")
+                                .put(handler::admin::update_membership_plan),
+                        )
                         // 平台资源管理
                         .push(
                             Router::with_path("resources")
                                 .get(handler::admin::list_resources)
                                 .post(handler::admin::create_platform_resource)
+                                .push(
+                                    Router::with_path("import")
+                                        .post(handler::admin::import_resources),
+                                )
+                                .push(
+                                    Router::with_path("export")
+                                        .get(handler::admin::export_resources),
+                                )
                                 .push(
                                     Router::with_path("{id}")
                                         .put(handler::admin::update_platform_resource)
@@ -201,6 +530,34 @@ pub fn create_router(state: AppState) -> Router {
                                         .push(
                                             Router::with_path("toggle-active")
                                                 .put(handler::admin::toggle_platform_resource),
+                                        )
+                                        .push(
+                                            Router::with_path("tags")
+                                                .put(handler::admin::admin_set_resource_tags),
+                                        )
+                                        .push(
+                                            Router::with_path("preview-policy")
+                                                .put(handler::admin::admin_set_preview_policy),
+                                        )
+                                        .push(
+                                            Router::with_path("licenses")
+                                                .get(handler::admin::admin_list_resource_licenses)
+                                                .post(handler::admin::admin_create_resource_license)
+                                                .push(Router::with_path("{license_id}").put(
+                                                    handler::admin::admin_update_resource_license,
+                                                )),
+                                        )
+                                        .push(
+                                            Router::with_path("versions")
+                                                .get(handler::admin::admin_list_resource_versions)
+                                                .post(
+                                                    handler::admin::admin_publish_resource_version,
+                                                ),
+                                        )
+                                        .push(
+                                            Router::with_path("update-policy").put(
+                                                handler::admin::admin_set_resource_update_policy,
+                                            ),
                                         ),
                                 ),
                         )
@@ -219,6 +576,36 @@ pub fn create_router(state: AppState) -> Router {
                                         ),
                                 ),
                         )
+                        // 支付宝配置管理
+                        .push(
+                            Router::with_path("alipay-configs")
+                                .post(handler::admin::create_alipay_config)
+                                .get(handler::admin::list_alipay_configs)
+                                .push(
+                                    Router::with_path("{id}")
+                                        .get(handler::admin::get_alipay_config)
+                                        .put(handler::admin::update_alipay_config)
+                                        .push(
+                                            Router::with_path("active/{active}")
+                                                .post(handler::admin::set_alipay_config_active),
+                                        ),
+                                ),
+                        )
+                        // 微信支付配置管理
+                        .push(
+                            Router::with_path("wechatpay-configs")
+                                .post(handler::admin::create_wechatpay_config)
+                                .get(handler::admin::list_wechatpay_configs)
+                                .push(
+                                    Router::with_path("{id}")
+                                        .get(handler::admin::get_wechatpay_config)
+                                        .put(handler::admin::update_wechatpay_config)
+                                        .push(
+                                            Router::with_path("active/{active}")
+                                                .post(handler::admin::set_wechatpay_config_active),
+                                        ),
+                                ),
+                        )
                         // 支付方式管理
                         .push(
                             Router::with_path("payment-methods")
@@ -239,6 +626,22 @@ pub fn create_router(state: AppState) -> Router {
                             Router::with_path("oss-configs/test")
                                 .post(handler::admin::test_storage_connection),
                         )
+                        // 付费资源文件迁移到私有存储区
+                        .push(
+                            Router::with_path("oss-configs/migrate-private")
+                                .post(handler::admin::migrate_private_storage),
+                        )
+                        // 下载配置（令牌有效期、每日下载上限）
+                        .push(
+                            Router::with_path("download-config")
+                                .get(handler::admin::get_download_config)
+                                .put(handler::admin::update_download_config),
+                        )
+                        // 泄露文件水印反查
+                        .push(
+                            Router::with_path("download-watermarks/lookup")
+                                .post(handler::admin::lookup_watermark),
+                        )
                         // 管理员当前信息（GET /admin）
                         .push(Router::new().get(handler::admin::get_current_admin))
                         // 管理员列表
@@ -297,16 +700,10 @@ pub fn create_router(state: AppState) -> Router {
         .push(
             Router::with_path("api/v1/webhook")
                 .push(Router::with_path("paypal").post(handler::common::paypal_webhook))
-                .push(Router::with_path("usdt").post(handler::common::usdt_webhook)),
-        )
-        // 文件上传
-        .push(
-            Router::with_path("api/v1/upload")
-                .push(Router::with_path("init").post(handler::common::init_upload))
-                .push(Router::with_path("chunk").post(handler::common::upload_chunk))
-                .push(Router::with_path("complete").post(handler::common::complete_upload)),
-        )
-        .push(Router::with_path("api/v1/upload/single").post(handler::common::upload_single));
+                .push(Router::with_path("usdt").post(handler::common::usdt_webhook))
+                .push(Router::with_path("alipay").post(handler::common::alipay_webhook))
+                .push(Router::with_path("wechatpay").post(handler::common::wechatpay_webhook)),
+        );
 
     let doc = OpenApi::new("RSWS API", "0.1.0").merge_router(&api_routes);
 
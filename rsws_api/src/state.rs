@@ -5,9 +5,13 @@
 use rsws_common::config::AppConfig;
 use rsws_db::CategoryRepository;
 use rsws_service::{
-    AdminRepository, AdminService, ApiKeyManager, AuditLogService, BlockchainService,
-    ConfigService, CrossPlatformService, ErrorLogService, LogService, LoginLogService,
-    OrderService, PayPalService, PaymentService, ResourceService, UserService, WebhookService,
+    AdminRepository, AdminService, AlipayService, ApiKeyManager, AuditLogService,
+    BlockchainService, CommissionService, ConfigService, CreatorService, CrossPlatformService,
+    DownloadService, ErrorLogService, InvoiceService, LedgerService, LicenseService, LogService,
+    LoginLogService, MembershipService, ModerationService, OrderService, PayPalService,
+    PaymentService, QuoteService, RatingService, RecommendationService, ReferralService,
+    ResourceBulkService, ResourceService, ResourceVersionService, RiskService, TagService,
+    UserService, WebhookService, WechatPayService, WishlistService,
 };
 use salvo::prelude::*;
 use sqlx::PgPool;
@@ -21,10 +25,29 @@ pub struct AppState {
     pub user_service: Arc<UserService>,
     pub order_service: Arc<OrderService>,
     pub resource_service: Arc<ResourceService>,
+    pub resource_bulk_service: Arc<ResourceBulkService>,
+    pub license_service: Arc<LicenseService>,
+    pub resource_version_service: Arc<ResourceVersionService>,
+    pub rating_service: Arc<RatingService>,
+    pub wishlist_service: Arc<WishlistService>,
+    pub tag_service: Arc<TagService>,
+    pub recommendation_service: Arc<RecommendationService>,
+    pub download_service: Arc<DownloadService>,
     pub admin_api_key_manager: Arc<ApiKeyManager>,
     pub user_api_key_manager: Arc<ApiKeyManager>,
     pub paypal_service: Arc<PayPalService>,
+    pub alipay_service: Arc<AlipayService>,
+    pub wechatpay_service: Arc<WechatPayService>,
     pub payment_service: Arc<PaymentService>,
+    pub invoice_service: Arc<InvoiceService>,
+    pub ledger_service: Arc<LedgerService>,
+    pub membership_service: Arc<MembershipService>,
+    pub quote_service: Arc<QuoteService>,
+    pub risk_service: Arc<RiskService>,
+    pub referral_service: Arc<ReferralService>,
+    pub commission_service: Arc<CommissionService>,
+    pub creator_service: Arc<CreatorService>,
+    pub moderation_service: Arc<ModerationService>,
     pub blockchain_service: Arc<BlockchainService>,
     pub webhook_service: Arc<WebhookService>,
     pub cross_platform_service: Arc<CrossPlatformService>,
@@ -45,14 +68,33 @@ impl AppState {
         config: AppConfig,
         user_service: UserService,
         order_service: OrderService,
-        resource_service: ResourceService,
+        resource_service: Arc<ResourceService>,
+        resource_bulk_service: Arc<ResourceBulkService>,
+        license_service: Arc<LicenseService>,
+        resource_version_service: Arc<ResourceVersionService>,
+        rating_service: RatingService,
+        wishlist_service: Arc<WishlistService>,
+        tag_service: Arc<TagService>,
+        recommendation_service: Arc<RecommendationService>,
+        download_service: Arc<DownloadService>,
         admin_api_key_manager: ApiKeyManager,
         user_api_key_manager: ApiKeyManager,
         paypal_service: Arc<PayPalService>,
+        alipay_service: AlipayService,
+        wechatpay_service: WechatPayService,
         payment_service: PaymentService,
+        invoice_service: Arc<InvoiceService>,
+        ledger_service: LedgerService,
+        membership_service: Arc<MembershipService>,
+        quote_service: Arc<QuoteService>,
+        risk_service: RiskService,
+        referral_service: ReferralService,
+        commission_service: Arc<CommissionService>,
+        creator_service: CreatorService,
+        moderation_service: ModerationService,
         blockchain_service: BlockchainService,
         webhook_service: WebhookService,
-        cross_platform_service: CrossPlatformService,
+        cross_platform_service: Arc<CrossPlatformService>,
         config_service: Arc<ConfigService>,
         admin_service: AdminService,
         log_service: LogService,
@@ -67,14 +109,33 @@ impl AppState {
             config,
             user_service: Arc::new(user_service),
             order_service: Arc::new(order_service),
-            resource_service: Arc::new(resource_service),
+            resource_service,
+            resource_bulk_service,
+            license_service,
+            resource_version_service,
+            rating_service: Arc::new(rating_service),
+            wishlist_service,
+            tag_service,
+            recommendation_service,
+            download_service,
             admin_api_key_manager: Arc::new(admin_api_key_manager),
             user_api_key_manager: Arc::new(user_api_key_manager),
             paypal_service,
+            alipay_service: Arc::new(alipay_service),
+            wechatpay_service: Arc::new(wechatpay_service),
             payment_service: Arc::new(payment_service),
+            invoice_service,
+            ledger_service: Arc::new(ledger_service),
+            membership_service,
+            quote_service,
+            risk_service: Arc::new(risk_service),
+            referral_service: Arc::new(referral_service),
+            commission_service,
+            creator_service: Arc::new(creator_service),
+            moderation_service: Arc::new(moderation_service),
             blockchain_service: Arc::new(blockchain_service),
             webhook_service: Arc::new(webhook_service),
-            cross_platform_service: Arc::new(cross_platform_service),
+            cross_platform_service,
             config_service,
             admin_service: Arc::new(admin_service),
             log_service: Arc::new(log_service),
@@ -125,6 +125,43 @@ async fn main() -> Result<(), RswsError> {
         }
     };
 
+    // 读取支付宝配置
+    let alipay_db_config = match config_service.get_alipay_config().await {
+        Ok(Some(c)) => {
+            info!("Alipay config loaded from database");
+            Some(c)
+        }
+        Ok(None) => {
+            warn!("No active Alipay config found in database — Alipay will run in mock mode");
+            None
+        }
+        Err(e) => {
+            warn!("Failed to load Alipay config from DB: {}, using None", e);
+            None
+        }
+    };
+
+    // 读取微信支付配置
+    let wechatpay_db_config = match config_service.get_wechatpay_config().await {
+        Ok(Some(c)) => {
+            info!("WeChat Pay config loaded from database");
+            Some(c)
+        }
+        Ok(None) => {
+            warn!(
+                "No active WeChat Pay config found in database — WeChat Pay will run in mock mode"
+            );
+            None
+        }
+        Err(e) => {
+            warn!(
+                "Failed to load WeChat Pay config from DB: {}, using None",
+                e
+            );
+            None
+        }
+    };
+
     // 读取区块链配置
     let blockchain_configs = config_service
         .get_blockchain_configs()
@@ -175,11 +212,70 @@ async fn main() -> Result<(), RswsError> {
     );
     let order_service = rsws_service::create_order_service(pool.clone());
     let order_service_arc = Arc::new(order_service);
+    // 授权档位服务 — 资源详情展示档位，下单按档位报价
+    let license_service = Arc::new(rsws_service::create_license_service(pool.clone()));
+    // 出站事件 Webhook — 订单支付 / 退款、资源上架事件推送给集成方
+    let cross_platform_service =
+        Arc::new(rsws_service::create_cross_platform_service(pool.clone()));
+    // 收藏与心愿单服务 — 资源列表 / 详情标记是否已保存，已保存资源降价时邮件通知
+    let wishlist_service = Arc::new(rsws_service::create_wishlist_service(
+        pool.clone(),
+        email_db_config.as_ref(),
+    ));
+    // 资源标签服务 — 管理员与创作者维护标签，资源详情展示标签
+    let tag_service = Arc::new(rsws_service::create_tag_service(pool.clone()));
     let resource_service = rsws_service::create_resource_service(
         pool.clone(),
         Some(config_service.as_ref().clone()),
         Some(order_service_arc.clone()),
+        Some(license_service.clone()),
+        Some(cross_platform_service.clone()),
+        Some(wishlist_service.clone()),
+        Some(tag_service.clone()),
     );
+
+    // 一次性命令：把付费资源文件迁移到私有存储区（`--dry-run` 只列出待迁移文件）
+    if std::env::args().nth(1).as_deref() == Some("migrate-private-storage") {
+        let dry_run = std::env::args().any(|a| a == "--dry-run");
+        let report = resource_service.migrate_private_files(dry_run).await?;
+        info!(
+            "Private storage migration finished: dry_run={}, pending={}, moved={}, failed={}",
+            report.dry_run,
+            report.pending.len(),
+            report.moved,
+            report.failed.len()
+        );
+        for failure in &report.failed {
+            error!("Failed to migrate {}: {}", failure.file_url, failure.error);
+        }
+        return Ok(());
+    }
+    let resource_service = Arc::new(resource_service);
+    // 资源批量导入导出服务 — 管理员从 CSV / JSON Lines 按 external_id 导入资源，导出备份
+    let resource_bulk_service = Arc::new(rsws_service::create_resource_bulk_service(
+        pool.clone(),
+        resource_service.clone(),
+        tag_service.clone(),
+        config_service.as_ref().clone(),
+    ));
+
+    // 资源版本服务 — 买家按更新政策下载版本，新版本邮件通知已购买的用户
+    let resource_version_service = Arc::new(rsws_service::create_resource_version_service(
+        pool.clone(),
+        email_db_config.as_ref(),
+        config_service.as_ref().clone(),
+    ));
+    // 资源评分服务 — 已购买的用户评分评价，创作者回复，管理员处理举报
+    let rating_service =
+        rsws_service::create_rating_service(pool.clone(), order_service_arc.clone());
+    // 资源推荐服务 — 相关资源、买了还买与本周热门，后台定期重算缓存
+    let recommendation_service =
+        Arc::new(rsws_service::create_recommendation_service(pool.clone()));
+    // 资源下载服务 — 签发短期下载令牌，S3 重定向预签名 URL，本地存储流式下载
+    let download_service = Arc::new(rsws_service::create_download_service(
+        pool.clone(),
+        config_service.as_ref().clone(),
+    ));
     let admin_api_key_manager = rsws_service::create_admin_api_key_manager(redis_pool.clone());
     let user_api_key_manager = rsws_service::create_user_api_key_manager(redis_pool.clone());
     let wallet_repo = rsws_db::WalletRepository::new(pool.clone());
@@ -188,10 +284,55 @@ async fn main() -> Result<(), RswsError> {
     let paypal_service = Arc::new(rsws_service::create_paypal_service(paypal_db_config));
     let payment_service = rsws_service::create_payment_service(pool.clone());
 
+    // 总账与余额服务
+    let ledger_service = rsws_service::create_ledger_service(pool.clone());
+
+    // 发票服务 — 付款成功邮件附 HTML 收据
+    let invoice_service = Arc::new(rsws_service::create_invoice_service(
+        pool.clone(),
+        email_db_config.as_ref(),
+    ));
+
+    // 支付报价服务 — 下单时按通道费率与限额计算费用明细
+    let quote_service = Arc::new(rsws_service::create_quote_service(config_service.clone()));
+
+    // 订单风控服务 — 下单 / 支付时按规则评分，可疑订单转人工审核
+    let risk_service = rsws_service::create_risk_service(pool.clone());
+
+    // 推荐计划服务 — 推荐码、注册 / 下单归因、推荐人仪表盘
+    let referral_service = rsws_service::create_referral_service(pool.clone());
+
+    // 佣金服务 — 记录推荐佣金，冻结期过后结算到收款人余额
+    let commission_service = Arc::new(rsws_service::create_commission_service(
+        pool.clone(),
+        config_service.clone(),
+    ));
+
+    // 创作者收益服务 — 销售统计与月度对账单
+    let creator_service = rsws_service::create_creator_service(pool.clone());
+
+    // 资源审核服务 — 审核结果邮件通知创作者，审核通过后推送资源上架事件
+    let moderation_service = rsws_service::create_moderation_service(
+        pool.clone(),
+        email_db_config.as_ref(),
+        Some(cross_platform_service.clone()),
+    );
+
+    // 会员服务 — 到期提醒邮件复用 email_configs
+    let membership_service = Arc::new(rsws_service::create_membership_service(
+        pool.clone(),
+        quote_service.clone(),
+        email_db_config.as_ref(),
+    ));
+
+    // 支付宝 / 微信支付服务 — 配置从 DB 读取
+    let alipay_service = rsws_service::create_alipay_service(alipay_db_config);
+    let wechatpay_service = rsws_service::create_wechatpay_service(wechatpay_db_config);
+
     // 区块链服务 — 不再依赖 config.toml
     let blockchain_service = rsws_service::create_blockchain_service(wallet_repo);
-    let webhook_service = rsws_service::create_webhook_service(paypal_service.clone());
-    let cross_platform_service = rsws_service::create_cross_platform_service();
+    let webhook_service =
+        rsws_service::create_webhook_service(paypal_service.clone(), pool.clone());
 
     // Admin 服务
     let admin_repo = rsws_db::AdminRepository::new(pool.clone());
@@ -211,13 +352,32 @@ async fn main() -> Result<(), RswsError> {
         user_service,
         order_service_arc.as_ref().clone(),
         resource_service,
+        resource_bulk_service,
+        license_service,
+        resource_version_service.clone(),
+        rating_service,
+        wishlist_service.clone(),
+        tag_service,
+        recommendation_service.clone(),
+        download_service.clone(),
         admin_api_key_manager,
         user_api_key_manager,
         paypal_service,
+        alipay_service,
+        wechatpay_service,
         payment_service,
+        invoice_service.clone(),
+        ledger_service,
+        membership_service.clone(),
+        quote_service,
+        risk_service,
+        referral_service,
+        commission_service.clone(),
+        creator_service,
+        moderation_service,
         blockchain_service,
         webhook_service,
-        cross_platform_service,
+        cross_platform_service.clone(),
         config_service.clone(),
         admin_service,
         log_service,
@@ -264,6 +424,38 @@ async fn main() -> Result<(), RswsError> {
         warn!("USDT listener disabled (no active listen configs in database)");
     }
 
+    // 发票后台任务：为 USDT 链上确认等路径补开发票、补发付款成功邮件
+    invoice_service.start_background(60);
+    info!("Invoice background task started");
+
+    // 会员后台任务：激活遗漏的订阅、发送到期提醒
+    membership_service.start_background(300);
+    info!("Membership background task started");
+
+    // 出站事件 Webhook 投递任务：发送到期的投递队列记录
+    cross_platform_service.start_background(15);
+    info!("Event webhook delivery task started");
+
+    // 佣金结算任务：结算冻结期已过的推荐佣金与创作者分成
+    commission_service.start_background(3600);
+    info!("Commission settlement task started");
+
+    // 资源版本通知任务：邮件通知已购买的用户新版本发布
+    resource_version_service.start_background(300);
+    info!("Resource version notification task started");
+
+    // 降价通知任务：已收藏或加入心愿单的资源降价时邮件通知用户
+    wishlist_service.start_background(600);
+    info!("Wishlist price drop task started");
+
+    // 推荐重算任务：相关资源、买了还买与本周热门缓存
+    recommendation_service.start_background(3600);
+    info!("Recommendation refresh task started");
+
+    // 下载令牌清理任务：删除超过保留期的过期令牌
+    download_service.start_background(3600);
+    info!("Download token cleanup task started");
+
     // ========== 6. 启动 HTTP/HTTPS/HTTP3 服务 ==========
     let router = router::create_router(app_state);
 
@@ -4,6 +4,7 @@
 
 use crate::error::RswsError;
 use crate::error_code::ErrorCode;
+use lettre::message::{header::ContentType, Attachment, MessageBuilder, MultiPart, SinglePart};
 use lettre::transport::smtp::authentication::Credentials;
 use lettre::{Message, SmtpTransport, Transport};
 
@@ -47,9 +48,9 @@ impl EmailService {
         })
     }
 
-    /// 发送邮件
-    pub fn send(&self, to: &str, subject: &str, body: &str) -> Result<(), RswsError> {
-        let email = Message::builder()
+    /// 构造带发件人、收件人、主题的邮件
+    fn message_builder(&self, to: &str, subject: &str) -> Result<MessageBuilder, RswsError> {
+        Ok(Message::builder()
             .from(
                 self.from_email
                     .parse()
@@ -58,7 +59,13 @@ impl EmailService {
             .to(to
                 .parse()
                 .map_err(|e| RswsError::bad_request(format!("Invalid to email: {}", e)))?)
-            .subject(subject)
+            .subject(subject))
+    }
+
+    /// 发送邮件
+    pub fn send(&self, to: &str, subject: &str, body: &str) -> Result<(), RswsError> {
+        let email = self
+            .message_builder(to, subject)?
             .body(body.to_string())
             .map_err(|e| RswsError::internal(format!("Email build error: {}", e)))?;
 
@@ -69,6 +76,35 @@ impl EmailService {
         Ok(())
     }
 
+    /// 发送带附件的邮件（正文为纯文本）
+    pub fn send_with_attachment(
+        &self,
+        to: &str,
+        subject: &str,
+        body: &str,
+        filename: &str,
+        content_type: &str,
+        content: Vec<u8>,
+    ) -> Result<(), RswsError> {
+        let content_type = ContentType::parse(content_type)
+            .map_err(|e| RswsError::bad_request(format!("Invalid content type: {}", e)))?;
+
+        let email = self
+            .message_builder(to, subject)?
+            .multipart(
+                MultiPart::mixed()
+                    .singlepart(SinglePart::plain(body.to_string()))
+                    .singlepart(Attachment::new(filename.to_string()).body(content, content_type)),
+            )
+            .map_err(|e| RswsError::internal(format!("Email build error: {}", e)))?;
+
+        self.smtp_transport
+            .send(&email)
+            .map_err(|e| RswsError::internal(format!("Email send error: {}", e)))?;
+
+        Ok(())
+    }
+
     /// 发送验证码
     pub async fn send_verification_code(
         &self,
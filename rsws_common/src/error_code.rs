@@ -63,6 +63,11 @@ impl ErrorCode {
     pub const USER_PROFILE_INCOMPLETE: Self = Self(30009);
     pub const USER_DISABLED: Self = Self(30010);
 
+    // 心愿单错误 (301xx)
+    pub const WISHLIST_NOT_FOUND: Self = Self(30101);
+    pub const WISHLIST_NAME_EXISTS: Self = Self(30102);
+    pub const WISHLIST_LIMIT_REACHED: Self = Self(30103);
+
     // ==================== 资源错误 (4xxxx) ====================
     pub const RESOURCE_NOT_FOUND: Self = Self(40001);
     pub const RESOURCE_ALREADY_EXISTS: Self = Self(40002);
@@ -74,6 +79,17 @@ impl ErrorCode {
     pub const RESOURCE_NOT_OWNER: Self = Self(40008);
     pub const RESOURCE_PENDING_REVIEW: Self = Self(40009);
     pub const RESOURCE_REJECTED: Self = Self(40010);
+    pub const RESOURCE_REVIEW_ACTION_INVALID: Self = Self(40011);
+    pub const RESOURCE_VERSION_NOT_FOUND: Self = Self(40012);
+    pub const RESOURCE_VERSION_EXISTS: Self = Self(40013);
+    pub const RESOURCE_VERSION_NOT_ENTITLED: Self = Self(40014);
+    pub const RESOURCE_RATING_NOT_FOUND: Self = Self(40015);
+    pub const RESOURCE_RATING_NOT_PURCHASED: Self = Self(40016);
+    pub const RESOURCE_RATING_ALREADY_REPORTED: Self = Self(40017);
+    pub const TAG_NOT_FOUND: Self = Self(40018);
+    pub const TAG_EXISTS: Self = Self(40019);
+    pub const DOWNLOAD_TOKEN_INVALID: Self = Self(40020);
+    pub const DOWNLOAD_LIMIT_EXCEEDED: Self = Self(40021);
 
     // ==================== 订单错误 (5xxxx) ====================
     pub const ORDER_NOT_FOUND: Self = Self(50001);
@@ -84,6 +100,32 @@ impl ErrorCode {
     pub const ORDER_STATUS_INVALID: Self = Self(50006);
     pub const ORDER_AMOUNT_MISMATCH: Self = Self(50007);
     pub const ORDER_REFUND_FAILED: Self = Self(50008);
+    pub const ORDER_NOT_PAID: Self = Self(50009);
+    pub const INVOICE_NOT_FOUND: Self = Self(50010);
+
+    // 会员错误 (501xx)
+    pub const MEMBERSHIP_PLAN_NOT_FOUND: Self = Self(50101);
+    pub const MEMBERSHIP_PLAN_INACTIVE: Self = Self(50102);
+
+    // 授权档位错误 (502xx)
+    pub const LICENSE_NOT_FOUND: Self = Self(50201);
+    pub const LICENSE_TIER_INVALID: Self = Self(50202);
+    pub const LICENSE_ALREADY_OWNED: Self = Self(50203);
+    pub const LICENSE_DOWNLOAD_LIMIT_REACHED: Self = Self(50204);
+
+    // 风控错误 (503xx)
+    pub const ORDER_RISK_BLOCKED: Self = Self(50301);
+    pub const ORDER_RISK_REVIEW: Self = Self(50302);
+    pub const RISK_RULE_NOT_FOUND: Self = Self(50303);
+    pub const RISK_RULE_INVALID: Self = Self(50304);
+    pub const RISK_REVIEW_NOT_PENDING: Self = Self(50305);
+
+    // 推荐错误 (504xx)
+    pub const REFERRAL_CODE_NOT_FOUND: Self = Self(50401);
+
+    // 分成规则错误 (505xx)
+    pub const COMMISSION_RULE_NOT_FOUND: Self = Self(50501);
+    pub const COMMISSION_RULE_INVALID: Self = Self(50502);
 
     // ==================== 支付错误 (6xxxx) ====================
     pub const PAYMENT_METHOD_NOT_SUPPORTED: Self = Self(60001);
@@ -109,6 +151,29 @@ impl ErrorCode {
     pub const USDT_NETWORK_ERROR: Self = Self(60205);
     pub const USDT_WALLET_NOT_FOUND: Self = Self(60206);
 
+    // 支付宝特定错误 (603xx)
+    pub const ALIPAY_NOT_CONFIGURED: Self = Self(60301);
+    pub const ALIPAY_REQUEST_FAILED: Self = Self(60302);
+    pub const ALIPAY_NOTIFY_INVALID: Self = Self(60303);
+
+    // 微信支付特定错误 (604xx)
+    pub const WECHATPAY_NOT_CONFIGURED: Self = Self(60401);
+    pub const WECHATPAY_REQUEST_FAILED: Self = Self(60402);
+    pub const WECHATPAY_NOTIFY_INVALID: Self = Self(60403);
+    pub const WECHATPAY_DECRYPT_FAILED: Self = Self(60404);
+
+    // 余额 / 总账错误 (605xx)
+    pub const BALANCE_INSUFFICIENT: Self = Self(60501);
+    pub const TOPUP_NOT_FOUND: Self = Self(60502);
+    pub const LEDGER_ENTRY_INVALID: Self = Self(60503);
+
+    // Webhook 错误 (606xx)
+    pub const WEBHOOK_LOG_NOT_FOUND: Self = Self(60601);
+    pub const WEBHOOK_SOURCE_INVALID: Self = Self(60602);
+    pub const WEBHOOK_SUBSCRIPTION_NOT_FOUND: Self = Self(60603);
+    pub const WEBHOOK_SUBSCRIPTION_INVALID: Self = Self(60604);
+    pub const WEBHOOK_DELIVERY_NOT_FOUND: Self = Self(60605);
+
     // ==================== 配置错误 (7xxxx) ====================
     pub const CONFIG_NOT_FOUND: Self = Self(70001);
     pub const CONFIG_INVALID_VALUE: Self = Self(70002);
@@ -179,6 +244,9 @@ impl ErrorCode {
             30008 => "Invalid avatar file type",
             30009 => "Profile incomplete",
             30010 => "User disabled",
+            30101 => "Wishlist not found",
+            30102 => "Wishlist name already exists",
+            30103 => "Wishlist limit reached",
 
             // 资源
             40001 => "Resource not found",
@@ -191,6 +259,17 @@ impl ErrorCode {
             40008 => "Not resource owner",
             40009 => "Resource pending review",
             40010 => "Resource rejected",
+            40011 => "Review action not allowed in current status",
+            40012 => "Resource version not found",
+            40013 => "Resource version already exists",
+            40014 => "Version released after your update period",
+            40015 => "Rating not found",
+            40016 => "Only buyers of this resource can rate it",
+            40017 => "Rating already reported",
+            40018 => "Tag not found",
+            40019 => "Tag already exists",
+            40020 => "Download link is invalid or expired",
+            40021 => "Daily download limit reached",
 
             // 订单
             50001 => "Order not found",
@@ -201,6 +280,32 @@ impl ErrorCode {
             50006 => "Invalid order status",
             50007 => "Amount mismatch",
             50008 => "Refund failed",
+            50009 => "Order not paid",
+            50010 => "Invoice not found",
+
+            // 会员
+            50101 => "Membership plan not found",
+            50102 => "Membership plan is not available",
+
+            // 授权档位
+            50201 => "License not found",
+            50202 => "Invalid license tier",
+            50203 => "License already owned",
+            50204 => "Download limit reached for this license",
+
+            // 风控
+            50301 => "Order blocked by risk control",
+            50302 => "Order is under risk review",
+            50303 => "Risk rule not found",
+            50304 => "Invalid risk rule",
+            50305 => "Order is not pending risk review",
+
+            // 推荐
+            50401 => "Referral code not found",
+
+            // 分成规则
+            50501 => "Commission rule not found",
+            50502 => "Invalid commission rule",
 
             // 支付
             60001 => "Payment method not supported",
@@ -226,6 +331,29 @@ impl ErrorCode {
             60205 => "USDT network error",
             60206 => "USDT wallet not found",
 
+            // 支付宝
+            60301 => "Alipay not configured",
+            60302 => "Alipay request failed",
+            60303 => "Invalid Alipay notify",
+
+            // 微信支付
+            60401 => "WeChat Pay not configured",
+            60402 => "WeChat Pay request failed",
+            60403 => "Invalid WeChat Pay notify",
+            60404 => "WeChat Pay resource decrypt failed",
+
+            // 余额 / 总账
+            60501 => "Insufficient balance",
+            60502 => "Top-up not found",
+            60503 => "Invalid ledger entry",
+
+            // Webhook
+            60601 => "Webhook log not found",
+            60602 => "Unknown webhook source",
+            60603 => "Webhook subscription not found",
+            60604 => "Invalid webhook subscription",
+            60605 => "Webhook delivery not found",
+
             // 配置
             70001 => "Config not found",
             70002 => "Invalid config value",
@@ -1,7 +1,8 @@
-﻿//! 签名服务
+//! 签名服务
 //!
 //! 基于 HMAC-SHA256 的请求签名服务（旧方案，保留兼容）
 //! 基于 MD5 的 Cregis 签名算法（当前方案）
+//! 基于 SHA256withRSA 的第三方支付签名（支付宝 RSA2 / 微信支付 API v3）
 
 use crate::error::RswsError;
 use base64::{engine::general_purpose, Engine as _};
@@ -141,6 +142,94 @@ impl ClientSignature {
     }
 }
 
+// ==================== SHA256withRSA 签名（支付宝 / 微信支付） ====================
+
+/// 解析 RSA 私钥
+///
+/// 支持 PEM（PKCS#8 / PKCS#1）以及支付宝开放平台导出的裸 base64（PKCS#8 / PKCS#1 DER）。
+pub fn parse_rsa_private_key(key: &str) -> Result<rsa::RsaPrivateKey, RswsError> {
+    use rsa::pkcs1::DecodeRsaPrivateKey;
+    use rsa::pkcs8::DecodePrivateKey;
+
+    let key = key.trim();
+    if key.starts_with("-----BEGIN") {
+        return rsa::RsaPrivateKey::from_pkcs8_pem(key)
+            .or_else(|_| rsa::RsaPrivateKey::from_pkcs1_pem(key))
+            .map_err(|e| RswsError::internal(format!("Invalid RSA private key: {}", e)));
+    }
+
+    let der = decode_bare_base64(key)?;
+    rsa::RsaPrivateKey::from_pkcs8_der(&der)
+        .or_else(|_| rsa::RsaPrivateKey::from_pkcs1_der(&der))
+        .map_err(|e| RswsError::internal(format!("Invalid RSA private key: {}", e)))
+}
+
+/// 解析 RSA 公钥
+///
+/// 支持 PEM（SPKI / PKCS#1）以及裸 base64（SPKI / PKCS#1 DER）。
+pub fn parse_rsa_public_key(key: &str) -> Result<rsa::RsaPublicKey, RswsError> {
+    use rsa::pkcs1::DecodeRsaPublicKey;
+    use rsa::pkcs8::DecodePublicKey;
+
+    let key = key.trim();
+    if key.starts_with("-----BEGIN") {
+        return rsa::RsaPublicKey::from_public_key_pem(key)
+            .or_else(|_| rsa::RsaPublicKey::from_pkcs1_pem(key))
+            .map_err(|e| RswsError::internal(format!("Invalid RSA public key: {}", e)));
+    }
+
+    let der = decode_bare_base64(key)?;
+    rsa::RsaPublicKey::from_public_key_der(&der)
+        .or_else(|_| rsa::RsaPublicKey::from_pkcs1_der(&der))
+        .map_err(|e| RswsError::internal(format!("Invalid RSA public key: {}", e)))
+}
+
+/// 解码去掉换行的裸 base64 密钥
+fn decode_bare_base64(key: &str) -> Result<Vec<u8>, RswsError> {
+    let compact: String = key.chars().filter(|c| !c.is_whitespace()).collect();
+    general_purpose::STANDARD
+        .decode(compact)
+        .map_err(|e| RswsError::internal(format!("Invalid base64 key: {}", e)))
+}
+
+/// SHA256withRSA（PKCS#1 v1.5）签名，返回 base64
+pub fn rsa_sha256_sign(private_key: &str, message: &[u8]) -> Result<String, RswsError> {
+    use rsa::signature::{SignatureEncoding, Signer};
+
+    let key = parse_rsa_private_key(private_key)?;
+    let signing_key = rsa::pkcs1v15::SigningKey::<Sha256>::new(key);
+    let signature = signing_key
+        .try_sign(message)
+        .map_err(|e| RswsError::internal(format!("RSA sign failed: {}", e)))?;
+
+    Ok(general_purpose::STANDARD.encode(signature.to_bytes()))
+}
+
+/// SHA256withRSA（PKCS#1 v1.5）验签，signature 为 base64
+///
+/// 签名格式错误或不匹配均返回 `Ok(false)`，仅公钥无法解析时返回错误。
+pub fn rsa_sha256_verify(
+    public_key: &str,
+    message: &[u8],
+    signature: &str,
+) -> Result<bool, RswsError> {
+    use rsa::signature::Verifier;
+
+    let key = parse_rsa_public_key(public_key)?;
+    let verifying_key = rsa::pkcs1v15::VerifyingKey::<Sha256>::new(key);
+
+    let sig_bytes = match general_purpose::STANDARD.decode(signature.trim()) {
+        Ok(b) => b,
+        Err(_) => return Ok(false),
+    };
+    let sig = match rsa::pkcs1v15::Signature::try_from(sig_bytes.as_slice()) {
+        Ok(s) => s,
+        Err(_) => return Ok(false),
+    };
+
+    Ok(verifying_key.verify(message, &sig).is_ok())
+}
+
 // ==================== 单元测试 ====================
 
 #[cfg(test)]
@@ -224,4 +313,54 @@ mod tests {
         assert!(timestamp > 0);
         assert!(!nonce.is_empty());
     }
+
+    // 测试密钥与期望签名由 OpenSSL 生成：
+    // openssl dgst -sha256 -sign key.pem msg.txt | base64
+    const TEST_RSA_PRIVATE_KEY: &str = "MIICdgIBADANBgkqhkiG9w0BAQEFAASCAmAwggJcAgEAAoGBAPjZzjBjDtKQv6RYciRQHsl+fHiYhVWw7a46u9k2MObYd0hNPRGqxTW3X4X8bdt1r8xqpz86epyYyaFW+hwToQumY3j9jmFGufwMxIb1fcQRjd+OlFiK82V4wBNA64qT0vFIUFdVnfRVCBW3yfTQJwam7jR4hIt2YwKoej+GrP8BAgMBAAECgYAlvzwUgOIdtJEtR7FAqtqQq9wLmu8WuXjEJeqanyq5yXoGgJjT1jiF1mPp+glb8bUR59eosqEoodBr5bqYia2yvx49f4gIBrAy9bMfHoPesy92KJ2HTpK0eYmqnEwfkGPL+Uz34IjZc4lQ68oiSA+A3BMm34WEGmagAsSaw6A0lQJBAP6x29OBsfglvYw01C3gD/t0zaZcM9arbepETIV8BWMYHIK6t0yEpH3SyNe64CnphGgcrXzMhVZUklkVkgP8SscCQQD6IEel4IGAYKV2BtTb4hG+o+PIjW+P/w/iyq0pU3RIq3k/Nl042ZoZbCPgG+c+Y2RVHo17Lk/usmqb3FVUd1/3AkBBH0763J2ZumSl1dguxqyveeUfVVH34KthDAeY1eY2uTKJBp7ZlMzOUzgBWQn6DXhKepKtZ0nskgE9f/Nuy3J/AkEAjv7DV06pWpZmIbq4GFj/IgkaIT7Sp/T+xP5vzq96TE1TzNKsquKkWaJcRDRPmHhEFPGZmvGYGvN/RUnKsrZocwJAIkVVgfKGT/hNq3o5GtwvFGnnTBoueKcZyK878iu/7iaxfbJAoETeYHpdQFL9iSK1MPNy/5IPwgs3s2L9pKJc2A==";
+
+    const TEST_RSA_PUBLIC_KEY: &str = "-----BEGIN PUBLIC KEY-----
+MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQKBgQD42c4wYw7SkL+kWHIkUB7Jfnx4
+mIVVsO2uOrvZNjDm2HdITT0RqsU1t1+F/G3bda/Maqc/OnqcmMmhVvocE6ELpmN4
+/Y5hRrn8DMSG9X3EEY3fjpRYivNleMATQOuKk9LxSFBXVZ30VQgVt8n00CcGpu40
+eISLdmMCqHo/hqz/AQIDAQAB
+-----END PUBLIC KEY-----";
+
+    #[test]
+    fn test_rsa_sha256_sign_alipay_vector() {
+        // 支付宝开放平台文档中的待签名字符串示例
+        let content = r#"app_id=2014072300007148&biz_content={"button":[{"actionParam":"ZFB_HFCZ","actionType":"out","name":"话费充值"}]}&charset=GBK&method=alipay.mobile.public.menu.add&sign_type=RSA2&timestamp=2014-07-24 03:07:50&version=1.0"#;
+        let expected = "PVZ/wC5xdT0w24b4igFxE9kZK3U4ZGZZgp3be254Pe6tglJe3ocJkYsylFd1gdmf++mKfbbyRTgchy72S1VEPhicEPH6pdtNEiDC2Ju2N6x+LAwQrgiFEwrZ8jHquI3gw7eDhxI1bCS9+3Gdr6R/aN+LU+jaYNIDcjf6eoR9CaE=";
+
+        let signature =
+            rsa_sha256_sign(TEST_RSA_PRIVATE_KEY, content.as_bytes()).expect("Sign failed");
+        assert_eq!(signature, expected);
+
+        let valid = rsa_sha256_verify(TEST_RSA_PUBLIC_KEY, content.as_bytes(), expected)
+            .expect("Verify failed");
+        assert!(valid);
+    }
+
+    #[test]
+    fn test_rsa_sha256_verify_wechatpay_vector() {
+        // 微信支付 API v3 文档中的签名串示例
+        let message = "GET\n/v3/certificates\n1554208460\n593BEC0C930BF1AFEB40B4A08C8FB242\n\n";
+        let signature = "1ZYceMVwmirg5dKI0Kc/eLXaahgtinHds8xX8+TjQODy8KdnrInkvwZj99gBP95afECcSrD6NrNBTuf+faErRkxBAbo/u5I/f7cmlJm16ogi6iFG09VC6QsS11KtLmQB2PfpMPWKtoQkMBbDkGSVogBqlq1RHj2HiUnnSb+2fZA=";
+
+        assert!(rsa_sha256_verify(TEST_RSA_PUBLIC_KEY, message.as_bytes(), signature).unwrap());
+
+        // 篡改消息后验签失败
+        let tampered = message.replace("1554208460", "1554208461");
+        assert!(!rsa_sha256_verify(TEST_RSA_PUBLIC_KEY, tampered.as_bytes(), signature).unwrap());
+
+        // 非法 base64 签名视为验签失败而非错误
+        assert!(
+            !rsa_sha256_verify(TEST_RSA_PUBLIC_KEY, message.as_bytes(), "not-base64!").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_rsa_key_invalid() {
+        assert!(parse_rsa_private_key("invalid").is_err());
+        assert!(parse_rsa_public_key("").is_err());
+    }
 }
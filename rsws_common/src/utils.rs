@@ -14,3 +14,73 @@ pub fn generate_api_key() -> String {
         base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(api_key_bytes)
     )
 }
+
+/// Generate outbound webhook signing secret
+///
+/// Format: whsec_ + base64(random 32 bytes)
+pub fn generate_webhook_secret() -> String {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    let mut rng = StdRng::from_os_rng();
+    let secret_bytes: [u8; 32] = rng.random();
+    format!(
+        "whsec_{}",
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(secret_bytes)
+    )
+}
+
+/// Generate download token
+///
+/// Format: dl_ + base64(random 32 bytes); only its SHA-256 hash is stored
+pub fn generate_download_token() -> String {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    let mut rng = StdRng::from_os_rng();
+    let token_bytes: [u8; 32] = rng.random();
+    format!(
+        "dl_{}",
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(token_bytes)
+    )
+}
+
+/// Generate referral code
+///
+/// Format: 8 uppercase letters / digits, excluding look-alike characters (0/O, 1/I/L)
+pub fn generate_referral_code() -> String {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    const CHARSET: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ23456789";
+    let mut rng = StdRng::from_os_rng();
+    (0..8)
+        .map(|_| CHARSET[rng.random_range(0..CHARSET.len())] as char)
+        .collect()
+}
+
+/// Escape a CSV field (RFC 4180)
+///
+/// Cells starting with a formula character are prefixed with `'` so spreadsheet
+/// applications do not evaluate them.
+pub fn csv_field(s: &str) -> String {
+    let s = if s.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", s)
+    } else {
+        s.to_string()
+    };
+    if s.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("=SUM(A1)"), "'=SUM(A1)");
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+    }
+}
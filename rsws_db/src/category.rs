@@ -3,6 +3,7 @@ use rsws_common::snowflake;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use sqlx::PgPool;
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Category {
@@ -22,9 +23,37 @@ pub struct Category {
 pub struct CategoryTreeNode {
     #[serde(flatten)]
     pub category: Category,
+    /// 含子分类的资源数
+    pub resource_count: i64,
     pub children: Vec<CategoryTreeNode>,
 }
 
+/// 分类资源数
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CategoryResourceCount {
+    pub category_id: i64,
+    /// 直接归属该分类的资源数
+    pub direct_count: i64,
+    /// 含全部子分类的资源数
+    pub total_count: i64,
+}
+
+/// 移动分类的结果
+#[derive(Debug)]
+pub enum CategoryMove {
+    Moved(Category),
+    NotFound,
+    ParentNotFound,
+    /// 目标父分类是自身或其后代
+    Cycle,
+}
+
+/// 公开分类树缓存 key（分类变更后删除）
+pub const CATEGORY_TREE_CACHE_KEY: &str = "category_tree";
+
+/// 公开分类树缓存时间（秒），资源数变化最多延迟这么久
+pub const CATEGORY_TREE_CACHE_TTL: u64 = 300;
+
 #[derive(Debug, Deserialize)]
 pub struct CreateCategory {
     pub name: String,
@@ -102,6 +131,7 @@ impl CategoryRepository {
         Ok(result.unwrap_or(0))
     }
 
+    /// 创建分类（path 由父分类 path 拼接自身 ID）
     pub async fn create(
         &self,
         name: &str,
@@ -111,8 +141,8 @@ impl CategoryRepository {
     ) -> Result<Category, sqlx::Error> {
         let new_id = snowflake::next_id();
         let category = sqlx::query_as::<_, Category>(
-            r#"INSERT INTO categories (id, name, description, parent_id, sort_order)
-            VALUES ($1, $2, $3, $4, $5)
+            r#"INSERT INTO categories (id, name, description, parent_id, sort_order, path)
+            VALUES ($1, $2, $3, $4, $5, COALESCE((SELECT path FROM categories WHERE id = $4), '/') || $1 || '/')
             RETURNING id, name, description, parent_id, path, sort_order, is_active, created_at, updated_at"#,
         )
         .bind(new_id)
@@ -123,37 +153,15 @@ impl CategoryRepository {
         .fetch_one(&self.pool)
         .await?;
 
-        // Compute path with the actual id
-        let actual_path = if let Some(pid) = parent_id {
-            let parent = self.find_by_id(pid).await?;
-            let base = parent
-                .and_then(|p| p.path)
-                .unwrap_or_else(|| format!("/{}", pid));
-            format!("{}{}/", base, category.id)
-        } else {
-            format!("/{}/", category.id)
-        };
-
-        // Update path in DB
-        let category = sqlx::query_as::<_, Category>(
-            r#"UPDATE categories SET path = $1 WHERE id = $2
-            RETURNING id, name, description, parent_id, path, sort_order, is_active, created_at, updated_at"#,
-        )
-        .bind(&actual_path)
-        .bind(category.id)
-        .fetch_one(&self.pool)
-        .await?;
-
         Ok(category)
     }
 
-    #[allow(clippy::too_many_arguments)]
+    /// 更新分类属性（移动分类使用 [`Self::move_to`]）
     pub async fn update(
         &self,
         id: i64,
         name: Option<&str>,
         description: Option<Option<&str>>,
-        parent_id: Option<Option<i64>>,
         sort_order: Option<i32>,
         is_active: Option<bool>,
     ) -> Result<Option<Category>, sqlx::Error> {
@@ -163,83 +171,104 @@ impl CategoryRepository {
             None => return Ok(None),
         };
 
-        // Resolve new parent_id
-        let new_parent_id = match &parent_id {
-            Some(Some(pid)) => {
-                if *pid == id {
-                    return Err(sqlx::Error::RowNotFound);
-                }
-                let parent_cat = self.find_by_id(*pid).await?;
-                if let Some(p) = &parent_cat {
-                    if let Some(ref p_path) = p.path {
-                        if p_path.contains(&format!("/{}/", id)) {
-                            return Err(sqlx::Error::RowNotFound);
-                        }
-                    }
-                }
-                Some(*pid)
-            }
-            Some(None) => None,
-            None => cat.parent_id,
-        };
-
         let new_name = name.unwrap_or(&cat.name);
         let new_description = description.unwrap_or(cat.description.as_deref());
         let new_sort_order = sort_order.unwrap_or(cat.sort_order);
         let new_is_active = is_active.unwrap_or(cat.is_active);
 
-        // Compute new path if parent changed
-        let new_path = if parent_id.is_some() {
-            match new_parent_id {
-                Some(pid) => {
-                    let parent = self.find_by_id(pid).await?;
-                    let base = parent
-                        .and_then(|p| p.path)
-                        .unwrap_or_else(|| format!("/{}", pid));
-                    format!("{}{}/", base, id)
-                }
-                None => format!("/{}/", id),
-            }
-        } else {
-            cat.path.unwrap_or_else(|| format!("/{}/", id))
-        };
-
         let category = sqlx::query_as::<_, Category>(
-            r#"UPDATE categories SET name = $1, description = $2, parent_id = $3, path = $4, sort_order = $5, is_active = $6, updated_at = NOW()
-            WHERE id = $7
+            r#"UPDATE categories SET name = $1, description = $2, sort_order = $3, is_active = $4, updated_at = NOW()
+            WHERE id = $5
             RETURNING id, name, description, parent_id, path, sort_order, is_active, created_at, updated_at"#,
         )
         .bind(new_name)
         .bind(new_description)
-        .bind(new_parent_id)
-        .bind(&new_path)
         .bind(new_sort_order)
         .bind(new_is_active)
         .bind(id)
         .fetch_optional(&self.pool)
         .await?;
 
-        // Rebuild descendant paths if parent changed
-        if parent_id.is_some() {
-            self.rebuild_descendant_paths(id, &new_path).await.ok();
-        }
-
         Ok(category)
     }
 
+    /// 移动分类（连同全部子分类）到新的父分类下，`None` 表示移为根分类
+    ///
+    /// 在一个事务内替换整棵子树的 path 前缀；目标父分类为自身或其后代时拒绝。
+    pub async fn move_to(
+        &self,
+        id: i64,
+        parent_id: Option<i64>,
+    ) -> Result<CategoryMove, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        // 串行化树结构变更，避免并发移动形成环（不阻塞读取）
+        sqlx::query("LOCK TABLE categories IN SHARE ROW EXCLUSIVE MODE")
+            .execute(&mut *tx)
+            .await?;
+
+        let Some(old_path) = Self::path_in_tx(&mut tx, id).await? else {
+            return Ok(CategoryMove::NotFound);
+        };
+        let parent_path = match parent_id {
+            Some(pid) => {
+                let Some(parent_path) = Self::path_in_tx(&mut tx, pid).await? else {
+                    return Ok(CategoryMove::ParentNotFound);
+                };
+                if is_in_subtree(&parent_path, &old_path) {
+                    return Ok(CategoryMove::Cycle);
+                }
+                parent_path
+            }
+            None => "/".to_string(),
+        };
+        let new_path = format!("{}{}/", parent_path, id);
+
+        sqlx::query(
+            "UPDATE categories SET path = $2 || substr(path, length($1) + 1) WHERE path LIKE $1 || '%'",
+        )
+        .bind(&old_path)
+        .bind(&new_path)
+        .execute(&mut *tx)
+        .await?;
+
+        let category = sqlx::query_as::<_, Category>(
+            r#"UPDATE categories SET parent_id = $2, updated_at = NOW() WHERE id = $1
+            RETURNING id, name, description, parent_id, path, sort_order, is_active, created_at, updated_at"#,
+        )
+        .bind(id)
+        .bind(parent_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(CategoryMove::Moved(category))
+    }
+
+    /// 删除分类，子分类成为根分类（path 去掉被删分类及其祖先）
     pub async fn delete(&self, id: i64) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("LOCK TABLE categories IN SHARE ROW EXCLUSIVE MODE")
+            .execute(&mut *tx)
+            .await?;
+
+        let Some(path) = Self::path_in_tx(&mut tx, id).await? else {
+            return Ok(false);
+        };
+
         // Children's parent_id will be set to NULL by ON DELETE SET NULL
-        let result = sqlx::query("DELETE FROM categories WHERE id = $1")
+        sqlx::query("DELETE FROM categories WHERE id = $1")
             .bind(id)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await?;
-        if result.rows_affected() > 0 {
-            // Rebuild paths for orphaned children
-            self.rebuild_descendant_paths(id, &format!("/{}/", id))
-                .await
-                .ok();
-        }
-        Ok(result.rows_affected() > 0)
+        sqlx::query(
+            "UPDATE categories SET path = '/' || substr(path, length($1) + 1) WHERE path LIKE $1 || '%'",
+        )
+        .bind(&path)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
     }
 
     /// 批量更新排序
@@ -265,85 +294,114 @@ impl CategoryRepository {
         Ok(count)
     }
 
-    /// 重建所有后代的 path
-    async fn rebuild_descendant_paths(
+    /// 各分类的资源数（直接归属 / 含子分类）
+    ///
+    /// `visible_only` 为 true 时只统计已上架且审核通过的资源与启用的分类（前台展示用）。
+    pub async fn resource_counts(
         &self,
-        parent_id: i64,
-        parent_path: &str,
-    ) -> Result<(), sqlx::Error> {
-        let children: Vec<Category> = sqlx::query_as::<_, Category>(
-            r#"SELECT id, name, description, parent_id, path, sort_order, is_active, created_at, updated_at
-            FROM categories WHERE parent_id = $1"#,
+        visible_only: bool,
+    ) -> Result<Vec<CategoryResourceCount>, sqlx::Error> {
+        let counts = sqlx::query_as::<_, CategoryResourceCount>(
+            r#"SELECT c.id AS category_id,
+                COUNT(r.id) FILTER (WHERE r.category_id = c.id) AS direct_count,
+                COUNT(r.id) AS total_count
+            FROM categories c
+            JOIN categories d ON d.path LIKE c.path || '%'
+            LEFT JOIN resources r ON r.category_id = d.id AND r.is_active = true
+                AND ($1 = false OR r.review_status = 'approved')
+            WHERE $1 = false OR d.is_active = true
+            GROUP BY c.id"#,
         )
-        .bind(parent_id)
+        .bind(visible_only)
         .fetch_all(&self.pool)
         .await?;
+        Ok(counts)
+    }
 
-        for child in children {
-            let child_path = format!("{}{}/", parent_path, child.id);
-            sqlx::query("UPDATE categories SET path = $1 WHERE id = $2")
-                .bind(&child_path)
-                .bind(child.id)
-                .execute(&self.pool)
-                .await?;
-            // Recursively rebuild
-            Box::pin(self.rebuild_descendant_paths(child.id, &child_path)).await?;
-        }
-        Ok(())
+    /// 面包屑：从根分类到该分类（含自身）
+    pub async fn ancestors(&self, id: i64) -> Result<Vec<Category>, sqlx::Error> {
+        let categories = sqlx::query_as::<_, Category>(
+            r#"SELECT a.id, a.name, a.description, a.parent_id, a.path, a.sort_order, a.is_active, a.created_at, a.updated_at
+            FROM categories c
+            JOIN categories a ON c.path LIKE a.path || '%'
+            WHERE c.id = $1
+            ORDER BY length(a.path)"#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(categories)
+    }
+
+    /// 全部后代分类（不含自身），按层级、排序返回
+    ///
+    /// `active_only` 为 true 时排除已停用的分类及其子树。
+    pub async fn descendants(
+        &self,
+        id: i64,
+        active_only: bool,
+    ) -> Result<Vec<Category>, sqlx::Error> {
+        let categories = sqlx::query_as::<_, Category>(
+            r#"SELECT d.id, d.name, d.description, d.parent_id, d.path, d.sort_order, d.is_active, d.created_at, d.updated_at
+            FROM categories c
+            JOIN categories d ON d.path LIKE c.path || '%' AND d.id <> c.id
+            WHERE c.id = $1
+              AND ($2 = false OR NOT EXISTS (
+                  SELECT 1 FROM categories a
+                  WHERE a.is_active = false AND d.path LIKE a.path || '%' AND a.path LIKE c.path || '%'
+              ))
+            ORDER BY length(d.path) - length(replace(d.path, '/', '')), d.sort_order, d.id"#,
+        )
+        .bind(id)
+        .bind(active_only)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(categories)
     }
 
-    /// 构建分类树（从扁平列表）
-    pub fn build_tree(categories: &[Category]) -> Vec<CategoryTreeNode> {
-        let mut root_nodes: Vec<CategoryTreeNode> = Vec::new();
-        let mut node_map: std::collections::HashMap<i64, CategoryTreeNode> =
-            std::collections::HashMap::new();
+    /// 事务内读取分类 path
+    async fn path_in_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: i64,
+    ) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar::<_, String>("SELECT path FROM categories WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await
+    }
 
-        // Create all nodes
+    /// 构建分类树（从按排序排列的扁平列表，保持同级顺序）
+    ///
+    /// `counts` 为各分类含子分类的资源数；父分类不在列表中（如已停用）的分类不出现在树中。
+    pub fn build_tree(
+        categories: &[Category],
+        counts: &HashMap<i64, i64>,
+    ) -> Vec<CategoryTreeNode> {
+        let mut children: HashMap<Option<i64>, Vec<&Category>> = HashMap::new();
         for cat in categories {
-            node_map.insert(
-                cat.id,
-                CategoryTreeNode {
-                    category: cat.clone(),
-                    children: Vec::new(),
-                },
-            );
+            children.entry(cat.parent_id).or_default().push(cat);
         }
 
-        // Build tree
-        let mut children_map: std::collections::HashMap<i64, Vec<CategoryTreeNode>> =
-            std::collections::HashMap::new();
-        let mut ids: Vec<i64> = node_map.keys().cloned().collect();
-        ids.sort();
-
-        for id in ids {
-            if let Some(node) = node_map.remove(&id) {
-                let pid = node.category.parent_id;
-                match pid {
-                    Some(pid) => {
-                        children_map.entry(pid).or_default().push(node);
-                    }
-                    None => {
-                        root_nodes.push(node);
-                    }
-                }
-            }
+        fn build(
+            parent_id: Option<i64>,
+            children: &HashMap<Option<i64>, Vec<&Category>>,
+            counts: &HashMap<i64, i64>,
+        ) -> Vec<CategoryTreeNode> {
+            children
+                .get(&parent_id)
+                .map(|cats| {
+                    cats.iter()
+                        .map(|cat| CategoryTreeNode {
+                            category: (*cat).clone(),
+                            resource_count: counts.get(&cat.id).copied().unwrap_or(0),
+                            children: build(Some(cat.id), children, counts),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default()
         }
 
-        // Attach children to parents
-        fn attach_children(
-            nodes: &mut [CategoryTreeNode],
-            children_map: &std::collections::HashMap<i64, Vec<CategoryTreeNode>>,
-        ) {
-            for node in nodes.iter_mut() {
-                if let Some(children) = children_map.get(&node.category.id) {
-                    node.children = children.clone();
-                    attach_children(&mut node.children, children_map);
-                }
-            }
-        }
-        attach_children(&mut root_nodes, &children_map);
-
-        root_nodes
+        build(None, &children, counts)
     }
 
     /// 获取指定父分类下的最大 sort_order
@@ -360,3 +418,59 @@ impl CategoryRepository {
         Ok(result.unwrap_or(0))
     }
 }
+
+/// `path` 是否位于以 `root_path` 为根的子树中（含根自身）
+fn is_in_subtree(path: &str, root_path: &str) -> bool {
+    path.starts_with(root_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category(id: i64, parent_id: Option<i64>, path: &str) -> Category {
+        Category {
+            id,
+            name: format!("c{}", id),
+            description: None,
+            parent_id,
+            path: Some(path.to_string()),
+            sort_order: 0,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_is_in_subtree() {
+        assert!(is_in_subtree("/1/2/3/", "/1/2/"));
+        assert!(is_in_subtree("/1/2/", "/1/2/"));
+        assert!(!is_in_subtree("/1/22/", "/1/2/"));
+        assert!(!is_in_subtree("/1/", "/1/2/"));
+    }
+
+    #[test]
+    fn test_build_tree() {
+        // 同级保持输入顺序；父分类不在列表中的分类被丢弃
+        let categories = vec![
+            category(3, None, "/3/"),
+            category(1, None, "/1/"),
+            category(5, Some(1), "/1/5/"),
+            category(4, Some(1), "/1/4/"),
+            category(6, Some(4), "/1/4/6/"),
+            category(8, Some(7), "/7/8/"),
+        ];
+        let counts = HashMap::from([(1, 9), (4, 2)]);
+        let tree = CategoryRepository::build_tree(&categories, &counts);
+
+        let roots: Vec<i64> = tree.iter().map(|n| n.category.id).collect();
+        assert_eq!(roots, vec![3, 1]);
+        assert_eq!(tree[1].resource_count, 9);
+        let children: Vec<i64> = tree[1].children.iter().map(|n| n.category.id).collect();
+        assert_eq!(children, vec![5, 4]);
+        assert_eq!(tree[1].children[1].resource_count, 2);
+        assert_eq!(tree[1].children[1].children[0].category.id, 6);
+        assert_eq!(tree[0].resource_count, 0);
+    }
+}
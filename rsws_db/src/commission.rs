@@ -0,0 +1,485 @@
+//! 创作者分成与收益仓储层
+//!
+//! 分成规则管理、创作者等级、按规则计算分成，以及创作者收益统计。
+
+use chrono::{DateTime, Utc};
+use rsws_common::error::RswsError;
+use rsws_common::snowflake;
+use rsws_model::commission::{
+    compute_commission, CommissionContext, CommissionQuote, CommissionRule,
+    CreateCommissionRuleRequest, UpdateCommissionRuleRequest, DEFAULT_CREATOR_TIER,
+};
+use rsws_model::creator::{CreatorEarningsSummary, ResourceSales, StatementLine, StatementMonth};
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, PgPool};
+
+const RULE_COLUMNS: &str = "id, name, rule_type, rate, category_id, creator_tier, min_amount, max_amount, starts_at, ends_at, priority, min_commission, max_commission, is_active, description, created_at, updated_at";
+
+/// 分类及其全部上级分类（$1 = 分类 ID，由近及远，最多 32 层）
+const CATEGORY_ANCESTORS: &str = r#"
+    WITH RECURSIVE ancestors(id, parent_id, depth) AS (
+        SELECT id, parent_id, 0 FROM categories WHERE id = $1
+        UNION ALL
+        SELECT c.id, c.parent_id, a.depth + 1
+        FROM categories c
+        JOIN ancestors a ON c.id = a.parent_id
+        WHERE a.depth < 32
+    )
+    SELECT id FROM ancestors ORDER BY depth
+"#;
+
+/// 创作者名下已支付订单的销售明细（$1 = 创作者 ID）
+///
+/// 销售额不含附加费；无分成记录（分成比例为 0）时净收入为 0。
+const CREATOR_SALES: &str = r#"
+    SELECT o.id AS order_id, o.paid_at, r.id AS resource_id, r.title AS resource_title,
+           (o.amount - o.surcharge) AS gross,
+           (o.amount - o.surcharge) - COALESCE(c.commission_amount, 0) AS platform_commission,
+           COALESCE(c.commission_amount, 0) AS net,
+           c.status AS commission_status
+    FROM orders o
+    JOIN resources r ON r.id = o.resource_id
+    LEFT JOIN commission_records c
+        ON c.order_id = o.id AND c.referrer_id IS NULL AND c.status <> 'cancelled'
+    WHERE r.provider_id = $1 AND o.status::TEXT IN ('paid', 'completed')
+"#;
+
+/// 创作者分成与收益仓储
+pub struct CommissionRepository {
+    pool: PgPool,
+}
+
+impl CommissionRepository {
+    /// 创建创作者分成仓储实例
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // ==================== 分成规则 ====================
+
+    /// 获取全部规则
+    pub async fn list_rules(&self) -> Result<Vec<CommissionRule>, RswsError> {
+        sqlx::query_as::<_, CommissionRule>(&format!(
+            "SELECT {} FROM commission_rules ORDER BY is_active DESC, priority DESC, id",
+            RULE_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list commission rules: {}", e)))
+    }
+
+    /// 根据 ID 获取规则
+    pub async fn find_rule(&self, id: i64) -> Result<Option<CommissionRule>, RswsError> {
+        sqlx::query_as::<_, CommissionRule>(&format!(
+            "SELECT {} FROM commission_rules WHERE id = $1",
+            RULE_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load commission rule: {}", e)))
+    }
+
+    /// 创建规则
+    pub async fn create_rule(
+        &self,
+        req: &CreateCommissionRuleRequest,
+        rule_type: &str,
+        creator_tier: Option<&str>,
+    ) -> Result<CommissionRule, RswsError> {
+        sqlx::query_as::<_, CommissionRule>(&format!(
+            r#"
+            INSERT INTO commission_rules
+                (id, name, rule_type, rate, category_id, creator_tier, min_amount, max_amount,
+                 starts_at, ends_at, priority, min_commission, max_commission, is_active,
+                 description, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, true, $14, NOW(), NOW())
+            RETURNING {}
+            "#,
+            RULE_COLUMNS
+        ))
+        .bind(snowflake::next_id())
+        .bind(&req.name)
+        .bind(rule_type)
+        .bind(req.rate)
+        .bind(req.category_id)
+        .bind(creator_tier)
+        .bind(req.min_amount)
+        .bind(req.max_amount)
+        .bind(req.starts_at)
+        .bind(req.ends_at)
+        .bind(req.priority)
+        .bind(req.min_commission)
+        .bind(req.max_commission)
+        .bind(&req.description)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to create commission rule: {}", e)))
+    }
+
+    /// 更新规则
+    pub async fn update_rule(
+        &self,
+        id: i64,
+        req: &UpdateCommissionRuleRequest,
+        creator_tier: Option<&str>,
+    ) -> Result<Option<CommissionRule>, RswsError> {
+        sqlx::query_as::<_, CommissionRule>(&format!(
+            r#"
+            UPDATE commission_rules
+            SET name = COALESCE($2, name),
+                rule_type = COALESCE($3, rule_type),
+                rate = COALESCE($4, rate),
+                category_id = COALESCE($5, category_id),
+                creator_tier = COALESCE($6, creator_tier),
+                min_amount = COALESCE($7, min_amount),
+                max_amount = COALESCE($8, max_amount),
+                starts_at = COALESCE($9, starts_at),
+                ends_at = COALESCE($10, ends_at),
+                priority = COALESCE($11, priority),
+                min_commission = COALESCE($12, min_commission),
+                max_commission = COALESCE($13, max_commission),
+                is_active = COALESCE($14, is_active),
+                description = COALESCE($15, description),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            RULE_COLUMNS
+        ))
+        .bind(id)
+        .bind(&req.name)
+        .bind(&req.rule_type)
+        .bind(req.rate)
+        .bind(req.category_id)
+        .bind(creator_tier)
+        .bind(req.min_amount)
+        .bind(req.max_amount)
+        .bind(req.starts_at)
+        .bind(req.ends_at)
+        .bind(req.priority)
+        .bind(req.min_commission)
+        .bind(req.max_commission)
+        .bind(req.is_active)
+        .bind(&req.description)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update commission rule: {}", e)))
+    }
+
+    /// 删除规则（已引用该规则的分成记录 rule_id 置空）
+    pub async fn delete_rule(&self, id: i64) -> Result<bool, RswsError> {
+        let result = sqlx::query("DELETE FROM commission_rules WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to delete commission rule: {}", e)))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 分类是否存在
+    pub async fn category_exists(&self, category_id: i64) -> Result<bool, RswsError> {
+        let row: (bool,) = sqlx::query_as("SELECT EXISTS(SELECT 1 FROM categories WHERE id = $1)")
+            .bind(category_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to check category: {}", e)))?;
+        Ok(row.0)
+    }
+
+    // ==================== 创作者等级 ====================
+
+    /// 获取创作者等级，未设置时为 standard
+    pub async fn creator_tier(&self, user_id: i64) -> Result<String, RswsError> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT tier FROM creator_tiers WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| RswsError::internal(format!("Failed to load creator tier: {}", e)))?;
+        Ok(row
+            .map(|r| r.0)
+            .unwrap_or_else(|| DEFAULT_CREATOR_TIER.to_string()))
+    }
+
+    /// 设置创作者等级，用户不存在时返回 false
+    pub async fn set_creator_tier(
+        &self,
+        user_id: i64,
+        tier: &str,
+        admin_id: i64,
+    ) -> Result<bool, RswsError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO creator_tiers (user_id, tier, updated_by, updated_at)
+            SELECT id, $2, $3, NOW() FROM users WHERE id = $1
+            ON CONFLICT (user_id) DO UPDATE
+                SET tier = EXCLUDED.tier, updated_by = EXCLUDED.updated_by, updated_at = NOW()
+            "#,
+        )
+        .bind(user_id)
+        .bind(tier)
+        .bind(admin_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to set creator tier: {}", e)))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ==================== 分成计算 ====================
+
+    /// 获取启用的规则
+    pub async fn active_rules_in_tx(
+        conn: &mut PgConnection,
+    ) -> Result<Vec<CommissionRule>, RswsError> {
+        sqlx::query_as::<_, CommissionRule>(&format!(
+            "SELECT {} FROM commission_rules WHERE is_active = true",
+            RULE_COLUMNS
+        ))
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load commission rules: {}", e)))
+    }
+
+    /// 构造资源的规则评估上下文，资源不存在或无创作者时返回 None
+    ///
+    /// `base` 为空时取资源价格。
+    pub async fn resource_context_in_tx(
+        conn: &mut PgConnection,
+        resource_id: i64,
+        base: Option<Decimal>,
+        at: DateTime<Utc>,
+    ) -> Result<Option<CommissionContext>, RswsError> {
+        let row: Option<(Option<i64>, Decimal, Decimal, Option<i64>, String)> = sqlx::query_as(
+            r#"
+            SELECT r.provider_id, COALESCE($2, r.price, 0), COALESCE(r.commission_rate, 0),
+                   r.category_id, COALESCE(t.tier, $3)
+            FROM resources r
+            LEFT JOIN creator_tiers t ON t.user_id = r.provider_id
+            WHERE r.id = $1
+            "#,
+        )
+        .bind(resource_id)
+        .bind(base)
+        .bind(DEFAULT_CREATOR_TIER)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| {
+            RswsError::internal(format!("Failed to load resource for commission: {}", e))
+        })?;
+
+        let (provider_id, base, resource_rate, category_id, creator_tier) = match row {
+            Some((Some(pid), base, rate, category_id, tier)) if pid > 0 => {
+                (pid, base, rate, category_id, tier)
+            }
+            _ => return Ok(None),
+        };
+
+        let category_ids: Vec<(i64,)> = match category_id {
+            Some(category_id) => sqlx::query_as(CATEGORY_ANCESTORS)
+                .bind(category_id)
+                .fetch_all(&mut *conn)
+                .await
+                .map_err(|e| {
+                    RswsError::internal(format!("Failed to load category ancestors: {}", e))
+                })?,
+            None => Vec::new(),
+        };
+
+        Ok(Some(CommissionContext {
+            resource_id,
+            provider_id,
+            category_ids: category_ids.into_iter().map(|r| r.0).collect(),
+            creator_tier,
+            base,
+            resource_rate,
+            at,
+        }))
+    }
+
+    /// 构造订单的规则评估上下文：商品金额不含附加费，时间取支付时间（未支付时为当前时间）
+    pub async fn order_context_in_tx(
+        conn: &mut PgConnection,
+        order_id: i64,
+    ) -> Result<Option<CommissionContext>, RswsError> {
+        let row: Option<(Option<i64>, Decimal, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT resource_id, amount - surcharge, COALESCE(paid_at, NOW()) FROM orders WHERE id = $1",
+        )
+        .bind(order_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load order for commission: {}", e)))?;
+
+        match row {
+            Some((Some(resource_id), base, at)) => {
+                Self::resource_context_in_tx(conn, resource_id, Some(base), at).await
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// 按规则计算订单的创作者分成，订单无创作者时返回 None
+    ///
+    /// 总账的应付创作者分成与分成记录都使用该结果。
+    pub async fn creator_share_in_tx(
+        conn: &mut PgConnection,
+        order_id: i64,
+    ) -> Result<Option<(CommissionContext, CommissionQuote)>, RswsError> {
+        let ctx = match Self::order_context_in_tx(&mut *conn, order_id).await? {
+            Some(ctx) => ctx,
+            None => return Ok(None),
+        };
+        let rules = Self::active_rules_in_tx(&mut *conn).await?;
+        let quote = compute_commission(&rules, &ctx);
+        Ok(Some((ctx, quote)))
+    }
+
+    /// 在事务内为已支付订单记录创作者分成（幂等），返回分成金额
+    ///
+    /// 分成按规则计算（见 `creator_share_in_tx`），记录命中的规则与实际分成比例；
+    /// 订单无创作者或分成为 0 时返回 None。
+    pub async fn record_creator_commission_in_tx(
+        conn: &mut PgConnection,
+        order_id: i64,
+    ) -> Result<Option<Decimal>, RswsError> {
+        let paid: (bool,) = sqlx::query_as(
+            "SELECT EXISTS(SELECT 1 FROM orders WHERE id = $1 AND status::TEXT IN ('paid', 'completed'))",
+        )
+        .bind(order_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load order for commission: {}", e)))?;
+        if !paid.0 {
+            return Ok(None);
+        }
+
+        let (ctx, quote) = match Self::creator_share_in_tx(&mut *conn, order_id).await? {
+            Some((ctx, quote)) if quote.amount > Decimal::ZERO => (ctx, quote),
+            _ => return Ok(None),
+        };
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO commission_records
+                (id, order_id, user_id, referrer_id, rule_id,
+                 order_amount, commission_amount, commission_rate, status, created_at)
+            VALUES ($1, $2, $3, NULL, $4, $5, $6, $7, 'pending', NOW())
+            ON CONFLICT (order_id) WHERE referrer_id IS NULL DO NOTHING
+            "#,
+        )
+        .bind(snowflake::next_id())
+        .bind(order_id)
+        .bind(ctx.provider_id)
+        .bind(quote.rule_id)
+        .bind(quote.base)
+        .bind(quote.amount)
+        .bind(quote.rate)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to record creator commission: {}", e)))?;
+
+        Ok((result.rows_affected() > 0).then_some(quote.amount))
+    }
+
+    // ==================== 创作者收益 ====================
+
+    /// 创作者收益概览
+    pub async fn summary(&self, provider_id: i64) -> Result<CreatorEarningsSummary, RswsError> {
+        sqlx::query_as::<_, CreatorEarningsSummary>(&format!(
+            r#"
+            WITH sales AS ({})
+            SELECT
+                (SELECT COUNT(*) FROM sales) AS orders,
+                (SELECT COALESCE(SUM(gross), 0) FROM sales) AS gross,
+                (SELECT COALESCE(SUM(platform_commission), 0) FROM sales) AS platform_commission,
+                (SELECT COALESCE(SUM(net), 0) FROM sales) AS net,
+                (SELECT COALESCE(SUM(commission_amount), 0) FROM commission_records
+                 WHERE user_id = $1 AND referrer_id IS NULL AND status = 'pending') AS pending,
+                (SELECT COALESCE(SUM(commission_amount), 0) FROM commission_records
+                 WHERE user_id = $1 AND referrer_id IS NULL AND status = 'settled') AS settled
+            "#,
+            CREATOR_SALES
+        ))
+        .bind(provider_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load creator earnings: {}", e)))
+    }
+
+    /// 按资源统计销售（含未售出的资源）
+    pub async fn resource_sales(&self, provider_id: i64) -> Result<Vec<ResourceSales>, RswsError> {
+        sqlx::query_as::<_, ResourceSales>(&format!(
+            r#"
+            WITH sales AS ({})
+            SELECT r.id AS resource_id, r.title, r.is_active,
+                   COUNT(s.order_id) AS orders,
+                   COALESCE(SUM(s.gross), 0) AS gross,
+                   COALESCE(SUM(s.platform_commission), 0) AS platform_commission,
+                   COALESCE(SUM(s.net), 0) AS net
+            FROM resources r
+            LEFT JOIN sales s ON s.resource_id = r.id
+            WHERE r.provider_id = $1
+            GROUP BY r.id, r.title, r.is_active
+            ORDER BY gross DESC, r.id
+            "#,
+            CREATOR_SALES
+        ))
+        .bind(provider_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load resource sales: {}", e)))
+    }
+
+    /// 按月汇总（最近的月份在前）
+    pub async fn statement_months(
+        &self,
+        provider_id: i64,
+        limit: i64,
+    ) -> Result<Vec<StatementMonth>, RswsError> {
+        sqlx::query_as::<_, StatementMonth>(&format!(
+            r#"
+            WITH sales AS ({})
+            SELECT TO_CHAR(DATE_TRUNC('month', paid_at AT TIME ZONE 'UTC'), 'YYYY-MM') AS month,
+                   COUNT(*) AS orders,
+                   SUM(gross) AS gross,
+                   SUM(platform_commission) AS platform_commission,
+                   SUM(net) AS net
+            FROM sales
+            WHERE paid_at IS NOT NULL
+            GROUP BY 1
+            ORDER BY 1 DESC
+            LIMIT $2
+            "#,
+            CREATOR_SALES
+        ))
+        .bind(provider_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load creator statements: {}", e)))
+    }
+
+    /// 获取时间区间内的对账明细
+    pub async fn statement_lines(
+        &self,
+        provider_id: i64,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<StatementLine>, RswsError> {
+        sqlx::query_as::<_, StatementLine>(&format!(
+            r#"
+            WITH sales AS ({})
+            SELECT * FROM sales
+            WHERE paid_at >= $2 AND paid_at < $3
+            ORDER BY paid_at, order_id
+            "#,
+            CREATOR_SALES
+        ))
+        .bind(provider_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load statement lines: {}", e)))
+    }
+}
@@ -0,0 +1,263 @@
+//! Download token repository
+//!
+//! 令牌明文只在签发时返回给客户端，数据库只保存 SHA-256 哈希。
+
+use chrono::{DateTime, Utc};
+use rsws_common::error::RswsError;
+use rsws_common::snowflake::next_id;
+use rsws_model::config::DownloadPluginConfig;
+use rsws_model::download::{DownloadToken, DownloadWatermark, UpdateDownloadConfigRequest};
+use sqlx::PgPool;
+
+use crate::license::LicenseRepository;
+use crate::resource::ResourceRepository;
+
+/// 新下载令牌
+pub struct NewDownloadToken<'a> {
+    pub token_hash: &'a str,
+    pub user_id: i64,
+    pub resource_id: i64,
+    pub version_id: Option<i64>,
+    pub file_url: &'a str,
+    pub file_name: &'a str,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 新下载水印记录
+pub struct NewDownloadWatermark<'a> {
+    pub payload: &'a str,
+    pub token_id: i64,
+    pub user_id: i64,
+    pub order_id: Option<i64>,
+    pub resource_id: i64,
+    pub version_id: Option<i64>,
+    pub file_name: &'a str,
+    pub issued_at: DateTime<Utc>,
+}
+
+/// 下载令牌仓储
+pub struct DownloadTokenRepository {
+    pool: PgPool,
+}
+
+impl DownloadTokenRepository {
+    /// 创建下载令牌仓储实例
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// 获取启用的下载配置
+    pub async fn get_config(&self) -> Result<Option<DownloadPluginConfig>, RswsError> {
+        sqlx::query_as::<_, DownloadPluginConfig>(
+            r#"
+            SELECT id, plugin_name, storage_type, storage_config, max_file_size,
+                   allowed_extensions, token_ttl_secs, daily_limit_per_user,
+                   watermark_enabled, watermark_max_bytes, is_active, created_at, updated_at
+            FROM download_plugin_configs
+            WHERE is_active = true
+            ORDER BY id
+            LIMIT 1
+            "#,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to get download config: {}", e)))
+    }
+
+    /// 更新下载配置（没有启用的配置时创建默认配置，水印字段为空时保持原值）
+    pub async fn save_config(
+        &self,
+        req: &UpdateDownloadConfigRequest,
+    ) -> Result<DownloadPluginConfig, RswsError> {
+        sqlx::query_as::<_, DownloadPluginConfig>(
+            r#"
+            INSERT INTO download_plugin_configs
+                (id, plugin_name, token_ttl_secs, daily_limit_per_user,
+                 watermark_enabled, watermark_max_bytes, is_active, created_at, updated_at)
+            VALUES ($1, 'default', $2, $3, COALESCE($4, true), COALESCE($5, 536870912), true, NOW(), NOW())
+            ON CONFLICT (plugin_name) DO UPDATE SET
+                token_ttl_secs = EXCLUDED.token_ttl_secs,
+                daily_limit_per_user = EXCLUDED.daily_limit_per_user,
+                watermark_enabled = COALESCE($4, download_plugin_configs.watermark_enabled),
+                watermark_max_bytes = COALESCE($5, download_plugin_configs.watermark_max_bytes),
+                is_active = true,
+                updated_at = NOW()
+            RETURNING id, plugin_name, storage_type, storage_config, max_file_size,
+                      allowed_extensions, token_ttl_secs, daily_limit_per_user,
+                      watermark_enabled, watermark_max_bytes, is_active, created_at, updated_at
+            "#,
+        )
+        .bind(next_id())
+        .bind(req.token_ttl_secs)
+        .bind(req.daily_limit_per_user)
+        .bind(req.watermark_enabled)
+        .bind(req.watermark_max_bytes)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to save download config: {}", e)))
+    }
+
+    /// 签发令牌：占用档位下载次数、保存令牌、递增资源下载计数在同一事务内完成
+    ///
+    /// `charge` 为（订单 ID, 档位下载上限），已达上限时不签发，返回 None。
+    pub async fn issue(
+        &self,
+        token: &NewDownloadToken<'_>,
+        charge: Option<(i64, i32)>,
+    ) -> Result<Option<DownloadToken>, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        if let Some((order_id, max_downloads)) = charge {
+            if !LicenseRepository::consume_download_in_tx(&mut *tx, order_id, max_downloads).await?
+            {
+                return Ok(None);
+            }
+        }
+
+        let stored = sqlx::query_as::<_, DownloadToken>(
+            r#"
+            INSERT INTO download_tokens
+                (id, token_hash, user_id, resource_id, version_id, file_url, file_name, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW())
+            RETURNING id, user_id, resource_id, version_id, file_url, file_name,
+                      expires_at, use_count, created_at
+            "#,
+        )
+        .bind(next_id())
+        .bind(token.token_hash)
+        .bind(token.user_id)
+        .bind(token.resource_id)
+        .bind(token.version_id)
+        .bind(token.file_url)
+        .bind(token.file_name)
+        .bind(token.expires_at)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to create download token: {}", e)))?;
+
+        ResourceRepository::increment_download_count_in_tx(&mut *tx, token.resource_id).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit download token: {}", e)))?;
+
+        Ok(Some(stored))
+    }
+
+    /// 使用未过期的令牌（累计使用次数），不存在或已过期返回 None
+    pub async fn use_valid(&self, token_hash: &str) -> Result<Option<DownloadToken>, RswsError> {
+        sqlx::query_as::<_, DownloadToken>(
+            r#"
+            UPDATE download_tokens
+            SET use_count = use_count + 1, last_used_at = NOW()
+            WHERE token_hash = $1 AND expires_at > NOW()
+            RETURNING id, user_id, resource_id, version_id, file_url, file_name,
+                      expires_at, use_count, created_at
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to use download token: {}", e)))
+    }
+
+    /// 用户在 `since` 之后签发的令牌数
+    pub async fn count_issued_since(
+        &self,
+        user_id: i64,
+        since: DateTime<Utc>,
+    ) -> Result<i64, RswsError> {
+        let count: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM download_tokens WHERE user_id = $1 AND created_at > $2",
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to count download tokens: {}", e)))?;
+        Ok(count.0)
+    }
+
+    /// 用户购买该资源的最近一笔已支付订单
+    pub async fn find_paid_order_id(
+        &self,
+        user_id: i64,
+        resource_id: i64,
+    ) -> Result<Option<i64>, RswsError> {
+        let row: Option<(i64,)> = sqlx::query_as(
+            r#"
+            SELECT id FROM orders
+            WHERE user_id = $1 AND resource_id = $2 AND status IN ('paid', 'completed')
+            ORDER BY COALESCE(paid_at, created_at) DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(user_id)
+        .bind(resource_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to find paid order: {}", e)))?;
+        Ok(row.map(|(id,)| id))
+    }
+
+    /// 保存水印记录
+    pub async fn create_watermark(
+        &self,
+        watermark: &NewDownloadWatermark<'_>,
+    ) -> Result<DownloadWatermark, RswsError> {
+        sqlx::query_as::<_, DownloadWatermark>(
+            r#"
+            INSERT INTO download_watermarks
+                (id, payload, token_id, user_id, order_id, resource_id, version_id, file_name, issued_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, NOW())
+            RETURNING id, payload, token_id, user_id, order_id, resource_id, version_id,
+                      file_name, issued_at, created_at
+            "#,
+        )
+        .bind(next_id())
+        .bind(watermark.payload)
+        .bind(watermark.token_id)
+        .bind(watermark.user_id)
+        .bind(watermark.order_id)
+        .bind(watermark.resource_id)
+        .bind(watermark.version_id)
+        .bind(watermark.file_name)
+        .bind(watermark.issued_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to create download watermark: {}", e)))
+    }
+
+    /// 按水印内容查找下载记录
+    pub async fn find_watermark(
+        &self,
+        payload: &str,
+    ) -> Result<Option<DownloadWatermark>, RswsError> {
+        sqlx::query_as::<_, DownloadWatermark>(
+            r#"
+            SELECT id, payload, token_id, user_id, order_id, resource_id, version_id,
+                   file_name, issued_at, created_at
+            FROM download_watermarks
+            WHERE payload = $1
+            "#,
+        )
+        .bind(payload)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to find download watermark: {}", e)))
+    }
+
+    /// 删除 `before` 之前过期的令牌
+    pub async fn purge_expired(&self, before: DateTime<Utc>) -> Result<u64, RswsError> {
+        let result = sqlx::query("DELETE FROM download_tokens WHERE expires_at < $1")
+            .bind(before)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to purge download tokens: {}", e)))?;
+        Ok(result.rows_affected())
+    }
+}
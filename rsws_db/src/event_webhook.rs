@@ -0,0 +1,373 @@
+//! 出站事件 Webhook 仓储层
+
+use chrono::{DateTime, Utc};
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_common::snowflake;
+use rsws_model::event_webhook::{
+    CreateWebhookSubscriptionRequest, PendingDelivery, UpdateWebhookSubscriptionRequest,
+    WebhookDelivery, WebhookDeliveryQuery, WebhookSubscription,
+};
+use serde_json::Value;
+use sqlx::{PgConnection, PgPool};
+
+const SUBSCRIPTION_COLUMNS: &str =
+    "id, name, url, secret, event_types, is_active, created_at, updated_at";
+
+const DELIVERY_COLUMNS: &str = "id, subscription_id, event_id, event_key, event_type, payload, status, attempts, next_attempt_at, last_response_code, last_error, delivered_at, created_at, updated_at";
+
+/// 投递记录筛选条件（参数 $1-$3 依次为 subscription_id / status / event_type）
+const DELIVERY_FILTER: &str = "($1::BIGINT IS NULL OR subscription_id = $1) AND ($2::TEXT IS NULL OR status = $2) AND ($3::TEXT IS NULL OR event_type = $3)";
+
+/// 事件 Webhook 仓储
+pub struct EventWebhookRepository {
+    pool: PgPool,
+}
+
+impl EventWebhookRepository {
+    /// 创建事件 Webhook 仓储实例
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // ==================== 订阅 ====================
+
+    /// 获取全部订阅
+    pub async fn list_subscriptions(&self) -> Result<Vec<WebhookSubscription>, RswsError> {
+        sqlx::query_as::<_, WebhookSubscription>(&format!(
+            "SELECT {} FROM webhook_subscriptions ORDER BY created_at",
+            SUBSCRIPTION_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list webhook subscriptions: {}", e)))
+    }
+
+    /// 创建订阅
+    pub async fn create_subscription(
+        &self,
+        req: &CreateWebhookSubscriptionRequest,
+        secret: &str,
+    ) -> Result<WebhookSubscription, RswsError> {
+        sqlx::query_as::<_, WebhookSubscription>(&format!(
+            r#"
+            INSERT INTO webhook_subscriptions
+                (id, name, url, secret, event_types, is_active, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, true, NOW(), NOW())
+            RETURNING {}
+            "#,
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(snowflake::next_id())
+        .bind(&req.name)
+        .bind(&req.url)
+        .bind(secret)
+        .bind(&req.event_types)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to create webhook subscription: {}", e)))
+    }
+
+    /// 更新订阅
+    pub async fn update_subscription(
+        &self,
+        id: i64,
+        req: &UpdateWebhookSubscriptionRequest,
+    ) -> Result<Option<WebhookSubscription>, RswsError> {
+        sqlx::query_as::<_, WebhookSubscription>(&format!(
+            r#"
+            UPDATE webhook_subscriptions
+            SET name = COALESCE($2, name),
+                url = COALESCE($3, url),
+                secret = COALESCE($4, secret),
+                event_types = COALESCE($5, event_types),
+                is_active = COALESCE($6, is_active),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            SUBSCRIPTION_COLUMNS
+        ))
+        .bind(id)
+        .bind(&req.name)
+        .bind(&req.url)
+        .bind(&req.secret)
+        .bind(&req.event_types)
+        .bind(req.is_active)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update webhook subscription: {}", e)))
+    }
+
+    /// 删除订阅（连同投递记录）
+    pub async fn delete_subscription(&self, id: i64) -> Result<bool, RswsError> {
+        let result = sqlx::query("DELETE FROM webhook_subscriptions WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                RswsError::internal(format!("Failed to delete webhook subscription: {}", e))
+            })?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ==================== 入队 ====================
+
+    /// 为每个订阅了该事件的启用订阅写入一条投递记录（可在业务事务内调用）
+    ///
+    /// `event_key` 为幂等键，同一订阅下已入队过相同键的事件会被跳过。返回入队的投递数量。
+    pub async fn enqueue_in_tx(
+        conn: &mut PgConnection,
+        event_type: &str,
+        event_key: Option<&str>,
+        data: Value,
+    ) -> Result<usize, RswsError> {
+        let subscription_ids: Vec<(i64,)> = sqlx::query_as(
+            "SELECT id FROM webhook_subscriptions WHERE is_active = true AND (cardinality(event_types) = 0 OR $1 = ANY(event_types))",
+        )
+        .bind(event_type)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load webhook subscriptions: {}", e)))?;
+
+        if subscription_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let event_id = snowflake::next_id();
+        let payload = serde_json::json!({
+            "id": event_id,
+            "type": event_type,
+            "created_at": Utc::now(),
+            "data": data,
+        });
+
+        let mut queued = 0;
+        for (subscription_id,) in &subscription_ids {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO webhook_deliveries
+                    (id, subscription_id, event_id, event_key, event_type, payload, status,
+                     attempts, next_attempt_at, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, $5, $6, 'pending', 0, NOW(), NOW(), NOW())
+                ON CONFLICT (subscription_id, event_key) DO NOTHING
+                "#,
+            )
+            .bind(snowflake::next_id())
+            .bind(subscription_id)
+            .bind(event_id)
+            .bind(event_key)
+            .bind(event_type)
+            .bind(&payload)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to enqueue webhook: {}", e)))?;
+            queued += result.rows_affected() as usize;
+        }
+
+        Ok(queued)
+    }
+
+    /// 订单事件入队（载荷为订单快照，每个订单每种事件只入队一次，可在业务事务内调用）
+    pub async fn enqueue_order_event_in_tx(
+        conn: &mut PgConnection,
+        event_type: &str,
+        order_id: i64,
+    ) -> Result<usize, RswsError> {
+        let order: Option<(Value,)> = sqlx::query_as(
+            r#"
+            SELECT jsonb_build_object(
+                'id', id,
+                'user_id', user_id,
+                'resource_id', resource_id,
+                'license_id', license_id,
+                'amount', amount::TEXT,
+                'surcharge', surcharge::TEXT,
+                'status', status::TEXT,
+                'payment_method', payment_method,
+                'created_at', created_at,
+                'updated_at', updated_at
+            )
+            FROM orders WHERE id = $1
+            "#,
+        )
+        .bind(order_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load order snapshot: {}", e)))?;
+
+        match order {
+            Some((data,)) => {
+                let event_key = format!("{}:{}", event_type, order_id);
+                Self::enqueue_in_tx(conn, event_type, Some(&event_key), data).await
+            }
+            None => Err(RswsError::business(ErrorCode::ORDER_NOT_FOUND)),
+        }
+    }
+
+    /// 事件入队
+    pub async fn enqueue(
+        &self,
+        event_type: &str,
+        event_key: Option<&str>,
+        data: Value,
+    ) -> Result<usize, RswsError> {
+        let mut conn = self.acquire().await?;
+        Self::enqueue_in_tx(&mut conn, event_type, event_key, data).await
+    }
+
+    /// 订单事件入队
+    pub async fn enqueue_order_event(
+        &self,
+        event_type: &str,
+        order_id: i64,
+    ) -> Result<usize, RswsError> {
+        let mut conn = self.acquire().await?;
+        Self::enqueue_order_event_in_tx(&mut conn, event_type, order_id).await
+    }
+
+    async fn acquire(&self) -> Result<sqlx::pool::PoolConnection<sqlx::Postgres>, RswsError> {
+        self.pool
+            .acquire()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to acquire connection: {}", e)))
+    }
+
+    // ==================== 投递 ====================
+
+    /// 取出到期的投递并租用 `lease_secs` 秒（`attempts + 1`）
+    ///
+    /// 使用 `FOR UPDATE SKIP LOCKED`，多实例并发取件互不重复；
+    /// 投递进程中断时租期到后自动重新投递。
+    pub async fn claim_due(
+        &self,
+        limit: i64,
+        lease_secs: i64,
+    ) -> Result<Vec<PendingDelivery>, RswsError> {
+        sqlx::query_as::<_, PendingDelivery>(
+            r#"
+            WITH due AS (
+                SELECT id FROM webhook_deliveries
+                WHERE status = 'pending' AND next_attempt_at <= NOW()
+                ORDER BY next_attempt_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            ), claimed AS (
+                UPDATE webhook_deliveries d
+                SET attempts = d.attempts + 1,
+                    next_attempt_at = NOW() + make_interval(secs => $2),
+                    updated_at = NOW()
+                FROM due
+                WHERE d.id = due.id
+                RETURNING d.id, d.subscription_id, d.event_id, d.event_type, d.payload, d.attempts
+            )
+            SELECT c.id, c.event_id, c.event_type, c.payload, c.attempts, s.url, s.secret
+            FROM claimed c
+            JOIN webhook_subscriptions s ON s.id = c.subscription_id
+            "#,
+        )
+        .bind(limit)
+        .bind(lease_secs as f64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to claim webhook deliveries: {}", e)))
+    }
+
+    /// 标记投递成功
+    pub async fn mark_succeeded(&self, id: i64, response_code: i32) -> Result<(), RswsError> {
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = 'succeeded', last_response_code = $2, last_error = NULL,
+                delivered_at = NOW(), updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(response_code)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update webhook delivery: {}", e)))?;
+        Ok(())
+    }
+
+    /// 记录投递失败，`next_attempt_at` 为 None 时进入死信
+    pub async fn mark_failed(
+        &self,
+        id: i64,
+        response_code: Option<i32>,
+        error: &str,
+        next_attempt_at: Option<DateTime<Utc>>,
+    ) -> Result<(), RswsError> {
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = CASE WHEN $4::TIMESTAMPTZ IS NULL THEN 'dead' ELSE 'pending' END,
+                next_attempt_at = COALESCE($4, next_attempt_at),
+                last_response_code = $2, last_error = $3, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(response_code)
+        .bind(error)
+        .bind(next_attempt_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update webhook delivery: {}", e)))?;
+        Ok(())
+    }
+
+    /// 重新投递（重置次数，立即进入队列）
+    pub async fn redeliver(&self, id: i64) -> Result<Option<WebhookDelivery>, RswsError> {
+        sqlx::query_as::<_, WebhookDelivery>(&format!(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = 'pending', attempts = 0, next_attempt_at = NOW(), updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            DELIVERY_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to redeliver webhook: {}", e)))
+    }
+
+    /// 分页查询投递记录（按创建时间倒序）
+    pub async fn list_deliveries(
+        &self,
+        query: &WebhookDeliveryQuery,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<WebhookDelivery>, i64), RswsError> {
+        let offset = (page - 1) * page_size;
+
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(&format!(
+            "SELECT {} FROM webhook_deliveries WHERE {} ORDER BY created_at DESC, id DESC LIMIT $4 OFFSET $5",
+            DELIVERY_COLUMNS, DELIVERY_FILTER
+        ))
+        .bind(query.subscription_id)
+        .bind(&query.status)
+        .bind(&query.event_type)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list webhook deliveries: {}", e)))?;
+
+        let total: (i64,) = sqlx::query_as(&format!(
+            "SELECT COUNT(*) FROM webhook_deliveries WHERE {}",
+            DELIVERY_FILTER
+        ))
+        .bind(query.subscription_id)
+        .bind(&query.status)
+        .bind(&query.event_type)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to count webhook deliveries: {}", e)))?;
+
+        Ok((deliveries, total.0))
+    }
+}
@@ -0,0 +1,196 @@
+//! 发票仓储层
+
+use chrono::{DateTime, Utc};
+use rsws_common::error::RswsError;
+use rsws_common::snowflake;
+use rsws_model::invoice::Invoice;
+use sqlx::PgPool;
+
+const INVOICE_COLUMNS: &str = "id, invoice_no, order_id, user_id, buyer_email, buyer_name, items, subtotal, paid_amount, currency, payment_method, tx_hash, issued_at, emailed_at, created_at";
+
+/// 发票仓储
+pub struct InvoiceRepository {
+    pool: PgPool,
+}
+
+impl InvoiceRepository {
+    /// 创建发票仓储实例
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// 根据订单 ID 获取发票
+    pub async fn get_by_order(&self, order_id: i64) -> Result<Option<Invoice>, RswsError> {
+        sqlx::query_as::<_, Invoice>(&format!(
+            "SELECT {} FROM invoices WHERE order_id = $1",
+            INVOICE_COLUMNS
+        ))
+        .bind(order_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to get invoice: {}", e)))
+    }
+
+    /// 为已支付订单开具发票
+    ///
+    /// 在一条 INSERT ... SELECT 中快照买家、商品、实付金额与交易号：
+    /// - 实付金额/币种/交易号优先取已完成的 payment_transactions，其次取 usdt_transactions
+    /// - 订单未支付时不开具，返回 `Ok(None)`
+    /// - 已开具过时直接返回已有发票（幂等），`bool` 表示本次是否新开具
+    pub async fn issue_for_order(
+        &self,
+        order_id: i64,
+    ) -> Result<Option<(Invoice, bool)>, RswsError> {
+        if let Some(existing) = self.get_by_order(order_id).await? {
+            return Ok(Some((existing, false)));
+        }
+
+        let inserted = sqlx::query_as::<_, Invoice>(&format!(
+            r#"
+            INSERT INTO invoices
+                (id, invoice_no, order_id, user_id, buyer_email, buyer_name, items,
+                 subtotal, paid_amount, currency, payment_method, tx_hash, issued_at, created_at)
+            SELECT
+                $1,
+                'INV-' || to_char(NOW() AT TIME ZONE 'UTC', 'YYYYMMDD') || '-'
+                    || lpad(nextval('invoice_number_seq')::text, 6, '0'),
+                o.id,
+                o.user_id,
+                COALESCE(u.email, ''),
+                COALESCE(NULLIF(u.nickname, ''), u.username, ''),
+                jsonb_build_array(jsonb_build_object(
+                    'resource_id', o.resource_id,
+                    'title', COALESCE(r.title, mp.name, ''),
+                    'quantity', 1,
+                    'unit_price', o.amount,
+                    'amount', o.amount
+                )),
+                o.amount,
+                COALESCE(pt.amount, ut.amount, o.amount),
+                COALESCE(pt.currency, ut.currency,
+                         CASE WHEN o.payment_method LIKE 'usdt%' THEN 'USDT' ELSE 'USD' END),
+                COALESCE(pt.payment_method, o.payment_method, ''),
+                COALESCE(pt.provider_transaction_id, ut.tx_hash),
+                NOW(),
+                NOW()
+            FROM orders o
+            LEFT JOIN users u ON u.id = o.user_id
+            LEFT JOIN resources r ON r.id = o.resource_id
+            LEFT JOIN user_memberships um ON um.order_id = o.id
+            LEFT JOIN membership_plans mp ON mp.id = um.plan_id
+            LEFT JOIN LATERAL (
+                SELECT amount, currency, payment_method, provider_transaction_id
+                FROM payment_transactions
+                WHERE order_id = o.id AND status = 'completed'
+                ORDER BY completed_at DESC NULLS LAST
+                LIMIT 1
+            ) pt ON true
+            LEFT JOIN LATERAL (
+                SELECT amount, currency, tx_hash
+                FROM usdt_transactions
+                WHERE order_id = o.id
+                ORDER BY created_at DESC
+                LIMIT 1
+            ) ut ON true
+            WHERE o.id = $2 AND o.status IN ('paid', 'completed')
+            ON CONFLICT (order_id) DO NOTHING
+            RETURNING {}
+            "#,
+            INVOICE_COLUMNS
+        ))
+        .bind(snowflake::next_id())
+        .bind(order_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to issue invoice: {}", e)))?;
+
+        match inserted {
+            Some(invoice) => Ok(Some((invoice, true))),
+            // 并发开具时 ON CONFLICT 落空，回读已有记录；订单未支付时同样为 None
+            None => Ok(self.get_by_order(order_id).await?.map(|i| (i, false))),
+        }
+    }
+
+    /// 获取最近一天内已支付但尚未开具发票的订单 ID
+    ///
+    /// 只回看一天，避免上线时为历史订单批量补开并群发邮件；
+    /// 更早的订单在请求收据时按需开具。
+    pub async fn list_paid_orders_without_invoice(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<i64>, RswsError> {
+        let rows: Vec<(i64,)> = sqlx::query_as(
+            r#"
+            SELECT o.id
+            FROM orders o
+            LEFT JOIN invoices i ON i.order_id = o.id
+            WHERE o.status IN ('paid', 'completed') AND i.id IS NULL
+              AND o.updated_at >= NOW() - INTERVAL '1 day'
+            ORDER BY o.updated_at
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list uninvoiced orders: {}", e)))?;
+
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// 获取最近一天内开具、待发送付款成功邮件的发票
+    pub async fn list_pending_email(&self, limit: i64) -> Result<Vec<Invoice>, RswsError> {
+        sqlx::query_as::<_, Invoice>(&format!(
+            "SELECT {} FROM invoices WHERE emailed_at IS NULL AND issued_at >= NOW() - INTERVAL '1 day' ORDER BY id LIMIT $1",
+            INVOICE_COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list pending invoices: {}", e)))
+    }
+
+    /// 抢占发票的邮件发送权
+    ///
+    /// 仅当 emailed_at 为空时置为当前时间，返回是否抢占成功，
+    /// 避免 webhook 与后台补发任务重复发送。
+    pub async fn claim_email(&self, invoice_id: i64) -> Result<bool, RswsError> {
+        let result = sqlx::query(
+            "UPDATE invoices SET emailed_at = NOW() WHERE id = $1 AND emailed_at IS NULL",
+        )
+        .bind(invoice_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to claim invoice email: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 发送失败时释放邮件发送权，留待下次重试
+    pub async fn release_email(&self, invoice_id: i64) -> Result<(), RswsError> {
+        sqlx::query("UPDATE invoices SET emailed_at = NULL WHERE id = $1")
+            .bind(invoice_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to release invoice email: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// 按开具时间区间获取发票（左闭右开）
+    pub async fn list_by_issued_range(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<Invoice>, RswsError> {
+        sqlx::query_as::<_, Invoice>(&format!(
+            "SELECT {} FROM invoices WHERE issued_at >= $1 AND issued_at < $2 ORDER BY issued_at, id",
+            INVOICE_COLUMNS
+        ))
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list invoices: {}", e)))
+    }
+}
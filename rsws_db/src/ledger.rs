@@ -0,0 +1,954 @@
+//! 复式记账仓储层
+//!
+//! 提供凭证写入、余额查询、试算平衡，以及余额充值单的数据库操作。
+//! 凭证与分录只追加不修改；科目余额缓存与分录在同一事务内更新。
+
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_common::snowflake;
+use rsws_model::event_webhook::EVENT_ORDER_PAID;
+use rsws_model::ledger::{
+    creator_payable_account, user_wallet_account, AccountStatementLine, AccountType,
+    NewJournalEntry, NewPosting, TrialBalanceRow, WalletTopup, FEES, PAYMENT_CLEARING,
+    PLATFORM_REVENUE,
+};
+use rsws_model::payment::usdt_overpayment;
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, PgPool};
+
+use crate::commission::CommissionRepository;
+use crate::event_webhook::EventWebhookRepository;
+use crate::referral::ReferralRepository;
+
+const TOPUP_COLUMNS: &str = "id, user_id, amount, payment_method, receive_address, status, provider_tx_id, created_at, updated_at, completed_at, expired_at";
+
+/// Pending commission record row: (id, user_id, referrer_id, commission_amount)
+type CommissionRow = (i64, Option<i64>, Option<i64>, Option<Decimal>);
+
+/// 复式记账仓储
+#[derive(Clone)]
+pub struct LedgerRepository {
+    pool: PgPool,
+}
+
+impl LedgerRepository {
+    /// 创建复式记账仓储实例
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // ==================== 凭证 ====================
+
+    /// 在调用方事务中写入凭证
+    ///
+    /// - 凭证借贷不平衡或含未知科目时返回 `LEDGER_ENTRY_INVALID`
+    /// - 幂等键已存在时不重复记账，返回 `Ok(None)`
+    /// - 用户余额科目出现负余额时返回 `BALANCE_INSUFFICIENT`（调用方回滚事务）
+    pub async fn post_in_tx(
+        conn: &mut PgConnection,
+        entry: NewJournalEntry,
+    ) -> Result<Option<i64>, RswsError> {
+        let mut entry = entry.normalized().map_err(|msg| {
+            RswsError::business_with_message(ErrorCode::LEDGER_ENTRY_INVALID, msg)
+        })?;
+
+        let entry_id = snowflake::next_id();
+        let inserted: Option<(i64,)> = sqlx::query_as(
+            r#"
+            INSERT INTO ledger_journal_entries
+                (id, entry_type, reference_type, reference_id, idempotency_key, description, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, NOW())
+            ON CONFLICT (idempotency_key) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(entry_id)
+        .bind(&entry.entry_type)
+        .bind(&entry.reference_type)
+        .bind(entry.reference_id)
+        .bind(&entry.idempotency_key)
+        .bind(&entry.description)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to insert journal entry: {}", e)))?;
+
+        if inserted.is_none() {
+            return Ok(None);
+        }
+
+        // 按科目代码顺序加锁，降低并发凭证互相等待的概率
+        entry
+            .postings
+            .sort_by(|a, b| a.account_code.cmp(&b.account_code));
+
+        for posting in &entry.postings {
+            let (account_type, owner_id) = AccountType::from_code(&posting.account_code)
+                .ok_or_else(|| RswsError::business(ErrorCode::LEDGER_ENTRY_INVALID))?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO ledger_accounts (id, code, account_type, owner_id, balance, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, 0, NOW(), NOW())
+                ON CONFLICT (code) DO NOTHING
+                "#,
+            )
+            .bind(snowflake::next_id())
+            .bind(&posting.account_code)
+            .bind(account_type.as_str())
+            .bind(owner_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to create ledger account: {}", e)))?;
+
+            let (account_id, balance): (i64, Decimal) = sqlx::query_as(
+                "UPDATE ledger_accounts SET balance = balance + $2, updated_at = NOW() WHERE code = $1 RETURNING id, balance",
+            )
+            .bind(&posting.account_code)
+            .bind(posting.amount)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to update ledger account: {}", e)))?;
+
+            // 用户余额为贷方科目，借方余额为正即表示透支
+            let is_user_wallet =
+                owner_id.is_some_and(|uid| posting.account_code == user_wallet_account(uid));
+            if is_user_wallet && balance > Decimal::ZERO {
+                return Err(RswsError::business(ErrorCode::BALANCE_INSUFFICIENT));
+            }
+
+            sqlx::query(
+                "INSERT INTO ledger_postings (id, entry_id, account_id, amount, created_at) VALUES ($1, $2, $3, $4, NOW())",
+            )
+            .bind(snowflake::next_id())
+            .bind(entry_id)
+            .bind(account_id)
+            .bind(posting.amount)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to insert posting: {}", e)))?;
+        }
+
+        Ok(Some(entry_id))
+    }
+
+    /// 写入凭证（独立事务）
+    pub async fn post(&self, entry: NewJournalEntry) -> Result<Option<i64>, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let entry_id = Self::post_in_tx(&mut *tx, entry).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit journal entry: {}", e)))?;
+
+        Ok(entry_id)
+    }
+
+    /// 构造订单收款凭证
+    ///
+    /// 借：`source_account`（第三方收款为 payment_clearing，余额购买为用户余额）
+    /// 贷：平台收入（订单金额 - 创作者分成）、应付创作者分成（按分成规则计算，见
+    /// `CommissionRepository::creator_share_in_tx`）
+    /// 商品金额不含买家承担的手续费（orders.surcharge），附加费全部计入平台收入。
+    /// 有通道手续费时另借：fees，贷：payment_clearing。
+    pub async fn order_payment_entry(
+        conn: &mut PgConnection,
+        order_id: i64,
+        source_account: &str,
+        fee: Decimal,
+    ) -> Result<NewJournalEntry, RswsError> {
+        let row: Option<(Decimal,)> = sqlx::query_as("SELECT amount FROM orders WHERE id = $1")
+            .bind(order_id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to load order for ledger: {}", e)))?;
+
+        let (amount,) = row.ok_or_else(|| RswsError::business(ErrorCode::ORDER_NOT_FOUND))?;
+
+        let creator_share = CommissionRepository::creator_share_in_tx(&mut *conn, order_id)
+            .await?
+            .filter(|(_, quote)| quote.amount > Decimal::ZERO)
+            .map(|(ctx, quote)| (ctx.provider_id, quote.amount));
+        let share = creator_share.map(|(_, s)| s).unwrap_or(Decimal::ZERO);
+
+        let mut postings = vec![
+            NewPosting::debit(source_account, amount),
+            NewPosting::credit(PLATFORM_REVENUE, amount - share),
+        ];
+        if let Some((pid, share)) = creator_share {
+            postings.push(NewPosting::credit(creator_payable_account(pid), share));
+        }
+        if fee > Decimal::ZERO {
+            postings.push(NewPosting::debit(FEES, fee));
+            postings.push(NewPosting::credit(PAYMENT_CLEARING, fee));
+        }
+
+        Ok(NewJournalEntry {
+            entry_type: "order_payment".to_string(),
+            reference_type: Some("order".to_string()),
+            reference_id: Some(order_id),
+            idempotency_key: format!("order_payment:{}", order_id),
+            description: Some(format!("Order #{} payment", order_id)),
+            postings,
+        })
+    }
+
+    /// 确认第三方渠道的订单收款
+    ///
+    /// 订单置为已支付、记录创作者分成与推荐佣金、记账在同一事务内完成。
+    /// 只确认待支付订单：已取消 / 过期 / 退款或已确认的订单返回 false，不改状态、不记账。
+    /// 通道手续费取下单报价时写入的 orders.fee_amount，与买家看到的手续费一致。
+    pub async fn confirm_order_payment(&self, order_id: i64) -> Result<bool, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let order: Option<(String, Decimal)> =
+            sqlx::query_as("SELECT status::text, fee_amount FROM orders WHERE id = $1 FOR UPDATE")
+                .bind(order_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| RswsError::internal(format!("Failed to lock order: {}", e)))?;
+
+        let (status, fee) = order.ok_or_else(|| RswsError::business(ErrorCode::ORDER_NOT_FOUND))?;
+        if status != "pending" {
+            return Ok(false);
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE orders
+            SET status = 'paid', paid_at = COALESCE(paid_at, NOW()), updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(order_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update order status: {}", e)))?;
+
+        Self::record_commissions_in_tx(&mut *tx, order_id).await?;
+
+        let entry = Self::order_payment_entry(&mut *tx, order_id, PAYMENT_CLEARING, fee).await?;
+        Self::post_in_tx(&mut *tx, entry).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit journal entry: {}", e)))?;
+
+        Ok(true)
+    }
+
+    /// 使用余额支付订单：扣减余额、记账、订单置为已支付、记录佣金，在同一事务内完成
+    pub async fn pay_order_from_balance(
+        &self,
+        order_id: i64,
+        user_id: i64,
+    ) -> Result<(), RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let order: Option<(Option<i64>, String, bool)> = sqlx::query_as(
+            r#"
+            SELECT user_id, status::text, (expired_at IS NOT NULL AND expired_at <= NOW())
+            FROM orders
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(order_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to lock order: {}", e)))?;
+
+        let (owner_id, status, expired) =
+            order.ok_or_else(|| RswsError::business(ErrorCode::ORDER_NOT_FOUND))?;
+        if owner_id != Some(user_id) {
+            return Err(RswsError::business(ErrorCode::AUTH_PERMISSION_DENIED));
+        }
+        match status.as_str() {
+            "pending" => {}
+            "paid" | "completed" => return Err(RswsError::business(ErrorCode::ORDER_ALREADY_PAID)),
+            _ => return Err(RswsError::business(ErrorCode::ORDER_STATUS_INVALID)),
+        }
+        if expired {
+            return Err(RswsError::business(ErrorCode::ORDER_EXPIRED));
+        }
+
+        let entry = Self::order_payment_entry(
+            &mut *tx,
+            order_id,
+            &user_wallet_account(user_id),
+            Decimal::ZERO,
+        )
+        .await?;
+        Self::post_in_tx(&mut *tx, entry).await?;
+
+        sqlx::query(
+            "UPDATE orders SET status = 'paid', payment_method = 'balance', paid_at = NOW(), updated_at = NOW() WHERE id = $1",
+        )
+        .bind(order_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update order status: {}", e)))?;
+
+        Self::record_commissions_in_tx(&mut *tx, order_id).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit balance payment: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// 记录已支付订单的创作者分成与推荐佣金（幂等），须在订单置为已支付之后调用
+    async fn record_commissions_in_tx(
+        conn: &mut PgConnection,
+        order_id: i64,
+    ) -> Result<(), RswsError> {
+        CommissionRepository::record_creator_commission_in_tx(&mut *conn, order_id).await?;
+        ReferralRepository::record_commission_in_tx(&mut *conn, order_id).await?;
+        Ok(())
+    }
+
+    /// 结算订单下待结算的佣金记录并记账，返回结算条数
+    ///
+    /// - 推荐佣金（referrer_id 非空）：借 平台收入，贷 推荐人余额
+    /// - 创作者分成：借 应付创作者分成，贷 创作者余额
+    pub async fn settle_commissions(&self, order_id: i64) -> Result<usize, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let records: Vec<CommissionRow> = sqlx::query_as(
+            r#"
+            SELECT id, user_id, referrer_id, commission_amount
+            FROM commission_records
+            WHERE order_id = $1 AND status = 'pending'
+            FOR UPDATE
+            "#,
+        )
+        .bind(order_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load commission records: {}", e)))?;
+
+        let mut settled = 0;
+        for (record_id, user_id, referrer_id, amount) in records {
+            let amount = amount.unwrap_or(Decimal::ZERO);
+            let postings = match (
+                referrer_id.filter(|id| *id > 0),
+                user_id.filter(|id| *id > 0),
+            ) {
+                (Some(referrer), _) => vec![
+                    NewPosting::debit(PLATFORM_REVENUE, amount),
+                    NewPosting::credit(user_wallet_account(referrer), amount),
+                ],
+                (None, Some(creator)) => vec![
+                    NewPosting::debit(creator_payable_account(creator), amount),
+                    NewPosting::credit(user_wallet_account(creator), amount),
+                ],
+                (None, None) => Vec::new(),
+            };
+
+            if amount > Decimal::ZERO && !postings.is_empty() {
+                Self::post_in_tx(
+                    &mut *tx,
+                    NewJournalEntry {
+                        entry_type: "commission_settlement".to_string(),
+                        reference_type: Some("commission_record".to_string()),
+                        reference_id: Some(record_id),
+                        idempotency_key: format!("commission_settlement:{}", record_id),
+                        description: Some(format!("Commission for order #{}", order_id)),
+                        postings,
+                    },
+                )
+                .await?;
+            }
+
+            sqlx::query(
+                "UPDATE commission_records SET status = 'settled', settled_at = NOW() WHERE id = $1",
+            )
+            .bind(record_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to settle commission: {}", e)))?;
+
+            settled += 1;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit commissions: {}", e)))?;
+
+        Ok(settled)
+    }
+
+    /// 获取冻结期已过、可结算佣金的订单（订单仍为已支付 / 已完成状态）
+    pub async fn due_commission_orders(
+        &self,
+        hold_days: i64,
+        limit: i64,
+    ) -> Result<Vec<i64>, RswsError> {
+        let rows: Vec<(i64,)> = sqlx::query_as(
+            r#"
+            SELECT DISTINCT c.order_id
+            FROM commission_records c
+            JOIN orders o ON o.id = c.order_id
+            WHERE c.status = 'pending'
+              AND o.status::TEXT IN ('paid', 'completed')
+              AND c.created_at <= NOW() - make_interval(days => $1::INT)
+            LIMIT $2
+            "#,
+        )
+        .bind(hold_days)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load due commissions: {}", e)))?;
+
+        Ok(rows.into_iter().map(|r| r.0).collect())
+    }
+
+    /// 订单退款，在同一事务内完成：
+    ///
+    /// - 订单置为已退款，会员订阅随之失效（下载与会员权限均按订单状态判断）
+    /// - 作废待结算的佣金记录
+    /// - 按原收款凭证逐笔反向记账；已结算给创作者的分成无法收回，由平台收入承担
+    ///
+    /// 只处理已支付 / 已完成的订单，其他状态返回 false。
+    pub async fn refund_order(&self, order_id: i64) -> Result<bool, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let status: Option<(String,)> =
+            sqlx::query_as("SELECT status::text FROM orders WHERE id = $1 FOR UPDATE")
+                .bind(order_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| RswsError::internal(format!("Failed to lock order: {}", e)))?;
+
+        let (status,) = status.ok_or_else(|| RswsError::business(ErrorCode::ORDER_NOT_FOUND))?;
+        if status != "paid" && status != "completed" {
+            return Ok(false);
+        }
+
+        sqlx::query("UPDATE orders SET status = 'refunded', updated_at = NOW() WHERE id = $1")
+            .bind(order_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to update order status: {}", e)))?;
+
+        sqlx::query(
+            "UPDATE user_memberships SET status = 'refunded', updated_at = NOW() WHERE order_id = $1",
+        )
+        .bind(order_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to revoke membership: {}", e)))?;
+
+        sqlx::query(
+            "UPDATE commission_records SET status = 'cancelled' WHERE order_id = $1 AND status = 'pending'",
+        )
+        .bind(order_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to cancel commissions: {}", e)))?;
+
+        let settled_creators: Vec<(i64,)> = sqlx::query_as(
+            r#"
+            SELECT DISTINCT user_id
+            FROM commission_records
+            WHERE order_id = $1 AND referrer_id IS NULL AND status = 'settled' AND user_id IS NOT NULL
+            "#,
+        )
+        .bind(order_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load settled commissions: {}", e)))?;
+        let settled_accounts: Vec<String> = settled_creators
+            .into_iter()
+            .map(|(uid,)| creator_payable_account(uid))
+            .collect();
+
+        let postings: Vec<(String, Decimal)> = sqlx::query_as(
+            r#"
+            SELECT a.code, p.amount
+            FROM ledger_postings p
+            JOIN ledger_accounts a ON a.id = p.account_id
+            JOIN ledger_journal_entries e ON e.id = p.entry_id
+            WHERE e.idempotency_key = $1
+            "#,
+        )
+        .bind(format!("order_payment:{}", order_id))
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load payment postings: {}", e)))?;
+
+        if !postings.is_empty() {
+            let postings = postings
+                .into_iter()
+                .map(|(code, amount)| {
+                    let code = if settled_accounts.contains(&code) {
+                        PLATFORM_REVENUE.to_string()
+                    } else {
+                        code
+                    };
+                    NewPosting::credit(code, amount)
+                })
+                .collect();
+            Self::post_in_tx(
+                &mut *tx,
+                NewJournalEntry {
+                    entry_type: "order_refund".to_string(),
+                    reference_type: Some("order".to_string()),
+                    reference_id: Some(order_id),
+                    idempotency_key: format!("order_refund:{}", order_id),
+                    description: Some(format!("Order #{} refund", order_id)),
+                    postings,
+                },
+            )
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit refund: {}", e)))?;
+
+        Ok(true)
+    }
+
+    // ==================== USDT 收款 ====================
+
+    /// 确认 USDT 订单收款（在调用方事务内执行）
+    ///
+    /// 只确认待支付订单，否则返回 false。订单置为已完成、记录创作者分成与推荐佣金、
+    /// 按下单报价的手续费记账；超额支付部分转入买家余额；order.paid 事件同事务入队。
+    pub async fn confirm_usdt_order_in_tx(
+        conn: &mut PgConnection,
+        order_id: i64,
+        user_id: i64,
+        tx_hash: &str,
+        fee: Decimal,
+        overpaid: Decimal,
+    ) -> Result<bool, RswsError> {
+        let affected = sqlx::query(
+            r#"
+            UPDATE orders
+            SET status = 'completed',
+                transaction_id = $2,
+                paid_at = COALESCE(paid_at, NOW()),
+                updated_at = NOW()
+            WHERE id = $1 AND status = 'pending'
+            "#,
+        )
+        .bind(order_id)
+        .bind(tx_hash)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update order status: {}", e)))?;
+
+        if affected.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        Self::record_commissions_in_tx(&mut *conn, order_id).await?;
+
+        let entry = Self::order_payment_entry(&mut *conn, order_id, PAYMENT_CLEARING, fee).await?;
+        Self::post_in_tx(&mut *conn, entry).await?;
+
+        if overpaid > Decimal::ZERO {
+            let entry = NewJournalEntry {
+                entry_type: "usdt_overpayment".to_string(),
+                reference_type: Some("order".to_string()),
+                reference_id: Some(order_id),
+                idempotency_key: format!("usdt_overpayment:{}", tx_hash),
+                description: Some(format!("USDT overpayment for order #{}", order_id)),
+                postings: vec![
+                    NewPosting::debit(PAYMENT_CLEARING, overpaid),
+                    NewPosting::credit(user_wallet_account(user_id), overpaid),
+                ],
+            };
+            Self::post_in_tx(&mut *conn, entry).await?;
+        }
+
+        EventWebhookRepository::enqueue_order_event_in_tx(&mut *conn, EVENT_ORDER_PAID, order_id)
+            .await?;
+
+        Ok(true)
+    }
+
+    /// 风控审核放行后确认暂扣的 USDT 收款
+    ///
+    /// 链上交易记录为 held 时入账并改为 processed；订单已不是待支付状态时改为
+    /// refund_pending 待财务退款。返回是否完成入账。
+    pub async fn release_held_usdt_payment(&self, order_id: i64) -> Result<bool, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let held: Option<(i64, String, Decimal)> = sqlx::query_as(
+            r#"
+            SELECT id, tx_hash, amount
+            FROM usdt_transactions
+            WHERE order_id = $1 AND status = 'held'
+            LIMIT 1
+            FOR UPDATE
+            "#,
+        )
+        .bind(order_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to lock USDT transaction: {}", e)))?;
+
+        let Some((usdt_tx_id, tx_hash, paid)) = held else {
+            return Ok(false);
+        };
+
+        let order: Option<(Option<i64>, Decimal, Decimal)> =
+            sqlx::query_as("SELECT user_id, amount, fee_amount FROM orders WHERE id = $1")
+                .bind(order_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| RswsError::internal(format!("Failed to get order: {}", e)))?;
+        let (user_id, amount, fee) =
+            order.ok_or_else(|| RswsError::business(ErrorCode::ORDER_NOT_FOUND))?;
+
+        // 无买家的订单不归属超额部分，留待人工处理
+        let (user_id, overpaid) = match user_id {
+            Some(user_id) => (user_id, usdt_overpayment(paid, amount)),
+            None => (0, Decimal::ZERO),
+        };
+        let confirmed =
+            Self::confirm_usdt_order_in_tx(&mut *tx, order_id, user_id, &tx_hash, fee, overpaid)
+                .await?;
+
+        sqlx::query("UPDATE usdt_transactions SET status = $2 WHERE id = $1")
+            .bind(usdt_tx_id)
+            .bind(if confirmed {
+                "processed"
+            } else {
+                "refund_pending"
+            })
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                RswsError::internal(format!("Failed to update USDT transaction: {}", e))
+            })?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit USDT payment: {}", e)))?;
+
+        Ok(confirmed)
+    }
+
+    /// 风控审核拒绝后将暂扣的 USDT 收款标记为待退款，返回是否需要退款
+    pub async fn flag_held_usdt_refund(&self, order_id: i64) -> Result<bool, RswsError> {
+        let result = sqlx::query(
+            "UPDATE usdt_transactions SET status = 'refund_pending' WHERE order_id = $1 AND status = 'held'",
+        )
+        .bind(order_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update USDT transaction: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ==================== 余额与报表 ====================
+
+    /// 获取用户可用余额
+    pub async fn get_user_balance(&self, user_id: i64) -> Result<Decimal, RswsError> {
+        let row: Option<(Decimal,)> =
+            sqlx::query_as("SELECT balance FROM ledger_accounts WHERE code = $1")
+                .bind(user_wallet_account(user_id))
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| RswsError::internal(format!("Failed to get balance: {}", e)))?;
+
+        // 贷方科目，取反后为用户可用余额
+        Ok(row.map(|(b,)| -b).unwrap_or(Decimal::ZERO))
+    }
+
+    /// 获取科目流水（金额按科目自然方向：增加为正）
+    pub async fn list_statement(
+        &self,
+        account_code: &str,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<AccountStatementLine>, i64), RswsError> {
+        let offset = (page - 1) * page_size;
+
+        let mut lines = sqlx::query_as::<_, AccountStatementLine>(
+            r#"
+            SELECT e.id AS entry_id, e.entry_type, e.reference_type, e.reference_id,
+                   e.description, p.amount, p.created_at
+            FROM ledger_postings p
+            JOIN ledger_accounts a ON a.id = p.account_id
+            JOIN ledger_journal_entries e ON e.id = p.entry_id
+            WHERE a.code = $1
+            ORDER BY p.created_at DESC, p.id DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(account_code)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list statement: {}", e)))?;
+
+        let total: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM ledger_postings p JOIN ledger_accounts a ON a.id = p.account_id WHERE a.code = $1",
+        )
+        .bind(account_code)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to count statement: {}", e)))?;
+
+        if AccountType::from_code(account_code).is_some_and(|(t, _)| t.is_credit_normal()) {
+            for line in &mut lines {
+                line.amount = -line.amount;
+            }
+        }
+
+        Ok((lines, total.0))
+    }
+
+    /// 试算平衡表：按分录汇总每个科目的借方 / 贷方余额
+    pub async fn trial_balance(&self) -> Result<Vec<TrialBalanceRow>, RswsError> {
+        sqlx::query_as::<_, TrialBalanceRow>(
+            r#"
+            SELECT a.code, a.account_type,
+                   GREATEST(COALESCE(SUM(p.amount), 0), 0) AS debit,
+                   GREATEST(-COALESCE(SUM(p.amount), 0), 0) AS credit
+            FROM ledger_accounts a
+            LEFT JOIN ledger_postings p ON p.account_id = a.id
+            GROUP BY a.id, a.code, a.account_type
+            ORDER BY a.code
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to build trial balance: {}", e)))
+    }
+
+    // ==================== 余额充值 ====================
+
+    /// 创建充值单
+    pub async fn create_topup(
+        &self,
+        user_id: i64,
+        amount: Decimal,
+        payment_method: &str,
+        receive_address: Option<&str>,
+        expire_minutes: i32,
+    ) -> Result<WalletTopup, RswsError> {
+        sqlx::query_as::<_, WalletTopup>(&format!(
+            r#"
+            INSERT INTO wallet_topups (id, user_id, amount, payment_method, receive_address, status, created_at, updated_at, expired_at)
+            VALUES ($1, $2, $3, $4, $5, 'pending', NOW(), NOW(), NOW() + INTERVAL '1 minute' * $6)
+            RETURNING {}
+            "#,
+            TOPUP_COLUMNS
+        ))
+        .bind(snowflake::next_id())
+        .bind(user_id)
+        .bind(amount)
+        .bind(payment_method)
+        .bind(receive_address)
+        .bind(expire_minutes)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to create topup: {}", e)))
+    }
+
+    /// 根据 ID 获取充值单
+    pub async fn get_topup(&self, id: i64) -> Result<Option<WalletTopup>, RswsError> {
+        sqlx::query_as::<_, WalletTopup>(&format!(
+            "SELECT {} FROM wallet_topups WHERE id = $1",
+            TOPUP_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to get topup: {}", e)))
+    }
+
+    /// 根据网关订单号 / 交易号获取充值单
+    pub async fn get_topup_by_provider_tx(
+        &self,
+        provider_tx_id: &str,
+    ) -> Result<Option<WalletTopup>, RswsError> {
+        sqlx::query_as::<_, WalletTopup>(&format!(
+            "SELECT {} FROM wallet_topups WHERE provider_tx_id = $1",
+            TOPUP_COLUMNS
+        ))
+        .bind(provider_tx_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to get topup: {}", e)))
+    }
+
+    /// 记录网关订单号（如 PayPal order ID）
+    pub async fn set_topup_provider_tx(
+        &self,
+        id: i64,
+        provider_tx_id: &str,
+    ) -> Result<(), RswsError> {
+        sqlx::query(
+            "UPDATE wallet_topups SET provider_tx_id = $2, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(id)
+        .bind(provider_tx_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update topup: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// 查找与链上收款地址、金额匹配的待支付 USDT 充值单（金额误差 1%，最早创建优先）
+    pub async fn find_pending_usdt_topup(
+        &self,
+        payment_method: &str,
+        receive_address: &str,
+        amount: Decimal,
+    ) -> Result<Option<WalletTopup>, RswsError> {
+        sqlx::query_as::<_, WalletTopup>(&format!(
+            r#"
+            SELECT {}
+            FROM wallet_topups
+            WHERE status = 'pending'
+              AND payment_method = $1
+              AND receive_address = $3
+              AND ABS(amount - $2) <= amount * 0.01
+              AND (expired_at IS NULL OR expired_at > NOW())
+            ORDER BY created_at ASC
+            LIMIT 1
+            "#,
+            TOPUP_COLUMNS
+        ))
+        .bind(payment_method)
+        .bind(amount)
+        .bind(receive_address)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to find topup: {}", e)))
+    }
+
+    /// 充值到账：充值单置为完成并记账（借 payment_clearing，贷 用户余额），幂等
+    ///
+    /// `received` 为实际到账金额（链上转账），传入时按到账金额入账并更新充值单金额。
+    /// 返回本次是否完成入账；充值单已完成或不存在时返回 `Ok(false)`。
+    pub async fn complete_topup(
+        &self,
+        id: i64,
+        provider_tx_id: Option<&str>,
+        received: Option<Decimal>,
+        fee: Decimal,
+    ) -> Result<bool, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let row: Option<(i64, Decimal)> = sqlx::query_as(
+            r#"
+            UPDATE wallet_topups
+            SET status = 'completed',
+                provider_tx_id = COALESCE($2, provider_tx_id),
+                amount = COALESCE($3, amount),
+                completed_at = NOW(),
+                updated_at = NOW()
+            WHERE id = $1 AND status = 'pending'
+            RETURNING user_id, amount
+            "#,
+        )
+        .bind(id)
+        .bind(provider_tx_id)
+        .bind(received)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to complete topup: {}", e)))?;
+
+        let Some((user_id, amount)) = row else {
+            return Ok(false);
+        };
+
+        let mut postings = vec![
+            NewPosting::debit(PAYMENT_CLEARING, amount),
+            NewPosting::credit(user_wallet_account(user_id), amount),
+        ];
+        if fee > Decimal::ZERO {
+            postings.push(NewPosting::debit(FEES, fee));
+            postings.push(NewPosting::credit(PAYMENT_CLEARING, fee));
+        }
+
+        Self::post_in_tx(
+            &mut *tx,
+            NewJournalEntry {
+                entry_type: "topup".to_string(),
+                reference_type: Some("wallet_topup".to_string()),
+                reference_id: Some(id),
+                idempotency_key: format!("topup:{}", id),
+                description: Some(format!("Balance top-up #{}", id)),
+                postings,
+            },
+        )
+        .await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit topup: {}", e)))?;
+
+        Ok(true)
+    }
+
+    /// 获取用户充值记录
+    pub async fn list_topups(
+        &self,
+        user_id: i64,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<WalletTopup>, i64), RswsError> {
+        let offset = (page - 1) * page_size;
+
+        let topups = sqlx::query_as::<_, WalletTopup>(&format!(
+            "SELECT {} FROM wallet_topups WHERE user_id = $1 ORDER BY created_at DESC LIMIT $2 OFFSET $3",
+            TOPUP_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list topups: {}", e)))?;
+
+        let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM wallet_topups WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to count topups: {}", e)))?;
+
+        Ok((topups, total.0))
+    }
+}
@@ -6,23 +6,57 @@ use sqlx::PgPool;
 
 pub mod admin;
 pub mod category;
+pub mod commission;
+pub mod download;
+pub mod event_webhook;
+pub mod invoice;
+pub mod ledger;
+pub mod license;
+pub mod membership;
+pub mod moderation;
 pub mod order;
 pub mod payment;
+pub mod rating;
+pub mod recommendation;
 pub mod redis;
+pub mod referral;
 pub mod resource;
+pub mod resource_version;
+pub mod risk;
+pub mod tag;
 pub mod user;
 pub mod wallet;
+pub mod webhook_log;
+pub mod wishlist;
 
 pub use admin::AdminRepository;
 pub use category::Category;
 pub use category::CategoryRepository;
+pub use commission::CommissionRepository;
+pub use download::DownloadTokenRepository;
+pub use event_webhook::EventWebhookRepository;
+pub use invoice::InvoiceRepository;
+pub use ledger::LedgerRepository;
+pub use license::LicenseRepository;
+pub use membership::MembershipRepository;
+pub use moderation::ModerationRepository;
 pub use order::OrderRepository;
+pub use payment::AlipayConfigRepository;
 pub use payment::PayPalConfigRepository;
 pub use payment::PaymentRepository;
+pub use payment::WechatPayConfigRepository;
+pub use rating::RatingRepository;
+pub use recommendation::RecommendationRepository;
 pub use redis::RedisService;
+pub use referral::ReferralRepository;
 pub use resource::ResourceRepository;
+pub use resource_version::ResourceVersionRepository;
+pub use risk::RiskRepository;
+pub use tag::TagRepository;
 pub use user::UserRepository;
 pub use wallet::WalletRepository;
+pub use webhook_log::WebhookLogRepository;
+pub use wishlist::WishlistRepository;
 
 /// Redis connection pool alias
 pub type RedisPool = RedisService;
@@ -0,0 +1,156 @@
+//! 资源授权档位仓储层
+
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_common::snowflake;
+use rsws_model::license::{
+    CreateResourceLicenseRequest, LicensedOrder, ResourceLicense, UpdateResourceLicenseRequest,
+};
+use sqlx::{PgConnection, PgPool};
+
+const LICENSE_COLUMNS: &str =
+    "id, resource_id, tier, name, price, terms, max_downloads, is_active, created_at, updated_at";
+
+/// 档位排序（与 `LICENSE_TIERS` 顺序一致）
+const TIER_ORDER: &str =
+    "CASE tier WHEN 'personal' THEN 0 WHEN 'commercial' THEN 1 WHEN 'extended' THEN 2 END";
+
+/// 授权档位仓储
+pub struct LicenseRepository {
+    pool: PgPool,
+}
+
+impl LicenseRepository {
+    /// 创建授权档位仓储实例
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// 获取资源的授权档位（按等级从低到高）
+    pub async fn list_by_resource(
+        &self,
+        resource_id: i64,
+        active_only: bool,
+    ) -> Result<Vec<ResourceLicense>, RswsError> {
+        sqlx::query_as::<_, ResourceLicense>(&format!(
+            "SELECT {} FROM resource_licenses WHERE resource_id = $1 AND ($2 = false OR is_active = true) ORDER BY {}",
+            LICENSE_COLUMNS, TIER_ORDER
+        ))
+        .bind(resource_id)
+        .bind(active_only)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list resource licenses: {}", e)))
+    }
+
+    /// 创建授权档位（同一资源每个档位只能有一条）
+    pub async fn create(
+        &self,
+        resource_id: i64,
+        req: &CreateResourceLicenseRequest,
+    ) -> Result<ResourceLicense, RswsError> {
+        sqlx::query_as::<_, ResourceLicense>(&format!(
+            r#"
+            INSERT INTO resource_licenses
+                (id, resource_id, tier, name, price, terms, max_downloads, is_active, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, true, NOW(), NOW())
+            RETURNING {}
+            "#,
+            LICENSE_COLUMNS
+        ))
+        .bind(snowflake::next_id())
+        .bind(resource_id)
+        .bind(&req.tier)
+        .bind(req.name.as_deref().unwrap_or(&req.tier))
+        .bind(req.price)
+        .bind(&req.terms)
+        .bind(req.max_downloads)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("duplicate key") {
+                RswsError::business_with_message(
+                    ErrorCode::LICENSE_TIER_INVALID,
+                    format!("License tier {} already exists for this resource", req.tier),
+                )
+            } else {
+                RswsError::internal(format!("Failed to create resource license: {}", e))
+            }
+        })
+    }
+
+    /// 更新授权档位
+    pub async fn update(
+        &self,
+        id: i64,
+        req: &UpdateResourceLicenseRequest,
+    ) -> Result<Option<ResourceLicense>, RswsError> {
+        sqlx::query_as::<_, ResourceLicense>(&format!(
+            r#"
+            UPDATE resource_licenses
+            SET name = COALESCE($2, name),
+                price = COALESCE($3, price),
+                terms = COALESCE($4, terms),
+                max_downloads = COALESCE($5, max_downloads),
+                is_active = COALESCE($6, is_active),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            LICENSE_COLUMNS
+        ))
+        .bind(id)
+        .bind(&req.name)
+        .bind(req.price)
+        .bind(&req.terms)
+        .bind(req.max_downloads)
+        .bind(req.is_active)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update resource license: {}", e)))
+    }
+
+    /// 获取用户对资源的已支付订单及其授权档位
+    pub async fn list_paid_orders(
+        &self,
+        user_id: i64,
+        resource_id: i64,
+    ) -> Result<Vec<LicensedOrder>, RswsError> {
+        sqlx::query_as::<_, LicensedOrder>(
+            r#"
+            SELECT o.id AS order_id, o.license_id, l.tier, l.price AS license_price,
+                   o.amount, o.download_count, l.max_downloads
+            FROM orders o
+            LEFT JOIN resource_licenses l ON l.id = o.license_id
+            WHERE o.user_id = $1 AND o.resource_id = $2 AND o.status IN ('paid', 'completed')
+            "#,
+        )
+        .bind(user_id)
+        .bind(resource_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list licensed orders: {}", e)))
+    }
+
+    /// 占用一次下载次数，已达上限时返回 false（可在业务事务内调用）
+    pub async fn consume_download_in_tx(
+        conn: &mut PgConnection,
+        order_id: i64,
+        max_downloads: i32,
+    ) -> Result<bool, RswsError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE orders
+            SET download_count = download_count + 1, updated_at = NOW()
+            WHERE id = $1 AND download_count < $2
+            "#,
+        )
+        .bind(order_id)
+        .bind(max_downloads)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to consume download: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
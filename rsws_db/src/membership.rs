@@ -0,0 +1,348 @@
+//! 会员仓储层
+
+use chrono::Utc;
+use rsws_common::error::RswsError;
+use rsws_common::snowflake;
+use rsws_model::membership::{
+    next_period, CreateMembershipPlanRequest, MembershipExpiryReminder, MembershipPlan,
+    UpdateMembershipPlanRequest, UserMembership, UserMembershipDetail, MEMBERSHIP_ACTIVE,
+};
+use rsws_model::payment::{Order, PaymentQuote};
+use sqlx::PgPool;
+
+use crate::order::ORDER_COLUMNS;
+
+const PLAN_COLUMNS: &str = "id, name, description, category_id, duration_days, price, is_active, sort_order, created_at, updated_at";
+const MEMBERSHIP_COLUMNS: &str = "id, user_id, plan_id, category_id, order_id, status, starts_at, expires_at, reminded_at, created_at, updated_at";
+
+/// 会员仓储
+pub struct MembershipRepository {
+    pool: PgPool,
+}
+
+impl MembershipRepository {
+    /// 创建会员仓储实例
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // ==================== 套餐 ====================
+
+    /// 获取套餐列表
+    pub async fn list_plans(&self, active_only: bool) -> Result<Vec<MembershipPlan>, RswsError> {
+        sqlx::query_as::<_, MembershipPlan>(&format!(
+            "SELECT {} FROM membership_plans WHERE ($1 = false OR is_active = true) ORDER BY sort_order, created_at",
+            PLAN_COLUMNS
+        ))
+        .bind(active_only)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list membership plans: {}", e)))
+    }
+
+    /// 根据 ID 获取套餐
+    pub async fn get_plan(&self, id: i64) -> Result<Option<MembershipPlan>, RswsError> {
+        sqlx::query_as::<_, MembershipPlan>(&format!(
+            "SELECT {} FROM membership_plans WHERE id = $1",
+            PLAN_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to get membership plan: {}", e)))
+    }
+
+    /// 创建套餐
+    pub async fn create_plan(
+        &self,
+        req: &CreateMembershipPlanRequest,
+    ) -> Result<MembershipPlan, RswsError> {
+        sqlx::query_as::<_, MembershipPlan>(&format!(
+            r#"
+            INSERT INTO membership_plans
+                (id, name, description, category_id, duration_days, price, is_active, sort_order, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, true, $7, NOW(), NOW())
+            RETURNING {}
+            "#,
+            PLAN_COLUMNS
+        ))
+        .bind(snowflake::next_id())
+        .bind(&req.name)
+        .bind(&req.description)
+        .bind(req.category_id)
+        .bind(req.duration_days)
+        .bind(req.price)
+        .bind(req.sort_order.unwrap_or(0))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to create membership plan: {}", e)))
+    }
+
+    /// 更新套餐（授权分类不可修改，已售订阅按下单时快照授权）
+    pub async fn update_plan(
+        &self,
+        id: i64,
+        req: &UpdateMembershipPlanRequest,
+    ) -> Result<Option<MembershipPlan>, RswsError> {
+        sqlx::query_as::<_, MembershipPlan>(&format!(
+            r#"
+            UPDATE membership_plans
+            SET name = COALESCE($2, name),
+                description = COALESCE($3, description),
+                duration_days = COALESCE($4, duration_days),
+                price = COALESCE($5, price),
+                is_active = COALESCE($6, is_active),
+                sort_order = COALESCE($7, sort_order),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            PLAN_COLUMNS
+        ))
+        .bind(id)
+        .bind(&req.name)
+        .bind(&req.description)
+        .bind(req.duration_days)
+        .bind(req.price)
+        .bind(req.is_active)
+        .bind(req.sort_order)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update membership plan: {}", e)))
+    }
+
+    // ==================== 订阅 ====================
+
+    /// 创建会员订单及待支付订阅（同一事务）
+    ///
+    /// 会员订单的 `resource_id` 为 NULL，支付流程与资源订单一致。
+    pub async fn create_order(
+        &self,
+        user_id: i64,
+        plan: &MembershipPlan,
+        quote: &PaymentQuote,
+        expire_minutes: i32,
+    ) -> Result<(Order, UserMembership), RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let order = sqlx::query_as::<_, Order>(&format!(
+            r#"
+            INSERT INTO orders (id, user_id, resource_id, amount, surcharge, fee_amount, status, payment_method, created_at, updated_at, expired_at)
+            VALUES ($1, $2, NULL, $3, $4, $5, 'pending', $6, NOW(), NOW(), NOW() + INTERVAL '1 minute' * $7)
+            RETURNING {}
+            "#,
+            ORDER_COLUMNS
+        ))
+        .bind(snowflake::next_id())
+        .bind(user_id)
+        .bind(quote.total)
+        .bind(quote.surcharge)
+        .bind(quote.fee)
+        .bind(&quote.payment_method)
+        .bind(expire_minutes)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to create membership order: {}", e)))?;
+
+        let membership = sqlx::query_as::<_, UserMembership>(&format!(
+            r#"
+            INSERT INTO user_memberships (id, user_id, plan_id, category_id, order_id, status, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, 'pending', NOW(), NOW())
+            RETURNING {}
+            "#,
+            MEMBERSHIP_COLUMNS
+        ))
+        .bind(snowflake::next_id())
+        .bind(user_id)
+        .bind(plan.id)
+        .bind(plan.category_id)
+        .bind(order.id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to create membership: {}", e)))?;
+
+        tx.commit().await.map_err(|e| {
+            RswsError::internal(format!("Failed to commit membership order: {}", e))
+        })?;
+
+        Ok((order, membership))
+    }
+
+    /// 订单支付后激活对应订阅（幂等）
+    ///
+    /// 锁定该用户同一套餐的全部订阅后计算新一期起止时间，避免并发续费重叠。
+    /// 订单不是会员订单、尚未支付或已激活时返回 `Ok(None)`。
+    pub async fn activate_for_order(
+        &self,
+        order_id: i64,
+    ) -> Result<Option<UserMembership>, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let pending: Option<(i64, i64, i64, i32)> = sqlx::query_as(
+            r#"
+            SELECT m.id, m.user_id, m.plan_id, p.duration_days
+            FROM user_memberships m
+            JOIN orders o ON o.id = m.order_id
+            JOIN membership_plans p ON p.id = m.plan_id
+            WHERE m.order_id = $1 AND m.status = 'pending'
+              AND o.status IN ('paid', 'completed')
+            "#,
+        )
+        .bind(order_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load membership: {}", e)))?;
+
+        let Some((membership_id, user_id, plan_id, duration_days)) = pending else {
+            return Ok(None);
+        };
+
+        let current: Vec<UserMembership> = sqlx::query_as::<_, UserMembership>(&format!(
+            "SELECT {} FROM user_memberships WHERE user_id = $1 AND plan_id = $2 FOR UPDATE",
+            MEMBERSHIP_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(plan_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to lock memberships: {}", e)))?;
+
+        // 加锁后复查，并发激活时只有一方生效
+        if current
+            .iter()
+            .any(|m| m.id == membership_id && m.status == MEMBERSHIP_ACTIVE)
+        {
+            return Ok(None);
+        }
+
+        let current_expires_at = current
+            .iter()
+            .filter(|m| m.status == MEMBERSHIP_ACTIVE)
+            .filter_map(|m| m.expires_at)
+            .max();
+        let (starts_at, expires_at) = next_period(Utc::now(), current_expires_at, duration_days);
+
+        let activated = sqlx::query_as::<_, UserMembership>(&format!(
+            r#"
+            UPDATE user_memberships
+            SET status = 'active', starts_at = $2, expires_at = $3, updated_at = NOW()
+            WHERE id = $1 AND status = 'pending'
+            RETURNING {}
+            "#,
+            MEMBERSHIP_COLUMNS
+        ))
+        .bind(membership_id)
+        .bind(starts_at)
+        .bind(expires_at)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to activate membership: {}", e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit membership: {}", e)))?;
+
+        Ok(activated)
+    }
+
+    /// 获取已支付但订阅尚未激活的订单 ID
+    pub async fn list_paid_pending_orders(&self, limit: i64) -> Result<Vec<i64>, RswsError> {
+        let rows: Vec<(i64,)> = sqlx::query_as(
+            r#"
+            SELECT m.order_id
+            FROM user_memberships m
+            JOIN orders o ON o.id = m.order_id
+            WHERE m.status = 'pending' AND o.status IN ('paid', 'completed')
+            ORDER BY o.updated_at
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list pending memberships: {}", e)))?;
+
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// 获取用户订阅记录（不含未支付）
+    pub async fn list_by_user(&self, user_id: i64) -> Result<Vec<UserMembershipDetail>, RswsError> {
+        sqlx::query_as::<_, UserMembershipDetail>(
+            r#"
+            SELECT m.id, m.plan_id, p.name AS plan_name, m.category_id, m.order_id,
+                   m.status, m.starts_at, m.expires_at, m.created_at
+            FROM user_memberships m
+            JOIN membership_plans p ON p.id = m.plan_id
+            WHERE m.user_id = $1 AND m.status = 'active'
+            ORDER BY m.expires_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list memberships: {}", e)))
+    }
+
+    /// 获取将在 `within_days` 天内到期且未续费、未提醒的订阅
+    pub async fn list_expiring(
+        &self,
+        within_days: i32,
+        limit: i64,
+    ) -> Result<Vec<MembershipExpiryReminder>, RswsError> {
+        sqlx::query_as::<_, MembershipExpiryReminder>(
+            r#"
+            SELECT m.id AS membership_id, m.user_id, COALESCE(u.email, '') AS email,
+                   p.name AS plan_name, m.expires_at
+            FROM user_memberships m
+            JOIN membership_plans p ON p.id = m.plan_id
+            JOIN users u ON u.id = m.user_id
+            WHERE m.status = 'active' AND m.reminded_at IS NULL
+              AND m.expires_at > NOW()
+              AND m.expires_at <= NOW() + INTERVAL '1 day' * $1
+              AND NOT EXISTS (
+                  SELECT 1 FROM user_memberships n
+                  WHERE n.user_id = m.user_id AND n.plan_id = m.plan_id
+                    AND n.status = 'active' AND n.expires_at > m.expires_at
+              )
+            ORDER BY m.expires_at
+            LIMIT $2
+            "#,
+        )
+        .bind(within_days)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list expiring memberships: {}", e)))
+    }
+
+    /// 抢占到期提醒发送权，返回是否抢占成功
+    pub async fn claim_reminder(&self, membership_id: i64) -> Result<bool, RswsError> {
+        let result = sqlx::query(
+            "UPDATE user_memberships SET reminded_at = NOW() WHERE id = $1 AND reminded_at IS NULL",
+        )
+        .bind(membership_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to claim reminder: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 发送失败时释放提醒发送权，等待下次重试
+    pub async fn release_reminder(&self, membership_id: i64) -> Result<(), RswsError> {
+        sqlx::query("UPDATE user_memberships SET reminded_at = NULL WHERE id = $1")
+            .bind(membership_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to release reminder: {}", e)))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+//! 资源审核仓储层
+
+use rsws_common::error::RswsError;
+use rsws_common::snowflake;
+use rsws_model::moderation::{
+    CreatorContact, ModerationQueueItem, ResourceReview, ACTOR_TYPE_ADMIN,
+};
+use rsws_model::resource::Resource;
+use sqlx::{PgConnection, PgPool};
+
+const REVIEW_COLUMNS: &str =
+    "id, resource_id, action, from_status, to_status, reason, actor_id, actor_type, created_at";
+
+/// 审核记录
+pub struct ReviewEvent<'a> {
+    pub resource_id: i64,
+    /// submit / approve / reject / take_down
+    pub action: &'a str,
+    pub from_status: &'a str,
+    pub to_status: &'a str,
+    pub reason: Option<&'a str>,
+    pub actor_id: Option<i64>,
+    /// user / admin
+    pub actor_type: &'a str,
+}
+
+/// 资源审核仓储
+pub struct ModerationRepository {
+    pool: PgPool,
+}
+
+impl ModerationRepository {
+    /// 创建资源审核仓储实例
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// 在事务内写入审核记录
+    pub async fn record_event_in_tx(
+        conn: &mut PgConnection,
+        event: &ReviewEvent<'_>,
+    ) -> Result<(), RswsError> {
+        sqlx::query(
+            r#"
+            INSERT INTO resource_reviews
+                (id, resource_id, action, from_status, to_status, reason, actor_id, actor_type, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW())
+            "#,
+        )
+        .bind(snowflake::next_id())
+        .bind(event.resource_id)
+        .bind(event.action)
+        .bind(event.from_status)
+        .bind(event.to_status)
+        .bind(event.reason)
+        .bind(event.actor_id)
+        .bind(event.actor_type)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to record resource review: {}", e)))?;
+        Ok(())
+    }
+
+    /// 根据 ID 获取资源（含未上架、未审核的资源）
+    pub async fn find_resource(&self, resource_id: i64) -> Result<Option<Resource>, RswsError> {
+        sqlx::query_as::<_, Resource>("SELECT * FROM resources WHERE id = $1")
+            .bind(resource_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to get resource: {}", e)))
+    }
+
+    /// 变更审核状态并写入审核记录，状态已被并发修改时返回 None
+    ///
+    /// 提交审核时刷新提交时间并清空上次的拒绝原因；管理员决定记录审核人与审核时间。
+    pub async fn transition(&self, event: &ReviewEvent<'_>) -> Result<Option<Resource>, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let is_admin = event.actor_type == ACTOR_TYPE_ADMIN;
+        let resource = sqlx::query_as::<_, Resource>(
+            r#"
+            UPDATE resources
+            SET review_status = $3,
+                review_reason = $4,
+                submitted_at = CASE WHEN $3 = 'pending' THEN NOW() ELSE submitted_at END,
+                reviewed_by = CASE WHEN $5 THEN $6 ELSE reviewed_by END,
+                reviewed_at = CASE WHEN $5 THEN NOW() ELSE reviewed_at END,
+                updated_at = NOW()
+            WHERE id = $1 AND review_status = $2
+            RETURNING *
+            "#,
+        )
+        .bind(event.resource_id)
+        .bind(event.from_status)
+        .bind(event.to_status)
+        .bind(event.reason)
+        .bind(is_admin)
+        .bind(event.actor_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update review status: {}", e)))?;
+
+        if resource.is_none() {
+            return Ok(None);
+        }
+        Self::record_event_in_tx(&mut *tx, event).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit review: {}", e)))?;
+
+        Ok(resource)
+    }
+
+    /// 分页获取指定审核状态的用户资源（待审核按提交时间先后排序）
+    pub async fn queue(
+        &self,
+        status: &str,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<ModerationQueueItem>, i64), RswsError> {
+        let offset = (page - 1) * page_size;
+
+        let items = sqlx::query_as::<_, ModerationQueueItem>(
+            r#"
+            SELECT r.id AS resource_id, r.title, r.description, r.price, r.category_id,
+                   r.file_url, r.thumbnail_url, r.provider_id, u.username AS provider_name,
+                   r.review_status, r.review_reason, r.submitted_at, r.reviewed_at,
+                   r.created_at, r.updated_at
+            FROM resources r
+            LEFT JOIN users u ON u.id = r.provider_id
+            WHERE r.owner_type = 'user' AND r.review_status = $1
+            ORDER BY r.submitted_at ASC NULLS LAST, r.created_at ASC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(status)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list moderation queue: {}", e)))?;
+
+        let total: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM resources WHERE owner_type = 'user' AND review_status = $1",
+        )
+        .bind(status)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to count moderation queue: {}", e)))?;
+
+        Ok((items, total.0))
+    }
+
+    /// 获取资源的审核记录
+    pub async fn history(&self, resource_id: i64) -> Result<Vec<ResourceReview>, RswsError> {
+        sqlx::query_as::<_, ResourceReview>(&format!(
+            "SELECT {} FROM resource_reviews WHERE resource_id = $1 ORDER BY created_at",
+            REVIEW_COLUMNS
+        ))
+        .bind(resource_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load resource reviews: {}", e)))
+    }
+
+    /// 获取创作者联系方式
+    pub async fn creator_contact(&self, user_id: i64) -> Result<Option<CreatorContact>, RswsError> {
+        sqlx::query_as::<_, CreatorContact>(
+            "SELECT id AS user_id, email, username FROM users WHERE id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load creator contact: {}", e)))
+    }
+}
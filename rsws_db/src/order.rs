@@ -3,10 +3,12 @@
 use rsws_common::error::RswsError;
 use rsws_common::error_code::ErrorCode;
 use rsws_common::snowflake;
-use rsws_model::payment::{Order, OrderDetail};
-use rust_decimal::Decimal;
+use rsws_model::payment::{Order, OrderDetail, PaymentQuote};
 use sqlx::PgPool;
 
+/// orders 表映射到 `Order` 的字段
+pub const ORDER_COLUMNS: &str = "id, user_id, resource_id, amount, status, payment_method, created_at, updated_at, expired_at, license_id, surcharge, fee_amount, risk_score, risk_status";
+
 /// 订单仓储
 pub struct OrderRepository {
     pool: PgPool,
@@ -23,24 +25,28 @@ impl OrderRepository {
         &self,
         user_id: i64,
         resource_id: i64,
-        amount: Decimal,
-        payment_method: &str,
+        license_id: Option<i64>,
+        quote: &PaymentQuote,
         expire_minutes: i32,
     ) -> Result<Order, RswsError> {
         let order_id = snowflake::next_id();
 
-        let order = sqlx::query_as::<_, Order>(
+        let order = sqlx::query_as::<_, Order>(&format!(
             r#"
-            INSERT INTO orders (id, user_id, resource_id, amount, status, payment_method, created_at, updated_at, expired_at)
-            VALUES ($1, $2, $3, $4, 'pending', $5, NOW(), NOW(), NOW() + INTERVAL '1 minute' * $6)
-            RETURNING id, user_id, resource_id, amount, status, payment_method, created_at, updated_at, expired_at
+            INSERT INTO orders (id, user_id, resource_id, license_id, amount, surcharge, fee_amount, status, payment_method, created_at, updated_at, expired_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, 'pending', $8, NOW(), NOW(), NOW() + INTERVAL '1 minute' * $9)
+            RETURNING {}
             "#,
-        )
+            ORDER_COLUMNS
+        ))
         .bind(order_id)
         .bind(user_id)
         .bind(resource_id)
-        .bind(amount)
-        .bind(payment_method)
+        .bind(license_id)
+        .bind(quote.total)
+        .bind(quote.surcharge)
+        .bind(quote.fee)
+        .bind(&quote.payment_method)
         .bind(expire_minutes)
         .fetch_one(&self.pool)
         .await
@@ -57,9 +63,10 @@ impl OrderRepository {
 
     /// 根据 ID 获取订单
     pub async fn get_by_id(&self, id: i64) -> Result<Option<Order>, RswsError> {
-        let order = sqlx::query_as::<_, Order>(
-            "SELECT id, user_id, resource_id, amount, status, payment_method, created_at, updated_at, expired_at FROM orders WHERE id = $1",
-        )
+        let order = sqlx::query_as::<_, Order>(&format!(
+            "SELECT {} FROM orders WHERE id = $1",
+            ORDER_COLUMNS
+        ))
         .bind(id)
         .fetch_optional(&self.pool)
         .await
@@ -78,15 +85,16 @@ impl OrderRepository {
         let offset = (page - 1) * page_size;
 
         // 获取订单列表
-        let orders = sqlx::query_as::<_, Order>(
+        let orders = sqlx::query_as::<_, Order>(&format!(
             r#"
-            SELECT id, user_id, resource_id, amount, status, payment_method, created_at, updated_at, expired_at
+            SELECT {}
             FROM orders
             WHERE user_id = $1
             ORDER BY created_at DESC
             LIMIT $2 OFFSET $3
             "#,
-        )
+            ORDER_COLUMNS
+        ))
         .bind(user_id)
         .bind(page_size)
         .bind(offset)
@@ -107,7 +115,13 @@ impl OrderRepository {
     /// 更新订单状态
     pub async fn update_status(&self, order_id: i64, status: &str) -> Result<(), RswsError> {
         sqlx::query(
-            "UPDATE orders SET status = $1::order_status, updated_at = NOW() WHERE id = $2",
+            r#"
+            UPDATE orders
+            SET status = $1::order_status,
+                paid_at = CASE WHEN $1 IN ('paid', 'completed') THEN COALESCE(paid_at, NOW()) ELSE paid_at END,
+                updated_at = NOW()
+            WHERE id = $2
+            "#,
         )
         .bind(status)
         .bind(order_id)
@@ -118,6 +132,19 @@ impl OrderRepository {
         Ok(())
     }
 
+    /// 取消待支付订单，订单已不是待支付状态时返回 false
+    pub async fn cancel_if_pending(&self, order_id: i64) -> Result<bool, RswsError> {
+        let result = sqlx::query(
+            "UPDATE orders SET status = 'cancelled'::order_status, updated_at = NOW() WHERE id = $1 AND status = 'pending'::order_status",
+        )
+        .bind(order_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to cancel order: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     /// 获取用户订单列表
     pub async fn list_by_user(
         &self,
@@ -142,9 +169,11 @@ impl OrderRepository {
         let orders = sqlx::query_as::<_, OrderDetail>(
             r#"
             SELECT o.id, o.user_id, o.resource_id, o.amount, o.status, o.payment_method,
-                   o.created_at, o.updated_at, o.expired_at, r.title as resource_title
+                   o.created_at, o.updated_at, o.expired_at, COALESCE(r.title, mp.name) as resource_title
             FROM orders o
             LEFT JOIN resources r ON o.resource_id = r.id
+            LEFT JOIN user_memberships um ON um.order_id = o.id
+            LEFT JOIN membership_plans mp ON mp.id = um.plan_id
             WHERE o.user_id = $1
             ORDER BY o.created_at DESC
             LIMIT $2 OFFSET $3
@@ -203,12 +232,14 @@ impl OrderRepository {
         let base_select = format!(
             r#"
             SELECT o.id, o.user_id, u.username as user_name, u.email as user_email,
-                   o.resource_id, r.title as resource_title,
+                   o.resource_id, COALESCE(r.title, mp.name) as resource_title,
                    o.amount, o.status, o.payment_method,
                    o.created_at, o.updated_at, o.expired_at
             FROM orders o
             LEFT JOIN users u ON o.user_id = u.id
             LEFT JOIN resources r ON o.resource_id = r.id
+            LEFT JOIN user_memberships um ON um.order_id = o.id
+            LEFT JOIN membership_plans mp ON mp.id = um.plan_id
             {} ORDER BY o.created_at DESC
             "#,
             where_clause
@@ -258,13 +289,39 @@ impl OrderRepository {
     }
 
     /// 检查用户是否已购买资源
+    ///
+    /// 资源订单已支付，或有效期内的会员订阅覆盖资源所在分类（含上级分类）时视为已购买。
+    /// 上级分类沿 `parent_id` 逐级查找（最多 32 层）。
     pub async fn check_user_purchased(
         &self,
         user_id: i64,
         resource_id: i64,
     ) -> Result<bool, RswsError> {
-        let count: (i64,) = sqlx::query_as(
-            "SELECT COUNT(*) FROM orders WHERE user_id = $1 AND resource_id = $2 AND status IN ('paid', 'completed')",
+        let purchased: (bool,) = sqlx::query_as(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM orders
+                WHERE user_id = $1 AND resource_id = $2 AND status IN ('paid', 'completed')
+            ) OR EXISTS (
+                WITH RECURSIVE ancestors(id, parent_id, depth) AS (
+                    SELECT c.id, c.parent_id, 0
+                    FROM resources r
+                    JOIN categories c ON c.id = r.category_id
+                    WHERE r.id = $2
+                    UNION ALL
+                    SELECT c.id, c.parent_id, a.depth + 1
+                    FROM categories c
+                    JOIN ancestors a ON c.id = a.parent_id
+                    WHERE a.depth < 32
+                )
+                SELECT 1
+                FROM ancestors a
+                JOIN user_memberships m ON m.category_id = a.id
+                WHERE m.user_id = $1
+                  AND m.status = 'active'
+                  AND m.starts_at <= NOW() AND m.expires_at > NOW()
+            )
+            "#,
         )
         .bind(user_id)
         .bind(resource_id)
@@ -272,7 +329,7 @@ impl OrderRepository {
         .await
         .map_err(|e| RswsError::internal(format!("Failed to check purchase: {}", e)))?;
 
-        Ok(count.0 > 0)
+        Ok(purchased.0)
     }
 
     /// 清理过期订单
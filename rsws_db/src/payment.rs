@@ -332,6 +332,260 @@ impl PayPalConfigRepository {
     }
 }
 
+// ==================== 支付宝配置仓储 ====================
+
+use rsws_model::payment::{AlipayConfig, WechatPayConfig};
+
+/// 支付宝配置仓储
+pub struct AlipayConfigRepository {
+    pool: PgPool,
+}
+
+impl AlipayConfigRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// 获取所有支付宝配置
+    pub async fn list_all(&self) -> Result<Vec<AlipayConfig>, RswsError> {
+        sqlx::query_as::<_, AlipayConfig>(
+            "SELECT id, app_id, private_key, alipay_public_key, sandbox, notify_url, return_url, exchange_rate, min_amount, max_amount, fee_rate, is_active, created_at, updated_at FROM alipay_configs ORDER BY id",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list alipay configs: {}", e)))
+    }
+
+    /// 根据 ID 获取支付宝配置
+    pub async fn get_by_id(&self, id: i64) -> Result<Option<AlipayConfig>, RswsError> {
+        sqlx::query_as::<_, AlipayConfig>(
+            "SELECT id, app_id, private_key, alipay_public_key, sandbox, notify_url, return_url, exchange_rate, min_amount, max_amount, fee_rate, is_active, created_at, updated_at FROM alipay_configs WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to get alipay config: {}", e)))
+    }
+
+    /// 创建支付宝配置
+    pub async fn create(
+        &self,
+        req: &rsws_model::payment::CreateAlipayConfigRequest,
+    ) -> Result<AlipayConfig, RswsError> {
+        let id = rsws_common::snowflake::next_id();
+        sqlx::query_as::<_, AlipayConfig>(
+            r#"INSERT INTO alipay_configs (
+                id, app_id, private_key, alipay_public_key, sandbox,
+                notify_url, return_url, exchange_rate,
+                min_amount, max_amount, fee_rate, is_active
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING id, app_id, private_key, alipay_public_key, sandbox, notify_url, return_url, exchange_rate, min_amount, max_amount, fee_rate, is_active, created_at, updated_at"#,
+        )
+        .bind(id)
+        .bind(&req.app_id)
+        .bind(&req.private_key)
+        .bind(&req.alipay_public_key)
+        .bind(req.sandbox)
+        .bind(&req.notify_url)
+        .bind(&req.return_url)
+        .bind(req.exchange_rate)
+        .bind(req.min_amount)
+        .bind(req.max_amount)
+        .bind(req.fee_rate)
+        .bind(req.is_active)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to create alipay config: {}", e)))
+    }
+
+    /// 更新支付宝配置（未提供的字段保持不变）
+    pub async fn update(
+        &self,
+        id: i64,
+        req: &rsws_model::payment::UpdateAlipayConfigRequest,
+    ) -> Result<AlipayConfig, RswsError> {
+        sqlx::query_as::<_, AlipayConfig>(
+            r#"
+            UPDATE alipay_configs SET
+                app_id = COALESCE($1, app_id),
+                private_key = COALESCE($2, private_key),
+                alipay_public_key = COALESCE($3, alipay_public_key),
+                sandbox = COALESCE($4, sandbox),
+                notify_url = COALESCE($5, notify_url),
+                return_url = COALESCE($6, return_url),
+                exchange_rate = COALESCE($7, exchange_rate),
+                min_amount = COALESCE($8, min_amount),
+                max_amount = COALESCE($9, max_amount),
+                fee_rate = COALESCE($10, fee_rate),
+                is_active = COALESCE($11, is_active),
+                updated_at = NOW()
+            WHERE id = $12
+            RETURNING id, app_id, private_key, alipay_public_key, sandbox, notify_url, return_url, exchange_rate, min_amount, max_amount, fee_rate, is_active, created_at, updated_at
+            "#,
+        )
+        .bind(req.app_id.as_deref())
+        .bind(req.private_key.as_deref())
+        .bind(req.alipay_public_key.as_deref())
+        .bind(req.sandbox)
+        .bind(req.notify_url.as_deref())
+        .bind(req.return_url.as_deref())
+        .bind(req.exchange_rate)
+        .bind(req.min_amount)
+        .bind(req.max_amount)
+        .bind(req.fee_rate)
+        .bind(req.is_active)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update alipay config: {}", e)))?
+        .ok_or_else(|| RswsError::not_found("Alipay config not found"))
+    }
+
+    /// 设置激活状态
+    pub async fn set_active(&self, id: i64, is_active: bool) -> Result<(), RswsError> {
+        sqlx::query("UPDATE alipay_configs SET is_active = $1, updated_at = NOW() WHERE id = $2")
+            .bind(is_active)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                RswsError::internal(format!("Failed to set alipay config active: {}", e))
+            })?;
+
+        Ok(())
+    }
+}
+
+// ==================== 微信支付配置仓储 ====================
+
+/// 微信支付配置仓储
+pub struct WechatPayConfigRepository {
+    pool: PgPool,
+}
+
+impl WechatPayConfigRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// 获取所有微信支付配置
+    pub async fn list_all(&self) -> Result<Vec<WechatPayConfig>, RswsError> {
+        sqlx::query_as::<_, WechatPayConfig>(
+            "SELECT id, mch_id, app_id, merchant_serial_no, merchant_private_key, api_v3_key, platform_public_key, platform_public_key_id, notify_url, exchange_rate, min_amount, max_amount, fee_rate, is_active, created_at, updated_at FROM wechatpay_configs ORDER BY id",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list wechatpay configs: {}", e)))
+    }
+
+    /// 根据 ID 获取微信支付配置
+    pub async fn get_by_id(&self, id: i64) -> Result<Option<WechatPayConfig>, RswsError> {
+        sqlx::query_as::<_, WechatPayConfig>(
+            "SELECT id, mch_id, app_id, merchant_serial_no, merchant_private_key, api_v3_key, platform_public_key, platform_public_key_id, notify_url, exchange_rate, min_amount, max_amount, fee_rate, is_active, created_at, updated_at FROM wechatpay_configs WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to get wechatpay config: {}", e)))
+    }
+
+    /// 创建微信支付配置
+    pub async fn create(
+        &self,
+        req: &rsws_model::payment::CreateWechatPayConfigRequest,
+    ) -> Result<WechatPayConfig, RswsError> {
+        let id = rsws_common::snowflake::next_id();
+        sqlx::query_as::<_, WechatPayConfig>(
+            r#"INSERT INTO wechatpay_configs (
+                id, mch_id, app_id, merchant_serial_no, merchant_private_key,
+                api_v3_key, platform_public_key, platform_public_key_id,
+                notify_url, exchange_rate, min_amount, max_amount, fee_rate, is_active
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            RETURNING id, mch_id, app_id, merchant_serial_no, merchant_private_key, api_v3_key, platform_public_key, platform_public_key_id, notify_url, exchange_rate, min_amount, max_amount, fee_rate, is_active, created_at, updated_at"#,
+        )
+        .bind(id)
+        .bind(&req.mch_id)
+        .bind(&req.app_id)
+        .bind(&req.merchant_serial_no)
+        .bind(&req.merchant_private_key)
+        .bind(&req.api_v3_key)
+        .bind(&req.platform_public_key)
+        .bind(req.platform_public_key_id.as_deref())
+        .bind(&req.notify_url)
+        .bind(req.exchange_rate)
+        .bind(req.min_amount)
+        .bind(req.max_amount)
+        .bind(req.fee_rate)
+        .bind(req.is_active)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to create wechatpay config: {}", e)))
+    }
+
+    /// 更新微信支付配置（未提供的字段保持不变）
+    pub async fn update(
+        &self,
+        id: i64,
+        req: &rsws_model::payment::UpdateWechatPayConfigRequest,
+    ) -> Result<WechatPayConfig, RswsError> {
+        sqlx::query_as::<_, WechatPayConfig>(
+            r#"
+            UPDATE wechatpay_configs SET
+                mch_id = COALESCE($1, mch_id),
+                app_id = COALESCE($2, app_id),
+                merchant_serial_no = COALESCE($3, merchant_serial_no),
+                merchant_private_key = COALESCE($4, merchant_private_key),
+                api_v3_key = COALESCE($5, api_v3_key),
+                platform_public_key = COALESCE($6, platform_public_key),
+                platform_public_key_id = COALESCE($7, platform_public_key_id),
+                notify_url = COALESCE($8, notify_url),
+                exchange_rate = COALESCE($9, exchange_rate),
+                min_amount = COALESCE($10, min_amount),
+                max_amount = COALESCE($11, max_amount),
+                fee_rate = COALESCE($12, fee_rate),
+                is_active = COALESCE($13, is_active),
+                updated_at = NOW()
+            WHERE id = $14
+            RETURNING id, mch_id, app_id, merchant_serial_no, merchant_private_key, api_v3_key, platform_public_key, platform_public_key_id, notify_url, exchange_rate, min_amount, max_amount, fee_rate, is_active, created_at, updated_at
+            "#,
+        )
+        .bind(req.mch_id.as_deref())
+        .bind(req.app_id.as_deref())
+        .bind(req.merchant_serial_no.as_deref())
+        .bind(req.merchant_private_key.as_deref())
+        .bind(req.api_v3_key.as_deref())
+        .bind(req.platform_public_key.as_deref())
+        .bind(req.platform_public_key_id.as_deref())
+        .bind(req.notify_url.as_deref())
+        .bind(req.exchange_rate)
+        .bind(req.min_amount)
+        .bind(req.max_amount)
+        .bind(req.fee_rate)
+        .bind(req.is_active)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update wechatpay config: {}", e)))?
+        .ok_or_else(|| RswsError::not_found("WeChat Pay config not found"))
+    }
+
+    /// 设置激活状态
+    pub async fn set_active(&self, id: i64, is_active: bool) -> Result<(), RswsError> {
+        sqlx::query(
+            "UPDATE wechatpay_configs SET is_active = $1, updated_at = NOW() WHERE id = $2",
+        )
+        .bind(is_active)
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            RswsError::internal(format!("Failed to set wechatpay config active: {}", e))
+        })?;
+
+        Ok(())
+    }
+}
+
 // ==================== 单元测试 ====================
 
 #[cfg(test)]
@@ -0,0 +1,317 @@
+//! 资源评分与评价仓储层
+
+use rsws_common::error::RswsError;
+use rsws_common::snowflake;
+use rsws_model::rating::{
+    RatingListItem, ReportedRating, ResourceRating, RATING_STATUS_HIDDEN, RATING_STATUS_VISIBLE,
+    REPORT_STATUS_OPEN, REPORT_STATUS_RESOLVED,
+};
+use sqlx::{PgConnection, PgPool};
+
+const RATING_COLUMNS: &str = "id, resource_id, user_id, rating, content, reply, replied_at, status, hidden_reason, report_count, created_at, updated_at";
+
+/// 资源评分仓储
+pub struct RatingRepository {
+    pool: PgPool,
+}
+
+impl RatingRepository {
+    /// 创建资源评分仓储实例
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// 在事务内按可见评价重新计算资源的平均分与评分数
+    pub async fn refresh_summary_in_tx(
+        conn: &mut PgConnection,
+        resource_id: i64,
+    ) -> Result<(), RswsError> {
+        sqlx::query(
+            r#"
+            UPDATE resources r
+            SET rating_avg = s.avg, rating_count = s.count
+            FROM (
+                SELECT COALESCE(ROUND(AVG(rating), 2), 0) AS avg, COUNT(*)::INTEGER AS count
+                FROM resource_ratings
+                WHERE resource_id = $1 AND status = $2
+            ) s
+            WHERE r.id = $1
+            "#,
+        )
+        .bind(resource_id)
+        .bind(RATING_STATUS_VISIBLE)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to refresh rating summary: {}", e)))?;
+        Ok(())
+    }
+
+    /// 提交或修改评价，并刷新资源评分汇总
+    pub async fn upsert(
+        &self,
+        resource_id: i64,
+        user_id: i64,
+        rating: i16,
+        content: Option<&str>,
+    ) -> Result<ResourceRating, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let saved = sqlx::query_as::<_, ResourceRating>(&format!(
+            r#"
+            INSERT INTO resource_ratings
+                (id, resource_id, user_id, rating, content, status, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, NOW(), NOW())
+            ON CONFLICT (resource_id, user_id) DO UPDATE
+            SET rating = EXCLUDED.rating, content = EXCLUDED.content, updated_at = NOW()
+            RETURNING {}
+            "#,
+            RATING_COLUMNS
+        ))
+        .bind(snowflake::next_id())
+        .bind(resource_id)
+        .bind(user_id)
+        .bind(rating)
+        .bind(content)
+        .bind(RATING_STATUS_VISIBLE)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to save rating: {}", e)))?;
+
+        Self::refresh_summary_in_tx(&mut *tx, resource_id).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit rating: {}", e)))?;
+
+        Ok(saved)
+    }
+
+    /// 根据 ID 获取评价
+    pub async fn find(&self, id: i64) -> Result<Option<ResourceRating>, RswsError> {
+        sqlx::query_as::<_, ResourceRating>(&format!(
+            "SELECT {} FROM resource_ratings WHERE id = $1",
+            RATING_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to get rating: {}", e)))
+    }
+
+    /// 资源的可见评价（最新在前）
+    pub async fn list_visible(
+        &self,
+        resource_id: i64,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<RatingListItem>, i64), RswsError> {
+        let offset = (page - 1) * page_size;
+
+        let items = sqlx::query_as::<_, RatingListItem>(
+            r#"
+            SELECT rr.id, rr.user_id, u.username, rr.rating, rr.content,
+                   rr.reply, rr.replied_at, rr.created_at, rr.updated_at
+            FROM resource_ratings rr
+            JOIN users u ON u.id = rr.user_id
+            WHERE rr.resource_id = $1 AND rr.status = $2
+            ORDER BY rr.created_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(resource_id)
+        .bind(RATING_STATUS_VISIBLE)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list ratings: {}", e)))?;
+
+        let total: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM resource_ratings WHERE resource_id = $1 AND status = $2",
+        )
+        .bind(resource_id)
+        .bind(RATING_STATUS_VISIBLE)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to count ratings: {}", e)))?;
+
+        Ok((items, total.0))
+    }
+
+    /// 创作者回复（再次回复覆盖上一次）
+    pub async fn reply(&self, id: i64, reply: &str) -> Result<Option<ResourceRating>, RswsError> {
+        sqlx::query_as::<_, ResourceRating>(&format!(
+            r#"
+            UPDATE resource_ratings
+            SET reply = $2, replied_at = NOW(), updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            RATING_COLUMNS
+        ))
+        .bind(id)
+        .bind(reply)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to reply rating: {}", e)))
+    }
+
+    /// 举报评价，同一用户重复举报时返回 false
+    pub async fn report(
+        &self,
+        rating_id: i64,
+        reporter_id: i64,
+        reason: &str,
+    ) -> Result<bool, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO rating_reports (id, rating_id, reporter_id, reason, status, created_at)
+            VALUES ($1, $2, $3, $4, $5, NOW())
+            ON CONFLICT (rating_id, reporter_id) DO NOTHING
+            "#,
+        )
+        .bind(snowflake::next_id())
+        .bind(rating_id)
+        .bind(reporter_id)
+        .bind(reason)
+        .bind(REPORT_STATUS_OPEN)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to report rating: {}", e)))?
+        .rows_affected()
+            > 0;
+
+        if inserted {
+            sqlx::query(
+                "UPDATE resource_ratings SET report_count = report_count + 1 WHERE id = $1",
+            )
+            .bind(rating_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to update report count: {}", e)))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit rating report: {}", e)))?;
+
+        Ok(inserted)
+    }
+
+    /// 有待处理举报的评价（举报数多的在前）
+    pub async fn reported(
+        &self,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<ReportedRating>, i64), RswsError> {
+        let offset = (page - 1) * page_size;
+
+        let items = sqlx::query_as::<_, ReportedRating>(
+            r#"
+            SELECT rr.id AS rating_id, rr.resource_id, r.title AS resource_title,
+                   rr.user_id, rr.rating, rr.content, rr.status,
+                   COUNT(rp.id) AS open_reports,
+                   ARRAY_AGG(rp.reason ORDER BY rp.created_at) AS reasons,
+                   MAX(rp.created_at) AS last_reported_at
+            FROM rating_reports rp
+            JOIN resource_ratings rr ON rr.id = rp.rating_id
+            JOIN resources r ON r.id = rr.resource_id
+            WHERE rp.status = $1
+            GROUP BY rr.id, r.title
+            ORDER BY open_reports DESC, last_reported_at
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(REPORT_STATUS_OPEN)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list reported ratings: {}", e)))?;
+
+        let total: (i64,) = sqlx::query_as(
+            "SELECT COUNT(DISTINCT rating_id) FROM rating_reports WHERE status = $1",
+        )
+        .bind(REPORT_STATUS_OPEN)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to count reported ratings: {}", e)))?;
+
+        Ok((items, total.0))
+    }
+
+    /// 管理员隐藏或恢复评价：关闭待处理举报，并刷新资源评分汇总
+    pub async fn set_status(
+        &self,
+        id: i64,
+        status: &str,
+        reason: Option<&str>,
+        admin_id: i64,
+    ) -> Result<Option<ResourceRating>, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let hidden = status == RATING_STATUS_HIDDEN;
+        let updated = sqlx::query_as::<_, ResourceRating>(&format!(
+            r#"
+            UPDATE resource_ratings
+            SET status = $2,
+                hidden_reason = CASE WHEN $3 THEN $4 ELSE NULL END,
+                hidden_by = CASE WHEN $3 THEN $5 ELSE NULL END,
+                hidden_at = CASE WHEN $3 THEN NOW() ELSE NULL END,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            RATING_COLUMNS
+        ))
+        .bind(id)
+        .bind(status)
+        .bind(hidden)
+        .bind(reason)
+        .bind(admin_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update rating status: {}", e)))?;
+
+        let Some(updated) = updated else {
+            return Ok(None);
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE rating_reports
+            SET status = $2, resolved_by = $3, resolved_at = NOW()
+            WHERE rating_id = $1 AND status = $4
+            "#,
+        )
+        .bind(id)
+        .bind(REPORT_STATUS_RESOLVED)
+        .bind(admin_id)
+        .bind(REPORT_STATUS_OPEN)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to resolve rating reports: {}", e)))?;
+
+        Self::refresh_summary_in_tx(&mut *tx, updated.resource_id).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit rating status: {}", e)))?;
+
+        Ok(Some(updated))
+    }
+}
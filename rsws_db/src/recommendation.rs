@@ -0,0 +1,254 @@
+//! 资源推荐仓储层
+//!
+//! 推荐缓存按类型整体重算：事务内先删除旧结果再写入，读取方不会看到半截数据。
+
+use rsws_common::error::RswsError;
+use rsws_model::recommendation::{
+    PopularResource, RecommendedResource, CATEGORY_WEIGHT, CO_PURCHASE_WEIGHT,
+    POPULAR_DOWNLOAD_WEIGHT, RECOMMENDATION_KIND_ALSO_BOUGHT, RECOMMENDATION_KIND_RELATED,
+    SHARED_TAG_WEIGHT,
+};
+use sqlx::{PgConnection, PgPool};
+
+/// 公开资源与已支付订单的共同购买统计（两类推荐共用）
+const CO_PURCHASE_CTE: &str = r#"
+    visible AS (
+        SELECT id, category_id, download_count
+        FROM resources
+        WHERE is_active = true AND review_status = 'approved'
+    ),
+    buyers AS (
+        SELECT DISTINCT o.user_id, o.resource_id
+        FROM orders o
+        JOIN visible p ON p.id = o.resource_id
+        WHERE o.status IN ('paid', 'completed') AND o.user_id IS NOT NULL
+    ),
+    co_pairs AS (
+        SELECT a.resource_id, b.resource_id AS related_id, COUNT(*) AS co_buyers
+        FROM buyers a
+        JOIN buyers b ON b.user_id = a.user_id AND b.resource_id <> a.resource_id
+        GROUP BY a.resource_id, b.resource_id
+    )
+"#;
+
+/// 同分类候选上限（按下载量取前 N 个，避免大分类两两组合）
+const CATEGORY_CANDIDATES: i64 = 50;
+
+/// 资源推荐仓储
+pub struct RecommendationRepository {
+    pool: PgPool,
+}
+
+impl RecommendationRepository {
+    /// 创建资源推荐仓储实例
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// 重算相关资源与"买了还买"缓存，返回 (相关资源条数, 买了还买条数)
+    pub async fn refresh_recommendations(&self, limit: i64) -> Result<(u64, u64), RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        sqlx::query("DELETE FROM resource_recommendations")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to clear recommendations: {}", e)))?;
+
+        let related = Self::insert_related(&mut *tx, limit).await?;
+        let also_bought = Self::insert_also_bought(&mut *tx, limit).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit recommendations: {}", e)))?;
+
+        Ok((related, also_bought))
+    }
+
+    /// 相关资源：同分类 + 共同标签 + 共同购买
+    async fn insert_related(conn: &mut PgConnection, limit: i64) -> Result<u64, RswsError> {
+        let result = sqlx::query(&format!(
+            r#"
+            WITH {},
+            cat_pairs AS (
+                SELECT a.id AS resource_id, c.id AS related_id, $1::DOUBLE PRECISION AS score
+                FROM visible a
+                CROSS JOIN LATERAL (
+                    SELECT b.id
+                    FROM visible b
+                    WHERE b.category_id = a.category_id AND b.id <> a.id
+                    ORDER BY b.download_count DESC, b.id
+                    LIMIT $2
+                ) c
+                WHERE a.category_id IS NOT NULL
+            ),
+            tag_pairs AS (
+                SELECT a.resource_id, b.resource_id AS related_id,
+                       COUNT(*) * $3::DOUBLE PRECISION AS score
+                FROM resource_tags a
+                JOIN resource_tags b ON b.tag_id = a.tag_id AND b.resource_id <> a.resource_id
+                JOIN visible pa ON pa.id = a.resource_id
+                JOIN visible pb ON pb.id = b.resource_id
+                GROUP BY a.resource_id, b.resource_id
+            ),
+            scored AS (
+                SELECT resource_id, related_id, SUM(score) AS score
+                FROM (
+                    SELECT resource_id, related_id, score FROM cat_pairs
+                    UNION ALL
+                    SELECT resource_id, related_id, score FROM tag_pairs
+                    UNION ALL
+                    SELECT resource_id, related_id, LN(1 + co_buyers::DOUBLE PRECISION) * $4::DOUBLE PRECISION
+                    FROM co_pairs
+                ) s
+                GROUP BY resource_id, related_id
+            ),
+            ranked AS (
+                SELECT resource_id, related_id, score,
+                       ROW_NUMBER() OVER (PARTITION BY resource_id ORDER BY score DESC, related_id) AS rank
+                FROM scored
+            )
+            INSERT INTO resource_recommendations (resource_id, kind, related_id, score, rank, computed_at)
+            SELECT resource_id, $5, related_id, score, rank::INTEGER, NOW()
+            FROM ranked
+            WHERE rank <= $6
+            "#,
+            CO_PURCHASE_CTE
+        ))
+        .bind(CATEGORY_WEIGHT)
+        .bind(CATEGORY_CANDIDATES)
+        .bind(SHARED_TAG_WEIGHT)
+        .bind(CO_PURCHASE_WEIGHT)
+        .bind(RECOMMENDATION_KIND_RELATED)
+        .bind(limit)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to compute related resources: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// 买了还买：共同购买人数
+    async fn insert_also_bought(conn: &mut PgConnection, limit: i64) -> Result<u64, RswsError> {
+        let result = sqlx::query(&format!(
+            r#"
+            WITH {},
+            ranked AS (
+                SELECT resource_id, related_id, co_buyers,
+                       ROW_NUMBER() OVER (PARTITION BY resource_id ORDER BY co_buyers DESC, related_id) AS rank
+                FROM co_pairs
+            )
+            INSERT INTO resource_recommendations (resource_id, kind, related_id, score, rank, computed_at)
+            SELECT resource_id, $1, related_id, co_buyers::DOUBLE PRECISION, rank::INTEGER, NOW()
+            FROM ranked
+            WHERE rank <= $2
+            "#,
+            CO_PURCHASE_CTE
+        ))
+        .bind(RECOMMENDATION_KIND_ALSO_BOUGHT)
+        .bind(limit)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to compute also bought: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// 重算本周热门缓存（近 `days` 天浏览数 + 下载数加权）
+    pub async fn refresh_popular(&self, days: i32, limit: i64) -> Result<u64, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        sqlx::query("DELETE FROM popular_resources")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                RswsError::internal(format!("Failed to clear popular resources: {}", e))
+            })?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO popular_resources (resource_id, views, downloads, score, rank, computed_at)
+            SELECT resource_id, views, downloads, score,
+                   ROW_NUMBER() OVER (ORDER BY score DESC, resource_id)::INTEGER, NOW()
+            FROM (
+                SELECT s.resource_id,
+                       SUM(s.views)::BIGINT AS views,
+                       SUM(s.downloads)::BIGINT AS downloads,
+                       SUM(s.views) + SUM(s.downloads) * $2::DOUBLE PRECISION AS score
+                FROM resource_daily_stats s
+                JOIN resources r ON r.id = s.resource_id
+                WHERE s.stat_date > CURRENT_DATE - $1::INTEGER
+                  AND r.is_active = true AND r.review_status = 'approved'
+                GROUP BY s.resource_id
+            ) t
+            ORDER BY score DESC, resource_id
+            LIMIT $3
+            "#,
+        )
+        .bind(days)
+        .bind(POPULAR_DOWNLOAD_WEIGHT)
+        .bind(limit)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to compute popular resources: {}", e)))?;
+
+        tx.commit().await.map_err(|e| {
+            RswsError::internal(format!("Failed to commit popular resources: {}", e))
+        })?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// 读取资源推荐缓存（只返回当前仍公开的资源）
+    pub async fn list(
+        &self,
+        resource_id: i64,
+        kind: &str,
+        limit: i64,
+    ) -> Result<Vec<RecommendedResource>, RswsError> {
+        sqlx::query_as::<_, RecommendedResource>(
+            r#"
+            SELECT r.id, r.title, r.price, r.thumbnail_url, r.owner_type, r.provider_id,
+                   r.rating_avg, r.rating_count, r.download_count, rr.score
+            FROM resource_recommendations rr
+            JOIN resources r ON r.id = rr.related_id
+            WHERE rr.resource_id = $1 AND rr.kind = $2
+              AND r.is_active = true AND r.review_status = 'approved'
+            ORDER BY rr.rank
+            LIMIT $3
+            "#,
+        )
+        .bind(resource_id)
+        .bind(kind)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to get recommendations: {}", e)))
+    }
+
+    /// 读取本周热门缓存
+    pub async fn popular(&self, limit: i64) -> Result<Vec<PopularResource>, RswsError> {
+        sqlx::query_as::<_, PopularResource>(
+            r#"
+            SELECT r.id, r.title, r.price, r.thumbnail_url, r.owner_type, r.provider_id,
+                   r.rating_avg, r.rating_count, p.views, p.downloads, p.rank, p.computed_at
+            FROM popular_resources p
+            JOIN resources r ON r.id = p.resource_id
+            WHERE r.is_active = true AND r.review_status = 'approved'
+            ORDER BY p.rank
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to get popular resources: {}", e)))
+    }
+}
@@ -0,0 +1,296 @@
+//! 推荐计划仓储层
+
+use rsws_common::error::RswsError;
+use rsws_common::snowflake;
+use rsws_model::referral::{
+    referral_commission, ReferralCode, ReferralEarning, ReferralStats, REFERRAL_RATE_CONFIG_KEY,
+};
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, PgPool};
+
+/// 推荐计划仓储
+pub struct ReferralRepository {
+    pool: PgPool,
+}
+
+impl ReferralRepository {
+    /// 创建推荐计划仓储实例
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // ==================== 推荐码 ====================
+
+    /// 获取用户推荐码
+    pub async fn find_code(&self, user_id: i64) -> Result<Option<ReferralCode>, RswsError> {
+        sqlx::query_as::<_, ReferralCode>(
+            "SELECT user_id, code, created_at FROM referral_codes WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to get referral code: {}", e)))
+    }
+
+    /// 为用户写入推荐码，推荐码或用户已存在时返回 None
+    pub async fn insert_code(
+        &self,
+        user_id: i64,
+        code: &str,
+    ) -> Result<Option<ReferralCode>, RswsError> {
+        sqlx::query_as::<_, ReferralCode>(
+            r#"
+            INSERT INTO referral_codes (user_id, code, created_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT DO NOTHING
+            RETURNING user_id, code, created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(code)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to create referral code: {}", e)))
+    }
+
+    /// 按推荐码查找推荐人
+    pub async fn find_referrer(&self, code: &str) -> Result<Option<i64>, RswsError> {
+        let row: Option<(i64,)> = sqlx::query_as(
+            r#"
+            SELECT rc.user_id
+            FROM referral_codes rc
+            JOIN users u ON u.id = rc.user_id
+            WHERE rc.code = $1 AND u.is_active = true
+            "#,
+        )
+        .bind(code)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to find referrer: {}", e)))?;
+        Ok(row.map(|r| r.0))
+    }
+
+    // ==================== 归因 ====================
+
+    /// 记录推荐链接点击
+    pub async fn record_click(
+        &self,
+        referrer_id: i64,
+        code: &str,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+        landing_path: Option<&str>,
+    ) -> Result<(), RswsError> {
+        sqlx::query(
+            r#"
+            INSERT INTO referral_clicks
+                (id, referrer_id, code, ip_address, user_agent, landing_path, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, NOW())
+            "#,
+        )
+        .bind(snowflake::next_id())
+        .bind(referrer_id)
+        .bind(code)
+        .bind(ip_address)
+        .bind(user_agent)
+        .bind(landing_path)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to record referral click: {}", e)))?;
+        Ok(())
+    }
+
+    /// 注册归因：记录被推荐用户的推荐人（已有推荐人或自我推荐时不记录）
+    pub async fn attribute_signup(
+        &self,
+        referee_id: i64,
+        referrer_id: i64,
+        code: &str,
+    ) -> Result<bool, RswsError> {
+        if referee_id == referrer_id {
+            return Ok(false);
+        }
+        let result = sqlx::query(
+            r#"
+            INSERT INTO referrals (referee_id, referrer_id, code, created_at)
+            VALUES ($1, $2, $3, NOW())
+            ON CONFLICT (referee_id) DO NOTHING
+            "#,
+        )
+        .bind(referee_id)
+        .bind(referrer_id)
+        .bind(code)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to attribute signup: {}", e)))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 订单归因，返回订单的推荐人
+    ///
+    /// 优先取本次下单携带的推荐人，否则取买家注册时的推荐人；不归因到买家本人，
+    /// 已归因的订单不再变更。
+    pub async fn attribute_order(
+        &self,
+        order_id: i64,
+        buyer_id: i64,
+        referrer_id: Option<i64>,
+    ) -> Result<Option<i64>, RswsError> {
+        let row: Option<(Option<i64>,)> = sqlx::query_as(
+            r#"
+            UPDATE orders
+            SET referrer_id = COALESCE(
+                    NULLIF($3, $2),
+                    (SELECT referrer_id FROM referrals WHERE referee_id = $2)
+                ),
+                updated_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND referrer_id IS NULL
+            RETURNING referrer_id
+            "#,
+        )
+        .bind(order_id)
+        .bind(buyer_id)
+        .bind(referrer_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to attribute order: {}", e)))?;
+        Ok(row.and_then(|r| r.0))
+    }
+
+    // ==================== 佣金 ====================
+
+    /// 在事务内为已支付订单记录推荐佣金（幂等），返回佣金金额
+    ///
+    /// 佣金 = 商品金额（orders.amount - orders.surcharge）× system_configs 的推荐佣金比例；
+    /// 订单无推荐人、比例为 0 或佣金已记录时返回 None。
+    pub async fn record_commission_in_tx(
+        conn: &mut PgConnection,
+        order_id: i64,
+    ) -> Result<Option<Decimal>, RswsError> {
+        let rate: Option<(String,)> =
+            sqlx::query_as("SELECT config_value FROM system_configs WHERE config_key = $1")
+                .bind(REFERRAL_RATE_CONFIG_KEY)
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(|e| {
+                    RswsError::internal(format!("Failed to load referral commission rate: {}", e))
+                })?;
+        let rate = rate
+            .and_then(|(v,)| v.trim().parse::<Decimal>().ok())
+            .unwrap_or(Decimal::ZERO);
+        if rate <= Decimal::ZERO {
+            return Ok(None);
+        }
+
+        let order: Option<(Option<i64>, Option<i64>, Decimal)> = sqlx::query_as(
+            r#"
+            SELECT user_id, referrer_id, amount - surcharge
+            FROM orders
+            WHERE id = $1 AND status::TEXT IN ('paid', 'completed')
+            "#,
+        )
+        .bind(order_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load order for referral: {}", e)))?;
+
+        let (buyer_id, referrer_id, base) = match order {
+            Some((buyer_id, Some(referrer_id), base)) if buyer_id != Some(referrer_id) => {
+                (buyer_id, referrer_id, base)
+            }
+            _ => return Ok(None),
+        };
+
+        let commission = referral_commission(base, rate);
+        if commission <= Decimal::ZERO {
+            return Ok(None);
+        }
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO commission_records
+                (id, order_id, user_id, referrer_id, rule_id,
+                 order_amount, commission_amount, commission_rate, status, created_at)
+            VALUES ($1, $2, $3, $4, NULL, $5, $6, $7, 'pending', NOW())
+            ON CONFLICT (order_id) WHERE referrer_id IS NOT NULL DO NOTHING
+            "#,
+        )
+        .bind(snowflake::next_id())
+        .bind(order_id)
+        .bind(buyer_id)
+        .bind(referrer_id)
+        .bind(base)
+        .bind(commission)
+        .bind(rate)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to record referral commission: {}", e)))?;
+
+        Ok((result.rows_affected() > 0).then_some(commission))
+    }
+
+    // ==================== 推荐人数据 ====================
+
+    /// 推荐人数据概览
+    pub async fn stats(&self, referrer_id: i64) -> Result<ReferralStats, RswsError> {
+        sqlx::query_as::<_, ReferralStats>(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM referral_clicks WHERE referrer_id = $1) AS clicks,
+                (SELECT COUNT(*) FROM referrals WHERE referrer_id = $1) AS signups,
+                (SELECT COUNT(*) FROM orders
+                 WHERE referrer_id = $1 AND status::TEXT IN ('paid', 'completed')) AS orders,
+                (SELECT COALESCE(SUM(commission_amount), 0) FROM commission_records
+                 WHERE referrer_id = $1 AND status = 'pending') AS pending_earnings,
+                (SELECT COALESCE(SUM(commission_amount), 0) FROM commission_records
+                 WHERE referrer_id = $1 AND status = 'settled') AS settled_earnings
+            "#,
+        )
+        .bind(referrer_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load referral stats: {}", e)))
+    }
+
+    /// 分页获取推荐佣金明细
+    pub async fn list_earnings(
+        &self,
+        referrer_id: i64,
+        status: Option<&str>,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<ReferralEarning>, i64), RswsError> {
+        let items = sqlx::query_as::<_, ReferralEarning>(
+            r#"
+            SELECT c.id, c.order_id, u.username AS buyer_name, c.order_amount,
+                   c.commission_amount, c.commission_rate, c.status, c.settled_at, c.created_at
+            FROM commission_records c
+            LEFT JOIN users u ON u.id = c.user_id
+            WHERE c.referrer_id = $1 AND ($2::TEXT IS NULL OR c.status = $2)
+            ORDER BY c.created_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(referrer_id)
+        .bind(status)
+        .bind(page_size)
+        .bind((page - 1) * page_size)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list referral earnings: {}", e)))?;
+
+        let total: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM commission_records
+            WHERE referrer_id = $1 AND ($2::TEXT IS NULL OR status = $2)
+            "#,
+        )
+        .bind(referrer_id)
+        .bind(status)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to count referral earnings: {}", e)))?;
+
+        Ok((items, total.0))
+    }
+}
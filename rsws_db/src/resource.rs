@@ -1,9 +1,23 @@
 //! Resource repository
 
+use chrono::Utc;
 use rsws_common::error::RswsError;
 use rsws_common::snowflake::next_id;
-use rsws_model::resource::{CreateResourceRequest, Resource, UpdateResourceRequest};
-use sqlx::PgPool;
+use rsws_model::moderation::{
+    review_status_after_edit, ACTOR_TYPE_USER, REVIEW_ACTION_SUBMIT, REVIEW_STATUS_DRAFT,
+    REVIEW_STATUS_PENDING,
+};
+use rsws_model::preview::{PreviewPolicy, SetPreviewPolicyRequest};
+use rsws_model::resource::{
+    CreateResourceRequest, FacetCount, Resource, ResourceFacets, ResourceFilter, ResourceSort,
+    UpdateResourceRequest,
+};
+use rsws_model::resource_import::ResourceExportRow;
+use rsws_model::resource_version::{PublishVersionRequest, INITIAL_VERSION};
+use sqlx::{PgConnection, PgPool, Postgres, QueryBuilder};
+
+use crate::moderation::{ModerationRepository, ReviewEvent};
+use crate::resource_version::ResourceVersionRepository;
 
 /// 璧勬簮浠撳偍
 pub struct ResourceRepository {
@@ -40,7 +54,7 @@ impl ResourceRepository {
 
         let (resources, total) = if let Some(cat_id) = category_id {
             let resources = sqlx::query_as::<_, Resource>(
-                "SELECT * FROM resources WHERE category_id = $1 AND is_active = true ORDER BY created_at DESC LIMIT $2 OFFSET $3",
+                "SELECT * FROM resources WHERE category_id = $1 AND is_active = true AND review_status = 'approved' ORDER BY created_at DESC LIMIT $2 OFFSET $3",
             )
             .bind(cat_id)
             .bind(page_size)
@@ -50,7 +64,7 @@ impl ResourceRepository {
             .map_err(|e| RswsError::internal(format!("Failed to get resources: {}", e)))?;
 
             let total: (i64,) = sqlx::query_as(
-                "SELECT COUNT(*) FROM resources WHERE category_id = $1 AND is_active = true",
+                "SELECT COUNT(*) FROM resources WHERE category_id = $1 AND is_active = true AND review_status = 'approved'",
             )
             .bind(cat_id)
             .fetch_one(&self.pool)
@@ -60,7 +74,7 @@ impl ResourceRepository {
             (resources, total.0)
         } else {
             let resources = sqlx::query_as::<_, Resource>(
-                "SELECT * FROM resources WHERE is_active = true ORDER BY created_at DESC LIMIT $1 OFFSET $2",
+                "SELECT * FROM resources WHERE is_active = true AND review_status = 'approved' ORDER BY created_at DESC LIMIT $1 OFFSET $2",
             )
             .bind(page_size)
             .bind(offset)
@@ -69,7 +83,7 @@ impl ResourceRepository {
             .map_err(|e| RswsError::internal(format!("Failed to get resources: {}", e)))?;
 
             let total: (i64,) =
-                sqlx::query_as("SELECT COUNT(*) FROM resources WHERE is_active = true")
+                sqlx::query_as("SELECT COUNT(*) FROM resources WHERE is_active = true AND review_status = 'approved'")
                     .fetch_one(&self.pool)
                     .await
                     .map_err(|e| {
@@ -118,6 +132,7 @@ impl ResourceRepository {
         req: &CreateResourceRequest,
         owner_type: &str,
         provider_id: i64,
+        review_status: &str,
     ) -> Result<Resource, RswsError> {
         // 鐢熸垚闆姳 ID
         let id = next_id();
@@ -134,8 +149,16 @@ impl ResourceRepository {
             )
         });
 
+        let initial_file = req.file_url.as_deref().filter(|url| !url.is_empty());
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
         let resource = sqlx::query_as::<_, Resource>(
-            "INSERT INTO resources (id, title, description, price, category_id, file_url, thumbnail_url, detail_description, specifications, usage_guide, precautions, display_images, supported_os, owner_type, provider_id, commission_rate) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, 0) RETURNING *"
+            "INSERT INTO resources (id, title, description, price, category_id, file_url, thumbnail_url, detail_description, specifications, usage_guide, precautions, display_images, supported_os, owner_type, provider_id, commission_rate, review_status, submitted_at, version) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, 0, $16, CASE WHEN $16 = 'pending' THEN NOW() END, $17) RETURNING *"
         )
         .bind(id)
         .bind(&req.title)
@@ -152,10 +175,44 @@ impl ResourceRepository {
         .bind(&supported_os_json)
         .bind(owner_type)
         .bind(provider_id)
-        .fetch_one(&self.pool)
+        .bind(review_status)
+        .bind(initial_file.map(|_| INITIAL_VERSION))
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| RswsError::internal(format!("Failed to create resource: {}", e)))?;
 
+        // 上传的文件记录为首个版本
+        if let Some(file_url) = initial_file {
+            ResourceVersionRepository::insert_in_tx(
+                &mut *tx,
+                id,
+                &initial_version(file_url),
+                Some(provider_id),
+                false,
+            )
+            .await?;
+        }
+
+        if review_status == REVIEW_STATUS_PENDING {
+            ModerationRepository::record_event_in_tx(
+                &mut *tx,
+                &ReviewEvent {
+                    resource_id: id,
+                    action: REVIEW_ACTION_SUBMIT,
+                    from_status: REVIEW_STATUS_DRAFT,
+                    to_status: REVIEW_STATUS_PENDING,
+                    reason: None,
+                    actor_id: Some(provider_id),
+                    actor_type: ACTOR_TYPE_USER,
+                },
+            )
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit resource: {}", e)))?;
+
         Ok(resource)
     }
 
@@ -164,6 +221,7 @@ impl ResourceRepository {
         &self,
         id: i64,
         req: &UpdateResourceRequest,
+        edited_by_creator: Option<i64>,
     ) -> Result<Resource, RswsError> {
         // 鍏堣幏鍙栧綋鍓嶈祫婧?
         let mut resource = self
@@ -184,9 +242,16 @@ impl ResourceRepository {
         if let Some(category_id) = req.category_id {
             resource.category_id = Some(category_id);
         }
+        // 资源首次上传文件时记录为首个版本，之后的文件通过发布新版本替换
+        let initial_file = req.file_url.as_deref().filter(|url| {
+            !url.is_empty() && resource.file_url.as_deref().is_none_or(str::is_empty)
+        });
         if let Some(file_url) = &req.file_url {
             resource.file_url = Some(file_url.clone());
         }
+        if initial_file.is_some() {
+            resource.version = Some(INITIAL_VERSION.to_string());
+        }
         if let Some(thumbnail_url) = &req.thumbnail_url {
             resource.thumbnail_url = Some(thumbnail_url.clone());
         }
@@ -210,9 +275,41 @@ impl ResourceRepository {
                 Some(serde_json::to_value(display_images).unwrap_or(serde_json::Value::Null));
         }
 
+        // 创作者修改已提交的资源后重新进入待审核
+        let from_status = resource.review_status.clone();
+        let resubmitted = match edited_by_creator {
+            Some(_) => {
+                let next = review_status_after_edit(&from_status);
+                if next != from_status {
+                    resource.review_status = next.to_string();
+                    resource.review_reason = None;
+                    resource.submitted_at = Some(Utc::now());
+                }
+                next != from_status
+            }
+            None => false,
+        };
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        if let Some(file_url) = initial_file {
+            ResourceVersionRepository::insert_in_tx(
+                &mut *tx,
+                id,
+                &initial_version(file_url),
+                edited_by_creator.or(resource.provider_id),
+                false,
+            )
+            .await?;
+        }
+
         // 鏇存柊鏁版嵁搴?
         let updated = sqlx::query_as::<_, Resource>(
-            "UPDATE resources SET title = $1, description = $2, price = $3, category_id = $4, file_url = $5, thumbnail_url = $6, is_active = $7, detail_description = $8, specifications = $9, usage_guide = $10, precautions = $11, display_images = $12, supported_os = $13, updated_at = NOW() WHERE id = $14 RETURNING *"
+            "UPDATE resources SET title = $1, description = $2, price = $3, category_id = $4, file_url = $5, thumbnail_url = $6, is_active = $7, detail_description = $8, specifications = $9, usage_guide = $10, precautions = $11, display_images = $12, supported_os = $13, review_status = $15, review_reason = $16, submitted_at = $17, version = $18, updated_at = NOW() WHERE id = $14 RETURNING *"
         )
         .bind(&resource.title)
         .bind(&resource.description)
@@ -228,10 +325,34 @@ impl ResourceRepository {
         .bind(&resource.display_images)
         .bind(&resource.supported_os)
         .bind(id)
-        .fetch_one(&self.pool)
+        .bind(&resource.review_status)
+        .bind(&resource.review_reason)
+        .bind(resource.submitted_at)
+        .bind(&resource.version)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| RswsError::internal(format!("Failed to update resource: {}", e)))?;
 
+        if resubmitted {
+            ModerationRepository::record_event_in_tx(
+                &mut *tx,
+                &ReviewEvent {
+                    resource_id: id,
+                    action: REVIEW_ACTION_SUBMIT,
+                    from_status: &from_status,
+                    to_status: &updated.review_status,
+                    reason: None,
+                    actor_id: edited_by_creator,
+                    actor_type: ACTOR_TYPE_USER,
+                },
+            )
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit resource: {}", e)))?;
+
         Ok(updated)
     }
 
@@ -246,105 +367,296 @@ impl ResourceRepository {
         Ok(())
     }
 
-    /// 鑾峰彇璧勬簮鍒楄〃锛堟敮鎸佸叧閿瘝鎼滅储锛?
+    /// 获取资源列表（关键词、标签、价格区间、免费 / 付费、系统、归属类型与创作者筛选）
     pub async fn get_list_with_search(
         &self,
-        category_id: Option<i64>,
-        search: Option<&str>,
+        filter: &ResourceFilter,
         page: i64,
         page_size: i64,
     ) -> Result<(Vec<Resource>, i64), RswsError> {
         let offset = (page - 1) * page_size;
 
-        // 鏋勫缓 WHERE 鏉′欢
-        let _base_where = "is_active = true";
-        let (resources, total) = match (category_id, search) {
-            (Some(cat_id), Some(kw)) => {
-                let kw_pattern = format!("%{}%", kw);
-                let resources = sqlx::query_as::<_, Resource>(
-                    "SELECT * FROM resources WHERE category_id = $1 AND is_active = true AND (title ILIKE $2 OR description ILIKE $2) ORDER BY created_at DESC LIMIT $3 OFFSET $4",
-                )
-                .bind(cat_id)
-                .bind(&kw_pattern)
-                .bind(page_size)
-                .bind(offset)
-                .fetch_all(&self.pool)
-                .await
-                .map_err(|e| RswsError::internal(format!("Failed to get resources: {}", e)))?;
+        let mut query_builder = QueryBuilder::new("SELECT r.* FROM resources r WHERE ");
+        push_filters(&mut query_builder, filter, None);
+        match search_keyword(filter).filter(|_| filter.sort == ResourceSort::Relevance) {
+            // 全文排名 + 标题词相似度（中文部分匹配、错拼只能靠后者）
+            Some(kw) => {
+                query_builder
+                    .push(" ORDER BY ts_rank_cd(r.search_vector, websearch_to_tsquery('simple', ");
+                query_builder.push_bind(kw.to_string());
+                query_builder.push(")) + word_similarity(");
+                query_builder.push_bind(kw.to_string());
+                query_builder.push(", r.title) DESC, r.created_at DESC LIMIT ");
+            }
+            None => {
+                query_builder.push(format!(" ORDER BY {} LIMIT ", filter.sort.order_by()));
+            }
+        }
+        query_builder.push_bind(page_size);
+        query_builder.push(" OFFSET ");
+        query_builder.push_bind(offset);
 
-                let total: (i64,) = sqlx::query_as(
-                    "SELECT COUNT(*) FROM resources WHERE category_id = $1 AND is_active = true AND (title ILIKE $2 OR description ILIKE $2)",
-                )
-                .bind(cat_id)
-                .bind(&kw_pattern)
-                .fetch_one(&self.pool)
-                .await
-                .map_err(|e| RswsError::internal(format!("Failed to count resources: {}", e)))?;
+        let resources = query_builder
+            .build_query_as::<Resource>()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to get resources: {}", e)))?;
 
-                (resources, total.0)
-            }
-            (Some(cat_id), None) => {
-                let resources = sqlx::query_as::<_, Resource>(
-                    "SELECT * FROM resources WHERE category_id = $1 AND is_active = true ORDER BY created_at DESC LIMIT $2 OFFSET $3",
-                )
-                .bind(cat_id)
-                .bind(page_size)
-                .bind(offset)
-                .fetch_all(&self.pool)
-                .await
-                .map_err(|e| RswsError::internal(format!("Failed to get resources: {}", e)))?;
+        let mut count_builder = QueryBuilder::new("SELECT COUNT(*) FROM resources r WHERE ");
+        push_filters(&mut count_builder, filter, None);
 
-                let total: (i64,) = sqlx::query_as(
-                    "SELECT COUNT(*) FROM resources WHERE category_id = $1 AND is_active = true",
-                )
-                .bind(cat_id)
-                .fetch_one(&self.pool)
-                .await
-                .map_err(|e| RswsError::internal(format!("Failed to count resources: {}", e)))?;
+        let total: (i64,) = count_builder
+            .build_query_as()
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to count resources: {}", e)))?;
 
-                (resources, total.0)
-            }
-            (None, Some(kw)) => {
-                let kw_pattern = format!("%{}%", kw);
-                let resources = sqlx::query_as::<_, Resource>(
-                    "SELECT * FROM resources WHERE is_active = true AND (title ILIKE $1 OR description ILIKE $1) ORDER BY created_at DESC LIMIT $2 OFFSET $3",
-                )
-                .bind(&kw_pattern)
-                .bind(page_size)
-                .bind(offset)
-                .fetch_all(&self.pool)
-                .await
-                .map_err(|e| RswsError::internal(format!("Failed to get resources: {}", e)))?;
+        Ok((resources, total.0))
+    }
 
-                let total: (i64,) = sqlx::query_as(
-                    "SELECT COUNT(*) FROM resources WHERE is_active = true AND (title ILIKE $1 OR description ILIKE $1)",
-                )
-                .bind(&kw_pattern)
-                .fetch_one(&self.pool)
+    /// 获取资源列表分面计数（与列表使用相同的筛选条件）
+    pub async fn get_facets(&self, filter: &ResourceFilter) -> Result<ResourceFacets, RswsError> {
+        Ok(ResourceFacets {
+            categories: self.facet_counts(filter, Facet::Category).await?,
+            tags: self.facet_counts(filter, Facet::Tag).await?,
+            supported_os: self.facet_counts(filter, Facet::SupportedOs).await?,
+            owner_types: self.facet_counts(filter, Facet::OwnerType).await?,
+            pricing: self.facet_counts(filter, Facet::Pricing).await?,
+        })
+    }
+
+    async fn facet_counts(
+        &self,
+        filter: &ResourceFilter,
+        facet: Facet,
+    ) -> Result<Vec<FacetCount>, RswsError> {
+        let (select, group_by) = facet.sql();
+        let mut query_builder = QueryBuilder::new(select);
+        push_filters(&mut query_builder, filter, Some(facet));
+        query_builder.push(group_by);
+
+        query_builder
+            .build_query_as::<FacetCount>()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to count resource facets: {}", e)))
+    }
+
+    /// 閫掑璧勬簮涓嬭浇璁℃暟
+    pub async fn increment_download_count_in_tx(
+        conn: &mut PgConnection,
+        resource_id: i64,
+    ) -> Result<(), RswsError> {
+        sqlx::query(
+            r#"
+            WITH daily AS (
+                INSERT INTO resource_daily_stats (resource_id, stat_date, downloads)
+                VALUES ($1, CURRENT_DATE, 1)
+                ON CONFLICT (resource_id, stat_date)
+                DO UPDATE SET downloads = resource_daily_stats.downloads + 1
+            )
+            UPDATE resources SET download_count = COALESCE(download_count, 0) + 1, updated_at = NOW() WHERE id = $1
+            "#,
+        )
+        .bind(resource_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to increment download count: {}", e)))?;
+        Ok(())
+    }
+
+    /// 记录资源浏览（按天累计，用于热门榜）
+    pub async fn record_view(&self, resource_id: i64) -> Result<(), RswsError> {
+        sqlx::query(
+            r#"
+            INSERT INTO resource_daily_stats (resource_id, stat_date, views)
+            VALUES ($1, CURRENT_DATE, 1)
+            ON CONFLICT (resource_id, stat_date)
+            DO UPDATE SET views = resource_daily_stats.views + 1
+            "#,
+        )
+        .bind(resource_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to record resource view: {}", e)))?;
+        Ok(())
+    }
+
+    /// 获取资源预览策略（未配置返回 None）
+    pub async fn get_preview_policy(
+        &self,
+        resource_id: i64,
+    ) -> Result<Option<PreviewPolicy>, RswsError> {
+        sqlx::query_as::<_, PreviewPolicy>(
+            r#"
+            SELECT resource_id, mode, percent, free_sections, free_images, updated_at
+            FROM resource_preview_policies
+            WHERE resource_id = $1
+            "#,
+        )
+        .bind(resource_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to get preview policy: {}", e)))
+    }
+
+    /// 设置资源预览策略
+    pub async fn upsert_preview_policy(
+        &self,
+        resource_id: i64,
+        req: &SetPreviewPolicyRequest,
+        percent: i16,
+        free_sections: &[String],
+    ) -> Result<PreviewPolicy, RswsError> {
+        sqlx::query_as::<_, PreviewPolicy>(
+            r#"
+            INSERT INTO resource_preview_policies
+                (resource_id, mode, percent, free_sections, free_images, updated_at)
+            VALUES ($1, $2, $3, $4, $5, NOW())
+            ON CONFLICT (resource_id) DO UPDATE SET
+                mode = EXCLUDED.mode,
+                percent = EXCLUDED.percent,
+                free_sections = EXCLUDED.free_sections,
+                free_images = EXCLUDED.free_images,
+                updated_at = NOW()
+            RETURNING resource_id, mode, percent, free_sections, free_images, updated_at
+            "#,
+        )
+        .bind(resource_id)
+        .bind(&req.mode)
+        .bind(percent)
+        .bind(free_sections)
+        .bind(req.free_images)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to save preview policy: {}", e)))
+    }
+
+    /// 付费资源（含历史版本）的文件 URL（去重）
+    pub async fn list_paid_file_urls(&self) -> Result<Vec<String>, RswsError> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT r.file_url
+            FROM resources r
+            WHERE r.price > 0 AND r.file_url IS NOT NULL AND r.file_url <> ''
+            UNION
+            SELECT v.file_url
+            FROM resource_versions v
+            JOIN resources r ON r.id = v.resource_id
+            WHERE r.price > 0
+            ORDER BY 1
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list paid file urls: {}", e)))?;
+
+        Ok(rows.into_iter().map(|(url,)| url).collect())
+    }
+
+    /// 替换文件 URL（资源、版本与未过期的下载令牌同时更新），返回资源与版本更新行数
+    pub async fn replace_file_url(&self, old_url: &str, new_url: &str) -> Result<u64, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let resources = sqlx::query("UPDATE resources SET file_url = $2 WHERE file_url = $1")
+            .bind(old_url)
+            .bind(new_url)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to update resource file: {}", e)))?;
+
+        let versions =
+            sqlx::query("UPDATE resource_versions SET file_url = $2 WHERE file_url = $1")
+                .bind(old_url)
+                .bind(new_url)
+                .execute(&mut *tx)
                 .await
-                .map_err(|e| RswsError::internal(format!("Failed to count resources: {}", e)))?;
+                .map_err(|e| {
+                    RswsError::internal(format!("Failed to update version file: {}", e))
+                })?;
 
-                (resources, total.0)
-            }
-            (None, None) => {
-                // 澶嶇敤鍘熸湁鐨勬棤杩囨护鏌ヨ
-                self.get_list(category_id, page, page_size).await?
-            }
-        };
+        sqlx::query(
+            "UPDATE download_tokens SET file_url = $2 WHERE file_url = $1 AND expires_at > NOW()",
+        )
+        .bind(old_url)
+        .bind(new_url)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update download tokens: {}", e)))?;
 
-        Ok((resources, total))
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit file url: {}", e)))?;
+
+        Ok(resources.rows_affected() + versions.rows_affected())
     }
 
-    /// 閫掑璧勬簮涓嬭浇璁℃暟
-    pub async fn increment_download_count(&self, resource_id: i64) -> Result<(), RswsError> {
-        sqlx::query("UPDATE resources SET download_count = COALESCE(download_count, 0) + 1, updated_at = NOW() WHERE id = $1")
-            .bind(resource_id)
+    /// 按 external_id 查找资源，返回 (资源 ID, 是否上架)（含已删除的资源）
+    pub async fn find_by_external_id(
+        &self,
+        external_id: &str,
+    ) -> Result<Option<(i64, bool)>, RswsError> {
+        sqlx::query_as("SELECT id, is_active FROM resources WHERE external_id = $1")
+            .bind(external_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to find resource: {}", e)))
+    }
+
+    /// 设置资源的 external_id
+    pub async fn set_external_id(&self, id: i64, external_id: &str) -> Result<(), RswsError> {
+        sqlx::query("UPDATE resources SET external_id = $2 WHERE id = $1")
+            .bind(id)
+            .bind(external_id)
             .execute(&self.pool)
             .await
-            .map_err(|e| RswsError::internal(format!("Failed to increment download count: {}", e)))?;
+            .map_err(|e| RswsError::internal(format!("Failed to set external id: {}", e)))?;
+
         Ok(())
     }
 
+    /// 导出资源（含已下架资源、分类名、标签与已支付订单统计）
+    pub async fn export_rows(
+        &self,
+        category_id: Option<i64>,
+    ) -> Result<Vec<ResourceExportRow>, RswsError> {
+        sqlx::query_as::<_, ResourceExportRow>(
+            r#"
+            SELECT r.id, r.external_id, r.title, r.description, r.price, r.category_id,
+                   c.name AS category, r.file_url AS file, r.thumbnail_url AS thumbnail,
+                   r.detail_description, r.usage_guide, r.precautions,
+                   ARRAY(SELECT jsonb_array_elements_text(
+                       COALESCE(to_jsonb(r.display_images), '[]'::jsonb))) AS display_images,
+                   ARRAY(SELECT jsonb_array_elements_text(
+                       COALESCE(r.supported_os, '[]'::jsonb))) AS supported_os,
+                   ARRAY(SELECT t.name FROM resource_tags rt JOIN tags t ON t.id = rt.tag_id
+                         WHERE rt.resource_id = r.id ORDER BY t.name) AS tags,
+                   r.is_active, r.review_status, r.owner_type, r.provider_id, r.download_count,
+                   COALESCE(s.sales_count, 0) AS sales_count,
+                   COALESCE(s.revenue, 0) AS revenue,
+                   r.rating_avg, r.rating_count, r.created_at, r.updated_at
+            FROM resources r
+            LEFT JOIN categories c ON c.id = r.category_id
+            LEFT JOIN (
+                SELECT resource_id, COUNT(*) AS sales_count, SUM(amount) AS revenue
+                FROM orders
+                WHERE status IN ('paid', 'completed')
+                GROUP BY resource_id
+            ) s ON s.resource_id = r.id
+            WHERE ($1::BIGINT IS NULL OR r.category_id = $1)
+            ORDER BY r.created_at, r.id
+            "#,
+        )
+        .bind(category_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to export resources: {}", e)))
+    }
+
     /// 鑾峰彇鍩虹缁熻锛堣祫婧愭€绘暟 + 宸蹭笂绾胯祫婧愭暟 + 杩囧幓30澶╂柊澧炶祫婧愭暟锛?
     pub async fn get_basic_stats(&self) -> Result<(i64, i64, i64), RswsError> {
         let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM resources")
@@ -371,10 +683,155 @@ impl ResourceRepository {
     }
 }
 
+/// 首个版本（资源首次上传文件）
+fn initial_version(file_url: &str) -> PublishVersionRequest {
+    PublishVersionRequest {
+        version: INITIAL_VERSION.to_string(),
+        file_url: file_url.to_string(),
+        file_size: None,
+        checksum: None,
+        changelog: None,
+    }
+}
+
+/// 资源列表分面
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Facet {
+    Category,
+    Tag,
+    SupportedOs,
+    OwnerType,
+    Pricing,
+}
+
+impl Facet {
+    /// 分面查询的 SELECT ... WHERE 前缀与 GROUP BY 后缀
+    fn sql(self) -> (&'static str, &'static str) {
+        match self {
+            Facet::Category => (
+                "SELECT r.category_id::TEXT AS value, c.name AS label, COUNT(*) AS count FROM resources r JOIN categories c ON c.id = r.category_id WHERE ",
+                " GROUP BY r.category_id, c.name ORDER BY count DESC, c.name",
+            ),
+            Facet::Tag => (
+                "SELECT t.slug AS value, t.name AS label, COUNT(*) AS count FROM resources r JOIN resource_tags rt ON rt.resource_id = r.id JOIN tags t ON t.id = rt.tag_id WHERE ",
+                " GROUP BY t.slug, t.name ORDER BY count DESC, t.slug LIMIT 50",
+            ),
+            Facet::SupportedOs => (
+                "SELECT os.value AS value, NULL::TEXT AS label, COUNT(DISTINCT r.id) AS count FROM resources r CROSS JOIN LATERAL jsonb_array_elements_text(CASE WHEN jsonb_typeof(r.supported_os) = 'array' THEN r.supported_os ELSE '[]'::jsonb END) AS os(value) WHERE ",
+                " GROUP BY os.value ORDER BY count DESC, os.value",
+            ),
+            Facet::OwnerType => (
+                "SELECT r.owner_type AS value, NULL::TEXT AS label, COUNT(*) AS count FROM resources r WHERE ",
+                " GROUP BY r.owner_type ORDER BY count DESC",
+            ),
+            Facet::Pricing => (
+                "SELECT CASE WHEN COALESCE(r.price, 0) = 0 THEN 'free' ELSE 'paid' END AS value, NULL::TEXT AS label, COUNT(*) AS count FROM resources r WHERE ",
+                " GROUP BY 1 ORDER BY 1",
+            ),
+        }
+    }
+}
+
+/// 去掉首尾空白后的搜索关键词
+fn search_keyword(filter: &ResourceFilter) -> Option<&str> {
+    filter
+        .search
+        .as_deref()
+        .map(str::trim)
+        .filter(|kw| !kw.is_empty())
+}
+
+/// 转义 LIKE 通配符
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// 追加资源列表筛选条件（表别名 `r`）
+///
+/// `skip` 为正在统计的分面：分类、系统、归属类型与免费 / 付费分面不应用自身条件，
+/// 选中一个取值后其余取值仍显示数量；标签为逐步收窄，始终应用。
+fn push_filters(
+    query_builder: &mut QueryBuilder<'_, Postgres>,
+    filter: &ResourceFilter,
+    skip: Option<Facet>,
+) {
+    query_builder.push("r.is_active = true AND r.review_status = 'approved'");
+
+    if let Some(category_id) = filter.category_id.filter(|_| skip != Some(Facet::Category)) {
+        if filter.include_subcategories {
+            query_builder.push(
+                " AND r.category_id IN (SELECT d.id FROM categories c JOIN categories d ON d.path LIKE c.path || '%' WHERE c.id = ",
+            );
+            query_builder.push_bind(category_id);
+            query_builder.push(")");
+        } else {
+            query_builder.push(" AND r.category_id = ");
+            query_builder.push_bind(category_id);
+        }
+    }
+    // 全文匹配，或标题 / 简介部分匹配、标题近似词（走 pg_trgm 索引）
+    if let Some(kw) = search_keyword(filter) {
+        let pattern = format!("%{}%", escape_like(kw));
+        query_builder.push(" AND (r.search_vector @@ websearch_to_tsquery('simple', ");
+        query_builder.push_bind(kw.to_string());
+        query_builder.push(") OR r.title ILIKE ");
+        query_builder.push_bind(pattern.clone());
+        query_builder.push(" OR r.description ILIKE ");
+        query_builder.push_bind(pattern);
+        query_builder.push(" OR ");
+        query_builder.push_bind(kw.to_string());
+        query_builder.push(" <% r.title)");
+    }
+    if !filter.tags.is_empty() {
+        query_builder.push(
+            " AND r.id IN (SELECT rt.resource_id FROM resource_tags rt JOIN tags t ON t.id = rt.tag_id WHERE t.slug = ANY(",
+        );
+        query_builder.push_bind(filter.tags.clone());
+        query_builder.push(") GROUP BY rt.resource_id HAVING COUNT(*) = ");
+        query_builder.push_bind(filter.tags.len() as i64);
+        query_builder.push(")");
+    }
+    if let Some(min_price) = filter.min_price {
+        query_builder.push(" AND COALESCE(r.price, 0) >= ");
+        query_builder.push_bind(min_price);
+    }
+    if let Some(max_price) = filter.max_price {
+        query_builder.push(" AND COALESCE(r.price, 0) <= ");
+        query_builder.push_bind(max_price);
+    }
+    if let Some(is_free) = filter.is_free.filter(|_| skip != Some(Facet::Pricing)) {
+        query_builder.push(if is_free {
+            " AND COALESCE(r.price, 0) = 0"
+        } else {
+            " AND COALESCE(r.price, 0) > 0"
+        });
+    }
+    if !filter.supported_os.is_empty() && skip != Some(Facet::SupportedOs) {
+        query_builder.push(" AND r.supported_os ?| ");
+        query_builder.push_bind(filter.supported_os.clone());
+    }
+    if let Some(owner_type) = filter
+        .owner_type
+        .as_deref()
+        .filter(|_| skip != Some(Facet::OwnerType))
+    {
+        query_builder.push(" AND r.owner_type = ");
+        query_builder.push_bind(owner_type.to_string());
+    }
+    if let Some(provider_id) = filter.provider_id {
+        query_builder.push(" AND r.provider_id = ");
+        query_builder.push_bind(provider_id);
+    }
+}
+
 // ==================== 鍗曞厓娴嬭瘯 ====================
 
 #[cfg(test)]
 mod tests {
+    use super::*;
 
     #[test]
     fn test_resource_repository_new() {
@@ -382,4 +839,10 @@ mod tests {
     }
 
     // create, update, delete 鏂规硶闇€瑕佹暟鎹簱娴嬭瘯锛岃繖閲岀渷鐣?
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("100%_off\\"), "100\\%\\_off\\\\");
+        assert_eq!(escape_like("像素"), "像素");
+    }
 }
@@ -0,0 +1,289 @@
+//! 资源版本仓储层
+
+use chrono::{DateTime, Utc};
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_common::snowflake;
+use rsws_model::moderation::{review_status_after_edit, ACTOR_TYPE_USER, REVIEW_ACTION_SUBMIT};
+use rsws_model::resource::Resource;
+use rsws_model::resource_version::{PublishVersionRequest, ResourceVersion, VersionOwner};
+use sqlx::{PgConnection, PgPool};
+
+use crate::moderation::{ModerationRepository, ReviewEvent};
+
+const VERSION_COLUMNS: &str = "id, resource_id, version, file_url, file_size, checksum, changelog, released_at, created_by, created_at";
+
+/// 资源版本仓储
+pub struct ResourceVersionRepository {
+    pool: PgPool,
+}
+
+impl ResourceVersionRepository {
+    /// 创建资源版本仓储实例
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// 在事务内写入版本记录
+    ///
+    /// `notify_owners` 为 false 时标记为已通知（资源首个版本无需通知买家）。
+    pub async fn insert_in_tx(
+        conn: &mut PgConnection,
+        resource_id: i64,
+        req: &PublishVersionRequest,
+        created_by: Option<i64>,
+        notify_owners: bool,
+    ) -> Result<ResourceVersion, RswsError> {
+        sqlx::query_as::<_, ResourceVersion>(&format!(
+            r#"
+            INSERT INTO resource_versions
+                (id, resource_id, version, file_url, file_size, checksum, changelog,
+                 released_at, created_by, owners_notified_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, NOW(), $8,
+                    CASE WHEN $9 THEN NULL ELSE NOW() END, NOW())
+            RETURNING {}
+            "#,
+            VERSION_COLUMNS
+        ))
+        .bind(snowflake::next_id())
+        .bind(resource_id)
+        .bind(&req.version)
+        .bind(&req.file_url)
+        .bind(req.file_size)
+        .bind(&req.checksum)
+        .bind(&req.changelog)
+        .bind(created_by)
+        .bind(notify_owners)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("duplicate key") {
+                RswsError::business_with_message(
+                    ErrorCode::RESOURCE_VERSION_EXISTS,
+                    format!("Version {} already exists for this resource", req.version),
+                )
+            } else {
+                RswsError::internal(format!("Failed to create resource version: {}", e))
+            }
+        })
+    }
+
+    /// 获取资源的全部版本（最新在前）
+    pub async fn list(&self, resource_id: i64) -> Result<Vec<ResourceVersion>, RswsError> {
+        sqlx::query_as::<_, ResourceVersion>(&format!(
+            "SELECT {} FROM resource_versions WHERE resource_id = $1 ORDER BY released_at DESC, id DESC",
+            VERSION_COLUMNS
+        ))
+        .bind(resource_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list resource versions: {}", e)))
+    }
+
+    /// 获取资源的指定版本
+    pub async fn find(
+        &self,
+        resource_id: i64,
+        version_id: i64,
+    ) -> Result<Option<ResourceVersion>, RswsError> {
+        sqlx::query_as::<_, ResourceVersion>(&format!(
+            "SELECT {} FROM resource_versions WHERE id = $1 AND resource_id = $2",
+            VERSION_COLUMNS
+        ))
+        .bind(version_id)
+        .bind(resource_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to get resource version: {}", e)))
+    }
+
+    /// 发布新版本，并将资源的文件、版本号和大小同步为该版本
+    ///
+    /// `resubmitted_by` 为创作者用户 ID 时，资源按修改规则重新进入审核。
+    pub async fn publish(
+        &self,
+        resource: &Resource,
+        req: &PublishVersionRequest,
+        created_by: Option<i64>,
+        resubmitted_by: Option<i64>,
+    ) -> Result<ResourceVersion, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let version = Self::insert_in_tx(&mut *tx, resource.id, req, created_by, true).await?;
+
+        let review_status = match resubmitted_by {
+            Some(_) => review_status_after_edit(&resource.review_status),
+            None => resource.review_status.as_str(),
+        };
+        let resubmitted = review_status != resource.review_status;
+
+        sqlx::query(
+            r#"
+            UPDATE resources
+            SET file_url = $2, file_size = $3, version = $4,
+                review_status = $5,
+                review_reason = CASE WHEN $6 THEN NULL ELSE review_reason END,
+                submitted_at = CASE WHEN $6 THEN NOW() ELSE submitted_at END,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(resource.id)
+        .bind(&version.file_url)
+        .bind(version.file_size)
+        .bind(&version.version)
+        .bind(review_status)
+        .bind(resubmitted)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update resource file: {}", e)))?;
+
+        if resubmitted {
+            ModerationRepository::record_event_in_tx(
+                &mut *tx,
+                &ReviewEvent {
+                    resource_id: resource.id,
+                    action: REVIEW_ACTION_SUBMIT,
+                    from_status: &resource.review_status,
+                    to_status: review_status,
+                    reason: None,
+                    actor_id: resubmitted_by,
+                    actor_type: ACTOR_TYPE_USER,
+                },
+            )
+            .await?;
+        }
+
+        tx.commit().await.map_err(|e| {
+            RswsError::internal(format!("Failed to commit resource version: {}", e))
+        })?;
+
+        Ok(version)
+    }
+
+    /// 设置更新政策
+    pub async fn set_update_months(
+        &self,
+        resource_id: i64,
+        update_months: Option<i32>,
+    ) -> Result<Option<Resource>, RswsError> {
+        sqlx::query_as::<_, Resource>(
+            "UPDATE resources SET update_months = $2, updated_at = NOW() WHERE id = $1 RETURNING *",
+        )
+        .bind(resource_id)
+        .bind(update_months)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to set update policy: {}", e)))
+    }
+
+    /// 用户最近一次购买资源的付款时间（无已支付订单时为 None）
+    pub async fn last_purchase(
+        &self,
+        user_id: i64,
+        resource_id: i64,
+    ) -> Result<Option<DateTime<Utc>>, RswsError> {
+        let row: (Option<DateTime<Utc>>,) = sqlx::query_as(
+            r#"
+            SELECT MAX(COALESCE(paid_at, created_at))
+            FROM orders
+            WHERE user_id = $1 AND resource_id = $2 AND status IN ('paid', 'completed')
+            "#,
+        )
+        .bind(user_id)
+        .bind(resource_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load last purchase: {}", e)))?;
+
+        Ok(row.0)
+    }
+
+    /// 用户是否有覆盖该资源分类的有效会员（会员可下载全部版本）
+    ///
+    /// 与 `OrderRepository::check_user_purchased` 一致，上级分类沿 `parent_id` 逐级查找。
+    pub async fn has_membership_access(
+        &self,
+        user_id: i64,
+        resource_id: i64,
+    ) -> Result<bool, RswsError> {
+        let row: (bool,) = sqlx::query_as(
+            r#"
+            SELECT EXISTS (
+                WITH RECURSIVE ancestors(id, parent_id, depth) AS (
+                    SELECT c.id, c.parent_id, 0
+                    FROM resources r
+                    JOIN categories c ON c.id = r.category_id
+                    WHERE r.id = $2
+                    UNION ALL
+                    SELECT c.id, c.parent_id, a.depth + 1
+                    FROM categories c
+                    JOIN ancestors a ON c.id = a.parent_id
+                    WHERE a.depth < 32
+                )
+                SELECT 1
+                FROM ancestors a
+                JOIN user_memberships m ON m.category_id = a.id
+                WHERE m.user_id = $1
+                  AND m.status = 'active'
+                  AND m.starts_at <= NOW() AND m.expires_at > NOW()
+            )
+            "#,
+        )
+        .bind(user_id)
+        .bind(resource_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to check membership access: {}", e)))?;
+
+        Ok(row.0)
+    }
+
+    /// 领取待通知买家的版本（标记为已通知，发送失败不重试）
+    pub async fn claim_pending_notifications(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<ResourceVersion>, RswsError> {
+        sqlx::query_as::<_, ResourceVersion>(&format!(
+            r#"
+            UPDATE resource_versions
+            SET owners_notified_at = NOW()
+            WHERE id IN (
+                SELECT id FROM resource_versions
+                WHERE owners_notified_at IS NULL
+                ORDER BY created_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING {}
+            "#,
+            VERSION_COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to claim version notifications: {}", e)))
+    }
+
+    /// 资源的已购买用户及其最近一次付款时间
+    pub async fn list_owners(&self, resource_id: i64) -> Result<Vec<VersionOwner>, RswsError> {
+        sqlx::query_as::<_, VersionOwner>(
+            r#"
+            SELECT u.id AS user_id, u.email, u.username,
+                   MAX(COALESCE(o.paid_at, o.created_at)) AS purchased_at
+            FROM orders o
+            JOIN users u ON u.id = o.user_id
+            WHERE o.resource_id = $1 AND o.status IN ('paid', 'completed')
+            GROUP BY u.id, u.email, u.username
+            "#,
+        )
+        .bind(resource_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list resource owners: {}", e)))
+    }
+}
@@ -0,0 +1,377 @@
+//! 订单风控仓储层
+
+use rsws_common::error::RswsError;
+use rsws_common::snowflake;
+use rsws_model::payment::Order;
+use rsws_model::risk::{
+    countries_mismatch, evaluate, next_risk_status, CreateRiskRuleRequest, OrderRiskAssessment,
+    RiskContext, RiskDecision, RiskOutcome, RiskReviewItem, RiskRule, RiskSignals,
+    UpdateRiskRuleRequest, RISK_ACTION_BLOCK, RISK_ACTION_REVIEW,
+};
+use sqlx::PgPool;
+
+use crate::order::ORDER_COLUMNS;
+
+const RULE_COLUMNS: &str = "id, name, signal, operator, threshold, score, action, stage, is_active, description, created_at, updated_at";
+
+const ASSESSMENT_COLUMNS: &str = "id, order_id, stage, score, action, triggered, signals, ip_address, ip_country, user_agent, payer_country, created_at";
+
+/// 登录日志中的设备标识：客户端上报的 device_id
+///
+/// 不回退到 User-Agent：同版本浏览器的 UA 完全相同，会把无关用户算作同一设备。
+const DEVICE_KEY: &str = "device_info->>'device_id'";
+
+/// 订单风控仓储
+pub struct RiskRepository {
+    pool: PgPool,
+}
+
+impl RiskRepository {
+    /// 创建订单风控仓储实例
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // ==================== 规则 ====================
+
+    /// 获取全部规则
+    pub async fn list_rules(&self) -> Result<Vec<RiskRule>, RswsError> {
+        sqlx::query_as::<_, RiskRule>(&format!(
+            "SELECT {} FROM risk_rules ORDER BY stage, signal, threshold",
+            RULE_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list risk rules: {}", e)))
+    }
+
+    /// 创建规则
+    pub async fn create_rule(
+        &self,
+        req: &CreateRiskRuleRequest,
+        stage: &str,
+    ) -> Result<RiskRule, RswsError> {
+        sqlx::query_as::<_, RiskRule>(&format!(
+            r#"
+            INSERT INTO risk_rules
+                (id, name, signal, operator, threshold, score, action, stage, is_active,
+                 description, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, true, $9, NOW(), NOW())
+            RETURNING {}
+            "#,
+            RULE_COLUMNS
+        ))
+        .bind(snowflake::next_id())
+        .bind(&req.name)
+        .bind(&req.signal)
+        .bind(&req.operator)
+        .bind(req.threshold)
+        .bind(req.score)
+        .bind(&req.action)
+        .bind(stage)
+        .bind(&req.description)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to create risk rule: {}", e)))
+    }
+
+    /// 更新规则
+    pub async fn update_rule(
+        &self,
+        id: i64,
+        req: &UpdateRiskRuleRequest,
+    ) -> Result<Option<RiskRule>, RswsError> {
+        sqlx::query_as::<_, RiskRule>(&format!(
+            r#"
+            UPDATE risk_rules
+            SET name = COALESCE($2, name),
+                signal = COALESCE($3, signal),
+                operator = COALESCE($4, operator),
+                threshold = COALESCE($5, threshold),
+                score = COALESCE($6, score),
+                action = COALESCE($7, action),
+                stage = COALESCE($8, stage),
+                is_active = COALESCE($9, is_active),
+                description = COALESCE($10, description),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            RULE_COLUMNS
+        ))
+        .bind(id)
+        .bind(&req.name)
+        .bind(&req.signal)
+        .bind(&req.operator)
+        .bind(req.threshold)
+        .bind(req.score)
+        .bind(&req.action)
+        .bind(&req.stage)
+        .bind(req.is_active)
+        .bind(&req.description)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update risk rule: {}", e)))
+    }
+
+    /// 删除规则
+    pub async fn delete_rule(&self, id: i64) -> Result<bool, RswsError> {
+        let result = sqlx::query("DELETE FROM risk_rules WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to delete risk rule: {}", e)))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ==================== 信号 ====================
+
+    /// 采集订单的风控信号
+    ///
+    /// 设备取用户最近一次上报了 device_id 的成功登录，没有时同设备账号数记为 0；
+    /// 付款人国家与下单评估时记录的 IP 国家比对。
+    pub async fn collect_signals(
+        &self,
+        order: &Order,
+        ctx: &RiskContext,
+    ) -> Result<RiskSignals, RswsError> {
+        let row: (i64, i64, i64, Option<i64>, i64, Option<String>) = sqlx::query_as(&format!(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM orders
+                 WHERE user_id = $1 AND id <> $2 AND created_at > NOW() - INTERVAL '1 hour'),
+                (SELECT COUNT(DISTINCT order_id) FROM order_risk_assessments
+                 WHERE stage = 'creation' AND ip_address = $3 AND order_id <> $2
+                   AND created_at > NOW() - INTERVAL '1 hour'),
+                (SELECT COUNT(DISTINCT user_id) FROM login_logs
+                 WHERE status = 'success' AND created_at > NOW() - INTERVAL '24 hours'
+                   AND {device} = (SELECT {device} FROM login_logs
+                                   WHERE user_id = $1 AND status = 'success'
+                                     AND {device} IS NOT NULL
+                                   ORDER BY created_at DESC LIMIT 1)),
+                (SELECT (EXTRACT(EPOCH FROM (NOW() - created_at)) / 3600)::BIGINT
+                 FROM users WHERE id = $1),
+                (SELECT COUNT(*) FROM payment_transactions
+                 WHERE user_id = $1 AND status::TEXT = 'failed'
+                   AND updated_at > NOW() - INTERVAL '24 hours'),
+                (SELECT ip_country FROM order_risk_assessments
+                 WHERE order_id = $2 AND stage = 'creation'
+                 ORDER BY created_at DESC LIMIT 1)
+            "#,
+            device = DEVICE_KEY
+        ))
+        .bind(order.user_id)
+        .bind(order.id)
+        .bind(&ctx.ip_address)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to collect risk signals: {}", e)))?;
+
+        let (user_orders, ip_orders, device_accounts, age_hours, failed_payments, ip_country) = row;
+        let ip_country = ip_country.or_else(|| ctx.ip_country.clone());
+
+        Ok(RiskSignals {
+            user_orders_1h: user_orders,
+            ip_orders_1h: ip_orders,
+            device_accounts_24h: device_accounts,
+            account_age_hours: age_hours.unwrap_or(0),
+            failed_payments_24h: failed_payments,
+            payer_country_mismatch: countries_mismatch(
+                ctx.payer_country.as_deref(),
+                ip_country.as_deref(),
+            ),
+            order_amount: order.amount,
+        })
+    }
+
+    // ==================== 评估 ====================
+
+    /// 按当前规则评估订单，记录结果并更新订单风控状态
+    ///
+    /// `captured` 为真（款项已到账）时拦截降级为人工审核，不取消订单。
+    pub async fn assess(
+        &self,
+        order: &Order,
+        stage: &str,
+        ctx: &RiskContext,
+        captured: bool,
+    ) -> Result<RiskOutcome, RswsError> {
+        let rules = self.list_rules().await?;
+        let signals = self.collect_signals(order, ctx).await?;
+        let decision = evaluate(&rules, &signals, stage);
+
+        let action = if captured && decision.action == RISK_ACTION_BLOCK {
+            RISK_ACTION_REVIEW
+        } else {
+            decision.action.as_str()
+        };
+        let next = next_risk_status(&order.risk_status, action);
+        self.record_assessment(order.id, stage, &decision, &signals, ctx, next)
+            .await?;
+
+        let risk_status = next
+            .map(str::to_string)
+            .unwrap_or_else(|| order.risk_status.clone());
+        Ok(RiskOutcome {
+            decision,
+            risk_status,
+        })
+    }
+
+    /// 记录评估结果并更新订单风控状态（拦截的待支付订单同时取消）
+    pub async fn record_assessment(
+        &self,
+        order_id: i64,
+        stage: &str,
+        decision: &RiskDecision,
+        signals: &RiskSignals,
+        ctx: &RiskContext,
+        risk_status: Option<&str>,
+    ) -> Result<OrderRiskAssessment, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let assessment = sqlx::query_as::<_, OrderRiskAssessment>(&format!(
+            r#"
+            INSERT INTO order_risk_assessments
+                (id, order_id, stage, score, action, triggered, signals, ip_address, ip_country,
+                 user_agent, payer_country, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, NOW())
+            RETURNING {}
+            "#,
+            ASSESSMENT_COLUMNS
+        ))
+        .bind(snowflake::next_id())
+        .bind(order_id)
+        .bind(stage)
+        .bind(decision.score)
+        .bind(&decision.action)
+        .bind(serde_json::to_value(&decision.triggered).unwrap_or_default())
+        .bind(serde_json::to_value(signals).unwrap_or_default())
+        .bind(&ctx.ip_address)
+        .bind(&ctx.ip_country)
+        .bind(&ctx.user_agent)
+        .bind(&ctx.payer_country)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to record risk assessment: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            UPDATE orders
+            SET risk_score = $2,
+                risk_status = COALESCE($3, risk_status),
+                status = CASE
+                    WHEN $3 = 'rejected' AND status = 'pending'::order_status
+                    THEN 'cancelled'::order_status
+                    ELSE status
+                END,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(order_id)
+        .bind(decision.score)
+        .bind(risk_status)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update order risk status: {}", e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(assessment)
+    }
+
+    /// 获取订单的评估记录
+    pub async fn list_assessments(
+        &self,
+        order_id: i64,
+    ) -> Result<Vec<OrderRiskAssessment>, RswsError> {
+        sqlx::query_as::<_, OrderRiskAssessment>(&format!(
+            "SELECT {} FROM order_risk_assessments WHERE order_id = $1 ORDER BY created_at",
+            ASSESSMENT_COLUMNS
+        ))
+        .bind(order_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list risk assessments: {}", e)))
+    }
+
+    // ==================== 审核 ====================
+
+    /// 分页获取待审核订单（先进先审）
+    pub async fn list_review_queue(
+        &self,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<RiskReviewItem>, i64), RswsError> {
+        let items = sqlx::query_as::<_, RiskReviewItem>(
+            r#"
+            SELECT o.id AS order_id, o.user_id, u.username, o.resource_id, o.amount,
+                   o.status::TEXT AS status, o.payment_method, o.risk_score, o.created_at
+            FROM orders o
+            LEFT JOIN users u ON u.id = o.user_id
+            WHERE o.risk_status = 'review'
+            ORDER BY o.created_at
+            LIMIT $1 OFFSET $2
+            "#,
+        )
+        .bind(page_size)
+        .bind((page - 1) * page_size)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list risk review queue: {}", e)))?;
+
+        let total: (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM orders WHERE risk_status = 'review'")
+                .fetch_one(&self.pool)
+                .await
+                .map_err(|e| {
+                    RswsError::internal(format!("Failed to count risk review queue: {}", e))
+                })?;
+
+        Ok((items, total.0))
+    }
+
+    /// 审核待审核订单，拒绝时取消待支付订单
+    ///
+    /// 订单不存在或不在审核中时返回 None。
+    pub async fn review(
+        &self,
+        order_id: i64,
+        admin_id: i64,
+        approve: bool,
+        note: Option<&str>,
+    ) -> Result<Option<Order>, RswsError> {
+        sqlx::query_as::<_, Order>(&format!(
+            r#"
+            UPDATE orders
+            SET risk_status = CASE WHEN $3 THEN 'approved' ELSE 'rejected' END,
+                status = CASE
+                    WHEN NOT $3 AND status = 'pending'::order_status
+                    THEN 'cancelled'::order_status
+                    ELSE status
+                END,
+                risk_reviewed_by = $2,
+                risk_reviewed_at = NOW(),
+                risk_review_note = $4,
+                updated_at = NOW()
+            WHERE id = $1 AND risk_status = 'review'
+            RETURNING {}
+            "#,
+            ORDER_COLUMNS
+        ))
+        .bind(order_id)
+        .bind(admin_id)
+        .bind(approve)
+        .bind(note)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to review order: {}", e)))
+    }
+}
@@ -0,0 +1,221 @@
+//! 资源标签仓储层
+
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_common::snowflake;
+use rsws_model::tag::{ResourceTag, Tag};
+use sqlx::PgPool;
+
+/// 标签查询（含使用该标签的资源数）
+const TAG_SELECT: &str = r#"
+    SELECT t.id, t.name, t.slug, t.created_by, t.created_at, t.updated_at,
+           (SELECT COUNT(*) FROM resource_tags rt WHERE rt.tag_id = t.id) AS resource_count
+    FROM tags t
+"#;
+
+/// 资源标签仓储
+pub struct TagRepository {
+    pool: PgPool,
+}
+
+impl TagRepository {
+    /// 创建资源标签仓储实例
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// 热门标签（按公开资源数排序，不含未被公开资源使用的标签）
+    pub async fn list_popular(&self, limit: i64) -> Result<Vec<Tag>, RswsError> {
+        sqlx::query_as::<_, Tag>(
+            r#"
+            SELECT t.id, t.name, t.slug, t.created_by, t.created_at, t.updated_at,
+                   COUNT(*) AS resource_count
+            FROM tags t
+            JOIN resource_tags rt ON rt.tag_id = t.id
+            JOIN resources r ON r.id = rt.resource_id
+            WHERE r.is_active = true AND r.review_status = 'approved'
+            GROUP BY t.id
+            ORDER BY resource_count DESC, t.slug
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list popular tags: {}", e)))
+    }
+
+    /// 分页查询全部标签（管理员）
+    pub async fn list(
+        &self,
+        search: Option<&str>,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<Tag>, i64), RswsError> {
+        let offset = (page - 1) * page_size;
+        let pattern = search.map(|s| format!("%{}%", s));
+
+        let tags = sqlx::query_as::<_, Tag>(&format!(
+            "{} WHERE ($1::TEXT IS NULL OR t.name ILIKE $1 OR t.slug ILIKE $1) ORDER BY t.slug LIMIT $2 OFFSET $3",
+            TAG_SELECT
+        ))
+        .bind(&pattern)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list tags: {}", e)))?;
+
+        let total: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM tags t WHERE ($1::TEXT IS NULL OR t.name ILIKE $1 OR t.slug ILIKE $1)",
+        )
+        .bind(&pattern)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to count tags: {}", e)))?;
+
+        Ok((tags, total.0))
+    }
+
+    /// 按 ID 查询标签
+    pub async fn find(&self, id: i64) -> Result<Option<Tag>, RswsError> {
+        sqlx::query_as::<_, Tag>(&format!("{} WHERE t.id = $1", TAG_SELECT))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to get tag: {}", e)))
+    }
+
+    /// 创建标签（slug 已存在时返回 TAG_EXISTS）
+    pub async fn create(
+        &self,
+        name: &str,
+        slug: &str,
+        created_by: Option<i64>,
+    ) -> Result<Tag, RswsError> {
+        let id = snowflake::next_id();
+        sqlx::query(
+            "INSERT INTO tags (id, name, slug, created_by, created_at, updated_at) VALUES ($1, $2, $3, $4, NOW(), NOW())",
+        )
+        .bind(id)
+        .bind(name)
+        .bind(slug)
+        .bind(created_by)
+        .execute(&self.pool)
+        .await
+        .map_err(map_tag_error)?;
+
+        self.find(id)
+            .await?
+            .ok_or_else(|| RswsError::internal("Tag disappeared after insert"))
+    }
+
+    /// 重命名标签（slug 随名称更新）
+    pub async fn update(&self, id: i64, name: &str, slug: &str) -> Result<Option<Tag>, RswsError> {
+        let updated =
+            sqlx::query("UPDATE tags SET name = $2, slug = $3, updated_at = NOW() WHERE id = $1")
+                .bind(id)
+                .bind(name)
+                .bind(slug)
+                .execute(&self.pool)
+                .await
+                .map_err(map_tag_error)?;
+
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+        self.find(id).await
+    }
+
+    /// 删除标签（资源上的该标签一并移除）
+    pub async fn delete(&self, id: i64) -> Result<bool, RswsError> {
+        let deleted = sqlx::query("DELETE FROM tags WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to delete tag: {}", e)))?;
+        Ok(deleted.rows_affected() > 0)
+    }
+
+    /// 整体替换资源标签，`tags` 为 (名称, slug)，不存在的标签自动创建
+    pub async fn set_resource_tags(
+        &self,
+        resource_id: i64,
+        tags: &[(String, String)],
+        created_by: Option<i64>,
+    ) -> Result<Vec<ResourceTag>, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        for (name, slug) in tags {
+            sqlx::query(
+                r#"
+                INSERT INTO tags (id, name, slug, created_by, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, NOW(), NOW())
+                ON CONFLICT (slug) DO NOTHING
+                "#,
+            )
+            .bind(snowflake::next_id())
+            .bind(name)
+            .bind(slug)
+            .bind(created_by)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to create tag: {}", e)))?;
+        }
+
+        sqlx::query("DELETE FROM resource_tags WHERE resource_id = $1")
+            .bind(resource_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to clear resource tags: {}", e)))?;
+
+        let slugs: Vec<String> = tags.iter().map(|(_, slug)| slug.clone()).collect();
+        sqlx::query(
+            r#"
+            INSERT INTO resource_tags (resource_id, tag_id, created_at)
+            SELECT $1, id, NOW() FROM tags WHERE slug = ANY($2)
+            "#,
+        )
+        .bind(resource_id)
+        .bind(&slugs)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to set resource tags: {}", e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit resource tags: {}", e)))?;
+
+        self.tags_for(resource_id).await
+    }
+
+    /// 资源上的标签
+    pub async fn tags_for(&self, resource_id: i64) -> Result<Vec<ResourceTag>, RswsError> {
+        sqlx::query_as::<_, ResourceTag>(
+            r#"
+            SELECT t.name, t.slug
+            FROM resource_tags rt
+            JOIN tags t ON t.id = rt.tag_id
+            WHERE rt.resource_id = $1
+            ORDER BY t.slug
+            "#,
+        )
+        .bind(resource_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to get resource tags: {}", e)))
+    }
+}
+
+/// 标签 slug 重复
+fn map_tag_error(e: sqlx::Error) -> RswsError {
+    if e.to_string().contains("duplicate key") {
+        RswsError::business(ErrorCode::TAG_EXISTS)
+    } else {
+        RswsError::internal(format!("Failed to save tag: {}", e))
+    }
+}
@@ -0,0 +1,177 @@
+//! 入站 Webhook 日志仓储层
+
+use rsws_common::error::RswsError;
+use rsws_common::snowflake;
+use rsws_model::log::webhook_log::{
+    CreateWebhookLogRequest, WebhookLog, WebhookLogQuery, WebhookRecord,
+    WEBHOOK_PROCESSING_TIMEOUT_SECS, WEBHOOK_STATUS_RECEIVED,
+};
+use sqlx::PgPool;
+
+const WEBHOOK_LOG_COLUMNS: &str = "id, webhook_type, source, event_id, event_type, payload, body, headers, signature, status, response_code, response_message, processed_at, retry_count, ip_address, created_at";
+
+/// 筛选条件（参数 $1-$4 依次为 source / status / event_type / event_id）
+const WEBHOOK_LOG_FILTER: &str = "($1::TEXT IS NULL OR source = $1) AND ($2::TEXT IS NULL OR status = $2) AND ($3::TEXT IS NULL OR event_type = $3) AND ($4::TEXT IS NULL OR event_id = $4)";
+
+/// Webhook 日志仓储
+pub struct WebhookLogRepository {
+    pool: PgPool,
+}
+
+impl WebhookLogRepository {
+    /// 创建 Webhook 日志仓储实例
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// 记录入站 Webhook
+    ///
+    /// 同一 (source, event_id) 已存在时：上次处理失败、或处理超时仍为 received
+    /// 则复用该记录重新处理（更新载荷、`retry_count + 1`）；仍在处理中返回
+    /// [`WebhookRecord::InProgress`]，否则视为重复投递。
+    pub async fn record(&self, req: &CreateWebhookLogRequest) -> Result<WebhookRecord, RswsError> {
+        let log = sqlx::query_as::<_, WebhookLog>(&format!(
+            r#"
+            INSERT INTO webhook_logs
+                (id, webhook_type, source, event_id, event_type, payload, body, headers,
+                 signature, status, retry_count, ip_address, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, 'received', 0, $10, NOW())
+            ON CONFLICT (source, event_id) DO UPDATE
+            SET payload = EXCLUDED.payload,
+                body = EXCLUDED.body,
+                headers = EXCLUDED.headers,
+                signature = EXCLUDED.signature,
+                ip_address = EXCLUDED.ip_address,
+                status = 'received',
+                received_at = NOW(),
+                retry_count = webhook_logs.retry_count + 1
+            WHERE webhook_logs.status = 'failed'
+               OR (webhook_logs.status = 'received'
+                   AND webhook_logs.received_at < NOW() - INTERVAL '1 second' * $11)
+            RETURNING {}
+            "#,
+            WEBHOOK_LOG_COLUMNS
+        ))
+        .bind(snowflake::next_id())
+        .bind(&req.webhook_type)
+        .bind(&req.source)
+        .bind(&req.event_id)
+        .bind(&req.event_type)
+        .bind(&req.payload)
+        .bind(&req.body)
+        .bind(&req.headers)
+        .bind(&req.signature)
+        .bind(&req.ip_address)
+        .bind(WEBHOOK_PROCESSING_TIMEOUT_SECS)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to record webhook: {}", e)))?;
+        if let Some(log) = log {
+            return Ok(WebhookRecord::Process(log));
+        }
+
+        let status: Option<(String,)> =
+            sqlx::query_as("SELECT status FROM webhook_logs WHERE source = $1 AND event_id = $2")
+                .bind(&req.source)
+                .bind(&req.event_id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| RswsError::internal(format!("Failed to get webhook status: {}", e)))?;
+        Ok(match status {
+            Some((status,)) if status == WEBHOOK_STATUS_RECEIVED => WebhookRecord::InProgress,
+            _ => WebhookRecord::Duplicate,
+        })
+    }
+
+    /// 回写处理结果
+    pub async fn finish(
+        &self,
+        id: i64,
+        status: &str,
+        response_code: i32,
+        response_message: &str,
+    ) -> Result<(), RswsError> {
+        sqlx::query(
+            r#"
+            UPDATE webhook_logs
+            SET status = $2, response_code = $3, response_message = $4, processed_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(status)
+        .bind(response_code)
+        .bind(response_message)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to update webhook log: {}", e)))?;
+        Ok(())
+    }
+
+    /// 标记重放开始（状态置为 received，`retry_count + 1`）
+    pub async fn begin_replay(&self, id: i64) -> Result<Option<WebhookLog>, RswsError> {
+        sqlx::query_as::<_, WebhookLog>(&format!(
+            r#"
+            UPDATE webhook_logs
+            SET status = 'received', received_at = NOW(), retry_count = retry_count + 1
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            WEBHOOK_LOG_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to replay webhook: {}", e)))
+    }
+
+    /// 根据 ID 获取 Webhook 日志
+    pub async fn get_by_id(&self, id: i64) -> Result<Option<WebhookLog>, RswsError> {
+        sqlx::query_as::<_, WebhookLog>(&format!(
+            "SELECT {} FROM webhook_logs WHERE id = $1",
+            WEBHOOK_LOG_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to get webhook log: {}", e)))
+    }
+
+    /// 分页查询 Webhook 日志（按接收时间倒序）
+    pub async fn list(
+        &self,
+        query: &WebhookLogQuery,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<WebhookLog>, i64), RswsError> {
+        let offset = (page - 1) * page_size;
+
+        let logs = sqlx::query_as::<_, WebhookLog>(&format!(
+            "SELECT {} FROM webhook_logs WHERE {} ORDER BY created_at DESC, id DESC LIMIT $5 OFFSET $6",
+            WEBHOOK_LOG_COLUMNS, WEBHOOK_LOG_FILTER
+        ))
+        .bind(&query.source)
+        .bind(&query.status)
+        .bind(&query.event_type)
+        .bind(&query.event_id)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list webhook logs: {}", e)))?;
+
+        let total: (i64,) = sqlx::query_as(&format!(
+            "SELECT COUNT(*) FROM webhook_logs WHERE {}",
+            WEBHOOK_LOG_FILTER
+        ))
+        .bind(&query.source)
+        .bind(&query.status)
+        .bind(&query.event_type)
+        .bind(&query.event_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to count webhook logs: {}", e)))?;
+
+        Ok((logs, total.0))
+    }
+}
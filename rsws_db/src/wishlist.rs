@@ -0,0 +1,501 @@
+//! 收藏与心愿单仓储层
+
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_common::snowflake;
+use rsws_model::wishlist::{MostWishedItem, PriceDropNotice, SavedResource, Wishlist};
+use sqlx::{PgConnection, PgPool};
+
+const WISHLIST_COLUMNS: &str = "id, user_id, name, description, is_public, (SELECT COUNT(*) FROM wishlist_items i WHERE i.wishlist_id = wishlists.id) AS item_count, created_at, updated_at";
+
+const SAVED_RESOURCE_COLUMNS: &str =
+    "r.id AS resource_id, r.title, r.price, r.thumbnail_url, r.rating_avg, r.rating_count";
+
+/// 收藏与心愿单仓储
+pub struct WishlistRepository {
+    pool: PgPool,
+}
+
+impl WishlistRepository {
+    /// 创建收藏与心愿单仓储实例
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// 在事务内登记已保存资源（按当前价格开始关注降价，已登记时不变）
+    async fn watch_in_tx(
+        conn: &mut PgConnection,
+        user_id: i64,
+        resource_id: i64,
+    ) -> Result<(), RswsError> {
+        sqlx::query(
+            r#"
+            INSERT INTO saved_resources (user_id, resource_id, watched_price, created_at)
+            SELECT $1, id, COALESCE(price, 0), NOW() FROM resources WHERE id = $2
+            ON CONFLICT (user_id, resource_id) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(resource_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to watch saved resource: {}", e)))?;
+        Ok(())
+    }
+
+    /// 在事务内移除不再收藏、也不在任何心愿单中的已保存资源
+    async fn unwatch_in_tx(
+        conn: &mut PgConnection,
+        user_id: i64,
+        resource_ids: &[i64],
+    ) -> Result<(), RswsError> {
+        sqlx::query(
+            r#"
+            DELETE FROM saved_resources s
+            WHERE s.user_id = $1 AND s.resource_id = ANY($2)
+              AND NOT EXISTS (
+                  SELECT 1 FROM favorites f
+                  WHERE f.user_id = s.user_id AND f.resource_id = s.resource_id
+              )
+              AND NOT EXISTS (
+                  SELECT 1 FROM wishlist_items i
+                  JOIN wishlists w ON w.id = i.wishlist_id
+                  WHERE w.user_id = s.user_id AND i.resource_id = s.resource_id
+              )
+            "#,
+        )
+        .bind(user_id)
+        .bind(resource_ids)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to unwatch saved resource: {}", e)))?;
+        Ok(())
+    }
+
+    // ==================== 收藏 ====================
+
+    /// 收藏资源，已收藏时返回 false
+    pub async fn add_favorite(&self, user_id: i64, resource_id: i64) -> Result<bool, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let added = sqlx::query(
+            r#"
+            INSERT INTO favorites (user_id, resource_id, created_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (user_id, resource_id) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(resource_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to add favorite: {}", e)))?
+        .rows_affected()
+            > 0;
+
+        Self::watch_in_tx(&mut *tx, user_id, resource_id).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit favorite: {}", e)))?;
+
+        Ok(added)
+    }
+
+    /// 取消收藏，未收藏时返回 false
+    pub async fn remove_favorite(&self, user_id: i64, resource_id: i64) -> Result<bool, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let removed = sqlx::query("DELETE FROM favorites WHERE user_id = $1 AND resource_id = $2")
+            .bind(user_id)
+            .bind(resource_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to remove favorite: {}", e)))?
+            .rows_affected()
+            > 0;
+
+        Self::unwatch_in_tx(&mut *tx, user_id, &[resource_id]).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit favorite: {}", e)))?;
+
+        Ok(removed)
+    }
+
+    /// 用户收藏的资源（最近收藏在前，仅上架资源）
+    pub async fn list_favorites(
+        &self,
+        user_id: i64,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<SavedResource>, i64), RswsError> {
+        let offset = (page - 1) * page_size;
+
+        let items = sqlx::query_as::<_, SavedResource>(&format!(
+            r#"
+            SELECT {}, f.created_at AS saved_at
+            FROM favorites f
+            JOIN resources r ON r.id = f.resource_id
+            WHERE f.user_id = $1 AND r.is_active = true
+            ORDER BY f.created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            SAVED_RESOURCE_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list favorites: {}", e)))?;
+
+        let total: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM favorites f
+            JOIN resources r ON r.id = f.resource_id
+            WHERE f.user_id = $1 AND r.is_active = true
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to count favorites: {}", e)))?;
+
+        Ok((items, total.0))
+    }
+
+    // ==================== 心愿单 ====================
+
+    /// 用户的全部心愿单
+    pub async fn list_wishlists(&self, user_id: i64) -> Result<Vec<Wishlist>, RswsError> {
+        sqlx::query_as::<_, Wishlist>(&format!(
+            "SELECT {} FROM wishlists WHERE user_id = $1 ORDER BY created_at",
+            WISHLIST_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list wishlists: {}", e)))
+    }
+
+    /// 根据 ID 获取心愿单
+    pub async fn find_wishlist(&self, id: i64) -> Result<Option<Wishlist>, RswsError> {
+        sqlx::query_as::<_, Wishlist>(&format!(
+            "SELECT {} FROM wishlists WHERE id = $1",
+            WISHLIST_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to get wishlist: {}", e)))
+    }
+
+    /// 用户心愿单数量
+    pub async fn count_wishlists(&self, user_id: i64) -> Result<i64, RswsError> {
+        let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM wishlists WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to count wishlists: {}", e)))?;
+        Ok(row.0)
+    }
+
+    /// 创建心愿单
+    pub async fn create_wishlist(
+        &self,
+        user_id: i64,
+        name: &str,
+        description: Option<&str>,
+        is_public: bool,
+    ) -> Result<Wishlist, RswsError> {
+        sqlx::query_as::<_, Wishlist>(&format!(
+            r#"
+            INSERT INTO wishlists (id, user_id, name, description, is_public, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, NOW(), NOW())
+            RETURNING {}
+            "#,
+            WISHLIST_COLUMNS
+        ))
+        .bind(snowflake::next_id())
+        .bind(user_id)
+        .bind(name)
+        .bind(description)
+        .bind(is_public)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(map_wishlist_error)
+    }
+
+    /// 更新心愿单（None 字段保持不变）
+    pub async fn update_wishlist(
+        &self,
+        id: i64,
+        name: Option<&str>,
+        description: Option<&str>,
+        is_public: Option<bool>,
+    ) -> Result<Option<Wishlist>, RswsError> {
+        sqlx::query_as::<_, Wishlist>(&format!(
+            r#"
+            UPDATE wishlists
+            SET name = COALESCE($2, name),
+                description = COALESCE($3, description),
+                is_public = COALESCE($4, is_public),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            WISHLIST_COLUMNS
+        ))
+        .bind(id)
+        .bind(name)
+        .bind(description)
+        .bind(is_public)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_wishlist_error)
+    }
+
+    /// 删除心愿单
+    pub async fn delete_wishlist(&self, wishlist: &Wishlist) -> Result<(), RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let resource_ids: Vec<(i64,)> =
+            sqlx::query_as("SELECT resource_id FROM wishlist_items WHERE wishlist_id = $1")
+                .bind(wishlist.id)
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(|e| {
+                    RswsError::internal(format!("Failed to list wishlist items: {}", e))
+                })?;
+
+        sqlx::query("DELETE FROM wishlists WHERE id = $1")
+            .bind(wishlist.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to delete wishlist: {}", e)))?;
+
+        let resource_ids: Vec<i64> = resource_ids.into_iter().map(|r| r.0).collect();
+        Self::unwatch_in_tx(&mut *tx, wishlist.user_id, &resource_ids).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit wishlist: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// 心愿单中的资源（最近加入在前，仅上架资源）
+    pub async fn list_items(&self, wishlist_id: i64) -> Result<Vec<SavedResource>, RswsError> {
+        sqlx::query_as::<_, SavedResource>(&format!(
+            r#"
+            SELECT {}, i.created_at AS saved_at
+            FROM wishlist_items i
+            JOIN resources r ON r.id = i.resource_id
+            WHERE i.wishlist_id = $1 AND r.is_active = true
+            ORDER BY i.created_at DESC
+            "#,
+            SAVED_RESOURCE_COLUMNS
+        ))
+        .bind(wishlist_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to list wishlist items: {}", e)))
+    }
+
+    /// 加入心愿单，已在心愿单中时返回 false
+    pub async fn add_item(&self, wishlist: &Wishlist, resource_id: i64) -> Result<bool, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let added = sqlx::query(
+            r#"
+            INSERT INTO wishlist_items (wishlist_id, resource_id, created_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (wishlist_id, resource_id) DO NOTHING
+            "#,
+        )
+        .bind(wishlist.id)
+        .bind(resource_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to add wishlist item: {}", e)))?
+        .rows_affected()
+            > 0;
+
+        Self::watch_in_tx(&mut *tx, wishlist.user_id, resource_id).await?;
+
+        sqlx::query("UPDATE wishlists SET updated_at = NOW() WHERE id = $1")
+            .bind(wishlist.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to update wishlist: {}", e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit wishlist item: {}", e)))?;
+
+        Ok(added)
+    }
+
+    /// 移出心愿单，不在心愿单中时返回 false
+    pub async fn remove_item(
+        &self,
+        wishlist: &Wishlist,
+        resource_id: i64,
+    ) -> Result<bool, RswsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+        let removed =
+            sqlx::query("DELETE FROM wishlist_items WHERE wishlist_id = $1 AND resource_id = $2")
+                .bind(wishlist.id)
+                .bind(resource_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| RswsError::internal(format!("Failed to remove wishlist item: {}", e)))?
+                .rows_affected()
+                > 0;
+
+        Self::unwatch_in_tx(&mut *tx, wishlist.user_id, &[resource_id]).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to commit wishlist item: {}", e)))?;
+
+        Ok(removed)
+    }
+
+    // ==================== 保存状态与降价通知 ====================
+
+    /// 给定资源中用户已保存的资源 ID
+    pub async fn saved_resource_ids(
+        &self,
+        user_id: i64,
+        resource_ids: &[i64],
+    ) -> Result<Vec<i64>, RswsError> {
+        let rows: Vec<(i64,)> = sqlx::query_as(
+            "SELECT resource_id FROM saved_resources WHERE user_id = $1 AND resource_id = ANY($2)",
+        )
+        .bind(user_id)
+        .bind(resource_ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load saved resources: {}", e)))?;
+
+        Ok(rows.into_iter().map(|r| r.0).collect())
+    }
+
+    /// 价格上涨的已保存资源同步关注价格（不通知），之后降回原价时仍会通知
+    pub async fn sync_raised_prices(&self) -> Result<u64, RswsError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE saved_resources s
+            SET watched_price = r.price
+            FROM resources r
+            WHERE r.id = s.resource_id AND r.price > s.watched_price
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to sync saved prices: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// 领取降价通知（关注价格更新为当前价格，发送失败不重试）
+    ///
+    /// 仅公开（上架且审核通过）的资源。
+    pub async fn claim_price_drops(&self, limit: i64) -> Result<Vec<PriceDropNotice>, RswsError> {
+        sqlx::query_as::<_, PriceDropNotice>(
+            r#"
+            WITH due AS (
+                SELECT s.user_id, s.resource_id, s.watched_price AS old_price
+                FROM saved_resources s
+                JOIN resources r ON r.id = s.resource_id
+                WHERE r.price < s.watched_price
+                  AND r.is_active = true AND r.review_status = 'approved'
+                ORDER BY s.resource_id, s.user_id
+                LIMIT $1
+                FOR UPDATE OF s SKIP LOCKED
+            )
+            UPDATE saved_resources s
+            SET watched_price = r.price, notified_at = NOW()
+            FROM due
+            JOIN resources r ON r.id = due.resource_id
+            JOIN users u ON u.id = due.user_id
+            WHERE s.user_id = due.user_id AND s.resource_id = due.resource_id
+            RETURNING s.user_id, u.email, u.username, s.resource_id, r.title,
+                      due.old_price, r.price AS new_price
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to claim price drops: {}", e)))
+    }
+
+    /// 最受期待资源：按保存用户数排序
+    ///
+    /// `days` 为"近期新增保存"的统计区间。
+    pub async fn most_wished(
+        &self,
+        days: i32,
+        limit: i64,
+    ) -> Result<Vec<MostWishedItem>, RswsError> {
+        sqlx::query_as::<_, MostWishedItem>(
+            r#"
+            SELECT r.id AS resource_id, r.title, r.price, r.provider_id,
+                   COUNT(*) AS saved_count,
+                   COUNT(*) FILTER (
+                       WHERE s.created_at >= NOW() - make_interval(days => $1::INT)
+                   ) AS recent_saves,
+                   COUNT(*) FILTER (
+                       WHERE EXISTS (
+                           SELECT 1 FROM orders o
+                           WHERE o.user_id = s.user_id AND o.resource_id = s.resource_id
+                             AND o.status IN ('paid', 'completed')
+                       )
+                   ) AS purchased_count
+            FROM saved_resources s
+            JOIN resources r ON r.id = s.resource_id
+            GROUP BY r.id
+            ORDER BY saved_count DESC, recent_saves DESC, r.id
+            LIMIT $2
+            "#,
+        )
+        .bind(days)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to load most wished resources: {}", e)))
+    }
+}
+
+/// 同一用户心愿单重名
+fn map_wishlist_error(e: sqlx::Error) -> RswsError {
+    if e.to_string().contains("duplicate key") {
+        RswsError::business(ErrorCode::WISHLIST_NAME_EXISTS)
+    } else {
+        RswsError::internal(format!("Failed to save wishlist: {}", e))
+    }
+}
@@ -0,0 +1,374 @@
+//! 创作者分成规则模型
+//!
+//! 订单支付时按规则计算创作者分成：
+//! - 条件：分类（含子分类）、创作者等级、商品金额区间 [min_amount, max_amount)、生效时间 [starts_at, ends_at)
+//! - 优先级：priority 高者优先，同优先级取条件更多者，再取 ID 较小者
+//! - 金额：商品金额 × 比例，按保底 / 封顶调整，且不超过商品金额
+//! - 无规则命中时沿用资源自身的 commission_rate
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use salvo_oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 规则类型：按规则比例
+pub const RULE_TYPE_PERCENTAGE: &str = "percentage";
+/// 规则类型：按资源自身比例，仅套用保底 / 封顶
+pub const RULE_TYPE_RESOURCE: &str = "resource";
+
+/// 全部规则类型
+pub const COMMISSION_RULE_TYPES: [&str; 2] = [RULE_TYPE_PERCENTAGE, RULE_TYPE_RESOURCE];
+
+/// 未设置等级的创作者
+pub const DEFAULT_CREATOR_TIER: &str = "standard";
+
+/// 创作者等级最大长度
+pub const CREATOR_TIER_MAX_LEN: usize = 30;
+
+/// 规范化创作者等级：去除首尾空白并转为小写，格式不合法时返回 None
+pub fn normalize_creator_tier(tier: &str) -> Option<String> {
+    let tier = tier.trim();
+    if tier.is_empty()
+        || tier.len() > CREATOR_TIER_MAX_LEN
+        || !tier
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return None;
+    }
+    Some(tier.to_ascii_lowercase())
+}
+
+/// 分成规则
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct CommissionRule {
+    pub id: i64,
+    pub name: String,
+    /// percentage / resource
+    pub rule_type: String,
+    /// 分成比例（percentage 规则）
+    pub rate: Decimal,
+    /// 限定分类，子分类同样适用
+    pub category_id: Option<i64>,
+    /// 限定创作者等级
+    pub creator_tier: Option<String>,
+    /// 商品金额下限（含）
+    pub min_amount: Option<Decimal>,
+    /// 商品金额上限（不含）
+    pub max_amount: Option<Decimal>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub priority: i32,
+    /// 保底分成
+    pub min_commission: Option<Decimal>,
+    /// 封顶分成
+    pub max_commission: Option<Decimal>,
+    pub is_active: bool,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CommissionRule {
+    /// 规则是否适用于该订单
+    pub fn matches(&self, ctx: &CommissionContext) -> bool {
+        self.is_active
+            && self
+                .category_id
+                .is_none_or(|id| ctx.category_ids.contains(&id))
+            && self
+                .creator_tier
+                .as_deref()
+                .is_none_or(|tier| tier == ctx.creator_tier)
+            && self.min_amount.is_none_or(|min| ctx.base >= min)
+            && self.max_amount.is_none_or(|max| ctx.base < max)
+            && self.starts_at.is_none_or(|start| ctx.at >= start)
+            && self.ends_at.is_none_or(|end| ctx.at < end)
+    }
+
+    /// 条件数量（同优先级时条件多者优先）
+    pub fn specificity(&self) -> usize {
+        [
+            self.category_id.is_some(),
+            self.creator_tier.is_some(),
+            self.min_amount.is_some_and(|m| m > Decimal::ZERO),
+            self.max_amount.is_some(),
+            self.starts_at.is_some(),
+            self.ends_at.is_some(),
+        ]
+        .iter()
+        .filter(|c| **c)
+        .count()
+    }
+}
+
+/// 创建分成规则请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateCommissionRuleRequest {
+    pub name: String,
+    /// 默认 percentage
+    pub rule_type: Option<String>,
+    #[serde(default)]
+    pub rate: Decimal,
+    pub category_id: Option<i64>,
+    pub creator_tier: Option<String>,
+    pub min_amount: Option<Decimal>,
+    pub max_amount: Option<Decimal>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub priority: i32,
+    pub min_commission: Option<Decimal>,
+    pub max_commission: Option<Decimal>,
+    pub description: Option<String>,
+}
+
+/// 更新分成规则请求
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
+pub struct UpdateCommissionRuleRequest {
+    pub name: Option<String>,
+    pub rule_type: Option<String>,
+    pub rate: Option<Decimal>,
+    pub category_id: Option<i64>,
+    pub creator_tier: Option<String>,
+    pub min_amount: Option<Decimal>,
+    pub max_amount: Option<Decimal>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub priority: Option<i32>,
+    pub min_commission: Option<Decimal>,
+    pub max_commission: Option<Decimal>,
+    pub is_active: Option<bool>,
+    pub description: Option<String>,
+}
+
+/// 设置创作者等级请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SetCreatorTierRequest {
+    pub tier: String,
+}
+
+/// 规则评估上下文
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CommissionContext {
+    pub resource_id: i64,
+    pub provider_id: i64,
+    /// 资源分类及其全部上级分类
+    pub category_ids: Vec<i64>,
+    pub creator_tier: String,
+    /// 商品金额（不含附加费）
+    pub base: Decimal,
+    /// 资源自身的分成比例
+    pub resource_rate: Decimal,
+    /// 评估时间（订单支付时间）
+    pub at: DateTime<Utc>,
+}
+
+/// 分成计算结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CommissionQuote {
+    /// 命中的规则，None 表示沿用资源比例
+    pub rule_id: Option<i64>,
+    pub rule_name: Option<String>,
+    pub base: Decimal,
+    /// 规则（或资源）给出的比例
+    pub rule_rate: Decimal,
+    /// 保底 / 封顶调整前的分成
+    pub raw_amount: Decimal,
+    pub amount: Decimal,
+    /// 实际分成比例（amount / base），记录在 commission_records
+    pub rate: Decimal,
+}
+
+/// 分成模拟结果
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CommissionSimulation {
+    pub context: CommissionContext,
+    /// 命中的全部规则 ID（按优先级排序，第一条生效）
+    pub matched_rule_ids: Vec<i64>,
+    pub quote: CommissionQuote,
+}
+
+/// 分成模拟请求：指定订单，或指定资源（可选金额与时间）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SimulateCommissionRequest {
+    pub order_id: Option<i64>,
+    pub resource_id: Option<i64>,
+    /// 商品金额，默认取资源价格
+    pub amount: Option<Decimal>,
+    /// 评估时间，默认当前时间
+    pub at: Option<DateTime<Utc>>,
+}
+
+/// 按优先级排序命中的规则（第一条生效）
+pub fn matching_rules<'a>(
+    rules: &'a [CommissionRule],
+    ctx: &CommissionContext,
+) -> Vec<&'a CommissionRule> {
+    let mut matched: Vec<&CommissionRule> = rules.iter().filter(|r| r.matches(ctx)).collect();
+    matched.sort_by(|a, b| {
+        b.priority
+            .cmp(&a.priority)
+            .then_with(|| b.specificity().cmp(&a.specificity()))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    matched
+}
+
+/// 计算创作者分成
+pub fn compute_commission(rules: &[CommissionRule], ctx: &CommissionContext) -> CommissionQuote {
+    let rule = matching_rules(rules, ctx).into_iter().next();
+    let rule_rate = match rule {
+        Some(r) if r.rule_type == RULE_TYPE_PERCENTAGE => r.rate,
+        _ => ctx.resource_rate,
+    };
+    let base = ctx.base.max(Decimal::ZERO);
+    let raw_amount = (base * rule_rate.max(Decimal::ZERO)).round_dp(2);
+
+    let mut amount = raw_amount;
+    if let Some(floor) = rule.and_then(|r| r.min_commission) {
+        amount = amount.max(floor);
+    }
+    if let Some(cap) = rule.and_then(|r| r.max_commission) {
+        amount = amount.min(cap);
+    }
+    let amount = amount.min(base).max(Decimal::ZERO);
+
+    let rate = if base > Decimal::ZERO {
+        (amount / base).round_dp(4)
+    } else {
+        Decimal::ZERO
+    };
+
+    CommissionQuote {
+        rule_id: rule.map(|r| r.id),
+        rule_name: rule.map(|r| r.name.clone()),
+        base,
+        rule_rate,
+        raw_amount,
+        amount,
+        rate,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn rule(id: i64, rate: i64, priority: i32) -> CommissionRule {
+        CommissionRule {
+            id,
+            name: format!("rule {}", id),
+            rule_type: RULE_TYPE_PERCENTAGE.to_string(),
+            rate: Decimal::new(rate, 2),
+            category_id: None,
+            creator_tier: None,
+            min_amount: None,
+            max_amount: None,
+            starts_at: None,
+            ends_at: None,
+            priority,
+            min_commission: None,
+            max_commission: None,
+            is_active: true,
+            description: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn ctx(base: i64) -> CommissionContext {
+        CommissionContext {
+            resource_id: 1,
+            provider_id: 2,
+            category_ids: vec![30, 3],
+            creator_tier: DEFAULT_CREATOR_TIER.to_string(),
+            base: Decimal::new(base, 2),
+            resource_rate: Decimal::new(70, 2),
+            at: Utc.with_ymd_and_hms(2026, 6, 15, 0, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_falls_back_to_resource_rate() {
+        let mut inactive = rule(1, 90, 0);
+        inactive.is_active = false;
+        let quote = compute_commission(&[inactive], &ctx(1000));
+        assert_eq!(quote.rule_id, None);
+        assert_eq!(quote.amount, Decimal::new(700, 2));
+        assert_eq!(quote.rate, Decimal::new(70, 2));
+    }
+
+    #[test]
+    fn test_precedence() {
+        let general = rule(1, 50, 0);
+        let mut category = rule(2, 60, 0);
+        category.category_id = Some(3);
+        let mut promo = rule(3, 80, 10);
+        promo.ends_at = Some(Utc.with_ymd_and_hms(2026, 6, 1, 0, 0, 0).unwrap());
+
+        // 同优先级取更具体的分类规则；已过期的高优先级规则不生效
+        let rules = [general, category, promo];
+        let quote = compute_commission(&rules, &ctx(1000));
+        assert_eq!(quote.rule_id, Some(2));
+        assert_eq!(quote.amount, Decimal::new(600, 2));
+        assert_eq!(
+            matching_rules(&rules, &ctx(1000))
+                .iter()
+                .map(|r| r.id)
+                .collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+    }
+
+    #[test]
+    fn test_tier_and_price_band() {
+        let mut pro = rule(1, 85, 0);
+        pro.creator_tier = Some("pro".to_string());
+        let mut band = rule(2, 65, 0);
+        band.min_amount = Some(Decimal::new(5000, 2));
+        band.max_amount = Some(Decimal::new(10000, 2));
+
+        let rules = [pro, band];
+        assert_eq!(compute_commission(&rules, &ctx(1000)).rule_id, None);
+        assert_eq!(compute_commission(&rules, &ctx(5000)).rule_id, Some(2));
+        assert_eq!(compute_commission(&rules, &ctx(10000)).rule_id, None);
+
+        let mut pro_ctx = ctx(5000);
+        pro_ctx.creator_tier = "pro".to_string();
+        // 两条规则同样具体，取 ID 较小者
+        assert_eq!(compute_commission(&rules, &pro_ctx).rule_id, Some(1));
+    }
+
+    #[test]
+    fn test_floor_and_cap() {
+        let mut floor = rule(1, 10, 0);
+        floor.min_commission = Some(Decimal::new(200, 2));
+        let quote = compute_commission(&[floor.clone()], &ctx(1000));
+        assert_eq!(quote.raw_amount, Decimal::new(100, 2));
+        assert_eq!(quote.amount, Decimal::new(200, 2));
+        assert_eq!(quote.rate, Decimal::new(20, 2));
+
+        // 保底不超过商品金额
+        let quote = compute_commission(&[floor], &ctx(150));
+        assert_eq!(quote.amount, Decimal::new(150, 2));
+        assert_eq!(quote.rate, Decimal::ONE);
+
+        let mut cap = rule(2, 0, 0);
+        cap.rule_type = RULE_TYPE_RESOURCE.to_string();
+        cap.max_commission = Some(Decimal::new(5000, 2));
+        let quote = compute_commission(&[cap], &ctx(100000));
+        assert_eq!(quote.rule_rate, Decimal::new(70, 2));
+        assert_eq!(quote.amount, Decimal::new(5000, 2));
+        assert_eq!(quote.rate, Decimal::new(5, 2));
+    }
+
+    #[test]
+    fn test_normalize_creator_tier() {
+        assert_eq!(normalize_creator_tier(" Pro "), Some("pro".to_string()));
+        assert_eq!(normalize_creator_tier(""), None);
+        assert_eq!(normalize_creator_tier("top tier"), None);
+    }
+}
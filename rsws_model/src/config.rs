@@ -60,6 +60,14 @@ pub struct DownloadPluginConfig {
     pub storage_config: Option<serde_json::Value>,
     pub max_file_size: Option<i64>,
     pub allowed_extensions: Option<Vec<String>>,
+    /// 下载令牌有效期（秒）
+    pub token_ttl_secs: i32,
+    /// 每用户每日下载次数上限（滚动 24 小时），为空表示不限
+    pub daily_limit_per_user: Option<i32>,
+    /// 是否按买家生成水印文件
+    pub watermark_enabled: bool,
+    /// 参与水印的文件大小上限（字节），超过则原样下载
+    pub watermark_max_bytes: i64,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
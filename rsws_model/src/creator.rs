@@ -0,0 +1,139 @@
+//! 创作者收益模型
+//!
+//! 基于 `orders`、`resources.provider_id` 与 `commission_records`（referrer_id 为空的创作者分成）：
+//! - 销售额（gross）：商品金额，不含买家承担的附加费
+//! - 创作者净收入（net）：创作者分成金额
+//! - 平台佣金：销售额 - 净收入
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use rust_decimal::Decimal;
+use salvo_oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 创作者收益概览
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct CreatorEarningsSummary {
+    /// 已支付订单数
+    pub orders: i64,
+    pub gross: Decimal,
+    pub platform_commission: Decimal,
+    pub net: Decimal,
+    /// 冻结期内待结算的分成
+    pub pending: Decimal,
+    /// 已结算到余额的分成
+    pub settled: Decimal,
+}
+
+/// 单个资源的销售统计
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ResourceSales {
+    pub resource_id: i64,
+    pub title: String,
+    pub is_active: bool,
+    pub orders: i64,
+    pub gross: Decimal,
+    pub platform_commission: Decimal,
+    pub net: Decimal,
+}
+
+/// 对账单明细（每个已支付订单一行）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct StatementLine {
+    pub order_id: i64,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub resource_id: i64,
+    pub resource_title: String,
+    pub gross: Decimal,
+    pub platform_commission: Decimal,
+    pub net: Decimal,
+    /// 分成状态：pending / settled，无分成时为空
+    pub commission_status: Option<String>,
+}
+
+/// 月度汇总
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct StatementMonth {
+    /// YYYY-MM
+    pub month: String,
+    pub orders: i64,
+    pub gross: Decimal,
+    pub platform_commission: Decimal,
+    pub net: Decimal,
+}
+
+/// 月度对账单
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MonthlyStatement {
+    /// YYYY-MM
+    pub month: String,
+    pub orders: i64,
+    pub gross: Decimal,
+    pub platform_commission: Decimal,
+    pub net: Decimal,
+    pub lines: Vec<StatementLine>,
+}
+
+impl MonthlyStatement {
+    /// 由明细汇总生成对账单
+    pub fn new(month: &str, lines: Vec<StatementLine>) -> Self {
+        let sum = |f: fn(&StatementLine) -> Decimal| lines.iter().map(f).sum::<Decimal>();
+        Self {
+            month: month.to_string(),
+            orders: lines.len() as i64,
+            gross: sum(|l| l.gross),
+            platform_commission: sum(|l| l.platform_commission),
+            net: sum(|l| l.net),
+            lines,
+        }
+    }
+}
+
+/// 解析对账月份（YYYY-MM），返回 [当月 1 日, 次月 1 日) 的 UTC 时间区间
+pub fn month_range(month: &str) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let (year, mon) = month.trim().split_once('-')?;
+    let (year, mon): (i32, u32) = (year.parse().ok()?, mon.parse().ok()?);
+    let start = NaiveDate::from_ymd_opt(year, mon, 1)?;
+    let end = if mon == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+    } else {
+        NaiveDate::from_ymd_opt(year, mon + 1, 1)?
+    };
+    Some((
+        Utc.from_utc_datetime(&start.and_hms_opt(0, 0, 0)?),
+        Utc.from_utc_datetime(&end.and_hms_opt(0, 0, 0)?),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_month_range() {
+        let (start, end) = month_range("2026-12").unwrap();
+        assert_eq!(start.to_rfc3339(), "2026-12-01T00:00:00+00:00");
+        assert_eq!(end.to_rfc3339(), "2027-01-01T00:00:00+00:00");
+        assert!(month_range("2026-13").is_none());
+        assert!(month_range("202606").is_none());
+    }
+
+    #[test]
+    fn test_monthly_statement_totals() {
+        let line = |gross: i64, net: i64| StatementLine {
+            order_id: 1,
+            paid_at: None,
+            resource_id: 1,
+            resource_title: "r".to_string(),
+            gross: Decimal::new(gross, 2),
+            platform_commission: Decimal::new(gross - net, 2),
+            net: Decimal::new(net, 2),
+            commission_status: None,
+        };
+        let statement = MonthlyStatement::new("2026-06", vec![line(1000, 700), line(500, 0)]);
+        assert_eq!(statement.orders, 2);
+        assert_eq!(statement.gross, Decimal::new(1500, 2));
+        assert_eq!(statement.platform_commission, Decimal::new(800, 2));
+        assert_eq!(statement.net, Decimal::new(700, 2));
+    }
+}
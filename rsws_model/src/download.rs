@@ -0,0 +1,92 @@
+//! 资源下载令牌模型
+//!
+//! 下载接口签发短期令牌（绑定用户、资源与版本），客户端凭令牌访问
+//! `/api/v1/download/{token}`：S3 存储重定向到预签名 URL，本地存储流式返回并支持 Range。
+//!
+//! 启用水印时每次下载生成个性化文件（zip 注入 LICENSE，PDF / 图片写入签名元数据），
+//! 水印记录长期保留，管理员可凭泄露文件反查订单。
+
+use chrono::{DateTime, Utc};
+use salvo_oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 默认下载令牌有效期（秒）
+pub const DEFAULT_DOWNLOAD_TOKEN_TTL_SECS: i32 = 300;
+
+/// 下载令牌有效期上限（秒）
+pub const MAX_DOWNLOAD_TOKEN_TTL_SECS: i32 = 86400;
+
+/// 过期令牌保留天数（每日限额按令牌签发记录统计）
+pub const DOWNLOAD_TOKEN_RETENTION_DAYS: i32 = 7;
+
+/// 下载令牌
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DownloadToken {
+    pub id: i64,
+    pub user_id: i64,
+    pub resource_id: i64,
+    pub version_id: Option<i64>,
+    pub file_url: String,
+    pub file_name: String,
+    pub expires_at: DateTime<Utc>,
+    pub use_count: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 签发给客户端的下载凭证
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DownloadTicket {
+    /// 下载地址（需携带认证信息访问）
+    pub download_url: String,
+    pub file_name: String,
+    pub expires_at: DateTime<Utc>,
+    pub version_id: Option<i64>,
+    pub version: Option<String>,
+    pub file_size: Option<i64>,
+    pub checksum: Option<String>,
+}
+
+/// 更新下载配置请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateDownloadConfigRequest {
+    /// 下载令牌有效期（秒）
+    pub token_ttl_secs: i32,
+    /// 每用户每日下载次数上限，为空表示不限
+    pub daily_limit_per_user: Option<i32>,
+    /// 是否按买家生成水印文件，为空表示不修改
+    #[serde(default)]
+    pub watermark_enabled: Option<bool>,
+    /// 参与水印的文件大小上限（字节），为空表示不修改
+    #[serde(default)]
+    pub watermark_max_bytes: Option<i64>,
+}
+
+/// 下载水印记录
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct DownloadWatermark {
+    pub id: i64,
+    /// 写入文件的签名水印
+    pub payload: String,
+    pub token_id: i64,
+    pub user_id: i64,
+    pub order_id: Option<i64>,
+    pub resource_id: i64,
+    pub version_id: Option<i64>,
+    pub file_name: String,
+    pub issued_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 泄露文件反查结果（文件中找到的每个水印一条）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WatermarkMatch {
+    pub payload: String,
+    pub user_id: i64,
+    pub order_id: Option<i64>,
+    pub issued_at: DateTime<Utc>,
+    /// 签名是否有效（无效说明水印被篡改或伪造）
+    pub signature_valid: bool,
+    /// 对应的下载记录
+    pub record: Option<DownloadWatermark>,
+}
@@ -0,0 +1,151 @@
+//! 出站事件 Webhook 模型
+//!
+//! 管理员为集成方（ERP 等）配置订阅端点，订单支付 / 退款、资源上架等事件
+//! 写入持久化投递队列，由后台任务签名投递，失败按指数退避重试，超过次数进入死信。
+
+use chrono::{DateTime, Utc};
+use salvo_oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 事件类型
+pub const EVENT_ORDER_PAID: &str = "order.paid";
+pub const EVENT_ORDER_REFUNDED: &str = "order.refunded";
+pub const EVENT_RESOURCE_PUBLISHED: &str = "resource.published";
+
+/// 全部可订阅的事件类型
+pub const EVENT_TYPES: [&str; 3] = [
+    EVENT_ORDER_PAID,
+    EVENT_ORDER_REFUNDED,
+    EVENT_RESOURCE_PUBLISHED,
+];
+
+/// 投递状态
+pub const DELIVERY_PENDING: &str = "pending";
+pub const DELIVERY_SUCCEEDED: &str = "succeeded";
+pub const DELIVERY_DEAD: &str = "dead";
+
+/// 最大投递次数，超过后进入死信
+pub const MAX_DELIVERY_ATTEMPTS: i32 = 10;
+
+/// 首次重试间隔（秒），之后每次翻倍
+const RETRY_BASE_SECS: i64 = 30;
+
+/// 最长重试间隔（秒）
+const RETRY_MAX_SECS: i64 = 6 * 3600;
+
+/// 第 `attempts` 次投递失败后的重试间隔（秒），达到最大次数时返回 None（进入死信）
+///
+/// 30s、1m、2m、4m …… 最长 6 小时。
+pub fn retry_delay_secs(attempts: i32) -> Option<i64> {
+    if attempts >= MAX_DELIVERY_ATTEMPTS {
+        return None;
+    }
+    let exp = (attempts.max(1) - 1).min(20) as u32;
+    Some((RETRY_BASE_SECS << exp).min(RETRY_MAX_SECS))
+}
+
+/// 事件订阅端点
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WebhookSubscription {
+    pub id: i64,
+    pub name: String,
+    pub url: String,
+    /// 签名密钥（仅创建时返回明文）
+    #[serde(skip_serializing)]
+    pub secret: String,
+    /// 订阅的事件类型，为空表示全部
+    pub event_types: Vec<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WebhookSubscription {
+    /// 是否订阅了该事件
+    pub fn accepts(&self, event_type: &str) -> bool {
+        self.event_types.is_empty() || self.event_types.iter().any(|t| t == event_type)
+    }
+}
+
+/// 创建订阅请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateWebhookSubscriptionRequest {
+    pub name: String,
+    pub url: String,
+    /// 为空时自动生成
+    pub secret: Option<String>,
+    /// 为空表示订阅全部事件
+    #[serde(default)]
+    pub event_types: Vec<String>,
+}
+
+/// 更新订阅请求
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
+pub struct UpdateWebhookSubscriptionRequest {
+    pub name: Option<String>,
+    pub url: Option<String>,
+    /// 轮换签名密钥
+    pub secret: Option<String>,
+    pub event_types: Option<Vec<String>>,
+    pub is_active: Option<bool>,
+}
+
+/// 投递记录
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub subscription_id: i64,
+    /// 事件 ID（同一事件投递给各订阅时相同，接收方据此去重）
+    pub event_id: i64,
+    /// 幂等键，同一订阅下相同键的事件只投递一次
+    pub event_key: Option<String>,
+    pub event_type: String,
+    /// 完整请求体（含 id / type / created_at / data）
+    pub payload: serde_json::Value,
+    /// pending / succeeded / dead
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_response_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 待投递记录（含订阅端点与密钥）
+#[derive(Debug, Clone, FromRow)]
+pub struct PendingDelivery {
+    pub id: i64,
+    pub event_id: i64,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub url: String,
+    pub secret: String,
+}
+
+/// 投递记录查询参数
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WebhookDeliveryQuery {
+    pub subscription_id: Option<i64>,
+    pub status: Option<String>,
+    pub event_type: Option<String>,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backoff() {
+        assert_eq!(retry_delay_secs(1), Some(30));
+        assert_eq!(retry_delay_secs(2), Some(60));
+        assert_eq!(retry_delay_secs(4), Some(240));
+        assert_eq!(retry_delay_secs(9), Some(RETRY_MAX_SECS));
+        assert_eq!(retry_delay_secs(MAX_DELIVERY_ATTEMPTS), None);
+    }
+}
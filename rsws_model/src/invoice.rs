@@ -0,0 +1,99 @@
+//! 发票模型
+//!
+//! 订单支付完成后开具，字段均为开具时的快照。
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use salvo_oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 发票
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Invoice {
+    pub id: i64,
+    /// 发票号（INV-YYYYMMDD-000001）
+    pub invoice_no: String,
+    pub order_id: i64,
+    pub user_id: Option<i64>,
+    pub buyer_email: String,
+    pub buyer_name: String,
+    /// 商品明细快照，元素结构见 [`InvoiceItem`]
+    pub items: serde_json::Value,
+    /// 订单金额
+    pub subtotal: Decimal,
+    /// 实付金额（按 currency 计）
+    pub paid_amount: Decimal,
+    pub currency: String,
+    pub payment_method: String,
+    /// 支付网关交易号 / 链上交易哈希
+    pub tx_hash: Option<String>,
+    pub issued_at: DateTime<Utc>,
+    pub emailed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Invoice {
+    /// 解析商品明细（格式异常时返回空列表）
+    pub fn line_items(&self) -> Vec<InvoiceItem> {
+        serde_json::from_value(self.items.clone()).unwrap_or_default()
+    }
+}
+
+/// 发票商品明细
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InvoiceItem {
+    pub resource_id: i64,
+    pub title: String,
+    pub quantity: i32,
+    pub unit_price: Decimal,
+    pub amount: Decimal,
+}
+
+/// 发票导出查询参数
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct InvoiceExportQuery {
+    /// 开始日期（含），格式 YYYY-MM-DD
+    pub start_date: String,
+    /// 结束日期（含），格式 YYYY-MM-DD
+    pub end_date: String,
+    /// 导出格式：csv（默认）/ json
+    pub format: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invoice_line_items() {
+        let invoice = Invoice {
+            id: 1,
+            invoice_no: "INV-20260613-000001".to_string(),
+            order_id: 2,
+            user_id: Some(3),
+            buyer_email: "buyer@example.com".to_string(),
+            buyer_name: "buyer".to_string(),
+            items: serde_json::json!([{
+                "resource_id": 4,
+                "title": "Template",
+                "quantity": 1,
+                "unit_price": "9.90",
+                "amount": "9.90",
+            }]),
+            subtotal: Decimal::new(990, 2),
+            paid_amount: Decimal::new(990, 2),
+            currency: "USD".to_string(),
+            payment_method: "paypal".to_string(),
+            tx_hash: None,
+            issued_at: Utc::now(),
+            emailed_at: None,
+            created_at: Utc::now(),
+        };
+
+        let items = invoice.line_items();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].resource_id, 4);
+        assert_eq!(items[0].amount, Decimal::new(990, 2));
+    }
+}
@@ -0,0 +1,314 @@
+//! 复式记账模型
+//!
+//! 记账约定：分录金额借方为正、贷方为负，同一凭证分录合计为 0。
+//! 负债 / 收入类科目为贷方余额，对外展示时取反。
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use salvo_oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// ==================== 科目 ====================
+
+/// 第三方支付 / 链上收款待清算（资产）
+pub const PAYMENT_CLEARING: &str = "payment_clearing";
+/// 平台收入（收入）
+pub const PLATFORM_REVENUE: &str = "platform_revenue";
+/// 支付通道手续费（费用）
+pub const FEES: &str = "fees";
+
+const USER_WALLET_PREFIX: &str = "user_wallet:";
+const CREATOR_PAYABLE_PREFIX: &str = "creator_payable:";
+
+/// 用户预付余额科目（负债）
+pub fn user_wallet_account(user_id: i64) -> String {
+    format!("{}{}", USER_WALLET_PREFIX, user_id)
+}
+
+/// 应付创作者分成科目（负债）
+pub fn creator_payable_account(user_id: i64) -> String {
+    format!("{}{}", CREATOR_PAYABLE_PREFIX, user_id)
+}
+
+/// 科目类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountType {
+    Asset,
+    Liability,
+    Revenue,
+    Expense,
+}
+
+impl AccountType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountType::Asset => "asset",
+            AccountType::Liability => "liability",
+            AccountType::Revenue => "revenue",
+            AccountType::Expense => "expense",
+        }
+    }
+
+    /// 是否为贷方余额科目（负债 / 收入）
+    pub fn is_credit_normal(&self) -> bool {
+        matches!(self, AccountType::Liability | AccountType::Revenue)
+    }
+
+    /// 根据科目代码推断科目类型与所属用户，未知科目返回 None
+    pub fn from_code(code: &str) -> Option<(Self, Option<i64>)> {
+        match code {
+            PAYMENT_CLEARING => Some((AccountType::Asset, None)),
+            PLATFORM_REVENUE => Some((AccountType::Revenue, None)),
+            FEES => Some((AccountType::Expense, None)),
+            _ => {
+                let owner = code
+                    .strip_prefix(USER_WALLET_PREFIX)
+                    .or_else(|| code.strip_prefix(CREATOR_PAYABLE_PREFIX))?
+                    .parse::<i64>()
+                    .ok()?;
+                Some((AccountType::Liability, Some(owner)))
+            }
+        }
+    }
+}
+
+/// 科目
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LedgerAccount {
+    pub id: i64,
+    pub code: String,
+    pub account_type: String,
+    pub owner_id: Option<i64>,
+    /// 借方余额（借正贷负）
+    pub balance: Decimal,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// ==================== 凭证 ====================
+
+/// 待记账分录
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewPosting {
+    pub account_code: String,
+    pub amount: Decimal,
+}
+
+impl NewPosting {
+    /// 借记
+    pub fn debit(account_code: impl Into<String>, amount: Decimal) -> Self {
+        Self {
+            account_code: account_code.into(),
+            amount,
+        }
+    }
+
+    /// 贷记
+    pub fn credit(account_code: impl Into<String>, amount: Decimal) -> Self {
+        Self {
+            account_code: account_code.into(),
+            amount: -amount,
+        }
+    }
+}
+
+/// 待记账凭证
+#[derive(Debug, Clone)]
+pub struct NewJournalEntry {
+    pub entry_type: String,
+    pub reference_type: Option<String>,
+    pub reference_id: Option<i64>,
+    /// 幂等键：同一业务事件只记账一次
+    pub idempotency_key: String,
+    pub description: Option<String>,
+    pub postings: Vec<NewPosting>,
+}
+
+impl NewJournalEntry {
+    /// 去掉金额为 0 的分录后校验借贷平衡
+    pub fn normalized(mut self) -> Result<Self, String> {
+        self.postings.retain(|p| !p.amount.is_zero());
+        if self.postings.len() < 2 {
+            return Err(format!(
+                "Journal entry {} needs at least two non-zero postings",
+                self.idempotency_key
+            ));
+        }
+        let sum: Decimal = self.postings.iter().map(|p| p.amount).sum();
+        if !sum.is_zero() {
+            return Err(format!(
+                "Journal entry {} is not balanced (sum = {})",
+                self.idempotency_key, sum
+            ));
+        }
+        for p in &self.postings {
+            if AccountType::from_code(&p.account_code).is_none() {
+                return Err(format!("Unknown ledger account: {}", p.account_code));
+            }
+        }
+        Ok(self)
+    }
+}
+
+/// 账户流水（按科目自然方向展示金额，增加为正）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AccountStatementLine {
+    pub entry_id: i64,
+    pub entry_type: String,
+    pub reference_type: Option<String>,
+    pub reference_id: Option<i64>,
+    pub description: Option<String>,
+    pub amount: Decimal,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 试算平衡表行
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TrialBalanceRow {
+    pub code: String,
+    pub account_type: String,
+    pub debit: Decimal,
+    pub credit: Decimal,
+}
+
+/// 试算平衡表
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TrialBalance {
+    pub rows: Vec<TrialBalanceRow>,
+    pub total_debit: Decimal,
+    pub total_credit: Decimal,
+    pub balanced: bool,
+}
+
+impl TrialBalance {
+    pub fn from_rows(rows: Vec<TrialBalanceRow>) -> Self {
+        let total_debit: Decimal = rows.iter().map(|r| r.debit).sum();
+        let total_credit: Decimal = rows.iter().map(|r| r.credit).sum();
+        Self {
+            rows,
+            total_debit,
+            total_credit,
+            balanced: total_debit == total_credit,
+        }
+    }
+}
+
+// ==================== 余额充值 ====================
+
+/// 余额充值单
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WalletTopup {
+    pub id: i64,
+    pub user_id: i64,
+    pub amount: Decimal,
+    pub payment_method: String,
+    /// USDT 收款地址（链上到账按地址与金额匹配）
+    pub receive_address: Option<String>,
+    pub status: String,
+    pub provider_tx_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub expired_at: Option<DateTime<Utc>>,
+}
+
+/// 创建充值请求
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct CreateTopupRequest {
+    pub amount: Decimal,
+    /// paypal / alipay / wechatpay / usdt_trc20 / usdt_erc20
+    pub payment_method: String,
+    /// 支付宝模式：page（默认）/ qr
+    pub mode: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(postings: Vec<NewPosting>) -> NewJournalEntry {
+        NewJournalEntry {
+            entry_type: "test".to_string(),
+            reference_type: None,
+            reference_id: None,
+            idempotency_key: "test:1".to_string(),
+            description: None,
+            postings,
+        }
+    }
+
+    #[test]
+    fn test_account_type_from_code() {
+        assert_eq!(
+            AccountType::from_code(PAYMENT_CLEARING),
+            Some((AccountType::Asset, None))
+        );
+        assert_eq!(
+            AccountType::from_code(&user_wallet_account(42)),
+            Some((AccountType::Liability, Some(42)))
+        );
+        assert_eq!(
+            AccountType::from_code(&creator_payable_account(7)),
+            Some((AccountType::Liability, Some(7)))
+        );
+        assert_eq!(AccountType::from_code("user_wallet:abc"), None);
+        assert_eq!(AccountType::from_code("cash"), None);
+    }
+
+    #[test]
+    fn test_journal_entry_balanced() {
+        let e = entry(vec![
+            NewPosting::debit(PAYMENT_CLEARING, Decimal::new(1000, 2)),
+            NewPosting::credit(PLATFORM_REVENUE, Decimal::new(800, 2)),
+            NewPosting::credit(creator_payable_account(7), Decimal::new(200, 2)),
+            NewPosting::credit(FEES, Decimal::ZERO),
+        ])
+        .normalized()
+        .unwrap();
+        assert_eq!(e.postings.len(), 3);
+    }
+
+    #[test]
+    fn test_journal_entry_rejects_unbalanced() {
+        assert!(entry(vec![
+            NewPosting::debit(PAYMENT_CLEARING, Decimal::new(1000, 2)),
+            NewPosting::credit(PLATFORM_REVENUE, Decimal::new(900, 2)),
+        ])
+        .normalized()
+        .is_err());
+
+        assert!(
+            entry(vec![NewPosting::debit(PAYMENT_CLEARING, Decimal::ZERO)])
+                .normalized()
+                .is_err()
+        );
+
+        assert!(entry(vec![
+            NewPosting::debit("cash", Decimal::ONE),
+            NewPosting::credit(PLATFORM_REVENUE, Decimal::ONE),
+        ])
+        .normalized()
+        .is_err());
+    }
+
+    #[test]
+    fn test_trial_balance_totals() {
+        let tb = TrialBalance::from_rows(vec![
+            TrialBalanceRow {
+                code: PAYMENT_CLEARING.to_string(),
+                account_type: "asset".to_string(),
+                debit: Decimal::new(1000, 2),
+                credit: Decimal::ZERO,
+            },
+            TrialBalanceRow {
+                code: PLATFORM_REVENUE.to_string(),
+                account_type: "revenue".to_string(),
+                debit: Decimal::ZERO,
+                credit: Decimal::new(1000, 2),
+            },
+        ]);
+        assert!(tb.balanced);
+        assert_eq!(tb.total_debit, Decimal::new(1000, 2));
+    }
+}
@@ -4,10 +4,28 @@
 
 pub mod api_key;
 pub mod auth;
+pub mod commission;
 pub mod config;
+pub mod creator;
+pub mod download;
+pub mod event_webhook;
+pub mod invoice;
+pub mod ledger;
+pub mod license;
 pub mod log;
+pub mod membership;
+pub mod moderation;
 pub mod payment;
+pub mod preview;
+pub mod rating;
+pub mod recommendation;
 pub mod request;
 pub mod resource;
+pub mod resource_import;
+pub mod resource_version;
 pub mod response;
+pub mod risk;
+pub mod search;
+pub mod tag;
 pub mod user_models;
+pub mod wishlist;
@@ -0,0 +1,183 @@
+//! 资源授权档位模型
+//!
+//! 同一资源可按不同授权条款出售（个人 / 商用 / 扩展），每个档位有独立的价格、
+//! 条款文本和下载次数。订单记录所购档位，升级时只需支付差价。
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use salvo_oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 授权档位常量（按等级从低到高）
+pub const LICENSE_PERSONAL: &str = "personal";
+pub const LICENSE_COMMERCIAL: &str = "commercial";
+pub const LICENSE_EXTENDED: &str = "extended";
+
+/// 全部授权档位（按等级从低到高）
+pub const LICENSE_TIERS: [&str; 3] = [LICENSE_PERSONAL, LICENSE_COMMERCIAL, LICENSE_EXTENDED];
+
+/// 档位等级，未知档位返回 None
+pub fn tier_rank(tier: &str) -> Option<usize> {
+    LICENSE_TIERS.iter().position(|t| *t == tier)
+}
+
+/// 资源授权档位
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ResourceLicense {
+    pub id: i64,
+    pub resource_id: i64,
+    /// personal / commercial / extended
+    pub tier: String,
+    pub name: String,
+    pub price: Decimal,
+    /// 授权条款
+    pub terms: Option<String>,
+    /// 每笔订单可下载次数，为空表示不限
+    pub max_downloads: Option<i32>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 创建授权档位请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateResourceLicenseRequest {
+    pub tier: String,
+    /// 为空时使用档位名
+    pub name: Option<String>,
+    pub price: Decimal,
+    pub terms: Option<String>,
+    pub max_downloads: Option<i32>,
+}
+
+/// 更新授权档位请求（档位类型不可修改）
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
+pub struct UpdateResourceLicenseRequest {
+    pub name: Option<String>,
+    pub price: Option<Decimal>,
+    pub terms: Option<String>,
+    pub max_downloads: Option<i32>,
+    pub is_active: Option<bool>,
+}
+
+/// 用户已支付的资源订单及其授权档位
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LicensedOrder {
+    pub order_id: i64,
+    /// 授权档位上线前的订单为空，视为最低档位
+    pub license_id: Option<i64>,
+    pub tier: Option<String>,
+    pub license_price: Option<Decimal>,
+    pub amount: Decimal,
+    pub download_count: i32,
+    pub max_downloads: Option<i32>,
+}
+
+impl LicensedOrder {
+    /// 档位等级（无档位的旧订单按最低档位计）
+    pub fn rank(&self) -> usize {
+        self.tier.as_deref().and_then(tier_rank).unwrap_or(0)
+    }
+
+    /// 升级时可抵扣的金额：档位当前价格，旧订单按实付金额
+    pub fn credit(&self) -> Decimal {
+        self.license_price.unwrap_or(self.amount)
+    }
+}
+
+/// 从用户已支付订单中选出当前持有的最高档位
+pub fn owned_license(orders: &[LicensedOrder]) -> Option<&LicensedOrder> {
+    orders
+        .iter()
+        .max_by(|a, b| a.rank().cmp(&b.rank()).then(a.credit().cmp(&b.credit())))
+}
+
+/// 计算购买目标档位应付金额
+///
+/// 未持有时为档位全价；持有更低档位时为差价；已持有同级或更高档位，
+/// 或差价不为正时返回 None。
+pub fn upgrade_price(target: &ResourceLicense, owned: Option<&LicensedOrder>) -> Option<Decimal> {
+    let Some(owned) = owned else {
+        return Some(target.price);
+    };
+    if tier_rank(&target.tier)? <= owned.rank() {
+        return None;
+    }
+    let diff = target.price - owned.credit();
+    (diff > Decimal::ZERO).then_some(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn license(tier: &str, price: i64) -> ResourceLicense {
+        ResourceLicense {
+            id: tier_rank(tier).unwrap() as i64 + 1,
+            resource_id: 1,
+            tier: tier.to_string(),
+            name: tier.to_string(),
+            price: Decimal::new(price, 0),
+            terms: None,
+            max_downloads: None,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn order(tier: Option<&str>, license_price: Option<i64>, amount: i64) -> LicensedOrder {
+        LicensedOrder {
+            order_id: 1,
+            license_id: tier.map(|t| tier_rank(t).unwrap() as i64 + 1),
+            tier: tier.map(str::to_string),
+            license_price: license_price.map(|p| Decimal::new(p, 0)),
+            amount: Decimal::new(amount, 0),
+            download_count: 0,
+            max_downloads: None,
+        }
+    }
+
+    #[test]
+    fn test_upgrade_price() {
+        let commercial = license(LICENSE_COMMERCIAL, 30);
+
+        assert_eq!(upgrade_price(&commercial, None), Some(Decimal::new(30, 0)));
+
+        let personal = order(Some(LICENSE_PERSONAL), Some(10), 10);
+        assert_eq!(
+            upgrade_price(&commercial, Some(&personal)),
+            Some(Decimal::new(20, 0))
+        );
+
+        // 已持有同级或更高档位
+        let owned = order(Some(LICENSE_COMMERCIAL), Some(30), 20);
+        assert_eq!(upgrade_price(&commercial, Some(&owned)), None);
+        assert_eq!(
+            upgrade_price(&license(LICENSE_PERSONAL, 10), Some(&owned)),
+            None
+        );
+
+        // 旧订单按实付金额抵扣
+        let legacy = order(None, None, 12);
+        assert_eq!(
+            upgrade_price(&commercial, Some(&legacy)),
+            Some(Decimal::new(18, 0))
+        );
+    }
+
+    #[test]
+    fn test_owned_license_picks_highest_tier() {
+        let orders = vec![
+            order(None, None, 10),
+            order(Some(LICENSE_EXTENDED), Some(50), 20),
+            order(Some(LICENSE_COMMERCIAL), Some(30), 30),
+        ];
+        assert_eq!(
+            owned_license(&orders).and_then(|o| o.tier.as_deref()),
+            Some(LICENSE_EXTENDED)
+        );
+        assert!(owned_license(&[]).is_none());
+    }
+}
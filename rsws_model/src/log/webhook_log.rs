@@ -2,13 +2,38 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+/// 已落库，尚未处理完成
+pub const WEBHOOK_STATUS_RECEIVED: &str = "received";
+/// 处理成功
+pub const WEBHOOK_STATUS_PROCESSED: &str = "processed";
+/// 已应答但无需处理（未知事件、找不到订单等）
+pub const WEBHOOK_STATUS_IGNORED: &str = "ignored";
+/// 处理失败，网关重试或管理员重放时重新处理
+pub const WEBHOOK_STATUS_FAILED: &str = "failed";
+
+/// 处理超时（秒）：超过该时间仍为 received 的记录视为处理中断（如进程崩溃），网关重试时重新处理
+pub const WEBHOOK_PROCESSING_TIMEOUT_SECS: i64 = 300;
+
+/// 入站回调落库结果
+#[derive(Debug, Clone)]
+pub enum WebhookRecord {
+    /// 新记录，或上次处理失败 / 中断需重新处理的记录
+    Process(WebhookLog),
+    /// 重复投递（已处理完成），直接应答成功
+    Duplicate,
+    /// 同一事件正在处理中，应答失败让网关稍后重试
+    InProgress,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct WebhookLog {
     pub id: i64,
     pub webhook_type: String,
     pub source: String,
+    pub event_id: Option<String>,
     pub event_type: String,
     pub payload: serde_json::Value,
+    pub body: Option<String>,
     pub headers: Option<serde_json::Value>,
     pub signature: Option<String>,
     pub status: String,
@@ -24,8 +49,10 @@ pub struct WebhookLog {
 pub struct CreateWebhookLogRequest {
     pub webhook_type: String,
     pub source: String,
+    pub event_id: Option<String>,
     pub event_type: String,
     pub payload: serde_json::Value,
+    pub body: Option<String>,
     pub headers: Option<serde_json::Value>,
     pub signature: Option<String>,
     pub ip_address: Option<String>,
@@ -38,3 +65,14 @@ pub struct UpdateWebhookLogRequest {
     pub response_message: Option<String>,
     pub retry_count: Option<i32>,
 }
+
+/// 管理员 Webhook 日志查询参数
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WebhookLogQuery {
+    pub source: Option<String>,
+    pub status: Option<String>,
+    pub event_type: Option<String>,
+    pub event_id: Option<String>,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
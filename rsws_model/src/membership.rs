@@ -0,0 +1,174 @@
+//! 会员模型
+//!
+//! 会员套餐按分类授权：订阅有效期内，套餐分类及其所有子分类下的资源视为已购买。
+//! 续费即再次下单，新周期从当前有效期结束时开始顺延。
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use salvo_oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 会员订阅状态：待支付
+pub const MEMBERSHIP_PENDING: &str = "pending";
+/// 会员订阅状态：已生效
+pub const MEMBERSHIP_ACTIVE: &str = "active";
+
+/// 会员套餐
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct MembershipPlan {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    /// 授权分类（含所有子分类）
+    pub category_id: i64,
+    /// 每期天数
+    pub duration_days: i32,
+    pub price: Decimal,
+    pub is_active: bool,
+    pub sort_order: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 用户会员订阅（每笔订单一期）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct UserMembership {
+    pub id: i64,
+    pub user_id: i64,
+    pub plan_id: i64,
+    /// 下单时套餐授权分类快照
+    pub category_id: i64,
+    pub order_id: i64,
+    /// pending / active
+    pub status: String,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub reminded_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UserMembership {
+    /// 指定时刻是否在有效期内
+    pub fn is_active_at(&self, now: DateTime<Utc>) -> bool {
+        self.status == MEMBERSHIP_ACTIVE
+            && matches!((self.starts_at, self.expires_at), (Some(s), Some(e)) if s <= now && now < e)
+    }
+}
+
+/// 用户会员订阅详情（含套餐名称）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct UserMembershipDetail {
+    pub id: i64,
+    pub plan_id: i64,
+    pub plan_name: String,
+    pub category_id: i64,
+    pub order_id: i64,
+    pub status: String,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 即将到期提醒
+#[derive(Debug, Clone, FromRow)]
+pub struct MembershipExpiryReminder {
+    pub membership_id: i64,
+    pub user_id: i64,
+    pub email: String,
+    pub plan_name: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 创建会员套餐请求
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct CreateMembershipPlanRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub category_id: i64,
+    pub duration_days: i32,
+    pub price: Decimal,
+    pub sort_order: Option<i32>,
+}
+
+/// 更新会员套餐请求
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct UpdateMembershipPlanRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub duration_days: Option<i32>,
+    pub price: Option<Decimal>,
+    pub is_active: Option<bool>,
+    pub sort_order: Option<i32>,
+}
+
+/// 订阅 / 续费会员请求
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct SubscribeMembershipRequest {
+    pub plan_id: i64,
+    /// paypal / alipay / wechatpay / balance
+    pub payment_method: String,
+}
+
+/// 计算新一期的起止时间：当前有效期未结束时从结束时刻顺延，否则从 `now` 开始
+pub fn next_period(
+    now: DateTime<Utc>,
+    current_expires_at: Option<DateTime<Utc>>,
+    duration_days: i32,
+) -> (DateTime<Utc>, DateTime<Utc>) {
+    let starts_at = match current_expires_at {
+        Some(e) if e > now => e,
+        _ => now,
+    };
+    (starts_at, starts_at + Duration::days(duration_days as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_period_starts_now_without_active_membership() {
+        let now = Utc::now();
+        let (s, e) = next_period(now, None, 30);
+        assert_eq!(s, now);
+        assert_eq!(e, now + Duration::days(30));
+
+        let expired = now - Duration::days(1);
+        let (s, _) = next_period(now, Some(expired), 30);
+        assert_eq!(s, now);
+    }
+
+    #[test]
+    fn test_next_period_extends_from_current_expiry() {
+        let now = Utc::now();
+        let current = now + Duration::days(5);
+        let (s, e) = next_period(now, Some(current), 30);
+        assert_eq!(s, current);
+        assert_eq!(e, current + Duration::days(30));
+    }
+
+    #[test]
+    fn test_is_active_at() {
+        let now = Utc::now();
+        let mut m = UserMembership {
+            id: 1,
+            user_id: 1,
+            plan_id: 1,
+            category_id: 1,
+            order_id: 1,
+            status: MEMBERSHIP_ACTIVE.to_string(),
+            starts_at: Some(now - Duration::days(1)),
+            expires_at: Some(now + Duration::days(1)),
+            reminded_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+        assert!(m.is_active_at(now));
+        assert!(!m.is_active_at(now + Duration::days(2)));
+
+        m.status = MEMBERSHIP_PENDING.to_string();
+        assert!(!m.is_active_at(now));
+    }
+}
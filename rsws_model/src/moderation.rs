@@ -0,0 +1,177 @@
+//! 资源审核模型
+//!
+//! 用户上传的资源：draft（草稿）→ pending（待审核）→ approved（已通过）/ rejected（已拒绝），
+//! 已通过的资源可被管理员下架（taken_down）。只有 approved 且上架的资源公开展示和售卖。
+//! 创作者修改已提交的资源后重新进入 pending。
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use salvo_oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 审核状态
+pub const REVIEW_STATUS_DRAFT: &str = "draft";
+pub const REVIEW_STATUS_PENDING: &str = "pending";
+pub const REVIEW_STATUS_APPROVED: &str = "approved";
+pub const REVIEW_STATUS_REJECTED: &str = "rejected";
+pub const REVIEW_STATUS_TAKEN_DOWN: &str = "taken_down";
+
+/// 全部审核状态
+pub const REVIEW_STATUSES: [&str; 5] = [
+    REVIEW_STATUS_DRAFT,
+    REVIEW_STATUS_PENDING,
+    REVIEW_STATUS_APPROVED,
+    REVIEW_STATUS_REJECTED,
+    REVIEW_STATUS_TAKEN_DOWN,
+];
+
+/// 审核动作
+pub const REVIEW_ACTION_SUBMIT: &str = "submit";
+pub const REVIEW_ACTION_APPROVE: &str = "approve";
+pub const REVIEW_ACTION_REJECT: &str = "reject";
+pub const REVIEW_ACTION_TAKE_DOWN: &str = "take_down";
+
+/// 操作人类型
+pub const ACTOR_TYPE_USER: &str = "user";
+pub const ACTOR_TYPE_ADMIN: &str = "admin";
+
+/// 审核动作后的状态，不允许的动作返回 None
+///
+/// - submit：草稿、被拒绝、被下架的资源提交审核
+/// - approve：待审核、被拒绝、被下架的资源审核通过
+/// - reject：待审核的资源被拒绝
+/// - take_down：已通过或待审核的资源下架
+pub fn next_review_status(current: &str, action: &str) -> Option<&'static str> {
+    match (action, current) {
+        (
+            REVIEW_ACTION_SUBMIT,
+            REVIEW_STATUS_DRAFT | REVIEW_STATUS_REJECTED | REVIEW_STATUS_TAKEN_DOWN,
+        ) => Some(REVIEW_STATUS_PENDING),
+        (
+            REVIEW_ACTION_APPROVE,
+            REVIEW_STATUS_PENDING | REVIEW_STATUS_REJECTED | REVIEW_STATUS_TAKEN_DOWN,
+        ) => Some(REVIEW_STATUS_APPROVED),
+        (REVIEW_ACTION_REJECT, REVIEW_STATUS_PENDING) => Some(REVIEW_STATUS_REJECTED),
+        (REVIEW_ACTION_TAKE_DOWN, REVIEW_STATUS_APPROVED | REVIEW_STATUS_PENDING) => {
+            Some(REVIEW_STATUS_TAKEN_DOWN)
+        }
+        _ => None,
+    }
+}
+
+/// 创作者修改资源后的审核状态：草稿保持草稿，其余重新进入待审核
+pub fn review_status_after_edit(current: &str) -> &'static str {
+    if current == REVIEW_STATUS_DRAFT {
+        REVIEW_STATUS_DRAFT
+    } else {
+        REVIEW_STATUS_PENDING
+    }
+}
+
+/// 审核记录
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ResourceReview {
+    pub id: i64,
+    pub resource_id: i64,
+    /// submit / approve / reject / take_down
+    pub action: String,
+    pub from_status: String,
+    pub to_status: String,
+    pub reason: Option<String>,
+    pub actor_id: Option<i64>,
+    /// user / admin
+    pub actor_type: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 审核队列条目
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ModerationQueueItem {
+    pub resource_id: i64,
+    pub title: String,
+    pub description: Option<String>,
+    pub price: Decimal,
+    pub category_id: Option<i64>,
+    pub file_url: Option<String>,
+    pub thumbnail_url: Option<String>,
+    pub provider_id: Option<i64>,
+    pub provider_name: Option<String>,
+    pub review_status: String,
+    pub review_reason: Option<String>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 管理员审核请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ModerateResourceRequest {
+    /// approve / reject / take_down
+    pub action: String,
+    /// 拒绝和下架时必填，会随邮件通知创作者
+    pub reason: Option<String>,
+}
+
+/// 创作者联系方式（审核结果通知）
+#[derive(Debug, Clone, FromRow)]
+pub struct CreatorContact {
+    pub user_id: i64,
+    pub email: String,
+    pub username: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_review_status() {
+        assert_eq!(
+            next_review_status(REVIEW_STATUS_DRAFT, REVIEW_ACTION_SUBMIT),
+            Some(REVIEW_STATUS_PENDING)
+        );
+        assert_eq!(
+            next_review_status(REVIEW_STATUS_PENDING, REVIEW_ACTION_SUBMIT),
+            None
+        );
+        assert_eq!(
+            next_review_status(REVIEW_STATUS_PENDING, REVIEW_ACTION_APPROVE),
+            Some(REVIEW_STATUS_APPROVED)
+        );
+        assert_eq!(
+            next_review_status(REVIEW_STATUS_DRAFT, REVIEW_ACTION_APPROVE),
+            None
+        );
+        assert_eq!(
+            next_review_status(REVIEW_STATUS_APPROVED, REVIEW_ACTION_REJECT),
+            None
+        );
+        assert_eq!(
+            next_review_status(REVIEW_STATUS_APPROVED, REVIEW_ACTION_TAKE_DOWN),
+            Some(REVIEW_STATUS_TAKEN_DOWN)
+        );
+        assert_eq!(
+            next_review_status(REVIEW_STATUS_TAKEN_DOWN, REVIEW_ACTION_APPROVE),
+            Some(REVIEW_STATUS_APPROVED)
+        );
+        assert_eq!(next_review_status(REVIEW_STATUS_PENDING, "publish"), None);
+    }
+
+    #[test]
+    fn test_review_status_after_edit() {
+        assert_eq!(
+            review_status_after_edit(REVIEW_STATUS_DRAFT),
+            REVIEW_STATUS_DRAFT
+        );
+        assert_eq!(
+            review_status_after_edit(REVIEW_STATUS_APPROVED),
+            REVIEW_STATUS_PENDING
+        );
+        assert_eq!(
+            review_status_after_edit(REVIEW_STATUS_REJECTED),
+            REVIEW_STATUS_PENDING
+        );
+    }
+}
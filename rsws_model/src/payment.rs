@@ -26,13 +26,24 @@ pub enum OrderStatus {
 pub struct Order {
     pub id: i64,
     pub user_id: i64,
-    pub resource_id: i64,
+    /// 会员订单为空
+    pub resource_id: Option<i64>,
     pub amount: Decimal,
     pub status: String,
     pub payment_method: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub expired_at: Option<DateTime<Utc>>,
+    /// 所购授权档位（资源未设置档位或会员订单为空）
+    pub license_id: Option<i64>,
+    /// 买家承担的通道手续费（已含在 amount 中）
+    pub surcharge: Decimal,
+    /// 下单时按通道费率估算的手续费
+    pub fee_amount: Decimal,
+    /// 最近一次风控评估分数
+    pub risk_score: i32,
+    /// 风控状态：clear / review / approved / rejected
+    pub risk_status: String,
 }
 
 /// 订单详情（包含资源信息）
@@ -40,14 +51,15 @@ pub struct Order {
 pub struct OrderDetail {
     pub id: i64,
     pub user_id: i64,
-    pub resource_id: i64,
+    /// 会员订单为空
+    pub resource_id: Option<i64>,
     pub amount: Decimal,
     pub status: String,
     pub payment_method: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub expired_at: Option<DateTime<Utc>>,
-    /// 资源标题
+    /// 资源标题（会员订单为套餐名称）
     pub resource_title: Option<String>,
 }
 
@@ -65,7 +77,8 @@ pub struct AdminOrderDetail {
     pub user_id: i64,
     pub user_name: Option<String>,
     pub user_email: Option<String>,
-    pub resource_id: i64,
+    /// 会员订单为空
+    pub resource_id: Option<i64>,
     pub resource_title: Option<String>,
     pub amount: Decimal,
     pub status: String,
@@ -75,6 +88,53 @@ pub struct AdminOrderDetail {
     pub expired_at: Option<DateTime<Utc>>,
 }
 
+// ==================== 支付报价 ====================
+
+/// 手续费承担方常量（system_configs.payment_fee_bearer）
+pub const FEE_BEARER_PLATFORM: &str = "platform";
+pub const FEE_BEARER_BUYER: &str = "buyer";
+
+/// 支付通道费率与限额（来自各通道配置表）
+#[derive(Debug, Clone)]
+pub struct PaymentFeeSchedule {
+    pub fee_rate: Decimal,
+    /// 限额以通道结算币种计
+    pub min_amount: Decimal,
+    pub max_amount: Decimal,
+    /// 订单金额 → 结算币种的换算比例
+    pub exchange_rate: Decimal,
+}
+
+/// 支付报价（下单前展示给买家的费用明细）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PaymentQuote {
+    pub payment_method: String,
+    /// 商品金额
+    pub subtotal: Decimal,
+    /// 通道手续费
+    pub fee: Decimal,
+    /// platform / buyer
+    pub fee_bearer: String,
+    /// 买家承担的手续费
+    pub surcharge: Decimal,
+    /// 买家应付金额（subtotal + surcharge）
+    pub total: Decimal,
+    /// 通道结算金额（按通道币种换算）
+    pub settlement_amount: Decimal,
+}
+
+/// USDT 转账金额与订单金额的匹配误差（订单金额的 1%）
+pub const USDT_AMOUNT_TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
+/// USDT 超额支付金额：转账超出订单金额误差范围的部分，误差范围内视为足额支付
+pub fn usdt_overpayment(paid: Decimal, order_amount: Decimal) -> Decimal {
+    if paid > order_amount + order_amount * USDT_AMOUNT_TOLERANCE {
+        paid - order_amount
+    } else {
+        Decimal::ZERO
+    }
+}
+
 // ==================== 支付交易 ====================
 
 /// 交易状态
@@ -236,6 +296,118 @@ pub struct CreatePayPalConfigRequest {
     pub is_active: bool,
 }
 
+// ==================== 支付宝配置 ====================
+
+/// 支付宝配置
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AlipayConfig {
+    pub id: i64,
+    pub app_id: String,
+    pub private_key: String,
+    pub alipay_public_key: String,
+    pub sandbox: bool,
+    pub notify_url: String,
+    pub return_url: String,
+    pub exchange_rate: Decimal,
+    pub min_amount: Decimal,
+    pub max_amount: Decimal,
+    pub fee_rate: Decimal,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 支付宝配置创建请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateAlipayConfigRequest {
+    pub app_id: String,
+    pub private_key: String,
+    pub alipay_public_key: String,
+    pub sandbox: bool,
+    pub notify_url: String,
+    pub return_url: String,
+    pub exchange_rate: Decimal,
+    pub min_amount: Decimal,
+    pub max_amount: Decimal,
+    pub fee_rate: Decimal,
+    pub is_active: bool,
+}
+
+/// 支付宝配置更新请求（管理员用）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateAlipayConfigRequest {
+    pub app_id: Option<String>,
+    pub private_key: Option<String>,
+    pub alipay_public_key: Option<String>,
+    pub sandbox: Option<bool>,
+    pub notify_url: Option<String>,
+    pub return_url: Option<String>,
+    pub exchange_rate: Option<Decimal>,
+    pub min_amount: Option<Decimal>,
+    pub max_amount: Option<Decimal>,
+    pub fee_rate: Option<Decimal>,
+    pub is_active: Option<bool>,
+}
+
+// ==================== 微信支付配置 ====================
+
+/// 微信支付配置（API v3）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WechatPayConfig {
+    pub id: i64,
+    pub mch_id: String,
+    pub app_id: String,
+    pub merchant_serial_no: String,
+    pub merchant_private_key: String,
+    pub api_v3_key: String,
+    pub platform_public_key: String,
+    pub platform_public_key_id: Option<String>,
+    pub notify_url: String,
+    pub exchange_rate: Decimal,
+    pub min_amount: Decimal,
+    pub max_amount: Decimal,
+    pub fee_rate: Decimal,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 微信支付配置创建请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateWechatPayConfigRequest {
+    pub mch_id: String,
+    pub app_id: String,
+    pub merchant_serial_no: String,
+    pub merchant_private_key: String,
+    pub api_v3_key: String,
+    pub platform_public_key: String,
+    pub platform_public_key_id: Option<String>,
+    pub notify_url: String,
+    pub exchange_rate: Decimal,
+    pub min_amount: Decimal,
+    pub max_amount: Decimal,
+    pub fee_rate: Decimal,
+    pub is_active: bool,
+}
+
+/// 微信支付配置更新请求（管理员用）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateWechatPayConfigRequest {
+    pub mch_id: Option<String>,
+    pub app_id: Option<String>,
+    pub merchant_serial_no: Option<String>,
+    pub merchant_private_key: Option<String>,
+    pub api_v3_key: Option<String>,
+    pub platform_public_key: Option<String>,
+    pub platform_public_key_id: Option<String>,
+    pub notify_url: Option<String>,
+    pub exchange_rate: Option<Decimal>,
+    pub min_amount: Option<Decimal>,
+    pub max_amount: Option<Decimal>,
+    pub fee_rate: Option<Decimal>,
+    pub is_active: Option<bool>,
+}
+
 // ==================== 单元测试 ====================
 
 #[cfg(test)]
@@ -264,4 +436,19 @@ mod tests {
         assert_eq!(req.resource_id, 1);
         assert_eq!(req.payment_method, "paypal");
     }
+
+    #[test]
+    fn test_usdt_overpayment() {
+        let amount = Decimal::from(100);
+        assert_eq!(
+            usdt_overpayment(Decimal::new(10050, 2), amount),
+            Decimal::ZERO
+        );
+        assert_eq!(usdt_overpayment(Decimal::from(101), amount), Decimal::ZERO);
+        assert_eq!(
+            usdt_overpayment(Decimal::from(120), amount),
+            Decimal::from(20)
+        );
+        assert_eq!(usdt_overpayment(Decimal::from(90), amount), Decimal::ZERO);
+    }
 }
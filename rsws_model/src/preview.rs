@@ -0,0 +1,378 @@
+//! 付费资源预览策略
+//!
+//! 未购买的用户只能看到 `detail_description`、`usage_guide`、`precautions` 的预览部分，
+//! 以及前若干张 `display_images`。预览方式按资源配置：
+//!
+//! - `percent`：按字符比例截取，只在块边界（空行）处截断，代码块不拆开
+//! - `marker`：截取到 `<!--more-->` 标记之前，没有标记时按比例截取
+//! - `sections`：只保留指定标题（Markdown `#` 标题）下的章节
+//!
+//! 截取结果会补齐未闭合的 HTML 标签。未配置策略的资源按 25% 截取，图片全部可见。
+
+use chrono::{DateTime, Utc};
+use salvo_oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 预览方式常量
+pub const PREVIEW_MODE_PERCENT: &str = "percent";
+pub const PREVIEW_MODE_MARKER: &str = "marker";
+pub const PREVIEW_MODE_SECTIONS: &str = "sections";
+
+/// 预览截止标记
+pub const PREVIEW_MARKER: &str = "<!--more-->";
+
+/// 默认预览比例
+pub const DEFAULT_PREVIEW_PERCENT: i16 = 25;
+
+/// 最多免费章节数
+pub const MAX_FREE_SECTIONS: usize = 20;
+
+/// 截断后追加的省略标记
+const ELLIPSIS: &str = "\n\n...";
+
+/// HTML 空元素（无闭合标签）
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// 资源预览策略
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct PreviewPolicy {
+    pub resource_id: i64,
+    /// percent / marker / sections
+    pub mode: String,
+    /// 按比例截取时的百分比（0-100），marker 模式无标记时同样使用
+    pub percent: i16,
+    /// sections 模式下免费的章节标题（不区分大小写）
+    pub free_sections: Vec<String>,
+    /// 免费展示的图片数量，为空表示全部免费
+    pub free_images: Option<i32>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PreviewPolicy {
+    /// 未配置时的默认策略：按 25% 截取，图片全部可见
+    pub fn default_for(resource_id: i64) -> Self {
+        Self {
+            resource_id,
+            mode: PREVIEW_MODE_PERCENT.to_string(),
+            percent: DEFAULT_PREVIEW_PERCENT,
+            free_sections: Vec::new(),
+            free_images: None,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// 设置预览策略请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SetPreviewPolicyRequest {
+    /// percent / marker / sections
+    pub mode: String,
+    pub percent: Option<i16>,
+    pub free_sections: Option<Vec<String>>,
+    pub free_images: Option<i32>,
+}
+
+/// 按策略生成文本预览；未截断时原样返回
+pub fn preview_text(text: &str, policy: &PreviewPolicy) -> String {
+    let preview = match policy.mode.as_str() {
+        PREVIEW_MODE_MARKER => match text.find(PREVIEW_MARKER) {
+            Some(pos) => Some(text[..pos].trim_end()),
+            None => cut_by_percent(text, policy.percent),
+        }
+        .map(str::to_string),
+        PREVIEW_MODE_SECTIONS => Some(free_sections(text, &policy.free_sections)),
+        _ => cut_by_percent(text, policy.percent).map(str::to_string),
+    };
+
+    match preview {
+        Some(preview) if preview.len() < text.trim_end().len() => {
+            let mut preview = close_open_tags(&preview);
+            preview.push_str(ELLIPSIS);
+            preview
+        }
+        _ => text.to_string(),
+    }
+}
+
+/// 免费图片：保留前 `free_images` 张，非数组或未限制时原样返回
+pub fn preview_images(
+    images: Option<serde_json::Value>,
+    free_images: Option<i32>,
+) -> Option<serde_json::Value> {
+    match (images, free_images) {
+        (Some(serde_json::Value::Array(list)), Some(n)) => Some(serde_json::Value::Array(
+            list.into_iter().take(n.max(0) as usize).collect(),
+        )),
+        (images, _) => images,
+    }
+}
+
+/// 文本块的字节区间：按空行切分，围栏代码块（``` / ~~~）整体为一块
+fn blocks(text: &str) -> Vec<(usize, usize)> {
+    let mut blocks = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut end = 0;
+    let mut fence: Option<&str> = None;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        let trimmed = line.trim();
+
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            end = line_start + line.trim_end().len();
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            if let Some(s) = start.take() {
+                blocks.push((s, end));
+            }
+            continue;
+        }
+
+        if trimmed.starts_with("```") {
+            fence = Some("```");
+        } else if trimmed.starts_with("~~~") {
+            fence = Some("~~~");
+        }
+        start.get_or_insert(line_start);
+        end = line_start + line.trim_end().len();
+    }
+    if let Some(s) = start {
+        blocks.push((s, end));
+    }
+    blocks
+}
+
+/// 按比例截取到块边界；首块已超出比例时，纯文本段落按字符截断，其他块整块隐藏。
+/// 未截断时返回 None。
+fn cut_by_percent(text: &str, percent: i16) -> Option<&str> {
+    let total = text.chars().count();
+    let target = total * percent.clamp(0, 100) as usize / 100;
+    if target >= total {
+        return None;
+    }
+
+    let mut cut = 0;
+    for (_, end) in blocks(text) {
+        if text[..end].chars().count() > target {
+            break;
+        }
+        cut = end;
+    }
+
+    if cut == 0 {
+        let first = text.trim_start();
+        let is_plain = !first.starts_with("```")
+            && !first.starts_with("~~~")
+            && !first.starts_with('<')
+            && !first.starts_with('|');
+        if is_plain && target > 0 {
+            let skipped = text.len() - first.len();
+            let end = first
+                .char_indices()
+                .nth(target)
+                .map_or(first.len(), |(i, _)| i);
+            let mut piece = &first[..end];
+            // 不截断在行内标签中间
+            if let Some(lt) = piece.rfind('<') {
+                if !piece[lt..].contains('>') {
+                    piece = &piece[..lt];
+                }
+            }
+            cut = skipped + piece.len();
+        }
+    }
+
+    Some(text[..cut].trim_end())
+}
+
+/// 只保留指定标题下的章节（含子章节），按原文顺序拼接
+fn free_sections(text: &str, names: &[String]) -> String {
+    let names: Vec<String> = names.iter().map(|n| n.trim().to_lowercase()).collect();
+
+    // (行起始字节, 标题级别, 标题文本)
+    let mut headings: Vec<(usize, usize, String)> = Vec::new();
+    let mut fence = false;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = !fence;
+        } else if !fence {
+            let level = trimmed.chars().take_while(|&c| c == '#').count();
+            if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+                let title = trimmed[level..].trim().trim_end_matches('#').trim();
+                headings.push((offset, level, title.to_lowercase()));
+            }
+        }
+        offset += line.len();
+    }
+
+    let mut parts: Vec<&str> = Vec::new();
+    let mut covered_until = 0;
+    for (i, (start, level, title)) in headings.iter().enumerate() {
+        if *start < covered_until || !names.contains(title) {
+            continue;
+        }
+        let end = headings[i + 1..]
+            .iter()
+            .find(|(_, l, _)| l <= level)
+            .map_or(text.len(), |(s, _, _)| *s);
+        parts.push(text[*start..end].trim_end());
+        covered_until = end;
+    }
+    parts.join("\n\n")
+}
+
+/// 补齐未闭合的 HTML 标签（忽略代码块与行内代码中的标签）
+fn close_open_tags(html: &str) -> String {
+    let scanned = strip_code(html);
+    let mut stack: Vec<String> = Vec::new();
+    let mut rest = scanned.as_str();
+
+    while let Some(start) = rest.find('<') {
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('>') else {
+            break;
+        };
+        let tag = &after[..end];
+        rest = &after[end + 1..];
+
+        if tag.ends_with('/') {
+            continue;
+        }
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = tag_name(name);
+            if let Some(pos) = stack.iter().rposition(|t| *t == name) {
+                stack.truncate(pos);
+            }
+        } else {
+            let name = tag_name(tag);
+            if !name.is_empty() && !VOID_ELEMENTS.contains(&name.as_str()) {
+                stack.push(name);
+            }
+        }
+    }
+
+    let mut closed = html.to_string();
+    for name in stack.iter().rev() {
+        closed.push_str("</");
+        closed.push_str(name);
+        closed.push('>');
+    }
+    closed
+}
+
+/// 标签名（必须紧跟 `<` 以字母开头，排除 `a < b` 之类的文本）
+fn tag_name(tag: &str) -> String {
+    if !tag.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return String::new();
+    }
+    tag.chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+/// 去掉围栏代码块与行内代码
+fn strip_code(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut fence = false;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = !fence;
+            continue;
+        }
+        if fence {
+            continue;
+        }
+        for (i, piece) in line.split('`').enumerate() {
+            if i % 2 == 0 {
+                out.push_str(piece);
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(mode: &str, percent: i16, sections: &[&str]) -> PreviewPolicy {
+        PreviewPolicy {
+            mode: mode.to_string(),
+            percent,
+            free_sections: sections.iter().map(|s| s.to_string()).collect(),
+            ..PreviewPolicy::default_for(1)
+        }
+    }
+
+    #[test]
+    fn test_percent_keeps_code_blocks_whole() {
+        let text = "Intro paragraph.\n\n```rust\nfn secret() {}\n```\n\nMore text here that is long enough.";
+        let preview = preview_text(text, &policy(PREVIEW_MODE_PERCENT, 40, &[]));
+        assert_eq!(preview, "Intro paragraph.\n\n...");
+    }
+
+    #[test]
+    fn test_percent_closes_html_tags() {
+        let text = "<div class=\"a\">\n<p>First</p>\n\n<p>Second part with more words</p>\n</div>";
+        let preview = preview_text(text, &policy(PREVIEW_MODE_PERCENT, 50, &[]));
+        assert_eq!(preview, "<div class=\"a\">\n<p>First</p></div>\n\n...");
+    }
+
+    #[test]
+    fn test_percent_cuts_plain_first_paragraph() {
+        let text = "abcdefghij klmnopqrst";
+        let preview = preview_text(text, &policy(PREVIEW_MODE_PERCENT, 50, &[]));
+        assert_eq!(preview, "abcdefghij\n\n...");
+        assert_eq!(
+            preview_text(text, &policy(PREVIEW_MODE_PERCENT, 100, &[])),
+            text
+        );
+    }
+
+    #[test]
+    fn test_marker() {
+        let text = "Free part\n\n<!--more-->\n\nPaid part";
+        assert_eq!(
+            preview_text(text, &policy(PREVIEW_MODE_MARKER, 25, &[])),
+            "Free part\n\n..."
+        );
+    }
+
+    #[test]
+    fn test_sections() {
+        let text = "Intro\n\n## Overview\nfree\n### Details\nnested\n## Install\npaid\n```\n# not a heading\n```\n## FAQ\nfree too";
+        let preview = preview_text(
+            text,
+            &policy(PREVIEW_MODE_SECTIONS, 25, &["overview", "FAQ"]),
+        );
+        assert_eq!(
+            preview,
+            "## Overview\nfree\n### Details\nnested\n\n## FAQ\nfree too\n\n..."
+        );
+    }
+
+    #[test]
+    fn test_preview_images() {
+        let images = serde_json::json!(["a.png", "b.png", "c.png"]);
+        assert_eq!(
+            preview_images(Some(images.clone()), Some(1)),
+            Some(serde_json::json!(["a.png"]))
+        );
+        assert_eq!(preview_images(Some(images.clone()), None), Some(images));
+    }
+}
@@ -0,0 +1,140 @@
+//! 资源评分与评价模型
+//!
+//! 已购买资源的用户可评分（1–5）并评价，每个用户每个资源一条，再次提交即修改。
+//! 创作者可回复评价；用户可举报评价，管理员隐藏或恢复。
+//! 资源的平均分与评分数只统计可见评价，冗余在 `resources.rating_avg / rating_count`。
+
+use chrono::{DateTime, Utc};
+use salvo_oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 评价状态
+pub const RATING_STATUS_VISIBLE: &str = "visible";
+pub const RATING_STATUS_HIDDEN: &str = "hidden";
+
+/// 举报状态
+pub const REPORT_STATUS_OPEN: &str = "open";
+pub const REPORT_STATUS_RESOLVED: &str = "resolved";
+
+/// 评分范围
+pub const MIN_RATING: i16 = 1;
+pub const MAX_RATING: i16 = 5;
+
+/// 评价内容、回复最大长度
+pub const MAX_RATING_CONTENT_LEN: usize = 2000;
+
+/// 举报原因最大长度
+pub const MAX_REPORT_REASON_LEN: usize = 500;
+
+/// 资源评价
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ResourceRating {
+    pub id: i64,
+    pub resource_id: i64,
+    pub user_id: i64,
+    pub rating: i16,
+    pub content: Option<String>,
+    /// 创作者回复
+    pub reply: Option<String>,
+    pub replied_at: Option<DateTime<Utc>>,
+    /// visible / hidden
+    pub status: String,
+    pub hidden_reason: Option<String>,
+    pub report_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 资源详情页展示的评价（仅可见评价）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct RatingListItem {
+    pub id: i64,
+    pub user_id: i64,
+    pub username: String,
+    pub rating: i16,
+    pub content: Option<String>,
+    pub reply: Option<String>,
+    pub replied_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 被举报的评价（管理员处理队列）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ReportedRating {
+    pub rating_id: i64,
+    pub resource_id: i64,
+    pub resource_title: String,
+    pub user_id: i64,
+    pub rating: i16,
+    pub content: Option<String>,
+    pub status: String,
+    /// 待处理举报数
+    pub open_reports: i64,
+    /// 待处理举报原因
+    pub reasons: Vec<String>,
+    pub last_reported_at: DateTime<Utc>,
+}
+
+/// 提交评价请求（已评价时为修改）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SubmitRatingRequest {
+    /// 1–5
+    pub rating: i16,
+    pub content: Option<String>,
+}
+
+/// 创作者回复请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReplyRatingRequest {
+    pub reply: String,
+}
+
+/// 举报评价请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReportRatingRequest {
+    pub reason: String,
+}
+
+/// 管理员隐藏评价请求
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct HideRatingRequest {
+    pub reason: Option<String>,
+}
+
+/// 评分是否在 1–5 之间
+pub fn is_valid_rating(rating: i16) -> bool {
+    (MIN_RATING..=MAX_RATING).contains(&rating)
+}
+
+/// 去除首尾空白，空字符串视为未填写
+pub fn normalize_text(text: Option<&str>) -> Option<String> {
+    text.map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_rating() {
+        assert!(is_valid_rating(1));
+        assert!(is_valid_rating(5));
+        assert!(!is_valid_rating(0));
+        assert!(!is_valid_rating(6));
+        assert!(!is_valid_rating(-1));
+    }
+
+    #[test]
+    fn test_normalize_text() {
+        assert_eq!(normalize_text(None), None);
+        assert_eq!(normalize_text(Some("   ")), None);
+        assert_eq!(
+            normalize_text(Some("  Great pack \n")),
+            Some("Great pack".to_string())
+        );
+    }
+}
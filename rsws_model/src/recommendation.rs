@@ -0,0 +1,85 @@
+//! 资源推荐模型
+//!
+//! - 相关资源：同分类、共同标签与共同购买加权打分
+//! - 买了还买：已支付订单中同时购买过两个资源的用户数
+//! - 本周热门：近 7 天浏览数 + 下载数加权
+//!
+//! 推荐结果由后台任务定期写入缓存表，详情页只读缓存。
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use salvo_oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 推荐类型常量
+pub const RECOMMENDATION_KIND_RELATED: &str = "related";
+pub const RECOMMENDATION_KIND_ALSO_BOUGHT: &str = "also_bought";
+
+/// 每个资源缓存的推荐数量
+pub const MAX_RECOMMENDATIONS: i64 = 12;
+
+/// 相关资源打分权重：同分类
+pub const CATEGORY_WEIGHT: f64 = 1.0;
+/// 相关资源打分权重：每个共同标签
+pub const SHARED_TAG_WEIGHT: f64 = 1.5;
+/// 相关资源打分权重：共同购买（乘以 ln(1 + 共同购买人数)）
+pub const CO_PURCHASE_WEIGHT: f64 = 2.0;
+
+/// 热门统计天数
+pub const POPULAR_DAYS: i32 = 7;
+/// 热门榜缓存数量
+pub const POPULAR_LIMIT: i64 = 100;
+/// 热门打分：一次下载折合的浏览数
+pub const POPULAR_DOWNLOAD_WEIGHT: f64 = 5.0;
+
+/// 推荐的资源
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct RecommendedResource {
+    pub id: i64,
+    pub title: String,
+    pub price: Decimal,
+    pub thumbnail_url: Option<String>,
+    pub owner_type: String,
+    pub provider_id: Option<i64>,
+    pub rating_avg: Decimal,
+    pub rating_count: i32,
+    pub download_count: i64,
+    pub score: f64,
+}
+
+/// 资源详情页推荐
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ResourceRecommendations {
+    /// 相关资源
+    pub related: Vec<RecommendedResource>,
+    /// 买了该资源的用户还买了
+    pub also_bought: Vec<RecommendedResource>,
+}
+
+/// 本周热门资源
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct PopularResource {
+    pub id: i64,
+    pub title: String,
+    pub price: Decimal,
+    pub thumbnail_url: Option<String>,
+    pub owner_type: String,
+    pub provider_id: Option<i64>,
+    pub rating_avg: Decimal,
+    pub rating_count: i32,
+    /// 统计区间内浏览数
+    pub views: i64,
+    /// 统计区间内下载数
+    pub downloads: i64,
+    pub rank: i32,
+    pub computed_at: DateTime<Utc>,
+}
+
+/// 推荐缓存刷新结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct RecommendationRefresh {
+    pub related: u64,
+    pub also_bought: u64,
+    pub popular: u64,
+}
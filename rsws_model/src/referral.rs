@@ -0,0 +1,132 @@
+//! 推荐计划模型
+//!
+//! 每个用户一个推荐码，推荐链接形如 `/?ref=CODE`。
+//! 点击推荐链接时记录点击并写入 Cookie，注册和下单时按请求参数或 Cookie 归因到推荐人。
+//! 推荐佣金按商品金额（不含附加费）× 推荐佣金比例计算，由平台收入承担，与创作者分成相互独立。
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use salvo_oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 推荐码 Cookie 名称
+pub const REFERRAL_COOKIE: &str = "rsws_ref";
+
+/// 推荐码 Cookie 有效期（天）
+pub const REFERRAL_COOKIE_DAYS: i64 = 30;
+
+/// 推荐码 URL 参数名
+pub const REFERRAL_QUERY_PARAM: &str = "ref";
+
+/// 推荐佣金比例配置键（system_configs，小数，0 为关闭）
+pub const REFERRAL_RATE_CONFIG_KEY: &str = "referral_commission_rate";
+
+/// 推荐码最大长度
+pub const REFERRAL_CODE_MAX_LEN: usize = 16;
+
+/// 规范化推荐码：去除首尾空白并转为大写，格式不合法时返回 None
+pub fn normalize_referral_code(code: &str) -> Option<String> {
+    let code = code.trim();
+    if code.is_empty()
+        || code.len() > REFERRAL_CODE_MAX_LEN
+        || !code.chars().all(|c| c.is_ascii_alphanumeric())
+    {
+        return None;
+    }
+    Some(code.to_ascii_uppercase())
+}
+
+/// 计算推荐佣金：商品金额 × 比例，保留两位小数
+pub fn referral_commission(base: Decimal, rate: Decimal) -> Decimal {
+    if base <= Decimal::ZERO || rate <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+    (base * rate).round_dp(2)
+}
+
+/// 用户推荐码
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ReferralCode {
+    pub user_id: i64,
+    pub code: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 记录推荐链接点击请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReferralClickRequest {
+    pub code: String,
+    /// 落地页路径
+    pub landing_path: Option<String>,
+}
+
+/// 推荐人数据概览
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ReferralStats {
+    /// 推荐链接点击次数
+    pub clicks: i64,
+    /// 通过推荐注册的用户数
+    pub signups: i64,
+    /// 归因到推荐人的已支付订单数
+    pub orders: i64,
+    /// 待结算佣金
+    pub pending_earnings: Decimal,
+    /// 已结算佣金
+    pub settled_earnings: Decimal,
+}
+
+/// 推荐人仪表盘
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReferralDashboard {
+    pub code: String,
+    /// 推荐链接（相对路径，由前端拼接站点域名）
+    pub link: String,
+    pub stats: ReferralStats,
+}
+
+/// 推荐佣金明细
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ReferralEarning {
+    pub id: i64,
+    pub order_id: Option<i64>,
+    /// 被推荐的买家
+    pub buyer_name: Option<String>,
+    pub order_amount: Option<Decimal>,
+    pub commission_amount: Option<Decimal>,
+    pub commission_rate: Option<Decimal>,
+    /// pending / settled / cancelled
+    pub status: Option<String>,
+    pub settled_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_referral_code() {
+        assert_eq!(
+            normalize_referral_code(" ab12cd34 "),
+            Some("AB12CD34".to_string())
+        );
+        assert_eq!(normalize_referral_code(""), None);
+        assert_eq!(normalize_referral_code("AB-12"), None);
+        assert_eq!(normalize_referral_code("A".repeat(17).as_str()), None);
+    }
+
+    #[test]
+    fn test_referral_commission() {
+        let rate = Decimal::new(5, 2);
+        assert_eq!(
+            referral_commission(Decimal::new(1999, 2), rate),
+            Decimal::new(100, 2)
+        );
+        assert_eq!(referral_commission(Decimal::ZERO, rate), Decimal::ZERO);
+        assert_eq!(
+            referral_commission(Decimal::new(100, 0), Decimal::ZERO),
+            Decimal::ZERO
+        );
+    }
+}
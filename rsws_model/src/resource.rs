@@ -6,6 +6,10 @@ use salvo_oapi::ToSchema;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use crate::license::ResourceLicense;
+use crate::moderation::REVIEW_STATUS_APPROVED;
+use crate::tag::ResourceTag;
+
 /// 资源归属类型常量
 pub const OWNER_TYPE_USER: &str = "user";
 pub const OWNER_TYPE_PLATFORM: &str = "platform";
@@ -31,10 +35,31 @@ pub struct Resource {
     pub supported_os: Option<serde_json::Value>,
     pub commission_rate: Decimal,
     pub download_count: i64,
+    /// 最新版本号
+    pub version: Option<String>,
+    /// 购买后包含的更新月数，为空表示包含全部后续更新
+    pub update_months: Option<i32>,
+    /// 可见评价的平均分
+    pub rating_avg: Decimal,
+    /// 可见评价数
+    pub rating_count: i32,
+    /// 审核状态：draft / pending / approved / rejected / taken_down
+    pub review_status: String,
+    /// 拒绝或下架原因
+    pub review_reason: Option<String>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub reviewed_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl Resource {
+    /// 是否公开展示和售卖（已上架且审核通过）
+    pub fn is_public(&self) -> bool {
+        self.is_active && self.review_status == REVIEW_STATUS_APPROVED
+    }
+}
+
 /// 创建资源请求
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CreateResourceRequest {
@@ -50,6 +75,9 @@ pub struct CreateResourceRequest {
     pub precautions: Option<String>,
     pub display_images: Option<Vec<String>>,
     pub supported_os: Option<Vec<String>>,
+    /// 仅保存为草稿，暂不提交审核（用户资源）
+    #[serde(default)]
+    pub draft: bool,
 }
 
 /// 更新资源请求
@@ -91,10 +119,127 @@ pub struct ResourceDetailResponse {
     pub supported_os: Option<serde_json::Value>,
     pub commission_rate: Decimal,
     pub download_count: i64,
+    pub version: Option<String>,
+    pub update_months: Option<i32>,
+    pub rating_avg: Decimal,
+    pub rating_count: i32,
+    pub review_status: String,
+    pub review_reason: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     /// 用户是否已购买此资源
     pub is_purchased: bool,
+    /// 可购买的授权档位（按等级从低到高）
+    pub licenses: Vec<ResourceLicense>,
+    /// 用户持有的授权档位
+    pub owned_license_id: Option<i64>,
+    /// 用户是否已收藏或加入心愿单
+    pub is_saved: bool,
+    pub tags: Vec<ResourceTag>,
+    /// 是否为预览内容（付费未购买时按预览策略截取）
+    pub is_preview: bool,
+}
+
+/// 资源列表条目（含当前用户的保存状态）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ResourceListItem {
+    #[serde(flatten)]
+    pub resource: Resource,
+    /// 用户是否已收藏或加入心愿单
+    pub is_saved: bool,
+    /// 关键词搜索时的高亮摘要（匹配词以 `<mark>` 包裹）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+}
+
+/// 资源列表排序
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResourceSort {
+    /// 最新发布（默认）
+    #[default]
+    Latest,
+    /// 搜索相关度从高到低（无关键词时同最新发布）
+    Relevance,
+    /// 价格从低到高
+    PriceAsc,
+    /// 价格从高到低
+    PriceDesc,
+    /// 评分从高到低，同分按评分数
+    Rating,
+    /// 下载量从高到低
+    Downloads,
+}
+
+impl ResourceSort {
+    /// 解析查询参数，未知值返回 None
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "latest" | "newest" => Some(Self::Latest),
+            "relevance" => Some(Self::Relevance),
+            "price_asc" => Some(Self::PriceAsc),
+            "price_desc" => Some(Self::PriceDesc),
+            "rating" => Some(Self::Rating),
+            "downloads" => Some(Self::Downloads),
+            _ => None,
+        }
+    }
+
+    /// ORDER BY 子句（相关度排序依赖关键词，由仓储层单独拼接，此处为无关键词时的回退）
+    pub fn order_by(&self) -> &'static str {
+        match self {
+            Self::Latest | Self::Relevance => "created_at DESC",
+            Self::PriceAsc => "COALESCE(price, 0) ASC, created_at DESC",
+            Self::PriceDesc => "COALESCE(price, 0) DESC, created_at DESC",
+            Self::Rating => "rating_avg DESC, rating_count DESC, created_at DESC",
+            Self::Downloads => "download_count DESC, created_at DESC",
+        }
+    }
+}
+
+/// 资源列表筛选条件（均为空时返回全部公开资源）
+#[derive(Debug, Clone, Default)]
+pub struct ResourceFilter {
+    pub category_id: Option<i64>,
+    /// 分类筛选包含全部子分类的资源
+    pub include_subcategories: bool,
+    /// 全文搜索关键词（标题、标签、简介、详情）
+    pub search: Option<String>,
+    /// 标签 slug，须同时包含全部标签
+    pub tags: Vec<String>,
+    pub min_price: Option<Decimal>,
+    pub max_price: Option<Decimal>,
+    /// true 仅免费，false 仅付费
+    pub is_free: Option<bool>,
+    /// 支持任一系统即可
+    pub supported_os: Vec<String>,
+    /// user / platform
+    pub owner_type: Option<String>,
+    /// 创作者用户 ID
+    pub provider_id: Option<i64>,
+    pub sort: ResourceSort,
+}
+
+/// 分面取值及计数
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct FacetCount {
+    pub value: String,
+    /// 展示名称（分类名、标签名）
+    pub label: Option<String>,
+    pub count: i64,
+}
+
+/// 资源列表分面计数
+///
+/// 单选 / 多选分面（分类、系统、归属类型、免费 / 付费）的计数不含该分面自身的筛选条件，
+/// 选中某个取值后其他取值仍显示可选数量；标签为逐步收窄，计数包含已选标签。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ResourceFacets {
+    pub categories: Vec<FacetCount>,
+    pub tags: Vec<FacetCount>,
+    pub supported_os: Vec<FacetCount>,
+    pub owner_types: Vec<FacetCount>,
+    /// free / paid
+    pub pricing: Vec<FacetCount>,
 }
 
 /// 资源列表响应
@@ -127,9 +272,24 @@ mod tests {
             precautions: None,
             display_images: None,
             supported_os: None,
+            draft: false,
         };
 
         assert_eq!(req.title, "Test Resource");
         assert_eq!(req.price, Decimal::new(1000, 0));
     }
+
+    #[test]
+    fn test_resource_sort_parse() {
+        assert_eq!(ResourceSort::parse("rating"), Some(ResourceSort::Rating));
+        assert_eq!(ResourceSort::parse("latest"), Some(ResourceSort::Latest));
+        assert_eq!(ResourceSort::parse("newest"), Some(ResourceSort::Latest));
+        assert_eq!(
+            ResourceSort::parse("price_desc"),
+            Some(ResourceSort::PriceDesc)
+        );
+        assert_eq!(ResourceSort::Relevance.order_by(), "created_at DESC");
+        assert_eq!(ResourceSort::parse("price; DROP TABLE"), None);
+        assert_eq!(ResourceSort::default().order_by(), "created_at DESC");
+    }
 }
@@ -0,0 +1,429 @@
+//! 资源批量导入导出模型
+//!
+//! 导入支持 CSV（首行为表头）与 JSON Lines（每行一个 JSON 对象），按 `external_id`
+//! （旧站点的资源 ID）新建或更新资源；CSV 中的列表字段（展示图、系统、标签）用 `|` 分隔，
+//! 空单元格表示不修改。导出包含分类名、标签与销售统计，导出文件可直接再导入。
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use salvo_oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::str::FromStr;
+
+use crate::tag::MAX_TAGS_PER_RESOURCE;
+
+/// 导入导出格式：CSV
+pub const FORMAT_CSV: &str = "csv";
+
+/// 导入导出格式：JSON Lines
+pub const FORMAT_JSONL: &str = "jsonl";
+
+/// 单次导入最多行数
+pub const MAX_IMPORT_ROWS: usize = 5000;
+
+/// external_id 最大长度
+pub const MAX_EXTERNAL_ID_LEN: usize = 100;
+
+/// 资源标题最大长度
+pub const MAX_TITLE_LEN: usize = 255;
+
+/// CSV 列表字段分隔符
+pub const LIST_SEPARATOR: char = '|';
+
+/// 导入动作：新建
+pub const IMPORT_ACTION_CREATE: &str = "create";
+
+/// 导入动作：更新
+pub const IMPORT_ACTION_UPDATE: &str = "update";
+
+/// 导入 CSV 可识别的列（其他列忽略，便于直接导入导出文件）
+pub const IMPORT_COLUMNS: &[&str] = &[
+    "external_id",
+    "title",
+    "description",
+    "price",
+    "category_id",
+    "category",
+    "file",
+    "thumbnail",
+    "detail_description",
+    "usage_guide",
+    "precautions",
+    "display_images",
+    "supported_os",
+    "tags",
+];
+
+/// 导入 CSV 必需的列
+const REQUIRED_COLUMNS: &[&str] = &["external_id", "title", "price"];
+
+/// 解析后的导入行：(源文件行号, 解析结果)
+pub type ParsedImportRow = (usize, Result<ResourceImportRow, String>);
+
+/// 导入行
+///
+/// `file` / `thumbnail` / `display_images` 可填导入目录下的相对路径、存储 key（`resources/...`）
+/// 或已有的 URL；分类优先按 `category_id`，未填时按名称匹配（不区分大小写）。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ResourceImportRow {
+    pub external_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub price: Decimal,
+    pub category_id: Option<i64>,
+    /// 分类名称
+    pub category: Option<String>,
+    /// 资源文件
+    pub file: Option<String>,
+    /// 缩略图
+    pub thumbnail: Option<String>,
+    pub detail_description: Option<String>,
+    pub usage_guide: Option<String>,
+    pub precautions: Option<String>,
+    pub display_images: Option<Vec<String>>,
+    pub supported_os: Option<Vec<String>>,
+    /// 标签（整体替换，不存在的标签自动创建）
+    pub tags: Option<Vec<String>>,
+}
+
+impl ResourceImportRow {
+    /// 校验字段，返回错误列表（为空表示通过）
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.external_id.is_empty() {
+            errors.push("external_id is required".to_string());
+        } else if self.external_id.chars().count() > MAX_EXTERNAL_ID_LEN {
+            errors.push(format!(
+                "external_id must be at most {} characters",
+                MAX_EXTERNAL_ID_LEN
+            ));
+        }
+        if self.title.trim().is_empty() {
+            errors.push("title is required".to_string());
+        } else if self.title.chars().count() > MAX_TITLE_LEN {
+            errors.push(format!(
+                "title must be at most {} characters",
+                MAX_TITLE_LEN
+            ));
+        }
+        if self.price < Decimal::ZERO {
+            errors.push("price must not be negative".to_string());
+        }
+        if self.price.scale() > 2 {
+            errors.push("price must have at most 2 decimal places".to_string());
+        }
+        if self
+            .tags
+            .as_ref()
+            .is_some_and(|tags| tags.len() > MAX_TAGS_PER_RESOURCE)
+        {
+            errors.push(format!(
+                "at most {} tags per resource",
+                MAX_TAGS_PER_RESOURCE
+            ));
+        }
+        errors
+    }
+}
+
+/// 单行导入结果
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImportRowResult {
+    /// 源文件中的行号（从 1 开始，CSV 含表头行）
+    pub row: usize,
+    pub external_id: Option<String>,
+    /// create / update；校验失败时为空
+    pub action: Option<String>,
+    /// 新建或更新的资源 ID（试运行时仅更新行有值）
+    pub resource_id: Option<i64>,
+    pub errors: Vec<String>,
+    /// 不影响导入的提示（如已有资源文件时忽略 file 列）
+    pub warnings: Vec<String>,
+}
+
+/// 导入报告
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImportReport {
+    /// 试运行：只校验，不写入
+    pub dry_run: bool,
+    pub total: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub failed: usize,
+    pub rows: Vec<ImportRowResult>,
+}
+
+/// 资源导出行
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ResourceExportRow {
+    pub id: i64,
+    pub external_id: Option<String>,
+    pub title: String,
+    pub description: Option<String>,
+    pub price: Decimal,
+    pub category_id: Option<i64>,
+    /// 分类名称
+    pub category: Option<String>,
+    /// 资源文件 URL
+    pub file: Option<String>,
+    /// 缩略图 URL
+    pub thumbnail: Option<String>,
+    pub detail_description: Option<String>,
+    pub usage_guide: Option<String>,
+    pub precautions: Option<String>,
+    pub display_images: Vec<String>,
+    pub supported_os: Vec<String>,
+    pub tags: Vec<String>,
+    pub is_active: bool,
+    pub review_status: String,
+    pub owner_type: String,
+    pub provider_id: Option<i64>,
+    pub download_count: i64,
+    /// 已支付订单数
+    pub sales_count: i64,
+    /// 已支付订单金额合计
+    pub revenue: Decimal,
+    pub rating_avg: Decimal,
+    pub rating_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 解析 CSV（RFC 4180：双引号转义，字段内可换行；兼容 UTF-8 BOM 与 CRLF）
+///
+/// 返回 (记录起始行号, 字段) 列表，跳过空行。
+pub fn parse_csv(text: &str) -> Result<Vec<(usize, Vec<String>)>, String> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records = Vec::new();
+    let mut fields: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => fields.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                if fields.iter().any(|f| !f.is_empty()) {
+                    records.push((record_line, std::mem::take(&mut fields)));
+                }
+                fields.clear();
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(format!("unterminated quoted field at line {}", record_line));
+    }
+    fields.push(field);
+    if fields.iter().any(|f| !f.is_empty()) {
+        records.push((record_line, fields));
+    }
+    Ok(records)
+}
+
+/// 解析 CSV 导入文件
+///
+/// 表头缺少必需列时整体失败；单行格式错误记入该行结果。
+pub fn import_rows_from_csv(text: &str) -> Result<Vec<ParsedImportRow>, String> {
+    let mut records = parse_csv(text)?.into_iter();
+    let Some((_, header)) = records.next() else {
+        return Err("CSV header is missing".to_string());
+    };
+    let header: Vec<String> = header.iter().map(|h| h.trim().to_lowercase()).collect();
+    for required in REQUIRED_COLUMNS {
+        if !header.iter().any(|h| h == required) {
+            return Err(format!("CSV column is missing: {}", required));
+        }
+    }
+
+    Ok(records
+        .map(|(line, fields)| {
+            let cells = header
+                .iter()
+                .map(String::as_str)
+                .zip(fields.iter().map(|f| unescape_formula(f.trim())))
+                .filter(|(name, _)| IMPORT_COLUMNS.contains(name));
+            (line, import_row_from_cells(cells))
+        })
+        .collect())
+}
+
+/// 解析 JSON Lines 导入文件（跳过空行）
+pub fn import_rows_from_jsonl(text: &str) -> Vec<ParsedImportRow> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let row = serde_json::from_str::<ResourceImportRow>(line)
+                .map(normalize_row)
+                .map_err(|e| format!("invalid JSON: {}", e));
+            (i + 1, row)
+        })
+        .collect()
+}
+
+/// 按列名组装导入行
+fn import_row_from_cells<'a>(
+    cells: impl Iterator<Item = (&'a str, &'a str)>,
+) -> Result<ResourceImportRow, String> {
+    let mut row = ResourceImportRow::default();
+    for (name, value) in cells {
+        let text = Some(value.to_string()).filter(|v| !v.is_empty());
+        match name {
+            "external_id" => row.external_id = value.to_string(),
+            "title" => row.title = value.to_string(),
+            "description" => row.description = text,
+            "price" => {
+                row.price =
+                    Decimal::from_str(value).map_err(|_| format!("invalid price: {}", value))?;
+            }
+            "category_id" if !value.is_empty() => {
+                row.category_id = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid category_id: {}", value))?,
+                );
+            }
+            "category" => row.category = text,
+            "file" => row.file = text,
+            "thumbnail" => row.thumbnail = text,
+            "detail_description" => row.detail_description = text,
+            "usage_guide" => row.usage_guide = text,
+            "precautions" => row.precautions = text,
+            "display_images" => row.display_images = text.map(|v| split_list(&v)),
+            "supported_os" => row.supported_os = text.map(|v| split_list(&v)),
+            "tags" => row.tags = text.map(|v| split_list(&v)),
+            _ => {}
+        }
+    }
+    Ok(normalize_row(row))
+}
+
+/// 去掉首尾空白，空字符串视为未填
+fn normalize_row(mut row: ResourceImportRow) -> ResourceImportRow {
+    fn clean(value: &mut Option<String>) {
+        *value = value
+            .take()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+    }
+    row.external_id = row.external_id.trim().to_string();
+    row.title = row.title.trim().to_string();
+    clean(&mut row.category);
+    clean(&mut row.file);
+    clean(&mut row.thumbnail);
+    row
+}
+
+/// 拆分 CSV 列表字段
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(LIST_SEPARATOR)
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 还原导出时为防公式注入加的 `'` 前缀
+fn unescape_formula(value: &str) -> &str {
+    match value.strip_prefix('\'') {
+        Some(rest) if rest.starts_with(['=', '+', '-', '@']) => rest,
+        _ => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv() {
+        let text = "\u{feff}a,b,c\r\n1,\"x, \"\"y\"\"\",\"line1\nline2\"\r\n\r\n2,,3";
+        let records = parse_csv(text).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0], (1, vec!["a".into(), "b".into(), "c".into()]));
+        assert_eq!(
+            records[1],
+            (
+                2,
+                vec!["1".into(), "x, \"y\"".into(), "line1\nline2".into()]
+            )
+        );
+        assert_eq!(records[2], (5, vec!["2".into(), "".into(), "3".into()]));
+
+        assert!(parse_csv("a,\"b\n").is_err());
+    }
+
+    #[test]
+    fn test_import_rows_from_csv() {
+        let text = "id,External_ID,title,price,category,tags,description,file\n\
+                    9,old-1,Icon Pack,9.90,Icons,ui | icons,'-50% off,packs/icons.zip\n\
+                    10,old-2,Bad,abc,,,,\n";
+        let rows = import_rows_from_csv(text).unwrap();
+        assert_eq!(rows.len(), 2);
+
+        let (line, row) = &rows[0];
+        let row = row.as_ref().unwrap();
+        assert_eq!(*line, 2);
+        assert_eq!(row.external_id, "old-1");
+        assert_eq!(row.price, Decimal::new(990, 2));
+        assert_eq!(row.category.as_deref(), Some("Icons"));
+        assert_eq!(row.tags, Some(vec!["ui".to_string(), "icons".to_string()]));
+        assert_eq!(row.description.as_deref(), Some("-50% off"));
+        assert_eq!(row.file.as_deref(), Some("packs/icons.zip"));
+        assert_eq!(row.supported_os, None);
+        assert!(row.validate().is_empty());
+
+        assert!(rows[1].1.is_err());
+        assert!(import_rows_from_csv("title,price\nA,1\n").is_err());
+    }
+
+    #[test]
+    fn test_import_rows_from_jsonl() {
+        let text = "{\"external_id\":\" old-1 \",\"title\":\"A\",\"price\":\"1.5\",\"tags\":[\"x\"],\"sales_count\":3}\n\n{bad}\n";
+        let rows = import_rows_from_jsonl(text);
+        assert_eq!(rows.len(), 2);
+        let row = rows[0].1.as_ref().unwrap();
+        assert_eq!(row.external_id, "old-1");
+        assert_eq!(row.price, Decimal::new(15, 1));
+        assert_eq!(rows[1].0, 3);
+        assert!(rows[1].1.is_err());
+    }
+
+    #[test]
+    fn test_validate_import_row() {
+        let row = ResourceImportRow {
+            price: Decimal::new(-1, 0),
+            tags: Some((0..=MAX_TAGS_PER_RESOURCE).map(|i| i.to_string()).collect()),
+            ..Default::default()
+        };
+        assert_eq!(row.validate().len(), 4);
+    }
+}
@@ -0,0 +1,154 @@
+//! 资源版本模型
+//!
+//! 每次发布新文件记录为一个版本，买家可下载其有权获取的任一版本。
+//! 资源的 `update_months` 为空时包含全部后续更新；否则买家可获取购买时已发布的版本，
+//! 以及购买后 N 个月内发布的版本。
+
+use chrono::{DateTime, Months, Utc};
+use salvo_oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 资源创建时首个版本号
+pub const INITIAL_VERSION: &str = "1.0";
+
+/// 更新期最长月数
+pub const MAX_UPDATE_MONTHS: i32 = 120;
+
+/// 资源版本
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ResourceVersion {
+    pub id: i64,
+    pub resource_id: i64,
+    pub version: String,
+    pub file_url: String,
+    pub file_size: Option<i64>,
+    /// SHA-256 十六进制
+    pub checksum: Option<String>,
+    pub changelog: Option<String>,
+    pub released_at: DateTime<Utc>,
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 买家看到的版本信息（不含文件地址）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ResourceVersionInfo {
+    pub id: i64,
+    pub version: String,
+    pub file_size: Option<i64>,
+    pub checksum: Option<String>,
+    pub changelog: Option<String>,
+    pub released_at: DateTime<Utc>,
+    /// 当前用户是否可下载此版本
+    pub entitled: bool,
+}
+
+impl ResourceVersionInfo {
+    pub fn new(version: ResourceVersion, entitled: bool) -> Self {
+        Self {
+            id: version.id,
+            version: version.version,
+            file_size: version.file_size,
+            checksum: version.checksum,
+            changelog: version.changelog,
+            released_at: version.released_at,
+            entitled,
+        }
+    }
+}
+
+/// 发布新版本请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PublishVersionRequest {
+    /// 版本号，同一资源内唯一
+    pub version: String,
+    pub file_url: String,
+    pub file_size: Option<i64>,
+    /// SHA-256 十六进制
+    pub checksum: Option<String>,
+    pub changelog: Option<String>,
+}
+
+/// 设置更新政策请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SetUpdatePolicyRequest {
+    /// 购买后包含的更新月数，为空表示包含全部后续更新
+    pub update_months: Option<i32>,
+}
+
+/// 资源的已购买用户（新版本通知）
+#[derive(Debug, Clone, FromRow)]
+pub struct VersionOwner {
+    pub user_id: i64,
+    pub email: String,
+    pub username: String,
+    /// 最近一次付款时间
+    pub purchased_at: DateTime<Utc>,
+}
+
+/// 更新期截止时间，`update_months` 为空时返回 None（不限）
+pub fn updates_until(
+    purchased_at: DateTime<Utc>,
+    update_months: Option<i32>,
+) -> Option<DateTime<Utc>> {
+    let months = u32::try_from(update_months?).unwrap_or(0);
+    Some(
+        purchased_at
+            .checked_add_months(Months::new(months))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC),
+    )
+}
+
+/// 按购买时间判断版本是否在可下载范围内
+pub fn is_version_entitled(
+    released_at: DateTime<Utc>,
+    purchased_at: DateTime<Utc>,
+    update_months: Option<i32>,
+) -> bool {
+    updates_until(purchased_at, update_months).is_none_or(|until| released_at <= until)
+}
+
+/// 校验 SHA-256 十六进制校验和
+pub fn is_valid_checksum(checksum: &str) -> bool {
+    checksum.len() == 64 && checksum.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_is_version_entitled() {
+        let purchased = Utc.with_ymd_and_hms(2026, 1, 31, 12, 0, 0).unwrap();
+        let before = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+        let within = Utc.with_ymd_and_hms(2026, 2, 28, 0, 0, 0).unwrap();
+        let after = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+
+        // 不限更新
+        assert!(is_version_entitled(after, purchased, None));
+        // 购买时已发布的版本始终可下载
+        assert!(is_version_entitled(before, purchased, Some(1)));
+        // 1 个月更新期：1 月 31 日 + 1 个月 = 2 月 28 日
+        assert!(is_version_entitled(within, purchased, Some(1)));
+        assert!(!is_version_entitled(after, purchased, Some(1)));
+    }
+
+    #[test]
+    fn test_updates_until() {
+        let purchased = Utc.with_ymd_and_hms(2026, 3, 15, 0, 0, 0).unwrap();
+        assert_eq!(updates_until(purchased, None), None);
+        assert_eq!(
+            updates_until(purchased, Some(12)),
+            Some(Utc.with_ymd_and_hms(2027, 3, 15, 0, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_is_valid_checksum() {
+        assert!(is_valid_checksum(&"a1".repeat(32)));
+        assert!(!is_valid_checksum("abc"));
+        assert!(!is_valid_checksum(&"zz".repeat(32)));
+    }
+}
@@ -0,0 +1,454 @@
+//! 订单风控模型
+//!
+//! 下单和发起支付时采集用户 / IP / 设备维度的下单频率、账户年龄、支付失败次数、
+//! PayPal 付款人国家等信号，按可配置规则打分并给出 allow / review / block 动作。
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use salvo_oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 风控动作（按严重程度从低到高）
+pub const RISK_ACTION_ALLOW: &str = "allow";
+pub const RISK_ACTION_REVIEW: &str = "review";
+pub const RISK_ACTION_BLOCK: &str = "block";
+
+/// 全部风控动作（按严重程度从低到高）
+pub const RISK_ACTIONS: [&str; 3] = [RISK_ACTION_ALLOW, RISK_ACTION_REVIEW, RISK_ACTION_BLOCK];
+
+/// 评估阶段
+pub const RISK_STAGE_CREATION: &str = "creation";
+pub const RISK_STAGE_PAYMENT: &str = "payment";
+/// 规则适用于全部阶段
+pub const RISK_STAGE_ANY: &str = "any";
+
+/// 订单风控状态
+pub const RISK_STATUS_CLEAR: &str = "clear";
+pub const RISK_STATUS_REVIEW: &str = "review";
+pub const RISK_STATUS_APPROVED: &str = "approved";
+pub const RISK_STATUS_REJECTED: &str = "rejected";
+
+/// 风控信号
+pub const SIGNAL_USER_ORDERS_1H: &str = "user_orders_1h";
+pub const SIGNAL_IP_ORDERS_1H: &str = "ip_orders_1h";
+pub const SIGNAL_DEVICE_ACCOUNTS_24H: &str = "device_accounts_24h";
+pub const SIGNAL_ACCOUNT_AGE_HOURS: &str = "account_age_hours";
+pub const SIGNAL_FAILED_PAYMENTS_24H: &str = "failed_payments_24h";
+pub const SIGNAL_PAYER_COUNTRY_MISMATCH: &str = "payer_country_mismatch";
+pub const SIGNAL_ORDER_AMOUNT: &str = "order_amount";
+
+/// 全部可配置的风控信号
+pub const RISK_SIGNALS: [&str; 7] = [
+    SIGNAL_USER_ORDERS_1H,
+    SIGNAL_IP_ORDERS_1H,
+    SIGNAL_DEVICE_ACCOUNTS_24H,
+    SIGNAL_ACCOUNT_AGE_HOURS,
+    SIGNAL_FAILED_PAYMENTS_24H,
+    SIGNAL_PAYER_COUNTRY_MISMATCH,
+    SIGNAL_ORDER_AMOUNT,
+];
+
+/// 规则比较运算符
+pub const RISK_OPERATORS: [&str; 5] = ["gt", "gte", "lt", "lte", "eq"];
+
+/// 累计分数达到该值时至少转人工审核
+pub const REVIEW_SCORE_THRESHOLD: i32 = 60;
+
+/// 累计分数达到该值时直接拦截
+pub const BLOCK_SCORE_THRESHOLD: i32 = 100;
+
+/// 动作严重程度，未知动作返回 None
+pub fn action_rank(action: &str) -> Option<usize> {
+    RISK_ACTIONS.iter().position(|a| *a == action)
+}
+
+/// 风控规则
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct RiskRule {
+    pub id: i64,
+    pub name: String,
+    /// 见 `RISK_SIGNALS`
+    pub signal: String,
+    /// gt / gte / lt / lte / eq
+    pub operator: String,
+    pub threshold: Decimal,
+    /// 命中时累加的分数
+    pub score: i32,
+    /// allow / review / block
+    pub action: String,
+    /// creation / payment / any
+    pub stage: String,
+    pub is_active: bool,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RiskRule {
+    /// 规则是否适用于该评估阶段
+    pub fn applies_to(&self, stage: &str) -> bool {
+        self.is_active && (self.stage == RISK_STAGE_ANY || self.stage == stage)
+    }
+
+    /// 信号值是否命中规则
+    pub fn matches(&self, value: Decimal) -> bool {
+        match self.operator.as_str() {
+            "gt" => value > self.threshold,
+            "gte" => value >= self.threshold,
+            "lt" => value < self.threshold,
+            "lte" => value <= self.threshold,
+            "eq" => value == self.threshold,
+            _ => false,
+        }
+    }
+}
+
+/// 创建风控规则请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateRiskRuleRequest {
+    pub name: String,
+    pub signal: String,
+    pub operator: String,
+    pub threshold: Decimal,
+    #[serde(default)]
+    pub score: i32,
+    pub action: String,
+    /// 默认 any
+    pub stage: Option<String>,
+    pub description: Option<String>,
+}
+
+/// 更新风控规则请求
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
+pub struct UpdateRiskRuleRequest {
+    pub name: Option<String>,
+    pub signal: Option<String>,
+    pub operator: Option<String>,
+    pub threshold: Option<Decimal>,
+    pub score: Option<i32>,
+    pub action: Option<String>,
+    pub stage: Option<String>,
+    pub is_active: Option<bool>,
+    pub description: Option<String>,
+}
+
+/// 请求上下文（评估时由 handler 采集）
+#[derive(Debug, Clone, Default)]
+pub struct RiskContext {
+    pub ip_address: Option<String>,
+    /// 代理层提供的 IP 国家（如 CF-IPCountry）
+    pub ip_country: Option<String>,
+    pub user_agent: Option<String>,
+    /// PayPal 付款人国家（仅支付阶段）
+    pub payer_country: Option<String>,
+}
+
+/// 评估时采集的信号
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RiskSignals {
+    /// 用户最近 1 小时的下单数（不含本单）
+    pub user_orders_1h: i64,
+    /// 同一 IP 最近 1 小时的下单数（不含本单）
+    pub ip_orders_1h: i64,
+    /// 与用户最近登录设备相同的账户数（24 小时内，含本人）
+    pub device_accounts_24h: i64,
+    pub account_age_hours: i64,
+    /// 用户最近 24 小时失败的支付交易数
+    pub failed_payments_24h: i64,
+    /// PayPal 付款人国家与下单 IP 国家不一致（任一未知时为 false）
+    pub payer_country_mismatch: bool,
+    pub order_amount: Decimal,
+}
+
+impl RiskSignals {
+    /// 按信号名取值，未知信号返回 None
+    pub fn value(&self, signal: &str) -> Option<Decimal> {
+        let value = match signal {
+            SIGNAL_USER_ORDERS_1H => Decimal::from(self.user_orders_1h),
+            SIGNAL_IP_ORDERS_1H => Decimal::from(self.ip_orders_1h),
+            SIGNAL_DEVICE_ACCOUNTS_24H => Decimal::from(self.device_accounts_24h),
+            SIGNAL_ACCOUNT_AGE_HOURS => Decimal::from(self.account_age_hours),
+            SIGNAL_FAILED_PAYMENTS_24H => Decimal::from(self.failed_payments_24h),
+            SIGNAL_PAYER_COUNTRY_MISMATCH => Decimal::from(self.payer_country_mismatch as i64),
+            SIGNAL_ORDER_AMOUNT => self.order_amount,
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+/// 命中的规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriggeredRule {
+    pub rule_id: i64,
+    pub name: String,
+    pub signal: String,
+    pub value: Decimal,
+    pub score: i32,
+    pub action: String,
+}
+
+/// 评估结论
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskDecision {
+    pub score: i32,
+    /// allow / review / block
+    pub action: String,
+    pub triggered: Vec<TriggeredRule>,
+}
+
+/// 按规则评估信号
+///
+/// 分数为命中规则分数之和；动作取命中规则中最严重者，
+/// 累计分数达到 `REVIEW_SCORE_THRESHOLD` / `BLOCK_SCORE_THRESHOLD` 时同样升级。
+pub fn evaluate(rules: &[RiskRule], signals: &RiskSignals, stage: &str) -> RiskDecision {
+    let triggered: Vec<TriggeredRule> = rules
+        .iter()
+        .filter(|rule| rule.applies_to(stage))
+        .filter_map(|rule| {
+            let value = signals.value(&rule.signal)?;
+            rule.matches(value).then(|| TriggeredRule {
+                rule_id: rule.id,
+                name: rule.name.clone(),
+                signal: rule.signal.clone(),
+                value,
+                score: rule.score,
+                action: rule.action.clone(),
+            })
+        })
+        .collect();
+
+    let score: i32 = triggered.iter().map(|t| t.score).sum();
+    let by_score = if score >= BLOCK_SCORE_THRESHOLD {
+        RISK_ACTION_BLOCK
+    } else if score >= REVIEW_SCORE_THRESHOLD {
+        RISK_ACTION_REVIEW
+    } else {
+        RISK_ACTION_ALLOW
+    };
+    let action = triggered
+        .iter()
+        .map(|t| t.action.as_str())
+        .chain(std::iter::once(by_score))
+        .max_by_key(|a| action_rank(a).unwrap_or(0))
+        .unwrap_or(RISK_ACTION_ALLOW);
+
+    RiskDecision {
+        score,
+        action: action.to_string(),
+        triggered,
+    }
+}
+
+/// 评估后的订单风控状态，None 表示保持不变
+///
+/// 已人工放行的订单只会被 block 拦截；待审核 / 已拒绝的订单不再变化。
+pub fn next_risk_status(current: &str, action: &str) -> Option<&'static str> {
+    match (current, action) {
+        (RISK_STATUS_REVIEW | RISK_STATUS_REJECTED, _) => None,
+        (_, RISK_ACTION_BLOCK) => Some(RISK_STATUS_REJECTED),
+        (RISK_STATUS_APPROVED, _) => None,
+        (_, RISK_ACTION_REVIEW) => Some(RISK_STATUS_REVIEW),
+        _ => None,
+    }
+}
+
+/// 两个国家代码是否不一致（任一未知时视为一致）
+pub fn countries_mismatch(a: Option<&str>, b: Option<&str>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) if !a.is_empty() && !b.is_empty() => !a.eq_ignore_ascii_case(b),
+        _ => false,
+    }
+}
+
+/// 评估结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskOutcome {
+    pub decision: RiskDecision,
+    /// 评估后的订单风控状态
+    pub risk_status: String,
+}
+
+impl RiskOutcome {
+    /// 订单待人工审核
+    pub fn is_held(&self) -> bool {
+        self.risk_status == RISK_STATUS_REVIEW
+    }
+
+    /// 订单已被拦截
+    pub fn is_blocked(&self) -> bool {
+        self.risk_status == RISK_STATUS_REJECTED
+    }
+}
+
+/// 订单风控评估记录
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct OrderRiskAssessment {
+    pub id: i64,
+    pub order_id: i64,
+    /// creation / payment
+    pub stage: String,
+    pub score: i32,
+    pub action: String,
+    /// 命中的规则（`TriggeredRule` 数组）
+    pub triggered: serde_json::Value,
+    /// 采集的信号（`RiskSignals`）
+    pub signals: serde_json::Value,
+    pub ip_address: Option<String>,
+    pub ip_country: Option<String>,
+    pub user_agent: Option<String>,
+    pub payer_country: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 待审核订单
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct RiskReviewItem {
+    pub order_id: i64,
+    pub user_id: i64,
+    pub username: Option<String>,
+    pub resource_id: Option<i64>,
+    pub amount: Decimal,
+    pub status: String,
+    pub payment_method: Option<String>,
+    pub risk_score: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 管理员审核请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RiskReviewRequest {
+    /// true 放行，false 拒绝并取消订单
+    pub approve: bool,
+    pub note: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(
+        id: i64,
+        signal: &str,
+        operator: &str,
+        threshold: i64,
+        score: i32,
+        action: &str,
+    ) -> RiskRule {
+        RiskRule {
+            id,
+            name: format!("rule {}", id),
+            signal: signal.to_string(),
+            operator: operator.to_string(),
+            threshold: Decimal::from(threshold),
+            score,
+            action: action.to_string(),
+            stage: RISK_STAGE_ANY.to_string(),
+            is_active: true,
+            description: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_evaluate_takes_most_severe_action() {
+        let rules = vec![
+            rule(1, SIGNAL_USER_ORDERS_1H, "gte", 5, 30, RISK_ACTION_REVIEW),
+            rule(2, SIGNAL_ACCOUNT_AGE_HOURS, "lt", 1, 20, RISK_ACTION_ALLOW),
+            rule(
+                3,
+                SIGNAL_FAILED_PAYMENTS_24H,
+                "gte",
+                8,
+                80,
+                RISK_ACTION_BLOCK,
+            ),
+        ];
+        let signals = RiskSignals {
+            user_orders_1h: 6,
+            account_age_hours: 0,
+            ..Default::default()
+        };
+
+        let decision = evaluate(&rules, &signals, RISK_STAGE_CREATION);
+        assert_eq!(decision.score, 50);
+        assert_eq!(decision.action, RISK_ACTION_REVIEW);
+        assert_eq!(decision.triggered.len(), 2);
+    }
+
+    #[test]
+    fn test_evaluate_escalates_by_score() {
+        let rules = vec![
+            rule(1, SIGNAL_ACCOUNT_AGE_HOURS, "lt", 1, 40, RISK_ACTION_ALLOW),
+            rule(
+                2,
+                SIGNAL_PAYER_COUNTRY_MISMATCH,
+                "eq",
+                1,
+                60,
+                RISK_ACTION_ALLOW,
+            ),
+        ];
+        let signals = RiskSignals {
+            payer_country_mismatch: true,
+            ..Default::default()
+        };
+
+        let decision = evaluate(&rules, &signals, RISK_STAGE_PAYMENT);
+        assert_eq!(decision.score, BLOCK_SCORE_THRESHOLD);
+        assert_eq!(decision.action, RISK_ACTION_BLOCK);
+    }
+
+    #[test]
+    fn test_evaluate_respects_stage_and_active() {
+        let mut payment_only = rule(1, SIGNAL_USER_ORDERS_1H, "gt", 0, 10, RISK_ACTION_REVIEW);
+        payment_only.stage = RISK_STAGE_PAYMENT.to_string();
+        let mut inactive = rule(2, SIGNAL_USER_ORDERS_1H, "gt", 0, 10, RISK_ACTION_BLOCK);
+        inactive.is_active = false;
+        let signals = RiskSignals {
+            user_orders_1h: 3,
+            account_age_hours: 100,
+            ..Default::default()
+        };
+
+        let decision = evaluate(&[payment_only, inactive], &signals, RISK_STAGE_CREATION);
+        assert_eq!(decision.score, 0);
+        assert_eq!(decision.action, RISK_ACTION_ALLOW);
+        assert!(decision.triggered.is_empty());
+    }
+
+    #[test]
+    fn test_next_risk_status() {
+        assert_eq!(next_risk_status(RISK_STATUS_CLEAR, RISK_ACTION_ALLOW), None);
+        assert_eq!(
+            next_risk_status(RISK_STATUS_CLEAR, RISK_ACTION_REVIEW),
+            Some(RISK_STATUS_REVIEW)
+        );
+        assert_eq!(
+            next_risk_status(RISK_STATUS_CLEAR, RISK_ACTION_BLOCK),
+            Some(RISK_STATUS_REJECTED)
+        );
+        assert_eq!(
+            next_risk_status(RISK_STATUS_APPROVED, RISK_ACTION_REVIEW),
+            None
+        );
+        assert_eq!(
+            next_risk_status(RISK_STATUS_APPROVED, RISK_ACTION_BLOCK),
+            Some(RISK_STATUS_REJECTED)
+        );
+        assert_eq!(
+            next_risk_status(RISK_STATUS_REVIEW, RISK_ACTION_BLOCK),
+            None
+        );
+    }
+
+    #[test]
+    fn test_countries_mismatch() {
+        assert!(countries_mismatch(Some("US"), Some("NG")));
+        assert!(!countries_mismatch(Some("us"), Some("US")));
+        assert!(!countries_mismatch(Some("US"), None));
+        assert!(!countries_mismatch(Some(""), Some("US")));
+    }
+}
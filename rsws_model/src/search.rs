@@ -0,0 +1,125 @@
+//! 资源搜索辅助
+//!
+//! 关键词按 websearch 语法交给 Postgres（`"短语"`、`-排除词`、`or`），
+//! 这里只负责从关键词中取出用于高亮的词，并生成列表摘要。
+
+/// 摘要默认长度（字符）
+pub const SNIPPET_CHARS: usize = 120;
+
+/// 高亮标签
+const MARK_START: &str = "<mark>";
+const MARK_END: &str = "</mark>";
+
+/// 取出用于高亮的搜索词：小写、去引号，跳过排除词与 `or`
+pub fn search_terms(keyword: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for word in keyword.split_whitespace() {
+        if word.starts_with('-') || word.eq_ignore_ascii_case("or") {
+            continue;
+        }
+        let term = word.trim_matches('"').to_lowercase();
+        if !term.is_empty() && !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    // 长词优先匹配，避免短词截断长词的高亮
+    terms.sort_by_key(|t| std::cmp::Reverse(t.chars().count()));
+    terms
+}
+
+/// 生成高亮摘要：截取首个匹配词附近 `max_chars` 个字符，匹配词以 `<mark>` 包裹，
+/// 其余文本做 HTML 转义。没有匹配词时返回 None。
+pub fn highlight_snippet(text: &str, terms: &[String], max_chars: usize) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = chars.iter().map(|&c| lower_char(c)).collect();
+    let terms: Vec<Vec<char>> = terms
+        .iter()
+        .map(|t| t.chars().map(lower_char).collect::<Vec<char>>())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    let match_len = |i: usize| {
+        terms
+            .iter()
+            .find(|t| lower[i..].starts_with(t))
+            .map(|t| t.len())
+    };
+
+    let first = (0..lower.len()).find(|&i| match_len(i).is_some())?;
+    let start = first.saturating_sub(max_chars / 4);
+    let end = (start + max_chars).min(chars.len());
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    let mut i = start;
+    while i < end {
+        match match_len(i) {
+            Some(len) => {
+                let stop = (i + len).min(end);
+                snippet.push_str(MARK_START);
+                push_escaped(&mut snippet, &chars[i..stop]);
+                snippet.push_str(MARK_END);
+                i = stop;
+            }
+            None => {
+                push_escaped(&mut snippet, &chars[i..i + 1]);
+                i += 1;
+            }
+        }
+    }
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    Some(snippet)
+}
+
+/// 单字符小写（保持字符位置一一对应）
+fn lower_char(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+fn push_escaped(out: &mut String, chars: &[char]) {
+    for &c in chars {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            '\n' | '\r' | '\t' => out.push(' '),
+            _ => out.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_terms() {
+        assert_eq!(
+            search_terms("\"Low Poly\" unity -paid OR unity"),
+            vec!["unity", "poly", "low"]
+        );
+        assert!(search_terms("  -x or ").is_empty());
+    }
+
+    #[test]
+    fn test_highlight_snippet() {
+        let terms = search_terms("unity");
+        assert_eq!(
+            highlight_snippet("Built for Unity <3", &terms, 120).unwrap(),
+            "Built for <mark>Unity</mark> &lt;3"
+        );
+
+        let terms = search_terms("像素");
+        let text = format!("{}像素风格素材", "很长的前缀".repeat(20));
+        let snippet = highlight_snippet(&text, &terms, 20).unwrap();
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.contains("<mark>像素</mark>"));
+
+        assert!(highlight_snippet("nothing here", &terms, 120).is_none());
+    }
+}
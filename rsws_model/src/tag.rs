@@ -0,0 +1,72 @@
+//! 资源标签模型
+//!
+//! 标签名自由填写，slug 为规范化后的标签名（小写、空白与下划线转为 `-`），
+//! 同 slug 视为同一标签，资源列表按 slug 筛选。
+
+use chrono::{DateTime, Utc};
+use salvo_oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 标签名最大长度
+pub const MAX_TAG_NAME_LEN: usize = 30;
+
+/// 每个资源最多标签数
+pub const MAX_TAGS_PER_RESOURCE: usize = 10;
+
+/// 标签（含使用该标签的资源数）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    pub slug: String,
+    pub resource_count: i64,
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 资源上的标签
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ResourceTag {
+    pub name: String,
+    pub slug: String,
+}
+
+/// 创建 / 重命名标签请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SaveTagRequest {
+    pub name: String,
+}
+
+/// 设置资源标签请求（整体替换，不存在的标签自动创建）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SetResourceTagsRequest {
+    pub tags: Vec<String>,
+}
+
+/// 标签名规范化为 slug：小写，空白、`-`、`_` 合并为单个 `-`，去掉其他符号
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.trim().chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if (c.is_whitespace() || c == '-' || c == '_') && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("  Unreal Engine 5 "), "unreal-engine-5");
+        assert_eq!(slugify("low_poly -- assets!"), "low-poly-assets");
+        assert_eq!(slugify("像素 风格"), "像素-风格");
+        assert_eq!(slugify("!!!"), "");
+    }
+}
@@ -54,6 +54,9 @@ pub struct RegisterRequest {
     pub email: String,
     pub password: String,
     pub verification_code: String, // 邮箱验证码
+    /// 推荐码（为空时取 `ref` 查询参数或推荐码 Cookie）
+    #[serde(default)]
+    pub referral_code: Option<String>,
 }
 
 /// 发送验证码请求
@@ -148,6 +151,7 @@ mod tests {
             email: "test@example.com".to_string(),
             password: "Password123".to_string(),
             verification_code: "123456".to_string(),
+            referral_code: None,
         };
 
         assert_eq!(req.username, "testuser");
@@ -0,0 +1,118 @@
+//! 收藏与心愿单模型
+//!
+//! 用户可收藏资源，也可创建公开或私有的命名心愿单。收藏或加入任一心愿单的资源记为"已保存"，
+//! 资源降价时邮件通知保存过的用户。
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use salvo_oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 心愿单名称最大长度
+pub const MAX_WISHLIST_NAME_LEN: usize = 100;
+
+/// 每个用户最多心愿单数量
+pub const MAX_WISHLISTS_PER_USER: i64 = 50;
+
+/// 心愿单
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Wishlist {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub is_public: bool,
+    pub item_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 收藏或心愿单中的资源
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct SavedResource {
+    pub resource_id: i64,
+    pub title: String,
+    pub price: Decimal,
+    pub thumbnail_url: Option<String>,
+    pub rating_avg: Decimal,
+    pub rating_count: i32,
+    pub saved_at: DateTime<Utc>,
+}
+
+/// 心愿单详情
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WishlistDetail {
+    #[serde(flatten)]
+    pub wishlist: Wishlist,
+    pub items: Vec<SavedResource>,
+}
+
+/// 创建心愿单请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateWishlistRequest {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub is_public: bool,
+}
+
+/// 更新心愿单请求
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct UpdateWishlistRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub is_public: Option<bool>,
+}
+
+/// 降价通知（后台任务领取）
+#[derive(Debug, Clone, FromRow)]
+pub struct PriceDropNotice {
+    pub user_id: i64,
+    pub email: String,
+    pub username: String,
+    pub resource_id: i64,
+    pub title: String,
+    pub old_price: Decimal,
+    pub new_price: Decimal,
+}
+
+/// 最受期待资源（管理员报表）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct MostWishedItem {
+    pub resource_id: i64,
+    pub title: String,
+    pub price: Decimal,
+    pub provider_id: Option<i64>,
+    /// 保存过该资源的用户数
+    pub saved_count: i64,
+    /// 报表区间内新增保存数
+    pub recent_saves: i64,
+    /// 已保存且已购买的用户数
+    pub purchased_count: i64,
+}
+
+/// 降价百分比（保留整数），原价为 0 时返回 0
+pub fn price_drop_percent(old_price: Decimal, new_price: Decimal) -> Decimal {
+    if old_price <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+    ((old_price - new_price) / old_price * Decimal::from(100)).round_dp(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_drop_percent() {
+        assert_eq!(
+            price_drop_percent(Decimal::new(2000, 2), Decimal::new(1500, 2)),
+            Decimal::from(25)
+        );
+        assert_eq!(
+            price_drop_percent(Decimal::ZERO, Decimal::ZERO),
+            Decimal::ZERO
+        );
+    }
+}
@@ -0,0 +1,394 @@
+//! 支付宝服务
+//!
+//! 配置从 alipay_configs 数据库表读取。
+//! - 电脑网站支付（alipay.trade.page.pay）：返回跳转收银台的 URL
+//! - 当面付扫码（alipay.trade.precreate）：返回二维码内容
+//! - 请求参数与异步通知均使用 RSA2（SHA256withRSA）签名
+
+use crate::config_service::AlipayDbConfig;
+use chrono::{FixedOffset, Utc};
+use reqwest::Client;
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_common::signature::{rsa_sha256_sign, rsa_sha256_verify};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use tracing::{info, warn};
+
+/// 支付宝正式网关
+const ALIPAY_GATEWAY: &str = "https://openapi.alipay.com/gateway.do";
+/// 支付宝沙箱网关
+const ALIPAY_SANDBOX_GATEWAY: &str = "https://openapi-sandbox.dl.alipaydev.com/gateway.do";
+
+/// 支付宝服务
+pub struct AlipayService {
+    client: Client,
+    config: Option<AlipayDbConfig>,
+}
+
+impl AlipayService {
+    /// 创建支付宝服务实例（可无配置，将使用 mock 模式）
+    pub fn new(config: Option<AlipayDbConfig>) -> Self {
+        Self {
+            client: Client::builder()
+                .use_rustls_tls()
+                .timeout(std::time::Duration::from_secs(10))
+                .build()
+                .expect("valid reqwest client"),
+            config,
+        }
+    }
+
+    /// 是否已配置
+    pub fn is_configured(&self) -> bool {
+        self.config.as_ref().is_some_and(|c| !c.app_id.is_empty())
+    }
+
+    /// 按配置费率估算通道手续费（以订单金额计，保留两位小数）
+    pub fn fee_for(&self, amount: Decimal) -> Decimal {
+        self.config
+            .as_ref()
+            .map(|c| (amount * c.fee_rate).round_dp(2))
+            .unwrap_or(Decimal::ZERO)
+    }
+
+    /// 获取网关地址（sandbox 或 live）
+    fn gateway_url(&self) -> &str {
+        match &self.config {
+            Some(c) if !c.sandbox => ALIPAY_GATEWAY,
+            _ => ALIPAY_SANDBOX_GATEWAY,
+        }
+    }
+
+    /// 将订单金额换算为支付宝结算金额（CNY，保留两位小数）
+    pub fn to_cny(&self, amount: Decimal) -> Decimal {
+        let rate = self
+            .config
+            .as_ref()
+            .map(|c| c.exchange_rate)
+            .filter(|r| *r > Decimal::ZERO)
+            .unwrap_or(Decimal::ONE);
+        (amount * rate).round_dp(2)
+    }
+
+    /// 校验异步通知中的 total_amount 是否与订单金额一致
+    pub fn amount_matches(&self, order_amount: Decimal, total_amount: &str) -> bool {
+        total_amount
+            .parse::<Decimal>()
+            .is_ok_and(|v| v == self.to_cny(order_amount))
+    }
+
+    /// 构造公共请求参数
+    fn common_params(config: &AlipayDbConfig, method: &str) -> BTreeMap<String, String> {
+        let beijing = FixedOffset::east_opt(8 * 3600).expect("valid UTC+8 offset");
+        let timestamp = Utc::now()
+            .with_timezone(&beijing)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+
+        let mut params = BTreeMap::new();
+        params.insert("app_id".to_string(), config.app_id.clone());
+        params.insert("method".to_string(), method.to_string());
+        params.insert("format".to_string(), "JSON".to_string());
+        params.insert("charset".to_string(), "utf-8".to_string());
+        params.insert("sign_type".to_string(), "RSA2".to_string());
+        params.insert("timestamp".to_string(), timestamp);
+        params.insert("version".to_string(), "1.0".to_string());
+        if !config.notify_url.is_empty() {
+            params.insert("notify_url".to_string(), config.notify_url.clone());
+        }
+        params
+    }
+
+    /// 对请求参数签名，并写入 sign 字段
+    fn sign_params(
+        config: &AlipayDbConfig,
+        params: &mut BTreeMap<String, String>,
+    ) -> Result<(), RswsError> {
+        let content = build_sign_content(params, &["sign"]);
+        let sign = rsa_sha256_sign(&config.private_key, content.as_bytes())?;
+        params.insert("sign".to_string(), sign);
+        Ok(())
+    }
+
+    /// 电脑网站支付：生成跳转支付宝收银台的 URL
+    pub fn page_pay_url(
+        &self,
+        order_id: i64,
+        amount: Decimal,
+        subject: &str,
+    ) -> Result<String, RswsError> {
+        let config = match &self.config {
+            Some(c) if !c.app_id.is_empty() => c,
+            _ => {
+                // Mock 模式
+                info!("Creating mock Alipay page pay for order_id: {}", order_id);
+                return Ok(format!(
+                    "http://localhost:3000/payment/success?out_trade_no={}",
+                    order_id
+                ));
+            }
+        };
+
+        let biz_content = serde_json::json!({
+            "out_trade_no": order_id.to_string(),
+            "total_amount": format!("{:.2}", self.to_cny(amount)),
+            "subject": subject,
+            "product_code": "FAST_INSTANT_TRADE_PAY",
+        });
+
+        let mut params = Self::common_params(config, "alipay.trade.page.pay");
+        if !config.return_url.is_empty() {
+            params.insert("return_url".to_string(), config.return_url.clone());
+        }
+        params.insert("biz_content".to_string(), biz_content.to_string());
+        Self::sign_params(config, &mut params)?;
+
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(params.iter())
+            .finish();
+
+        Ok(format!("{}?{}", self.gateway_url(), query))
+    }
+
+    /// 当面付扫码：预下单并返回二维码内容
+    pub async fn precreate(
+        &self,
+        order_id: i64,
+        amount: Decimal,
+        subject: &str,
+    ) -> Result<String, RswsError> {
+        let config = match &self.config {
+            Some(c) if !c.app_id.is_empty() => c,
+            _ => {
+                info!("Creating mock Alipay precreate for order_id: {}", order_id);
+                return Ok(format!("https://qr.alipay.com/mock{}", order_id));
+            }
+        };
+
+        let biz_content = serde_json::json!({
+            "out_trade_no": order_id.to_string(),
+            "total_amount": format!("{:.2}", self.to_cny(amount)),
+            "subject": subject,
+        });
+
+        let mut params = Self::common_params(config, "alipay.trade.precreate");
+        params.insert("biz_content".to_string(), biz_content.to_string());
+        Self::sign_params(config, &mut params)?;
+
+        let resp = self
+            .client
+            .post(self.gateway_url())
+            .form(&params)
+            .send()
+            .await
+            .map_err(|e| {
+                RswsError::business_with_message(
+                    ErrorCode::ALIPAY_REQUEST_FAILED,
+                    format!("Alipay precreate request failed: {}", e),
+                )
+            })?;
+
+        let body = resp.text().await.map_err(|e| {
+            RswsError::business_with_message(
+                ErrorCode::ALIPAY_REQUEST_FAILED,
+                format!("Failed to read Alipay response: {}", e),
+            )
+        })?;
+
+        // 响应验签：对 xxx_response 节点的原始 JSON 文本验签
+        let node =
+            extract_response_node(&body, "alipay_trade_precreate_response").ok_or_else(|| {
+                RswsError::business_with_message(
+                    ErrorCode::ALIPAY_REQUEST_FAILED,
+                    "Malformed Alipay response",
+                )
+            })?;
+        let json: Value = serde_json::from_str(&body)
+            .map_err(|e| RswsError::internal(format!("Failed to parse Alipay response: {}", e)))?;
+        let sign = json["sign"].as_str().unwrap_or("");
+        if !rsa_sha256_verify(&config.alipay_public_key, node.as_bytes(), sign)? {
+            warn!("Alipay precreate response signature invalid");
+            return Err(RswsError::business(ErrorCode::ALIPAY_REQUEST_FAILED));
+        }
+
+        let data = &json["alipay_trade_precreate_response"];
+        if data["code"].as_str() != Some("10000") {
+            warn!(
+                "Alipay precreate failed: {} {} ({})",
+                data["code"], data["sub_code"], data["sub_msg"]
+            );
+            return Err(RswsError::business_with_message(
+                ErrorCode::ALIPAY_REQUEST_FAILED,
+                data["sub_msg"]
+                    .as_str()
+                    .or(data["msg"].as_str())
+                    .unwrap_or("Alipay precreate failed")
+                    .to_string(),
+            ));
+        }
+
+        let qr_code = data["qr_code"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| RswsError::internal("Alipay response missing qr_code"))?;
+
+        info!("Alipay precreate succeeded for order {}", order_id);
+        Ok(qr_code)
+    }
+
+    /// 验证异步通知签名
+    ///
+    /// 未配置时拒绝所有通知（mock 模式下不存在真实支付）。
+    /// 验签通过后还会校验 app_id 是否属于本应用。
+    pub fn verify_notify(&self, params: &HashMap<String, String>) -> Result<bool, RswsError> {
+        let config = match &self.config {
+            Some(c) if !c.app_id.is_empty() => c,
+            _ => {
+                warn!("Alipay not configured — rejecting notify");
+                return Ok(false);
+            }
+        };
+
+        let sign = match params.get("sign") {
+            Some(s) if !s.is_empty() => s,
+            _ => return Ok(false),
+        };
+
+        let sorted: BTreeMap<String, String> =
+            params.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        let content = build_sign_content(&sorted, &["sign", "sign_type"]);
+
+        if !rsa_sha256_verify(&config.alipay_public_key, content.as_bytes(), sign)? {
+            return Ok(false);
+        }
+
+        if params.get("app_id").map(|s| s.as_str()) != Some(config.app_id.as_str()) {
+            warn!("Alipay notify app_id mismatch: {:?}", params.get("app_id"));
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+}
+
+// ==================== 签名辅助 ====================
+
+/// 构造待签名字符串
+///
+/// 按 key 升序排列，跳过空值和 `exclude` 中的字段，以 `key=value` 形式用 `&` 连接，
+/// value 保持原值（不做 URL 编码）。
+pub fn build_sign_content(params: &BTreeMap<String, String>, exclude: &[&str]) -> String {
+    params
+        .iter()
+        .filter(|(k, v)| !v.is_empty() && !exclude.contains(&k.as_str()))
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// 从网关响应中截取指定节点的原始 JSON 文本（用于响应验签）
+///
+/// 支付宝对响应节点的原始字节签名，重新序列化会改变字段顺序或转义，
+/// 因此必须直接截取原文。
+pub fn extract_response_node<'a>(body: &'a str, node: &str) -> Option<&'a str> {
+    let key = format!("\"{}\"", node);
+    let key_pos = body.find(&key)?;
+    let rest = &body[key_pos + key.len()..];
+    let colon = rest.find(':')?;
+    let value = rest[colon + 1..].trim_start();
+    let start = body.len() - value.len();
+
+    if !value.starts_with('{') {
+        return None;
+    }
+
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in value.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&body[start..start + i + 1]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+// ==================== 单元测试 ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_sign_content_doc_example() {
+        // 支付宝开放平台「自行实现签名」文档示例
+        let mut params = BTreeMap::new();
+        params.insert(
+            "method".to_string(),
+            "alipay.mobile.public.menu.add".to_string(),
+        );
+        params.insert("charset".to_string(), "GBK".to_string());
+        params.insert("sign_type".to_string(), "RSA2".to_string());
+        params.insert("timestamp".to_string(), "2014-07-24 03:07:50".to_string());
+        params.insert(
+            "biz_content".to_string(),
+            r#"{"button":[{"actionParam":"ZFB_HFCZ","actionType":"out","name":"话费充值"}]}"#
+                .to_string(),
+        );
+        params.insert("app_id".to_string(), "2014072300007148".to_string());
+        params.insert("version".to_string(), "1.0".to_string());
+        params.insert("sign".to_string(), "ignored".to_string());
+        params.insert("empty".to_string(), String::new());
+
+        let content = build_sign_content(&params, &["sign"]);
+        assert_eq!(
+            content,
+            r#"app_id=2014072300007148&biz_content={"button":[{"actionParam":"ZFB_HFCZ","actionType":"out","name":"话费充值"}]}&charset=GBK&method=alipay.mobile.public.menu.add&sign_type=RSA2&timestamp=2014-07-24 03:07:50&version=1.0"#
+        );
+
+        // 异步通知验签时 sign_type 也不参与
+        let notify_content = build_sign_content(&params, &["sign", "sign_type"]);
+        assert!(!notify_content.contains("sign_type"));
+    }
+
+    #[test]
+    fn test_extract_response_node() {
+        let body = r#"{"alipay_trade_precreate_response":{"code":"10000","msg":"Success","out_trade_no":"6823789339978248","qr_code":"https://qr.alipay.com/bavh4wjlxf12tper3a","memo":"a \"}\" b"},"sign":"abc"}"#;
+        let node = extract_response_node(body, "alipay_trade_precreate_response").unwrap();
+        assert_eq!(
+            node,
+            r#"{"code":"10000","msg":"Success","out_trade_no":"6823789339978248","qr_code":"https://qr.alipay.com/bavh4wjlxf12tper3a","memo":"a \"}\" b"}"#
+        );
+
+        assert!(extract_response_node(body, "missing_response").is_none());
+        assert!(extract_response_node(r#"{"x_response":{"a":1"#, "x_response").is_none());
+    }
+
+    #[test]
+    fn test_verify_notify_unconfigured() {
+        let service = AlipayService::new(None);
+        let mut params = HashMap::new();
+        params.insert("sign".to_string(), "abc".to_string());
+        assert!(!service.verify_notify(&params).unwrap());
+    }
+}
@@ -1,110 +1,394 @@
 //! 佣金服务
+//!
+//! - 创作者分成：订单支付后按分成规则计算并记录（referrer_id 为空），无规则命中时
+//!   沿用 resources.commission_rate；规则由后台维护，可按订单模拟
+//! - 推荐佣金：订单支付后按推荐佣金比例记录（referrer_id 非空），由平台收入承担
+//!
+//! 佣金记录先为 pending，冻结期（system_configs 的 `commission_hold_days`）过后由后台任务
+//! 结算到收款人余额；冻结期内订单退款则作废。
 
+use crate::config_service::ConfigService;
+use chrono::Utc;
 use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_db::{CommissionRepository, LedgerRepository};
+use rsws_model::commission::{
+    compute_commission, matching_rules, normalize_creator_tier, CommissionRule,
+    CommissionSimulation, CreateCommissionRuleRequest, SimulateCommissionRequest,
+    UpdateCommissionRuleRequest, COMMISSION_RULE_TYPES, RULE_TYPE_PERCENTAGE,
+};
+use rust_decimal::Decimal;
 use sqlx::PgPool;
-use tracing::info;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// 佣金冻结天数配置键
+pub const COMMISSION_HOLD_DAYS_CONFIG_KEY: &str = "commission_hold_days";
+
+/// 默认佣金冻结天数
+const DEFAULT_HOLD_DAYS: i64 = 7;
+
+/// 每轮结算的订单数上限
+const SETTLE_BATCH_SIZE: i64 = 100;
 
 pub struct CommissionService {
     pool: PgPool,
+    repo: CommissionRepository,
+    config_service: Arc<ConfigService>,
 }
 
 impl CommissionService {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, config_service: Arc<ConfigService>) -> Self {
+        Self {
+            repo: CommissionRepository::new(pool.clone()),
+            pool,
+            config_service,
+        }
     }
 
-    /// 计算佣金金额。amount 是以分为单位的订单金额，rate 是万分比。
-    pub fn calculate(&self, order_id: i64, amount: i64, rate: i64) -> i64 {
-        let commission = amount * rate / 10000;
-        info!(
-            "Commission calc: order={} amount={} rate={} => {}",
-            order_id, amount, rate, commission
-        );
-        commission
-    }
-
-    /// 结算并记录佣金。
-    /// 从 orders 表取 referrer_id，从 resources 表取 commission_rate，
-    /// 计算后写入 commission_records。若无推荐人或佣金率为 0，跳过。
-    pub async fn settle_commission(&self, order_id: i64) -> Result<(), RswsError> {
-        let row: Option<(i64, i64, Option<i64>)> = sqlx::query_as(
-            r#"
-            SELECT o.amount, r.commission_rate, o.referrer_id
-            FROM orders o
-            JOIN resources r ON r.id = o.resource_id
-            WHERE o.id = $1
-            "#,
-        )
-        .bind(order_id)
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(|e| RswsError::internal(format!("Query commission data: {}", e)))?;
-
-        let (order_amount, commission_rate, referrer_id) = match row {
-            Some(r) => r,
-            None => {
-                info!("Order {} not found for commission", order_id);
-                return Ok(());
-            }
-        };
+    // ==================== 分成规则 ====================
 
-        let referrer_id = match referrer_id {
-            Some(id) if id > 0 => id,
-            _ => {
-                info!("Order {} has no referrer, skipping", order_id);
-                return Ok(());
-            }
+    /// 获取全部规则
+    pub async fn list_rules(&self) -> Result<Vec<CommissionRule>, RswsError> {
+        self.repo.list_rules().await
+    }
+
+    /// 创建规则
+    pub async fn create_rule(
+        &self,
+        req: &CreateCommissionRuleRequest,
+    ) -> Result<CommissionRule, RswsError> {
+        let rule_type = req.rule_type.as_deref().unwrap_or(RULE_TYPE_PERCENTAGE);
+        let creator_tier = normalize_tier(req.creator_tier.as_deref())?;
+        let now = Utc::now();
+        let candidate = CommissionRule {
+            id: 0,
+            name: req.name.clone(),
+            rule_type: rule_type.to_string(),
+            rate: req.rate,
+            category_id: req.category_id,
+            creator_tier: creator_tier.clone(),
+            min_amount: req.min_amount,
+            max_amount: req.max_amount,
+            starts_at: req.starts_at,
+            ends_at: req.ends_at,
+            priority: req.priority,
+            min_commission: req.min_commission,
+            max_commission: req.max_commission,
+            is_active: true,
+            description: req.description.clone(),
+            created_at: now,
+            updated_at: now,
         };
+        self.validate(&candidate).await?;
+
+        let rule = self
+            .repo
+            .create_rule(req, rule_type, creator_tier.as_deref())
+            .await?;
+        info!("Commission rule created: {} ({})", rule.id, rule.name);
+        Ok(rule)
+    }
 
-        if commission_rate <= 0 {
-            info!(
-                "Order {} commission_rate={}, skipping",
-                order_id, commission_rate
-            );
-            return Ok(());
+    /// 更新规则（按更新后的完整规则校验）
+    pub async fn update_rule(
+        &self,
+        id: i64,
+        req: &UpdateCommissionRuleRequest,
+    ) -> Result<CommissionRule, RswsError> {
+        let mut candidate = self
+            .repo
+            .find_rule(id)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::COMMISSION_RULE_NOT_FOUND))?;
+        let creator_tier = normalize_tier(req.creator_tier.as_deref())?;
+
+        if let Some(name) = &req.name {
+            candidate.name = name.clone();
+        }
+        if let Some(rule_type) = &req.rule_type {
+            candidate.rule_type = rule_type.clone();
         }
+        candidate.rate = req.rate.unwrap_or(candidate.rate);
+        candidate.category_id = req.category_id.or(candidate.category_id);
+        candidate.creator_tier = creator_tier.clone().or(candidate.creator_tier);
+        candidate.min_amount = req.min_amount.or(candidate.min_amount);
+        candidate.max_amount = req.max_amount.or(candidate.max_amount);
+        candidate.starts_at = req.starts_at.or(candidate.starts_at);
+        candidate.ends_at = req.ends_at.or(candidate.ends_at);
+        candidate.min_commission = req.min_commission.or(candidate.min_commission);
+        candidate.max_commission = req.max_commission.or(candidate.max_commission);
+        self.validate(&candidate).await?;
 
-        let commission_amount = self.calculate(order_id, order_amount, commission_rate);
-        if commission_amount <= 0 {
-            info!("Commission amount=0 for order {}, skipping", order_id);
-            return Ok(());
+        let rule = self
+            .repo
+            .update_rule(id, req, creator_tier.as_deref())
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::COMMISSION_RULE_NOT_FOUND))?;
+        info!("Commission rule updated: {} ({})", rule.id, rule.name);
+        Ok(rule)
+    }
+
+    /// 删除规则
+    pub async fn delete_rule(&self, id: i64) -> Result<(), RswsError> {
+        if !self.repo.delete_rule(id).await? {
+            return Err(RswsError::business(ErrorCode::COMMISSION_RULE_NOT_FOUND));
         }
+        info!("Commission rule deleted: {}", id);
+        Ok(())
+    }
 
-        sqlx::query(
-            r#"
-            INSERT INTO commission_records
-                (order_id, user_id, referrer_id, order_amount, commission_amount, commission_rate, status)
-            VALUES ($1, 0, $2, $3, $4, $5, 'pending')
-            ON CONFLICT (order_id) DO NOTHING
-            "#,
-        )
-            .bind(order_id)
-            .bind(referrer_id)
-            .bind(order_amount)
-            .bind(commission_amount)
-            .bind(commission_rate)
-            .execute(&self.pool)
+    /// 设置创作者等级，返回规范化后的等级
+    pub async fn set_creator_tier(
+        &self,
+        user_id: i64,
+        tier: &str,
+        admin_id: i64,
+    ) -> Result<String, RswsError> {
+        let tier = normalize_tier(Some(tier))?.unwrap_or_default();
+        if !self.repo.set_creator_tier(user_id, &tier, admin_id).await? {
+            return Err(RswsError::business(ErrorCode::USER_NOT_FOUND));
+        }
+        info!("Creator {} tier set to {} by {}", user_id, tier, admin_id);
+        Ok(tier)
+    }
+
+    /// 获取创作者等级
+    pub async fn creator_tier(&self, user_id: i64) -> Result<String, RswsError> {
+        self.repo.creator_tier(user_id).await
+    }
+
+    /// 模拟订单（或资源按指定金额、时间下单）的分成计算，不写入任何记录
+    pub async fn simulate(
+        &self,
+        req: &SimulateCommissionRequest,
+    ) -> Result<CommissionSimulation, RswsError> {
+        if req.amount.is_some_and(|a| a < Decimal::ZERO) {
+            return Err(RswsError::business_with_message(
+                ErrorCode::INVALID_PARAMETER,
+                "amount must not be negative",
+            ));
+        }
+
+        let mut conn = self
+            .pool
+            .acquire()
             .await
-            .map_err(|e| RswsError::internal(format!("Insert commission: {}", e)))?;
+            .map_err(|e| RswsError::internal(format!("Failed to acquire connection: {}", e)))?;
 
-        info!(
-            "Commission settled: order={} referrer={} amount={} rate={}",
-            order_id, referrer_id, commission_amount, commission_rate
-        );
+        let context = match (req.order_id, req.resource_id) {
+            (Some(order_id), _) => {
+                CommissionRepository::order_context_in_tx(&mut *conn, order_id).await?
+            }
+            (None, Some(resource_id)) => {
+                CommissionRepository::resource_context_in_tx(
+                    &mut *conn,
+                    resource_id,
+                    req.amount,
+                    req.at.unwrap_or_else(Utc::now),
+                )
+                .await?
+            }
+            (None, None) => {
+                return Err(RswsError::business_with_message(
+                    ErrorCode::INVALID_PARAMETER,
+                    "order_id or resource_id is required",
+                ))
+            }
+        }
+        .ok_or_else(|| {
+            RswsError::business_with_message(
+                ErrorCode::INVALID_PARAMETER,
+                "Order or resource not found, or it has no creator",
+            )
+        })?;
+
+        let rules = CommissionRepository::active_rules_in_tx(&mut *conn).await?;
+        let matched_rule_ids = matching_rules(&rules, &context)
+            .iter()
+            .map(|r| r.id)
+            .collect();
+        let quote = compute_commission(&rules, &context);
+
+        Ok(CommissionSimulation {
+            context,
+            matched_rule_ids,
+            quote,
+        })
+    }
+
+    /// 校验规则（含分类是否存在）
+    async fn validate(&self, rule: &CommissionRule) -> Result<(), RswsError> {
+        validate_rule(rule)?;
+        if let Some(category_id) = rule.category_id {
+            if !self.repo.category_exists(category_id).await? {
+                return Err(RswsError::business_with_message(
+                    ErrorCode::COMMISSION_RULE_INVALID,
+                    format!("Category not found: {}", category_id),
+                ));
+            }
+        }
         Ok(())
     }
 
-    /// 将 pending 佣金记录标记为 settled
+    // ==================== 分成记录与结算 ====================
+
+    /// 将 pending 佣金记录标记为 settled，并在总账中记入收款人余额
     pub async fn settle(&self, order_id: i64) -> Result<(), RswsError> {
-        sqlx::query(
-            "UPDATE commission_records SET status = 'settled', settled_at = NOW() WHERE order_id = $1 AND status = 'pending'",
-        )
-            .bind(order_id)
-            .execute(&self.pool)
-            .await
-            .map_err(|e| RswsError::internal(format!("Settle commission: {}", e)))?;
+        let settled = LedgerRepository::new(self.pool.clone())
+            .settle_commissions(order_id)
+            .await?;
 
-        info!("Commission settled for order: {}", order_id);
+        info!(
+            "Commission settled for order: {} ({} records)",
+            order_id, settled
+        );
         Ok(())
     }
+
+    /// 结算冻结期已过的佣金，返回结算的订单数
+    pub async fn settle_due(&self) -> Result<usize, RswsError> {
+        let hold_days = self
+            .config_service
+            .get_int(COMMISSION_HOLD_DAYS_CONFIG_KEY)
+            .await?
+            .unwrap_or(DEFAULT_HOLD_DAYS)
+            .max(0);
+
+        let ledger_repo = LedgerRepository::new(self.pool.clone());
+        let order_ids = ledger_repo
+            .due_commission_orders(hold_days, SETTLE_BATCH_SIZE)
+            .await?;
+
+        let mut settled = 0;
+        for order_id in order_ids {
+            match ledger_repo.settle_commissions(order_id).await {
+                Ok(_) => settled += 1,
+                Err(e) => error!("Failed to settle commission for order {}: {}", order_id, e),
+            }
+        }
+        Ok(settled)
+    }
+
+    /// 启动后台结算任务
+    pub fn start_background(self: Arc<Self>, interval_secs: u64) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+                match self.settle_due().await {
+                    Ok(0) => {}
+                    Ok(n) => info!("Settled commissions for {} orders", n),
+                    Err(e) => error!("Commission settlement task failed: {}", e),
+                }
+            }
+        });
+    }
+}
+
+/// 规范化创作者等级，格式不合法时报错
+fn normalize_tier(tier: Option<&str>) -> Result<Option<String>, RswsError> {
+    tier.map(|t| {
+        normalize_creator_tier(t).ok_or_else(|| {
+            RswsError::business_with_message(
+                ErrorCode::COMMISSION_RULE_INVALID,
+                format!("Invalid creator tier: {}", t),
+            )
+        })
+    })
+    .transpose()
+}
+
+/// 校验规则字段：类型、比例、金额区间、生效时间与保底 / 封顶
+fn validate_rule(rule: &CommissionRule) -> Result<(), RswsError> {
+    let invalid = |message: String| -> Result<(), RswsError> {
+        Err(RswsError::business_with_message(
+            ErrorCode::COMMISSION_RULE_INVALID,
+            message,
+        ))
+    };
+    let negative = |v: Option<Decimal>| v.is_some_and(|v| v < Decimal::ZERO);
+
+    if rule.name.trim().is_empty() {
+        return invalid("Rule name is required".to_string());
+    }
+    if !COMMISSION_RULE_TYPES.contains(&rule.rule_type.as_str()) {
+        return invalid(format!("Unknown rule_type: {}", rule.rule_type));
+    }
+    if rule.rate < Decimal::ZERO || rule.rate > Decimal::ONE {
+        return invalid("rate must be between 0 and 1".to_string());
+    }
+    if negative(rule.min_amount) || negative(rule.max_amount) {
+        return invalid("Price band must not be negative".to_string());
+    }
+    if let (Some(min), Some(max)) = (rule.min_amount, rule.max_amount) {
+        if min >= max {
+            return invalid("min_amount must be less than max_amount".to_string());
+        }
+    }
+    if let (Some(start), Some(end)) = (rule.starts_at, rule.ends_at) {
+        if start >= end {
+            return invalid("starts_at must be earlier than ends_at".to_string());
+        }
+    }
+    if negative(rule.min_commission) || negative(rule.max_commission) {
+        return invalid("Commission floor and cap must not be negative".to_string());
+    }
+    if let (Some(floor), Some(cap)) = (rule.min_commission, rule.max_commission) {
+        if floor > cap {
+            return invalid("min_commission must not exceed max_commission".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule() -> CommissionRule {
+        CommissionRule {
+            id: 1,
+            name: "Default".to_string(),
+            rule_type: RULE_TYPE_PERCENTAGE.to_string(),
+            rate: Decimal::new(70, 2),
+            category_id: None,
+            creator_tier: None,
+            min_amount: None,
+            max_amount: None,
+            starts_at: None,
+            ends_at: None,
+            priority: 0,
+            min_commission: None,
+            max_commission: None,
+            is_active: true,
+            description: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_validate_rule() {
+        assert!(validate_rule(&rule()).is_ok());
+
+        let mut r = rule();
+        r.rule_type = "fixed".to_string();
+        assert!(validate_rule(&r).is_err());
+
+        let mut r = rule();
+        r.rate = Decimal::new(15, 1);
+        assert!(validate_rule(&r).is_err());
+
+        let mut r = rule();
+        r.min_amount = Some(Decimal::new(100, 0));
+        r.max_amount = Some(Decimal::new(50, 0));
+        assert!(validate_rule(&r).is_err());
+
+        let mut r = rule();
+        r.min_commission = Some(Decimal::new(10, 0));
+        r.max_commission = Some(Decimal::new(5, 0));
+        assert!(validate_rule(&r).is_err());
+    }
 }
@@ -3,9 +3,11 @@
 //! 提供系统配置的读取和写入。
 //! 除 server/database/redis 三个连接配置外，所有业务配置均从数据库读取。
 
+use rsws_common::email::EmailService;
 use rsws_common::error::RswsError;
 use rsws_db::RedisService;
 use sqlx::PgPool;
+use std::sync::Arc;
 use tracing::warn;
 
 /// PayPal 配置（从 paypal_configs 表读取）
@@ -25,6 +27,40 @@ pub struct PayPalDbConfig {
     pub fee_rate: rust_decimal::Decimal,
 }
 
+/// 支付宝配置（从 alipay_configs 表读取）
+#[derive(Debug, Clone)]
+pub struct AlipayDbConfig {
+    pub app_id: String,
+    pub private_key: String,
+    pub alipay_public_key: String,
+    pub sandbox: bool,
+    pub notify_url: String,
+    pub return_url: String,
+    /// 订单金额 → 人民币的换算比例（支付宝仅以 CNY 结算）
+    pub exchange_rate: rust_decimal::Decimal,
+    pub min_amount: rust_decimal::Decimal,
+    pub max_amount: rust_decimal::Decimal,
+    pub fee_rate: rust_decimal::Decimal,
+}
+
+/// 微信支付配置（从 wechatpay_configs 表读取）
+#[derive(Debug, Clone)]
+pub struct WechatPayDbConfig {
+    pub mch_id: String,
+    pub app_id: String,
+    pub merchant_serial_no: String,
+    pub merchant_private_key: String,
+    pub api_v3_key: String,
+    pub platform_public_key: String,
+    pub platform_public_key_id: Option<String>,
+    pub notify_url: String,
+    /// 订单金额 → 人民币的换算比例（微信支付仅以 CNY 结算）
+    pub exchange_rate: rust_decimal::Decimal,
+    pub min_amount: rust_decimal::Decimal,
+    pub max_amount: rust_decimal::Decimal,
+    pub fee_rate: rust_decimal::Decimal,
+}
+
 /// 区块链配置（从 blockchain_configs 表读取）
 #[derive(Debug, Clone)]
 pub struct BlockchainDbConfig {
@@ -54,6 +90,30 @@ pub struct EmailDbConfig {
     pub reply_to: Option<String>,
 }
 
+impl EmailDbConfig {
+    /// 是否为开发模式（provider 为 development/dev/mock，只打印日志，不走 SMTP）
+    pub fn is_dev_mode(&self) -> bool {
+        matches!(
+            self.provider.to_lowercase().as_str(),
+            "development" | "dev" | "mock"
+        )
+    }
+
+    /// 构建 SMTP 邮件服务（开发模式或配置无效时返回 None，调用方只打印日志）
+    pub fn smtp_service(&self) -> Option<Arc<EmailService>> {
+        if self.is_dev_mode() {
+            return None;
+        }
+        let email_config = rsws_common::email::EmailConfig {
+            smtp_server: self.host.clone(),
+            smtp_username: self.username.clone(),
+            smtp_password: self.password.clone(),
+            from_email: self.from_email.clone(),
+        };
+        EmailService::new(&email_config).ok().map(Arc::new)
+    }
+}
+
 /// USDT 监听配置（从 usdt_listen_configs 表读取）
 #[derive(Debug, Clone)]
 pub struct UsdtListenDbConfig {
@@ -86,6 +146,38 @@ type PayPalConfigRow = (
     rust_decimal::Decimal,
 );
 
+/// 支付宝配置查询结果行（10 列）
+#[allow(clippy::type_complexity)]
+type AlipayConfigRow = (
+    String,
+    String,
+    String,
+    bool,
+    String,
+    String,
+    rust_decimal::Decimal,
+    rust_decimal::Decimal,
+    rust_decimal::Decimal,
+    rust_decimal::Decimal,
+);
+
+/// 微信支付配置查询结果行（12 列）
+#[allow(clippy::type_complexity)]
+type WechatPayConfigRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    Option<String>,
+    String,
+    rust_decimal::Decimal,
+    rust_decimal::Decimal,
+    rust_decimal::Decimal,
+    rust_decimal::Decimal,
+);
+
 /// 区块链配置查询结果行（10 列）
 #[allow(clippy::type_complexity)]
 type BlockchainConfigRow = (
@@ -130,7 +222,11 @@ pub struct OssStorageConfig {
     pub region: String,   // region（可选）
     pub prefix: String,   // 存储路径前缀，默认 "resources/"
     pub custom_domain: Option<String>, // 自定义 CDN 域名（可选）
-    pub is_active: bool,  // 是否启用
+    #[serde(default)]
+    pub private_bucket: Option<String>, // 私有区 bucket（可选，未配置时与公开区共用 bucket，key 加 private/ 前缀）
+    #[serde(default)]
+    pub private_dir: Option<String>, // 本地存储私有区目录（可选，默认 storage/private，不能位于静态文件目录或公开区目录下）
+    pub is_active: bool, // 是否启用
 }
 
 impl OssStorageConfig {
@@ -163,6 +259,16 @@ impl OssStorageConfig {
                 .find(|(k, _, _)| k == "storage.custom_domain")
                 .map(|(_, v, _)| Some(v.clone()))
                 .unwrap_or(None),
+            private_bucket: pairs
+                .iter()
+                .find(|(k, _, _)| k == "storage.private_bucket")
+                .map(|(_, v, _)| v.clone())
+                .filter(|v| !v.is_empty()),
+            private_dir: pairs
+                .iter()
+                .find(|(k, _, _)| k == "storage.private_dir")
+                .map(|(_, v, _)| v.clone())
+                .filter(|v| !v.is_empty()),
             is_active: get_bool("storage.enabled", pairs),
         }
     }
@@ -300,6 +406,103 @@ impl ConfigService {
         ))
     }
 
+    // ==================== 支付宝配置 ====================
+
+    /// 从 alipay_configs 表获取活跃的支付宝配置
+    pub async fn get_alipay_config(&self) -> Result<Option<AlipayDbConfig>, RswsError> {
+        let row: Option<AlipayConfigRow> = sqlx::query_as(
+            r#"
+                SELECT app_id, private_key, alipay_public_key, sandbox,
+                       notify_url, return_url, exchange_rate,
+                       min_amount, max_amount, fee_rate
+                FROM alipay_configs
+                WHERE is_active = true
+                ORDER BY id DESC
+                LIMIT 1
+                "#,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to get Alipay config: {}", e)))?;
+
+        Ok(row.map(
+            |(
+                app_id,
+                private_key,
+                alipay_public_key,
+                sandbox,
+                notify_url,
+                return_url,
+                exchange_rate,
+                min_amount,
+                max_amount,
+                fee_rate,
+            )| AlipayDbConfig {
+                app_id,
+                private_key,
+                alipay_public_key,
+                sandbox,
+                notify_url,
+                return_url,
+                exchange_rate,
+                min_amount,
+                max_amount,
+                fee_rate,
+            },
+        ))
+    }
+
+    // ==================== 微信支付配置 ====================
+
+    /// 从 wechatpay_configs 表获取活跃的微信支付配置
+    pub async fn get_wechatpay_config(&self) -> Result<Option<WechatPayDbConfig>, RswsError> {
+        let row: Option<WechatPayConfigRow> = sqlx::query_as(
+            r#"
+                SELECT mch_id, app_id, merchant_serial_no, merchant_private_key,
+                       api_v3_key, platform_public_key, platform_public_key_id,
+                       notify_url, exchange_rate,
+                       min_amount, max_amount, fee_rate
+                FROM wechatpay_configs
+                WHERE is_active = true
+                ORDER BY id DESC
+                LIMIT 1
+                "#,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RswsError::internal(format!("Failed to get WeChat Pay config: {}", e)))?;
+
+        Ok(row.map(
+            |(
+                mch_id,
+                app_id,
+                merchant_serial_no,
+                merchant_private_key,
+                api_v3_key,
+                platform_public_key,
+                platform_public_key_id,
+                notify_url,
+                exchange_rate,
+                min_amount,
+                max_amount,
+                fee_rate,
+            )| WechatPayDbConfig {
+                mch_id,
+                app_id,
+                merchant_serial_no,
+                merchant_private_key,
+                api_v3_key,
+                platform_public_key,
+                platform_public_key_id,
+                notify_url,
+                exchange_rate,
+                min_amount,
+                max_amount,
+                fee_rate,
+            },
+        ))
+    }
+
     // ==================== 区块链配置 ====================
 
     /// 从 blockchain_configs 表获取所有活跃的区块链配置
@@ -521,6 +724,12 @@ impl ConfigService {
         if let Some(ref domain) = config.custom_domain {
             pairs.push(("storage.custom_domain", domain.clone()));
         }
+        if let Some(ref bucket) = config.private_bucket {
+            pairs.push(("storage.private_bucket", bucket.clone()));
+        }
+        if let Some(ref dir) = config.private_dir {
+            pairs.push(("storage.private_dir", dir.clone()));
+        }
         for (key, value) in pairs {
             self.set(key, &value).await?;
         }
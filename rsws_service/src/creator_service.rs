@@ -0,0 +1,114 @@
+//! 创作者收益服务
+//!
+//! 为发布资源的用户提供销售统计、待结算 / 已结算分成与月度对账单（含 CSV 导出）。
+
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_common::utils::csv_field;
+use rsws_db::CommissionRepository;
+use rsws_model::creator::{
+    month_range, CreatorEarningsSummary, MonthlyStatement, ResourceSales, StatementMonth,
+};
+use std::sync::Arc;
+
+/// 对账单月份列表最多返回的月数
+const MAX_STATEMENT_MONTHS: i64 = 36;
+
+/// 创作者收益服务
+pub struct CreatorService {
+    repo: Arc<CommissionRepository>,
+}
+
+impl CreatorService {
+    /// 创建创作者收益服务实例
+    pub fn new(repo: Arc<CommissionRepository>) -> Self {
+        Self { repo }
+    }
+
+    /// 收益概览
+    pub async fn summary(&self, user_id: i64) -> Result<CreatorEarningsSummary, RswsError> {
+        self.repo.summary(user_id).await
+    }
+
+    /// 按资源统计销售
+    pub async fn resource_sales(&self, user_id: i64) -> Result<Vec<ResourceSales>, RswsError> {
+        self.repo.resource_sales(user_id).await
+    }
+
+    /// 有销售的月份及月度汇总
+    pub async fn statement_months(&self, user_id: i64) -> Result<Vec<StatementMonth>, RswsError> {
+        self.repo
+            .statement_months(user_id, MAX_STATEMENT_MONTHS)
+            .await
+    }
+
+    /// 月度对账单
+    pub async fn statement(
+        &self,
+        user_id: i64,
+        month: &str,
+    ) -> Result<MonthlyStatement, RswsError> {
+        let (start, end) = month_range(month).ok_or_else(|| {
+            RswsError::business_with_message(ErrorCode::INVALID_PARAMETER, "month must be YYYY-MM")
+        })?;
+        let lines = self.repo.statement_lines(user_id, start, end).await?;
+        Ok(MonthlyStatement::new(month.trim(), lines))
+    }
+}
+
+/// 导出月度对账单为 CSV（每个订单一行，末行为合计）
+pub fn statement_to_csv(statement: &MonthlyStatement) -> String {
+    let mut csv = String::from(
+        "order_id,paid_at,resource_id,resource_title,gross,platform_commission,net,commission_status\r\n",
+    );
+
+    for line in &statement.lines {
+        let fields = [
+            line.order_id.to_string(),
+            line.paid_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            line.resource_id.to_string(),
+            line.resource_title.clone(),
+            line.gross.to_string(),
+            line.platform_commission.to_string(),
+            line.net.to_string(),
+            line.commission_status.clone().unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+
+    csv.push_str(&format!(
+        "total,{},,,{},{},{},\r\n",
+        statement.month, statement.gross, statement.platform_commission, statement.net
+    ));
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsws_model::creator::StatementLine;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn test_statement_to_csv() {
+        let statement = MonthlyStatement::new(
+            "2026-06",
+            vec![StatementLine {
+                order_id: 42,
+                paid_at: None,
+                resource_id: 7,
+                resource_title: "Icons, vol. 1".to_string(),
+                gross: Decimal::new(1000, 2),
+                platform_commission: Decimal::new(300, 2),
+                net: Decimal::new(700, 2),
+                commission_status: Some("pending".to_string()),
+            }],
+        );
+        let csv = statement_to_csv(&statement);
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(lines[1], "42,,7,\"Icons, vol. 1\",10.00,3.00,7.00,pending");
+        assert_eq!(lines[2], "total,2026-06,,,10.00,3.00,7.00,");
+    }
+}
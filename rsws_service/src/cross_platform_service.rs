@@ -1,222 +1,288 @@
-//! 跨平台服务
+//! 跨平台服务 — 出站事件 Webhook
+//!
+//! 订单支付 / 退款、资源上架等事件推送给管理员配置的订阅端点（ERP 等集成方）：
+//! - 事件先写入 webhook_deliveries 队列（每个匹配的订阅一条），由后台任务投递
+//! - 请求体使用订阅密钥做 HMAC-SHA256 签名
+//! - 失败按指数退避重试，超过最大次数进入死信，管理员可手动重新投递
+//!
+//! 签名方式：`X-Signature = hex(HMAC-SHA256(secret, "{X-Timestamp}.{body}"))`，
+//! 接收方应校验签名并拒绝时间戳过旧的请求，按请求体中的事件 `id` 去重。
 
-use chrono::{DateTime, Utc};
+use chrono::Utc;
 use rsws_common::error::RswsError;
-use serde::{Deserialize, Serialize};
+use rsws_common::error_code::ErrorCode;
+use rsws_common::utils::generate_webhook_secret;
+use rsws_db::EventWebhookRepository;
+use rsws_model::event_webhook::{
+    retry_delay_secs, CreateWebhookSubscriptionRequest, PendingDelivery,
+    UpdateWebhookSubscriptionRequest, WebhookDelivery, WebhookDeliveryQuery, WebhookSubscription,
+    EVENT_TYPES,
+};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::{error, info, warn};
 
-/// 平台配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PlatformConfig {
-    pub platform_name: String,
-    pub api_endpoint: String,
-    pub api_key: Option<String>,
-    pub signing_key: Option<String>,
-    pub webhook_url: Option<String>,
-    pub is_active: bool,
-}
+/// 每轮取出的投递数量
+const BATCH_SIZE: i64 = 50;
 
-/// 同步记录
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SyncRecord {
-    pub id: i64,
-    pub platform_name: String,
-    pub operation_type: String,
-    pub local_id: String,
-    pub remote_id: Option<String>,
-    pub sync_status: String,
-    pub sync_data: Value,
-    pub error_message: Option<String>,
-    pub last_sync_at: Option<DateTime<Utc>>,
-    pub created_at: DateTime<Utc>,
-}
+/// 投递租期（秒），需大于请求超时
+const DELIVERY_LEASE_SECS: i64 = 120;
+
+/// 记录的响应体最大长度
+const MAX_ERROR_LEN: usize = 500;
 
 /// 跨平台服务
 pub struct CrossPlatformService {
-    configs: HashMap<String, PlatformConfig>,
+    repo: Arc<EventWebhookRepository>,
     client: reqwest::Client,
 }
 
 impl CrossPlatformService {
     /// 创建跨平台服务实例
-    pub fn new() -> Self {
+    pub fn new(repo: Arc<EventWebhookRepository>) -> Self {
         let client = reqwest::Client::builder()
             .timeout(Duration::from_secs(30))
             .build()
             .unwrap_or_default();
-        Self {
-            configs: HashMap::new(),
-            client,
-        }
+        Self { repo, client }
     }
 
-    /// 注册平台
-    pub fn register_platform(&mut self, config: PlatformConfig) {
-        info!("Registering platform: {}", config.platform_name);
-        self.configs.insert(config.platform_name.clone(), config);
-    }
+    // ==================== 订阅管理 ====================
 
-    /// 获取平台配置
-    pub fn get_platform_config(&self, platform_name: &str) -> Option<&PlatformConfig> {
-        self.configs.get(platform_name)
+    /// 获取全部订阅
+    pub async fn list_subscriptions(&self) -> Result<Vec<WebhookSubscription>, RswsError> {
+        self.repo.list_subscriptions().await
     }
 
-    /// 同步数据到远程平台
-    ///
-    /// 向目标平台的 API 发送 POST 请求，附带签名认证。
-    /// 重试策略：最多 3 次，指数退避（1s/2s/4s）。
-    pub async fn sync_data(
+    /// 创建订阅，返回订阅及签名密钥明文（仅此一次）
+    pub async fn create_subscription(
         &self,
-        platform_name: &str,
-        operation_type: &str,
-        local_id: &str,
-        data: Value,
-    ) -> Result<SyncRecord, RswsError> {
-        let config = self.configs.get(platform_name).ok_or_else(|| {
-            RswsError::bad_request(format!("Platform not found: {}", platform_name))
-        })?;
-
-        if !config.is_active {
-            return Err(RswsError::bad_request("Platform is not active"));
+        req: &CreateWebhookSubscriptionRequest,
+    ) -> Result<(WebhookSubscription, String), RswsError> {
+        validate_subscription(Some(&req.url), Some(req.event_types.as_slice()))?;
+        if req.name.trim().is_empty() {
+            return Err(RswsError::business_with_message(
+                ErrorCode::WEBHOOK_SUBSCRIPTION_INVALID,
+                "Subscription name is required",
+            ));
         }
 
+        let secret = match req.secret.as_deref().map(str::trim) {
+            Some(s) if !s.is_empty() => s.to_string(),
+            _ => generate_webhook_secret(),
+        };
+        let subscription = self.repo.create_subscription(req, &secret).await?;
         info!(
-            "Syncing data to platform: {}, operation: {}, local_id: {}",
-            platform_name, operation_type, local_id
+            "Webhook subscription created: {} -> {}",
+            subscription.id, subscription.url
         );
+        Ok((subscription, secret))
+    }
 
-        // 构造同步 URL: {api_endpoint}/{operation_type}
-        let url = format!(
-            "{}/{}",
-            config.api_endpoint.trim_end_matches('/'),
-            operation_type
-        );
+    /// 更新订阅（`is_active = false` 暂停投递，已入队的投递仍会发送）
+    pub async fn update_subscription(
+        &self,
+        id: i64,
+        req: &UpdateWebhookSubscriptionRequest,
+    ) -> Result<WebhookSubscription, RswsError> {
+        validate_subscription(req.url.as_deref(), req.event_types.as_deref())?;
+        self.repo
+            .update_subscription(id, req)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::WEBHOOK_SUBSCRIPTION_NOT_FOUND))
+    }
 
-        // 构造请求体
-        let mut body = serde_json::json!({
-            "local_id": local_id,
-            "operation": operation_type,
-            "data": data,
-            "timestamp": Utc::now().to_rfc3339(),
-        });
+    /// 删除订阅
+    pub async fn delete_subscription(&self, id: i64) -> Result<(), RswsError> {
+        if !self.repo.delete_subscription(id).await? {
+            return Err(RswsError::business(
+                ErrorCode::WEBHOOK_SUBSCRIPTION_NOT_FOUND,
+            ));
+        }
+        info!("Webhook subscription deleted: {}", id);
+        Ok(())
+    }
 
-        // 签名：HMAC-SHA256(signing_key, body_json) — 如果配置了 signing_key
-        let mut request = self
-            .client
-            .post(&url)
-            .header("Content-Type", "application/json");
+    // ==================== 事件发布 ====================
 
-        if let Some(ref api_key) = config.api_key {
-            request = request.header("X-Api-Key", api_key);
+    /// 发布事件（写入投递队列，由后台任务发送）
+    pub async fn publish(&self, event_type: &str, data: Value) -> Result<(), RswsError> {
+        let queued = self.repo.enqueue(event_type, None, data).await?;
+        if queued > 0 {
+            info!("Event {} queued for {} subscriber(s)", event_type, queued);
         }
+        Ok(())
+    }
 
-        if let Some(ref signing_key) = config.signing_key {
-            let body_str = serde_json::to_string(&body).map_err(|e| {
-                RswsError::internal(format!("Failed to serialize sync body: {}", e))
-            })?;
-            let signature = hmac_sha256(signing_key.as_bytes(), body_str.as_bytes());
-            request = request.header("X-Signature", signature.clone());
-            body["signature"] = Value::String(signature);
+    /// 发布订单事件（载荷为订单快照）
+    pub async fn publish_order_event(
+        &self,
+        event_type: &str,
+        order_id: i64,
+    ) -> Result<(), RswsError> {
+        let queued = self.repo.enqueue_order_event(event_type, order_id).await?;
+        if queued > 0 {
+            info!(
+                "Event {} for order {} queued for {} subscriber(s)",
+                event_type, order_id, queued
+            );
         }
+        Ok(())
+    }
+
+    // ==================== 投递 ====================
+
+    /// 查询投递记录
+    pub async fn list_deliveries(
+        &self,
+        query: &WebhookDeliveryQuery,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<WebhookDelivery>, i64), RswsError> {
+        self.repo.list_deliveries(query, page, page_size).await
+    }
+
+    /// 手动重新投递（含已成功和死信记录）
+    pub async fn redeliver(&self, delivery_id: i64) -> Result<WebhookDelivery, RswsError> {
+        let delivery = self
+            .repo
+            .redeliver(delivery_id)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::WEBHOOK_DELIVERY_NOT_FOUND))?;
+        info!("Webhook delivery {} queued for redelivery", delivery_id);
+        Ok(delivery)
+    }
 
-        // 带重试的请求
-        let mut last_error = None;
-        for attempt in 0..3 {
-            if attempt > 0 {
-                let delay = Duration::from_secs(1 << attempt); // 2s, 4s
+    /// 投递到期的队列记录
+    pub async fn process_due(&self) -> Result<(), RswsError> {
+        loop {
+            let batch = self.repo.claim_due(BATCH_SIZE, DELIVERY_LEASE_SECS).await?;
+            let done = (batch.len() as i64) < BATCH_SIZE;
+
+            for delivery in &batch {
+                if let Err(e) = self.deliver(delivery).await {
+                    error!("Failed to record webhook delivery {}: {}", delivery.id, e);
+                }
+            }
+
+            if done {
+                return Ok(());
+            }
+        }
+    }
+
+    /// 发送单条投递并记录结果
+    async fn deliver(&self, delivery: &PendingDelivery) -> Result<(), RswsError> {
+        let body = delivery.payload.to_string();
+        let timestamp = Utc::now().timestamp().to_string();
+        let signature = sign_payload(&delivery.secret, &timestamp, &body);
+
+        let result = self
+            .client
+            .post(&delivery.url)
+            .header("Content-Type", "application/json")
+            .header("X-Event-Type", &delivery.event_type)
+            .header("X-Event-Id", delivery.event_id.to_string())
+            .header("X-Delivery-Id", delivery.id.to_string())
+            .header("X-Timestamp", &timestamp)
+            .header("X-Signature", signature)
+            .body(body)
+            .send()
+            .await;
+
+        let (response_code, error_message) = match result {
+            Ok(resp) if resp.status().is_success() => {
                 info!(
-                    "Retry attempt {} for {} sync, waiting {:?}",
-                    attempt + 1,
-                    platform_name,
-                    delay
+                    "Webhook delivered: {} {} -> {}",
+                    delivery.event_type, delivery.id, delivery.url
                 );
-                tokio::time::sleep(delay).await;
+                return self
+                    .repo
+                    .mark_succeeded(delivery.id, resp.status().as_u16() as i32)
+                    .await;
             }
-
-            match request
-                .try_clone()
-                .ok_or_else(|| RswsError::internal("Failed to clone request"))?
-                .json(&body)
-                .send()
-                .await
-            {
-                Ok(resp) => {
-                    let status = resp.status();
-                    if status.is_success() {
-                        let resp_data: Value = resp
-                            .json()
-                            .await
-                            .unwrap_or_else(|_| serde_json::json!({"status": "ok"}));
-                        let remote_id = resp_data
-                            .get("id")
-                            .or_else(|| resp_data.get("remote_id"))
-                            .and_then(|v| v.as_str())
-                            .map(|s| s.to_string());
-
-                        info!(
-                            "Sync succeeded: platform={}, local_id={}, remote_id={:?}",
-                            platform_name, local_id, remote_id
-                        );
-
-                        return Ok(SyncRecord {
-                            id: rsws_common::snowflake::next_id(),
-                            platform_name: platform_name.to_string(),
-                            operation_type: operation_type.to_string(),
-                            local_id: local_id.to_string(),
-                            remote_id,
-                            sync_status: "success".to_string(),
-                            sync_data: data,
-                            error_message: None,
-                            last_sync_at: Some(Utc::now()),
-                            created_at: Utc::now(),
-                        });
-                    } else {
-                        let error_body = resp.text().await.unwrap_or_default();
-                        let err_msg = format!("HTTP {} from {}: {}", status, url, error_body);
-                        warn!("Sync failed (attempt {}): {}", attempt + 1, err_msg);
-                        last_error = Some(err_msg);
-
-                        // 4xx 不重试
-                        if status.as_u16() >= 400 && status.as_u16() < 500 {
-                            break;
-                        }
-                    }
-                }
-                Err(e) => {
-                    let err_msg = format!("Request failed: {}", e);
-                    warn!("Sync request error (attempt {}): {}", attempt + 1, err_msg);
-                    last_error = Some(err_msg);
-                }
+            Ok(resp) => {
+                let status = resp.status();
+                let text = resp.text().await.unwrap_or_default();
+                (
+                    Some(status.as_u16() as i32),
+                    format!("HTTP {}: {}", status, truncate(&text, MAX_ERROR_LEN)),
+                )
             }
+            Err(e) => (None, format!("Request failed: {}", e)),
+        };
+
+        let next_attempt_at = retry_delay_secs(delivery.attempts)
+            .map(|secs| Utc::now() + chrono::Duration::seconds(secs));
+        match next_attempt_at {
+            Some(at) => warn!(
+                "Webhook delivery {} failed (attempt {}), retry at {}: {}",
+                delivery.id, delivery.attempts, at, error_message
+            ),
+            None => error!(
+                "Webhook delivery {} dead-lettered after {} attempts: {}",
+                delivery.id, delivery.attempts, error_message
+            ),
         }
+        self.repo
+            .mark_failed(delivery.id, response_code, &error_message, next_attempt_at)
+            .await
+    }
 
-        let error_message = last_error.unwrap_or_else(|| "Unknown sync error".to_string());
-        error!(
-            "Sync ultimately failed: platform={}, local_id={}, error={}",
-            platform_name, local_id, error_message
-        );
+    /// 启动后台投递任务
+    pub fn start_background(self: Arc<Self>, interval_secs: u64) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.process_due().await {
+                    error!("Webhook delivery task failed: {}", e);
+                }
+            }
+        });
+    }
+}
 
-        Ok(SyncRecord {
-            id: rsws_common::snowflake::next_id(),
-            platform_name: platform_name.to_string(),
-            operation_type: operation_type.to_string(),
-            local_id: local_id.to_string(),
-            remote_id: None,
-            sync_status: "failed".to_string(),
-            sync_data: data,
-            error_message: Some(error_message),
-            last_sync_at: Some(Utc::now()),
-            created_at: Utc::now(),
-        })
+/// 校验订阅 URL 与事件类型
+fn validate_subscription(
+    url: Option<&str>,
+    event_types: Option<&[String]>,
+) -> Result<(), RswsError> {
+    if let Some(url) = url {
+        let valid = url::Url::parse(url)
+            .map(|u| matches!(u.scheme(), "http" | "https") && u.host().is_some())
+            .unwrap_or(false);
+        if !valid {
+            return Err(RswsError::business_with_message(
+                ErrorCode::WEBHOOK_SUBSCRIPTION_INVALID,
+                "Subscription URL must be an absolute http(s) URL",
+            ));
+        }
+    }
+    if let Some(unknown) = event_types
+        .into_iter()
+        .flatten()
+        .find(|t| !EVENT_TYPES.contains(&t.as_str()))
+    {
+        return Err(RswsError::business_with_message(
+            ErrorCode::WEBHOOK_SUBSCRIPTION_INVALID,
+            format!("Unknown event type: {}", unknown),
+        ));
     }
+    Ok(())
 }
 
-impl Default for CrossPlatformService {
-    fn default() -> Self {
-        Self::new()
+/// 投递签名：HMAC-SHA256(secret, "{timestamp}.{body}")
+pub fn sign_payload(secret: &str, timestamp: &str, body: &str) -> String {
+    let message = format!("{}.{}", timestamp, body);
+    hmac_sha256(secret.as_bytes(), message.as_bytes())
+}
+
+fn truncate(s: &str, max: usize) -> &str {
+    match s.char_indices().nth(max) {
+        Some((idx, _)) => &s[..idx],
+        None => s,
     }
 }
 
@@ -236,3 +302,31 @@ fn hmac_sha256(key: &[u8], message: &[u8]) -> String {
             s
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_payload() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            hmac_sha256(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            sign_payload("secret", "1700000000", "{}"),
+            hmac_sha256(b"secret", b"1700000000.{}")
+        );
+    }
+
+    #[test]
+    fn test_validate_subscription() {
+        let events = ["order.paid".to_string()];
+        let unknown = ["order.created".to_string()];
+        assert!(validate_subscription(Some("https://erp.example.com/hook"), Some(&events)).is_ok());
+        assert!(validate_subscription(Some("ftp://erp.example.com"), None).is_err());
+        assert!(validate_subscription(Some("/relative"), None).is_err());
+        assert!(validate_subscription(None, Some(&unknown)).is_err());
+    }
+}
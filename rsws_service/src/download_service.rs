@@ -0,0 +1,468 @@
+//! 资源下载服务
+//!
+//! - 下载接口签发绑定用户、资源与版本的短期令牌，不再暴露原始 file_url
+//! - 凭令牌下载：S3 存储重定向到预签名 URL，本地存储返回文件路径由接口流式输出（支持 Range）
+//! - 每用户每日下载次数上限与令牌有效期来自下载插件配置
+//! - 启用水印时按令牌生成买家个性化文件（缓存在临时目录，同一令牌断点续传复用），
+//!   水印记录长期保留，管理员可凭泄露文件反查订单
+
+use crate::config_service::ConfigService;
+use crate::oss_service::{key_from_url, StorageService};
+use crate::watermark::{self, WatermarkFormat, WatermarkStamp};
+use chrono::{Duration, Utc};
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_common::snowflake::next_id;
+use rsws_common::utils::generate_download_token;
+use rsws_db::download::{NewDownloadToken, NewDownloadWatermark};
+use rsws_db::DownloadTokenRepository;
+use rsws_model::config::DownloadPluginConfig;
+use rsws_model::download::{
+    DownloadTicket, DownloadToken, UpdateDownloadConfigRequest, WatermarkMatch,
+    DEFAULT_DOWNLOAD_TOKEN_TTL_SECS, DOWNLOAD_TOKEN_RETENTION_DAYS, MAX_DOWNLOAD_TOKEN_TTL_SECS,
+};
+use rsws_model::license::LicensedOrder;
+use rsws_model::resource::Resource;
+use rsws_model::resource_version::ResourceVersion;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+/// 水印签名密钥配置项（迁移时生成）
+const WATERMARK_SECRET_KEY: &str = "download.watermark_secret";
+
+/// 令牌对应的下载方式
+#[derive(Debug)]
+pub enum DownloadTarget {
+    /// 本地文件，由接口流式输出
+    Local(PathBuf),
+    /// 重定向（S3 预签名 URL 或外部链接）
+    Redirect(String),
+}
+
+/// 资源下载服务
+pub struct DownloadService {
+    token_repo: Arc<DownloadTokenRepository>,
+    config_service: ConfigService,
+}
+
+impl DownloadService {
+    /// 创建资源下载服务实例
+    pub fn new(token_repo: Arc<DownloadTokenRepository>, config_service: ConfigService) -> Self {
+        Self {
+            token_repo,
+            config_service,
+        }
+    }
+
+    /// 获取下载配置
+    pub async fn get_config(&self) -> Result<Option<DownloadPluginConfig>, RswsError> {
+        self.token_repo.get_config().await
+    }
+
+    /// 更新下载配置
+    pub async fn update_config(
+        &self,
+        req: UpdateDownloadConfigRequest,
+    ) -> Result<DownloadPluginConfig, RswsError> {
+        if !(1..=MAX_DOWNLOAD_TOKEN_TTL_SECS).contains(&req.token_ttl_secs) {
+            return Err(RswsError::bad_request(format!(
+                "token_ttl_secs must be between 1 and {}",
+                MAX_DOWNLOAD_TOKEN_TTL_SECS
+            )));
+        }
+        if req.daily_limit_per_user.is_some_and(|n| n < 0) {
+            return Err(RswsError::bad_request(
+                "daily_limit_per_user must not be negative",
+            ));
+        }
+        if req.watermark_max_bytes.is_some_and(|n| n <= 0) {
+            return Err(RswsError::bad_request(
+                "watermark_max_bytes must be positive",
+            ));
+        }
+
+        self.token_repo.save_config(&req).await
+    }
+
+    /// 令牌有效期与每日下载上限（未配置时使用默认值且不限次数）
+    async fn limits(&self) -> Result<(i32, Option<i32>), RswsError> {
+        Ok(match self.token_repo.get_config().await? {
+            Some(config) => (config.token_ttl_secs, config.daily_limit_per_user),
+            None => (DEFAULT_DOWNLOAD_TOKEN_TTL_SECS, None),
+        })
+    }
+
+    /// 检查用户是否已达每日下载上限（滚动 24 小时）
+    pub async fn check_quota(&self, user_id: i64) -> Result<(), RswsError> {
+        let (_, daily_limit) = self.limits().await?;
+        if let Some(limit) = daily_limit {
+            let issued = self
+                .token_repo
+                .count_issued_since(user_id, Utc::now() - Duration::hours(24))
+                .await?;
+            if issued >= i64::from(limit) {
+                return Err(RswsError::business(ErrorCode::DOWNLOAD_LIMIT_EXCEEDED));
+            }
+        }
+        Ok(())
+    }
+
+    /// 签发下载令牌（有版本时下载该版本文件，否则下载资源主文件）
+    ///
+    /// `licensed` 为用户持有的最高档位订单，档位限次时与令牌同事务占用一次下载次数；
+    /// 无订单（会员授权）或档位不限次数时不计次。资源下载计数同事务递增。
+    pub async fn issue(
+        &self,
+        user_id: i64,
+        resource: &Resource,
+        version: Option<&ResourceVersion>,
+        licensed: Option<&LicensedOrder>,
+    ) -> Result<DownloadTicket, RswsError> {
+        let file_url = match version {
+            Some(v) => v.file_url.as_str(),
+            None => resource.file_url.as_deref().ok_or_else(|| {
+                RswsError::business_with_message(
+                    ErrorCode::RESOURCE_NOT_FOUND,
+                    "Resource has no downloadable file",
+                )
+            })?,
+        };
+        let file_name = download_file_name(resource, version, file_url);
+        // 水印文件与原文件内容不同，不返回原文件的大小与校验和
+        let personalized = WatermarkFormat::from_name(file_url).is_some()
+            && self
+                .token_repo
+                .get_config()
+                .await?
+                .is_some_and(|c| c.watermark_enabled);
+
+        let (ttl_secs, _) = self.limits().await?;
+        let token = generate_download_token();
+        let charge = licensed.and_then(|o| o.max_downloads.map(|max| (o.order_id, max)));
+        let stored = self
+            .token_repo
+            .issue(
+                &NewDownloadToken {
+                    token_hash: &hash_token(&token),
+                    user_id,
+                    resource_id: resource.id,
+                    version_id: version.map(|v| v.id),
+                    file_url,
+                    file_name: &file_name,
+                    expires_at: Utc::now() + Duration::seconds(i64::from(ttl_secs)),
+                },
+                charge,
+            )
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::LICENSE_DOWNLOAD_LIMIT_REACHED))?;
+
+        info!(
+            "Download token issued: resource {} for user {}",
+            resource.id, user_id
+        );
+
+        Ok(DownloadTicket {
+            download_url: format!("/api/v1/download/{}", token),
+            file_name,
+            expires_at: stored.expires_at,
+            version_id: version.map(|v| v.id),
+            version: version.map(|v| v.version.clone()),
+            file_size: version.and_then(|v| v.file_size).filter(|_| !personalized),
+            checksum: version
+                .and_then(|v| v.checksum.clone())
+                .filter(|_| !personalized),
+        })
+    }
+
+    /// 校验令牌（须为签发给当前用户且未过期）
+    pub async fn resolve(&self, token: &str, user_id: i64) -> Result<DownloadToken, RswsError> {
+        let stored = self
+            .token_repo
+            .use_valid(&hash_token(token))
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::DOWNLOAD_TOKEN_INVALID))?;
+
+        if stored.user_id != user_id {
+            return Err(RswsError::business(ErrorCode::DOWNLOAD_TOKEN_INVALID));
+        }
+        Ok(stored)
+    }
+
+    /// 令牌对应的下载方式
+    pub async fn target(&self, token: &DownloadToken) -> Result<DownloadTarget, RswsError> {
+        // 外部链接（非本站上传）直接重定向
+        let Some(key) = key_from_url(&token.file_url) else {
+            return Ok(DownloadTarget::Redirect(token.file_url.clone()));
+        };
+
+        let config = self.config_service.get_storage_config().await?;
+        let storage = StorageService::for_url(&config, &token.file_url).await?;
+
+        if let Some(format) = WatermarkFormat::from_name(&key) {
+            if let Some(path) = self.watermarked(token, &storage, &key, format).await? {
+                return Ok(DownloadTarget::Local(path));
+            }
+        }
+
+        if let Some(path) = storage.local_path(&key) {
+            return Ok(DownloadTarget::Local(path));
+        }
+
+        // 预签名 URL 有效期不超过令牌剩余时间
+        let remaining = (token.expires_at - Utc::now()).num_seconds().max(1) as u64;
+        let url = storage.presign_url(&key, remaining).await?;
+        Ok(DownloadTarget::Redirect(url))
+    }
+
+    /// 生成买家水印文件（同一令牌复用已生成的文件）
+    ///
+    /// 未启用水印、文件超过大小上限、未配置签名密钥或文件结构不支持时返回 None，按原文件下载。
+    async fn watermarked(
+        &self,
+        token: &DownloadToken,
+        storage: &StorageService,
+        key: &str,
+        format: WatermarkFormat,
+    ) -> Result<Option<PathBuf>, RswsError> {
+        let Some(config) = self
+            .token_repo
+            .get_config()
+            .await?
+            .filter(|c| c.watermark_enabled)
+        else {
+            return Ok(None);
+        };
+
+        let path = watermark_cache_dir().join(format!("{}.{}", token.id, format.extension()));
+        if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Ok(Some(path));
+        }
+
+        let Some(secret) = self.config_service.get(WATERMARK_SECRET_KEY).await? else {
+            warn!("Watermark secret is not configured, serving original file");
+            return Ok(None);
+        };
+
+        let local = storage.local_path(key);
+        let size = match local {
+            Some(ref local) => tokio::fs::metadata(local).await.map(|m| m.len()).ok(),
+            None => storage.metadata(key).await.map(|m| m.size).ok(),
+        };
+        if size.is_none_or(|size| size > config.watermark_max_bytes as u64) {
+            return Ok(None);
+        }
+        let data = match local {
+            Some(local) => tokio::fs::read(&local)
+                .await
+                .map_err(|e| RswsError::internal(format!("Failed to read file: {}", e)))?,
+            None => storage.download(key).await?.to_vec(),
+        };
+
+        let stamp = WatermarkStamp {
+            user_id: token.user_id,
+            order_id: self
+                .token_repo
+                .find_paid_order_id(token.user_id, token.resource_id)
+                .await?,
+            issued_at: Utc::now(),
+        };
+        let payload = stamp.payload(secret.as_bytes());
+        let license = watermark::license_text(&stamp, &token.file_name, &payload);
+
+        let marked = {
+            let payload = payload.clone();
+            tokio::task::spawn_blocking(move || watermark::apply(format, &data, &payload, &license))
+                .await
+                .map_err(|e| RswsError::internal(format!("Watermark task failed: {}", e)))?
+        };
+        let marked = match marked {
+            Ok(Some(marked)) => marked,
+            Ok(None) => {
+                warn!(
+                    "Unsupported file structure, serving original file: resource {}",
+                    token.resource_id
+                );
+                return Ok(None);
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to watermark resource {}, serving original file: {}",
+                    token.resource_id, e
+                );
+                return Ok(None);
+            }
+        };
+
+        self.token_repo
+            .create_watermark(&NewDownloadWatermark {
+                payload: &payload,
+                token_id: token.id,
+                user_id: token.user_id,
+                order_id: stamp.order_id,
+                resource_id: token.resource_id,
+                version_id: token.version_id,
+                file_name: &token.file_name,
+                issued_at: stamp.issued_at,
+            })
+            .await?;
+
+        // 先写临时文件再改名，避免并发请求读到半截文件
+        let io_err = |e: std::io::Error| {
+            RswsError::internal(format!("Failed to write watermarked file: {}", e))
+        };
+        tokio::fs::create_dir_all(watermark_cache_dir())
+            .await
+            .map_err(io_err)?;
+        let partial = path.with_extension(format!("{}.part", next_id()));
+        tokio::fs::write(&partial, marked).await.map_err(io_err)?;
+        tokio::fs::rename(&partial, &path).await.map_err(io_err)?;
+
+        info!(
+            "Watermarked download: resource {} for user {}",
+            token.resource_id, token.user_id
+        );
+        Ok(Some(path))
+    }
+
+    /// 从泄露文件中找出水印并反查下载记录
+    pub async fn lookup_watermark(&self, data: Vec<u8>) -> Result<Vec<WatermarkMatch>, RswsError> {
+        let secret = self
+            .config_service
+            .get(WATERMARK_SECRET_KEY)
+            .await?
+            .ok_or_else(|| RswsError::internal("Watermark secret is not configured"))?;
+
+        let payloads = tokio::task::spawn_blocking(move || watermark::find_payloads(&data))
+            .await
+            .map_err(|e| RswsError::internal(format!("Watermark lookup task failed: {}", e)))?;
+
+        let mut matches = Vec::with_capacity(payloads.len());
+        for payload in payloads {
+            let Some((stamp, signature_valid)) = WatermarkStamp::parse(&payload, secret.as_bytes())
+            else {
+                continue;
+            };
+            let record = self.token_repo.find_watermark(&payload).await?;
+            matches.push(WatermarkMatch {
+                payload,
+                user_id: stamp.user_id,
+                order_id: stamp.order_id,
+                issued_at: stamp.issued_at,
+                signature_valid,
+                record,
+            });
+        }
+        Ok(matches)
+    }
+
+    /// 启动后台过期令牌清理任务
+    pub fn start_background(self: Arc<Self>, interval_secs: u64) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+                let before = Utc::now() - Duration::days(i64::from(DOWNLOAD_TOKEN_RETENTION_DAYS));
+                match self.token_repo.purge_expired(before).await {
+                    Ok(0) => {}
+                    Ok(n) => info!("Purged {} expired download tokens", n),
+                    Err(e) => error!("Download token cleanup task failed: {}", e),
+                }
+                match purge_watermark_cache().await {
+                    Ok(0) => {}
+                    Ok(n) => info!("Purged {} watermarked download files", n),
+                    Err(e) => error!("Watermark cache cleanup failed: {}", e),
+                }
+            }
+        });
+    }
+}
+
+/// 水印文件缓存目录
+fn watermark_cache_dir() -> PathBuf {
+    std::env::temp_dir().join("rsws_watermarks")
+}
+
+/// 删除超过令牌最长有效期的水印文件
+async fn purge_watermark_cache() -> std::io::Result<u64> {
+    let max_age = std::time::Duration::from_secs(MAX_DOWNLOAD_TOKEN_TTL_SECS as u64);
+    let mut entries = match tokio::fs::read_dir(watermark_cache_dir()).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    let mut purged = 0;
+    while let Some(entry) = entries.next_entry().await? {
+        let expired = entry
+            .metadata()
+            .await?
+            .modified()?
+            .elapsed()
+            .is_ok_and(|age| age > max_age);
+        if expired {
+            tokio::fs::remove_file(entry.path()).await?;
+            purged += 1;
+        }
+    }
+    Ok(purged)
+}
+
+/// 令牌哈希（数据库只保存哈希）
+fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// 下载文件名：资源标题（空格替换为下划线）+ 版本号，扩展名取存储文件的扩展名
+fn download_file_name(
+    resource: &Resource,
+    version: Option<&ResourceVersion>,
+    file_url: &str,
+) -> String {
+    let title = resource.title.replace(' ', "_");
+    let stem = match version {
+        Some(v) => format!("{}_{}", title, v.version),
+        None => title,
+    };
+    match file_extension(file_url) {
+        Some(ext) => format!("{}.{}", stem, ext),
+        None => stem,
+    }
+}
+
+/// 存储文件的扩展名（取 URL 最后一段路径，忽略查询参数）
+fn file_extension(file_url: &str) -> Option<&str> {
+    let path = file_url.split(['?', '#']).next().unwrap_or_default();
+    let name = path.rsplit('/').next().unwrap_or_default();
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && !ext.is_empty() => Some(ext),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_token() {
+        let hash = hash_token("dl_abc");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, hash_token("dl_abc"));
+        assert_ne!(hash, hash_token("dl_abd"));
+    }
+
+    #[test]
+    fn test_file_extension() {
+        assert_eq!(file_extension("private://resources/1/a.pdf"), Some("pdf"));
+        assert_eq!(
+            file_extension("https://cdn.example.com/resources/pack.tar.gz?sig=1"),
+            Some("gz")
+        );
+        assert_eq!(
+            file_extension("https://cdn.example.com/resources/v1.2/file"),
+            None
+        );
+        assert_eq!(file_extension("private://resources/.env"), None);
+    }
+}
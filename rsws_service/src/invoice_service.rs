@@ -0,0 +1,379 @@
+//! 发票服务
+//!
+//! - 订单支付完成后开具发票（快照买家、商品、金额、币种、支付方式、交易哈希）
+//! - 渲染 HTML 收据，并作为附件随付款成功邮件发送
+//! - 按日期区间导出发票（CSV）
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rsws_common::email::EmailService;
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_common::utils::csv_field;
+use rsws_db::InvoiceRepository;
+use rsws_model::invoice::Invoice;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::config_service::EmailDbConfig;
+
+/// 单次导出允许的最大天数
+const MAX_EXPORT_DAYS: i64 = 366;
+
+/// 后台补开/补发单批处理数量
+const BATCH_SIZE: i64 = 100;
+
+/// 发票服务
+#[derive(Clone)]
+pub struct InvoiceService {
+    invoice_repo: Arc<InvoiceRepository>,
+    email_service: Option<Arc<EmailService>>,
+}
+
+impl InvoiceService {
+    /// 创建发票服务实例
+    pub fn new(invoice_repo: Arc<InvoiceRepository>, email_config: Option<&EmailDbConfig>) -> Self {
+        let email_service = email_config.and_then(EmailDbConfig::smtp_service);
+
+        Self {
+            invoice_repo,
+            email_service,
+        }
+    }
+
+    /// 获取订单发票，订单已支付但尚未开具时按需开具
+    ///
+    /// 订单未支付返回 `Ok(None)`。
+    pub async fn get_or_issue(&self, order_id: i64) -> Result<Option<Invoice>, RswsError> {
+        Ok(self
+            .invoice_repo
+            .issue_for_order(order_id)
+            .await?
+            .map(|(invoice, _)| invoice))
+    }
+
+    /// 开具发票并发送付款成功邮件（附 HTML 收据）
+    ///
+    /// 支付回调确认订单后调用；重复调用不会重复开具或重复发信。
+    pub async fn issue_and_notify(&self, order_id: i64) -> Result<(), RswsError> {
+        match self.invoice_repo.issue_for_order(order_id).await? {
+            Some((invoice, created)) => {
+                if created {
+                    info!(
+                        "Invoice {} issued for order {}",
+                        invoice.invoice_no, order_id
+                    );
+                }
+                self.notify(&invoice).await
+            }
+            None => Err(RswsError::business(ErrorCode::ORDER_NOT_PAID)),
+        }
+    }
+
+    /// 补开遗漏的发票并补发邮件（USDT 链上确认等不经过回调 handler 的路径）
+    pub async fn process_pending(&self) -> Result<(), RswsError> {
+        for order_id in self
+            .invoice_repo
+            .list_paid_orders_without_invoice(BATCH_SIZE)
+            .await?
+        {
+            if let Err(e) = self.invoice_repo.issue_for_order(order_id).await {
+                error!("Failed to issue invoice for order {}: {}", order_id, e);
+            }
+        }
+
+        for invoice in self.invoice_repo.list_pending_email(BATCH_SIZE).await? {
+            if let Err(e) = self.notify(&invoice).await {
+                warn!(
+                    "Failed to send order paid email for invoice {}: {}",
+                    invoice.invoice_no, e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 启动后台补开/补发任务
+    pub fn start_background(self: Arc<Self>, interval_secs: u64) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.process_pending().await {
+                    error!("Invoice background task failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// 按日期区间获取发票（起止日期均含，UTC）
+    pub async fn list_by_date_range(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<Invoice>, RswsError> {
+        let (start, end) = parse_date_range(start_date, end_date)?;
+        self.invoice_repo.list_by_issued_range(start, end).await
+    }
+
+    /// 发送付款成功邮件（先抢占发送权，失败时释放以便后台重试）
+    async fn notify(&self, invoice: &Invoice) -> Result<(), RswsError> {
+        if !self.invoice_repo.claim_email(invoice.id).await? {
+            return Ok(());
+        }
+
+        if invoice.buyer_email.is_empty() {
+            warn!(
+                "Invoice {} has no buyer email, skip order paid email",
+                invoice.invoice_no
+            );
+            return Ok(());
+        }
+
+        let subject = format!("Payment received - Invoice {}", invoice.invoice_no);
+        let body = format!(
+            r#"Thank you for your purchase!
+
+Order: #{}
+Invoice: {}
+Amount: {} {}
+
+Your receipt is attached to this email."#,
+            invoice.order_id, invoice.invoice_no, invoice.paid_amount, invoice.currency
+        );
+
+        let Some(ref svc) = self.email_service else {
+            // Dev 模式：只打印日志
+            warn!(
+                "ORDER PAID EMAIL [DEV MODE] To: {} | Subject: {}",
+                invoice.buyer_email, subject
+            );
+            return Ok(());
+        };
+
+        let result = svc.send_with_attachment(
+            &invoice.buyer_email,
+            &subject,
+            &body,
+            &format!("receipt-{}.html", invoice.invoice_no),
+            "text/html; charset=utf-8",
+            render_receipt_html(invoice).into_bytes(),
+        );
+
+        if let Err(e) = result {
+            self.invoice_repo.release_email(invoice.id).await?;
+            return Err(e);
+        }
+
+        info!(
+            "Order paid email sent: invoice {} to {}",
+            invoice.invoice_no, invoice.buyer_email
+        );
+        Ok(())
+    }
+}
+
+/// 解析导出日期区间，返回 `[start, end + 1 day)` 的 UTC 时间范围
+pub fn parse_date_range(
+    start_date: &str,
+    end_date: &str,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), RswsError> {
+    let parse = |s: &str| {
+        NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").map_err(|_| {
+            RswsError::business_with_message(
+                ErrorCode::INVALID_PARAMETER,
+                format!("Invalid date '{}', expected YYYY-MM-DD", s),
+            )
+        })
+    };
+    let start = parse(start_date)?;
+    let end = parse(end_date)?;
+
+    if end < start {
+        return Err(RswsError::business_with_message(
+            ErrorCode::INVALID_PARAMETER,
+            "end_date must not be earlier than start_date",
+        ));
+    }
+    if (end - start).num_days() >= MAX_EXPORT_DAYS {
+        return Err(RswsError::business_with_message(
+            ErrorCode::INVALID_PARAMETER,
+            format!("Date range must not exceed {} days", MAX_EXPORT_DAYS),
+        ));
+    }
+
+    let start = start.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+    let end = (end + Duration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .and_utc();
+    Ok((start, end))
+}
+
+/// 渲染 HTML 收据
+pub fn render_receipt_html(invoice: &Invoice) -> String {
+    let rows: String = invoice
+        .line_items()
+        .iter()
+        .map(|item| {
+            format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+                escape_html(&item.title),
+                item.quantity,
+                item.unit_price,
+                item.amount
+            )
+        })
+        .collect();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Receipt {invoice_no}</title>
+<style>
+body {{ font-family: sans-serif; max-width: 720px; margin: 24px auto; color: #222; }}
+table {{ width: 100%; border-collapse: collapse; margin: 16px 0; }}
+th, td {{ border-bottom: 1px solid #ddd; padding: 8px; text-align: left; }}
+.num {{ text-align: right; }}
+.meta td {{ border: none; padding: 2px 8px 2px 0; }}
+</style>
+</head>
+<body>
+<h1>Receipt</h1>
+<table class="meta">
+<tr><td>Invoice No.</td><td>{invoice_no}</td></tr>
+<tr><td>Issued At</td><td>{issued_at}</td></tr>
+<tr><td>Order</td><td>#{order_id}</td></tr>
+<tr><td>Billed To</td><td>{buyer_name} &lt;{buyer_email}&gt;</td></tr>
+<tr><td>Payment Method</td><td>{payment_method}</td></tr>
+<tr><td>Transaction</td><td>{tx_hash}</td></tr>
+</table>
+<table>
+<thead><tr><th>Item</th><th class="num">Qty</th><th class="num">Unit Price</th><th class="num">Amount</th></tr></thead>
+<tbody>{rows}</tbody>
+<tfoot>
+<tr><td colspan="3" class="num">Subtotal</td><td class="num">{subtotal}</td></tr>
+<tr><td colspan="3" class="num"><strong>Paid</strong></td><td class="num"><strong>{paid_amount} {currency}</strong></td></tr>
+</tfoot>
+</table>
+</body>
+</html>
+"#,
+        invoice_no = escape_html(&invoice.invoice_no),
+        issued_at = invoice.issued_at.format("%Y-%m-%d %H:%M:%S UTC"),
+        order_id = invoice.order_id,
+        buyer_name = escape_html(&invoice.buyer_name),
+        buyer_email = escape_html(&invoice.buyer_email),
+        payment_method = escape_html(&invoice.payment_method),
+        tx_hash = escape_html(invoice.tx_hash.as_deref().unwrap_or("-")),
+        subtotal = invoice.subtotal,
+        paid_amount = invoice.paid_amount,
+        currency = escape_html(&invoice.currency),
+    )
+}
+
+/// 导出发票为 CSV（每个商品明细一行）
+pub fn invoices_to_csv(invoices: &[Invoice]) -> String {
+    let mut csv = String::from(
+        "invoice_no,issued_at,order_id,user_id,buyer_email,buyer_name,item_title,quantity,unit_price,subtotal,paid_amount,currency,payment_method,tx_hash\r\n",
+    );
+
+    for invoice in invoices {
+        for item in invoice.line_items() {
+            let fields = [
+                invoice.invoice_no.clone(),
+                invoice.issued_at.to_rfc3339(),
+                invoice.order_id.to_string(),
+                invoice.user_id.map(|id| id.to_string()).unwrap_or_default(),
+                invoice.buyer_email.clone(),
+                invoice.buyer_name.clone(),
+                item.title.clone(),
+                item.quantity.to_string(),
+                item.unit_price.to_string(),
+                invoice.subtotal.to_string(),
+                invoice.paid_amount.to_string(),
+                invoice.currency.clone(),
+                invoice.payment_method.clone(),
+                invoice.tx_hash.clone().unwrap_or_default(),
+            ];
+            let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            csv.push_str(&line.join(","));
+            csv.push_str("\r\n");
+        }
+    }
+
+    csv
+}
+
+/// HTML 转义
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    fn sample_invoice() -> Invoice {
+        Invoice {
+            id: 1,
+            invoice_no: "INV-20260613-000001".to_string(),
+            order_id: 42,
+            user_id: Some(7),
+            buyer_email: "buyer@example.com".to_string(),
+            buyer_name: "Tom <script>".to_string(),
+            items: serde_json::json!([{
+                "resource_id": 3,
+                "title": "Icons, \"Pro\" pack",
+                "quantity": 1,
+                "unit_price": 19.9,
+                "amount": 19.9,
+            }]),
+            subtotal: Decimal::new(1990, 2),
+            paid_amount: Decimal::new(1990, 2),
+            currency: "USD".to_string(),
+            payment_method: "paypal".to_string(),
+            tx_hash: Some("5O190127TN364715T".to_string()),
+            issued_at: Utc::now(),
+            emailed_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_render_receipt_html_escapes() {
+        let html = render_receipt_html(&sample_invoice());
+        assert!(html.contains("INV-20260613-000001"));
+        assert!(html.contains("Tom &lt;script&gt;"));
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("5O190127TN364715T"));
+    }
+
+    #[test]
+    fn test_invoices_to_csv() {
+        let csv = invoices_to_csv(&[sample_invoice()]);
+        let lines: Vec<&str> = csv.split("\r\n").filter(|l| !l.is_empty()).collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("invoice_no,issued_at,order_id"));
+        assert!(lines[1].contains("\"Icons, \"\"Pro\"\" pack\""));
+        assert!(lines[1].contains(",19.9,"));
+    }
+
+    #[test]
+    fn test_parse_date_range() {
+        let (start, end) = parse_date_range("2026-06-01", "2026-06-30").unwrap();
+        assert_eq!(start.to_rfc3339(), "2026-06-01T00:00:00+00:00");
+        assert_eq!(end.to_rfc3339(), "2026-07-01T00:00:00+00:00");
+
+        assert!(parse_date_range("2026-06-30", "2026-06-01").is_err());
+        assert!(parse_date_range("2026/06/01", "2026-06-30").is_err());
+        assert!(parse_date_range("2025-01-01", "2026-06-30").is_err());
+    }
+}
@@ -0,0 +1,196 @@
+//! 总账与余额服务
+//!
+//! 基于 rsws_db::LedgerRepository 的复式记账：
+//! - 用户余额充值（任意支付方式，含 USDT 超额支付转入余额）
+//! - 余额即时购买资源
+//! - 订单收款、佣金结算记账
+//! - 试算平衡表
+
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_db::LedgerRepository;
+use rsws_model::ledger::{user_wallet_account, AccountStatementLine, TrialBalance, WalletTopup};
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use tracing::info;
+
+/// 充值单有效期（分钟），与订单一致
+const TOPUP_EXPIRE_MINUTES: i32 = 30;
+
+/// 支持充值的支付方式
+pub const TOPUP_METHODS: [&str; 5] = ["paypal", "alipay", "wechatpay", "usdt_trc20", "usdt_erc20"];
+
+/// 总账与余额服务
+#[derive(Clone)]
+pub struct LedgerService {
+    ledger_repo: Arc<LedgerRepository>,
+}
+
+impl LedgerService {
+    /// 创建总账服务实例
+    pub fn new(ledger_repo: Arc<LedgerRepository>) -> Self {
+        Self { ledger_repo }
+    }
+
+    /// 获取用户可用余额
+    pub async fn balance(&self, user_id: i64) -> Result<Decimal, RswsError> {
+        self.ledger_repo.get_user_balance(user_id).await
+    }
+
+    /// 获取用户余额流水
+    pub async fn statement(
+        &self,
+        user_id: i64,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<AccountStatementLine>, i64), RswsError> {
+        self.ledger_repo
+            .list_statement(&user_wallet_account(user_id), page, page_size)
+            .await
+    }
+
+    /// 创建充值单
+    ///
+    /// USDT 充值须传入收款地址，链上到账时按收款地址与金额匹配。
+    pub async fn create_topup(
+        &self,
+        user_id: i64,
+        amount: Decimal,
+        payment_method: &str,
+        receive_address: Option<&str>,
+    ) -> Result<WalletTopup, RswsError> {
+        if amount <= Decimal::ZERO || amount.scale() > 2 {
+            return Err(RswsError::business(ErrorCode::PAYMENT_AMOUNT_INVALID));
+        }
+        if !TOPUP_METHODS.contains(&payment_method) {
+            return Err(RswsError::business(ErrorCode::PAYMENT_METHOD_NOT_SUPPORTED));
+        }
+
+        let topup = self
+            .ledger_repo
+            .create_topup(
+                user_id,
+                amount,
+                payment_method,
+                receive_address,
+                TOPUP_EXPIRE_MINUTES,
+            )
+            .await?;
+
+        info!(
+            "Topup created: {} user={} amount={} method={}",
+            topup.id, user_id, amount, payment_method
+        );
+        Ok(topup)
+    }
+
+    /// 获取充值单
+    pub async fn get_topup(&self, topup_id: i64) -> Result<Option<WalletTopup>, RswsError> {
+        self.ledger_repo.get_topup(topup_id).await
+    }
+
+    /// 根据网关订单号 / 交易号获取充值单
+    pub async fn get_topup_by_provider_tx(
+        &self,
+        provider_tx_id: &str,
+    ) -> Result<Option<WalletTopup>, RswsError> {
+        self.ledger_repo
+            .get_topup_by_provider_tx(provider_tx_id)
+            .await
+    }
+
+    /// 记录网关订单号
+    pub async fn set_topup_provider_tx(
+        &self,
+        topup_id: i64,
+        provider_tx_id: &str,
+    ) -> Result<(), RswsError> {
+        self.ledger_repo
+            .set_topup_provider_tx(topup_id, provider_tx_id)
+            .await
+    }
+
+    /// 充值到账（幂等），返回本次是否入账
+    pub async fn complete_topup(
+        &self,
+        topup_id: i64,
+        provider_tx_id: Option<&str>,
+        fee: Decimal,
+    ) -> Result<bool, RswsError> {
+        let credited = self
+            .ledger_repo
+            .complete_topup(topup_id, provider_tx_id, None, fee)
+            .await?;
+        if credited {
+            info!("Topup {} credited", topup_id);
+        }
+        Ok(credited)
+    }
+
+    /// 获取用户充值记录
+    pub async fn list_topups(
+        &self,
+        user_id: i64,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<WalletTopup>, i64), RswsError> {
+        self.ledger_repo.list_topups(user_id, page, page_size).await
+    }
+
+    /// 使用余额支付订单
+    pub async fn pay_order(&self, order_id: i64, user_id: i64) -> Result<(), RswsError> {
+        self.ledger_repo
+            .pay_order_from_balance(order_id, user_id)
+            .await?;
+        info!("Order {} paid from balance by user {}", order_id, user_id);
+        Ok(())
+    }
+
+    /// 确认第三方渠道的订单收款：订单置为已支付、记录佣金并记账（同一事务）
+    ///
+    /// 订单已不是待支付状态时返回 false，不做任何变更。
+    pub async fn confirm_order_payment(&self, order_id: i64) -> Result<bool, RswsError> {
+        let confirmed = self.ledger_repo.confirm_order_payment(order_id).await?;
+        if confirmed {
+            info!("Order {} payment confirmed", order_id);
+        }
+        Ok(confirmed)
+    }
+
+    /// 订单退款：订单置为已退款、作废会员订阅与未结算佣金、冲销收款凭证（同一事务）
+    ///
+    /// 订单不是已支付 / 已完成状态时返回 false，不做任何变更。
+    pub async fn refund_order(&self, order_id: i64) -> Result<bool, RswsError> {
+        let refunded = self.ledger_repo.refund_order(order_id).await?;
+        if refunded {
+            info!("Order {} refunded", order_id);
+        }
+        Ok(refunded)
+    }
+
+    /// 风控审核放行后确认暂扣的 USDT 收款，返回是否完成入账
+    pub async fn release_held_usdt_payment(&self, order_id: i64) -> Result<bool, RswsError> {
+        let released = self.ledger_repo.release_held_usdt_payment(order_id).await?;
+        if released {
+            info!("Order {} paid via held USDT payment", order_id);
+        }
+        Ok(released)
+    }
+
+    /// 风控审核拒绝后将暂扣的 USDT 收款标记为待退款，返回是否需要退款
+    pub async fn flag_held_usdt_refund(&self, order_id: i64) -> Result<bool, RswsError> {
+        self.ledger_repo.flag_held_usdt_refund(order_id).await
+    }
+
+    /// 结算订单佣金并记账
+    pub async fn settle_commissions(&self, order_id: i64) -> Result<usize, RswsError> {
+        self.ledger_repo.settle_commissions(order_id).await
+    }
+
+    /// 试算平衡表
+    pub async fn trial_balance(&self) -> Result<TrialBalance, RswsError> {
+        Ok(TrialBalance::from_rows(
+            self.ledger_repo.trial_balance().await?,
+        ))
+    }
+}
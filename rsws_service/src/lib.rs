@@ -3,28 +3,48 @@
 //! 提供各业务模块的服务逻辑
 
 pub mod admin_service;
+pub mod alipay_service;
 pub mod api_key_manager;
 pub mod audit_log_service;
 pub mod blockchain_service;
 pub mod commission_service;
 pub mod config_service;
+pub mod creator_service;
 pub mod cross_platform_service;
+pub mod download_service;
 pub mod email_verification_service;
 pub mod error_log_service;
+pub mod invoice_service;
+pub mod ledger_service;
+pub mod license_service;
 pub mod log_service;
 pub mod login_log_service;
+pub mod membership_service;
+pub mod moderation_service;
 pub mod order_service;
 pub mod oss_service;
 pub mod payment_service;
 pub mod paypal_service;
+pub mod quote_service;
+pub mod rating_service;
+pub mod recommendation_service;
+pub mod referral_service;
 pub mod request_service;
+pub mod resource_bulk_service;
 pub mod resource_service;
+pub mod resource_version_service;
+pub mod risk_service;
+pub mod tag_service;
 pub mod user_payment_service;
 pub mod user_service;
+pub mod watermark;
 pub mod webhook_service;
+pub mod wechatpay_service;
+pub mod wishlist_service;
 
 // 导出主要服务
 pub use admin_service::AdminService;
+pub use alipay_service::AlipayService;
 pub use api_key_manager::ApiKeyManager;
 pub use audit_log_service::{
     AuditAction, AuditLog, AuditLogPage, AuditLogQuery, AuditLogService, AuditStats,
@@ -33,33 +53,56 @@ pub use audit_log_service::{
 pub use blockchain_service::BlockchainService;
 pub use commission_service::CommissionService;
 pub use config_service::ConfigService;
-pub use config_service::{BlockchainDbConfig, EmailDbConfig, PayPalDbConfig, UsdtListenDbConfig};
+pub use config_service::{
+    AlipayDbConfig, BlockchainDbConfig, EmailDbConfig, PayPalDbConfig, UsdtListenDbConfig,
+    WechatPayDbConfig,
+};
+pub use creator_service::CreatorService;
 pub use cross_platform_service::CrossPlatformService;
+pub use download_service::DownloadService;
 pub use email_verification_service::EmailVerificationService;
 pub use error_log_service::{
     CreateErrorLogRequest, ErrorLog, ErrorLogPage, ErrorLogQuery, ErrorLogService, ErrorStats,
     ErrorType, ResolveErrorRequest,
 };
+pub use invoice_service::InvoiceService;
+pub use ledger_service::LedgerService;
+pub use license_service::LicenseService;
 pub use log_service::LogService;
 pub use log_service::{LogConfig, UpdateLogConfigRequest};
 pub use login_log_service::{
     CreateLoginLogRequest, LoginLog, LoginLogPage, LoginLogQuery, LoginLogService, LoginStatus,
     LoginType,
 };
+pub use membership_service::MembershipService;
+pub use moderation_service::ModerationService;
 pub use order_service::OrderService;
 pub use oss_service::{FileMetadata, StorageBackend, StorageError, StorageService, UploadResult};
 pub use payment_service::PaymentService;
 pub use paypal_service::PayPalService;
+pub use quote_service::QuoteService;
+pub use rating_service::RatingService;
+pub use recommendation_service::RecommendationService;
+pub use referral_service::ReferralService;
 pub use request_service::RequestService;
+pub use resource_bulk_service::ResourceBulkService;
 pub use resource_service::ResourceService;
+pub use resource_version_service::ResourceVersionService;
+pub use risk_service::RiskService;
 pub use rsws_db::admin::AdminRepository;
+pub use tag_service::TagService;
 pub use user_payment_service::UserPaymentService;
 pub use user_service::UserService;
 pub use webhook_service::WebhookService;
+pub use wechatpay_service::WechatPayService;
+pub use wishlist_service::WishlistService;
 
 use rsws_db::{
-    OrderRepository, PaymentRepository, RedisService, ResourceRepository, UserRepository,
-    WalletRepository,
+    CommissionRepository, DownloadTokenRepository, EventWebhookRepository, InvoiceRepository,
+    LedgerRepository, LicenseRepository, MembershipRepository, ModerationRepository,
+    OrderRepository, PaymentRepository, RatingRepository, RecommendationRepository, RedisService,
+    ReferralRepository, ResourceRepository, ResourceVersionRepository, RiskRepository,
+    TagRepository, UserRepository, WalletRepository, WebhookLogRepository, WishlistRepository,
 };
 use std::sync::Arc;
 
@@ -70,19 +113,59 @@ pub fn create_paypal_service(
     PayPalService::new(config)
 }
 
+/// 创建支付宝服务（配置从数据库读取）
+pub fn create_alipay_service(
+    config: Option<crate::config_service::AlipayDbConfig>,
+) -> AlipayService {
+    AlipayService::new(config)
+}
+
+/// 创建微信支付服务（配置从数据库读取）
+pub fn create_wechatpay_service(
+    config: Option<crate::config_service::WechatPayDbConfig>,
+) -> WechatPayService {
+    WechatPayService::new(config)
+}
+
 /// 创建区块链服务（配置从数据库读取）
 pub fn create_blockchain_service(wallet_repo: WalletRepository) -> BlockchainService {
     BlockchainService::new(wallet_repo)
 }
 
 /// 创建 Webhook 服务
-pub fn create_webhook_service(paypal_service: Arc<PayPalService>) -> WebhookService {
-    WebhookService::new(paypal_service)
+pub fn create_webhook_service(
+    paypal_service: Arc<PayPalService>,
+    pool: sqlx::PgPool,
+) -> WebhookService {
+    WebhookService::new(paypal_service, Arc::new(WebhookLogRepository::new(pool)))
+}
+
+/// 创建跨平台服务（出站事件 Webhook）
+pub fn create_cross_platform_service(pool: sqlx::PgPool) -> CrossPlatformService {
+    CrossPlatformService::new(Arc::new(EventWebhookRepository::new(pool)))
 }
 
-/// 创建跨平台服务
-pub fn create_cross_platform_service() -> CrossPlatformService {
-    CrossPlatformService::new()
+/// 创建订单风控服务
+pub fn create_risk_service(pool: sqlx::PgPool) -> RiskService {
+    RiskService::new(Arc::new(RiskRepository::new(pool)))
+}
+
+/// 创建推荐计划服务
+pub fn create_referral_service(pool: sqlx::PgPool) -> ReferralService {
+    ReferralService::new(Arc::new(ReferralRepository::new(pool)))
+}
+
+/// 创建创作者收益服务
+pub fn create_creator_service(pool: sqlx::PgPool) -> CreatorService {
+    CreatorService::new(Arc::new(CommissionRepository::new(pool)))
+}
+
+/// 创建佣金服务
+pub fn create_commission_service(
+    pool: sqlx::PgPool,
+    config_service: Arc<ConfigService>,
+) -> CommissionService {
+    CommissionService::new(pool, config_service)
 }
 
 /// 创建 Admin API Key 管理器
@@ -120,6 +203,10 @@ pub fn create_resource_service(
     pool: sqlx::PgPool,
     config_service: Option<ConfigService>,
     order_service: Option<Arc<OrderService>>,
+    license_service: Option<Arc<LicenseService>>,
+    event_publisher: Option<Arc<CrossPlatformService>>,
+    wishlist_service: Option<Arc<WishlistService>>,
+    tag_service: Option<Arc<TagService>>,
 ) -> ResourceService {
     let mut service = if let Some(cfg) = config_service {
         ResourceService::with_oss(Arc::new(ResourceRepository::new(pool)), cfg)
@@ -129,9 +216,105 @@ pub fn create_resource_service(
     if let Some(os) = order_service {
         service.set_order_service(os);
     }
+    if let Some(ls) = license_service {
+        service.set_license_service(ls);
+    }
+    if let Some(ep) = event_publisher {
+        service.set_event_publisher(ep);
+    }
+    if let Some(ws) = wishlist_service {
+        service.set_wishlist_service(ws);
+    }
+    if let Some(ts) = tag_service {
+        service.set_tag_service(ts);
+    }
     service
 }
 
+/// 创建授权档位服务
+pub fn create_license_service(pool: sqlx::PgPool) -> LicenseService {
+    LicenseService::new(
+        Arc::new(LicenseRepository::new(pool.clone())),
+        Arc::new(ResourceRepository::new(pool)),
+    )
+}
+
+/// 创建资源版本服务（新版本通知邮件复用 email_configs）
+pub fn create_resource_version_service(
+    pool: sqlx::PgPool,
+    email_config: Option<&EmailDbConfig>,
+    config_service: ConfigService,
+) -> ResourceVersionService {
+    let mut service = ResourceVersionService::new(
+        Arc::new(ResourceVersionRepository::new(pool.clone())),
+        Arc::new(ResourceRepository::new(pool)),
+        email_config,
+    );
+    service.set_config_service(config_service);
+    service
+}
+
+/// 创建收藏与心愿单服务（降价通知邮件复用 email_configs）
+pub fn create_wishlist_service(
+    pool: sqlx::PgPool,
+    email_config: Option<&EmailDbConfig>,
+) -> WishlistService {
+    WishlistService::new(
+        Arc::new(WishlistRepository::new(pool.clone())),
+        Arc::new(ResourceRepository::new(pool)),
+        email_config,
+    )
+}
+
+/// 创建资源标签服务
+pub fn create_tag_service(pool: sqlx::PgPool) -> TagService {
+    TagService::new(
+        Arc::new(TagRepository::new(pool.clone())),
+        Arc::new(ResourceRepository::new(pool)),
+    )
+}
+
+/// 创建资源批量导入导出服务
+pub fn create_resource_bulk_service(
+    pool: sqlx::PgPool,
+    resource_service: Arc<ResourceService>,
+    tag_service: Arc<TagService>,
+    config_service: ConfigService,
+) -> ResourceBulkService {
+    ResourceBulkService::new(
+        Arc::new(ResourceRepository::new(pool.clone())),
+        Arc::new(rsws_db::CategoryRepository::new(pool)),
+        resource_service,
+        tag_service,
+        config_service,
+    )
+}
+
+/// 创建资源推荐服务
+pub fn create_recommendation_service(pool: sqlx::PgPool) -> RecommendationService {
+    RecommendationService::new(Arc::new(RecommendationRepository::new(pool)))
+}
+
+/// 创建资源下载服务（存储配置从数据库读取）
+pub fn create_download_service(
+    pool: sqlx::PgPool,
+    config_service: ConfigService,
+) -> DownloadService {
+    DownloadService::new(Arc::new(DownloadTokenRepository::new(pool)), config_service)
+}
+
+/// 创建资源评分服务（购买校验复用订单服务）
+pub fn create_rating_service(
+    pool: sqlx::PgPool,
+    order_service: Arc<OrderService>,
+) -> RatingService {
+    RatingService::new(
+        Arc::new(RatingRepository::new(pool.clone())),
+        Arc::new(ResourceRepository::new(pool)),
+        order_service,
+    )
+}
+
 /// 创建配置服务
 pub fn create_config_service(pool: sqlx::PgPool, redis: RedisService) -> ConfigService {
     ConfigService::new(pool, redis)
@@ -142,6 +325,50 @@ pub fn create_payment_service(pool: sqlx::PgPool) -> PaymentService {
     PaymentService::new(Arc::new(PaymentRepository::new(pool)))
 }
 
+/// 创建发票服务（付款成功邮件复用 email_configs）
+pub fn create_invoice_service(
+    pool: sqlx::PgPool,
+    email_config: Option<&EmailDbConfig>,
+) -> InvoiceService {
+    InvoiceService::new(Arc::new(InvoiceRepository::new(pool)), email_config)
+}
+
+/// 创建会员服务（到期提醒邮件复用 email_configs）
+pub fn create_membership_service(
+    pool: sqlx::PgPool,
+    quote_service: Arc<QuoteService>,
+    email_config: Option<&EmailDbConfig>,
+) -> MembershipService {
+    MembershipService::new(
+        Arc::new(MembershipRepository::new(pool)),
+        quote_service,
+        email_config,
+    )
+}
+
+/// 创建资源审核服务（审核结果邮件复用 email_configs）
+pub fn create_moderation_service(
+    pool: sqlx::PgPool,
+    email_config: Option<&EmailDbConfig>,
+    event_publisher: Option<Arc<CrossPlatformService>>,
+) -> ModerationService {
+    ModerationService::new(
+        Arc::new(ModerationRepository::new(pool)),
+        email_config,
+        event_publisher,
+    )
+}
+
+/// 创建支付报价服务（费率与限额读取各通道配置表）
+pub fn create_quote_service(config_service: Arc<ConfigService>) -> QuoteService {
+    QuoteService::new(config_service)
+}
+
+/// 创建总账与余额服务
+pub fn create_ledger_service(pool: sqlx::PgPool) -> LedgerService {
+    LedgerService::new(Arc::new(LedgerRepository::new(pool)))
+}
+
 /// 创建管理员服务（不再持有 Redis，API Key 管理已迁移至 api_key_manager）
 pub fn create_admin_service(pool: sqlx::PgPool) -> AdminService {
     AdminService::new(AdminRepository::new(pool))
@@ -0,0 +1,261 @@
+//! 资源授权档位服务
+//!
+//! - 档位管理（创作者管理自己的资源，管理员可管理全部资源）
+//! - 下单报价：未持有时按档位全价，持有低档位时只收差价
+//! - 下载次数：按用户持有最高档位的订单计数
+//!
+//! 未设置档位的资源保持原有单一价格逻辑。
+
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_db::{LicenseRepository, ResourceRepository};
+use rsws_model::license::{
+    owned_license, tier_rank, upgrade_price, CreateResourceLicenseRequest, LicensedOrder,
+    ResourceLicense, UpdateResourceLicenseRequest,
+};
+use rsws_model::resource::{Resource, OWNER_TYPE_USER};
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use tracing::info;
+
+/// 授权档位服务
+#[derive(Clone)]
+pub struct LicenseService {
+    license_repo: Arc<LicenseRepository>,
+    resource_repo: Arc<ResourceRepository>,
+}
+
+impl LicenseService {
+    /// 创建授权档位服务实例
+    pub fn new(
+        license_repo: Arc<LicenseRepository>,
+        resource_repo: Arc<ResourceRepository>,
+    ) -> Self {
+        Self {
+            license_repo,
+            resource_repo,
+        }
+    }
+
+    // ==================== 档位管理 ====================
+
+    /// 获取资源的授权档位（按等级从低到高）
+    pub async fn list(
+        &self,
+        resource_id: i64,
+        active_only: bool,
+    ) -> Result<Vec<ResourceLicense>, RswsError> {
+        self.license_repo
+            .list_by_resource(resource_id, active_only)
+            .await
+    }
+
+    /// 获取资源全部授权档位（含已下架，供创作者 / 管理员管理）
+    pub async fn list_for_owner(
+        &self,
+        resource_id: i64,
+        owner_id: Option<i64>,
+    ) -> Result<Vec<ResourceLicense>, RswsError> {
+        self.check_resource_owner(resource_id, owner_id).await?;
+        self.list(resource_id, false).await
+    }
+
+    /// 创建授权档位
+    ///
+    /// `owner_id` 为创作者用户 ID，管理员操作时传 None 跳过归属校验。
+    pub async fn create(
+        &self,
+        resource_id: i64,
+        owner_id: Option<i64>,
+        req: &CreateResourceLicenseRequest,
+    ) -> Result<ResourceLicense, RswsError> {
+        self.check_resource_owner(resource_id, owner_id).await?;
+
+        if tier_rank(&req.tier).is_none() {
+            return Err(RswsError::business(ErrorCode::LICENSE_TIER_INVALID));
+        }
+        validate_license_terms(Some(req.price), req.max_downloads)?;
+
+        let mut tiers: Vec<(String, Decimal)> = self
+            .list(resource_id, true)
+            .await?
+            .into_iter()
+            .map(|l| (l.tier, l.price))
+            .collect();
+        tiers.push((req.tier.clone(), req.price));
+        check_tier_prices(&tiers)?;
+
+        let license = self.license_repo.create(resource_id, req).await?;
+        info!(
+            "Resource license created: {} resource={} tier={}",
+            license.id, resource_id, license.tier
+        );
+        Ok(license)
+    }
+
+    /// 更新授权档位（下架请设置 `is_active = false`，已售订单不受影响）
+    pub async fn update(
+        &self,
+        resource_id: i64,
+        license_id: i64,
+        owner_id: Option<i64>,
+        req: &UpdateResourceLicenseRequest,
+    ) -> Result<ResourceLicense, RswsError> {
+        self.check_resource_owner(resource_id, owner_id).await?;
+        validate_license_terms(req.price, req.max_downloads)?;
+
+        let licenses = self.list(resource_id, false).await?;
+        let existing = licenses
+            .iter()
+            .find(|l| l.id == license_id)
+            .ok_or_else(|| RswsError::business(ErrorCode::LICENSE_NOT_FOUND))?;
+
+        // 按更新后的状态校验上架档位价格
+        if req.price.is_some() || req.is_active == Some(true) {
+            let tiers: Vec<(String, Decimal)> = licenses
+                .iter()
+                .filter_map(|l| {
+                    if l.id == existing.id {
+                        let active = req.is_active.unwrap_or(l.is_active);
+                        active.then(|| (l.tier.clone(), req.price.unwrap_or(l.price)))
+                    } else {
+                        l.is_active.then(|| (l.tier.clone(), l.price))
+                    }
+                })
+                .collect();
+            check_tier_prices(&tiers)?;
+        }
+
+        self.license_repo
+            .update(license_id, req)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::LICENSE_NOT_FOUND))
+    }
+
+    /// 校验资源存在且属于该创作者
+    async fn check_resource_owner(
+        &self,
+        resource_id: i64,
+        owner_id: Option<i64>,
+    ) -> Result<Resource, RswsError> {
+        let resource = self
+            .resource_repo
+            .get_by_id(resource_id)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_NOT_FOUND))?;
+
+        if let Some(uid) = owner_id {
+            if resource.provider_id != Some(uid) || resource.owner_type != OWNER_TYPE_USER {
+                return Err(RswsError::business(ErrorCode::AUTH_PERMISSION_DENIED));
+            }
+        }
+        Ok(resource)
+    }
+
+    // ==================== 购买与下载 ====================
+
+    /// 获取用户持有的最高授权（无已支付订单时为 None）
+    pub async fn owned(
+        &self,
+        user_id: i64,
+        resource_id: i64,
+    ) -> Result<Option<LicensedOrder>, RswsError> {
+        let orders = self
+            .license_repo
+            .list_paid_orders(user_id, resource_id)
+            .await?;
+        Ok(owned_license(&orders).cloned())
+    }
+
+    /// 下单报价，返回 (授权档位 ID, 应付金额)
+    ///
+    /// - 资源未设置档位：按资源价格，`license_id` 必须为空
+    /// - 未指定档位：默认最低的上架档位
+    /// - 已持有同级或更高档位：返回 LICENSE_ALREADY_OWNED
+    pub async fn quote(
+        &self,
+        user_id: i64,
+        resource: &Resource,
+        license_id: Option<i64>,
+    ) -> Result<(Option<i64>, Decimal), RswsError> {
+        let licenses = self.list(resource.id, true).await?;
+        if licenses.is_empty() {
+            return match license_id {
+                Some(_) => Err(RswsError::business(ErrorCode::LICENSE_NOT_FOUND)),
+                None => Ok((None, resource.price)),
+            };
+        }
+
+        let target = match license_id {
+            Some(id) => licenses.iter().find(|l| l.id == id),
+            None => licenses.first(),
+        }
+        .ok_or_else(|| RswsError::business(ErrorCode::LICENSE_NOT_FOUND))?;
+
+        let owned = self.owned(user_id, resource.id).await?;
+        let amount = upgrade_price(target, owned.as_ref())
+            .ok_or_else(|| RswsError::business(ErrorCode::LICENSE_ALREADY_OWNED))?;
+
+        Ok((Some(target.id), amount))
+    }
+}
+
+/// 用户持有的档位在列表中对应的 ID
+///
+/// 无档位的旧订单按最低档位计；档位已下架时取不高于持有等级的最高档位。
+pub fn owned_license_id(licenses: &[ResourceLicense], owned: &LicensedOrder) -> Option<i64> {
+    if owned.license_id.is_some() && licenses.iter().any(|l| Some(l.id) == owned.license_id) {
+        return owned.license_id;
+    }
+    licenses
+        .iter()
+        .filter(|l| tier_rank(&l.tier).is_some_and(|r| r <= owned.rank()))
+        .last()
+        .map(|l| l.id)
+}
+
+/// 校验档位价格与下载次数
+fn validate_license_terms(
+    price: Option<Decimal>,
+    max_downloads: Option<i32>,
+) -> Result<(), RswsError> {
+    if price.is_some_and(|p| p <= Decimal::ZERO || p.scale() > 2) {
+        return Err(RswsError::business(ErrorCode::PAYMENT_AMOUNT_INVALID));
+    }
+    if max_downloads.is_some_and(|n| n <= 0) {
+        return Err(RswsError::bad_request("max_downloads must be positive"));
+    }
+    Ok(())
+}
+
+/// 校验上架档位价格随等级递增（保证升级差价为正）
+fn check_tier_prices(tiers: &[(String, Decimal)]) -> Result<(), RswsError> {
+    let mut ranked: Vec<(usize, Decimal)> = tiers
+        .iter()
+        .filter_map(|(tier, price)| tier_rank(tier).map(|r| (r, *price)))
+        .collect();
+    ranked.sort_by_key(|(rank, _)| *rank);
+
+    if ranked.windows(2).any(|w| w[1].1 <= w[0].1) {
+        return Err(RswsError::business_with_message(
+            ErrorCode::LICENSE_TIER_INVALID,
+            "Higher license tiers must be priced above lower tiers",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_tier_prices() {
+        let tier = |t: &str, p: i64| (t.to_string(), Decimal::new(p, 0));
+
+        assert!(check_tier_prices(&[tier("extended", 50), tier("personal", 10)]).is_ok());
+        assert!(check_tier_prices(&[tier("personal", 10), tier("commercial", 10)]).is_err());
+        assert!(check_tier_prices(&[tier("commercial", 30), tier("extended", 20)]).is_err());
+        assert!(check_tier_prices(&[]).is_ok());
+    }
+}
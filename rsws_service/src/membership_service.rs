@@ -0,0 +1,282 @@
+//! 会员服务
+//!
+//! - 套餐管理（按分类授权、固定天数）
+//! - 订阅 / 续费：创建会员订单，复用现有支付方式完成支付
+//! - 订单支付后激活订阅，续费从当前有效期结束时顺延
+//! - 到期前邮件提醒
+//!
+//! 资源访问校验见 `OrderRepository::check_user_purchased`。
+
+use rsws_common::email::EmailService;
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_db::MembershipRepository;
+use rsws_model::membership::{
+    CreateMembershipPlanRequest, MembershipExpiryReminder, MembershipPlan,
+    UpdateMembershipPlanRequest, UserMembership, UserMembershipDetail,
+};
+use rsws_model::payment::Order;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::config_service::EmailDbConfig;
+use crate::quote_service::QuoteService;
+
+/// 会员订单有效期（分钟），与资源订单一致
+const ORDER_EXPIRE_MINUTES: i32 = 30;
+
+/// 到期前提醒天数
+const REMIND_BEFORE_DAYS: i32 = 3;
+
+/// 后台任务单批处理数量
+const BATCH_SIZE: i64 = 100;
+
+/// 支持购买会员的支付方式
+///
+/// USDT 订单按资源钱包地址匹配，会员订单没有对应资源钱包，暂不支持。
+pub const MEMBERSHIP_PAYMENT_METHODS: [&str; 4] = ["paypal", "alipay", "wechatpay", "balance"];
+
+/// 会员服务
+#[derive(Clone)]
+pub struct MembershipService {
+    membership_repo: Arc<MembershipRepository>,
+    quote_service: Arc<QuoteService>,
+    email_service: Option<Arc<EmailService>>,
+}
+
+impl MembershipService {
+    /// 创建会员服务实例
+    pub fn new(
+        membership_repo: Arc<MembershipRepository>,
+        quote_service: Arc<QuoteService>,
+        email_config: Option<&EmailDbConfig>,
+    ) -> Self {
+        let email_service = email_config.and_then(EmailDbConfig::smtp_service);
+
+        Self {
+            membership_repo,
+            quote_service,
+            email_service,
+        }
+    }
+
+    // ==================== 套餐 ====================
+
+    /// 获取套餐列表
+    pub async fn list_plans(&self, active_only: bool) -> Result<Vec<MembershipPlan>, RswsError> {
+        self.membership_repo.list_plans(active_only).await
+    }
+
+    /// 创建套餐
+    pub async fn create_plan(
+        &self,
+        req: &CreateMembershipPlanRequest,
+    ) -> Result<MembershipPlan, RswsError> {
+        if req.name.trim().is_empty() {
+            return Err(RswsError::bad_request("Plan name is required"));
+        }
+        validate_plan_terms(Some(req.duration_days), Some(req.price))?;
+
+        let plan = self.membership_repo.create_plan(req).await?;
+        info!(
+            "Membership plan created: {} category={}",
+            plan.id, plan.category_id
+        );
+        Ok(plan)
+    }
+
+    /// 更新套餐
+    pub async fn update_plan(
+        &self,
+        plan_id: i64,
+        req: &UpdateMembershipPlanRequest,
+    ) -> Result<MembershipPlan, RswsError> {
+        validate_plan_terms(req.duration_days, req.price)?;
+
+        self.membership_repo
+            .update_plan(plan_id, req)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::MEMBERSHIP_PLAN_NOT_FOUND))
+    }
+
+    // ==================== 订阅 ====================
+
+    /// 订阅 / 续费会员：创建待支付的会员订单
+    pub async fn subscribe(
+        &self,
+        user_id: i64,
+        plan_id: i64,
+        payment_method: &str,
+    ) -> Result<(Order, UserMembership), RswsError> {
+        if !MEMBERSHIP_PAYMENT_METHODS.contains(&payment_method) {
+            return Err(RswsError::business(ErrorCode::PAYMENT_METHOD_NOT_SUPPORTED));
+        }
+
+        let plan = self
+            .membership_repo
+            .get_plan(plan_id)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::MEMBERSHIP_PLAN_NOT_FOUND))?;
+        if !plan.is_active {
+            return Err(RswsError::business(ErrorCode::MEMBERSHIP_PLAN_INACTIVE));
+        }
+
+        // 按通道费率与限额报价
+        let quote = self.quote_service.quote(payment_method, plan.price).await?;
+
+        let (order, membership) = self
+            .membership_repo
+            .create_order(user_id, &plan, &quote, ORDER_EXPIRE_MINUTES)
+            .await?;
+
+        info!(
+            "Membership order created: order={} user={} plan={}",
+            order.id, user_id, plan.id
+        );
+        Ok((order, membership))
+    }
+
+    /// 订单支付后激活会员订阅（非会员订单或已激活时为 no-op）
+    pub async fn activate_for_order(
+        &self,
+        order_id: i64,
+    ) -> Result<Option<UserMembership>, RswsError> {
+        let activated = self.membership_repo.activate_for_order(order_id).await?;
+        if let Some(ref m) = activated {
+            info!(
+                "Membership {} activated for user {}: {:?} ~ {:?}",
+                m.id, m.user_id, m.starts_at, m.expires_at
+            );
+        }
+        Ok(activated)
+    }
+
+    /// 获取用户会员订阅
+    pub async fn list_by_user(&self, user_id: i64) -> Result<Vec<UserMembershipDetail>, RswsError> {
+        self.membership_repo.list_by_user(user_id).await
+    }
+
+    /// 激活遗漏的订阅并发送到期提醒
+    pub async fn process_pending(&self) -> Result<(), RswsError> {
+        for order_id in self
+            .membership_repo
+            .list_paid_pending_orders(BATCH_SIZE)
+            .await?
+        {
+            if let Err(e) = self.activate_for_order(order_id).await {
+                error!(
+                    "Failed to activate membership for order {}: {}",
+                    order_id, e
+                );
+            }
+        }
+
+        for reminder in self
+            .membership_repo
+            .list_expiring(REMIND_BEFORE_DAYS, BATCH_SIZE)
+            .await?
+        {
+            if let Err(e) = self.send_reminder(&reminder).await {
+                warn!(
+                    "Failed to send expiry reminder for membership {}: {}",
+                    reminder.membership_id, e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 启动后台激活 / 到期提醒任务
+    pub fn start_background(self: Arc<Self>, interval_secs: u64) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.process_pending().await {
+                    error!("Membership background task failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// 发送到期提醒（先抢占发送权，失败时释放以便后台重试）
+    async fn send_reminder(&self, reminder: &MembershipExpiryReminder) -> Result<(), RswsError> {
+        if !self
+            .membership_repo
+            .claim_reminder(reminder.membership_id)
+            .await?
+        {
+            return Ok(());
+        }
+
+        if reminder.email.is_empty() {
+            warn!(
+                "User {} has no email, skip membership expiry reminder",
+                reminder.user_id
+            );
+            return Ok(());
+        }
+
+        let subject = format!("Your {} membership is expiring soon", reminder.plan_name);
+        let body = format!(
+            r#"Your {} membership expires at {} (UTC).
+
+Renew before it expires to keep access to all resources in the plan.
+The new period starts right after the current one ends, so renewing early loses nothing."#,
+            reminder.plan_name,
+            reminder.expires_at.format("%Y-%m-%d %H:%M")
+        );
+
+        let Some(ref svc) = self.email_service else {
+            // Dev 模式：只打印日志
+            warn!(
+                "MEMBERSHIP REMINDER [DEV MODE] To: {} | Subject: {}",
+                reminder.email, subject
+            );
+            return Ok(());
+        };
+
+        if let Err(e) = svc.send(&reminder.email, &subject, &body) {
+            self.membership_repo
+                .release_reminder(reminder.membership_id)
+                .await?;
+            return Err(e);
+        }
+
+        info!(
+            "Membership expiry reminder sent: membership {} to {}",
+            reminder.membership_id, reminder.email
+        );
+        Ok(())
+    }
+}
+
+/// 校验套餐天数与价格
+fn validate_plan_terms(
+    duration_days: Option<i32>,
+    price: Option<Decimal>,
+) -> Result<(), RswsError> {
+    if duration_days.is_some_and(|d| d <= 0) {
+        return Err(RswsError::bad_request("duration_days must be positive"));
+    }
+    if price.is_some_and(|p| p <= Decimal::ZERO || p.scale() > 2) {
+        return Err(RswsError::business(ErrorCode::PAYMENT_AMOUNT_INVALID));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_plan_terms() {
+        assert!(validate_plan_terms(Some(30), Some(Decimal::new(999, 2))).is_ok());
+        assert!(validate_plan_terms(None, None).is_ok());
+        assert!(validate_plan_terms(Some(0), None).is_err());
+        assert!(validate_plan_terms(None, Some(Decimal::ZERO)).is_err());
+        assert!(validate_plan_terms(None, Some(Decimal::new(1001, 3))).is_err());
+    }
+}
@@ -0,0 +1,311 @@
+//! 资源审核服务
+//!
+//! - 管理员审核队列（默认待审核）、通过 / 拒绝 / 下架，拒绝与下架需填写原因
+//! - 创作者提交草稿或被拒绝 / 下架的资源重新审核
+//! - 审核结果邮件通知创作者；资源审核通过后变为公开时推送 `resource.published`
+
+use rsws_common::email::EmailService;
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_db::moderation::ReviewEvent;
+use rsws_db::ModerationRepository;
+use rsws_model::event_webhook::EVENT_RESOURCE_PUBLISHED;
+use rsws_model::moderation::{
+    next_review_status, ModerateResourceRequest, ModerationQueueItem, ResourceReview,
+    ACTOR_TYPE_ADMIN, ACTOR_TYPE_USER, REVIEW_ACTION_APPROVE, REVIEW_ACTION_REJECT,
+    REVIEW_ACTION_SUBMIT, REVIEW_ACTION_TAKE_DOWN, REVIEW_STATUSES, REVIEW_STATUS_PENDING,
+};
+use rsws_model::resource::{Resource, OWNER_TYPE_USER};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::config_service::EmailDbConfig;
+use crate::cross_platform_service::CrossPlatformService;
+use crate::resource_service::resource_event_data;
+
+/// 审核原因最大长度
+const MAX_REASON_LEN: usize = 1000;
+
+/// 资源审核服务
+#[derive(Clone)]
+pub struct ModerationService {
+    moderation_repo: Arc<ModerationRepository>,
+    email_service: Option<Arc<EmailService>>,
+    event_publisher: Option<Arc<CrossPlatformService>>,
+}
+
+impl ModerationService {
+    /// 创建资源审核服务实例
+    pub fn new(
+        moderation_repo: Arc<ModerationRepository>,
+        email_config: Option<&EmailDbConfig>,
+        event_publisher: Option<Arc<CrossPlatformService>>,
+    ) -> Self {
+        let email_service = email_config.and_then(EmailDbConfig::smtp_service);
+
+        Self {
+            moderation_repo,
+            email_service,
+            event_publisher,
+        }
+    }
+
+    /// 审核队列（默认待审核，按提交时间先后）
+    pub async fn queue(
+        &self,
+        status: Option<&str>,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<ModerationQueueItem>, i64), RswsError> {
+        let status = status.unwrap_or(REVIEW_STATUS_PENDING);
+        if !REVIEW_STATUSES.contains(&status) {
+            return Err(RswsError::bad_request(format!(
+                "Invalid review status: {}",
+                status
+            )));
+        }
+        self.moderation_repo.queue(status, page, page_size).await
+    }
+
+    /// 资源审核记录
+    pub async fn history(&self, resource_id: i64) -> Result<Vec<ResourceReview>, RswsError> {
+        self.moderation_repo.history(resource_id).await
+    }
+
+    /// 管理员审核：通过 / 拒绝 / 下架
+    pub async fn moderate(
+        &self,
+        resource_id: i64,
+        admin_id: i64,
+        req: &ModerateResourceRequest,
+    ) -> Result<Resource, RswsError> {
+        let action = req.action.as_str();
+        if ![
+            REVIEW_ACTION_APPROVE,
+            REVIEW_ACTION_REJECT,
+            REVIEW_ACTION_TAKE_DOWN,
+        ]
+        .contains(&action)
+        {
+            return Err(RswsError::bad_request(format!(
+                "Invalid review action: {}",
+                action
+            )));
+        }
+        let reason = validate_reason(action, req.reason.as_deref())?;
+
+        let resource = self
+            .moderation_repo
+            .find_resource(resource_id)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_NOT_FOUND))?;
+
+        let updated = self
+            .apply(&resource, action, reason, Some(admin_id), ACTOR_TYPE_ADMIN)
+            .await?;
+
+        info!(
+            "Resource {} moderated by admin {}: {} -> {}",
+            resource_id, admin_id, resource.review_status, updated.review_status
+        );
+
+        if updated.owner_type == OWNER_TYPE_USER {
+            if let Some(provider_id) = updated.provider_id {
+                self.notify_creator(provider_id, &updated, action, reason)
+                    .await;
+            }
+        }
+        if !resource.is_public() && updated.is_public() {
+            self.publish(&updated).await;
+        }
+
+        Ok(updated)
+    }
+
+    /// 创作者提交审核（草稿、被拒绝或被下架的资源）
+    pub async fn submit(&self, resource_id: i64, user_id: i64) -> Result<Resource, RswsError> {
+        let resource = self
+            .moderation_repo
+            .find_resource(resource_id)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_NOT_FOUND))?;
+
+        if resource.provider_id != Some(user_id) || resource.owner_type != OWNER_TYPE_USER {
+            return Err(RswsError::business(ErrorCode::AUTH_PERMISSION_DENIED));
+        }
+
+        let updated = self
+            .apply(
+                &resource,
+                REVIEW_ACTION_SUBMIT,
+                None,
+                Some(user_id),
+                ACTOR_TYPE_USER,
+            )
+            .await?;
+
+        info!(
+            "Resource {} submitted for review by user {}",
+            resource_id, user_id
+        );
+        Ok(updated)
+    }
+
+    /// 按状态机变更审核状态
+    async fn apply(
+        &self,
+        resource: &Resource,
+        action: &str,
+        reason: Option<&str>,
+        actor_id: Option<i64>,
+        actor_type: &str,
+    ) -> Result<Resource, RswsError> {
+        let to_status = next_review_status(&resource.review_status, action)
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_REVIEW_ACTION_INVALID))?;
+
+        self.moderation_repo
+            .transition(&ReviewEvent {
+                resource_id: resource.id,
+                action,
+                from_status: &resource.review_status,
+                to_status,
+                reason,
+                actor_id,
+                actor_type,
+            })
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_REVIEW_ACTION_INVALID))
+    }
+
+    /// 审核结果邮件（失败只记录日志，不影响审核结果）
+    async fn notify_creator(
+        &self,
+        user_id: i64,
+        resource: &Resource,
+        action: &str,
+        reason: Option<&str>,
+    ) {
+        let contact = match self.moderation_repo.creator_contact(user_id).await {
+            Ok(Some(contact)) if !contact.email.is_empty() => contact,
+            Ok(_) => {
+                warn!(
+                    "User {} has no email, skip moderation notification",
+                    user_id
+                );
+                return;
+            }
+            Err(e) => {
+                warn!("Failed to load creator {} contact: {}", user_id, e);
+                return;
+            }
+        };
+
+        let (subject, body) = review_email(&contact.username, &resource.title, action, reason);
+
+        let Some(ref svc) = self.email_service else {
+            // Dev 模式：只打印日志
+            warn!(
+                "MODERATION EMAIL [DEV MODE] To: {} | Subject: {}",
+                contact.email, subject
+            );
+            return;
+        };
+
+        match svc.send(&contact.email, &subject, &body) {
+            Ok(_) => info!(
+                "Moderation notification sent: resource {} to {}",
+                resource.id, contact.email
+            ),
+            Err(e) => warn!(
+                "Failed to send moderation notification for resource {}: {}",
+                resource.id, e
+            ),
+        }
+    }
+
+    /// 资源上架事件（入队失败只记录日志）
+    async fn publish(&self, resource: &Resource) {
+        let Some(ref publisher) = self.event_publisher else {
+            return;
+        };
+        if let Err(e) = publisher
+            .publish(EVENT_RESOURCE_PUBLISHED, resource_event_data(resource))
+            .await
+        {
+            warn!("Failed to publish resource {} event: {}", resource.id, e);
+        }
+    }
+}
+
+/// 校验审核原因：拒绝和下架必填
+fn validate_reason<'a>(
+    action: &str,
+    reason: Option<&'a str>,
+) -> Result<Option<&'a str>, RswsError> {
+    let reason = reason.map(str::trim).filter(|r| !r.is_empty());
+    if reason.is_none() && (action == REVIEW_ACTION_REJECT || action == REVIEW_ACTION_TAKE_DOWN) {
+        return Err(RswsError::bad_request(
+            "Reason is required when rejecting or taking down a resource",
+        ));
+    }
+    if reason.is_some_and(|r| r.chars().count() > MAX_REASON_LEN) {
+        return Err(RswsError::bad_request(format!(
+            "Reason must be at most {} characters",
+            MAX_REASON_LEN
+        )));
+    }
+    Ok(reason)
+}
+
+/// 审核结果邮件主题与正文
+fn review_email(
+    username: &str,
+    title: &str,
+    action: &str,
+    reason: Option<&str>,
+) -> (String, String) {
+    let reason_line = reason
+        .map(|r| format!("\n\nReason: {}", r))
+        .unwrap_or_default();
+    match action {
+        REVIEW_ACTION_APPROVE => (
+            format!("Your resource \"{}\" has been approved", title),
+            format!(
+                "Hi {},\n\nYour resource \"{}\" has passed review and is now visible in the store.",
+                username, title
+            ),
+        ),
+        REVIEW_ACTION_REJECT => (
+            format!("Your resource \"{}\" was not approved", title),
+            format!(
+                "Hi {},\n\nYour resource \"{}\" did not pass review.{}\n\nYou can edit the resource and submit it for review again.",
+                username, title, reason_line
+            ),
+        ),
+        _ => (
+            format!("Your resource \"{}\" has been taken down", title),
+            format!(
+                "Hi {},\n\nYour resource \"{}\" has been taken down and is no longer visible in the store.{}\n\nYou can edit the resource and submit it for review again.",
+                username, title, reason_line
+            ),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_reason() {
+        assert!(validate_reason(REVIEW_ACTION_REJECT, None).is_err());
+        assert!(validate_reason(REVIEW_ACTION_TAKE_DOWN, Some("  ")).is_err());
+        assert_eq!(
+            validate_reason(REVIEW_ACTION_REJECT, Some(" blurry screenshots ")).unwrap(),
+            Some("blurry screenshots")
+        );
+        assert_eq!(validate_reason(REVIEW_ACTION_APPROVE, None).unwrap(), None);
+        let long = "x".repeat(MAX_REASON_LEN + 1);
+        assert!(validate_reason(REVIEW_ACTION_REJECT, Some(&long)).is_err());
+    }
+}
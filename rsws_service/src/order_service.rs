@@ -5,7 +5,7 @@ use rust_decimal::Decimal;
 use rsws_common::error::RswsError;
 use rsws_common::error_code::ErrorCode;
 use rsws_db::OrderRepository;
-use rsws_model::payment::{Order, OrderDetail};
+use rsws_model::payment::{Order, OrderDetail, PaymentQuote};
 use std::sync::Arc;
 use tracing::info;
 
@@ -21,22 +21,22 @@ impl OrderService {
         Self { order_repo }
     }
 
-    /// 创建订单
+    /// 创建订单（金额与手续费取自支付报价，见 `QuoteService::quote`）
     pub async fn create(
         &self,
         user_id: i64,
         resource_id: i64,
-        amount: Decimal,
-        payment_method: &str,
+        license_id: Option<i64>,
+        quote: &PaymentQuote,
     ) -> Result<Order, RswsError> {
         // 检查金额
-        if amount < Decimal::ZERO {
+        if quote.total < Decimal::ZERO {
             return Err(RswsError::business(ErrorCode::PAYMENT_AMOUNT_INVALID));
         }
 
         let order = self
             .order_repo
-            .create(user_id, resource_id, amount, payment_method, 30)
+            .create(user_id, resource_id, license_id, quote, 30)
             .await?;
 
         info!("Order created: {}", order.id);
@@ -127,12 +127,12 @@ impl OrderService {
         self.order_repo.update_status(order_id, "completed").await
     }
 
-    /// 退款订单
-    pub async fn refund(&self, order_id: i64) -> Result<(), RswsError> {
-        self.order_repo.update_status(order_id, "refunded").await
+    /// 取消未支付的订单（支付网关拒绝付款时调用），订单已不是待支付状态时返回 false
+    pub async fn cancel_unpaid(&self, order_id: i64) -> Result<bool, RswsError> {
+        self.order_repo.cancel_if_pending(order_id).await
     }
 
-    /// 检查用户是否已购买某资源（通过已完成订单）
+    /// 检查用户是否已购买某资源（已支付订单或有效会员覆盖资源分类）
     pub async fn check_purchased(&self, user_id: i64, resource_id: i64) -> Result<bool, RswsError> {
         self.order_repo
             .check_user_purchased(user_id, resource_id)
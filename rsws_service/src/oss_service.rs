@@ -6,15 +6,20 @@
 //! - MinIO (S3 兼容)
 //! - 阿里云 OSS (S3 兼容)
 //! - 腾讯云 COS (S3 兼容)
+//!
+//! 存储分为两个区域：
+//! - 公开区：缩略图、头像、展示图，本地存储由 `/uploads` 静态目录直接访问
+//! - 私有区：资源文件，URL 记为 `private://<key>`，只能经授权下载接口获取
 
 use async_trait::async_trait;
 use bytes::Bytes;
 use chrono::{DateTime, Utc};
 use rsws_common::error::RswsError;
+use rsws_db::RedisService;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
 
 // ==================== 错误类型 ====================
 
@@ -114,6 +119,97 @@ pub trait StorageBackend: Send + Sync + std::fmt::Debug {
 
     /// 获取公开访问 URL
     fn public_url(&self, key: &str) -> String;
+
+    /// 本地文件路径（仅本地存储，用于流式下载）
+    fn local_path(&self, _key: &str) -> Option<PathBuf> {
+        None
+    }
+}
+
+/// 私有区文件 URL 前缀
+pub const PRIVATE_URL_PREFIX: &str = "private://";
+
+/// 本地存储私有区默认目录
+pub const DEFAULT_PRIVATE_DIR: &str = "storage/private";
+
+/// 本地存储私有区目录（未配置时使用默认目录）
+pub fn local_private_dir(config: &crate::config_service::OssStorageConfig) -> &str {
+    config
+        .private_dir
+        .as_deref()
+        .filter(|d| !d.is_empty())
+        .unwrap_or(DEFAULT_PRIVATE_DIR)
+}
+
+/// 校验本地私有区目录不在静态文件目录下（否则私有文件可经 `/uploads` 直接访问）
+pub fn check_private_dir(private_dir: &str, static_dir: &str) -> Result<(), StorageError> {
+    if resolve_dir(private_dir).starts_with(resolve_dir(static_dir)) {
+        return Err(StorageError::Config(format!(
+            "私有区目录 {} 不能位于静态文件目录 {} 下",
+            private_dir, static_dir
+        )));
+    }
+    Ok(())
+}
+
+/// 目录绝对路径（目录不存在时按字面规整 `.` 与 `..`）
+fn resolve_dir(dir: &str) -> PathBuf {
+    if let Ok(path) = Path::new(dir).canonicalize() {
+        return path;
+    }
+    let absolute = std::env::current_dir().unwrap_or_default().join(dir);
+    let mut resolved = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            other => resolved.push(other),
+        }
+    }
+    resolved
+}
+
+/// S3 未配置私有 bucket 时，私有区 key 的前缀
+const PRIVATE_KEY_PREFIX: &str = "private";
+
+/// 存储区域
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, salvo_oapi::ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageArea {
+    /// 公开区（缩略图、头像、展示图）
+    #[default]
+    Public,
+    /// 私有区（资源文件，仅经授权下载）
+    Private,
+}
+
+/// 是否为私有区文件 URL
+pub fn is_private_url(url: &str) -> bool {
+    url.starts_with(PRIVATE_URL_PREFIX)
+}
+
+/// 私有区文件 URL
+pub fn private_url(key: &str) -> String {
+    format!("{}{}", PRIVATE_URL_PREFIX, key)
+}
+
+/// 从文件 URL 提取存储 key
+///
+/// 上传的文件 key 均以 `resources/` 开头：
+/// - 私有区：private://resources/20240601/12345678.zip
+/// - 本地：http://host:port/uploads/resources/20240601/12345678.zip
+/// - S3：https://bucket.s3.region.amazonaws.com/resources/20240601/12345678.zip
+/// - 自定义域名：https://cdn.example.com/resources/20240601/12345678.zip
+pub fn key_from_url(url: &str) -> Option<String> {
+    if let Some(key) = url.strip_prefix(PRIVATE_URL_PREFIX) {
+        return Some(key.to_string());
+    }
+    url.rfind("/resources/")
+        .map(|pos| url[pos + 1..].to_string())
 }
 
 // ==================== 本地存储实现 ====================
@@ -294,6 +390,14 @@ impl StorageBackend for LocalStorage {
     fn public_url(&self, key: &str) -> String {
         format!("{}/{}", self.base_url.trim_end_matches('/'), key)
     }
+
+    fn local_path(&self, key: &str) -> Option<PathBuf> {
+        // 拒绝 `..` 等越出存储目录的 key
+        let safe = std::path::Path::new(key)
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_)));
+        safe.then(|| self.file_path(key))
+    }
 }
 
 // ==================== S3 兼容存储实现 ====================
@@ -596,18 +700,20 @@ impl StorageBackend for S3Storage {
 #[derive(Debug)]
 pub struct StorageService {
     backend: Arc<dyn StorageBackend>,
+    area: StorageArea,
 }
 
 impl Clone for StorageService {
     fn clone(&self) -> Self {
         Self {
             backend: self.backend.clone(),
+            area: self.area,
         }
     }
 }
 
 impl StorageService {
-    /// 创建存储服务实例
+    /// 创建公开区存储服务实例
     pub async fn new(
         config: &crate::config_service::OssStorageConfig,
     ) -> Result<Self, StorageError> {
@@ -626,7 +732,79 @@ impl StorageService {
             }
         };
 
-        Ok(Self { backend })
+        Ok(Self {
+            backend,
+            area: StorageArea::Public,
+        })
+    }
+
+    /// 创建私有区存储服务实例
+    ///
+    /// 本地存储使用独立目录（不在静态文件目录下）；S3 使用私有 bucket，
+    /// 未配置时与公开区共用 bucket，key 加 `private/` 前缀（需在 bucket 策略中禁止公开读取）。
+    pub async fn new_private(
+        config: &crate::config_service::OssStorageConfig,
+    ) -> Result<Self, StorageError> {
+        let backend: Arc<dyn StorageBackend> = match config.provider.as_str() {
+            "local" => {
+                // 私有区不能与公开区目录重叠
+                let dir = local_private_dir(config);
+                check_private_dir(dir, &config.endpoint)?;
+                Arc::new(LocalStorage::new(
+                    PathBuf::from(dir),
+                    PRIVATE_URL_PREFIX.to_string(),
+                )?)
+            }
+            _ => {
+                let mut private = config.clone();
+                match config.private_bucket.as_deref().filter(|b| !b.is_empty()) {
+                    Some(bucket) => private.bucket = bucket.to_string(),
+                    None => {
+                        private.prefix = if config.prefix.is_empty() {
+                            PRIVATE_KEY_PREFIX.to_string()
+                        } else {
+                            format!("{}/{}", PRIVATE_KEY_PREFIX, config.prefix)
+                        };
+                    }
+                }
+                private.custom_domain = None;
+                Arc::new(S3Storage::new(&private).await?)
+            }
+        };
+
+        Ok(Self {
+            backend,
+            area: StorageArea::Private,
+        })
+    }
+
+    /// 按区域创建存储服务实例
+    pub async fn for_area(
+        config: &crate::config_service::OssStorageConfig,
+        area: StorageArea,
+    ) -> Result<Self, StorageError> {
+        match area {
+            StorageArea::Public => Self::new(config).await,
+            StorageArea::Private => Self::new_private(config).await,
+        }
+    }
+
+    /// 按文件 URL 所在区域创建存储服务实例
+    pub async fn for_url(
+        config: &crate::config_service::OssStorageConfig,
+        url: &str,
+    ) -> Result<Self, StorageError> {
+        let area = if is_private_url(url) {
+            StorageArea::Private
+        } else {
+            StorageArea::Public
+        };
+        Self::for_area(config, area).await
+    }
+
+    /// 存储区域
+    pub fn area(&self) -> StorageArea {
+        self.area
     }
 
     /// 上传文件
@@ -636,10 +814,11 @@ impl StorageService {
         data: Bytes,
         content_type: Option<&str>,
     ) -> Result<UploadResult, RswsError> {
-        self.backend
-            .upload(key, data, content_type)
-            .await
-            .map_err(Into::into)
+        let mut result = self.backend.upload(key, data, content_type).await?;
+        if self.area == StorageArea::Private {
+            result.url = private_url(key);
+        }
+        Ok(result)
     }
 
     /// 下载文件
@@ -682,12 +861,143 @@ impl StorageService {
             .map_err(Into::into)
     }
 
-    /// 获取公开访问 URL
+    /// 获取访问 URL（私有区返回 `private://<key>`，只能经授权下载接口访问）
     pub fn public_url(&self, key: &str) -> String {
-        self.backend.public_url(key)
+        match self.area {
+            StorageArea::Public => self.backend.public_url(key),
+            StorageArea::Private => private_url(key),
+        }
+    }
+
+    /// 本地文件路径（非本地存储返回 None）
+    pub fn local_path(&self, key: &str) -> Option<PathBuf> {
+        self.backend.local_path(key)
+    }
+}
+
+// ==================== 私有区迁移 ====================
+
+/// 把公开区上传的文件复制到私有区，返回私有区 URL；
+/// 已在私有区或非本站上传的外部链接返回 None
+pub async fn copy_to_private(
+    config: &crate::config_service::OssStorageConfig,
+    url: &str,
+) -> Result<Option<String>, RswsError> {
+    if is_private_url(url) {
+        return Ok(None);
+    }
+    let Some(key) = key_from_url(url) else {
+        return Ok(None);
+    };
+
+    let public = StorageService::new(config).await?;
+    let private = StorageService::new_private(config).await?;
+    let content_type = public
+        .metadata(&key)
+        .await
+        .ok()
+        .and_then(|m| m.content_type);
+    let data = public.download(&key).await?;
+    let result = private.upload(&key, data, content_type.as_deref()).await?;
+
+    info!("File copied to private storage: {}", key);
+    Ok(Some(result.url))
+}
+
+/// 删除公开区副本（失败只记录日志）
+pub async fn delete_public_copy(config: &crate::config_service::OssStorageConfig, url: &str) {
+    let Some(key) = key_from_url(url) else {
+        return;
+    };
+    let public = match StorageService::new(config).await {
+        Ok(public) => public,
+        Err(e) => {
+            warn!("Failed to create storage service: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = public.delete(&key).await {
+        warn!("Failed to delete public copy {}: {}", key, e);
     }
 }
 
+/// 把公开区上传的文件移入私有区，返回最终 URL（无需移动时原样返回）
+pub async fn move_to_private(
+    config: &crate::config_service::OssStorageConfig,
+    url: &str,
+) -> Result<String, RswsError> {
+    match copy_to_private(config, url).await? {
+        Some(private) => {
+            delete_public_copy(config, url).await;
+            Ok(private)
+        }
+        None => Ok(url.to_string()),
+    }
+}
+
+// ==================== 私有区上传归属 ====================
+
+/// 私有区上传归属记录有效期（秒），超时未被资源引用需重新上传
+pub const PRIVATE_UPLOAD_TTL: u64 = 24 * 3600;
+
+fn private_upload_key(key: &str) -> String {
+    format!("private_upload:{}", key)
+}
+
+/// 记录私有区上传文件的上传者（资源引用该文件时校验）
+pub async fn record_private_upload(
+    redis: &RedisService,
+    key: &str,
+    user_id: i64,
+) -> Result<(), RswsError> {
+    redis
+        .set_ex(
+            &private_upload_key(key),
+            &user_id.to_string(),
+            PRIVATE_UPLOAD_TTL,
+        )
+        .await
+}
+
+/// 占用私有区上传文件：只接受该用户上传到私有区且尚未被引用的文件
+///
+/// 公开区文件、外部链接、他人上传或已被引用的私有文件一律拒绝。
+pub async fn claim_private_upload(
+    redis: &RedisService,
+    url: &str,
+    user_id: i64,
+) -> Result<(), RswsError> {
+    let record_key = url.strip_prefix(PRIVATE_URL_PREFIX).map(private_upload_key);
+    let owner = match record_key {
+        Some(ref record_key) => redis.get(record_key).await?,
+        None => None,
+    };
+    match (record_key, owner.and_then(|o| o.parse::<i64>().ok())) {
+        (Some(record_key), Some(owner)) if owner == user_id => redis.del(&record_key).await,
+        _ => Err(RswsError::bad_request(
+            "资源文件须由本人上传到私有区（area=private）",
+        )),
+    }
+}
+
+/// 私有区迁移失败的文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivateMigrationFailure {
+    pub file_url: String,
+    pub error: String,
+}
+
+/// 私有区迁移结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrivateMigrationReport {
+    pub dry_run: bool,
+    /// 需要迁移的文件
+    pub pending: Vec<String>,
+    /// 已迁移数量
+    pub moved: u64,
+    pub failed: Vec<PrivateMigrationFailure>,
+}
+
 // ==================== 单元测试 ====================
 
 #[cfg(test)]
@@ -721,10 +1031,40 @@ mod tests {
         let downloaded = storage.download("test.txt").await.unwrap();
         assert_eq!(downloaded, data);
 
+        // 本地路径
+        assert_eq!(
+            storage.local_path("test.txt"),
+            Some(temp_dir.join("test.txt"))
+        );
+        assert_eq!(storage.local_path("../secret.txt"), None);
+
         // 删除
         storage.delete("test.txt").await.unwrap();
 
         // 清理
         let _ = std::fs::remove_dir_all(temp_dir);
     }
+
+    #[test]
+    fn test_key_from_url() {
+        assert_eq!(
+            key_from_url("http://localhost:5170/uploads/resources/20240601/1_a.zip"),
+            Some("resources/20240601/1_a.zip".to_string())
+        );
+        assert_eq!(
+            key_from_url(&private_url("resources/20240601/1_a.zip")),
+            Some("resources/20240601/1_a.zip".to_string())
+        );
+        assert!(is_private_url("private://resources/1_a.zip"));
+        assert_eq!(key_from_url("https://example.com/file.zip"), None);
+    }
+
+    #[test]
+    fn test_check_private_dir() {
+        assert!(check_private_dir("storage/private", "uploads").is_ok());
+        assert!(check_private_dir("uploads_private", "uploads").is_ok());
+        assert!(check_private_dir("uploads/private", "uploads").is_err());
+        assert!(check_private_dir("./uploads/../uploads/private", "uploads/").is_err());
+        assert!(check_private_dir("uploads", "uploads").is_err());
+    }
 }
@@ -6,6 +6,7 @@ use crate::config_service::PayPalDbConfig;
 use reqwest::Client;
 use rsws_common::error::RswsError;
 use rsws_common::snowflake;
+use rust_decimal::Decimal;
 use serde_json::Value;
 use tracing::{info, warn};
 
@@ -35,6 +36,14 @@ impl PayPalService {
             .is_some_and(|c| !c.client_id.is_empty())
     }
 
+    /// 按配置费率估算通道手续费（以订单金额计，保留两位小数）
+    pub fn fee_for(&self, amount: Decimal) -> Decimal {
+        self.config
+            .as_ref()
+            .map(|c| (amount * c.fee_rate).round_dp(2))
+            .unwrap_or(Decimal::ZERO)
+    }
+
     /// 获取 base URL（sandbox 或 live）
     fn api_base_url(&self) -> &str {
         match &self.config {
@@ -0,0 +1,247 @@
+//! 支付报价服务
+//!
+//! 下单时按支付通道配置（paypal_configs / alipay_configs / wechatpay_configs /
+//! blockchain_configs）的 `fee_rate`、`min_amount`、`max_amount` 计算费用明细并校验限额。
+//!
+//! 手续费承担方由 system_configs 的 `payment_fee_bearer` 决定：
+//! - `platform`（默认）：平台承担，买家只付商品金额
+//! - `buyer`：手续费作为附加费计入订单金额
+//!
+//! 余额支付无手续费、不限额。
+
+use crate::config_service::ConfigService;
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_model::payment::{
+    PaymentFeeSchedule, PaymentQuote, FEE_BEARER_BUYER, FEE_BEARER_PLATFORM,
+};
+use rust_decimal::Decimal;
+use std::sync::Arc;
+
+/// 手续费承担方配置键
+pub const FEE_BEARER_CONFIG_KEY: &str = "payment_fee_bearer";
+
+/// 支付报价服务
+#[derive(Clone)]
+pub struct QuoteService {
+    config_service: Arc<ConfigService>,
+}
+
+impl QuoteService {
+    /// 创建支付报价服务实例
+    pub fn new(config_service: Arc<ConfigService>) -> Self {
+        Self { config_service }
+    }
+
+    /// 获取支付方式对应通道的费率与限额（余额支付或通道未配置时为 None）
+    pub async fn schedule(
+        &self,
+        payment_method: &str,
+    ) -> Result<Option<PaymentFeeSchedule>, RswsError> {
+        let schedule = match payment_method {
+            "paypal" => {
+                self.config_service
+                    .get_paypal_config()
+                    .await?
+                    .map(|c| PaymentFeeSchedule {
+                        fee_rate: c.fee_rate,
+                        min_amount: c.min_amount,
+                        max_amount: c.max_amount,
+                        exchange_rate: Decimal::ONE,
+                    })
+            }
+            "alipay" => {
+                self.config_service
+                    .get_alipay_config()
+                    .await?
+                    .map(|c| PaymentFeeSchedule {
+                        fee_rate: c.fee_rate,
+                        min_amount: c.min_amount,
+                        max_amount: c.max_amount,
+                        exchange_rate: c.exchange_rate,
+                    })
+            }
+            "wechatpay" => {
+                self.config_service
+                    .get_wechatpay_config()
+                    .await?
+                    .map(|c| PaymentFeeSchedule {
+                        fee_rate: c.fee_rate,
+                        min_amount: c.min_amount,
+                        max_amount: c.max_amount,
+                        exchange_rate: c.exchange_rate,
+                    })
+            }
+            "usdt_trc20" | "usdt_erc20" => {
+                let network = if payment_method == "usdt_trc20" {
+                    "tron"
+                } else {
+                    "ethereum"
+                };
+                self.config_service
+                    .get_blockchain_config(network)
+                    .await?
+                    .map(|c| PaymentFeeSchedule {
+                        fee_rate: c.fee_rate,
+                        min_amount: c.min_amount,
+                        max_amount: c.max_amount,
+                        exchange_rate: Decimal::ONE,
+                    })
+            }
+            "balance" => None,
+            _ => return Err(RswsError::business(ErrorCode::PAYMENT_METHOD_NOT_SUPPORTED)),
+        };
+        Ok(schedule)
+    }
+
+    /// 获取手续费承担方（未配置或配置无效时为平台承担）
+    pub async fn fee_bearer(&self) -> Result<&'static str, RswsError> {
+        let bearer = self.config_service.get(FEE_BEARER_CONFIG_KEY).await?;
+        Ok(match bearer.as_deref().map(str::trim) {
+            Some(FEE_BEARER_BUYER) => FEE_BEARER_BUYER,
+            _ => FEE_BEARER_PLATFORM,
+        })
+    }
+
+    /// 计算报价并校验通道限额
+    pub async fn quote(
+        &self,
+        payment_method: &str,
+        subtotal: Decimal,
+    ) -> Result<PaymentQuote, RswsError> {
+        let schedule = self.schedule(payment_method).await?;
+        let bearer = self.fee_bearer().await?;
+        build_quote(payment_method, subtotal, schedule.as_ref(), bearer)
+    }
+}
+
+/// 按费率与承担方计算报价，超出通道限额时返回
+/// `PAYMENT_AMOUNT_TOO_SMALL` / `PAYMENT_AMOUNT_TOO_LARGE`
+///
+/// 限额按买家实付金额换算为通道结算币种后比较；免费订单不校验限额。
+pub fn build_quote(
+    payment_method: &str,
+    subtotal: Decimal,
+    schedule: Option<&PaymentFeeSchedule>,
+    fee_bearer: &str,
+) -> Result<PaymentQuote, RswsError> {
+    if subtotal < Decimal::ZERO {
+        return Err(RswsError::business(ErrorCode::PAYMENT_AMOUNT_INVALID));
+    }
+
+    let fee = schedule
+        .map(|s| (subtotal * s.fee_rate).round_dp(2))
+        .unwrap_or(Decimal::ZERO);
+    let surcharge = if fee_bearer == FEE_BEARER_BUYER {
+        fee
+    } else {
+        Decimal::ZERO
+    };
+    let total = subtotal + surcharge;
+
+    let exchange_rate = schedule
+        .map(|s| s.exchange_rate)
+        .filter(|r| *r > Decimal::ZERO)
+        .unwrap_or(Decimal::ONE);
+    let settlement_amount = (total * exchange_rate).round_dp(2);
+
+    if let Some(s) = schedule {
+        if total > Decimal::ZERO {
+            if settlement_amount < s.min_amount {
+                return Err(RswsError::business_with_message(
+                    ErrorCode::PAYMENT_AMOUNT_TOO_SMALL,
+                    format!("Minimum amount for {} is {}", payment_method, s.min_amount),
+                ));
+            }
+            if s.max_amount > Decimal::ZERO && settlement_amount > s.max_amount {
+                return Err(RswsError::business_with_message(
+                    ErrorCode::PAYMENT_AMOUNT_TOO_LARGE,
+                    format!("Maximum amount for {} is {}", payment_method, s.max_amount),
+                ));
+            }
+        }
+    }
+
+    Ok(PaymentQuote {
+        payment_method: payment_method.to_string(),
+        subtotal,
+        fee,
+        fee_bearer: fee_bearer.to_string(),
+        surcharge,
+        total,
+        settlement_amount,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(rate: i64, min: i64, max: i64) -> PaymentFeeSchedule {
+        PaymentFeeSchedule {
+            fee_rate: Decimal::new(rate, 4),
+            min_amount: Decimal::new(min, 0),
+            max_amount: Decimal::new(max, 0),
+            exchange_rate: Decimal::ONE,
+        }
+    }
+
+    #[test]
+    fn test_build_quote_fee_bearer() {
+        let s = schedule(300, 1, 1000);
+
+        let absorbed = build_quote(
+            "paypal",
+            Decimal::new(100, 0),
+            Some(&s),
+            FEE_BEARER_PLATFORM,
+        )
+        .unwrap();
+        assert_eq!(absorbed.fee, Decimal::new(3, 0));
+        assert_eq!(absorbed.surcharge, Decimal::ZERO);
+        assert_eq!(absorbed.total, Decimal::new(100, 0));
+
+        let surcharged =
+            build_quote("paypal", Decimal::new(100, 0), Some(&s), FEE_BEARER_BUYER).unwrap();
+        assert_eq!(surcharged.surcharge, Decimal::new(3, 0));
+        assert_eq!(surcharged.total, Decimal::new(103, 0));
+
+        let balance = build_quote("balance", Decimal::new(100, 0), None, FEE_BEARER_BUYER).unwrap();
+        assert_eq!(balance.total, Decimal::new(100, 0));
+    }
+
+    #[test]
+    fn test_build_quote_limits() {
+        let s = schedule(0, 1, 100);
+
+        let err =
+            build_quote("paypal", Decimal::new(50, 2), Some(&s), FEE_BEARER_PLATFORM).unwrap_err();
+        assert_eq!(err.error_code(), ErrorCode::PAYMENT_AMOUNT_TOO_SMALL);
+
+        let err = build_quote(
+            "paypal",
+            Decimal::new(101, 0),
+            Some(&s),
+            FEE_BEARER_PLATFORM,
+        )
+        .unwrap_err();
+        assert_eq!(err.error_code(), ErrorCode::PAYMENT_AMOUNT_TOO_LARGE);
+
+        // 免费订单不校验限额
+        assert!(build_quote("paypal", Decimal::ZERO, Some(&s), FEE_BEARER_PLATFORM).is_ok());
+
+        // 限额按结算币种比较
+        let cny = PaymentFeeSchedule {
+            exchange_rate: Decimal::new(7, 0),
+            ..schedule(0, 1, 100)
+        };
+        let err = build_quote(
+            "alipay",
+            Decimal::new(20, 0),
+            Some(&cny),
+            FEE_BEARER_PLATFORM,
+        )
+        .unwrap_err();
+        assert_eq!(err.error_code(), ErrorCode::PAYMENT_AMOUNT_TOO_LARGE);
+    }
+}
@@ -0,0 +1,245 @@
+//! 资源评分与评价服务
+//!
+//! - 已购买资源的用户（`OrderService::check_purchased`）可评分并评价，创作者不能评价自己的资源
+//! - 创作者回复自己资源下的评价
+//! - 用户举报评价，管理员隐藏 / 恢复，处理后关闭该评价的待处理举报
+//! - 评价变更后刷新资源的平均分与评分数
+
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_db::{RatingRepository, ResourceRepository};
+use rsws_model::rating::{
+    is_valid_rating, normalize_text, HideRatingRequest, RatingListItem, ReplyRatingRequest,
+    ReportRatingRequest, ReportedRating, ResourceRating, SubmitRatingRequest,
+    MAX_RATING_CONTENT_LEN, MAX_REPORT_REASON_LEN, RATING_STATUS_HIDDEN, RATING_STATUS_VISIBLE,
+};
+use rsws_model::resource::OWNER_TYPE_USER;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::order_service::OrderService;
+
+/// 资源评分服务
+#[derive(Clone)]
+pub struct RatingService {
+    rating_repo: Arc<RatingRepository>,
+    resource_repo: Arc<ResourceRepository>,
+    order_service: Arc<OrderService>,
+}
+
+impl RatingService {
+    /// 创建资源评分服务实例
+    pub fn new(
+        rating_repo: Arc<RatingRepository>,
+        resource_repo: Arc<ResourceRepository>,
+        order_service: Arc<OrderService>,
+    ) -> Self {
+        Self {
+            rating_repo,
+            resource_repo,
+            order_service,
+        }
+    }
+
+    /// 资源的可见评价（仅公开资源）
+    pub async fn list(
+        &self,
+        resource_id: i64,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<RatingListItem>, i64), RswsError> {
+        let resource = self
+            .resource_repo
+            .get_by_id(resource_id)
+            .await?
+            .filter(|r| r.is_public())
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_NOT_FOUND))?;
+
+        self.rating_repo
+            .list_visible(resource.id, page, page_size)
+            .await
+    }
+
+    /// 提交或修改评价（仅已购买的用户）
+    pub async fn submit(
+        &self,
+        user_id: i64,
+        resource_id: i64,
+        req: &SubmitRatingRequest,
+    ) -> Result<ResourceRating, RswsError> {
+        if !is_valid_rating(req.rating) {
+            return Err(RswsError::bad_request("Rating must be between 1 and 5"));
+        }
+        let content = validate_text(req.content.as_deref(), MAX_RATING_CONTENT_LEN, "Content")?;
+
+        let resource = self
+            .resource_repo
+            .get_by_id(resource_id)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_NOT_FOUND))?;
+
+        if resource.provider_id == Some(user_id) {
+            return Err(RswsError::business_with_message(
+                ErrorCode::AUTH_PERMISSION_DENIED,
+                "Creators cannot rate their own resources",
+            ));
+        }
+
+        if !self
+            .order_service
+            .check_purchased(user_id, resource_id)
+            .await?
+        {
+            return Err(RswsError::business(
+                ErrorCode::RESOURCE_RATING_NOT_PURCHASED,
+            ));
+        }
+
+        let saved = self
+            .rating_repo
+            .upsert(resource_id, user_id, req.rating, content.as_deref())
+            .await?;
+
+        info!(
+            "User {} rated resource {}: {}",
+            user_id, resource_id, saved.rating
+        );
+        Ok(saved)
+    }
+
+    /// 创作者回复评价
+    pub async fn reply(
+        &self,
+        rating_id: i64,
+        user_id: i64,
+        req: &ReplyRatingRequest,
+    ) -> Result<ResourceRating, RswsError> {
+        let reply = validate_text(Some(&req.reply), MAX_RATING_CONTENT_LEN, "Reply")?
+            .ok_or_else(|| RswsError::bad_request("Reply is required"))?;
+
+        let rating = self.find(rating_id).await?;
+        let resource = self
+            .resource_repo
+            .get_by_id(rating.resource_id)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_NOT_FOUND))?;
+
+        if resource.owner_type != OWNER_TYPE_USER || resource.provider_id != Some(user_id) {
+            return Err(RswsError::business(ErrorCode::AUTH_PERMISSION_DENIED));
+        }
+
+        self.rating_repo
+            .reply(rating_id, &reply)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_RATING_NOT_FOUND))
+    }
+
+    /// 举报评价（每个用户对同一评价只能举报一次）
+    pub async fn report(
+        &self,
+        rating_id: i64,
+        user_id: i64,
+        req: &ReportRatingRequest,
+    ) -> Result<(), RswsError> {
+        let reason = validate_text(Some(&req.reason), MAX_REPORT_REASON_LEN, "Reason")?
+            .ok_or_else(|| RswsError::bad_request("Reason is required"))?;
+
+        let rating = self.find(rating_id).await?;
+        if rating.status != RATING_STATUS_VISIBLE {
+            return Err(RswsError::business(ErrorCode::RESOURCE_RATING_NOT_FOUND));
+        }
+        if rating.user_id == user_id {
+            return Err(RswsError::bad_request("Cannot report your own rating"));
+        }
+
+        if !self.rating_repo.report(rating_id, user_id, &reason).await? {
+            return Err(RswsError::business(
+                ErrorCode::RESOURCE_RATING_ALREADY_REPORTED,
+            ));
+        }
+
+        info!("Rating {} reported by user {}", rating_id, user_id);
+        Ok(())
+    }
+
+    /// 待处理举报队列
+    pub async fn reported(
+        &self,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<ReportedRating>, i64), RswsError> {
+        self.rating_repo.reported(page, page_size).await
+    }
+
+    /// 管理员隐藏评价（不计入资源评分）
+    pub async fn hide(
+        &self,
+        rating_id: i64,
+        admin_id: i64,
+        req: &HideRatingRequest,
+    ) -> Result<ResourceRating, RswsError> {
+        let reason = validate_text(req.reason.as_deref(), MAX_REPORT_REASON_LEN, "Reason")?;
+        let rating = self
+            .rating_repo
+            .set_status(rating_id, RATING_STATUS_HIDDEN, reason.as_deref(), admin_id)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_RATING_NOT_FOUND))?;
+
+        info!("Rating {} hidden by admin {}", rating_id, admin_id);
+        Ok(rating)
+    }
+
+    /// 管理员恢复评价
+    pub async fn restore(
+        &self,
+        rating_id: i64,
+        admin_id: i64,
+    ) -> Result<ResourceRating, RswsError> {
+        let rating = self
+            .rating_repo
+            .set_status(rating_id, RATING_STATUS_VISIBLE, None, admin_id)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_RATING_NOT_FOUND))?;
+
+        info!("Rating {} restored by admin {}", rating_id, admin_id);
+        Ok(rating)
+    }
+
+    async fn find(&self, rating_id: i64) -> Result<ResourceRating, RswsError> {
+        self.rating_repo
+            .find(rating_id)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_RATING_NOT_FOUND))
+    }
+}
+
+/// 去除首尾空白并校验长度
+fn validate_text(
+    text: Option<&str>,
+    max_len: usize,
+    field: &str,
+) -> Result<Option<String>, RswsError> {
+    let text = normalize_text(text);
+    if text.as_ref().is_some_and(|t| t.chars().count() > max_len) {
+        return Err(RswsError::bad_request(format!(
+            "{} must be at most {} characters",
+            field, max_len
+        )));
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_text() {
+        assert_eq!(
+            validate_text(Some("  ok "), 10, "Reply").unwrap(),
+            Some("ok".to_string())
+        );
+        assert_eq!(validate_text(Some(" "), 10, "Reply").unwrap(), None);
+        assert!(validate_text(Some("too long text"), 5, "Reply").is_err());
+    }
+}
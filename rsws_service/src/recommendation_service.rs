@@ -0,0 +1,93 @@
+//! 资源推荐服务
+//!
+//! - 资源详情页"相关资源"与"买了还买"
+//! - "本周热门"资源榜
+//!
+//! 推荐与热门榜由后台任务定期重算写入缓存表，请求只读缓存；
+//! 新上架资源在下一次重算前没有推荐，详情页返回空列表。
+
+use rsws_common::error::RswsError;
+use rsws_db::RecommendationRepository;
+use rsws_model::recommendation::{
+    PopularResource, RecommendationRefresh, ResourceRecommendations, MAX_RECOMMENDATIONS,
+    POPULAR_DAYS, POPULAR_LIMIT, RECOMMENDATION_KIND_ALSO_BOUGHT, RECOMMENDATION_KIND_RELATED,
+};
+use std::sync::Arc;
+use tracing::{error, info};
+
+/// 资源推荐服务
+pub struct RecommendationService {
+    recommendation_repo: Arc<RecommendationRepository>,
+}
+
+impl RecommendationService {
+    /// 创建资源推荐服务实例
+    pub fn new(recommendation_repo: Arc<RecommendationRepository>) -> Self {
+        Self {
+            recommendation_repo,
+        }
+    }
+
+    /// 资源详情页推荐
+    pub async fn for_resource(
+        &self,
+        resource_id: i64,
+        limit: i64,
+    ) -> Result<ResourceRecommendations, RswsError> {
+        let limit = limit.clamp(1, MAX_RECOMMENDATIONS);
+        let related = self
+            .recommendation_repo
+            .list(resource_id, RECOMMENDATION_KIND_RELATED, limit)
+            .await?;
+        let also_bought = self
+            .recommendation_repo
+            .list(resource_id, RECOMMENDATION_KIND_ALSO_BOUGHT, limit)
+            .await?;
+        Ok(ResourceRecommendations {
+            related,
+            also_bought,
+        })
+    }
+
+    /// 本周热门
+    pub async fn popular(&self, limit: i64) -> Result<Vec<PopularResource>, RswsError> {
+        self.recommendation_repo
+            .popular(limit.clamp(1, POPULAR_LIMIT))
+            .await
+    }
+
+    /// 重算推荐与热门榜缓存（后台任务与管理员手动触发）
+    pub async fn refresh(&self) -> Result<RecommendationRefresh, RswsError> {
+        let (related, also_bought) = self
+            .recommendation_repo
+            .refresh_recommendations(MAX_RECOMMENDATIONS)
+            .await?;
+        let popular = self
+            .recommendation_repo
+            .refresh_popular(POPULAR_DAYS, POPULAR_LIMIT)
+            .await?;
+
+        info!(
+            "Recommendations refreshed: {} related, {} also bought, {} popular",
+            related, also_bought, popular
+        );
+        Ok(RecommendationRefresh {
+            related,
+            also_bought,
+            popular,
+        })
+    }
+
+    /// 启动后台推荐重算任务
+    pub fn start_background(self: Arc<Self>, interval_secs: u64) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.refresh().await {
+                    error!("Recommendation refresh task failed: {}", e);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,149 @@
+//! 推荐计划服务
+//!
+//! - 推荐码：首次访问时为用户生成，之后保持不变
+//! - 归因：注册时记录推荐人（每个用户仅一次）；下单时优先取本次携带的推荐码，
+//!   否则沿用注册时的推荐人
+//! - 推荐人仪表盘：点击、注册、订单与佣金统计
+
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_common::utils::generate_referral_code;
+use rsws_db::ReferralRepository;
+use rsws_model::referral::{
+    normalize_referral_code, ReferralCode, ReferralDashboard, ReferralEarning, REFERRAL_QUERY_PARAM,
+};
+use std::sync::Arc;
+use tracing::info;
+
+/// 生成推荐码的最大重试次数（推荐码冲突时重试）
+const MAX_CODE_ATTEMPTS: usize = 5;
+
+/// 推荐计划服务
+pub struct ReferralService {
+    repo: Arc<ReferralRepository>,
+}
+
+impl ReferralService {
+    /// 创建推荐计划服务实例
+    pub fn new(repo: Arc<ReferralRepository>) -> Self {
+        Self { repo }
+    }
+
+    /// 获取用户推荐码，不存在时生成
+    pub async fn get_or_create_code(&self, user_id: i64) -> Result<ReferralCode, RswsError> {
+        if let Some(code) = self.repo.find_code(user_id).await? {
+            return Ok(code);
+        }
+        for _ in 0..MAX_CODE_ATTEMPTS {
+            if let Some(code) = self
+                .repo
+                .insert_code(user_id, &generate_referral_code())
+                .await?
+            {
+                return Ok(code);
+            }
+            // 并发请求已为该用户生成推荐码
+            if let Some(code) = self.repo.find_code(user_id).await? {
+                return Ok(code);
+            }
+        }
+        Err(RswsError::internal(
+            "Failed to generate a unique referral code",
+        ))
+    }
+
+    /// 解析推荐码对应的推荐人，推荐码无效时返回 None
+    pub async fn resolve(&self, code: &str) -> Result<Option<(i64, String)>, RswsError> {
+        let code = match normalize_referral_code(code) {
+            Some(c) => c,
+            None => return Ok(None),
+        };
+        Ok(self.repo.find_referrer(&code).await?.map(|id| (id, code)))
+    }
+
+    /// 记录推荐链接点击，返回规范化后的推荐码
+    pub async fn record_click(
+        &self,
+        code: &str,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+        landing_path: Option<&str>,
+    ) -> Result<String, RswsError> {
+        let (referrer_id, code) = self
+            .resolve(code)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::REFERRAL_CODE_NOT_FOUND))?;
+        let landing_path = landing_path.map(|p| p.chars().take(500).collect::<String>());
+        self.repo
+            .record_click(
+                referrer_id,
+                &code,
+                ip_address,
+                user_agent,
+                landing_path.as_deref(),
+            )
+            .await?;
+        Ok(code)
+    }
+
+    /// 注册归因，推荐码无效时忽略
+    pub async fn attribute_signup(
+        &self,
+        referee_id: i64,
+        code: &str,
+    ) -> Result<Option<i64>, RswsError> {
+        let (referrer_id, code) = match self.resolve(code).await? {
+            Some(r) => r,
+            None => return Ok(None),
+        };
+        if !self
+            .repo
+            .attribute_signup(referee_id, referrer_id, &code)
+            .await?
+        {
+            return Ok(None);
+        }
+        info!("User {} referred by {}", referee_id, referrer_id);
+        Ok(Some(referrer_id))
+    }
+
+    /// 订单归因，返回订单的推荐人
+    pub async fn attribute_order(
+        &self,
+        order_id: i64,
+        buyer_id: i64,
+        code: Option<&str>,
+    ) -> Result<Option<i64>, RswsError> {
+        let referrer_id = match code {
+            Some(code) => self.resolve(code).await?.map(|(id, _)| id),
+            None => None,
+        };
+        self.repo
+            .attribute_order(order_id, buyer_id, referrer_id)
+            .await
+    }
+
+    /// 推荐人仪表盘
+    pub async fn dashboard(&self, user_id: i64) -> Result<ReferralDashboard, RswsError> {
+        let code = self.get_or_create_code(user_id).await?;
+        let stats = self.repo.stats(user_id).await?;
+        Ok(ReferralDashboard {
+            link: format!("/?{}={}", REFERRAL_QUERY_PARAM, code.code),
+            code: code.code,
+            stats,
+        })
+    }
+
+    /// 分页获取推荐佣金明细
+    pub async fn list_earnings(
+        &self,
+        user_id: i64,
+        status: Option<&str>,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<ReferralEarning>, i64), RswsError> {
+        self.repo
+            .list_earnings(user_id, status, page, page_size)
+            .await
+    }
+}
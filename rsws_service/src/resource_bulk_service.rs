@@ -0,0 +1,674 @@
+//! 资源批量导入导出
+//!
+//! - 管理员上传 CSV / JSON Lines，先整体校验，试运行只返回逐行报告；正式导入逐行按
+//!   `external_id` 新建或更新平台资源，失败的行不影响其他行，修正后可重复导入
+//! - 资源文件与图片可引用导入目录下的文件（导入时上传到存储）、已有存储 key 或 URL
+//! - 导出全部资源（含分类、标签与销售统计），用于备份与表格编辑
+
+use crate::config_service::{ConfigService, OssStorageConfig};
+use crate::oss_service::{is_private_url, key_from_url, StorageArea, StorageService};
+use crate::resource_service::ResourceService;
+use crate::tag_service::TagService;
+use bytes::Bytes;
+use rand::Rng;
+use rsws_common::error::RswsError;
+use rsws_common::utils::csv_field;
+use rsws_db::{Category, CategoryRepository, ResourceRepository};
+use rsws_model::resource::{CreateResourceRequest, UpdateResourceRequest, OWNER_TYPE_PLATFORM};
+use rsws_model::resource_import::{
+    import_rows_from_csv, import_rows_from_jsonl, ImportReport, ImportRowResult, ResourceExportRow,
+    ResourceImportRow, FORMAT_CSV, FORMAT_JSONL, IMPORT_ACTION_CREATE, IMPORT_ACTION_UPDATE,
+    LIST_SEPARATOR, MAX_IMPORT_ROWS,
+};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// 导入目录默认路径（可用环境变量 `RSWS_IMPORT_DIR` 覆盖）
+pub const DEFAULT_IMPORT_DIR: &str = "storage/import";
+
+/// 资源批量导入导出服务
+pub struct ResourceBulkService {
+    resource_repo: Arc<ResourceRepository>,
+    category_repo: Arc<CategoryRepository>,
+    resource_service: Arc<ResourceService>,
+    tag_service: Arc<TagService>,
+    config_service: ConfigService,
+    import_dir: PathBuf,
+}
+
+/// 资源文件 / 图片引用
+#[derive(Debug, Clone, PartialEq)]
+enum AssetRef {
+    /// 已有 URL（http(s) 或 `private://`），原样使用
+    Url(String),
+    /// 公开区存储 key（`resources/...`）
+    Key(String),
+    /// 导入目录下的文件（相对路径）
+    Local(PathBuf),
+}
+
+/// 校验通过的导入行
+struct PlannedRow {
+    line: usize,
+    row: ResourceImportRow,
+    category_id: Option<i64>,
+    /// 已存在的资源 ID（更新）
+    existing: Option<i64>,
+    /// 已有资源文件的资源不替换文件（需发布新版本）
+    keep_file: bool,
+}
+
+/// 导入过程中按需创建的存储服务
+struct AssetStore {
+    config: OssStorageConfig,
+    public: Option<StorageService>,
+    private: Option<StorageService>,
+}
+
+impl AssetStore {
+    async fn get(&mut self, area: StorageArea) -> Result<&StorageService, RswsError> {
+        let slot = match area {
+            StorageArea::Public => &mut self.public,
+            StorageArea::Private => &mut self.private,
+        };
+        let service = match slot.take() {
+            Some(service) => service,
+            None => StorageService::for_area(&self.config, area).await?,
+        };
+        Ok(slot.insert(service))
+    }
+}
+
+impl ResourceBulkService {
+    /// 创建资源批量导入导出服务实例
+    pub fn new(
+        resource_repo: Arc<ResourceRepository>,
+        category_repo: Arc<CategoryRepository>,
+        resource_service: Arc<ResourceService>,
+        tag_service: Arc<TagService>,
+        config_service: ConfigService,
+    ) -> Self {
+        let import_dir =
+            std::env::var("RSWS_IMPORT_DIR").unwrap_or_else(|_| DEFAULT_IMPORT_DIR.to_string());
+        Self {
+            resource_repo,
+            category_repo,
+            resource_service,
+            tag_service,
+            config_service,
+            import_dir: PathBuf::from(import_dir),
+        }
+    }
+
+    /// 导入资源（`format` 为 csv / jsonl；`dry_run` 只校验不写入）
+    pub async fn import(
+        &self,
+        text: &str,
+        format: &str,
+        dry_run: bool,
+        admin_id: i64,
+    ) -> Result<ImportReport, RswsError> {
+        let parsed = match format {
+            FORMAT_CSV => import_rows_from_csv(text).map_err(RswsError::bad_request)?,
+            FORMAT_JSONL => import_rows_from_jsonl(text),
+            other => {
+                return Err(RswsError::bad_request(format!(
+                    "Unsupported import format: {}",
+                    other
+                )))
+            }
+        };
+        if parsed.is_empty() {
+            return Err(RswsError::bad_request("Import file has no rows"));
+        }
+        if parsed.len() > MAX_IMPORT_ROWS {
+            return Err(RswsError::bad_request(format!(
+                "At most {} rows per import",
+                MAX_IMPORT_ROWS
+            )));
+        }
+
+        let categories = self
+            .category_repo
+            .find_all_with_inactive()
+            .await
+            .map_err(|e| RswsError::internal(format!("Failed to load categories: {}", e)))?;
+        let mut store = AssetStore {
+            config: self.config_service.get_storage_config().await?,
+            public: None,
+            private: None,
+        };
+
+        let mut report = ImportReport {
+            dry_run,
+            total: parsed.len(),
+            created: 0,
+            updated: 0,
+            failed: 0,
+            rows: Vec::with_capacity(parsed.len()),
+        };
+        let mut seen: HashMap<String, usize> = HashMap::new();
+
+        for (line, row) in parsed {
+            let mut result = ImportRowResult {
+                row: line,
+                external_id: None,
+                action: None,
+                resource_id: None,
+                errors: Vec::new(),
+                warnings: Vec::new(),
+            };
+
+            let plan = match row {
+                Ok(row) => {
+                    result.external_id = Some(row.external_id.clone()).filter(|id| !id.is_empty());
+                    self.plan_row(line, row, &categories, &mut seen, &mut store, &mut result)
+                        .await?
+                }
+                Err(e) => {
+                    result.errors.push(e);
+                    None
+                }
+            };
+
+            if let Some(plan) = plan {
+                let action = if plan.existing.is_some() {
+                    IMPORT_ACTION_UPDATE
+                } else {
+                    IMPORT_ACTION_CREATE
+                };
+                result.action = Some(action.to_string());
+                result.resource_id = plan.existing;
+
+                if !dry_run {
+                    match self.apply_row(&plan, &mut store, admin_id).await {
+                        Ok(id) => result.resource_id = Some(id),
+                        Err(e) => {
+                            warn!("Failed to import row {}: {}", line, e);
+                            result.errors.push(e.to_string());
+                        }
+                    }
+                }
+            }
+
+            if !result.errors.is_empty() {
+                report.failed += 1;
+            } else if result.action.as_deref() == Some(IMPORT_ACTION_CREATE) {
+                report.created += 1;
+            } else {
+                report.updated += 1;
+            }
+            report.rows.push(result);
+        }
+
+        info!(
+            "Resource import by admin {}: dry_run={}, total={}, created={}, updated={}, failed={}",
+            admin_id, dry_run, report.total, report.created, report.updated, report.failed
+        );
+        Ok(report)
+    }
+
+    /// 校验单行：字段、文件内重复、分类、已有资源与资源文件引用，错误记入 `result`
+    async fn plan_row(
+        &self,
+        line: usize,
+        row: ResourceImportRow,
+        categories: &[Category],
+        seen: &mut HashMap<String, usize>,
+        store: &mut AssetStore,
+        result: &mut ImportRowResult,
+    ) -> Result<Option<PlannedRow>, RswsError> {
+        result.errors.extend(row.validate());
+
+        if !row.external_id.is_empty() {
+            if let Some(first) = seen.get(&row.external_id) {
+                result
+                    .errors
+                    .push(format!("duplicate external_id (first at row {})", first));
+            } else {
+                seen.insert(row.external_id.clone(), line);
+            }
+        }
+
+        let category_id = resolve_category(&row, categories).unwrap_or_else(|e| {
+            result.errors.push(e);
+            None
+        });
+
+        let mut existing = None;
+        let mut keep_file = false;
+        if !row.external_id.is_empty() {
+            match self
+                .resource_repo
+                .find_by_external_id(&row.external_id)
+                .await?
+            {
+                Some((id, true)) => {
+                    existing = Some(id);
+                    let current = self.resource_repo.get_by_id(id).await?;
+                    let current_file = current
+                        .and_then(|r| r.file_url)
+                        .filter(|url| !url.is_empty());
+                    if let Some(current_file) = current_file {
+                        keep_file = true;
+                        if row.file.as_ref().is_some_and(|f| *f != current_file) {
+                            result.warnings.push(
+                                "file ignored: resource already has a file, publish a new version to replace it"
+                                    .to_string(),
+                            );
+                        }
+                    }
+                }
+                Some((id, false)) => result.errors.push(format!(
+                    "resource {} with this external_id has been deleted",
+                    id
+                )),
+                None => {}
+            }
+        }
+
+        let file = row.file.as_deref().filter(|_| !keep_file);
+        let images = row.display_images.iter().flatten().map(String::as_str);
+        for value in file
+            .into_iter()
+            .chain(row.thumbnail.as_deref())
+            .chain(images)
+        {
+            if let Err(e) = self.check_asset(value, store).await {
+                result.errors.push(e);
+            }
+        }
+
+        if !result.errors.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(PlannedRow {
+            line,
+            row,
+            category_id,
+            existing,
+            keep_file,
+        }))
+    }
+
+    /// 检查资源文件引用是否存在
+    async fn check_asset(&self, value: &str, store: &mut AssetStore) -> Result<(), String> {
+        let (area, key) = match parse_asset(value)? {
+            AssetRef::Url(url) if is_private_url(&url) => {
+                (StorageArea::Private, key_from_url(&url).unwrap_or_default())
+            }
+            AssetRef::Url(_) => return Ok(()),
+            AssetRef::Key(key) => (StorageArea::Public, key),
+            AssetRef::Local(path) => {
+                return match tokio::fs::metadata(self.import_dir.join(&path)).await {
+                    Ok(meta) if meta.is_file() => Ok(()),
+                    _ => Err(format!("file not found in import directory: {}", value)),
+                };
+            }
+        };
+
+        let storage = store.get(area).await.map_err(|e| e.to_string())?;
+        match storage.exists(&key).await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(format!("file not found in storage: {}", value)),
+            Err(e) => Err(format!("failed to check {}: {}", value, e)),
+        }
+    }
+
+    /// 把资源文件引用转为 URL，导入目录下的文件上传到存储
+    async fn resolve_asset(
+        &self,
+        value: &str,
+        area: StorageArea,
+        store: &mut AssetStore,
+    ) -> Result<String, RswsError> {
+        match parse_asset(value).map_err(RswsError::bad_request)? {
+            AssetRef::Url(url) => Ok(url),
+            AssetRef::Key(key) => Ok(store.get(StorageArea::Public).await?.public_url(&key)),
+            AssetRef::Local(path) => {
+                let data = tokio::fs::read(self.import_dir.join(&path))
+                    .await
+                    .map_err(|e| RswsError::internal(format!("Failed to read {}: {}", value, e)))?;
+                let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+                let key = import_file_key(ext);
+                let result = store
+                    .get(area)
+                    .await?
+                    .upload(&key, Bytes::from(data), content_type_for(ext))
+                    .await?;
+                Ok(result.url)
+            }
+        }
+    }
+
+    /// 写入单行，返回资源 ID
+    async fn apply_row(
+        &self,
+        plan: &PlannedRow,
+        store: &mut AssetStore,
+        admin_id: i64,
+    ) -> Result<i64, RswsError> {
+        let row = &plan.row;
+
+        // 付费资源文件直接上传到私有区
+        let file_area = if row.price > Decimal::ZERO {
+            StorageArea::Private
+        } else {
+            StorageArea::Public
+        };
+        let file_url = match row.file.as_deref().filter(|_| !plan.keep_file) {
+            Some(value) => Some(self.resolve_asset(value, file_area, store).await?),
+            None => None,
+        };
+        let thumbnail_url = match row.thumbnail.as_deref() {
+            Some(value) => Some(
+                self.resolve_asset(value, StorageArea::Public, store)
+                    .await?,
+            ),
+            None => None,
+        };
+        let display_images = match row.display_images.as_ref() {
+            Some(images) => {
+                let mut urls = Vec::with_capacity(images.len());
+                for value in images {
+                    urls.push(
+                        self.resolve_asset(value, StorageArea::Public, store)
+                            .await?,
+                    );
+                }
+                Some(urls)
+            }
+            None => None,
+        };
+
+        let id = match plan.existing {
+            Some(id) => {
+                let req = UpdateResourceRequest {
+                    title: Some(row.title.clone()),
+                    description: row.description.clone(),
+                    price: Some(row.price),
+                    category_id: plan.category_id,
+                    file_url,
+                    thumbnail_url,
+                    is_active: None,
+                    detail_description: row.detail_description.clone(),
+                    specifications: None,
+                    usage_guide: row.usage_guide.clone(),
+                    precautions: row.precautions.clone(),
+                    display_images,
+                    supported_os: row.supported_os.clone(),
+                };
+                self.resource_service.admin_update(id, req).await?.id
+            }
+            None => {
+                let req = CreateResourceRequest {
+                    title: row.title.clone(),
+                    description: row.description.clone(),
+                    price: row.price,
+                    category_id: plan.category_id,
+                    file_url,
+                    thumbnail_url,
+                    detail_description: row.detail_description.clone(),
+                    specifications: None,
+                    usage_guide: row.usage_guide.clone(),
+                    precautions: row.precautions.clone(),
+                    display_images,
+                    supported_os: row.supported_os.clone(),
+                    draft: false,
+                };
+                let resource = self
+                    .resource_service
+                    .create(req, OWNER_TYPE_PLATFORM, admin_id)
+                    .await?;
+                self.resource_repo
+                    .set_external_id(resource.id, &row.external_id)
+                    .await?;
+                resource.id
+            }
+        };
+
+        if let Some(ref tags) = row.tags {
+            self.tag_service.set_resource_tags(id, None, tags).await?;
+        }
+
+        info!(
+            "Imported resource {} from row {} (external_id {})",
+            id, plan.line, row.external_id
+        );
+        Ok(id)
+    }
+
+    /// 导出资源（`format` 为 csv / jsonl），可按分类筛选
+    pub async fn export(
+        &self,
+        format: &str,
+        category_id: Option<i64>,
+    ) -> Result<String, RswsError> {
+        if format != FORMAT_CSV && format != FORMAT_JSONL {
+            return Err(RswsError::bad_request(format!(
+                "Unsupported export format: {}",
+                format
+            )));
+        }
+
+        let rows = self.resource_repo.export_rows(category_id).await?;
+        info!("Exporting {} resources as {}", rows.len(), format);
+
+        if format == FORMAT_CSV {
+            return Ok(resources_to_csv(&rows));
+        }
+        let mut jsonl = String::new();
+        for row in &rows {
+            let line = serde_json::to_string(row)
+                .map_err(|e| RswsError::internal(format!("Failed to serialize resource: {}", e)))?;
+            jsonl.push_str(&line);
+            jsonl.push('\n');
+        }
+        Ok(jsonl)
+    }
+}
+
+/// 导出资源为 CSV（列表字段用 `|` 连接，可直接再导入）
+pub fn resources_to_csv(rows: &[ResourceExportRow]) -> String {
+    let mut csv = String::from(
+        "id,external_id,title,description,price,category_id,category,file,thumbnail,detail_description,usage_guide,precautions,display_images,supported_os,tags,is_active,review_status,owner_type,provider_id,download_count,sales_count,revenue,rating_avg,rating_count,created_at,updated_at\r\n",
+    );
+
+    let separator = LIST_SEPARATOR.to_string();
+    for row in rows {
+        let fields = [
+            row.id.to_string(),
+            row.external_id.clone().unwrap_or_default(),
+            row.title.clone(),
+            row.description.clone().unwrap_or_default(),
+            row.price.to_string(),
+            row.category_id.map(|id| id.to_string()).unwrap_or_default(),
+            row.category.clone().unwrap_or_default(),
+            row.file.clone().unwrap_or_default(),
+            row.thumbnail.clone().unwrap_or_default(),
+            row.detail_description.clone().unwrap_or_default(),
+            row.usage_guide.clone().unwrap_or_default(),
+            row.precautions.clone().unwrap_or_default(),
+            row.display_images.join(&separator),
+            row.supported_os.join(&separator),
+            row.tags.join(&separator),
+            row.is_active.to_string(),
+            row.review_status.clone(),
+            row.owner_type.clone(),
+            row.provider_id.map(|id| id.to_string()).unwrap_or_default(),
+            row.download_count.to_string(),
+            row.sales_count.to_string(),
+            row.revenue.to_string(),
+            row.rating_avg.to_string(),
+            row.rating_count.to_string(),
+            row.created_at.to_rfc3339(),
+            row.updated_at.to_rfc3339(),
+        ];
+        let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&line.join(","));
+        csv.push_str("\r\n");
+    }
+
+    csv
+}
+
+/// 解析资源文件引用：URL、存储 key 或导入目录下的相对路径（禁止绝对路径与 `..`）
+fn parse_asset(value: &str) -> Result<AssetRef, String> {
+    if value.starts_with("http://") || value.starts_with("https://") || is_private_url(value) {
+        return Ok(AssetRef::Url(value.to_string()));
+    }
+
+    let path = Path::new(value);
+    if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(format!("invalid file path: {}", value));
+    }
+    if value.starts_with("resources/") {
+        Ok(AssetRef::Key(value.to_string()))
+    } else {
+        Ok(AssetRef::Local(path.to_path_buf()))
+    }
+}
+
+/// 按 ID 或名称（不区分大小写）匹配分类
+fn resolve_category(
+    row: &ResourceImportRow,
+    categories: &[Category],
+) -> Result<Option<i64>, String> {
+    if let Some(id) = row.category_id {
+        return if categories.iter().any(|c| c.id == id) {
+            Ok(Some(id))
+        } else {
+            Err(format!("category not found: {}", id))
+        };
+    }
+    let Some(ref name) = row.category else {
+        return Ok(None);
+    };
+
+    let name = name.to_lowercase();
+    let matches: Vec<i64> = categories
+        .iter()
+        .filter(|c| c.name.trim().to_lowercase() == name)
+        .map(|c| c.id)
+        .collect();
+    match matches.as_slice() {
+        [id] => Ok(Some(*id)),
+        [] => Err(format!(
+            "category not found: {}",
+            row.category.as_deref().unwrap_or("")
+        )),
+        _ => Err(format!(
+            "category name is ambiguous, use category_id: {}",
+            row.category.as_deref().unwrap_or("")
+        )),
+    }
+}
+
+/// 导入文件的存储 key（与上传接口的 key 规则一致）
+fn import_file_key(ext: &str) -> String {
+    let random: String = rand::rng()
+        .sample_iter(rand::distr::Alphanumeric)
+        .take(8)
+        .map(char::from)
+        .collect();
+    let now = chrono::Utc::now();
+    let key = format!(
+        "resources/{}/{}_{}",
+        now.format("%Y%m%d"),
+        now.timestamp(),
+        random
+    );
+    if ext.is_empty() {
+        key
+    } else {
+        format!("{}.{}", key, ext)
+    }
+}
+
+/// 常见资源文件扩展名对应的 Content-Type
+fn content_type_for(ext: &str) -> Option<&'static str> {
+    match ext.to_ascii_lowercase().as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        "svg" => Some("image/svg+xml"),
+        "pdf" => Some("application/pdf"),
+        "zip" => Some("application/zip"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn category(id: i64, name: &str) -> Category {
+        Category {
+            id,
+            name: name.to_string(),
+            description: None,
+            parent_id: None,
+            path: None,
+            sort_order: 0,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_asset() {
+        assert_eq!(
+            parse_asset("https://cdn.example.com/a.png"),
+            Ok(AssetRef::Url("https://cdn.example.com/a.png".to_string()))
+        );
+        assert_eq!(
+            parse_asset("private://resources/20260101/1_a.zip"),
+            Ok(AssetRef::Url(
+                "private://resources/20260101/1_a.zip".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_asset("resources/20260101/1_a.png"),
+            Ok(AssetRef::Key("resources/20260101/1_a.png".to_string()))
+        );
+        assert_eq!(
+            parse_asset("packs/icons.zip"),
+            Ok(AssetRef::Local(PathBuf::from("packs/icons.zip")))
+        );
+        assert!(parse_asset("../secret.txt").is_err());
+        assert!(parse_asset("/etc/passwd").is_err());
+        assert!(parse_asset("resources/../../etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_resolve_category() {
+        let categories = vec![
+            category(1, "Icons"),
+            category(2, "Fonts"),
+            category(3, "fonts"),
+        ];
+        let mut row = ResourceImportRow {
+            category: Some("icons".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(resolve_category(&row, &categories), Ok(Some(1)));
+
+        row.category = Some("Fonts".to_string());
+        assert!(resolve_category(&row, &categories).is_err());
+        row.category_id = Some(2);
+        assert_eq!(resolve_category(&row, &categories), Ok(Some(2)));
+        row.category_id = Some(9);
+        assert!(resolve_category(&row, &categories).is_err());
+
+        assert_eq!(
+            resolve_category(&ResourceImportRow::default(), &categories),
+            Ok(None)
+        );
+    }
+}
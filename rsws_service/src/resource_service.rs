@@ -1,13 +1,30 @@
 //! 资源服务
 
+use crate::cross_platform_service::CrossPlatformService;
+use crate::license_service::{owned_license_id, LicenseService};
 use crate::order_service::OrderService;
-use crate::oss_service::StorageService;
+use crate::oss_service::{
+    claim_private_upload, copy_to_private, delete_public_copy, is_private_url, key_from_url,
+    move_to_private, PrivateMigrationFailure, PrivateMigrationReport, StorageService,
+};
+use crate::tag_service::TagService;
+use crate::wishlist_service::WishlistService;
 use rsws_common::error::RswsError;
 use rsws_common::error_code::ErrorCode;
 use rsws_db::ResourceRepository;
+use rsws_model::event_webhook::EVENT_RESOURCE_PUBLISHED;
+use rsws_model::moderation::{
+    REVIEW_STATUS_APPROVED, REVIEW_STATUS_DRAFT, REVIEW_STATUS_PENDING, REVIEW_STATUS_REJECTED,
+};
+use rsws_model::preview::{
+    preview_images, preview_text, PreviewPolicy, SetPreviewPolicyRequest, DEFAULT_PREVIEW_PERCENT,
+    MAX_FREE_SECTIONS, PREVIEW_MODE_MARKER, PREVIEW_MODE_PERCENT, PREVIEW_MODE_SECTIONS,
+};
 use rsws_model::resource::{
-    CreateResourceRequest, Resource, ResourceDetailResponse, UpdateResourceRequest, OWNER_TYPE_USER,
+    CreateResourceRequest, Resource, ResourceDetailResponse, ResourceFacets, ResourceFilter,
+    ResourceListItem, UpdateResourceRequest, OWNER_TYPE_USER,
 };
+use rsws_model::search::{highlight_snippet, search_terms, SNIPPET_CHARS};
 use rust_decimal::Decimal;
 use std::sync::Arc;
 use tracing::{info, warn};
@@ -16,6 +33,10 @@ use tracing::{info, warn};
 pub struct ResourceService {
     resource_repo: Arc<ResourceRepository>,
     order_service: Option<Arc<OrderService>>,
+    license_service: Option<Arc<LicenseService>>,
+    event_publisher: Option<Arc<CrossPlatformService>>,
+    wishlist_service: Option<Arc<WishlistService>>,
+    tag_service: Option<Arc<TagService>>,
     config_service: Option<crate::config_service::ConfigService>,
 }
 
@@ -25,6 +46,10 @@ impl ResourceService {
         Self {
             resource_repo,
             order_service: None,
+            license_service: None,
+            event_publisher: None,
+            wishlist_service: None,
+            tag_service: None,
             config_service: None,
         }
     }
@@ -37,6 +62,10 @@ impl ResourceService {
         Self {
             resource_repo,
             order_service: None,
+            license_service: None,
+            event_publisher: None,
+            wishlist_service: None,
+            tag_service: None,
             config_service: Some(config_service),
         }
     }
@@ -46,11 +75,48 @@ impl ResourceService {
         self.order_service = Some(order_service);
     }
 
-    /// 获取 OSS 存储服务（如果配置了）
-    async fn get_storage_service(&self) -> Option<StorageService> {
+    /// 设置授权档位服务（用于详情展示档位及已持有档位）
+    pub fn set_license_service(&mut self, license_service: Arc<LicenseService>) {
+        self.license_service = Some(license_service);
+    }
+
+    /// 设置事件发布服务（资源上架时推送 `resource.published`）
+    pub fn set_event_publisher(&mut self, event_publisher: Arc<CrossPlatformService>) {
+        self.event_publisher = Some(event_publisher);
+    }
+
+    /// 设置收藏与心愿单服务（用于列表和详情标记是否已保存）
+    pub fn set_wishlist_service(&mut self, wishlist_service: Arc<WishlistService>) {
+        self.wishlist_service = Some(wishlist_service);
+    }
+
+    /// 设置标签服务（用于详情展示资源标签）
+    pub fn set_tag_service(&mut self, tag_service: Arc<TagService>) {
+        self.tag_service = Some(tag_service);
+    }
+
+    /// 资源上架事件（入队失败只记录日志，不影响资源操作）
+    ///
+    /// 资源从不公开变为公开（上架且审核通过）时推送。
+    async fn publish_if_published(&self, before: Option<&Resource>, after: &Resource) {
+        let was_public = before.is_some_and(|r| r.is_public());
+        if was_public || !after.is_public() {
+            return;
+        }
+        let Some(ref publisher) = self.event_publisher else {
+            return;
+        };
+        let data = resource_event_data(after);
+        if let Err(e) = publisher.publish(EVENT_RESOURCE_PUBLISHED, data).await {
+            warn!("Failed to publish resource {} event: {}", after.id, e);
+        }
+    }
+
+    /// 获取文件所在区域的 OSS 存储服务（如果配置了）
+    async fn get_storage_service(&self, url: &str) -> Option<StorageService> {
         if let Some(ref config_service) = self.config_service {
             match config_service.get_storage_config().await {
-                Ok(config) => match StorageService::new(&config).await {
+                Ok(config) => match StorageService::for_url(&config, url).await {
                     Ok(service) => return Some(service),
                     Err(e) => {
                         warn!("Failed to create storage service: {}", e);
@@ -66,19 +132,91 @@ impl ResourceService {
         None
     }
 
-    /// 从 URL 提取文件 key
-    fn extract_key_from_url(&self, url: &str) -> Option<String> {
-        // 处理不同存储后端的 URL 格式
-        // 本地：http://host:port/uploads/resources/20240601/12345678.zip
-        // S3：https://bucket.s3.region.amazonaws.com/resources/20240601/12345678.zip
-        // 自定义域名：https://cdn.example.com/resources/20240601/12345678.zip
+    /// 校验资源文件引用，返回最终 URL（未配置存储时原样返回）
+    ///
+    /// `uploader` 为创作者用户 ID：只接受本人上传到私有区的文件。
+    /// 管理员操作时传 None，公开区上传的文件移入私有区后使用。
+    async fn privatize_file_url(
+        &self,
+        file_url: Option<String>,
+        uploader: Option<i64>,
+    ) -> Result<Option<String>, RswsError> {
+        let (Some(config_service), Some(url)) = (self.config_service.as_ref(), file_url.as_deref())
+        else {
+            return Ok(file_url);
+        };
+        if url.is_empty() {
+            return Ok(file_url);
+        }
+        if let Some(user_id) = uploader {
+            claim_private_upload(config_service.redis_client(), url, user_id).await?;
+            return Ok(file_url);
+        }
+        if is_private_url(url) {
+            return Ok(file_url);
+        }
+        let config = config_service.get_storage_config().await?;
+        Ok(Some(move_to_private(&config, url).await?))
+    }
 
-        // 简单处理：取最后一个 "/" 之后的路径作为 key 的前缀
-        // 实际应该根据配置的 endpoint 和 custom_domain 来解析
-        if let Some(pos) = url.rfind("/resources/") {
-            return Some(url[pos + 1..].to_string());
+    /// 把付费资源（含历史版本）仍在公开区的文件迁移到私有区
+    ///
+    /// 逐个文件复制到私有区、更新数据库引用后再删除公开副本，中断后可重复执行；
+    /// `dry_run` 只列出待迁移文件。
+    pub async fn migrate_private_files(
+        &self,
+        dry_run: bool,
+    ) -> Result<PrivateMigrationReport, RswsError> {
+        let config_service = self
+            .config_service
+            .as_ref()
+            .ok_or_else(|| RswsError::internal("Storage is not configured"))?;
+        let config = config_service.get_storage_config().await?;
+
+        let pending: Vec<String> = self
+            .resource_repo
+            .list_paid_file_urls()
+            .await?
+            .into_iter()
+            .filter(|url| !is_private_url(url) && key_from_url(url).is_some())
+            .collect();
+
+        let mut report = PrivateMigrationReport {
+            dry_run,
+            pending,
+            ..Default::default()
+        };
+        if dry_run {
+            return Ok(report);
         }
-        None
+
+        for url in &report.pending {
+            let result = match copy_to_private(&config, url).await {
+                Ok(Some(private)) => self.resource_repo.replace_file_url(url, &private).await,
+                Ok(None) => continue,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(_) => {
+                    delete_public_copy(&config, url).await;
+                    report.moved += 1;
+                }
+                Err(e) => {
+                    warn!("Failed to migrate {} to private storage: {}", url, e);
+                    report.failed.push(PrivateMigrationFailure {
+                        file_url: url.clone(),
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        info!(
+            "Private storage migration: {} moved, {} failed",
+            report.moved,
+            report.failed.len()
+        );
+        Ok(report)
     }
 
     /// 获取资源
@@ -88,19 +226,32 @@ impl ResourceService {
 
     /// 获取资源详情（含购买状态和付费内容截断）
     ///
-    /// - 未登录或未购买且资源有价格时，截断 `detail_description` 至 25% 并隐藏 `file_url`
-    /// - 已购买或免费资源返回完整内容
+    /// - 未登录或未购买且资源有价格时，按资源预览策略截取详情、使用说明、注意事项与展示图，并隐藏 `file_url`
+    /// - 已购买（含有效会员覆盖资源分类）或免费资源返回完整内容
+    /// - 附带上架的授权档位及用户持有的档位
     pub async fn get_detail(
         &self,
         user_id: Option<i64>,
         resource_id: i64,
     ) -> Result<Option<ResourceDetailResponse>, RswsError> {
-        let resource = match self.resource_repo.get_by_id(resource_id).await? {
+        let mut resource = match self.resource_repo.get_by_id(resource_id).await? {
             Some(r) => r,
             None => return Ok(None),
         };
 
-        // 检查是否已购买
+        // 未审核通过的资源仅创作者本人可见
+        if !resource.is_public() && (user_id.is_none() || user_id != resource.provider_id) {
+            return Ok(None);
+        }
+
+        // 浏览计数（热门榜数据源，创作者本人浏览不计）
+        if resource.is_public() && user_id != resource.provider_id {
+            if let Err(e) = self.resource_repo.record_view(resource_id).await {
+                warn!("Failed to record view for resource {}: {}", resource_id, e);
+            }
+        }
+
+        // 检查是否已购买（含会员授权）
         let is_purchased = if let Some(uid) = user_id {
             if let Some(ref order_service) = self.order_service {
                 order_service
@@ -114,25 +265,55 @@ impl ResourceService {
             false
         };
 
-        // 付费资源且未购买：截断 detail_description 并隐藏 file_url
+        // 授权档位及已持有档位（无档位的旧订单按最低档位计）
+        let (licenses, owned_id) = if let Some(ref license_service) = self.license_service {
+            let licenses = license_service.list(resource_id, true).await?;
+            let owned = match user_id {
+                Some(uid) if is_purchased && !licenses.is_empty() => license_service
+                    .owned(uid, resource_id)
+                    .await
+                    .unwrap_or(None),
+                _ => None,
+            };
+            let owned_id = owned.and_then(|o| owned_license_id(&licenses, &o));
+            (licenses, owned_id)
+        } else {
+            (Vec::new(), None)
+        };
+
+        // 是否已收藏或加入心愿单
+        let is_saved = match (user_id, &self.wishlist_service) {
+            (Some(uid), Some(wishlist_service)) => wishlist_service
+                .is_saved(uid, resource_id)
+                .await
+                .unwrap_or(false),
+            _ => false,
+        };
+
+        let tags = match self.tag_service {
+            Some(ref tag_service) => tag_service.tags_for(resource_id).await?,
+            None => Vec::new(),
+        };
+
+        // 付费资源且未购买：按预览策略截取详情、使用说明、注意事项与展示图，并隐藏 file_url
         let is_paid = resource.price > Decimal::ZERO;
-        let (detail_description, file_url) = if is_paid && !is_purchased {
-            let truncated = resource.detail_description.as_ref().map(|content| {
-                let len = content.chars().count();
-                let cutoff = (len as f64 * 0.25).floor() as usize;
-                if cutoff >= len {
-                    content.clone()
-                } else {
-                    let truncated: String = content.chars().take(cutoff).collect();
-                    format!("{}...", truncated)
+        let is_preview = is_paid && !is_purchased;
+        let file_url = if is_preview {
+            let policy = self.get_preview_policy(resource_id).await?;
+            for text in [
+                &mut resource.detail_description,
+                &mut resource.usage_guide,
+                &mut resource.precautions,
+            ] {
+                if let Some(content) = text.as_mut() {
+                    *content = preview_text(content, &policy);
                 }
-            });
-            (truncated, None) // 隐藏 file_url 防止直接下载绕过
+            }
+            resource.display_images =
+                preview_images(resource.display_images.take(), policy.free_images);
+            None // 隐藏 file_url 防止直接下载绕过
         } else {
-            (
-                resource.detail_description.clone(),
-                resource.file_url.clone(),
-            )
+            resource.file_url.clone()
         };
 
         Ok(Some(ResourceDetailResponse {
@@ -144,7 +325,7 @@ impl ResourceService {
             file_url,
             thumbnail_url: resource.thumbnail_url,
             is_active: resource.is_active,
-            detail_description,
+            detail_description: resource.detail_description,
             specifications: resource.specifications,
             usage_guide: resource.usage_guide,
             precautions: resource.precautions,
@@ -154,12 +335,94 @@ impl ResourceService {
             supported_os: resource.supported_os,
             commission_rate: resource.commission_rate,
             download_count: resource.download_count,
+            version: resource.version,
+            update_months: resource.update_months,
+            rating_avg: resource.rating_avg,
+            rating_count: resource.rating_count,
+            review_status: resource.review_status,
+            review_reason: resource.review_reason,
             created_at: resource.created_at,
             updated_at: resource.updated_at,
             is_purchased,
+            licenses,
+            owned_license_id: owned_id,
+            is_saved,
+            tags,
+            is_preview,
         }))
     }
 
+    /// 获取资源预览策略（未配置时返回默认策略）
+    pub async fn get_preview_policy(&self, resource_id: i64) -> Result<PreviewPolicy, RswsError> {
+        Ok(self
+            .resource_repo
+            .get_preview_policy(resource_id)
+            .await?
+            .unwrap_or_else(|| PreviewPolicy::default_for(resource_id)))
+    }
+
+    /// 设置资源预览策略（`owner_id` 为空表示管理员操作，跳过归属校验）
+    pub async fn set_preview_policy(
+        &self,
+        resource_id: i64,
+        owner_id: Option<i64>,
+        req: SetPreviewPolicyRequest,
+    ) -> Result<PreviewPolicy, RswsError> {
+        let resource = self
+            .resource_repo
+            .get_by_id(resource_id)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_NOT_FOUND))?;
+
+        if let Some(user_id) = owner_id {
+            if resource.provider_id != Some(user_id) || resource.owner_type != OWNER_TYPE_USER {
+                return Err(RswsError::business(ErrorCode::AUTH_PERMISSION_DENIED));
+            }
+        }
+
+        let (percent, free_sections) = validate_preview_policy(&req)?;
+        let policy = self
+            .resource_repo
+            .upsert_preview_policy(resource_id, &req, percent, &free_sections)
+            .await?;
+
+        info!(
+            "Preview policy set for resource {}: mode {}",
+            resource_id, policy.mode
+        );
+        Ok(policy)
+    }
+
+    /// 资源列表标记当前用户是否已保存（未登录时均为 false），关键词搜索时附加高亮摘要
+    pub async fn mark_saved(
+        &self,
+        user_id: Option<i64>,
+        search: Option<&str>,
+        resources: Vec<Resource>,
+    ) -> Vec<ResourceListItem> {
+        let saved = match (user_id, &self.wishlist_service) {
+            (Some(uid), Some(wishlist_service)) => {
+                let ids: Vec<i64> = resources.iter().map(|r| r.id).collect();
+                wishlist_service
+                    .saved_ids(uid, &ids)
+                    .await
+                    .unwrap_or_default()
+            }
+            _ => Default::default(),
+        };
+
+        let terms = search.map(search_terms).unwrap_or_default();
+
+        resources
+            .into_iter()
+            .map(|resource| ResourceListItem {
+                is_saved: saved.contains(&resource.id),
+                snippet: search_snippet(&resource, &terms),
+                resource,
+            })
+            .collect()
+    }
+
     /// 获取资源列表
     pub async fn list(
         &self,
@@ -175,20 +438,29 @@ impl ResourceService {
     /// 搜索资源列表
     pub async fn search(
         &self,
-        category_id: Option<i64>,
-        search: Option<&str>,
+        filter: &ResourceFilter,
         page: i64,
         page_size: i64,
     ) -> Result<(Vec<Resource>, i64), RswsError> {
         self.resource_repo
-            .get_list_with_search(category_id, search, page, page_size)
+            .get_list_with_search(filter, page, page_size)
             .await
     }
 
-    /// 递增资源下载计数
-    pub async fn increment_download_count(&self, resource_id: i64) -> Result<(), RswsError> {
+    /// 资源列表分面计数
+    pub async fn facets(&self, filter: &ResourceFilter) -> Result<ResourceFacets, RswsError> {
+        self.resource_repo.get_facets(filter).await
+    }
+
+    /// 我的资源（创作者，含各审核状态）
+    pub async fn list_mine(
+        &self,
+        user_id: i64,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<Resource>, i64), RswsError> {
         self.resource_repo
-            .increment_download_count(resource_id)
+            .get_user_resources(user_id, page, page_size)
             .await
     }
 
@@ -199,20 +471,35 @@ impl ResourceService {
         owner_type: &str,
         provider_id: i64,
     ) -> Result<Resource, RswsError> {
+        let mut req = req;
         // 验证价格
         if req.price < rust_decimal::Decimal::ZERO {
             return Err(RswsError::business(ErrorCode::INVALID_PARAMETER));
         }
+        let uploader = (owner_type == OWNER_TYPE_USER).then_some(provider_id);
+        req.file_url = self
+            .privatize_file_url(req.file_url.take(), uploader)
+            .await?;
+
+        // 用户资源需审核（或保存为草稿），平台资源直接通过
+        let review_status = if owner_type != OWNER_TYPE_USER {
+            REVIEW_STATUS_APPROVED
+        } else if req.draft {
+            REVIEW_STATUS_DRAFT
+        } else {
+            REVIEW_STATUS_PENDING
+        };
 
         let resource = self
             .resource_repo
-            .create(&req, owner_type, provider_id)
+            .create(&req, owner_type, provider_id, review_status)
             .await?;
 
         info!(
             "Resource created: {} ({}:{})",
             resource.id, owner_type, provider_id
         );
+        self.publish_if_published(None, &resource).await;
 
         Ok(resource)
     }
@@ -234,10 +521,22 @@ impl ResourceService {
         if existing.provider_id != Some(user_id) || existing.owner_type != OWNER_TYPE_USER {
             return Err(RswsError::business(ErrorCode::AUTH_PERMISSION_DENIED));
         }
+        check_file_replacement(&existing, &req)?;
+        let mut req = req;
+        if req.file_url != existing.file_url {
+            req.file_url = self
+                .privatize_file_url(req.file_url.take(), Some(user_id))
+                .await?;
+        }
 
-        let updated = self.resource_repo.update(resource_id, &req).await?;
+        // 创作者修改后重新进入审核
+        let updated = self
+            .resource_repo
+            .update(resource_id, &req, Some(user_id))
+            .await?;
 
         info!("Resource updated: {} by user {}", resource_id, user_id);
+        self.publish_if_published(Some(&existing), &updated).await;
 
         Ok(updated)
     }
@@ -286,27 +585,31 @@ impl ResourceService {
         Ok(())
     }
 
-    /// 删除资源关联的文件（从 OSS）
+    /// 删除资源关联的文件（从 OSS，按文件所在区域）
     async fn delete_resource_files(&self, resource: &Resource) {
-        if let Some(ref storage_service) = self.get_storage_service().await {
-            // 删除主文件
-            if let Some(ref file_url) = resource.file_url {
-                if let Some(key) = self.extract_key_from_url(file_url) {
-                    match storage_service.delete(&key).await {
-                        Ok(_) => info!("Deleted file from OSS: {}", key),
-                        Err(e) => warn!("Failed to delete file from OSS: {} (key: {})", e, key),
-                    }
+        // 删除主文件
+        if let Some(ref file_url) = resource.file_url {
+            if let (Some(key), Some(storage_service)) = (
+                key_from_url(file_url),
+                self.get_storage_service(file_url).await,
+            ) {
+                match storage_service.delete(&key).await {
+                    Ok(_) => info!("Deleted file from OSS: {}", key),
+                    Err(e) => warn!("Failed to delete file from OSS: {} (key: {})", e, key),
                 }
             }
+        }
 
-            // 删除缩略图
-            if let Some(ref thumbnail_url) = resource.thumbnail_url {
-                if let Some(key) = self.extract_key_from_url(thumbnail_url) {
-                    match storage_service.delete(&key).await {
-                        Ok(_) => info!("Deleted thumbnail from OSS: {}", key),
-                        Err(e) => {
-                            warn!("Failed to delete thumbnail from OSS: {} (key: {})", e, key)
-                        }
+        // 删除缩略图
+        if let Some(ref thumbnail_url) = resource.thumbnail_url {
+            if let (Some(key), Some(storage_service)) = (
+                key_from_url(thumbnail_url),
+                self.get_storage_service(thumbnail_url).await,
+            ) {
+                match storage_service.delete(&key).await {
+                    Ok(_) => info!("Deleted thumbnail from OSS: {}", key),
+                    Err(e) => {
+                        warn!("Failed to delete thumbnail from OSS: {} (key: {})", e, key)
                     }
                 }
             }
@@ -320,16 +623,132 @@ impl ResourceService {
         req: UpdateResourceRequest,
     ) -> Result<Resource, RswsError> {
         // 仅检查资源是否存在
-        let _existing = self
+        let existing = self
             .resource_repo
             .get_by_id(resource_id)
             .await?
             .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_NOT_FOUND))?;
+        check_file_replacement(&existing, &req)?;
+        let mut req = req;
+        if req.file_url != existing.file_url {
+            req.file_url = self.privatize_file_url(req.file_url.take(), None).await?;
+        }
 
-        let updated = self.resource_repo.update(resource_id, &req).await?;
+        let updated = self.resource_repo.update(resource_id, &req, None).await?;
 
         info!("Resource updated by admin: {}", resource_id);
+        self.publish_if_published(Some(&existing), &updated).await;
 
         Ok(updated)
     }
 }
+
+/// 资源事件数据（`resource.published`）
+pub(crate) fn resource_event_data(resource: &Resource) -> serde_json::Value {
+    serde_json::json!({
+        "id": resource.id,
+        "title": resource.title,
+        "description": resource.description,
+        "price": resource.price,
+        "category_id": resource.category_id,
+        "thumbnail_url": resource.thumbnail_url,
+        "owner_type": resource.owner_type,
+        "provider_id": resource.provider_id,
+        "created_at": resource.created_at,
+        "updated_at": resource.updated_at,
+    })
+}
+
+/// 搜索结果摘要：简介优先，其次详情（仅免费资源，避免泄露付费内容），最后标题
+fn search_snippet(resource: &Resource, terms: &[String]) -> Option<String> {
+    if terms.is_empty() {
+        return None;
+    }
+    let detail = resource
+        .detail_description
+        .as_deref()
+        .filter(|_| resource.price <= Decimal::ZERO);
+    [
+        resource.description.as_deref(),
+        detail,
+        Some(resource.title.as_str()),
+    ]
+    .into_iter()
+    .flatten()
+    .find_map(|text| highlight_snippet(text, terms, SNIPPET_CHARS))
+}
+
+/// 校验预览策略，返回 (百分比, 规范化后的免费章节)
+fn validate_preview_policy(req: &SetPreviewPolicyRequest) -> Result<(i16, Vec<String>), RswsError> {
+    if !matches!(
+        req.mode.as_str(),
+        PREVIEW_MODE_PERCENT | PREVIEW_MODE_MARKER | PREVIEW_MODE_SECTIONS
+    ) {
+        return Err(RswsError::bad_request(format!(
+            "Invalid preview mode: {}",
+            req.mode
+        )));
+    }
+
+    let percent = req.percent.unwrap_or(DEFAULT_PREVIEW_PERCENT);
+    if !(0..=100).contains(&percent) {
+        return Err(RswsError::bad_request(
+            "Preview percent must be between 0 and 100",
+        ));
+    }
+    if req.free_images.is_some_and(|n| n < 0) {
+        return Err(RswsError::bad_request("free_images must not be negative"));
+    }
+
+    let mut free_sections: Vec<String> = Vec::new();
+    for name in req.free_sections.iter().flatten() {
+        let name = name.trim();
+        if !name.is_empty() && !free_sections.iter().any(|s| s.eq_ignore_ascii_case(name)) {
+            free_sections.push(name.to_string());
+        }
+    }
+    if free_sections.len() > MAX_FREE_SECTIONS {
+        return Err(RswsError::bad_request(format!(
+            "At most {} free sections allowed",
+            MAX_FREE_SECTIONS
+        )));
+    }
+    if req.mode == PREVIEW_MODE_SECTIONS && free_sections.is_empty() {
+        return Err(RswsError::bad_request(
+            "free_sections is required for sections mode",
+        ));
+    }
+
+    Ok((percent, free_sections))
+}
+
+/// 已有文件的资源只能通过发布新版本替换文件，保证买家仍可下载购买时的版本
+fn check_file_replacement(
+    existing: &Resource,
+    req: &UpdateResourceRequest,
+) -> Result<(), RswsError> {
+    let replaced = match (existing.file_url.as_deref(), req.file_url.as_deref()) {
+        (Some(current), Some(new)) => !current.is_empty() && current != new,
+        _ => false,
+    };
+    if replaced {
+        return Err(RswsError::bad_request(
+            "file_url cannot be replaced directly, publish a new version instead",
+        ));
+    }
+    Ok(())
+}
+
+/// 检查资源是否可购买（已上架且审核通过）
+pub fn ensure_purchasable(resource: &Resource) -> Result<(), RswsError> {
+    if resource.is_public() {
+        return Ok(());
+    }
+    let code = match resource.review_status.as_str() {
+        _ if !resource.is_active => ErrorCode::RESOURCE_NOT_ACTIVE,
+        REVIEW_STATUS_PENDING => ErrorCode::RESOURCE_PENDING_REVIEW,
+        REVIEW_STATUS_REJECTED => ErrorCode::RESOURCE_REJECTED,
+        _ => ErrorCode::RESOURCE_NOT_ACTIVE,
+    };
+    Err(RswsError::business(code))
+}
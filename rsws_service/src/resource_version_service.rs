@@ -0,0 +1,450 @@
+//! 资源版本服务
+//!
+//! - 创作者（或管理员）发布新版本：文件、大小、校验和、更新日志，资源文件同步为最新版本
+//! - 更新政策：`update_months` 为空时包含全部后续更新，否则包含购买后 N 个月内发布的版本
+//! - 买家按购买时间下载有权获取的任一版本；资源创作者与覆盖该分类的会员可下载全部版本
+//! - 新版本发布后由后台任务邮件通知已购买的用户
+
+use chrono::{DateTime, Utc};
+use rsws_common::email::EmailService;
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_db::{ResourceRepository, ResourceVersionRepository};
+use rsws_model::resource::{Resource, OWNER_TYPE_USER};
+use rsws_model::resource_version::{
+    is_valid_checksum, is_version_entitled, updates_until, PublishVersionRequest, ResourceVersion,
+    ResourceVersionInfo, SetUpdatePolicyRequest, VersionOwner, MAX_UPDATE_MONTHS,
+};
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::config_service::{ConfigService, EmailDbConfig};
+use crate::oss_service::{claim_private_upload, move_to_private};
+
+/// 后台任务单批处理版本数量
+const BATCH_SIZE: i64 = 20;
+
+/// 用户对资源版本的访问范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VersionAccess {
+    /// 全部版本（资源创作者、会员）
+    All,
+    /// 按最近一次付款时间与更新政策
+    PurchasedAt(DateTime<Utc>),
+    /// 未购买
+    None,
+}
+
+impl VersionAccess {
+    fn allows(&self, version: &ResourceVersion, update_months: Option<i32>) -> bool {
+        match self {
+            Self::All => true,
+            Self::PurchasedAt(at) => is_version_entitled(version.released_at, *at, update_months),
+            Self::None => false,
+        }
+    }
+}
+
+/// 资源版本服务
+#[derive(Clone)]
+pub struct ResourceVersionService {
+    version_repo: Arc<ResourceVersionRepository>,
+    resource_repo: Arc<ResourceRepository>,
+    email_service: Option<Arc<EmailService>>,
+    config_service: Option<ConfigService>,
+}
+
+impl ResourceVersionService {
+    /// 创建资源版本服务实例
+    pub fn new(
+        version_repo: Arc<ResourceVersionRepository>,
+        resource_repo: Arc<ResourceRepository>,
+        email_config: Option<&EmailDbConfig>,
+    ) -> Self {
+        let email_service = email_config.and_then(EmailDbConfig::smtp_service);
+
+        Self {
+            version_repo,
+            resource_repo,
+            email_service,
+            config_service: None,
+        }
+    }
+
+    /// 设置配置服务（发布版本时校验资源文件归属并移入私有存储区）
+    pub fn set_config_service(&mut self, config_service: ConfigService) {
+        self.config_service = Some(config_service);
+    }
+
+    // ==================== 版本管理 ====================
+
+    /// 获取资源全部版本（含文件地址，供创作者 / 管理员管理）
+    ///
+    /// `owner_id` 为创作者用户 ID，管理员操作时传 None 跳过归属校验。
+    pub async fn list_for_owner(
+        &self,
+        resource_id: i64,
+        owner_id: Option<i64>,
+    ) -> Result<Vec<ResourceVersion>, RswsError> {
+        self.check_resource_owner(resource_id, owner_id).await?;
+        self.version_repo.list(resource_id).await
+    }
+
+    /// 发布新版本
+    ///
+    /// 创作者发布时资源按修改规则重新进入审核；已购买的用户由后台任务邮件通知。
+    pub async fn publish(
+        &self,
+        resource_id: i64,
+        owner_id: Option<i64>,
+        actor_id: i64,
+        req: &PublishVersionRequest,
+    ) -> Result<ResourceVersion, RswsError> {
+        let resource = self.check_resource_owner(resource_id, owner_id).await?;
+        let mut req = normalize_version_request(req)?;
+        if let Some(ref config_service) = self.config_service {
+            // 创作者只能发布本人上传到私有区的文件
+            match owner_id {
+                Some(user_id) => {
+                    claim_private_upload(config_service.redis_client(), &req.file_url, user_id)
+                        .await?
+                }
+                None => {
+                    let config = config_service.get_storage_config().await?;
+                    req.file_url = move_to_private(&config, &req.file_url).await?;
+                }
+            }
+        }
+
+        let version = self
+            .version_repo
+            .publish(&resource, &req, Some(actor_id), owner_id)
+            .await?;
+
+        info!(
+            "Resource version published: {} resource={} version={} by {}",
+            version.id, resource_id, version.version, actor_id
+        );
+        Ok(version)
+    }
+
+    /// 设置更新政策（`update_months` 为空表示包含全部后续更新）
+    pub async fn set_update_policy(
+        &self,
+        resource_id: i64,
+        owner_id: Option<i64>,
+        req: &SetUpdatePolicyRequest,
+    ) -> Result<Resource, RswsError> {
+        self.check_resource_owner(resource_id, owner_id).await?;
+        if req
+            .update_months
+            .is_some_and(|m| !(1..=MAX_UPDATE_MONTHS).contains(&m))
+        {
+            return Err(RswsError::bad_request(format!(
+                "update_months must be between 1 and {}",
+                MAX_UPDATE_MONTHS
+            )));
+        }
+
+        let resource = self
+            .version_repo
+            .set_update_months(resource_id, req.update_months)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_NOT_FOUND))?;
+
+        info!(
+            "Resource {} update policy set to {:?} months",
+            resource_id, req.update_months
+        );
+        Ok(resource)
+    }
+
+    /// 校验资源存在且属于该创作者
+    async fn check_resource_owner(
+        &self,
+        resource_id: i64,
+        owner_id: Option<i64>,
+    ) -> Result<Resource, RswsError> {
+        let resource = self
+            .resource_repo
+            .get_by_id(resource_id)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_NOT_FOUND))?;
+
+        if let Some(uid) = owner_id {
+            if resource.provider_id != Some(uid) || resource.owner_type != OWNER_TYPE_USER {
+                return Err(RswsError::business(ErrorCode::AUTH_PERMISSION_DENIED));
+            }
+        }
+        Ok(resource)
+    }
+
+    // ==================== 买家访问 ====================
+
+    /// 获取资源版本列表（不含文件地址），标记当前用户可下载的版本
+    pub async fn list_for_user(
+        &self,
+        user_id: i64,
+        resource_id: i64,
+    ) -> Result<Vec<ResourceVersionInfo>, RswsError> {
+        let resource = self
+            .resource_repo
+            .get_by_id(resource_id)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_NOT_FOUND))?;
+        let access = self.access(user_id, &resource).await?;
+
+        let versions = self.version_repo.list(resource_id).await?;
+        Ok(versions
+            .into_iter()
+            .map(|v| {
+                let entitled = access.allows(&v, resource.update_months);
+                ResourceVersionInfo::new(v, entitled)
+            })
+            .collect())
+    }
+
+    /// 获取用户可下载的指定版本
+    pub async fn entitled_version(
+        &self,
+        user_id: i64,
+        resource_id: i64,
+        version_id: i64,
+    ) -> Result<ResourceVersion, RswsError> {
+        let resource = self
+            .resource_repo
+            .get_by_id(resource_id)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_NOT_FOUND))?;
+        let version = self
+            .version_repo
+            .find(resource_id, version_id)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_VERSION_NOT_FOUND))?;
+
+        match self.access(user_id, &resource).await? {
+            VersionAccess::None => Err(RswsError::business_with_message(
+                ErrorCode::AUTH_PERMISSION_DENIED,
+                "Please purchase this resource first",
+            )),
+            access if access.allows(&version, resource.update_months) => Ok(version),
+            _ => Err(RswsError::business(
+                ErrorCode::RESOURCE_VERSION_NOT_ENTITLED,
+            )),
+        }
+    }
+
+    /// 用户可下载的最新版本（资源尚无版本记录或未购买时为 None）
+    pub async fn latest_entitled(
+        &self,
+        user_id: i64,
+        resource: &Resource,
+    ) -> Result<Option<ResourceVersion>, RswsError> {
+        let access = self.access(user_id, resource).await?;
+        let versions = self.version_repo.list(resource.id).await?;
+        Ok(versions
+            .into_iter()
+            .find(|v| access.allows(v, resource.update_months)))
+    }
+
+    /// 用户对资源版本的访问范围
+    async fn access(&self, user_id: i64, resource: &Resource) -> Result<VersionAccess, RswsError> {
+        if resource.provider_id == Some(user_id) {
+            return Ok(VersionAccess::All);
+        }
+        if self
+            .version_repo
+            .has_membership_access(user_id, resource.id)
+            .await?
+        {
+            return Ok(VersionAccess::All);
+        }
+        Ok(
+            match self
+                .version_repo
+                .last_purchase(user_id, resource.id)
+                .await?
+            {
+                Some(at) => VersionAccess::PurchasedAt(at),
+                None => VersionAccess::None,
+            },
+        )
+    }
+
+    // ==================== 新版本通知 ====================
+
+    /// 通知已购买的用户新版本发布
+    pub async fn process_pending(&self) -> Result<(), RswsError> {
+        for version in self
+            .version_repo
+            .claim_pending_notifications(BATCH_SIZE)
+            .await?
+        {
+            if let Err(e) = self.notify_owners(&version).await {
+                warn!(
+                    "Failed to notify owners of resource version {}: {}",
+                    version.id, e
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// 启动后台新版本通知任务
+    pub fn start_background(self: Arc<Self>, interval_secs: u64) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.process_pending().await {
+                    error!("Resource version background task failed: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn notify_owners(&self, version: &ResourceVersion) -> Result<(), RswsError> {
+        let Some(resource) = self.resource_repo.get_by_id(version.resource_id).await? else {
+            return Ok(());
+        };
+
+        let owners = self.version_repo.list_owners(resource.id).await?;
+        let mut sent = 0;
+        for owner in owners.iter().filter(|o| !o.email.is_empty()) {
+            let (subject, body) = version_email(&resource, version, owner);
+
+            let Some(ref svc) = self.email_service else {
+                // Dev 模式：只打印日志
+                warn!(
+                    "VERSION EMAIL [DEV MODE] To: {} | Subject: {}",
+                    owner.email, subject
+                );
+                continue;
+            };
+
+            match svc.send(&owner.email, &subject, &body) {
+                Ok(_) => sent += 1,
+                Err(e) => warn!(
+                    "Failed to send version notification to user {}: {}",
+                    owner.user_id, e
+                ),
+            }
+        }
+
+        info!(
+            "Resource version {} notification sent to {}/{} owner(s)",
+            version.id,
+            sent,
+            owners.len()
+        );
+        Ok(())
+    }
+}
+
+/// 校验并规范化发布请求（校验和统一为小写）
+fn normalize_version_request(
+    req: &PublishVersionRequest,
+) -> Result<PublishVersionRequest, RswsError> {
+    let version = req.version.trim();
+    if version.is_empty() || version.chars().count() > 50 {
+        return Err(RswsError::bad_request("version must be 1-50 characters"));
+    }
+    let file_url = req.file_url.trim();
+    if file_url.is_empty() || file_url.len() > 500 {
+        return Err(RswsError::bad_request("file_url must be 1-500 characters"));
+    }
+    if req.file_size.is_some_and(|s| s < 0) {
+        return Err(RswsError::bad_request("file_size must not be negative"));
+    }
+    let checksum = req
+        .checksum
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(str::to_lowercase);
+    if checksum.as_deref().is_some_and(|c| !is_valid_checksum(c)) {
+        return Err(RswsError::bad_request(
+            "checksum must be a SHA-256 hex digest",
+        ));
+    }
+
+    Ok(PublishVersionRequest {
+        version: version.to_string(),
+        file_url: file_url.to_string(),
+        file_size: req.file_size,
+        checksum,
+        changelog: req
+            .changelog
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(str::to_string),
+    })
+}
+
+/// 新版本通知邮件主题与正文
+fn version_email(
+    resource: &Resource,
+    version: &ResourceVersion,
+    owner: &VersionOwner,
+) -> (String, String) {
+    let subject = format!("{} {} is available", resource.title, version.version);
+    let changelog = version
+        .changelog
+        .as_deref()
+        .map(|c| format!("\n\nWhat's new:\n{}", c))
+        .unwrap_or_default();
+    let access = if is_version_entitled(
+        version.released_at,
+        owner.purchased_at,
+        resource.update_months,
+    ) {
+        "This update is included in your purchase and is ready to download.".to_string()
+    } else {
+        let until = updates_until(owner.purchased_at, resource.update_months)
+            .map(|t| t.format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        format!(
+            "Your update period ended on {}. Versions released up to that date remain available to download.",
+            until
+        )
+    };
+    let body = format!(
+        "Hi {},\n\nVersion {} of \"{}\" was released on {} (UTC).{}\n\n{}",
+        owner.username,
+        version.version,
+        resource.title,
+        version.released_at.format("%Y-%m-%d %H:%M"),
+        changelog,
+        access
+    );
+    (subject, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(version: &str, checksum: Option<&str>) -> PublishVersionRequest {
+        PublishVersionRequest {
+            version: version.to_string(),
+            file_url: " https://cdn.example.com/resources/v2.zip ".to_string(),
+            file_size: Some(1024),
+            checksum: checksum.map(str::to_string),
+            changelog: Some("  ".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_normalize_version_request() {
+        let upper = "AB".repeat(32);
+        let req = normalize_version_request(&request(" 2.0 ", Some(&upper))).unwrap();
+        assert_eq!(req.version, "2.0");
+        assert_eq!(req.file_url, "https://cdn.example.com/resources/v2.zip");
+        assert_eq!(req.checksum, Some("ab".repeat(32)));
+        assert_eq!(req.changelog, None);
+
+        assert!(normalize_version_request(&request("", None)).is_err());
+        assert!(normalize_version_request(&request("2.0", Some("md5"))).is_err());
+    }
+}
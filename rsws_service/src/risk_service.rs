@@ -0,0 +1,242 @@
+//! 订单风控服务
+//!
+//! 下单时和发起支付时各评估一次（PayPal 付款回调时按付款人国家再评估，
+//! USDT 链上到账时再评估一次）：
+//! - 采集用户 / IP / 设备维度的下单频率、账户年龄、支付失败次数等信号
+//! - 按后台配置的规则打分，动作取命中规则中最严重者
+//! - review：订单进入审核队列，审核通过前不可支付
+//! - block：订单直接取消；款项已到账（PayPal 扣款完成、USDT 到账）时改为转人工审核，
+//!   拒绝后由财务退款，避免取消订单却留下款项
+//!
+//! 每次评估都记录在 order_risk_assessments，审核结论记录在订单上。
+
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_db::RiskRepository;
+use rsws_model::payment::Order;
+use rsws_model::risk::{
+    CreateRiskRuleRequest, OrderRiskAssessment, RiskContext, RiskOutcome, RiskReviewItem,
+    RiskReviewRequest, RiskRule, UpdateRiskRuleRequest, RISK_ACTIONS, RISK_ACTION_ALLOW,
+    RISK_OPERATORS, RISK_SIGNALS, RISK_STAGE_ANY, RISK_STAGE_CREATION, RISK_STAGE_PAYMENT,
+    RISK_STATUS_REJECTED, RISK_STATUS_REVIEW,
+};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// 订单风控服务
+pub struct RiskService {
+    repo: Arc<RiskRepository>,
+}
+
+impl RiskService {
+    /// 创建订单风控服务实例
+    pub fn new(repo: Arc<RiskRepository>) -> Self {
+        Self { repo }
+    }
+
+    // ==================== 规则管理 ====================
+
+    /// 获取全部规则
+    pub async fn list_rules(&self) -> Result<Vec<RiskRule>, RswsError> {
+        self.repo.list_rules().await
+    }
+
+    /// 创建规则
+    pub async fn create_rule(&self, req: &CreateRiskRuleRequest) -> Result<RiskRule, RswsError> {
+        if req.name.trim().is_empty() {
+            return Err(RswsError::business_with_message(
+                ErrorCode::RISK_RULE_INVALID,
+                "Rule name is required",
+            ));
+        }
+        let stage = req.stage.as_deref().unwrap_or(RISK_STAGE_ANY);
+        validate_rule(
+            Some(&req.signal),
+            Some(&req.operator),
+            Some(&req.action),
+            Some(stage),
+        )?;
+
+        let rule = self.repo.create_rule(req, stage).await?;
+        info!("Risk rule created: {} ({})", rule.id, rule.name);
+        Ok(rule)
+    }
+
+    /// 更新规则
+    pub async fn update_rule(
+        &self,
+        id: i64,
+        req: &UpdateRiskRuleRequest,
+    ) -> Result<RiskRule, RswsError> {
+        validate_rule(
+            req.signal.as_deref(),
+            req.operator.as_deref(),
+            req.action.as_deref(),
+            req.stage.as_deref(),
+        )?;
+        self.repo
+            .update_rule(id, req)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::RISK_RULE_NOT_FOUND))
+    }
+
+    /// 删除规则
+    pub async fn delete_rule(&self, id: i64) -> Result<(), RswsError> {
+        if !self.repo.delete_rule(id).await? {
+            return Err(RswsError::business(ErrorCode::RISK_RULE_NOT_FOUND));
+        }
+        info!("Risk rule deleted: {}", id);
+        Ok(())
+    }
+
+    // ==================== 评估 ====================
+
+    /// 订单是否允许支付（待审核 / 已拦截的订单不可支付）
+    pub fn ensure_payable(&self, order: &Order) -> Result<(), RswsError> {
+        match order.risk_status.as_str() {
+            RISK_STATUS_REVIEW => Err(RswsError::business(ErrorCode::ORDER_RISK_REVIEW)),
+            RISK_STATUS_REJECTED => Err(RswsError::business(ErrorCode::ORDER_RISK_BLOCKED)),
+            _ => Ok(()),
+        }
+    }
+
+    /// 评估订单并记录结果
+    pub async fn assess(
+        &self,
+        order: &Order,
+        stage: &str,
+        ctx: &RiskContext,
+    ) -> Result<RiskOutcome, RswsError> {
+        self.assess_inner(order, stage, ctx, false).await
+    }
+
+    /// 评估已扣款的付款并记录结果
+    ///
+    /// 拦截动作降级为人工审核：订单保持待支付，放行后入账，拒绝后退款。
+    pub async fn assess_captured_payment(
+        &self,
+        order: &Order,
+        ctx: &RiskContext,
+    ) -> Result<RiskOutcome, RswsError> {
+        self.assess_inner(order, RISK_STAGE_PAYMENT, ctx, true)
+            .await
+    }
+
+    async fn assess_inner(
+        &self,
+        order: &Order,
+        stage: &str,
+        ctx: &RiskContext,
+        captured: bool,
+    ) -> Result<RiskOutcome, RswsError> {
+        let outcome = self.repo.assess(order, stage, ctx, captured).await?;
+        let decision = &outcome.decision;
+
+        if decision.action != RISK_ACTION_ALLOW {
+            warn!(
+                "Order {} risk {} at {}: score {}, rules {:?}",
+                order.id,
+                decision.action,
+                stage,
+                decision.score,
+                decision
+                    .triggered
+                    .iter()
+                    .map(|t| t.name.as_str())
+                    .collect::<Vec<_>>()
+            );
+        }
+        Ok(outcome)
+    }
+
+    /// 获取订单的评估记录
+    pub async fn list_assessments(
+        &self,
+        order_id: i64,
+    ) -> Result<Vec<OrderRiskAssessment>, RswsError> {
+        self.repo.list_assessments(order_id).await
+    }
+
+    // ==================== 人工审核 ====================
+
+    /// 分页获取待审核订单
+    pub async fn review_queue(
+        &self,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<RiskReviewItem>, i64), RswsError> {
+        self.repo.list_review_queue(page, page_size).await
+    }
+
+    /// 审核订单：通过后可继续支付，拒绝则取消订单
+    pub async fn review(
+        &self,
+        order_id: i64,
+        admin_id: i64,
+        req: &RiskReviewRequest,
+    ) -> Result<Order, RswsError> {
+        let order = self
+            .repo
+            .review(order_id, admin_id, req.approve, req.note.as_deref())
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::RISK_REVIEW_NOT_PENDING))?;
+        info!(
+            "Order {} risk review by admin {}: {}",
+            order_id,
+            admin_id,
+            if req.approve { "approved" } else { "rejected" }
+        );
+        Ok(order)
+    }
+}
+
+/// 校验规则字段取值
+fn validate_rule(
+    signal: Option<&str>,
+    operator: Option<&str>,
+    action: Option<&str>,
+    stage: Option<&str>,
+) -> Result<(), RswsError> {
+    let invalid = |field: &str, value: &str| -> Result<(), RswsError> {
+        Err(RswsError::business_with_message(
+            ErrorCode::RISK_RULE_INVALID,
+            format!("Unknown {}: {}", field, value),
+        ))
+    };
+    if let Some(s) = signal.filter(|s| !RISK_SIGNALS.contains(s)) {
+        return invalid("signal", s);
+    }
+    if let Some(o) = operator.filter(|o| !RISK_OPERATORS.contains(o)) {
+        return invalid("operator", o);
+    }
+    if let Some(a) = action.filter(|a| !RISK_ACTIONS.contains(a)) {
+        return invalid("action", a);
+    }
+    if let Some(s) =
+        stage.filter(|s| ![RISK_STAGE_CREATION, RISK_STAGE_PAYMENT, RISK_STAGE_ANY].contains(s))
+    {
+        return invalid("stage", s);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_rule() {
+        assert!(validate_rule(
+            Some("user_orders_1h"),
+            Some("gte"),
+            Some("review"),
+            Some("creation")
+        )
+        .is_ok());
+        assert!(validate_rule(None, None, None, None).is_ok());
+        assert!(validate_rule(Some("card_bin"), None, None, None).is_err());
+        assert!(validate_rule(None, Some("ne"), None, None).is_err());
+        assert!(validate_rule(None, None, Some("hold"), None).is_err());
+        assert!(validate_rule(None, None, None, Some("refund")).is_err());
+    }
+}
@@ -0,0 +1,174 @@
+//! 资源标签服务
+//!
+//! - 管理员维护标签（创建、重命名、删除）
+//! - 创作者为自己的资源设置标签，不存在的标签自动创建；管理员可设置任意资源的标签
+//! - 游客浏览热门标签，资源列表按标签 slug 筛选
+
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_db::{ResourceRepository, TagRepository};
+use rsws_model::resource::OWNER_TYPE_USER;
+use rsws_model::tag::{slugify, ResourceTag, Tag, MAX_TAGS_PER_RESOURCE, MAX_TAG_NAME_LEN};
+use std::sync::Arc;
+use tracing::info;
+
+/// 资源标签服务
+pub struct TagService {
+    tag_repo: Arc<TagRepository>,
+    resource_repo: Arc<ResourceRepository>,
+}
+
+impl TagService {
+    /// 创建资源标签服务实例
+    pub fn new(tag_repo: Arc<TagRepository>, resource_repo: Arc<ResourceRepository>) -> Self {
+        Self {
+            tag_repo,
+            resource_repo,
+        }
+    }
+
+    /// 热门标签
+    pub async fn popular(&self, limit: i64) -> Result<Vec<Tag>, RswsError> {
+        self.tag_repo.list_popular(limit).await
+    }
+
+    /// 标签列表（管理员）
+    pub async fn list(
+        &self,
+        search: Option<&str>,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<Tag>, i64), RswsError> {
+        self.tag_repo.list(search, page, page_size).await
+    }
+
+    /// 创建标签（管理员）
+    pub async fn create(&self, admin_id: i64, name: &str) -> Result<Tag, RswsError> {
+        let (name, slug) = validate_tag(name)?;
+        let tag = self.tag_repo.create(&name, &slug, Some(admin_id)).await?;
+        info!(
+            "Tag created: {} ({}) by admin {}",
+            tag.id, tag.slug, admin_id
+        );
+        Ok(tag)
+    }
+
+    /// 重命名标签（管理员）
+    pub async fn rename(&self, id: i64, name: &str) -> Result<Tag, RswsError> {
+        let (name, slug) = validate_tag(name)?;
+        self.tag_repo
+            .update(id, &name, &slug)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::TAG_NOT_FOUND))
+    }
+
+    /// 删除标签（管理员）
+    pub async fn delete(&self, id: i64) -> Result<(), RswsError> {
+        if !self.tag_repo.delete(id).await? {
+            return Err(RswsError::business(ErrorCode::TAG_NOT_FOUND));
+        }
+        info!("Tag deleted: {}", id);
+        Ok(())
+    }
+
+    /// 资源上的标签
+    pub async fn tags_for(&self, resource_id: i64) -> Result<Vec<ResourceTag>, RswsError> {
+        self.tag_repo.tags_for(resource_id).await
+    }
+
+    /// 整体替换资源标签
+    ///
+    /// `owner_id` 为创作者用户 ID，只能设置自己的资源；管理员传 `None`。
+    pub async fn set_resource_tags(
+        &self,
+        resource_id: i64,
+        owner_id: Option<i64>,
+        names: &[String],
+    ) -> Result<Vec<ResourceTag>, RswsError> {
+        let resource = self
+            .resource_repo
+            .get_by_id(resource_id)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_NOT_FOUND))?;
+
+        if let Some(user_id) = owner_id {
+            if resource.provider_id != Some(user_id) || resource.owner_type != OWNER_TYPE_USER {
+                return Err(RswsError::business(ErrorCode::AUTH_PERMISSION_DENIED));
+            }
+        }
+
+        let tags = normalize_tags(names)?;
+        let saved = self
+            .tag_repo
+            .set_resource_tags(resource_id, &tags, owner_id)
+            .await?;
+
+        info!("Resource {} tagged with {} tags", resource_id, saved.len());
+        Ok(saved)
+    }
+}
+
+/// 校验标签名，返回 (名称, slug)
+fn validate_tag(name: &str) -> Result<(String, String), RswsError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_TAG_NAME_LEN {
+        return Err(RswsError::bad_request(format!(
+            "tag name must be 1-{} characters",
+            MAX_TAG_NAME_LEN
+        )));
+    }
+    let slug = slugify(name);
+    if slug.is_empty() {
+        return Err(RswsError::bad_request(format!(
+            "tag name must contain letters or digits: {}",
+            name
+        )));
+    }
+    Ok((name.to_string(), slug))
+}
+
+/// 校验资源标签并按 slug 去重（保留首次出现的写法）
+fn normalize_tags(names: &[String]) -> Result<Vec<(String, String)>, RswsError> {
+    let mut tags: Vec<(String, String)> = Vec::new();
+    for name in names {
+        let (name, slug) = validate_tag(name)?;
+        if !tags.iter().any(|(_, s)| *s == slug) {
+            tags.push((name, slug));
+        }
+    }
+    if tags.len() > MAX_TAGS_PER_RESOURCE {
+        return Err(RswsError::bad_request(format!(
+            "at most {} tags per resource",
+            MAX_TAGS_PER_RESOURCE
+        )));
+    }
+    Ok(tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_tags() {
+        let names = vec![
+            "Low Poly".to_string(),
+            "low-poly".to_string(),
+            " Unity ".to_string(),
+        ];
+        let tags = normalize_tags(&names).unwrap();
+        assert_eq!(
+            tags,
+            vec![
+                ("Low Poly".to_string(), "low-poly".to_string()),
+                ("Unity".to_string(), "unity".to_string()),
+            ]
+        );
+
+        assert!(normalize_tags(&["???".to_string()]).is_err());
+        let too_many: Vec<String> = (0..=MAX_TAGS_PER_RESOURCE)
+            .map(|i| format!("t{}", i))
+            .collect();
+        assert!(normalize_tags(&too_many).is_err());
+    }
+}
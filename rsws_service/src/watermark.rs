@@ -0,0 +1,638 @@
+//! 下载水印
+//!
+//! 按买家生成个性化文件：
+//! - zip：注入 LICENSE（买家、订单、下载时间与签名水印），包内 PDF / 图片同样写入元数据
+//! - PDF：增量更新写入 Info 字段 `/RswsWatermark`，不改动原有内容
+//! - PNG / JPEG：写入 XMP（`rsws:Watermark`）
+//!
+//! 水印格式为 `RSWS-WM1.<user_id>.<order_id>.<timestamp>.<signature>`（无订单时 order_id 为 0），
+//! 签名为 HMAC-SHA256 的前 32 位 hex。水印以明文写入文件，反查时直接扫描文件字节，
+//! 找不到时再解压 zip 内各文件扫描（应对重新打包的情况）。
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use rsws_common::error::RswsError;
+use sha2::Sha256;
+use std::io::{Cursor, Read, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// 水印前缀
+pub const WATERMARK_PREFIX: &str = "RSWS-WM1.";
+
+/// 签名长度（hex 字符）
+const SIGNATURE_LEN: usize = 32;
+
+/// 注入的许可文件名（包内已有同名文件时改用备用名）
+const LICENSE_NAME: &str = "LICENSE.txt";
+const LICENSE_FALLBACK_NAME: &str = "LICENSE-DOWNLOAD.txt";
+
+/// zip 内单个文件参与水印的大小上限，超过则原样复制
+const MAX_ENTRY_BYTES: u64 = 64 * 1024 * 1024;
+
+/// XMP 命名空间
+const XMP_NAMESPACE: &str = "https://rsws.dev/ns/watermark/1.0/";
+
+/// JPEG XMP 段标识
+const JPEG_XMP_HEADER: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+
+/// PNG 文件头
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// 水印内容
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatermarkStamp {
+    pub user_id: i64,
+    pub order_id: Option<i64>,
+    pub issued_at: DateTime<Utc>,
+}
+
+impl WatermarkStamp {
+    /// 生成带签名的水印
+    pub fn payload(&self, secret: &[u8]) -> String {
+        let message = self.message();
+        format!("{}{}.{}", WATERMARK_PREFIX, message, sign(secret, &message))
+    }
+
+    /// 解析水印，返回 (水印内容, 签名是否有效)
+    pub fn parse(payload: &str, secret: &[u8]) -> Option<(Self, bool)> {
+        let parts: Vec<&str> = payload.strip_prefix(WATERMARK_PREFIX)?.split('.').collect();
+        let [user_id, order_id, timestamp, signature] = parts[..] else {
+            return None;
+        };
+        let order_id: i64 = order_id.parse().ok()?;
+        let stamp = Self {
+            user_id: user_id.parse().ok()?,
+            order_id: (order_id > 0).then_some(order_id),
+            issued_at: DateTime::from_timestamp(timestamp.parse().ok()?, 0)?,
+        };
+        let valid = sign(secret, &stamp.message()) == signature;
+        Some((stamp, valid))
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "{}.{}.{}",
+            self.user_id,
+            self.order_id.unwrap_or(0),
+            self.issued_at.timestamp()
+        )
+    }
+}
+
+/// HMAC-SHA256 签名（前 32 位 hex）
+fn sign(secret: &[u8], message: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC can take key of any size");
+    mac.update(message.as_bytes());
+    let hex: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    hex[..SIGNATURE_LEN].to_string()
+}
+
+/// 注入 zip 的 LICENSE 内容
+pub fn license_text(stamp: &WatermarkStamp, file_name: &str, payload: &str) -> String {
+    format!(
+        "RSWS Download License\r\n\
+         \r\n\
+         This copy is licensed to a single buyer and personalized for them.\r\n\
+         Redistribution or resale is prohibited; leaked copies can be traced back to the order.\r\n\
+         \r\n\
+         File: {}\r\n\
+         Buyer ID: {}\r\n\
+         Order ID: {}\r\n\
+         Downloaded at: {}\r\n\
+         Watermark: {}\r\n",
+        file_name,
+        stamp.user_id,
+        stamp
+            .order_id
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| "-".to_string()),
+        stamp.issued_at.to_rfc3339(),
+        payload
+    )
+}
+
+/// 支持水印的文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatermarkFormat {
+    Zip,
+    Pdf,
+    Png,
+    Jpeg,
+}
+
+impl WatermarkFormat {
+    /// 按文件扩展名识别
+    pub fn from_name(name: &str) -> Option<Self> {
+        let (_, ext) = name.rsplit_once('.')?;
+        match ext.to_ascii_lowercase().as_str() {
+            "zip" => Some(Self::Zip),
+            "pdf" => Some(Self::Pdf),
+            "png" => Some(Self::Png),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            _ => None,
+        }
+    }
+
+    /// 文件扩展名
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Zip => "zip",
+            Self::Pdf => "pdf",
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+        }
+    }
+}
+
+/// 写入水印，文件结构不支持（如加密 PDF、损坏的图片）时返回 None
+pub fn apply(
+    format: WatermarkFormat,
+    data: &[u8],
+    payload: &str,
+    license: &str,
+) -> Result<Option<Vec<u8>>, RswsError> {
+    match format {
+        WatermarkFormat::Zip => apply_zip(data, payload, license).map(Some),
+        WatermarkFormat::Pdf => Ok(apply_pdf(data, payload)),
+        WatermarkFormat::Png => Ok(apply_png(data, payload)),
+        WatermarkFormat::Jpeg => Ok(apply_jpeg(data, payload)),
+    }
+}
+
+/// zip：包内 PDF / 图片写入元数据，其余文件原样复制（不重新压缩），追加 LICENSE 与注释
+fn apply_zip(data: &[u8], payload: &str, license: &str) -> Result<Vec<u8>, RswsError> {
+    let zip_err = |e: zip::result::ZipError| {
+        RswsError::internal(format!("Failed to watermark zip archive: {}", e))
+    };
+
+    let mut archive = ZipArchive::new(Cursor::new(data)).map_err(zip_err)?;
+    let mut writer = ZipWriter::new(Cursor::new(Vec::with_capacity(data.len() + 4096)));
+
+    let license_name = if archive
+        .file_names()
+        .any(|name| name.eq_ignore_ascii_case(LICENSE_NAME))
+    {
+        LICENSE_FALLBACK_NAME
+    } else {
+        LICENSE_NAME
+    };
+
+    for i in 0..archive.len() {
+        let (name, options, format) = {
+            let file = archive.by_index_raw(i).map_err(zip_err)?;
+            let format = WatermarkFormat::from_name(file.name())
+                .filter(|f| *f != WatermarkFormat::Zip)
+                .filter(|_| !file.is_dir() && !file.encrypted() && file.size() <= MAX_ENTRY_BYTES);
+            let method = match file.compression() {
+                CompressionMethod::Stored => CompressionMethod::Stored,
+                _ => CompressionMethod::Deflated,
+            };
+            let mut options = SimpleFileOptions::default().compression_method(method);
+            if let Some(modified) = file.last_modified() {
+                options = options.last_modified_time(modified);
+            }
+            if let Some(mode) = file.unix_mode() {
+                options = options.unix_permissions(mode);
+            }
+            (file.name().to_string(), options, format)
+        };
+
+        let marked = match format {
+            Some(format) => read_entry(&mut archive, i)
+                .and_then(|content| apply(format, &content, payload, license).ok().flatten()),
+            None => None,
+        };
+        match marked {
+            Some(content) => {
+                writer.start_file(name, options).map_err(zip_err)?;
+                writer.write_all(&content).map_err(|e| {
+                    RswsError::internal(format!("Failed to write zip entry: {}", e))
+                })?;
+            }
+            None => {
+                let file = archive.by_index_raw(i).map_err(zip_err)?;
+                writer.raw_copy_file(file).map_err(zip_err)?;
+            }
+        }
+    }
+
+    // LICENSE 不压缩，直接扫描文件字节即可找到水印
+    writer
+        .start_file(
+            license_name,
+            SimpleFileOptions::default().compression_method(CompressionMethod::Stored),
+        )
+        .map_err(zip_err)?;
+    writer
+        .write_all(license.as_bytes())
+        .map_err(|e| RswsError::internal(format!("Failed to write license: {}", e)))?;
+
+    let comment = String::from_utf8_lossy(archive.comment())
+        .trim()
+        .to_string();
+    writer.set_comment(if comment.is_empty() {
+        payload.to_string()
+    } else {
+        format!("{}\n{}", comment, payload)
+    });
+
+    Ok(writer.finish().map_err(zip_err)?.into_inner())
+}
+
+/// 读取 zip 内文件（解压失败返回 None）
+fn read_entry(archive: &mut ZipArchive<Cursor<&[u8]>>, index: usize) -> Option<Vec<u8>> {
+    let mut file = archive.by_index(index).ok()?;
+    let mut content = Vec::with_capacity(file.size() as usize);
+    file.read_to_end(&mut content).ok()?;
+    Some(content)
+}
+
+/// PDF：追加增量更新，新 Info 字典保留原有字段并加入 `/RswsWatermark`
+///
+/// 按最后一个 `startxref` 找到原交叉引用：传统 xref 表追加 xref 表，xref 流追加 xref 流。
+/// 加密文档不处理。
+fn apply_pdf(data: &[u8], payload: &str) -> Option<Vec<u8>> {
+    let startxref = rfind(data, b"startxref")?;
+    let prev = parse_uint(&data[startxref + b"startxref".len()..])?;
+    let tail = data.get(prev..)?;
+
+    let classic = tail.starts_with(b"xref");
+    let dict = if classic {
+        let start = find(tail, b"trailer")?;
+        let end = find(&tail[start..], b"startxref").map_or(tail.len(), |i| start + i);
+        &tail[start..end]
+    } else {
+        &tail[..find(tail, b"stream")?]
+    };
+    if find(dict, b"/Encrypt").is_some() {
+        return None;
+    }
+
+    let size = dict_value(dict, b"/Size").and_then(parse_uint)?;
+    let root = dict_value(dict, b"/Root").and_then(parse_ref)?;
+    let info = dict_value(dict, b"/Info")
+        .and_then(parse_ref)
+        .and_then(|r| object_dict(data, &r))
+        .unwrap_or_default();
+
+    let mut out = data.to_vec();
+    if !out.ends_with(b"\n") {
+        out.push(b'\n');
+    }
+    let info_offset = out.len();
+    out.extend_from_slice(
+        format!(
+            "{} 0 obj\n<< /RswsWatermark ({}) {} >>\nendobj\n",
+            size,
+            payload,
+            String::from_utf8_lossy(&info)
+        )
+        .as_bytes(),
+    );
+
+    let xref_offset = out.len();
+    if classic {
+        out.extend_from_slice(
+            format!(
+                "xref\n{} 1\n{:010} 00000 n \ntrailer\n<< /Size {} /Root {} R /Info {} 0 R /Prev {} >>\n",
+                size,
+                info_offset,
+                size + 1,
+                root,
+                size,
+                prev
+            )
+            .as_bytes(),
+        );
+    } else {
+        let mut entries = Vec::with_capacity(14);
+        for offset in [info_offset, xref_offset] {
+            entries.push(1u8);
+            entries.extend_from_slice(&u32::try_from(offset).ok()?.to_be_bytes());
+            entries.extend_from_slice(&[0, 0]);
+        }
+        out.extend_from_slice(
+            format!(
+                "{} 0 obj\n<< /Type /XRef /Size {} /Root {} R /Info {} 0 R /Prev {} /Index [{} 2] /W [1 4 2] /Length {} >>\nstream\n",
+                size + 1,
+                size + 2,
+                root,
+                size,
+                prev,
+                size,
+                entries.len()
+            )
+            .as_bytes(),
+        );
+        out.extend_from_slice(&entries);
+        out.extend_from_slice(b"\nendstream\nendobj\n");
+    }
+    out.extend_from_slice(format!("startxref\n{}\n%%EOF\n", xref_offset).as_bytes());
+    Some(out)
+}
+
+/// PDF 字典中键之后的内容
+fn dict_value<'a>(dict: &'a [u8], key: &[u8]) -> Option<&'a [u8]> {
+    let mut from = 0;
+    while let Some(i) = find(&dict[from..], key) {
+        let end = from + i + key.len();
+        // 排除前缀相同的键（如 /Size 与 /SizeX）
+        if dict.get(end).is_none_or(|c| !c.is_ascii_alphanumeric()) {
+            return Some(&dict[end..]);
+        }
+        from = end;
+    }
+    None
+}
+
+/// 解析间接引用 `N G R`，返回 "N G"
+fn parse_ref(bytes: &[u8]) -> Option<String> {
+    let text = std::str::from_utf8(&bytes[..bytes.len().min(32)]).ok()?;
+    let mut parts = text.split_ascii_whitespace();
+    let num: u64 = parts.next()?.parse().ok()?;
+    let gen: u64 = parts.next()?.parse().ok()?;
+    parts
+        .next()?
+        .starts_with('R')
+        .then(|| format!("{} {}", num, gen))
+}
+
+/// 取出对象 `N G obj << ... >>` 的字典内容（不含外层尖括号）；对象位于对象流中时返回 None
+fn object_dict(data: &[u8], reference: &str) -> Option<Vec<u8>> {
+    let header = format!("{} obj", reference);
+    let mut pos = rfind(data, header.as_bytes())?;
+    // 排除 "12 0 obj" 匹配到 "112 0 obj"
+    while pos > 0 && data[pos - 1].is_ascii_digit() {
+        pos = rfind(&data[..pos], header.as_bytes())?;
+    }
+    let body = &data[pos + header.len()..];
+    let start = find(body, b"<<")?;
+    let mut depth = 0usize;
+    let mut i = start;
+    while i + 1 < body.len() {
+        match &body[i..i + 2] {
+            b"<<" => {
+                depth += 1;
+                i += 2;
+            }
+            b">>" => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(body[start + 2..i].to_vec());
+                }
+                i += 2;
+            }
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// PNG：在 IEND 前插入 iTXt（XML:com.adobe.xmp）
+fn apply_png(data: &[u8], payload: &str) -> Option<Vec<u8>> {
+    if !data.starts_with(PNG_SIGNATURE) {
+        return None;
+    }
+    let mut pos = PNG_SIGNATURE.len();
+    let iend = loop {
+        let len = u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?) as usize;
+        if data.get(pos + 4..pos + 8)? == b"IEND" {
+            break pos;
+        }
+        pos += 12 + len;
+    };
+
+    let mut chunk = b"iTXt".to_vec();
+    chunk.extend_from_slice(b"XML:com.adobe.xmp\0");
+    // 未压缩、无语言标签与翻译关键字
+    chunk.extend_from_slice(&[0, 0, 0, 0]);
+    chunk.extend_from_slice(xmp_packet(payload).as_bytes());
+
+    let mut out = Vec::with_capacity(data.len() + chunk.len() + 8);
+    out.extend_from_slice(&data[..iend]);
+    out.extend_from_slice(&(chunk.len() as u32 - 4).to_be_bytes());
+    out.extend_from_slice(&chunk);
+    out.extend_from_slice(&crc32fast::hash(&chunk).to_be_bytes());
+    out.extend_from_slice(&data[iend..]);
+    Some(out)
+}
+
+/// JPEG：在开头的 APP0 / APP1 段之后插入 XMP APP1 段
+fn apply_jpeg(data: &[u8], payload: &str) -> Option<Vec<u8>> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut pos = 2;
+    while data.get(pos) == Some(&0xFF) && matches!(data.get(pos + 1), Some(0xE0 | 0xE1)) {
+        let len = u16::from_be_bytes(data.get(pos + 2..pos + 4)?.try_into().ok()?) as usize;
+        pos += 2 + len;
+    }
+    if pos > data.len() {
+        return None;
+    }
+
+    let xmp = xmp_packet(payload);
+    let len = u16::try_from(2 + JPEG_XMP_HEADER.len() + xmp.len()).ok()?;
+    let mut out = Vec::with_capacity(data.len() + len as usize + 2);
+    out.extend_from_slice(&data[..pos]);
+    out.extend_from_slice(&[0xFF, 0xE1]);
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(JPEG_XMP_HEADER);
+    out.extend_from_slice(xmp.as_bytes());
+    out.extend_from_slice(&data[pos..]);
+    Some(out)
+}
+
+/// XMP 数据包
+fn xmp_packet(payload: &str) -> String {
+    format!(
+        "<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\
+         <x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\
+         <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\
+         <rdf:Description rdf:about=\"\" xmlns:rsws=\"{}\" rsws:Watermark=\"{}\"/>\
+         </rdf:RDF></x:xmpmeta><?xpacket end=\"r\"?>",
+        XMP_NAMESPACE, payload
+    )
+}
+
+/// 从文件中找出全部水印（去重）：先扫描原始字节，找不到时解压 zip 内各文件扫描
+pub fn find_payloads(data: &[u8]) -> Vec<String> {
+    let mut payloads = Vec::new();
+    scan_payloads(data, &mut payloads);
+    if payloads.is_empty() {
+        if let Ok(mut archive) = ZipArchive::new(Cursor::new(data)) {
+            for i in 0..archive.len() {
+                let small = archive
+                    .by_index_raw(i)
+                    .is_ok_and(|f| f.size() <= MAX_ENTRY_BYTES);
+                if let Some(content) = small.then(|| read_entry(&mut archive, i)).flatten() {
+                    scan_payloads(&content, &mut payloads);
+                }
+            }
+        }
+    }
+    payloads
+}
+
+fn scan_payloads(data: &[u8], payloads: &mut Vec<String>) {
+    let prefix = WATERMARK_PREFIX.as_bytes();
+    let mut from = 0;
+    while let Some(i) = find(&data[from..], prefix) {
+        let start = from + i;
+        let end = data[start + prefix.len()..]
+            .iter()
+            .position(|c| !(c.is_ascii_hexdigit() || *c == b'.'))
+            .map_or(data.len(), |n| start + prefix.len() + n);
+        let payload = String::from_utf8_lossy(&data[start..end])
+            .trim_end_matches('.')
+            .to_string();
+        if payload.matches('.').count() == 4 && !payloads.contains(&payload) {
+            payloads.push(payload);
+        }
+        from = end.max(start + 1);
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).rposition(|w| w == needle)
+}
+
+/// 解析开头（跳过空白）的非负整数
+fn parse_uint(bytes: &[u8]) -> Option<usize> {
+    let digits: String = bytes
+        .iter()
+        .skip_while(|c| c.is_ascii_whitespace())
+        .take_while(|c| c.is_ascii_digit())
+        .map(|c| *c as char)
+        .collect();
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"test-secret";
+
+    fn stamp() -> WatermarkStamp {
+        WatermarkStamp {
+            user_id: 42,
+            order_id: Some(7001),
+            issued_at: DateTime::from_timestamp(1_780_000_000, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_payload_roundtrip() {
+        let payload = stamp().payload(SECRET);
+        assert!(payload.starts_with("RSWS-WM1.42.7001.1780000000."));
+        assert_eq!(
+            WatermarkStamp::parse(&payload, SECRET),
+            Some((stamp(), true))
+        );
+
+        // 篡改买家或换密钥后签名无效
+        let forged = payload.replacen(".42.", ".43.", 1);
+        assert!(!WatermarkStamp::parse(&forged, SECRET).unwrap().1);
+        assert!(!WatermarkStamp::parse(&payload, b"other").unwrap().1);
+        assert_eq!(WatermarkStamp::parse("RSWS-WM1.x.1.2.abc", SECRET), None);
+    }
+
+    #[test]
+    fn test_zip_license_and_lookup() {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        writer.start_file("readme.md", options).unwrap();
+        writer.write_all(b"# Demo").unwrap();
+        writer.start_file("LICENSE.txt", options).unwrap();
+        writer.write_all(b"MIT").unwrap();
+        let original = writer.finish().unwrap().into_inner();
+
+        let payload = stamp().payload(SECRET);
+        let license = license_text(&stamp(), "demo.zip", &payload);
+        let marked = apply(WatermarkFormat::Zip, &original, &payload, &license)
+            .unwrap()
+            .unwrap();
+
+        let mut archive = ZipArchive::new(Cursor::new(marked.as_slice())).unwrap();
+        assert_eq!(archive.len(), 3);
+        let mut injected = String::new();
+        archive
+            .by_name(LICENSE_FALLBACK_NAME)
+            .unwrap()
+            .read_to_string(&mut injected)
+            .unwrap();
+        assert!(injected.contains("Order ID: 7001"));
+        assert_eq!(find_payloads(&marked), vec![payload]);
+    }
+
+    #[test]
+    fn test_pdf_info() {
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        let catalog = pdf.len();
+        pdf.extend_from_slice(b"1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n");
+        let pages = pdf.len();
+        pdf.extend_from_slice(b"2 0 obj\n<< /Type /Pages /Kids [] /Count 0 >>\nendobj\n");
+        let info = pdf.len();
+        pdf.extend_from_slice(b"3 0 obj\n<< /Title (Demo) >>\nendobj\n");
+        let xref = pdf.len();
+        pdf.extend_from_slice(
+            format!(
+                "xref\n0 4\n0000000000 65535 f \n{:010} 00000 n \n{:010} 00000 n \n{:010} 00000 n \ntrailer\n<< /Size 4 /Root 1 0 R /Info 3 0 R >>\nstartxref\n{}\n%%EOF\n",
+                catalog, pages, info, xref
+            )
+            .as_bytes(),
+        );
+
+        let payload = stamp().payload(SECRET);
+        let marked = apply(WatermarkFormat::Pdf, &pdf, &payload, "")
+            .unwrap()
+            .unwrap();
+        let text = String::from_utf8_lossy(&marked);
+        assert!(marked.starts_with(&pdf));
+        assert!(text.contains(&format!(
+            "4 0 obj\n<< /RswsWatermark ({})  /Title (Demo)  >>",
+            payload
+        )));
+        assert!(text.contains(&format!("/Size 5 /Root 1 0 R /Info 4 0 R /Prev {}", xref)));
+        assert_eq!(find_payloads(&marked), vec![payload]);
+    }
+
+    #[test]
+    fn test_image_xmp() {
+        let payload = stamp().payload(SECRET);
+
+        let mut png = PNG_SIGNATURE.to_vec();
+        png.extend_from_slice(&[0, 0, 0, 0]);
+        png.extend_from_slice(b"IEND");
+        png.extend_from_slice(&crc32fast::hash(b"IEND").to_be_bytes());
+        let marked = apply(WatermarkFormat::Png, &png, &payload, "")
+            .unwrap()
+            .unwrap();
+        assert!(marked.ends_with(&png[8..]));
+        assert_eq!(find_payloads(&marked), vec![payload.clone()]);
+
+        let jpeg = [0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, 0xFF, 0xD9];
+        let marked = apply(WatermarkFormat::Jpeg, &jpeg, &payload, "")
+            .unwrap()
+            .unwrap();
+        assert_eq!(&marked[..8], &jpeg[..8]);
+        assert_eq!(&marked[8..10], &[0xFF, 0xE1]);
+        assert_eq!(find_payloads(&marked), vec![payload]);
+
+        assert_eq!(
+            apply(WatermarkFormat::Png, b"not a png", "x", "").unwrap(),
+            None
+        );
+    }
+}
@@ -1,6 +1,14 @@
 //! Webhook 服务
+//!
+//! 入站回调验签通过后、处理前记录到 webhook_logs，按网关事件 ID 去重，处理结果回写日志，
+//! 管理员可重放已存储的回调。
 
 use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_db::WebhookLogRepository;
+use rsws_model::log::webhook_log::{
+    CreateWebhookLogRequest, WebhookLog, WebhookLogQuery, WebhookRecord,
+};
 use serde_json::Value;
 use std::sync::Arc;
 use tracing::info;
@@ -10,12 +18,19 @@ use crate::PayPalService;
 /// Webhook 服务
 pub struct WebhookService {
     paypal_service: Arc<PayPalService>,
+    webhook_log_repo: Arc<WebhookLogRepository>,
 }
 
 impl WebhookService {
     /// 创建 Webhook 服务实例
-    pub fn new(paypal_service: Arc<PayPalService>) -> Self {
-        Self { paypal_service }
+    pub fn new(
+        paypal_service: Arc<PayPalService>,
+        webhook_log_repo: Arc<WebhookLogRepository>,
+    ) -> Self {
+        Self {
+            paypal_service,
+            webhook_log_repo,
+        }
     }
 
     /// 处理 PayPal Webhook
@@ -83,4 +98,72 @@ impl WebhookService {
     ) -> Result<bool, RswsError> {
         self.paypal_service.verify_webhook(headers, body).await
     }
+
+    // ==================== 入站日志 ====================
+
+    /// 验签通过后、处理前记录入站回调
+    ///
+    /// 无事件 ID 的回调不去重；同一事件上次处理失败或处理超时时复用原记录重新处理。
+    pub async fn record(&self, req: &CreateWebhookLogRequest) -> Result<WebhookRecord, RswsError> {
+        let record = self.webhook_log_repo.record(req).await?;
+        match record {
+            WebhookRecord::Duplicate => info!(
+                "Duplicate {} webhook dropped: {}",
+                req.source,
+                req.event_id.as_deref().unwrap_or("")
+            ),
+            WebhookRecord::InProgress => info!(
+                "{} webhook still in progress: {}",
+                req.source,
+                req.event_id.as_deref().unwrap_or("")
+            ),
+            WebhookRecord::Process(_) => {}
+        }
+        Ok(record)
+    }
+
+    /// 回写处理结果
+    pub async fn finish(
+        &self,
+        id: i64,
+        status: &str,
+        response_code: i32,
+        response_message: &str,
+    ) -> Result<(), RswsError> {
+        self.webhook_log_repo
+            .finish(id, status, response_code, response_message)
+            .await
+    }
+
+    /// 开始重放已存储的回调（状态置为 received，重试次数 + 1）
+    pub async fn begin_replay(&self, id: i64) -> Result<WebhookLog, RswsError> {
+        let log = self
+            .webhook_log_repo
+            .begin_replay(id)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::WEBHOOK_LOG_NOT_FOUND))?;
+        info!(
+            "Replaying {} webhook {} (retry {})",
+            log.source, log.id, log.retry_count
+        );
+        Ok(log)
+    }
+
+    /// 获取 Webhook 日志详情
+    pub async fn get_log(&self, id: i64) -> Result<WebhookLog, RswsError> {
+        self.webhook_log_repo
+            .get_by_id(id)
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::WEBHOOK_LOG_NOT_FOUND))
+    }
+
+    /// 分页查询 Webhook 日志
+    pub async fn list_logs(
+        &self,
+        query: &WebhookLogQuery,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<WebhookLog>, i64), RswsError> {
+        self.webhook_log_repo.list(query, page, page_size).await
+    }
 }
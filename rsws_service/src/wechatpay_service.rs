@@ -0,0 +1,434 @@
+//! 微信支付服务
+//!
+//! 配置从 wechatpay_configs 数据库表读取。
+//! - Native 支付（API v3）：下单返回 code_url，前端生成二维码
+//! - 请求使用商户私钥 SHA256withRSA 签名（WECHATPAY2-SHA256-RSA2048）
+//! - 回调使用微信支付公钥验签，resource 使用 APIv3 密钥 AEAD_AES_256_GCM 解密
+
+use crate::config_service::WechatPayDbConfig;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::{engine::general_purpose, Engine as _};
+use reqwest::Client;
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_common::signature::{rsa_sha256_sign, rsa_sha256_verify, SignatureService};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{info, warn};
+
+/// 微信支付 API 域名
+const WECHATPAY_API_BASE: &str = "https://api.mch.weixin.qq.com";
+/// Native 下单接口路径
+const NATIVE_ORDER_PATH: &str = "/v3/pay/transactions/native";
+/// 回调时间戳容差（秒）
+const NOTIFY_TIMESTAMP_TOLERANCE: u64 = 300;
+
+/// 回调通知中的加密资源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WechatPayNotifyResource {
+    pub algorithm: String,
+    pub ciphertext: String,
+    #[serde(default)]
+    pub associated_data: Option<String>,
+    #[serde(default)]
+    pub original_type: Option<String>,
+    pub nonce: String,
+}
+
+/// 回调通知报文
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WechatPayNotify {
+    pub id: String,
+    #[serde(default)]
+    pub create_time: Option<String>,
+    pub event_type: String,
+    #[serde(default)]
+    pub resource_type: Option<String>,
+    pub resource: WechatPayNotifyResource,
+    #[serde(default)]
+    pub summary: Option<String>,
+}
+
+/// 微信支付服务
+pub struct WechatPayService {
+    client: Client,
+    config: Option<WechatPayDbConfig>,
+}
+
+impl WechatPayService {
+    /// 创建微信支付服务实例（可无配置，将使用 mock 模式）
+    pub fn new(config: Option<WechatPayDbConfig>) -> Self {
+        Self {
+            client: Client::builder()
+                .use_rustls_tls()
+                .timeout(std::time::Duration::from_secs(10))
+                .build()
+                .expect("valid reqwest client"),
+            config,
+        }
+    }
+
+    /// 是否已配置
+    pub fn is_configured(&self) -> bool {
+        self.config.as_ref().is_some_and(|c| !c.mch_id.is_empty())
+    }
+
+    /// 按配置费率估算通道手续费（以订单金额计，保留两位小数）
+    pub fn fee_for(&self, amount: Decimal) -> Decimal {
+        self.config
+            .as_ref()
+            .map(|c| (amount * c.fee_rate).round_dp(2))
+            .unwrap_or(Decimal::ZERO)
+    }
+
+    /// 将订单金额换算为微信支付结算金额（CNY，保留两位小数）
+    pub fn to_cny(&self, amount: Decimal) -> Decimal {
+        let rate = self
+            .config
+            .as_ref()
+            .map(|c| c.exchange_rate)
+            .filter(|r| *r > Decimal::ZERO)
+            .unwrap_or(Decimal::ONE);
+        (amount * rate).round_dp(2)
+    }
+
+    /// 将订单金额换算为微信支付结算金额（CNY，单位：分）
+    pub fn to_fen(&self, amount: Decimal) -> i64 {
+        (self.to_cny(amount) * Decimal::from(100))
+            .round()
+            .to_i64()
+            .unwrap_or(0)
+    }
+
+    /// 构造 Authorization 请求头
+    fn authorization(
+        config: &WechatPayDbConfig,
+        method: &str,
+        url_path: &str,
+        body: &str,
+    ) -> Result<String, RswsError> {
+        let timestamp = chrono::Utc::now().timestamp().to_string();
+        let nonce = format!("{:032X}", rand::random::<u128>());
+        let message = build_sign_message(&[method, url_path, &timestamp, &nonce, body]);
+        let signature = rsa_sha256_sign(&config.merchant_private_key, message.as_bytes())?;
+
+        Ok(format!(
+            r#"WECHATPAY2-SHA256-RSA2048 mchid="{}",nonce_str="{}",signature="{}",timestamp="{}",serial_no="{}""#,
+            config.mch_id, nonce, signature, timestamp, config.merchant_serial_no
+        ))
+    }
+
+    /// Native 下单，返回二维码链接 code_url
+    pub async fn native_order(
+        &self,
+        order_id: i64,
+        amount: Decimal,
+        description: &str,
+    ) -> Result<String, RswsError> {
+        let config = match &self.config {
+            Some(c) if !c.mch_id.is_empty() => c,
+            _ => {
+                // Mock 模式
+                info!(
+                    "Creating mock WeChat Pay native order for order_id: {}",
+                    order_id
+                );
+                return Ok(format!("weixin://wxpay/bizpayurl?pr=mock{}", order_id));
+            }
+        };
+
+        let body = serde_json::json!({
+            "appid": config.app_id,
+            "mchid": config.mch_id,
+            "description": description,
+            "out_trade_no": order_id.to_string(),
+            "notify_url": config.notify_url,
+            "amount": {
+                "total": self.to_fen(amount),
+                "currency": "CNY",
+            },
+        })
+        .to_string();
+
+        let authorization = Self::authorization(config, "POST", NATIVE_ORDER_PATH, &body)?;
+
+        let resp = self
+            .client
+            .post(format!("{}{}", WECHATPAY_API_BASE, NATIVE_ORDER_PATH))
+            .header("Authorization", authorization)
+            .header("Accept", "application/json")
+            .header("Content-Type", "application/json")
+            .header("User-Agent", "rsws")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| {
+                RswsError::business_with_message(
+                    ErrorCode::WECHATPAY_REQUEST_FAILED,
+                    format!("WeChat Pay request failed: {}", e),
+                )
+            })?;
+
+        let status = resp.status();
+        let header = |name: &str| -> String {
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("")
+                .to_string()
+        };
+        let (timestamp, nonce, signature, serial) = (
+            header("Wechatpay-Timestamp"),
+            header("Wechatpay-Nonce"),
+            header("Wechatpay-Signature"),
+            header("Wechatpay-Serial"),
+        );
+
+        let text = resp.text().await.map_err(|e| {
+            RswsError::business_with_message(
+                ErrorCode::WECHATPAY_REQUEST_FAILED,
+                format!("Failed to read WeChat Pay response: {}", e),
+            )
+        })?;
+
+        if !status.is_success() {
+            warn!("WeChat Pay native order failed: {} - {}", status, text);
+            return Err(RswsError::business(ErrorCode::WECHATPAY_REQUEST_FAILED));
+        }
+
+        // 应答验签
+        if !self.verify_signature(&timestamp, &nonce, &text, &signature, &serial)? {
+            warn!("WeChat Pay response signature invalid");
+            return Err(RswsError::business(ErrorCode::WECHATPAY_REQUEST_FAILED));
+        }
+
+        let json: Value = serde_json::from_str(&text).map_err(|e| {
+            RswsError::internal(format!("Failed to parse WeChat Pay response: {}", e))
+        })?;
+
+        let code_url = json["code_url"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| RswsError::internal("WeChat Pay response missing code_url"))?;
+
+        info!("WeChat Pay native order created for order {}", order_id);
+        Ok(code_url)
+    }
+
+    /// 验证应答/回调签名
+    ///
+    /// 签名串为 `时间戳\n随机串\n报文主体\n`，使用配置的微信支付公钥验签。
+    /// 配置了 platform_public_key_id 时还会校验 Wechatpay-Serial 是否一致。
+    pub fn verify_signature(
+        &self,
+        timestamp: &str,
+        nonce: &str,
+        body: &str,
+        signature: &str,
+        serial: &str,
+    ) -> Result<bool, RswsError> {
+        let config = match &self.config {
+            Some(c) if !c.mch_id.is_empty() => c,
+            _ => {
+                warn!("WeChat Pay not configured — rejecting signature");
+                return Ok(false);
+            }
+        };
+
+        if timestamp.is_empty() || nonce.is_empty() || signature.is_empty() {
+            return Ok(false);
+        }
+
+        if let Some(ref key_id) = config.platform_public_key_id {
+            if !key_id.is_empty() && key_id != serial {
+                warn!(
+                    "WeChat Pay serial mismatch: expected {}, got {}",
+                    key_id, serial
+                );
+                return Ok(false);
+            }
+        }
+
+        let message = build_sign_message(&[timestamp, nonce, body]);
+        rsa_sha256_verify(&config.platform_public_key, message.as_bytes(), signature)
+    }
+
+    /// 验证回调通知（签名 + 时间戳防重放）
+    pub fn verify_notify(
+        &self,
+        timestamp: &str,
+        nonce: &str,
+        body: &str,
+        signature: &str,
+        serial: &str,
+    ) -> Result<bool, RswsError> {
+        let ts = match timestamp.parse::<u64>() {
+            Ok(ts) => ts,
+            Err(_) => return Ok(false),
+        };
+        if !SignatureService::is_timestamp_valid(ts, NOTIFY_TIMESTAMP_TOLERANCE) {
+            warn!("WeChat Pay notify timestamp expired: {}", timestamp);
+            return Ok(false);
+        }
+
+        self.verify_signature(timestamp, nonce, body, signature, serial)
+    }
+
+    /// 解密回调通知中的 resource，返回明文 JSON
+    pub fn decrypt_resource(&self, resource: &WechatPayNotifyResource) -> Result<Value, RswsError> {
+        let config = self
+            .config
+            .as_ref()
+            .ok_or_else(|| RswsError::business(ErrorCode::WECHATPAY_NOT_CONFIGURED))?;
+
+        if resource.algorithm != "AEAD_AES_256_GCM" {
+            return Err(RswsError::business_with_message(
+                ErrorCode::WECHATPAY_DECRYPT_FAILED,
+                format!("Unsupported algorithm: {}", resource.algorithm),
+            ));
+        }
+
+        let plaintext = aead_aes_256_gcm_decrypt(
+            config.api_v3_key.as_bytes(),
+            resource.nonce.as_bytes(),
+            resource.associated_data.as_deref().unwrap_or("").as_bytes(),
+            &resource.ciphertext,
+        )?;
+
+        serde_json::from_slice(&plaintext).map_err(|e| {
+            RswsError::business_with_message(
+                ErrorCode::WECHATPAY_DECRYPT_FAILED,
+                format!("Invalid decrypted resource: {}", e),
+            )
+        })
+    }
+}
+
+// ==================== 签名 / 解密辅助 ====================
+
+/// 构造签名串：每个字段后追加 `\n`
+pub fn build_sign_message(parts: &[&str]) -> String {
+    parts.iter().fold(String::new(), |mut s, p| {
+        s.push_str(p);
+        s.push('\n');
+        s
+    })
+}
+
+/// AEAD_AES_256_GCM 解密
+///
+/// ciphertext 为 base64 编码的「密文 + 16 字节认证标签」。
+pub fn aead_aes_256_gcm_decrypt(
+    key: &[u8],
+    nonce: &[u8],
+    associated_data: &[u8],
+    ciphertext: &str,
+) -> Result<Vec<u8>, RswsError> {
+    if key.len() != 32 {
+        return Err(RswsError::business_with_message(
+            ErrorCode::WECHATPAY_DECRYPT_FAILED,
+            "APIv3 key must be 32 bytes",
+        ));
+    }
+    if nonce.len() != 12 {
+        return Err(RswsError::business_with_message(
+            ErrorCode::WECHATPAY_DECRYPT_FAILED,
+            "Nonce must be 12 bytes",
+        ));
+    }
+
+    let data = general_purpose::STANDARD.decode(ciphertext).map_err(|e| {
+        RswsError::business_with_message(
+            ErrorCode::WECHATPAY_DECRYPT_FAILED,
+            format!("Invalid base64 ciphertext: {}", e),
+        )
+    })?;
+
+    let cipher = Aes256Gcm::new_from_slice(key)
+        .map_err(|e| RswsError::internal(format!("Invalid AES key: {}", e)))?;
+
+    cipher
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: &data,
+                aad: associated_data,
+            },
+        )
+        .map_err(|_| RswsError::business(ErrorCode::WECHATPAY_DECRYPT_FAILED))
+}
+
+// ==================== 单元测试 ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_build_sign_message() {
+        // 微信支付 API v3「签名生成」文档示例
+        let message = build_sign_message(&[
+            "GET",
+            "/v3/certificates",
+            "1554208460",
+            "593BEC0C930BF1AFEB40B4A08C8FB242",
+            "",
+        ]);
+        assert_eq!(
+            message,
+            "GET\n/v3/certificates\n1554208460\n593BEC0C930BF1AFEB40B4A08C8FB242\n\n"
+        );
+    }
+
+    #[test]
+    fn test_aes_256_gcm_nist_vector() {
+        // McGrew & Viega《The Galois/Counter Mode of Operation》Test Case 16
+        let key = hex("feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308");
+        let iv = hex("cafebabefacedbaddecaf888");
+        let aad = hex("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+        let ciphertext = "Ui3B8JlWfQf0fzejKoRCfWQ6jNy/5cDJdZiivSVV0aqMsI5IWQ27PaewixBWgog4xfYeY5O6egq8yfZidvxuzg9OF2jN34hTuy1VGw==";
+        let expected = hex("d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39");
+
+        let plaintext = aead_aes_256_gcm_decrypt(&key, &iv, &aad, ciphertext).unwrap();
+        assert_eq!(plaintext, expected);
+
+        // 认证数据不一致时解密失败
+        assert!(aead_aes_256_gcm_decrypt(&key, &iv, b"tampered", ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_aes_256_gcm_notify_resource() {
+        // 模拟支付成功回调的 resource（APIv3 密钥与 nonce 均为 ASCII 字符串）
+        let key = b"0123456789abcdef0123456789abcdef";
+        let ciphertext = "DUcclrHBE5NffFMk6pzEyhVtzlngwd+QDAMbIyZ27Pb6fgUajNM4et6LZNzz1e0ZUKSzuEg2xAP78pHDZ4HZWoNzGMJVhi10I0ZsRm5tY7xkmTLv5wUV3CGwKIKGSdqSQ0rCjEnXwDQcGzS4J0JJjwbBK3NTkOEU7QbFJ2NPYE+xUTfJv5PIhEhHydzFgw==";
+
+        let plaintext =
+            aead_aes_256_gcm_decrypt(key, b"fdasflkja484", b"transaction", ciphertext).unwrap();
+        let json: Value = serde_json::from_slice(&plaintext).unwrap();
+        assert_eq!(json["out_trade_no"], "7300000000123");
+        assert_eq!(json["trade_state"], "SUCCESS");
+        assert_eq!(json["amount"]["total"], 720);
+
+        // 密钥长度错误
+        assert!(aead_aes_256_gcm_decrypt(b"short", b"fdasflkja484", b"", ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_verify_notify_unconfigured() {
+        let service = WechatPayService::new(None);
+        let now = chrono::Utc::now().timestamp().to_string();
+        assert!(!service
+            .verify_notify(&now, "nonce", "{}", "c2ln", "serial")
+            .unwrap());
+    }
+}
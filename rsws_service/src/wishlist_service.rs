@@ -0,0 +1,340 @@
+//! 收藏与心愿单服务
+//!
+//! - 用户收藏资源，创建公开或私有的命名心愿单（公开心愿单游客可查看）
+//! - 资源列表与详情标记当前用户是否已保存
+//! - 已保存资源降价（含限时降价促销）时由后台任务邮件通知用户
+//! - 管理员"最受期待"报表
+
+use rsws_common::email::EmailService;
+use rsws_common::error::RswsError;
+use rsws_common::error_code::ErrorCode;
+use rsws_db::{ResourceRepository, WishlistRepository};
+use rsws_model::wishlist::{
+    price_drop_percent, CreateWishlistRequest, MostWishedItem, PriceDropNotice, SavedResource,
+    UpdateWishlistRequest, Wishlist, WishlistDetail, MAX_WISHLISTS_PER_USER, MAX_WISHLIST_NAME_LEN,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::config_service::EmailDbConfig;
+
+/// 后台任务单批处理通知数量
+const BATCH_SIZE: i64 = 100;
+
+/// 收藏与心愿单服务
+#[derive(Clone)]
+pub struct WishlistService {
+    wishlist_repo: Arc<WishlistRepository>,
+    resource_repo: Arc<ResourceRepository>,
+    email_service: Option<Arc<EmailService>>,
+}
+
+impl WishlistService {
+    /// 创建收藏与心愿单服务实例
+    pub fn new(
+        wishlist_repo: Arc<WishlistRepository>,
+        resource_repo: Arc<ResourceRepository>,
+        email_config: Option<&EmailDbConfig>,
+    ) -> Self {
+        let email_service = email_config.and_then(EmailDbConfig::smtp_service);
+
+        Self {
+            wishlist_repo,
+            resource_repo,
+            email_service,
+        }
+    }
+
+    // ==================== 收藏 ====================
+
+    /// 收藏资源（仅公开资源，重复收藏不报错）
+    pub async fn add_favorite(&self, user_id: i64, resource_id: i64) -> Result<(), RswsError> {
+        self.check_public_resource(resource_id).await?;
+        if self
+            .wishlist_repo
+            .add_favorite(user_id, resource_id)
+            .await?
+        {
+            info!("User {} favorited resource {}", user_id, resource_id);
+        }
+        Ok(())
+    }
+
+    /// 取消收藏
+    pub async fn remove_favorite(&self, user_id: i64, resource_id: i64) -> Result<(), RswsError> {
+        self.wishlist_repo
+            .remove_favorite(user_id, resource_id)
+            .await?;
+        Ok(())
+    }
+
+    /// 我的收藏
+    pub async fn list_favorites(
+        &self,
+        user_id: i64,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<SavedResource>, i64), RswsError> {
+        self.wishlist_repo
+            .list_favorites(user_id, page, page_size)
+            .await
+    }
+
+    // ==================== 心愿单 ====================
+
+    /// 我的心愿单
+    pub async fn list_wishlists(&self, user_id: i64) -> Result<Vec<Wishlist>, RswsError> {
+        self.wishlist_repo.list_wishlists(user_id).await
+    }
+
+    /// 心愿单详情：公开心愿单任何人可查看，私有心愿单仅本人
+    pub async fn get_wishlist(
+        &self,
+        viewer_id: Option<i64>,
+        wishlist_id: i64,
+    ) -> Result<WishlistDetail, RswsError> {
+        let wishlist = self
+            .wishlist_repo
+            .find_wishlist(wishlist_id)
+            .await?
+            .filter(|w| w.is_public || viewer_id == Some(w.user_id))
+            .ok_or_else(|| RswsError::business(ErrorCode::WISHLIST_NOT_FOUND))?;
+
+        let items = self.wishlist_repo.list_items(wishlist.id).await?;
+        Ok(WishlistDetail { wishlist, items })
+    }
+
+    /// 创建心愿单
+    pub async fn create_wishlist(
+        &self,
+        user_id: i64,
+        req: &CreateWishlistRequest,
+    ) -> Result<Wishlist, RswsError> {
+        let name = validate_name(&req.name)?;
+        if self.wishlist_repo.count_wishlists(user_id).await? >= MAX_WISHLISTS_PER_USER {
+            return Err(RswsError::business(ErrorCode::WISHLIST_LIMIT_REACHED));
+        }
+
+        let wishlist = self
+            .wishlist_repo
+            .create_wishlist(
+                user_id,
+                &name,
+                normalize_description(req.description.as_deref()).as_deref(),
+                req.is_public,
+            )
+            .await?;
+
+        info!("Wishlist created: {} by user {}", wishlist.id, user_id);
+        Ok(wishlist)
+    }
+
+    /// 修改心愿单名称、描述或公开状态
+    pub async fn update_wishlist(
+        &self,
+        user_id: i64,
+        wishlist_id: i64,
+        req: &UpdateWishlistRequest,
+    ) -> Result<Wishlist, RswsError> {
+        self.owned_wishlist(user_id, wishlist_id).await?;
+        let name = req.name.as_deref().map(validate_name).transpose()?;
+
+        self.wishlist_repo
+            .update_wishlist(
+                wishlist_id,
+                name.as_deref(),
+                normalize_description(req.description.as_deref()).as_deref(),
+                req.is_public,
+            )
+            .await?
+            .ok_or_else(|| RswsError::business(ErrorCode::WISHLIST_NOT_FOUND))
+    }
+
+    /// 删除心愿单
+    pub async fn delete_wishlist(&self, user_id: i64, wishlist_id: i64) -> Result<(), RswsError> {
+        let wishlist = self.owned_wishlist(user_id, wishlist_id).await?;
+        self.wishlist_repo.delete_wishlist(&wishlist).await?;
+        info!("Wishlist deleted: {} by user {}", wishlist_id, user_id);
+        Ok(())
+    }
+
+    /// 资源加入心愿单（仅公开资源，重复加入不报错）
+    pub async fn add_item(
+        &self,
+        user_id: i64,
+        wishlist_id: i64,
+        resource_id: i64,
+    ) -> Result<(), RswsError> {
+        let wishlist = self.owned_wishlist(user_id, wishlist_id).await?;
+        self.check_public_resource(resource_id).await?;
+        self.wishlist_repo.add_item(&wishlist, resource_id).await?;
+        Ok(())
+    }
+
+    /// 资源移出心愿单
+    pub async fn remove_item(
+        &self,
+        user_id: i64,
+        wishlist_id: i64,
+        resource_id: i64,
+    ) -> Result<(), RswsError> {
+        let wishlist = self.owned_wishlist(user_id, wishlist_id).await?;
+        self.wishlist_repo
+            .remove_item(&wishlist, resource_id)
+            .await?;
+        Ok(())
+    }
+
+    // ==================== 保存状态 ====================
+
+    /// 用户是否已保存资源（收藏或任一心愿单）
+    pub async fn is_saved(&self, user_id: i64, resource_id: i64) -> Result<bool, RswsError> {
+        Ok(!self
+            .wishlist_repo
+            .saved_resource_ids(user_id, &[resource_id])
+            .await?
+            .is_empty())
+    }
+
+    /// 给定资源中用户已保存的资源 ID
+    pub async fn saved_ids(
+        &self,
+        user_id: i64,
+        resource_ids: &[i64],
+    ) -> Result<HashSet<i64>, RswsError> {
+        if resource_ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+        Ok(self
+            .wishlist_repo
+            .saved_resource_ids(user_id, resource_ids)
+            .await?
+            .into_iter()
+            .collect())
+    }
+
+    /// 最受期待资源报表
+    pub async fn most_wished(
+        &self,
+        days: i32,
+        limit: i64,
+    ) -> Result<Vec<MostWishedItem>, RswsError> {
+        self.wishlist_repo.most_wished(days, limit).await
+    }
+
+    // ==================== 降价通知 ====================
+
+    /// 处理降价通知（后台任务调用）
+    pub async fn process_price_drops(&self) -> Result<(), RswsError> {
+        self.wishlist_repo.sync_raised_prices().await?;
+
+        loop {
+            let notices = self.wishlist_repo.claim_price_drops(BATCH_SIZE).await?;
+            let claimed = notices.len() as i64;
+            for notice in notices.iter().filter(|n| !n.email.is_empty()) {
+                self.notify_price_drop(notice);
+            }
+            if claimed < BATCH_SIZE {
+                return Ok(());
+            }
+        }
+    }
+
+    /// 启动后台降价通知任务
+    pub fn start_background(self: Arc<Self>, interval_secs: u64) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.process_price_drops().await {
+                    error!("Wishlist price drop task failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// 降价邮件（失败只记录日志）
+    fn notify_price_drop(&self, notice: &PriceDropNotice) {
+        let (subject, body) = price_drop_email(notice);
+
+        let Some(ref svc) = self.email_service else {
+            // Dev 模式：只打印日志
+            warn!(
+                "PRICE DROP EMAIL [DEV MODE] To: {} | Subject: {}",
+                notice.email, subject
+            );
+            return;
+        };
+
+        if let Err(e) = svc.send(&notice.email, &subject, &body) {
+            warn!(
+                "Failed to send price drop notification to user {}: {}",
+                notice.user_id, e
+            );
+        }
+    }
+
+    async fn owned_wishlist(&self, user_id: i64, wishlist_id: i64) -> Result<Wishlist, RswsError> {
+        self.wishlist_repo
+            .find_wishlist(wishlist_id)
+            .await?
+            .filter(|w| w.user_id == user_id)
+            .ok_or_else(|| RswsError::business(ErrorCode::WISHLIST_NOT_FOUND))
+    }
+
+    async fn check_public_resource(&self, resource_id: i64) -> Result<(), RswsError> {
+        self.resource_repo
+            .get_by_id(resource_id)
+            .await?
+            .filter(|r| r.is_public())
+            .map(|_| ())
+            .ok_or_else(|| RswsError::business(ErrorCode::RESOURCE_NOT_FOUND))
+    }
+}
+
+/// 校验心愿单名称
+fn validate_name(name: &str) -> Result<String, RswsError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_WISHLIST_NAME_LEN {
+        return Err(RswsError::bad_request(format!(
+            "name must be 1-{} characters",
+            MAX_WISHLIST_NAME_LEN
+        )));
+    }
+    Ok(name.to_string())
+}
+
+fn normalize_description(description: Option<&str>) -> Option<String> {
+    description
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(str::to_string)
+}
+
+/// 降价通知邮件主题与正文
+fn price_drop_email(notice: &PriceDropNotice) -> (String, String) {
+    let percent = price_drop_percent(notice.old_price, notice.new_price);
+    let subject = format!("Price drop: {} is now {}% off", notice.title, percent);
+    let body = format!(
+        "Hi {},\n\n\"{}\", which you saved, dropped from {} to {} ({}% off).\n\nThe new price applies to orders placed now and may end at any time.",
+        notice.username,
+        notice.title,
+        notice.old_price.round_dp(2),
+        notice.new_price.round_dp(2),
+        percent
+    );
+    (subject, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        assert_eq!(validate_name("  Later  ").unwrap(), "Later");
+        assert!(validate_name("   ").is_err());
+        assert!(validate_name(&"x".repeat(MAX_WISHLIST_NAME_LEN + 1)).is_err());
+    }
+}
@@ -34,6 +34,9 @@ pub struct PendingOrder {
     /// 订单金额
     pub amount: Decimal,
 
+    /// 下单时报价的通道手续费（orders.fee_amount）
+    pub fee_amount: Decimal,
+
     /// 收款地址
     pub wallet_address: String,
 
@@ -230,6 +233,7 @@ mod tests {
             order_id: 1,
             user_id: 1,
             amount: Decimal::from(10),
+            fee_amount: Decimal::ZERO,
             wallet_address: "T123".to_string(),
             network: "tron".to_string(),
             created_at: Utc::now(),
@@ -249,6 +253,7 @@ mod tests {
             order_id: 1,
             user_id: 1,
             amount: Decimal::from(10),
+            fee_amount: Decimal::ZERO,
             wallet_address: "T123".to_string(),
             network: "tron".to_string(),
             created_at: Utc::now(),
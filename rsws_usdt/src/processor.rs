@@ -2,14 +2,24 @@
 
 use crate::{matcher::PendingOrder, UsdtError};
 use chrono::{DateTime, Utc};
+use rsws_db::{LedgerRepository, OrderRepository, RiskRepository};
+use rsws_model::payment::{usdt_overpayment, USDT_AMOUNT_TOLERANCE};
+use rsws_model::risk::{RiskContext, RISK_STAGE_PAYMENT, RISK_STATUS_APPROVED};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
-use tracing::info;
+use tracing::{error, info};
 
-/// Pending order query result row (5 columns)
+/// Pending order query result row (6 columns)
 #[allow(clippy::type_complexity)]
-type PendingOrderRow = (i64, i64, Decimal, DateTime<Utc>, Option<DateTime<Utc>>);
+type PendingOrderRow = (
+    i64,
+    i64,
+    Decimal,
+    Decimal,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+);
 
 /// USDT 交易记录
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,33 +58,85 @@ impl TransactionProcessor {
         // 查询该地址的待支付订单
         let pending_orders = self.get_pending_orders(&tx.to_address).await?;
 
-        for order in pending_orders {
-            let tolerance =
-                order.amount * Decimal::try_from(0.01).map_err(|_| UsdtError::InvalidAmount)?;
+        // 先按误差范围精确匹配订单
+        let exact = pending_orders
+            .iter()
+            .find(|o| (tx.amount - o.amount).abs() <= o.amount * USDT_AMOUNT_TOLERANCE)
+            .map(|o| (o, Decimal::ZERO));
+
+        // 未命中时尝试匹配余额充值单
+        if exact.is_none() && self.credit_topup(&tx).await? {
+            self.record_transaction(
+                tx.tx_hash,
+                tx.network,
+                tx.from_address,
+                tx.to_address,
+                tx.amount,
+                tx.block_number,
+                tx.confirmations,
+                None,
+                "processed",
+            )
+            .await?;
+            return Ok(true);
+        }
 
-            if (tx.amount - order.amount).abs() <= tolerance {
+        // 超额支付：仅当转出地址此前只为同一买家付过款时，匹配该买家金额低于转账金额的订单，
+        // 超额部分转入该买家余额；无法归属的转账留待人工处理
+        let matched = match exact {
+            Some(matched) => Some(matched),
+            None => match self.payer_user_id(&tx.from_address).await? {
+                Some(user_id) => pending_orders
+                    .iter()
+                    .map(|o| (o, usdt_overpayment(tx.amount, o.amount)))
+                    .find(|(o, overpaid)| o.user_id == user_id && *overpaid > Decimal::ZERO),
+                None => None,
+            },
+        };
+
+        if let Some((order, overpaid)) = matched {
+            info!(
+                "Matched order {}: tx_amount={}, order_amount={}, overpaid={}",
+                order.order_id, tx.amount, order.amount, overpaid
+            );
+
+            // 命中风控规则的付款暂扣待审核，不入账
+            if self.hold_for_review(order.order_id).await {
                 info!(
-                    "Matched order {}: tx_amount={}, order_amount={}",
-                    order.order_id, tx.amount, order.amount
+                    "Order {} held for risk review: tx={}",
+                    order.order_id, tx.tx_hash
                 );
-
-                self.confirm_order(order.order_id, &tx.tx_hash).await?;
-
                 self.record_transaction(
-                    tx.tx_hash.clone(),
-                    tx.network.clone(),
-                    tx.from_address.clone(),
-                    tx.to_address.clone(),
+                    tx.tx_hash,
+                    tx.network,
+                    tx.from_address,
+                    tx.to_address,
                     tx.amount,
                     tx.block_number,
                     tx.confirmations,
                     Some(order.order_id),
-                    "processed",
+                    "held",
                 )
                 .await?;
-
                 return Ok(true);
             }
+
+            self.confirm_order(order, &tx.tx_hash, overpaid).await?;
+
+            self.record_transaction(
+                tx.tx_hash.clone(),
+                tx.network.clone(),
+                tx.from_address.clone(),
+                tx.to_address.clone(),
+                tx.amount,
+                tx.block_number,
+                tx.confirmations,
+                Some(order.order_id),
+                "processed",
+            )
+            .await?;
+
+            return Ok(true);
         }
 
         // 未匹配，记录为未匹配交易
@@ -111,12 +173,13 @@ impl TransactionProcessor {
     ) -> Result<Vec<PendingOrder>, UsdtError> {
         let rows: Vec<PendingOrderRow> = sqlx::query_as(
             r#"
-            SELECT o.id, o.user_id, o.amount, o.created_at, o.expired_at
+            SELECT o.id, o.user_id, o.amount, o.fee_amount, o.created_at, o.expired_at
             FROM orders o
             JOIN resources r ON r.id = o.resource_id
             JOIN usdt_wallets w ON w.id = r.wallet_id
             WHERE w.address = $1
               AND o.status = 'pending'
+              AND o.risk_status NOT IN ('review', 'rejected')
               AND (o.expired_at IS NULL OR o.expired_at > NOW())
             ORDER BY o.created_at ASC
             "#,
@@ -129,10 +192,11 @@ impl TransactionProcessor {
         Ok(rows
             .into_iter()
             .map(
-                |(order_id, user_id, amount, created_at, expires_at)| PendingOrder {
+                |(order_id, user_id, amount, fee_amount, created_at, expires_at)| PendingOrder {
                     order_id,
                     user_id,
                     amount,
+                    fee_amount,
                     wallet_address: wallet_address.to_string(),
                     network: "tron".to_string(),
                     created_at,
@@ -142,108 +206,143 @@ impl TransactionProcessor {
             .collect())
     }
 
-    /// 确认订单 — 在数据库事务中执行
-    ///
-    /// 包含两个业务操作：
-    /// 1. **佣金结算**：订单完成后，根据资源的 `commission_rate` 计算佣金并记录到 `commission_records`
-    /// 2. **资源下载权限**：`status = 'completed'` 即代表用户有下载权限（下载时通过 orders 表验证）
-    async fn confirm_order(&self, order_id: i64, tx_hash: &str) -> Result<(), UsdtError> {
-        let mut db_tx = self
-            .db_pool
-            .begin()
-            .await
-            .map_err(|e| UsdtError::DatabaseError(e.to_string()))?;
-
-        // ① 更新订单状态为已完成
-        let affected = sqlx::query(
+    /// 转出地址此前付款的买家（只为一个买家付过款时返回），用于归属超额转账
+    async fn payer_user_id(&self, from_address: &str) -> Result<Option<i64>, UsdtError> {
+        if from_address.is_empty() {
+            return Ok(None);
+        }
+        let rows: Vec<(i64,)> = sqlx::query_as(
             r#"
-            UPDATE orders
-            SET status = 'completed',
-                transaction_id = $2,
-                updated_at = NOW()
-            WHERE id = $1 AND status = 'pending'
+            SELECT DISTINCT o.user_id
+            FROM usdt_transactions t
+            JOIN orders o ON o.id = t.order_id
+            WHERE t.from_address = $1 AND t.status = 'processed'
+            LIMIT 2
             "#,
         )
-        .bind(order_id)
-        .bind(tx_hash)
-        .execute(&mut *db_tx)
+        .bind(from_address)
+        .fetch_all(&self.db_pool)
         .await
         .map_err(|e| UsdtError::DatabaseError(e.to_string()))?;
 
-        if affected.rows_affected() == 0 {
-            db_tx
-                .commit()
-                .await
-                .map_err(|e| UsdtError::DatabaseError(e.to_string()))?;
-            return Ok(());
-        }
+        Ok(match rows.as_slice() {
+            [(user_id,)] => Some(*user_id),
+            _ => None,
+        })
+    }
 
-        // ② 佣金结算
-        let order_info: Option<(i64, i64, Decimal)> =
-            sqlx::query_as("SELECT user_id, resource_id, amount FROM orders WHERE id = $1")
-                .bind(order_id)
-                .fetch_optional(&mut *db_tx)
-                .await
-                .map_err(|e| UsdtError::DatabaseError(e.to_string()))?;
+    /// 按收款地址与链上金额匹配待支付的 USDT 余额充值单，按实际到账金额入账
+    async fn credit_topup(&self, tx: &UsdtTransaction) -> Result<bool, UsdtError> {
+        let payment_method = match tx.network.as_str() {
+            "tron" => "usdt_trc20",
+            "ethereum" => "usdt_erc20",
+            _ => return Ok(false),
+        };
+
+        let ledger = LedgerRepository::new(self.db_pool.clone());
+        let topup = ledger
+            .find_pending_usdt_topup(payment_method, &tx.to_address, tx.amount)
+            .await
+            .map_err(|e| UsdtError::DatabaseError(e.to_string()))?;
 
-        if let Some((_buyer_id, resource_id, order_amount)) = order_info {
-            let resource: Option<(Decimal, Option<i64>)> =
-                sqlx::query_as("SELECT commission_rate, provider_id FROM resources WHERE id = $1")
-                    .bind(resource_id)
-                    .fetch_optional(&mut *db_tx)
-                    .await
-                    .map_err(|e| UsdtError::DatabaseError(e.to_string()))?;
+        let Some(topup) = topup else {
+            return Ok(false);
+        };
 
-            if let Some((commission_rate, provider_id)) = resource {
-                let commission_amount = order_amount * commission_rate;
+        let credited = ledger
+            .complete_topup(topup.id, Some(&tx.tx_hash), Some(tx.amount), Decimal::ZERO)
+            .await
+            .map_err(|e| UsdtError::DatabaseError(e.to_string()))?;
 
-                let default_rule_id: (i64,) = sqlx::query_as(
-                    "SELECT COALESCE(MIN(id), 0) FROM commission_rules WHERE is_active = true",
-                )
-                .fetch_one(&mut *db_tx)
-                .await
-                .map_err(|e| UsdtError::DatabaseError(e.to_string()))?;
+        if credited {
+            info!(
+                "Matched topup {}: tx_amount={}, topup_amount={}",
+                topup.id, tx.amount, topup.amount
+            );
+        }
+        Ok(credited)
+    }
+
+    /// 付款风控：按当前规则再评估一次，返回是否暂扣待人工审核
+    ///
+    /// 款项已到账，命中拦截规则也只转人工审核；审核放行时由
+    /// `LedgerRepository::release_held_usdt_payment` 入账，拒绝时标记待退款。
+    /// 评估失败时不阻断收款。
+    async fn hold_for_review(&self, order_id: i64) -> bool {
+        let order = match OrderRepository::new(self.db_pool.clone())
+            .get_by_id(order_id)
+            .await
+        {
+            Ok(Some(order)) if order.risk_status != RISK_STATUS_APPROVED => order,
+            Ok(_) => return false,
+            Err(e) => {
+                error!("Failed to load order {} for risk check: {}", order_id, e);
+                return false;
+            }
+        };
 
-                if commission_amount > Decimal::ZERO {
-                    let commission_record_id = rsws_common::snowflake::next_id();
-                    let _ = sqlx::query(
-                        r#"
-                        INSERT INTO commission_records
-                            (id, order_id, user_id, referrer_id, rule_id,
-                             order_amount, commission_amount, commission_rate, status, created_at)
-                        VALUES ($1, $2, $3, NULL, $4, $5, $6, $7, 'pending', NOW())
-                        ON CONFLICT DO NOTHING
-                        "#,
-                    )
-                    .bind(commission_record_id)
-                    .bind(order_id)
-                    .bind(provider_id.unwrap_or(0))
-                    .bind(default_rule_id.0)
-                    .bind(order_amount)
-                    .bind(commission_amount)
-                    .bind(commission_rate)
-                    .execute(&mut *db_tx)
-                    .await
-                    .map_err(|e| UsdtError::DatabaseError(e.to_string()))?;
-
-                    info!(
-                        "Commission settled: order_id={}, amount={} (rate={})",
-                        order_id, commission_amount, commission_rate
-                    );
-                }
+        match RiskRepository::new(self.db_pool.clone())
+            .assess(&order, RISK_STAGE_PAYMENT, &RiskContext::default(), true)
+            .await
+        {
+            Ok(outcome) => outcome.is_held(),
+            Err(e) => {
+                error!("Risk assessment failed for order {}: {}", order_id, e);
+                false
             }
         }
+    }
 
-        // ③ 资源下载权限
-        // orders 表已有 UNIQUE(user_id, resource_id) 约束
-        // status = 'completed' 即代表该用户已购买此资源
-        // 下载接口通过 check_user_purchased(order_id, user_id) 验证权限
-        info!("Order {} confirmed: download access granted", order_id);
+    /// 确认订单 — 在数据库事务中执行
+    ///
+    /// 包含以下业务操作：
+    /// 1. **佣金结算**：订单完成后，按分成规则计算创作者分成并记录到 `commission_records`；
+    ///    订单有推荐人时同时记录推荐佣金
+    /// 2. **资源下载权限**：`status = 'completed'` 即代表用户有下载权限（下载时通过 orders 表验证）
+    /// 3. **总账**：记录订单收款凭证（手续费取下单时的报价）；超额支付部分转入买家余额
+    /// 4. **事件推送**：order.paid 事件写入出站 Webhook 队列
+    async fn confirm_order(
+        &self,
+        order: &PendingOrder,
+        tx_hash: &str,
+        overpaid: Decimal,
+    ) -> Result<(), UsdtError> {
+        let mut db_tx = self
+            .db_pool
+            .begin()
+            .await
+            .map_err(|e| UsdtError::DatabaseError(e.to_string()))?;
+
+        let confirmed = LedgerRepository::confirm_usdt_order_in_tx(
+            &mut *db_tx,
+            order.order_id,
+            order.user_id,
+            tx_hash,
+            order.fee_amount,
+            overpaid,
+        )
+        .await
+        .map_err(|e| UsdtError::DatabaseError(e.to_string()))?;
 
         db_tx
             .commit()
             .await
             .map_err(|e| UsdtError::DatabaseError(e.to_string()))?;
+
+        if confirmed {
+            if overpaid > Decimal::ZERO {
+                info!(
+                    "Order {} overpaid by {}: credited to user {} balance",
+                    order.order_id, overpaid, order.user_id
+                );
+            }
+            // orders 表已有 UNIQUE(user_id, resource_id) 约束，status = 'completed'
+            // 即代表该用户已购买此资源，下载接口通过 check_user_purchased 验证权限
+            info!(
+                "Order {} confirmed: download access granted",
+                order.order_id
+            );
+        }
         Ok(())
     }
 